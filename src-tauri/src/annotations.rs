@@ -0,0 +1,241 @@
+use crate::types::{Annotation, AnnotationKind, ZOrder};
+
+/// How far from a line a click still grabs it, normalized
+const HIT_SLOP: f64 = 0.01;
+
+/// Annotations in the order they are drawn: by `z_index`, ties in list order
+pub(crate) fn paint_order(annotations: &[Annotation]) -> Vec<&Annotation> {
+    let mut ordered: Vec<&Annotation> = annotations.iter().collect();
+    ordered.sort_by_key(|a| a.z_index);
+    ordered
+}
+
+/// Topmost annotation on screen at `at_ms` under the normalized point (x, y)
+pub(crate) fn hit_test(annotations: &[Annotation], x: f64, y: f64, at_ms: u64) -> Option<&Annotation> {
+    paint_order(annotations)
+        .into_iter()
+        .rev()
+        .filter(|a| a.start_ms <= at_ms && at_ms < a.end_ms)
+        .find(|a| contains(a, x, y))
+}
+
+fn contains(ann: &Annotation, x: f64, y: f64) -> bool {
+    match ann.kind {
+        AnnotationKind::Arrow => {
+            distance_to_segment((x, y), (ann.x, ann.y), (ann.x + ann.width, ann.y + ann.height)) <= HIT_SLOP
+        }
+        AnnotationKind::Freehand => {
+            let points = ann.points.as_deref().unwrap_or_default();
+            match points {
+                [] => false,
+                [p] => distance_to_segment((x, y), *p, *p) <= HIT_SLOP,
+                _ => points.windows(2).any(|w| distance_to_segment((x, y), w[0], w[1]) <= HIT_SLOP),
+            }
+        }
+        // Shapes and text are grabbed anywhere inside their box
+        _ => {
+            x >= ann.x - HIT_SLOP
+                && x <= ann.x + ann.width + HIT_SLOP
+                && y >= ann.y - HIT_SLOP
+                && y <= ann.y + ann.height + HIT_SLOP
+        }
+    }
+}
+
+fn distance_to_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 > 0.0 { (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0) } else { 0.0 };
+    ((p.0 - a.0 - t * dx).powi(2) + (p.1 - a.1 - t * dy).powi(2)).sqrt()
+}
+
+/// Indices of the annotations in `ids` and of the rest of their groups
+fn selection(annotations: &[Annotation], ids: &[String]) -> Vec<usize> {
+    let picked = |a: &Annotation| ids.contains(&a.id);
+    let groups: Vec<&str> = annotations.iter().filter(|a| picked(a)).filter_map(|a| a.group.as_deref()).collect();
+    (0..annotations.len())
+        .filter(|&i| {
+            let a = &annotations[i];
+            picked(a) || a.group.as_deref().is_some_and(|g| groups.contains(&g))
+        })
+        .collect()
+}
+
+/// Move the selected annotations in the stacking order, then renumber every
+/// `z_index` from 0 at the bottom
+pub(crate) fn reorder(annotations: &mut [Annotation], ids: &[String], change: ZOrder) {
+    let selected = selection(annotations, ids);
+    let is_selected = |i: &usize| selected.contains(i);
+    // Bottom to top
+    let mut stack: Vec<usize> = (0..annotations.len()).collect();
+    stack.sort_by_key(|&i| annotations[i].z_index);
+
+    match change {
+        ZOrder::ToFront | ZOrder::ToBack => {
+            let (picked, rest): (Vec<usize>, Vec<usize>) = stack.iter().partition(|&&i| is_selected(&i));
+            stack = if change == ZOrder::ToFront { [rest, picked].concat() } else { [picked, rest].concat() };
+        }
+        // Walking against the move keeps a selected block together
+        ZOrder::Forward => {
+            for pos in (0..stack.len().saturating_sub(1)).rev() {
+                if is_selected(&stack[pos]) && !is_selected(&stack[pos + 1]) {
+                    stack.swap(pos, pos + 1);
+                }
+            }
+        }
+        ZOrder::Backward => {
+            for pos in 1..stack.len() {
+                if is_selected(&stack[pos]) && !is_selected(&stack[pos - 1]) {
+                    stack.swap(pos, pos - 1);
+                }
+            }
+        }
+    }
+    for (z, &i) in stack.iter().enumerate() {
+        annotations[i].z_index = z as i32;
+    }
+}
+
+/// Put the selected annotations, with the groups they were in, in a new
+/// group. None when fewer than two annotations are selected.
+pub(crate) fn group(annotations: &mut [Annotation], ids: &[String]) -> Option<String> {
+    let selected = selection(annotations, ids);
+    if selected.len() < 2 {
+        return None;
+    }
+    let group = uuid::Uuid::new_v4().to_string();
+    for i in selected {
+        annotations[i].group = Some(group.clone());
+    }
+    Some(group)
+}
+
+pub(crate) fn ungroup(annotations: &mut [Annotation], group: &str) {
+    for ann in annotations.iter_mut().filter(|a| a.group.as_deref() == Some(group)) {
+        ann.group = None;
+    }
+}
+
+/// Scale the selected annotations by `scale` around the center of their
+/// bounds, then move them by (`dx`, `dy`), all normalized
+pub(crate) fn transform(annotations: &mut [Annotation], ids: &[String], dx: f64, dy: f64, scale: f64) {
+    let selected = selection(annotations, ids);
+    let Some((min_x, min_y, max_x, max_y)) = selected.iter().map(|&i| bounds(&annotations[i])).reduce(|a, b| {
+        (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))
+    }) else {
+        return;
+    };
+    let scale = if scale.is_finite() && scale > 0.0 { scale } else { 1.0 };
+    let (cx, cy) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+    let map = |x: f64, y: f64| (cx + (x - cx) * scale + dx, cy + (y - cy) * scale + dy);
+
+    for i in selected {
+        let ann = &mut annotations[i];
+        (ann.x, ann.y) = map(ann.x, ann.y);
+        ann.width *= scale;
+        ann.height *= scale;
+        for p in ann.points.iter_mut().flatten() {
+            *p = map(p.0, p.1);
+        }
+    }
+}
+
+/// (min x, min y, max x, max y) of the box and of the freehand points
+fn bounds(ann: &Annotation) -> (f64, f64, f64, f64) {
+    let corners = [(ann.x, ann.y), (ann.x + ann.width, ann.y + ann.height)];
+    corners.iter().chain(ann.points.iter().flatten()).fold(
+        (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+        |b, p| (b.0.min(p.0), b.1.min(p.1), b.2.max(p.0), b.3.max(p.1)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_annotation;
+
+    fn ann(id: &str, kind: AnnotationKind, z_index: i32) -> Annotation {
+        Annotation {
+            id: id.into(),
+            x: 0.2,
+            y: 0.2,
+            width: 0.4,
+            height: 0.4,
+            z_index,
+            ..test_annotation(kind, 0, 1000)
+        }
+    }
+
+    fn ids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn stack(annotations: &[Annotation]) -> Vec<&str> {
+        paint_order(annotations).into_iter().map(|a| a.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_hit_test_picks_the_topmost_visible() {
+        let mut top = ann("top", AnnotationKind::Rectangle, 2);
+        top.end_ms = 500;
+        let annotations = [top, ann("bottom", AnnotationKind::Circle, 0), ann("arrow", AnnotationKind::Arrow, 1)];
+        assert_eq!(hit_test(&annotations, 0.5, 0.3, 100).map(|a| a.id.as_str()), Some("top"));
+        // Once the top one is gone
+        assert_eq!(hit_test(&annotations, 0.5, 0.3, 600).map(|a| a.id.as_str()), Some("bottom"));
+        // The arrow is only grabbed along its line
+        assert_eq!(hit_test(&annotations, 0.4, 0.4, 600).map(|a| a.id.as_str()), Some("arrow"));
+        assert!(hit_test(&annotations, 0.9, 0.9, 100).is_none());
+    }
+
+    #[test]
+    fn test_reorder_moves_groups_together() {
+        let mut annotations = vec![
+            ann("a", AnnotationKind::Rectangle, 0),
+            ann("b", AnnotationKind::Rectangle, 0),
+            ann("c", AnnotationKind::Rectangle, 0),
+            ann("d", AnnotationKind::Rectangle, 0),
+        ];
+        reorder(&mut annotations, &ids(&["a"]), ZOrder::ToFront);
+        assert_eq!(stack(&annotations), ["b", "c", "d", "a"]);
+        reorder(&mut annotations, &ids(&["a"]), ZOrder::Backward);
+        assert_eq!(stack(&annotations), ["b", "c", "a", "d"]);
+
+        group(&mut annotations, &ids(&["b", "c"]));
+        reorder(&mut annotations, &ids(&["b"]), ZOrder::Forward);
+        assert_eq!(stack(&annotations), ["a", "b", "c", "d"]);
+        reorder(&mut annotations, &ids(&["c"]), ZOrder::ToFront);
+        assert_eq!(stack(&annotations), ["a", "d", "b", "c"]);
+        reorder(&mut annotations, &ids(&["d"]), ZOrder::ToBack);
+        assert_eq!(stack(&annotations), ["d", "a", "b", "c"]);
+        assert_eq!(annotations.iter().map(|a| a.z_index).max(), Some(3));
+    }
+
+    #[test]
+    fn test_group_and_ungroup() {
+        let mut annotations = vec![ann("a", AnnotationKind::Rectangle, 0), ann("b", AnnotationKind::Circle, 0)];
+        assert!(group(&mut annotations, &ids(&["a"])).is_none());
+        let id = group(&mut annotations, &ids(&["a", "b"])).unwrap();
+        assert!(annotations.iter().all(|a| a.group.as_deref() == Some(id.as_str())));
+        ungroup(&mut annotations, &id);
+        assert!(annotations.iter().all(|a| a.group.is_none()));
+    }
+
+    #[test]
+    fn test_transform_scales_around_the_selection() {
+        let mut freehand = ann("f", AnnotationKind::Freehand, 0);
+        freehand.points = Some(vec![(0.2, 0.2), (0.6, 0.6)]);
+        let mut annotations = vec![ann("r", AnnotationKind::Rectangle, 0), freehand, ann("other", AnnotationKind::Circle, 0)];
+        annotations[0].group = Some("g".into());
+        annotations[1].group = Some("g".into());
+
+        transform(&mut annotations, &ids(&["r"]), 0.1, 0.0, 0.5);
+        // Bounds 0.2-0.6 around 0.4, halved then moved right
+        let r = &annotations[0];
+        assert!((r.x - 0.4).abs() < 1e-9 && (r.y - 0.3).abs() < 1e-9);
+        assert!((r.width - 0.2).abs() < 1e-9 && (r.height - 0.2).abs() < 1e-9);
+        let points = annotations[1].points.as_ref().unwrap();
+        assert!((points[1].0 - 0.6).abs() < 1e-9 && (points[1].1 - 0.5).abs() < 1e-9);
+        // Not in the selection
+        assert_eq!(annotations[2].x, 0.2);
+    }
+}
@@ -1,4 +1,27 @@
-use crate::types::AudioDevice;
+use super::wav::WavSink;
+use crate::types::{AudioDevice, AudioFormat};
+use std::collections::VecDeque;
+
+/// Maximum monitoring latency before old samples are dropped (in ms).
+const MONITOR_MAX_LATENCY_MS: usize = 100;
+
+/// Loudest sample a capture callback wrote since the level was last read,
+/// shared with the recording waveform
+#[derive(Debug, Default)]
+pub struct PeakMeter(std::sync::atomic::AtomicU32);
+
+impl PeakMeter {
+    pub fn feed(&self, data: &[f32]) {
+        let peak = data.iter().fold(0.0f32, |peak, s| peak.max(s.abs())).min(1.0);
+        // Non-negative floats order like their bits
+        self.0.fetch_max(peak.to_bits(), std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Peak since the previous call, in [0, 1]
+    pub fn take(&self) -> f32 {
+        f32::from_bits(self.0.swap(0, std::sync::atomic::Ordering::Relaxed))
+    }
+}
 
 /// List available audio devices (input and output)
 pub fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
@@ -42,86 +65,18 @@ pub fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
     Ok(devices)
 }
 
-/// Start system audio capture (WASAPI loopback) writing to WAV
-pub fn start_system_capture(
-    output_path: &std::path::Path,
-    stop_flag: std::sync::Arc<std::sync::atomic::AtomicBool>,
-) -> Result<std::thread::JoinHandle<()>, String> {
-    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-    use std::sync::atomic::Ordering;
-
-    let host = cpal::default_host();
-    let device = host.default_output_device()
-        .ok_or("No default output device found")?;
-
-    let config = device.default_output_config()
-        .map_err(|e| format!("Failed to get output config: {}", e))?;
-
-    let spec = hound::WavSpec {
-        channels: config.channels(),
-        sample_rate: config.sample_rate().0,
-        bits_per_sample: 32,
-        sample_format: hound::SampleFormat::Float,
-    };
-
-    let path = output_path.to_path_buf();
-    let stop = stop_flag.clone();
-
-    let handle = std::thread::spawn(move || {
-        let writer = match hound::WavWriter::create(&path, spec) {
-            Ok(w) => w,
-            Err(e) => {
-                eprintln!("[audio] Failed to create WAV writer: {}", e);
-                return;
-            }
-        };
-        let writer = std::sync::Arc::new(std::sync::Mutex::new(Some(writer)));
-        let writer_clone = writer.clone();
-
-        let stream = device.build_input_stream(
-            &config.into(),
-            move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                if let Ok(mut guard) = writer_clone.lock() {
-                    if let Some(ref mut w) = *guard {
-                        for &sample in data {
-                            let _ = w.write_sample(sample);
-                        }
-                    }
-                }
-            },
-            |err| eprintln!("[audio] Stream error: {}", err),
-            None,
-        );
-
-        match stream {
-            Ok(stream) => {
-                let _ = stream.play();
-                while !stop.load(Ordering::Relaxed) {
-                    std::thread::sleep(std::time::Duration::from_millis(50));
-                }
-                drop(stream);
-            }
-            Err(e) => {
-                eprintln!("[audio] Failed to build stream: {}", e);
-            }
-        }
-
-        // Finalize WAV
-        if let Ok(mut guard) = writer.lock() {
-            if let Some(w) = guard.take() {
-                let _ = w.finalize();
-            }
-        };
-    });
-
-    Ok(handle)
-}
-
-/// Start microphone capture writing to WAV, optionally using a specific device
+/// Start microphone capture writing to WAV, optionally using a specific device.
+/// When `monitor` is true the mic is also played back on the default output.
+/// Silence is written while `muted` is set, keeping the track in sync.
+/// `meter` follows the level of what is written.
 pub fn start_mic_capture_device(
     output_path: &std::path::Path,
     stop_flag: std::sync::Arc<std::sync::atomic::AtomicBool>,
     device_name: Option<&str>,
+    monitor: bool,
+    muted: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    format: AudioFormat,
+    meter: std::sync::Arc<PeakMeter>,
 ) -> Result<std::thread::JoinHandle<()>, String> {
     use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
     use std::sync::atomic::Ordering;
@@ -140,61 +95,206 @@ pub fn start_mic_capture_device(
     let config = device.default_input_config()
         .map_err(|e| format!("Failed to get input config: {}", e))?;
 
-    let spec = hound::WavSpec {
-        channels: config.channels(),
-        sample_rate: config.sample_rate().0,
-        bits_per_sample: 32,
-        sample_format: hound::SampleFormat::Float,
-    };
+    let in_channels = config.channels();
+    let in_rate = config.sample_rate().0;
 
     let path = output_path.to_path_buf();
     let stop = stop_flag.clone();
 
     let handle = std::thread::spawn(move || {
-        let writer = match hound::WavWriter::create(&path, spec) {
+        let writer = match WavSink::create(&path, in_channels, in_rate, format) {
             Ok(w) => w,
             Err(e) => {
-                eprintln!("[audio] Failed to create mic WAV writer: {}", e);
+                tracing::warn!("Failed to create mic WAV writer: {}", e);
                 return;
             }
         };
         let writer = std::sync::Arc::new(std::sync::Mutex::new(Some(writer)));
         let writer_clone = writer.clone();
 
+        let in_channels = in_channels as usize;
+        let monitor_buf = if monitor {
+            Some(std::sync::Arc::new(std::sync::Mutex::new(VecDeque::<f32>::new())))
+        } else {
+            None
+        };
+        let monitor_in = monitor_buf.clone();
+        let max_buffered = in_rate as usize * MONITOR_MAX_LATENCY_MS / 1000;
+
         let stream = device.build_input_stream(
             &config.into(),
             move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                let is_muted = muted.load(Ordering::Relaxed);
                 if let Ok(mut guard) = writer_clone.lock() {
                     if let Some(ref mut w) = *guard {
-                        for &sample in data {
-                            let _ = w.write_sample(sample);
+                        if is_muted {
+                            w.write_silence((data.len() / in_channels.max(1)) as u64);
+                        } else {
+                            meter.feed(data);
+                            w.write(data);
                         }
                     }
                 }
+                // An empty monitor buffer plays silence
+                if is_muted {
+                    return;
+                }
+                if let Some(ref buf) = monitor_in {
+                    if let Ok(mut b) = buf.lock() {
+                        push_monitor_samples(&mut b, data, in_channels, max_buffered);
+                    }
+                }
             },
-            |err| eprintln!("[audio] Mic stream error: {}", err),
+            |err| tracing::warn!("Mic stream error: {}", err),
             None,
         );
 
+        // Monitoring output must be built on this thread (cpal streams are not Send)
+        let monitor_stream = monitor_buf.and_then(|buf| match build_monitor_output(buf, in_rate) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                tracing::warn!("Failed to start mic monitoring: {}", e);
+                None
+            }
+        });
+
         match stream {
             Ok(stream) => {
                 let _ = stream.play();
+                if let Some(ref m) = monitor_stream {
+                    let _ = m.play();
+                }
                 while !stop.load(Ordering::Relaxed) {
                     std::thread::sleep(std::time::Duration::from_millis(50));
                 }
                 drop(stream);
             }
             Err(e) => {
-                eprintln!("[audio] Failed to build mic stream: {}", e);
+                tracing::warn!("Failed to build mic stream: {}", e);
             }
         }
+        drop(monitor_stream);
 
         if let Ok(mut guard) = writer.lock() {
             if let Some(w) = guard.take() {
-                let _ = w.finalize();
+                w.finalize();
             }
         };
     });
 
     Ok(handle)
 }
+
+/// Downmix interleaved frames to mono and queue them for monitoring,
+/// dropping the oldest samples so latency never exceeds `max_buffered`.
+fn push_monitor_samples(buf: &mut VecDeque<f32>, data: &[f32], channels: usize, max_buffered: usize) {
+    let channels = channels.max(1);
+    for frame in data.chunks(channels) {
+        buf.push_back(frame.iter().sum::<f32>() / frame.len() as f32);
+    }
+    while buf.len() > max_buffered {
+        buf.pop_front();
+    }
+}
+
+/// Fill an interleaved output buffer from the mono monitor queue.
+/// `ratio` is input_rate / output_rate; `phase` carries the resampling position.
+fn fill_monitor_output(buf: &mut VecDeque<f32>, out: &mut [f32], channels: usize, ratio: f64, phase: &mut f64) {
+    let channels = channels.max(1);
+    for frame in out.chunks_mut(channels) {
+        let sample = buf.front().copied().unwrap_or(0.0);
+        for s in frame.iter_mut() {
+            *s = sample;
+        }
+        *phase += ratio;
+        while *phase >= 1.0 {
+            *phase -= 1.0;
+            if buf.pop_front().is_none() {
+                *phase = 0.0;
+                break;
+            }
+        }
+    }
+}
+
+/// Play queued mic samples on the default output device
+fn build_monitor_output(
+    buf: std::sync::Arc<std::sync::Mutex<VecDeque<f32>>>,
+    in_rate: u32,
+) -> Result<cpal::Stream, String> {
+    use cpal::traits::{DeviceTrait, HostTrait};
+
+    let host = cpal::default_host();
+    let device = host.default_output_device()
+        .ok_or("No default output device found")?;
+    let config = device.default_output_config()
+        .map_err(|e| format!("Failed to get output config: {}", e))?;
+
+    let channels = config.channels() as usize;
+    let ratio = in_rate as f64 / config.sample_rate().0 as f64;
+    let mut phase = 0.0;
+
+    device.build_output_stream(
+        &config.into(),
+        move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+            match buf.lock() {
+                Ok(mut b) => fill_monitor_output(&mut b, data, channels, ratio, &mut phase),
+                Err(_) => data.iter_mut().for_each(|s| *s = 0.0),
+            }
+        },
+        |err| tracing::warn!("Monitor stream error: {}", err),
+        None,
+    )
+    .map_err(|e| format!("Failed to build monitor stream: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peak_meter_resets_on_take() {
+        let meter = PeakMeter::default();
+        meter.feed(&[0.1, -0.6, 0.3]);
+        meter.feed(&[0.2]);
+        assert_eq!(meter.take(), 0.6);
+        assert_eq!(meter.take(), 0.0);
+        meter.feed(&[-3.0]);
+        assert_eq!(meter.take(), 1.0);
+    }
+
+    #[test]
+    fn test_push_monitor_downmix() {
+        let mut buf = VecDeque::new();
+        push_monitor_samples(&mut buf, &[0.2, 0.4, -1.0, 1.0], 2, 100);
+        assert_eq!(buf.len(), 2);
+        assert!((buf[0] - 0.3).abs() < 1e-6);
+        assert!(buf[1].abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_push_monitor_caps_latency() {
+        let mut buf = VecDeque::new();
+        push_monitor_samples(&mut buf, &[1.0; 50], 1, 10);
+        assert_eq!(buf.len(), 10);
+    }
+
+    #[test]
+    fn test_fill_monitor_same_rate() {
+        let mut buf: VecDeque<f32> = vec![0.1, 0.2, 0.3].into();
+        let mut out = [0.0f32; 6];
+        let mut phase = 0.0;
+        fill_monitor_output(&mut buf, &mut out, 2, 1.0, &mut phase);
+        assert_eq!(out, [0.1, 0.1, 0.2, 0.2, 0.3, 0.3]);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_fill_monitor_underrun_is_silent() {
+        let mut buf = VecDeque::new();
+        let mut out = [1.0f32; 4];
+        let mut phase = 0.0;
+        fill_monitor_output(&mut buf, &mut out, 1, 1.0, &mut phase);
+        assert_eq!(out, [0.0; 4]);
+    }
+}
@@ -0,0 +1,418 @@
+use super::wgc;
+use crate::types::{CaptureBackendInfo, CaptureBackendKind, CaptureWindow, FfmpegCapabilities, MonitorArea, Region};
+use anyhow::Result;
+
+/// Where a recording's frames come from. Every backend is an FFmpeg input,
+/// a device, a filter or raw frames piped into its stdin, so all of them are
+/// started, paused (the segment is closed) and stopped the same way by
+/// `screen::start_capture` / `screen::stop_capture`: a backend only says how
+/// to open the screen and what it can record.
+pub trait CaptureBackend: Sync {
+    fn kind(&self) -> CaptureBackendKind;
+
+    /// Filter or device of the FFmpeg build it relies on, if not built in
+    fn requirement(&self) -> Option<&'static str>;
+
+    /// Frames are grabbed on the GPU
+    fn gpu(&self) -> bool;
+
+    /// A region may cross monitor edges
+    fn spans_monitors(&self) -> bool;
+
+    /// This version of Windows has what it needs
+    fn os_supported(&self) -> bool {
+        true
+    }
+
+    /// FFmpeg input arguments grabbing `region` (None = whole desktop) at
+    /// `framerate`, the region being one `supports` accepted
+    fn input_args(&self, region: Option<&Region>, monitors: &[MonitorArea], framerate: u32) -> Vec<String>;
+
+    /// Whether it can record `region` of this desktop
+    fn supports(&self, region: Option<&Region>, monitors: &[MonitorArea]) -> bool {
+        self.spans_monitors() || monitor_of(region, monitors).is_some()
+    }
+
+    /// Start grabbing the frames FFmpeg reads from its stdin, for backends
+    /// that don't let FFmpeg open the screen. Called before FFmpeg starts,
+    /// so a backend failing here can still be swapped for another.
+    fn open_frames(&self, _region: Option<&Region>, _monitors: &[MonitorArea], _framerate: u32) -> Result<Option<wgc::Frames>> {
+        Ok(None)
+    }
+}
+
+/// GDI screen grab: any region of the virtual desktop, on the CPU
+pub struct Gdigrab;
+
+impl CaptureBackend for Gdigrab {
+    fn kind(&self) -> CaptureBackendKind {
+        CaptureBackendKind::Gdigrab
+    }
+
+    fn requirement(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn gpu(&self) -> bool {
+        false
+    }
+
+    fn spans_monitors(&self) -> bool {
+        true
+    }
+
+    fn input_args(&self, region: Option<&Region>, _monitors: &[MonitorArea], framerate: u32) -> Vec<String> {
+        let mut args = vec!["-f".to_string(), "gdigrab".into(), "-framerate".into(), framerate.to_string()];
+        if let Some(region) = region {
+            let region = clamp_region(region);
+            args.extend([
+                "-offset_x".into(), region.x.to_string(),
+                "-offset_y".into(), region.y.to_string(),
+                "-video_size".into(), format!("{}x{}", region.width, region.height),
+            ]);
+        }
+        args.extend(["-draw_mouse".into(), "1".into(), "-i".into(), "desktop".into()]);
+        args
+    }
+}
+
+/// Desktop Duplication (DXGI) grab: one monitor at a time, on the GPU,
+/// which keeps up with hardware-accelerated and full-screen windows
+pub struct Ddagrab;
+
+impl CaptureBackend for Ddagrab {
+    fn kind(&self) -> CaptureBackendKind {
+        CaptureBackendKind::Ddagrab
+    }
+
+    fn requirement(&self) -> Option<&'static str> {
+        Some("ddagrab")
+    }
+
+    fn gpu(&self) -> bool {
+        true
+    }
+
+    fn spans_monitors(&self) -> bool {
+        false
+    }
+
+    fn input_args(&self, region: Option<&Region>, monitors: &[MonitorArea], framerate: u32) -> Vec<String> {
+        let index = monitor_of(region, monitors).unwrap_or(0);
+        let monitor = monitors.get(index);
+        let output = monitor.and_then(|m| Ddagrab::output_index(&m.bounds)).unwrap_or_else(|| {
+            tracing::warn!("No DXGI output found for monitor {}, assuming the same order", index);
+            index as u32
+        });
+        Ddagrab::source(output, region, monitor, framerate)
+    }
+}
+
+impl Ddagrab {
+    /// DXGI index of the output showing `monitor`. ddagrab counts the
+    /// outputs of the default adapter, whose order needn't follow the
+    /// monitor enumeration, so the two are matched by monitor handle.
+    fn output_index(monitor: &Region) -> Option<u32> {
+        use windows::Win32::Foundation::POINT;
+        use windows::Win32::Graphics::Dxgi::{CreateDXGIFactory1, IDXGIFactory1};
+        use windows::Win32::Graphics::Gdi::{MonitorFromPoint, MONITOR_DEFAULTTONULL};
+
+        let center = POINT {
+            x: monitor.x + monitor.width as i32 / 2,
+            y: monitor.y + monitor.height as i32 / 2,
+        };
+        unsafe {
+            let handle = MonitorFromPoint(center, MONITOR_DEFAULTTONULL);
+            if handle.is_invalid() {
+                return None;
+            }
+            let factory = CreateDXGIFactory1::<IDXGIFactory1>().ok()?;
+            let adapter = factory.EnumAdapters1(0).ok()?;
+            let mut o = 0;
+            while let Ok(output) = adapter.EnumOutputs(o) {
+                if output.GetDesc().is_ok_and(|desc| desc.Monitor == handle) {
+                    return Some(o);
+                }
+                o += 1;
+            }
+        }
+        None
+    }
+
+    /// The lavfi source grabbing DXGI output `output`, which shows `monitor`
+    fn source(output: u32, region: Option<&Region>, monitor: Option<&MonitorArea>, framerate: u32) -> Vec<String> {
+        let mut source = format!("ddagrab=output_idx={output}:framerate={framerate}:draw_mouse=1");
+        if let (Some(region), Some(monitor)) = (region, monitor) {
+            // Offsets are relative to the monitor, not the virtual desktop
+            let region = clamp_region(region);
+            source.push_str(&format!(
+                ":offset_x={}:offset_y={}:video_size={}x{}",
+                region.x - monitor.bounds.x,
+                region.y - monitor.bounds.y,
+                region.width,
+                region.height,
+            ));
+        }
+        // Download the frames for the CPU encoders
+        source.push_str(",hwdownload,format=bgra");
+        vec!["-f".into(), "lavfi".into(), "-i".into(), source]
+    }
+}
+
+/// Windows Graphics Capture: one monitor at a time, grabbed on the GPU by
+/// the compositor at its own refresh rate, high-DPI and hardware-accelerated
+/// windows included. The frames reach FFmpeg as raw BGRA through its stdin.
+pub struct Wgc;
+
+impl Wgc {
+    /// Monitor holding the recorded area, and that area, both in desktop
+    /// pixels: all of the monitor for the whole desktop
+    fn area(region: Option<&Region>, monitors: &[MonitorArea]) -> Option<(Region, Region)> {
+        let monitor = &monitors.get(monitor_of(region, monitors)?)?.bounds;
+        let area = clamp_region(region.unwrap_or(monitor));
+        Some((monitor.clone(), area))
+    }
+
+    /// FFmpeg input arguments reading `width`x`height` BGRA frames from stdin
+    fn raw_input_args(width: u32, height: u32, framerate: u32) -> Vec<String> {
+        vec![
+            "-f".into(), "rawvideo".into(),
+            "-pix_fmt".into(), "bgra".into(),
+            "-video_size".into(), format!("{width}x{height}"),
+            "-framerate".into(), framerate.to_string(),
+            "-i".into(), "-".into(),
+        ]
+    }
+
+    /// Start grabbing `window` wherever it is dragged, with the FFmpeg input
+    /// arguments reading its frames. Only this backend can follow a window.
+    pub fn open_window(window: &CaptureWindow, framerate: u32) -> Result<(Vec<String>, wgc::Frames)> {
+        if !wgc::is_supported() {
+            anyhow::bail!("Windows Graphics Capture isn't available");
+        }
+        let frames = wgc::Frames::open_window(window, framerate)?;
+        Ok((Wgc::raw_input_args(window.width, window.height, framerate), frames))
+    }
+}
+
+impl CaptureBackend for Wgc {
+    fn kind(&self) -> CaptureBackendKind {
+        CaptureBackendKind::Wgc
+    }
+
+    fn requirement(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn gpu(&self) -> bool {
+        true
+    }
+
+    fn spans_monitors(&self) -> bool {
+        false
+    }
+
+    fn os_supported(&self) -> bool {
+        wgc::is_supported()
+    }
+
+    fn input_args(&self, region: Option<&Region>, monitors: &[MonitorArea], framerate: u32) -> Vec<String> {
+        let size = Wgc::area(region, monitors).map(|(_, area)| (area.width, area.height)).unwrap_or((1920, 1080));
+        Wgc::raw_input_args(size.0, size.1, framerate)
+    }
+
+    fn open_frames(&self, region: Option<&Region>, monitors: &[MonitorArea], framerate: u32) -> Result<Option<wgc::Frames>> {
+        let (monitor, area) = Wgc::area(region, monitors)
+            .ok_or_else(|| anyhow::anyhow!("No monitor holds the whole capture region"))?;
+        wgc::Frames::open(&monitor, &area, framerate).map(Some)
+    }
+}
+
+const ALL: [&dyn CaptureBackend; 3] = [&Gdigrab, &Wgc, &Ddagrab];
+
+pub fn backend(kind: CaptureBackendKind) -> &'static dyn CaptureBackend {
+    match kind {
+        CaptureBackendKind::Gdigrab => &Gdigrab,
+        CaptureBackendKind::Ddagrab => &Ddagrab,
+        CaptureBackendKind::Wgc => &Wgc,
+    }
+}
+
+/// The `preferred` backend when it can record `region` on this system, else
+/// gdigrab, which records anything
+pub fn select(
+    preferred: CaptureBackendKind,
+    region: Option<&Region>,
+    monitors: &[MonitorArea],
+) -> &'static dyn CaptureBackend {
+    let wanted = backend(preferred);
+    if wanted.os_supported() && wanted.supports(region, monitors) {
+        return wanted;
+    }
+    tracing::info!("{:?} capture can't record this region, using gdigrab", preferred);
+    &Gdigrab
+}
+
+/// Every backend and what it can do with this FFmpeg build
+pub fn describe(caps: Option<&FfmpegCapabilities>) -> Vec<CaptureBackendInfo> {
+    ALL.iter()
+        .map(|b| CaptureBackendInfo {
+            kind: b.kind(),
+            // An empty or missing probe gates nothing, as for exports
+            available: b.os_supported()
+                && match (b.requirement(), caps) {
+                    (Some(name), Some(caps)) if !caps.filters.is_empty() => caps.has_filter(name),
+                    _ => true,
+                },
+            gpu: b.gpu(),
+            spans_monitors: b.spans_monitors(),
+        })
+        .collect()
+}
+
+/// Index of the monitor holding all of `region`, or the only monitor for
+/// the whole desktop
+pub(crate) fn monitor_of(region: Option<&Region>, monitors: &[MonitorArea]) -> Option<usize> {
+    let Some(region) = region else {
+        return (monitors.len() == 1).then_some(0);
+    };
+    let region = clamp_region(region);
+    monitors.iter().position(|m| {
+        let b = &m.bounds;
+        region.x >= b.x
+            && region.y >= b.y
+            && region.x + region.width as i32 <= b.x + b.width as i32
+            && region.y + region.height as i32 <= b.y + b.height as i32
+    })
+}
+
+/// Clamp a region so it stays within the virtual desktop bounds.
+/// Negative coordinates (from window shadow borders) and overflow are trimmed.
+/// Width/height are forced to even values (required by libx264 yuv420p).
+fn clamp_region(region: &Region) -> Region {
+    let mut x = region.x;
+    let mut y = region.y;
+    let mut w = region.width as i32;
+    let mut h = region.height as i32;
+
+    // If origin is negative, shrink dimensions and clamp to 0
+    if x < 0 {
+        w += x; // reduce width by the overshoot
+        x = 0;
+    }
+    if y < 0 {
+        h += y;
+        y = 0;
+    }
+
+    // Ensure positive dimensions
+    if w < 2 { w = 2; }
+    if h < 2 { h = 2; }
+
+    // Force even dimensions for h264 yuv420p
+    let w = (w as u32) & !1;
+    let h = (h as u32) & !1;
+
+    Region { x, y, width: w, height: h }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(x: i32, width: u32) -> MonitorArea {
+        let bounds = Region { x, y: 0, width, height: 1080 };
+        MonitorArea { bounds: bounds.clone(), work_area: bounds, is_primary: x == 0, refresh_rate: 60 }
+    }
+
+    #[test]
+    fn test_clamp_region_normal() {
+        let r = clamp_region(&Region { x: 100, y: 200, width: 800, height: 600 });
+        assert_eq!(r, Region { x: 100, y: 200, width: 800, height: 600 });
+    }
+
+    #[test]
+    fn test_clamp_region_negative_y() {
+        // Window shadow: y=-8, height=1048 → y=0, height=1040
+        let r = clamp_region(&Region { x: 1912, y: -8, width: 1936, height: 1048 });
+        assert_eq!(r.x, 0.max(1912));
+        assert_eq!(r.y, 0);
+        assert_eq!(r.height, 1040);
+    }
+
+    #[test]
+    fn test_clamp_region_negative_x_and_y() {
+        let r = clamp_region(&Region { x: -10, y: -8, width: 1000, height: 800 });
+        assert_eq!(r.x, 0);
+        assert_eq!(r.y, 0);
+        assert_eq!(r.width, 990);
+        assert_eq!(r.height, 792);
+    }
+
+    #[test]
+    fn test_clamp_region_odd_dimensions() {
+        let r = clamp_region(&Region { x: 0, y: 0, width: 801, height: 601 });
+        assert_eq!(r.width, 800);
+        assert_eq!(r.height, 600);
+    }
+
+    #[test]
+    fn test_clamp_region_minimum_size() {
+        // Extremely negative: width would go to 0 or negative
+        let r = clamp_region(&Region { x: -500, y: -500, width: 100, height: 100 });
+        assert_eq!(r.x, 0);
+        assert_eq!(r.y, 0);
+        assert!(r.width >= 2);
+        assert!(r.height >= 2);
+    }
+
+    #[test]
+    fn test_gdigrab_args() {
+        let args = Gdigrab.input_args(Some(&Region { x: 10, y: 20, width: 641, height: 480 }), &[], 30);
+        assert_eq!(
+            args.join(" "),
+            "-f gdigrab -framerate 30 -offset_x 10 -offset_y 20 -video_size 640x480 -draw_mouse 1 -i desktop"
+        );
+        assert_eq!(Gdigrab.input_args(None, &[], 60).join(" "), "-f gdigrab -framerate 60 -draw_mouse 1 -i desktop");
+    }
+
+    #[test]
+    fn test_ddagrab_offsets_within_the_monitor() {
+        let monitors = [monitor(0, 1920), monitor(1920, 2560)];
+        let region = Region { x: 2020, y: 100, width: 1280, height: 720 };
+        // The second monitor may be the first DXGI output
+        let args = Ddagrab::source(0, Some(&region), monitors.get(1), 30);
+        assert_eq!(
+            args[3],
+            "ddagrab=output_idx=0:framerate=30:draw_mouse=1:offset_x=100:offset_y=100:video_size=1280x720,hwdownload,format=bgra"
+        );
+    }
+
+    #[test]
+    fn test_wgc_reads_the_area_from_stdin() {
+        let monitors = [monitor(0, 1920), monitor(1920, 2560)];
+        let region = Region { x: 2020, y: 100, width: 1281, height: 720 };
+        assert_eq!(
+            Wgc.input_args(Some(&region), &monitors, 60).join(" "),
+            "-f rawvideo -pix_fmt bgra -video_size 1280x720 -framerate 60 -i -"
+        );
+        let (bounds, area) = Wgc::area(Some(&region), &monitors).unwrap();
+        assert_eq!((bounds.x, area.x, area.width), (1920, 2020, 1280));
+        // The whole desktop of a single monitor
+        let (_, area) = Wgc::area(None, &monitors[..1]).unwrap();
+        assert_eq!((area.width, area.height), (1920, 1080));
+        assert!(Wgc::area(None, &monitors).is_none());
+    }
+
+    #[test]
+    fn test_select_falls_back_across_monitors() {
+        let monitors = [monitor(0, 1920), monitor(1920, 2560)];
+        let inside = Region { x: 100, y: 100, width: 800, height: 600 };
+        let across = Region { x: 1800, y: 100, width: 800, height: 600 };
+        let pick = |region: Option<&Region>| select(CaptureBackendKind::Ddagrab, region, &monitors).kind();
+        assert_eq!(pick(Some(&inside)), CaptureBackendKind::Ddagrab);
+        assert_eq!(pick(Some(&across)), CaptureBackendKind::Gdigrab);
+        assert_eq!(pick(None), CaptureBackendKind::Gdigrab);
+        assert_eq!(select(CaptureBackendKind::Ddagrab, None, &monitors[..1]).kind(), CaptureBackendKind::Ddagrab);
+    }
+}
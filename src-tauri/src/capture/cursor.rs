@@ -1,7 +1,14 @@
+use crate::recording::timing::{DriftTracker, RecordingClock};
 use crate::types::{CursorPosition, Region};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+
+/// Largest distance (normalized to the region) a dropped sample may be from
+/// the kept path, about 4 px on a 1080p capture
+pub const DECIMATION_TOLERANCE: f64 = 0.002;
+/// Samples buffered before they are decimated into the kept path (30 s at 10 Hz)
+const DECIMATE_EVERY: usize = 300;
 
 pub struct CursorTrackingHandle {
     pub stop_flag: Arc<AtomicBool>,
@@ -9,8 +16,11 @@ pub struct CursorTrackingHandle {
     pub join_handle: Option<std::thread::JoinHandle<()>>,
 }
 
-/// Start tracking cursor position relative to the capture region
-pub fn start_tracking(region: &Region, start_time: Instant) -> CursorTrackingHandle {
+/// Start tracking cursor position relative to the capture region. Samples
+/// are stamped in video time, read off the capture's `progress` file, so
+/// zooms keep up with the visible cursor however much the capture drifts
+/// from the wall clock. Nothing is sampled while the recording is paused.
+pub fn start_tracking(region: &Region, clock: RecordingClock, progress: PathBuf) -> CursorTrackingHandle {
     let stop_flag = Arc::new(AtomicBool::new(false));
     let positions = Arc::new(Mutex::new(Vec::new()));
 
@@ -25,20 +35,38 @@ pub fn start_tracking(region: &Region, start_time: Instant) -> CursorTrackingHan
         use windows::Win32::Foundation::POINT;
         use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
 
+        // Raw samples since the last decimation, the first one already kept
+        let mut pending: Vec<CursorPosition> = Vec::new();
+        let mut drift = DriftTracker::new(progress);
         while !stop.load(Ordering::Relaxed) {
+            drift.poll(&clock);
             let mut point = POINT::default();
-            let ok = unsafe { GetCursorPos(&mut point) };
-            if ok.is_ok() {
-                let timestamp_ms = start_time.elapsed().as_millis() as u64;
+            let ok = !clock.is_paused() && unsafe { GetCursorPos(&mut point) }.is_ok();
+            if ok {
+                let timestamp_ms = clock.elapsed_ms();
                 let x = ((point.x - reg_x) as f64 / reg_w).clamp(0.0, 1.0);
                 let y = ((point.y - reg_y) as f64 / reg_h).clamp(0.0, 1.0);
 
-                if let Ok(mut p) = pos.lock() {
-                    p.push(CursorPosition { timestamp_ms, x, y });
+                pending.push(CursorPosition { timestamp_ms, x, y });
+                if pending.len() >= DECIMATE_EVERY {
+                    let kept = decimate(&pending, DECIMATION_TOLERANCE);
+                    if let Ok(mut p) = pos.lock() {
+                        p.extend_from_slice(&kept[..kept.len() - 1]);
+                    }
+                    // The last kept sample starts the next batch
+                    pending = kept[kept.len() - 1..].to_vec();
                 }
             }
             std::thread::sleep(std::time::Duration::from_millis(100));
         }
+        if let Ok(mut p) = pos.lock() {
+            p.extend(decimate(&pending, DECIMATION_TOLERANCE));
+            // Decimated in wall-clock time: the drift moves too slowly to
+            // change which samples matter
+            for sample in p.iter_mut() {
+                sample.timestamp_ms = crate::recording::timing::video_ms(drift.points(), sample.timestamp_ms);
+            }
+        }
     });
 
     CursorTrackingHandle {
@@ -56,3 +84,113 @@ pub fn stop_tracking(handle: &mut CursorTrackingHandle) -> Vec<CursorPosition> {
     }
     handle.positions.lock().map(|p| p.clone()).unwrap_or_default()
 }
+
+/// Douglas–Peucker over the cursor path. Distances are measured to where the
+/// kept segment is at the sample's own timestamp, so pauses and speed changes
+/// survive along with the shape. Endpoints are always kept.
+pub fn decimate(points: &[CursorPosition], tolerance: f64) -> Vec<CursorPosition> {
+    if points.len() <= 2 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    // Explicit stack: an hour of samples would be too deep for recursion
+    let mut ranges = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = ranges.pop() {
+        let (mut worst, mut worst_dist) = (0, 0.0);
+        for i in first + 1..last {
+            let d = synced_distance(&points[i], &points[first], &points[last]);
+            if d > worst_dist {
+                (worst, worst_dist) = (i, d);
+            }
+        }
+        if worst_dist > tolerance {
+            keep[worst] = true;
+            ranges.push((first, worst));
+            ranges.push((worst, last));
+        }
+    }
+    points.iter().zip(keep).filter(|(_, k)| *k).map(|(p, _)| p.clone()).collect()
+}
+
+/// [`decimate`] with the tolerance doubled until at most `max_points` are
+/// left, so filter expressions built from the path stay small
+pub fn thin(points: &[CursorPosition], tolerance: f64, max_points: usize) -> Vec<CursorPosition> {
+    let mut tolerance = tolerance;
+    let mut kept = decimate(points, tolerance);
+    while kept.len() > max_points {
+        tolerance *= 2.0;
+        kept = decimate(points, tolerance);
+    }
+    kept
+}
+
+/// Distance from `p` to the point of segment `a`-`b` at `p`'s timestamp
+fn synced_distance(p: &CursorPosition, a: &CursorPosition, b: &CursorPosition) -> f64 {
+    let f = synced_fraction(a, b, p.timestamp_ms);
+    let (x, y) = (a.x + (b.x - a.x) * f, a.y + (b.y - a.y) * f);
+    ((p.x - x).powi(2) + (p.y - y).powi(2)).sqrt()
+}
+
+/// How far along `a`-`b` the path is at `timestamp_ms` (0-1)
+fn synced_fraction(a: &CursorPosition, b: &CursorPosition, timestamp_ms: u64) -> f64 {
+    let span = b.timestamp_ms.saturating_sub(a.timestamp_ms) as f64;
+    if span > 0.0 {
+        (timestamp_ms.saturating_sub(a.timestamp_ms) as f64 / span).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Cursor position at `timestamp_ms`, interpolated along the (decimated)
+/// path and held before the first and after the last sample
+pub fn position_at(points: &[CursorPosition], timestamp_ms: u64) -> Option<(f64, f64)> {
+    let first = points.first()?;
+    if timestamp_ms <= first.timestamp_ms {
+        return Some((first.x, first.y));
+    }
+    let next = points.partition_point(|p| p.timestamp_ms <= timestamp_ms);
+    let Some(b) = points.get(next) else {
+        return points.last().map(|p| (p.x, p.y));
+    };
+    let a = &points[next - 1];
+    let f = synced_fraction(a, b, timestamp_ms);
+    Some((a.x + (b.x - a.x) * f, a.y + (b.y - a.y) * f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(timestamp_ms: u64, x: f64, y: f64) -> CursorPosition {
+        CursorPosition { timestamp_ms, x, y }
+    }
+
+    #[test]
+    fn test_decimate_drops_straight_runs() {
+        let line: Vec<CursorPosition> = (0..=10).map(|i| at(i * 100, i as f64 * 0.05, 0.5)).collect();
+        let kept = decimate(&line, DECIMATION_TOLERANCE);
+        assert_eq!(kept.len(), 2);
+        assert_eq!((kept[0].timestamp_ms, kept[1].timestamp_ms), (0, 1000));
+    }
+
+    #[test]
+    fn test_decimate_keeps_corners_and_pauses() {
+        // Right, then a pause, then down
+        let mut path: Vec<CursorPosition> = (0..=5).map(|i| at(i * 100, i as f64 * 0.1, 0.2)).collect();
+        path.extend((6..=10).map(|i| at(i * 100, 0.5, 0.2)));
+        path.extend((11..=15).map(|i| at(i * 100, 0.5, 0.2 + (i - 10) as f64 * 0.1)));
+        let kept: Vec<u64> = decimate(&path, DECIMATION_TOLERANCE).iter().map(|p| p.timestamp_ms).collect();
+        assert_eq!(kept, vec![0, 500, 1000, 1500]);
+    }
+
+    #[test]
+    fn test_position_at_interpolates_and_holds() {
+        let path = [at(1000, 0.0, 0.0), at(2000, 1.0, 0.5)];
+        assert_eq!(position_at(&path, 0), Some((0.0, 0.0)));
+        assert_eq!(position_at(&path, 1500), Some((0.5, 0.25)));
+        assert_eq!(position_at(&path, 5000), Some((1.0, 0.5)));
+        assert_eq!(position_at(&[], 0), None);
+    }
+}
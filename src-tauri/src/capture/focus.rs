@@ -0,0 +1,193 @@
+use crate::types::{FocusChange, Region};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+const POLL_INTERVAL_MS: u64 = 250;
+/// A window must stay focused (and in place) this long before the framing
+/// moves to it, so alt-tabbing through windows doesn't cut to each of them
+const DEBOUNCE_MS: u64 = 1000;
+/// Windows smaller than this (physical pixels) are popups, not worth framing
+const MIN_SIZE: i32 = 200;
+
+pub struct FocusTrackingHandle {
+    pub stop_flag: Arc<AtomicBool>,
+    pub changes: Arc<Mutex<Vec<FocusChange>>>,
+    pub join_handle: Option<std::thread::JoinHandle<()>>,
+}
+
+/// Start following the foreground window over the capture `region`
+pub fn start_tracking(region: &Region, start_time: Instant) -> FocusTrackingHandle {
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let changes = Arc::new(Mutex::new(Vec::new()));
+
+    let stop = stop_flag.clone();
+    let recorded = changes.clone();
+    let region = region.clone();
+
+    let handle = std::thread::spawn(move || {
+        let mut debouncer = Debouncer::default();
+        while !stop.load(Ordering::Relaxed) {
+            let timestamp_ms = start_time.elapsed().as_millis() as u64;
+            let framed = foreground_rect().and_then(|rect| normalize(rect, &region));
+            if let Some(change) = debouncer.observe(timestamp_ms, framed) {
+                if let Ok(mut c) = recorded.lock() {
+                    c.push(change);
+                }
+            }
+            std::thread::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS));
+        }
+    });
+
+    FocusTrackingHandle {
+        stop_flag,
+        changes,
+        join_handle: Some(handle),
+    }
+}
+
+/// Stop tracking and return the framing changes
+pub fn stop_tracking(handle: &mut FocusTrackingHandle) -> Vec<FocusChange> {
+    handle.stop_flag.store(true, Ordering::Relaxed);
+    if let Some(h) = handle.join_handle.take() {
+        let _ = h.join();
+    }
+    handle.changes.lock().map(|c| c.clone()).unwrap_or_default()
+}
+
+/// Screen rect (left, top, right, bottom) of the foreground window, without
+/// its drop shadow. None for ClipFlow's own windows, minimized or tiny ones.
+fn foreground_rect() -> Option<(i32, i32, i32, i32)> {
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS};
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowRect, GetWindowThreadProcessId, IsIconic};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_invalid() || IsIconic(hwnd).as_bool() {
+            return None;
+        }
+        // Clicking pause in ClipFlow must not reframe on ClipFlow
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid as *mut u32));
+        if pid == std::process::id() {
+            return None;
+        }
+
+        let mut rect = RECT::default();
+        let bounds = DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut rect as *mut _ as *mut _,
+            std::mem::size_of::<RECT>() as u32,
+        );
+        if bounds.is_err() && GetWindowRect(hwnd, &mut rect).is_err() {
+            return None;
+        }
+        Some((rect.left, rect.top, rect.right, rect.bottom))
+    }
+}
+
+/// Part of `rect` inside the capture region, normalized to it. None when
+/// too little of the window is on the recorded screen.
+fn normalize(rect: (i32, i32, i32, i32), region: &Region) -> Option<FocusChange> {
+    let (left, top, right, bottom) = rect;
+    let left = left.max(region.x);
+    let top = top.max(region.y);
+    let right = right.min(region.x + region.width as i32);
+    let bottom = bottom.min(region.y + region.height as i32);
+    if right - left < MIN_SIZE || bottom - top < MIN_SIZE {
+        return None;
+    }
+    let (w, h) = (region.width.max(1) as f64, region.height.max(1) as f64);
+    Some(FocusChange {
+        timestamp_ms: 0,
+        x: (left - region.x) as f64 / w,
+        y: (top - region.y) as f64 / h,
+        width: (right - left) as f64 / w,
+        height: (bottom - top) as f64 / h,
+    })
+}
+
+/// Turns polled window rects into framing changes once they have settled
+#[derive(Default)]
+struct Debouncer {
+    /// Framing in use
+    current: Option<FocusChange>,
+    /// Framing waiting for the debounce delay, with the time it was first seen
+    candidate: Option<FocusChange>,
+}
+
+impl Debouncer {
+    fn observe(&mut self, timestamp_ms: u64, framed: Option<FocusChange>) -> Option<FocusChange> {
+        // Nothing worth framing: keep the current window
+        let framed = framed?;
+        if matches!(&self.current, Some(c) if same_rect(c, &framed)) {
+            self.candidate = None;
+            return None;
+        }
+        match &self.candidate {
+            Some(c) if same_rect(c, &framed) => {
+                if timestamp_ms < c.timestamp_ms + DEBOUNCE_MS {
+                    return None;
+                }
+                let change = self.candidate.take();
+                self.current = change.clone();
+                change
+            }
+            _ => {
+                // The first window is framed from the very start of the clip
+                if self.current.is_none() {
+                    let first = FocusChange { timestamp_ms: 0, ..framed };
+                    self.current = Some(first.clone());
+                    return Some(first);
+                }
+                self.candidate = Some(FocusChange { timestamp_ms, ..framed });
+                None
+            }
+        }
+    }
+}
+
+fn same_rect(a: &FocusChange, b: &FocusChange) -> bool {
+    (a.x, a.y, a.width, a.height) == (b.x, b.y, b.width, b.height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen() -> Region {
+        Region { x: 0, y: 0, width: 2000, height: 1000 }
+    }
+
+    #[test]
+    fn test_normalize_clips_to_region() {
+        let f = normalize((-100, 100, 1000, 600), &screen()).unwrap();
+        assert_eq!((f.x, f.y, f.width, f.height), (0.0, 0.1, 0.5, 0.5));
+        assert!(normalize((1900, 0, 2500, 800), &screen()).is_none());
+        let offset = Region { x: 2000, y: 0, width: 2000, height: 1000 };
+        assert_eq!(normalize((2500, 0, 3000, 500), &offset).unwrap().x, 0.25);
+    }
+
+    #[test]
+    fn test_debouncer_waits_for_focus_to_settle() {
+        let screen = screen();
+        let editor = normalize((0, 0, 1000, 1000), &screen);
+        let browser = normalize((1000, 0, 2000, 1000), &screen);
+        let mut d = Debouncer::default();
+
+        // The first window is framed from the start
+        assert_eq!(d.observe(300, editor.clone()).unwrap().timestamp_ms, 0);
+        assert!(d.observe(600, editor.clone()).is_none());
+        // A brief alt-tab is ignored
+        assert!(d.observe(1000, browser.clone()).is_none());
+        assert!(d.observe(1250, editor.clone()).is_none());
+        assert!(d.observe(2500, browser.clone()).is_none());
+        // Nothing to frame (desktop, ClipFlow) keeps the pending switch going
+        assert!(d.observe(3000, None).is_none());
+        let change = d.observe(3500, browser.clone()).unwrap();
+        assert_eq!((change.timestamp_ms, change.x), (2500, 0.5));
+        assert!(d.observe(4000, browser).is_none());
+    }
+}
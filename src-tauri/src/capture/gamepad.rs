@@ -0,0 +1,171 @@
+use crate::types::GamepadSample;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+// XInput button flags, as stored in `GamepadSample::buttons`
+pub const DPAD_UP: u16 = 0x0001;
+pub const DPAD_DOWN: u16 = 0x0002;
+pub const DPAD_LEFT: u16 = 0x0004;
+pub const DPAD_RIGHT: u16 = 0x0008;
+pub const START: u16 = 0x0010;
+pub const BACK: u16 = 0x0020;
+pub const LEFT_THUMB: u16 = 0x0040;
+pub const RIGHT_THUMB: u16 = 0x0080;
+pub const LEFT_SHOULDER: u16 = 0x0100;
+pub const RIGHT_SHOULDER: u16 = 0x0200;
+pub const BUTTON_A: u16 = 0x1000;
+pub const BUTTON_B: u16 = 0x2000;
+pub const BUTTON_X: u16 = 0x4000;
+pub const BUTTON_Y: u16 = 0x8000;
+
+/// About 30 Hz, enough for button taps without flooding the overlay
+const POLL_INTERVAL_MS: u64 = 33;
+/// How often empty slots are probed while no controller is connected
+const SCAN_INTERVAL_MS: u64 = 1000;
+/// Stick values under this are noise (XInput's recommended dead zone)
+const STICK_DEADZONE: i16 = 7849;
+/// Trigger values under this count as released
+const TRIGGER_THRESHOLD: u8 = 30;
+
+pub struct GamepadPollingHandle {
+    pub stop_flag: Arc<AtomicBool>,
+    pub samples: Arc<Mutex<Vec<GamepadSample>>>,
+    pub join_handle: Option<std::thread::JoinHandle<()>>,
+}
+
+/// Start polling the first connected XInput controller
+pub fn start_polling(start_time: Instant) -> GamepadPollingHandle {
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let samples = Arc::new(Mutex::new(Vec::new()));
+
+    let stop = stop_flag.clone();
+    let recorded = samples.clone();
+
+    let handle = std::thread::spawn(move || {
+        use windows::Win32::UI::Input::XboxController::{XInputGetState, XINPUT_STATE, XUSER_MAX_COUNT};
+
+        let read = |index: u32| {
+            let mut state = XINPUT_STATE::default();
+            // 0 is ERROR_SUCCESS, anything else means nothing is plugged in
+            (unsafe { XInputGetState(index, &mut state) } == 0).then_some(state)
+        };
+
+        let mut pad: Option<u32> = None;
+        let mut last_scan: Option<Instant> = None;
+        let mut last: Option<GamepadSample> = None;
+        while !stop.load(Ordering::Relaxed) {
+            let scan_due = !matches!(last_scan, Some(t) if (t.elapsed().as_millis() as u64) < SCAN_INTERVAL_MS);
+            if pad.is_none() && scan_due {
+                last_scan = Some(Instant::now());
+                pad = (0..XUSER_MAX_COUNT).find(|i| read(*i).is_some());
+                if let Some(index) = pad {
+                    tracing::info!("Gamepad {} connected", index);
+                }
+            }
+
+            if let Some(index) = pad {
+                let timestamp_ms = start_time.elapsed().as_millis() as u64;
+                let current = match read(index) {
+                    Some(state) => {
+                        let g = state.Gamepad;
+                        let sticks = [g.sThumbLX, g.sThumbLY, g.sThumbRX, g.sThumbRY];
+                        sample(timestamp_ms, g.wButtons.0, sticks, [g.bLeftTrigger, g.bRightTrigger])
+                    }
+                    None => {
+                        // Unplugged: release everything, then look for another one
+                        tracing::info!("Gamepad {} disconnected", index);
+                        pad = None;
+                        sample(timestamp_ms, 0, [0; 4], [0; 2])
+                    }
+                };
+                if !matches!(last, Some(l) if same_state(&l, &current)) {
+                    if let Ok(mut s) = recorded.lock() {
+                        s.push(current);
+                    }
+                    last = Some(current);
+                }
+            }
+            std::thread::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS));
+        }
+    });
+
+    GamepadPollingHandle {
+        stop_flag,
+        samples,
+        join_handle: Some(handle),
+    }
+}
+
+/// Stop polling and return the recorded state changes
+pub fn stop_polling(handle: &mut GamepadPollingHandle) -> Vec<GamepadSample> {
+    handle.stop_flag.store(true, Ordering::Relaxed);
+    if let Some(h) = handle.join_handle.take() {
+        let _ = h.join();
+    }
+    handle.samples.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// Quantize a raw XInput reading: sticks as left x/y then right x/y,
+/// triggers as left then right
+pub fn sample(timestamp_ms: u64, buttons: u16, sticks: [i16; 4], triggers: [u8; 2]) -> GamepadSample {
+    GamepadSample {
+        timestamp_ms,
+        buttons,
+        left_x: stick_tenths(sticks[0]),
+        left_y: stick_tenths(sticks[1]),
+        right_x: stick_tenths(sticks[2]),
+        right_y: stick_tenths(sticks[3]),
+        left_trigger: trigger_tenths(triggers[0]),
+        right_trigger: trigger_tenths(triggers[1]),
+    }
+}
+
+fn stick_tenths(raw: i16) -> i8 {
+    if raw.unsigned_abs() < STICK_DEADZONE as u16 {
+        return 0;
+    }
+    (raw as f64 / i16::MAX as f64 * 10.0).round().clamp(-10.0, 10.0) as i8
+}
+
+fn trigger_tenths(raw: u8) -> u8 {
+    if raw < TRIGGER_THRESHOLD {
+        return 0;
+    }
+    (raw as f64 / u8::MAX as f64 * 10.0).round() as u8
+}
+
+/// Same buttons, sticks and triggers, whatever the time
+pub fn same_state(a: &GamepadSample, b: &GamepadSample) -> bool {
+    GamepadSample { timestamp_ms: 0, ..*a } == GamepadSample { timestamp_ms: 0, ..*b }
+}
+
+/// Nothing pressed, sticks centered
+pub fn is_idle(s: &GamepadSample) -> bool {
+    same_state(s, &GamepadSample { timestamp_ms: 0, ..Default::default() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_quantizes_and_ignores_noise() {
+        let s = sample(100, BUTTON_A, [3000, -32768, 32767, 16384], [20, 255]);
+        assert_eq!(s.buttons, BUTTON_A);
+        assert_eq!((s.left_x, s.left_y), (0, -10));
+        assert_eq!((s.right_x, s.right_y), (10, 5));
+        assert_eq!((s.left_trigger, s.right_trigger), (0, 10));
+    }
+
+    #[test]
+    fn test_same_state_ignores_time() {
+        let a = sample(100, 0, [20000, 0, 0, 0], [0, 0]);
+        // A tiny stick drift lands in the same tenth
+        let b = sample(500, 0, [20300, 0, 0, 0], [0, 0]);
+        assert!(same_state(&a, &b));
+        assert!(!same_state(&a, &sample(500, BUTTON_B, [20000, 0, 0, 0], [0, 0])));
+        assert!(!is_idle(&a));
+        assert!(is_idle(&sample(0, 0, [100, -100, 0, 0], [10, 0])));
+    }
+}
@@ -0,0 +1,70 @@
+use crate::types::Region;
+use windows::core::Interface;
+use windows::Win32::Graphics::Dxgi::Common::DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020;
+use windows::Win32::Graphics::Dxgi::{CreateDXGIFactory1, IDXGIFactory1, IDXGIOutput6};
+
+/// Desktop bounds of every display currently running in HDR (PQ / BT.2020)
+pub fn hdr_displays() -> Vec<Region> {
+    let mut displays = Vec::new();
+    unsafe {
+        let Ok(factory) = CreateDXGIFactory1::<IDXGIFactory1>() else {
+            return displays;
+        };
+        let mut a = 0;
+        while let Ok(adapter) = factory.EnumAdapters1(a) {
+            let mut o = 0;
+            while let Ok(output) = adapter.EnumOutputs(o) {
+                // IDXGIOutput6 needs Windows 10 1803+, older systems have no HDR desktop
+                if let Ok(desc) = output.cast::<IDXGIOutput6>().and_then(|out| out.GetDesc1()) {
+                    if desc.ColorSpace == DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020 {
+                        let r = desc.DesktopCoordinates;
+                        displays.push(Region {
+                            x: r.left,
+                            y: r.top,
+                            width: (r.right - r.left).max(0) as u32,
+                            height: (r.bottom - r.top).max(0) as u32,
+                        });
+                    }
+                }
+                o += 1;
+            }
+            a += 1;
+        }
+    }
+    displays
+}
+
+/// Whether a capture region shows any part of an HDR display
+pub fn region_is_hdr(region: &Region) -> bool {
+    let displays = hdr_displays();
+    if !displays.is_empty() {
+        tracing::debug!("HDR displays: {:?}", displays);
+    }
+    overlaps_any(region, &displays)
+}
+
+fn overlaps_any(region: &Region, displays: &[Region]) -> bool {
+    displays.iter().any(|d| {
+        region.x < d.x + d.width as i32
+            && d.x < region.x + region.width as i32
+            && region.y < d.y + d.height as i32
+            && d.y < region.y + region.height as i32
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(x: i32, y: i32, width: u32, height: u32) -> Region {
+        Region { x, y, width, height }
+    }
+
+    #[test]
+    fn test_overlaps_any() {
+        let displays = [r(1920, 0, 2560, 1440)];
+        assert!(overlaps_any(&r(1800, 100, 400, 300), &displays));
+        assert!(!overlaps_any(&r(0, 0, 1920, 1080), &displays));
+        assert!(!overlaps_any(&r(0, 0, 1920, 1080), &[]));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::types::{FfmpegCapabilities, RecordingEncoder, RecordingEncoderInfo};
+use std::process::Stdio;
+
+/// GPU encoders in the order Auto tries them
+pub const PREFERENCE: [RecordingEncoder; 4] = [
+    RecordingEncoder::H264Nvenc,
+    RecordingEncoder::HevcNvenc,
+    RecordingEncoder::H264Qsv,
+    RecordingEncoder::H264Amf,
+];
+
+/// GPU encoders of `caps` that can encode here. Builds list NVENC, QSV and
+/// AMF whatever the GPU, so each one listed encodes a test frame first.
+pub async fn detect(caps: &FfmpegCapabilities) -> Vec<RecordingEncoder> {
+    let mut working = Vec::new();
+    for encoder in PREFERENCE {
+        if caps.has_encoder(encoder.encoder_name()) && encodes(encoder).await {
+            working.push(encoder);
+        }
+    }
+    tracing::info!("GPU capture encoders: {:?}", working);
+    working
+}
+
+async fn encodes(encoder: RecordingEncoder) -> bool {
+    // Smaller frames are refused by some of them
+    let status = crate::ffmpeg_command()
+        .args(["-hide_banner", "-f", "lavfi", "-i", "color=black:size=256x256:duration=0.1", "-frames:v", "1"])
+        .args(["-c:v", encoder.encoder_name(), "-f", "null", "-"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await;
+    matches!(status, Ok(s) if s.success())
+}
+
+/// Encoder to record with for the user's `choice`, given the `detected`
+/// ones (None while the probe hasn't run). A GPU encoder that doesn't work
+/// here falls back to libx264.
+pub fn resolve(choice: RecordingEncoder, detected: Option<&[RecordingEncoder]>) -> RecordingEncoder {
+    match (choice, detected) {
+        (RecordingEncoder::Auto, Some(detected)) => detected.first().copied().unwrap_or(RecordingEncoder::Software),
+        (RecordingEncoder::Auto, None) => RecordingEncoder::Software,
+        (RecordingEncoder::Software, _) | (_, None) => choice,
+        (_, Some(detected)) if detected.contains(&choice) => choice,
+        _ => {
+            tracing::warn!("{} doesn't work here, recording with libx264", choice.encoder_name());
+            RecordingEncoder::Software
+        }
+    }
+}
+
+/// Every choice offered to the user, Auto and libx264 always available
+pub fn describe(detected: Option<&[RecordingEncoder]>) -> Vec<RecordingEncoderInfo> {
+    let auto = resolve(RecordingEncoder::Auto, detected);
+    [RecordingEncoder::Auto, RecordingEncoder::Software]
+        .into_iter()
+        .chain(PREFERENCE)
+        .map(|kind| RecordingEncoderInfo {
+            kind,
+            available: matches!(kind, RecordingEncoder::Auto | RecordingEncoder::Software)
+                || detected.is_some_and(|d| d.contains(&kind)),
+            auto: kind == auto,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_prefers_detected_gpu() {
+        let detected = [RecordingEncoder::H264Qsv, RecordingEncoder::H264Amf];
+        assert_eq!(resolve(RecordingEncoder::Auto, Some(&detected)), RecordingEncoder::H264Qsv);
+        assert_eq!(resolve(RecordingEncoder::Auto, Some(&[])), RecordingEncoder::Software);
+        assert_eq!(resolve(RecordingEncoder::Auto, None), RecordingEncoder::Software);
+        // Overrides
+        assert_eq!(resolve(RecordingEncoder::H264Amf, Some(&detected)), RecordingEncoder::H264Amf);
+        assert_eq!(resolve(RecordingEncoder::Software, Some(&detected)), RecordingEncoder::Software);
+        assert_eq!(resolve(RecordingEncoder::H264Nvenc, Some(&detected)), RecordingEncoder::Software);
+        // Not probed yet: trusted
+        assert_eq!(resolve(RecordingEncoder::H264Nvenc, None), RecordingEncoder::H264Nvenc);
+    }
+
+    #[test]
+    fn test_describe() {
+        let infos = describe(Some(&[RecordingEncoder::HevcNvenc]));
+        assert_eq!(infos.len(), 6);
+        let info = |kind| infos.iter().find(|i| i.kind == kind).unwrap();
+        assert!(info(RecordingEncoder::Software).available);
+        assert!(info(RecordingEncoder::HevcNvenc).available && info(RecordingEncoder::HevcNvenc).auto);
+        assert!(!info(RecordingEncoder::H264Nvenc).available);
+        assert_eq!(infos.iter().filter(|i| i.auto).count(), 1);
+    }
+}
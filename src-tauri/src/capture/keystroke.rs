@@ -1,3 +1,4 @@
+use crate::recording::timing::RecordingClock;
 use crate::types::KeystrokeEvent;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
@@ -21,6 +22,22 @@ const VK_LBUTTON: u32 = 0x01;
 const VK_RBUTTON: u32 = 0x02;
 const VK_MBUTTON: u32 = 0x04;
 
+/// Wheel distance of one notch
+const WHEEL_DELTA: i32 = 120;
+/// Wheel steps this close together extend the same scroll label
+const SCROLL_MERGE_MS: u64 = 400;
+/// Past this a long scroll starts a new label, so it stays on screen
+const SCROLL_RUN_MAX_MS: u64 = 1500;
+
+/// A scroll gesture whose label is still being extended
+struct ScrollRun {
+    index: usize,
+    start_ms: u64,
+    last_ms: u64,
+    horizontal: bool,
+    total: i32,
+}
+
 fn is_modifier(vk: u32) -> bool {
     matches!(vk, VK_SHIFT | VK_CTRL | VK_ALT | VK_LWIN | VK_RWIN
         | 0xA0 | 0xA1  // LShift, RShift
@@ -45,8 +62,55 @@ fn modifier_prefix(get_state: impl Fn(u32) -> bool) -> String {
     }
 }
 
-/// Start capturing keystrokes and mouse clicks
-pub fn start_capture(start_time: Instant) -> Result<KeystrokeCaptureHandle, String> {
+/// "Molette ↓ ×3": direction and amount in notches
+fn scroll_label(horizontal: bool, total: i32) -> String {
+    let arrow = match (horizontal, total > 0) {
+        (false, true) => "\u{2191}",
+        (false, false) => "\u{2193}",
+        (true, true) => "\u{2192}",
+        (true, false) => "\u{2190}",
+    };
+    let notches = ((total.abs() as f64 / WHEEL_DELTA as f64).round() as i32).max(1);
+    let wheel = crate::i18n::t("scroll_wheel");
+    if notches > 1 {
+        format!("{wheel} {arrow} \u{d7}{notches}")
+    } else {
+        format!("{wheel} {arrow}")
+    }
+}
+
+/// Record a wheel step, extending the current scroll label while the
+/// gesture goes on in the same direction. Returns the label.
+fn record_scroll(
+    events: &mut Vec<KeystrokeEvent>,
+    run: &mut Option<ScrollRun>,
+    timestamp_ms: u64,
+    horizontal: bool,
+    delta: i32,
+) -> String {
+    if let Some(r) = run.as_mut() {
+        let continues = r.horizontal == horizontal
+            && (r.total > 0) == (delta > 0)
+            && timestamp_ms.saturating_sub(r.last_ms) <= SCROLL_MERGE_MS
+            && timestamp_ms.saturating_sub(r.start_ms) <= SCROLL_RUN_MAX_MS;
+        if continues {
+            if let Some(event) = events.get_mut(r.index) {
+                r.total += delta;
+                r.last_ms = timestamp_ms;
+                event.key_name = scroll_label(horizontal, r.total);
+                return event.key_name.clone();
+            }
+        }
+    }
+    let key_name = scroll_label(horizontal, delta);
+    *run = Some(ScrollRun { index: events.len(), start_ms: timestamp_ms, last_ms: timestamp_ms, horizontal, total: delta });
+    events.push(KeystrokeEvent { timestamp_ms, key_name: key_name.clone() });
+    key_name
+}
+
+/// Start capturing keystrokes, mouse clicks and scrolling, stamped with the
+/// recording `clock` and left out while it is paused
+pub fn start_capture(clock: RecordingClock) -> Result<KeystrokeCaptureHandle, String> {
     let stop_flag = Arc::new(AtomicBool::new(false));
     let events = Arc::new(Mutex::new(Vec::new()));
 
@@ -57,6 +121,8 @@ pub fn start_capture(start_time: Instant) -> Result<KeystrokeCaptureHandle, Stri
         use windows::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
 
         let mut prev_states = [false; 256];
+        let wheel = super::wheel::WheelHook::start();
+        let mut scroll_run = None;
 
         let get_pressed = |vk: u32| -> bool {
             let state = unsafe { GetAsyncKeyState(vk as i32) };
@@ -64,11 +130,22 @@ pub fn start_capture(start_time: Instant) -> Result<KeystrokeCaptureHandle, Stri
         };
 
         while !stop.load(Ordering::Relaxed) {
+            // Input during a pause is not in the video
+            let paused = clock.is_paused();
+            for tick in wheel.drain() {
+                if paused {
+                    continue;
+                }
+                let timestamp_ms = clock.at(tick.at).as_millis() as u64;
+                if let Ok(mut e) = evts.lock() {
+                    record_scroll(&mut e, &mut scroll_run, timestamp_ms, tick.horizontal, tick.delta);
+                }
+            }
             for vk in 0u32..256 {
                 let pressed = get_pressed(vk);
                 let was_pressed = prev_states[vk as usize];
 
-                if pressed && !was_pressed {
+                if pressed && !was_pressed && !paused {
                     // Skip modifier-only presses — they'll be part of combos
                     if is_modifier(vk) {
                         prev_states[vk as usize] = pressed;
@@ -84,7 +161,7 @@ pub fn start_capture(start_time: Instant) -> Result<KeystrokeCaptureHandle, Stri
                     let prefix = modifier_prefix(&get_pressed);
                     let key_name = format!("{}{}", prefix, base_name);
 
-                    let timestamp_ms = start_time.elapsed().as_millis() as u64;
+                    let timestamp_ms = clock.elapsed_ms();
                     if let Ok(mut e) = evts.lock() {
                         e.push(KeystrokeEvent { timestamp_ms, key_name });
                     }
@@ -105,7 +182,7 @@ pub fn start_capture(start_time: Instant) -> Result<KeystrokeCaptureHandle, Stri
 /// Start capturing keystrokes with live event emission to the frontend.
 /// Emits `keystroke-live` events via the AppHandle for real-time display.
 pub fn start_capture_with_emitter(
-    start_time: Instant,
+    clock: RecordingClock,
     app_handle: tauri::AppHandle,
 ) -> Result<KeystrokeCaptureHandle, String> {
     let stop_flag = Arc::new(AtomicBool::new(false));
@@ -120,6 +197,8 @@ pub fn start_capture_with_emitter(
 
         let mut prev_states = [false; 256];
         let mut last_emit = Instant::now();
+        let wheel = super::wheel::WheelHook::start();
+        let mut scroll_run = None;
 
         let get_pressed = |vk: u32| -> bool {
             let state = unsafe { GetAsyncKeyState(vk as i32) };
@@ -127,11 +206,27 @@ pub fn start_capture_with_emitter(
         };
 
         while !stop.load(Ordering::Relaxed) {
+            // Input during a pause is not in the video
+            let paused = clock.is_paused();
+            for tick in wheel.drain() {
+                if paused {
+                    continue;
+                }
+                let timestamp_ms = clock.at(tick.at).as_millis() as u64;
+                let label = match evts.lock() {
+                    Ok(mut e) => record_scroll(&mut e, &mut scroll_run, timestamp_ms, tick.horizontal, tick.delta),
+                    Err(_) => continue,
+                };
+                if last_emit.elapsed().as_millis() >= 50 {
+                    let _ = app_handle.emit("keystroke-live", &label);
+                    last_emit = Instant::now();
+                }
+            }
             for vk in 0u32..256 {
                 let pressed = get_pressed(vk);
                 let was_pressed = prev_states[vk as usize];
 
-                if pressed && !was_pressed {
+                if pressed && !was_pressed && !paused {
                     if is_modifier(vk) {
                         prev_states[vk as usize] = pressed;
                         continue;
@@ -146,7 +241,7 @@ pub fn start_capture_with_emitter(
                     let prefix = modifier_prefix(&get_pressed);
                     let key_name = format!("{}{}", prefix, base_name);
 
-                    let timestamp_ms = start_time.elapsed().as_millis() as u64;
+                    let timestamp_ms = clock.elapsed_ms();
                     if let Ok(mut e) = evts.lock() {
                         e.push(KeystrokeEvent { timestamp_ms, key_name: key_name.clone() });
                     }
@@ -293,6 +388,33 @@ mod tests {
         assert_eq!(vk_to_name(0x6B), "+");
     }
 
+    // ── scrolling ──
+
+    #[test]
+    fn test_scroll_label() {
+        assert_eq!(scroll_label(false, 120), "Molette \u{2191}");
+        assert_eq!(scroll_label(false, -360), "Molette \u{2193} \u{d7}3");
+        assert_eq!(scroll_label(true, 240), "Molette \u{2192} \u{d7}2");
+        // High-resolution wheels send fractions of a notch
+        assert_eq!(scroll_label(false, -30), "Molette \u{2193}");
+    }
+
+    #[test]
+    fn test_record_scroll_merges_one_gesture() {
+        let mut events = Vec::new();
+        let mut run = None;
+        record_scroll(&mut events, &mut run, 1000, false, -120);
+        record_scroll(&mut events, &mut run, 1100, false, -120);
+        assert_eq!(record_scroll(&mut events, &mut run, 1200, false, -120), "Molette \u{2193} \u{d7}3");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].timestamp_ms, 1000);
+
+        // Direction change, then a pause: new labels
+        record_scroll(&mut events, &mut run, 1300, false, 120);
+        record_scroll(&mut events, &mut run, 2000, false, 120);
+        assert_eq!(events.len(), 3);
+    }
+
     #[test]
     fn test_vk_unknown() {
         assert_eq!(vk_to_name(0xFF), "");
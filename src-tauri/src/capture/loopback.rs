@@ -0,0 +1,301 @@
+use super::audio::PeakMeter;
+use super::wav::WavSink;
+use crate::types::AudioFormat;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use windows::core::GUID;
+use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+use windows::Win32::Media::Audio::{
+    eConsole, eRender, IAudioCaptureClient, IAudioClient, IMMDevice, IMMDeviceEnumerator, MMDeviceEnumerator,
+    AUDCLNT_BUFFERFLAGS_SILENT, AUDCLNT_SHAREMODE_SHARED, AUDCLNT_STREAMFLAGS_LOOPBACK, DEVICE_STATE_ACTIVE,
+    WAVEFORMATEX, WAVEFORMATEXTENSIBLE,
+};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED, STGM_READ,
+};
+
+/// Shared-mode buffer length, in 100 ns units (200 ms)
+const BUFFER_DURATION: i64 = 2_000_000;
+/// How often the capture buffer is drained
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// Loopback delivers nothing while the device plays nothing: a longer gap
+/// is filled with silence so the track stays as long as the video
+const MAX_GAP_MS: u64 = 100;
+
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+/// KSDATAFORMAT_SUBTYPE_PCM and KSDATAFORMAT_SUBTYPE_IEEE_FLOAT
+const SUBTYPE_PCM: GUID = GUID::from_u128(0x00000001_0000_0010_8000_00aa00389b71);
+const SUBTYPE_IEEE_FLOAT: GUID = GUID::from_u128(0x00000003_0000_0010_8000_00aa00389b71);
+
+/// Sample type of the device mix format
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Sample {
+    F32,
+    I16,
+    I24,
+    I32,
+}
+
+impl Sample {
+    /// From the format tag, the container size and, for extensible
+    /// formats, their sub-format
+    fn of(tag: u16, bits: u16, sub_format: Option<GUID>) -> Option<Self> {
+        let float = match (tag, sub_format) {
+            (WAVE_FORMAT_IEEE_FLOAT, _) => true,
+            (WAVE_FORMAT_PCM, _) => false,
+            (WAVE_FORMAT_EXTENSIBLE, Some(SUBTYPE_IEEE_FLOAT)) => true,
+            (WAVE_FORMAT_EXTENSIBLE, Some(SUBTYPE_PCM)) => false,
+            _ => return None,
+        };
+        match (float, bits) {
+            (true, 32) => Some(Sample::F32),
+            (false, 16) => Some(Sample::I16),
+            (false, 24) => Some(Sample::I24),
+            // Also 24-bit samples padded to 32, left-justified
+            (false, 32) => Some(Sample::I32),
+            _ => None,
+        }
+    }
+
+    fn bytes(self) -> usize {
+        match self {
+            Sample::I16 => 2,
+            Sample::I24 => 3,
+            Sample::F32 | Sample::I32 => 4,
+        }
+    }
+
+    /// One little-endian sample as a float in [-1, 1]
+    fn decode(self, b: &[u8]) -> f32 {
+        match self {
+            Sample::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            Sample::I16 => i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+            Sample::I24 => i32::from_le_bytes([0, b[0], b[1], b[2]]) as f32 / 2_147_483_648.0,
+            Sample::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0,
+        }
+    }
+}
+
+/// Frames of silence bringing `written` up to `elapsed` of capture, none
+/// while the gap is within `MAX_GAP_MS`
+fn missing_frames(elapsed: Duration, written: u64, rate: u32) -> u64 {
+    let expected = elapsed.as_millis() as u64 * rate as u64 / 1000;
+    let gap = expected.saturating_sub(written);
+    if gap * 1000 > MAX_GAP_MS * rate as u64 {
+        gap
+    } else {
+        0
+    }
+}
+
+/// Start capturing what `device_name` (the default output when None) plays,
+/// through WASAPI loopback, to a WAV in `format`. Unlike an input stream
+/// opened on an output device this works with every driver and hears
+/// exclusive-mode audio. `meter` follows the level of what is captured.
+pub fn start_capture(
+    output_path: &Path,
+    stop_flag: Arc<AtomicBool>,
+    device_name: Option<&str>,
+    format: AudioFormat,
+    meter: Arc<PeakMeter>,
+) -> Result<std::thread::JoinHandle<()>, String> {
+    let path = output_path.to_path_buf();
+    let device_name = device_name.map(str::to_string);
+    let (ready_tx, ready_rx) = std::sync::mpsc::sync_channel(1);
+
+    // COM objects stay on the thread that created them
+    let handle = std::thread::spawn(move || {
+        let _ = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+        match Loopback::open(device_name.as_deref(), &path, format) {
+            Ok(loopback) => {
+                let _ = ready_tx.send(Ok(()));
+                if let Err(e) = loopback.run(&stop_flag, &meter) {
+                    tracing::warn!("{}", e);
+                }
+            }
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+            }
+        }
+        unsafe { CoUninitialize() };
+    });
+
+    match ready_rx.recv() {
+        Ok(Ok(())) => Ok(handle),
+        Ok(Err(e)) => {
+            let _ = handle.join();
+            Err(e)
+        }
+        Err(_) => Err("System audio capture thread stopped".into()),
+    }
+}
+
+/// Output device named `name`, as cpal lists it
+fn find_output(enumerator: &IMMDeviceEnumerator, name: &str) -> Result<IMMDevice, String> {
+    unsafe {
+        let devices = enumerator
+            .EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)
+            .map_err(|e| format!("Failed to list output devices: {}", e))?;
+        let count = devices.GetCount().map_err(|e| format!("Failed to list output devices: {}", e))?;
+        for i in 0..count {
+            if let Ok(device) = devices.Item(i) {
+                if friendly_name(&device).as_deref() == Some(name) {
+                    return Ok(device);
+                }
+            }
+        }
+    }
+    Err(format!("Output device '{}' not found", name))
+}
+
+/// Name Windows shows for `device`
+fn friendly_name(device: &IMMDevice) -> Option<String> {
+    unsafe {
+        let store = device.OpenPropertyStore(STGM_READ).ok()?;
+        let value = store.GetValue(&PKEY_Device_FriendlyName).ok()?;
+        Some(value.to_string())
+    }
+}
+
+struct Loopback {
+    client: IAudioClient,
+    capture: IAudioCaptureClient,
+    sample: Sample,
+    channels: u16,
+    rate: u32,
+    writer: WavSink,
+}
+
+impl Loopback {
+    fn open(device_name: Option<&str>, path: &Path, format: AudioFormat) -> Result<Self, String> {
+        unsafe {
+            let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
+            let device = match device_name {
+                Some(name) => find_output(&enumerator, name)?,
+                None => enumerator
+                    .GetDefaultAudioEndpoint(eRender, eConsole)
+                    .map_err(|e| format!("No default output device found: {}", e))?,
+            };
+            let client: IAudioClient = device
+                .Activate(CLSCTX_ALL, None)
+                .map_err(|e| format!("Failed to open output device: {}", e))?;
+
+            // Shared mode captures in the mix format, whatever it is
+            let mix_format = client.GetMixFormat().map_err(|e| format!("Failed to get output config: {}", e))?;
+            let mix = *mix_format;
+            let (tag, channels, rate, bits) = (mix.wFormatTag, mix.nChannels, mix.nSamplesPerSec, mix.wBitsPerSample);
+            let sub_format = (tag == WAVE_FORMAT_EXTENSIBLE).then(|| (*(mix_format as *const WAVEFORMATEXTENSIBLE)).SubFormat);
+            let init = client.Initialize(
+                AUDCLNT_SHAREMODE_SHARED,
+                AUDCLNT_STREAMFLAGS_LOOPBACK,
+                BUFFER_DURATION,
+                0,
+                mix_format as *const WAVEFORMATEX,
+                None,
+            );
+            CoTaskMemFree(Some(mix_format as *const _));
+            init.map_err(|e| format!("Failed to start loopback capture: {}", e))?;
+
+            let sample = Sample::of(tag, bits, sub_format)
+                .ok_or_else(|| format!("Unsupported output format (tag {:#x}, {} bits)", tag, bits))?;
+            let capture: IAudioCaptureClient = client
+                .GetService()
+                .map_err(|e| format!("Failed to start loopback capture: {}", e))?;
+
+            let writer = WavSink::create(path, channels, rate, format)?;
+            Ok(Loopback { client, capture, sample, channels, rate, writer })
+        }
+    }
+
+    /// Record until `stop` is set. A device lost on the way (unplugged
+    /// headset) ends the track early, what it holds is kept.
+    fn run(mut self, stop: &AtomicBool, meter: &PeakMeter) -> Result<(), String> {
+        unsafe { self.client.Start() }.map_err(|e| format!("Failed to start loopback capture: {}", e))?;
+        let started = Instant::now();
+        let mut written = 0u64;
+        let mut result = Ok(());
+        while !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(POLL_INTERVAL);
+            match self.drain(meter) {
+                Ok(frames) => written += frames,
+                Err(e) => {
+                    result = Err(format!("Loopback capture stopped: {}", e));
+                    break;
+                }
+            }
+            let missing = missing_frames(started.elapsed(), written, self.rate);
+            if missing > 0 {
+                self.writer.write_silence(missing);
+                written += missing;
+            }
+        }
+        let _ = unsafe { self.client.Stop() };
+        self.writer.finalize();
+        result
+    }
+
+    /// Write every packet waiting in the capture buffer, returning how many
+    /// frames they held
+    fn drain(&mut self, meter: &PeakMeter) -> windows::core::Result<u64> {
+        let sample_bytes = self.sample.bytes();
+        let mut total = 0;
+        unsafe {
+            while self.capture.GetNextPacketSize()? > 0 {
+                let mut data = std::ptr::null_mut();
+                let mut frames = 0;
+                let mut flags = 0;
+                self.capture.GetBuffer(&mut data, &mut frames, &mut flags, None, None)?;
+                if data.is_null() || flags & AUDCLNT_BUFFERFLAGS_SILENT.0 as u32 != 0 {
+                    self.writer.write_silence(frames as u64);
+                } else {
+                    let len = frames as usize * self.channels as usize * sample_bytes;
+                    let samples: Vec<f32> = std::slice::from_raw_parts(data, len)
+                        .chunks_exact(sample_bytes)
+                        .map(|b| self.sample.decode(b))
+                        .collect();
+                    meter.feed(&samples);
+                    self.writer.write(&samples);
+                }
+                self.capture.ReleaseBuffer(frames)?;
+                total += frames as u64;
+            }
+        }
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_of_mix_format() {
+        assert_eq!(Sample::of(WAVE_FORMAT_EXTENSIBLE, 32, Some(SUBTYPE_IEEE_FLOAT)), Some(Sample::F32));
+        assert_eq!(Sample::of(WAVE_FORMAT_EXTENSIBLE, 32, Some(SUBTYPE_PCM)), Some(Sample::I32));
+        assert_eq!(Sample::of(WAVE_FORMAT_PCM, 16, None), Some(Sample::I16));
+        assert_eq!(Sample::of(WAVE_FORMAT_IEEE_FLOAT, 64, None), None);
+        assert_eq!(Sample::of(WAVE_FORMAT_EXTENSIBLE, 32, None), None);
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(Sample::F32.decode(&0.25f32.to_le_bytes()), 0.25);
+        assert_eq!(Sample::I16.decode(&i16::MIN.to_le_bytes()), -1.0);
+        assert_eq!(Sample::I24.decode(&[0x00, 0x00, 0x40]), 0.5);
+        assert_eq!(Sample::I32.decode(&(1i32 << 30).to_le_bytes()), 0.5);
+    }
+
+    #[test]
+    fn test_silence_fills_long_gaps_only() {
+        // 50 ms behind at 48 kHz: packets are just late
+        assert_eq!(missing_frames(Duration::from_millis(1000), 45_600, 48_000), 0);
+        // Nothing played for half a second
+        assert_eq!(missing_frames(Duration::from_millis(1000), 24_000, 48_000), 24_000);
+        assert_eq!(missing_frames(Duration::from_millis(100), 48_000, 48_000), 0);
+    }
+}
@@ -1,4 +1,14 @@
 pub mod screen;
 pub mod audio;
+pub mod backend;
 pub mod cursor;
+pub mod focus;
+pub mod gamepad;
+pub mod hdr;
+pub mod hwaccel;
 pub mod keystroke;
+pub mod loopback;
+pub mod snapshot;
+pub mod wav;
+pub mod wgc;
+pub mod wheel;
@@ -1,100 +1,76 @@
-use crate::types::Region;
+use super::backend;
+use crate::types::{CaptureBackendKind, CaptureWindow, RecordingQuality, Region};
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use tokio::process::Child;
 
-/// Clamp a region so it stays within the virtual desktop bounds for gdigrab.
-/// Negative coordinates (from window shadow borders) and overflow are trimmed.
-/// Width/height are forced to even values (required by libx264 yuv420p).
-fn clamp_region(region: &Region) -> Region {
-    let mut x = region.x;
-    let mut y = region.y;
-    let mut w = region.width as i32;
-    let mut h = region.height as i32;
-
-    // If origin is negative, shrink dimensions and clamp to 0
-    if x < 0 {
-        w += x; // reduce width by the overshoot
-        x = 0;
-    }
-    if y < 0 {
-        h += y;
-        y = 0;
-    }
-
-    // Ensure positive dimensions
-    if w < 2 { w = 2; }
-    if h < 2 { h = 2; }
-
-    // Force even dimensions for h264 yuv420p
-    let w = (w as u32) & !1;
-    let h = (h as u32) & !1;
-
-    Region { x, y, width: w, height: h }
-}
-
-/// Start screen capture of a region using FFmpeg's gdigrab
+/// Start recording `region` (None = whole desktop) with the `preferred`
+/// backend, or with gdigrab when that one can't record it. With a `window`
+/// bound, its own frames are recorded instead wherever it moves, and
+/// `region` only when that fails.
 pub fn start_capture(
-    region: &Region,
-    output_path: &PathBuf,
+    preferred: CaptureBackendKind,
+    region: Option<&Region>,
+    window: Option<&CaptureWindow>,
+    output_path: &Path,
     framerate: u32,
+    quality: &RecordingQuality,
+    progress: Option<&Path>,
 ) -> Result<Child> {
-    let region = clamp_region(region);
+    let (input_args, frames) = match window.map(|w| backend::Wgc::open_window(w, framerate)) {
+        Some(Ok((args, frames))) => (args, Some(frames)),
+        opened => {
+            if let Some(Err(e)) = opened {
+                tracing::warn!("Window capture failed to start, recording where it is: {:#}", e);
+            }
+            screen_input(preferred, region, framerate)
+        }
+    };
 
-    let child = crate::ffmpeg_command()
-        .args([
-            "-f", "gdigrab",
-            "-framerate", &framerate.to_string(),
-            "-offset_x", &region.x.to_string(),
-            "-offset_y", &region.y.to_string(),
-            "-video_size", &format!("{}x{}", region.width, region.height),
-            "-draw_mouse", "1",
-            "-i", "desktop",
-            "-c:v", "libx264",
-            "-preset", "ultrafast",
-            "-crf", "18",
-            "-pix_fmt", "yuv420p",
-            "-y",
-            &output_path.to_string_lossy(),
-        ])
+    let mut cmd = crate::ffmpeg_command();
+    // Frames actually captured, for the recording stats
+    if let Some(progress) = progress {
+        cmd.args(["-progress", &*progress.to_string_lossy(), "-stats_period", "1"]);
+    }
+    let mut child = cmd
+        .args(input_args)
+        .args(quality.encoder_args())
+        .args(["-y", &output_path.to_string_lossy()])
         .stdin(Stdio::piped())
         .stdout(Stdio::null())
         .stderr(Stdio::piped())
         .kill_on_drop(true)
         .spawn()?;
+    if let Some(frames) = frames {
+        frames.pipe_into(&mut child);
+    }
 
     Ok(child)
 }
 
-/// Start full-screen capture using FFmpeg's gdigrab
-pub fn start_fullscreen_capture(
-    output_path: &PathBuf,
+/// FFmpeg input arguments recording `region` with the `preferred` backend,
+/// or gdigrab, and the frames to pipe in for backends that need it
+fn screen_input(
+    preferred: CaptureBackendKind,
+    region: Option<&Region>,
     framerate: u32,
-) -> Result<Child> {
-    let child = crate::ffmpeg_command()
-        .args([
-            "-f", "gdigrab",
-            "-framerate", &framerate.to_string(),
-            "-draw_mouse", "1",
-            "-i", "desktop",
-            "-c:v", "libx264",
-            "-preset", "ultrafast",
-            "-crf", "18",
-            "-pix_fmt", "yuv420p",
-            "-y",
-            &output_path.to_string_lossy(),
-        ])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::null())
-        .stderr(Stdio::piped())
-        .kill_on_drop(true)
-        .spawn()?;
-
-    Ok(child)
+) -> (Vec<String>, Option<super::wgc::Frames>) {
+    let monitors = crate::region::selector::enumerate_monitor_areas().unwrap_or_default();
+    let mut backend = backend::select(preferred, region, &monitors);
+    let frames = match backend.open_frames(region, &monitors, framerate) {
+        Ok(frames) => frames,
+        Err(e) => {
+            tracing::warn!("{:?} capture failed to start, using gdigrab: {:#}", backend.kind(), e);
+            backend = &backend::Gdigrab;
+            None
+        }
+    };
+    (backend.input_args(region, &monitors, framerate), frames)
 }
 
-/// Stop an FFmpeg capture by sending 'q' to stdin
+/// Stop an FFmpeg capture by sending 'q' to stdin, or by closing it when
+/// frames are piped in
 pub async fn stop_capture(child: &mut Child) -> Result<()> {
     // Drain stderr concurrently to avoid pipe buffer deadlock
     let stderr_task = child.stderr.take().map(|mut stderr| {
@@ -106,6 +82,9 @@ pub async fn stop_capture(child: &mut Child) -> Result<()> {
         })
     });
 
+    if let Some(pid) = child.id() {
+        super::wgc::stop(pid);
+    }
     // Send quit command
     if let Some(stdin) = child.stdin.as_mut() {
         use tokio::io::AsyncWriteExt;
@@ -123,71 +102,92 @@ pub async fn stop_capture(child: &mut Child) -> Result<()> {
 
     if !status.success() {
         let snippet: String = stderr_output.chars().rev().take(500).collect::<String>().chars().rev().collect();
-        eprintln!("[screen] FFmpeg capture exited with {:?}. Stderr: {}", status.code(), snippet);
+        tracing::error!("FFmpeg capture exited with {:?}. Stderr: {}", status.code(), snippet);
         anyhow::bail!("FFmpeg capture failed (exit code {:?}): {}", status.code(), snippet);
     }
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_clamp_region_normal() {
-        let r = clamp_region(&Region { x: 100, y: 200, width: 800, height: 600 });
-        assert_eq!(r, Region { x: 100, y: 200, width: 800, height: 600 });
-    }
-
-    #[test]
-    fn test_clamp_region_negative_y() {
-        // Window shadow: y=-8, height=1048 → y=0, height=1040
-        let r = clamp_region(&Region { x: 1912, y: -8, width: 1936, height: 1048 });
-        assert_eq!(r.x, 0.max(1912));
-        assert_eq!(r.y, 0);
-        assert_eq!(r.height, 1040);
-    }
-
-    #[test]
-    fn test_clamp_region_negative_x_and_y() {
-        let r = clamp_region(&Region { x: -10, y: -8, width: 1000, height: 800 });
-        assert_eq!(r.x, 0);
-        assert_eq!(r.y, 0);
-        assert_eq!(r.width, 990);
-        assert_eq!(r.height, 792);
-    }
-
-    #[test]
-    fn test_clamp_region_odd_dimensions() {
-        let r = clamp_region(&Region { x: 0, y: 0, width: 801, height: 601 });
-        assert_eq!(r.width, 800);
-        assert_eq!(r.height, 600);
-    }
+/// Share of the clip default thumbnails are taken at: the first frame is
+/// often the desktop before the demo starts
+const THUMBNAIL_POSITION: f64 = 0.1;
 
-    #[test]
-    fn test_clamp_region_minimum_size() {
-        // Extremely negative: width would go to 0 or negative
-        let r = clamp_region(&Region { x: -500, y: -500, width: 100, height: 100 });
-        assert_eq!(r.x, 0);
-        assert_eq!(r.y, 0);
-        assert!(r.width >= 2);
-        assert!(r.height >= 2);
-    }
+/// Where to take the thumbnail of a clip lasting `duration_ms` by default
+pub fn default_thumbnail_ms(duration_ms: u64) -> u64 {
+    (duration_ms as f64 * THUMBNAIL_POSITION) as u64
 }
 
-/// Generate a thumbnail from a video file (first frame)
-pub fn generate_thumbnail(video_path: &PathBuf, thumbnail_path: &PathBuf) -> Result<()> {
-    crate::ffmpeg_command_sync()
+/// Generate a thumbnail from the frame at `at_ms` of a video file
+pub fn generate_thumbnail(video_path: &PathBuf, thumbnail_path: &PathBuf, at_ms: u64) -> Result<()> {
+    let status = crate::ffmpeg_command_sync()
         .args([
+            "-ss", &format!("{:.3}", at_ms as f64 / 1000.0),
             "-i", &video_path.to_string_lossy(),
             "-vframes", "1",
             "-vf", "scale=192:-1",
-            "-y",
-            &thumbnail_path.to_string_lossy(),
         ])
+        .args(crate::ffmpeg::paths::image_output(thumbnail_path))
         .stdout(Stdio::null())
         .stderr(Stdio::null())
         .spawn()?
         .wait()?;
+    if !status.success() || !thumbnail_path.exists() {
+        anyhow::bail!(crate::i18n::tf("thumbnail_failed", &[&at_ms]));
+    }
     Ok(())
 }
+
+/// Probe width, height and duration (seconds) of a video file
+pub async fn probe_video(path: &Path) -> Option<(u32, u32, f64)> {
+    let output = crate::ffprobe_command()
+        .args([
+            "-v", "error",
+            "-select_streams", "v:0",
+            "-show_entries", "stream=width,height:format=duration",
+            "-of", "default=noprint_wrappers=1",
+            &path.to_string_lossy(),
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .await
+        .ok()?;
+    parse_probe_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_probe_output(s: &str) -> Option<(u32, u32, f64)> {
+    let mut width = None;
+    let mut height = None;
+    let mut duration = None;
+    for line in s.lines() {
+        match line.trim().split_once('=') {
+            Some(("width", v)) => width = v.parse::<u32>().ok(),
+            Some(("height", v)) => height = v.parse::<u32>().ok(),
+            Some(("duration", v)) => duration = v.parse::<f64>().ok(),
+            _ => {}
+        }
+    }
+    Some((width?, height?, duration.unwrap_or(0.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_probe_output() {
+        let out = "width=1280\nheight=720\nduration=12.5\n";
+        assert_eq!(parse_probe_output(out), Some((1280, 720, 12.5)));
+    }
+
+    #[test]
+    fn test_parse_probe_output_missing_video() {
+        assert_eq!(parse_probe_output("duration=3.0\n"), None);
+    }
+
+    #[test]
+    fn test_default_thumbnail_skips_first_frame() {
+        assert_eq!(default_thumbnail_ms(30_000), 3000);
+        assert_eq!(default_thumbnail_ms(0), 0);
+    }
+}
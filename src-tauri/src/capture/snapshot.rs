@@ -0,0 +1,144 @@
+use crate::types::Region;
+
+/// Grab a region of the desktop with GDI and return a downscaled PNG data URL.
+/// Cheap enough to poll while FFmpeg is capturing the same area.
+pub fn grab_screen_region(region: &Region, max_width: u32) -> Result<String, String> {
+    use windows::Win32::Graphics::Gdi::*;
+
+    let width = region.width.max(1);
+    let height = region.height.max(1);
+
+    let pixels = unsafe {
+        let screen_dc = GetDC(None);
+        let mem_dc = CreateCompatibleDC(Some(screen_dc));
+        let bitmap = CreateCompatibleBitmap(screen_dc, width as i32, height as i32);
+        let previous = SelectObject(mem_dc, bitmap.into());
+
+        let copied = BitBlt(
+            mem_dc,
+            0,
+            0,
+            width as i32,
+            height as i32,
+            Some(screen_dc),
+            region.x,
+            region.y,
+            SRCCOPY,
+        )
+        .is_ok();
+
+        let pixels = if copied { read_bitmap_pixels(mem_dc, bitmap, width, height) } else { None };
+
+        SelectObject(mem_dc, previous);
+        let _ = DeleteObject(bitmap.into());
+        let _ = DeleteDC(mem_dc);
+        ReleaseDC(None, screen_dc);
+        pixels
+    };
+
+    let pixels = pixels.ok_or_else(|| crate::i18n::t("screen_capture_failed"))?;
+    bgra_to_png_data_url(pixels, width, height, max_width).ok_or_else(|| crate::i18n::t("png_encode_failed"))
+}
+
+/// Bounds of the whole virtual desktop (what gdigrab records as "desktop")
+pub fn virtual_screen_region() -> Region {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+    };
+
+    unsafe {
+        Region {
+            x: GetSystemMetrics(SM_XVIRTUALSCREEN),
+            y: GetSystemMetrics(SM_YVIRTUALSCREEN),
+            width: GetSystemMetrics(SM_CXVIRTUALSCREEN).max(1) as u32,
+            height: GetSystemMetrics(SM_CYVIRTUALSCREEN).max(1) as u32,
+        }
+    }
+}
+
+/// Read a 32-bit top-down BGRA copy of a bitmap selected into `dc`.
+pub unsafe fn read_bitmap_pixels(
+    dc: windows::Win32::Graphics::Gdi::HDC,
+    bitmap: windows::Win32::Graphics::Gdi::HBITMAP,
+    width: u32,
+    height: u32,
+) -> Option<Vec<u8>> {
+    use windows::Win32::Graphics::Gdi::*;
+
+    let mut info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width as i32,
+            biHeight: -(height as i32), // top-down rows
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut pixels = vec![0u8; (width * height * 4) as usize];
+    let lines = GetDIBits(
+        dc,
+        bitmap,
+        0,
+        height,
+        Some(pixels.as_mut_ptr() as *mut _),
+        &mut info,
+        DIB_RGB_COLORS,
+    );
+    if lines == 0 {
+        None
+    } else {
+        Some(pixels)
+    }
+}
+
+/// Convert GDI BGRA pixels to a PNG data URL no wider than `max_width`
+pub fn bgra_to_png_data_url(mut pixels: Vec<u8>, width: u32, height: u32, max_width: u32) -> Option<String> {
+    use base64::Engine;
+
+    bgra_to_rgba(&mut pixels);
+    let img = image::RgbaImage::from_raw(width, height, pixels)?;
+    let out_w = max_width.clamp(1, width);
+    let out_h = ((height as u64 * out_w as u64) / width.max(1) as u64).clamp(1, height as u64) as u32;
+    let thumb = image::imageops::thumbnail(&img, out_w, out_h);
+
+    let mut png = Vec::new();
+    image::DynamicImage::ImageRgba8(thumb)
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .ok()?;
+    let b64 = base64::engine::general_purpose::STANDARD.encode(&png);
+    Some(format!("data:image/png;base64,{}", b64))
+}
+
+/// Swap B and R channels in place and force opaque alpha (GDI leaves it at 0).
+fn bgra_to_rgba(pixels: &mut [u8]) {
+    for px in pixels.chunks_exact_mut(4) {
+        px.swap(0, 2);
+        px[3] = 255;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bgra_to_rgba() {
+        let mut px = vec![10, 20, 30, 0, 1, 2, 3, 0];
+        bgra_to_rgba(&mut px);
+        assert_eq!(px, vec![30, 20, 10, 255, 3, 2, 1, 255]);
+    }
+
+    #[test]
+    fn test_png_data_url() {
+        let url = bgra_to_png_data_url(vec![0u8; 4 * 4 * 4], 4, 4, 240).unwrap();
+        assert!(url.starts_with("data:image/png;base64,"));
+    }
+
+    #[test]
+    fn test_png_data_url_rejects_short_buffer() {
+        assert!(bgra_to_png_data_url(vec![0u8; 8], 4, 4, 240).is_none());
+    }
+}
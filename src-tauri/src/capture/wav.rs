@@ -0,0 +1,168 @@
+use crate::types::AudioFormat;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// Sample rate of `AudioFormat::Canonical` tracks
+pub const CANONICAL_RATE: u32 = 48_000;
+
+/// WAV file the capture callbacks write interleaved float frames to, in the
+/// device format or converted on the fly to 48 kHz 16-bit PCM
+pub struct WavSink {
+    writer: hound::WavWriter<BufWriter<File>>,
+    channels: usize,
+    format: AudioFormat,
+    resampler: Option<Resampler>,
+}
+
+impl WavSink {
+    pub fn create(path: &Path, channels: u16, rate: u32, format: AudioFormat) -> Result<Self, String> {
+        let spec = match format {
+            AudioFormat::Canonical => hound::WavSpec {
+                channels,
+                sample_rate: CANONICAL_RATE,
+                bits_per_sample: 16,
+                sample_format: hound::SampleFormat::Int,
+            },
+            AudioFormat::Native => hound::WavSpec {
+                channels,
+                sample_rate: rate,
+                bits_per_sample: 32,
+                sample_format: hound::SampleFormat::Float,
+            },
+        };
+        let writer = hound::WavWriter::create(path, spec).map_err(|e| format!("Failed to create WAV writer: {}", e))?;
+        let resampler = (format == AudioFormat::Canonical && rate != CANONICAL_RATE)
+            .then(|| Resampler::new(channels as usize, rate, CANONICAL_RATE));
+        Ok(WavSink { writer, channels: channels.max(1) as usize, format, resampler })
+    }
+
+    /// Write interleaved frames captured at the device rate
+    pub fn write(&mut self, data: &[f32]) {
+        match self.resampler.as_mut() {
+            Some(resampler) => {
+                let mut out = Vec::with_capacity(data.len() * 2);
+                resampler.process(data, &mut out);
+                for s in out {
+                    self.write_sample(s);
+                }
+            }
+            None => {
+                for &s in data {
+                    self.write_sample(s);
+                }
+            }
+        }
+    }
+
+    /// Write `frames` frames of silence at the device rate
+    pub fn write_silence(&mut self, frames: u64) {
+        self.write(&vec![0.0; frames as usize * self.channels]);
+    }
+
+    fn write_sample(&mut self, sample: f32) {
+        let _ = match self.format {
+            AudioFormat::Canonical => self.writer.write_sample(to_i16(sample)),
+            AudioFormat::Native => self.writer.write_sample(sample),
+        };
+    }
+
+    pub fn finalize(self) {
+        if let Err(e) = self.writer.finalize() {
+            tracing::warn!("Failed to finalize WAV: {}", e);
+        }
+    }
+}
+
+fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16
+}
+
+/// Linear resampling of interleaved frames fed in any chunks
+struct Resampler {
+    channels: usize,
+    /// Input frames per output frame
+    step: f64,
+    /// Position of the next output frame, in input frames after `last`
+    pos: f64,
+    /// Last frame of the previous chunk, None before the first
+    last: Option<Vec<f32>>,
+}
+
+impl Resampler {
+    fn new(channels: usize, in_rate: u32, out_rate: u32) -> Self {
+        Resampler {
+            channels: channels.max(1),
+            step: in_rate as f64 / out_rate as f64,
+            // The first output frame is the first input frame
+            pos: 1.0,
+            last: None,
+        }
+    }
+
+    fn process(&mut self, data: &[f32], out: &mut Vec<f32>) {
+        let channels = self.channels;
+        let frames = data.len() / channels;
+        if frames == 0 {
+            return;
+        }
+        // Frame 0 is the last one of the previous chunk, frame i + 1 is
+        // frame i of `data`
+        let last = self.last.take().unwrap_or_else(|| data[..channels].to_vec());
+        let frame = |i: usize, c: usize| if i == 0 { last[c] } else { data[(i - 1) * channels + c] };
+        while self.pos < frames as f64 {
+            let i = self.pos as usize;
+            let frac = (self.pos - i as f64) as f32;
+            for c in 0..channels {
+                out.push(frame(i, c) + (frame(i + 1, c) - frame(i, c)) * frac);
+            }
+            self.pos += self.step;
+        }
+        self.pos -= frames as f64;
+        self.last = Some(data[(frames - 1) * channels..frames * channels].to_vec());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resampler_keeps_the_duration_across_chunks() {
+        let mut resampler = Resampler::new(2, 44_100, 48_000);
+        let mut out = Vec::new();
+        // One second of stereo in uneven chunks
+        let input = vec![0.5f32; 44_100 * 2];
+        for chunk in input.chunks(441 * 2 + 6) {
+            resampler.process(chunk, &mut out);
+        }
+        let frames = out.len() / 2;
+        assert!((47_999..=48_001).contains(&frames), "{frames} frames");
+        assert!(out.iter().all(|s| (s - 0.5).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_resampler_interpolates() {
+        // Down by two: every other frame
+        let mut resampler = Resampler::new(1, 96_000, 48_000);
+        let mut out = Vec::new();
+        resampler.process(&[0.0, 0.25, 0.5, 0.75], &mut out);
+        resampler.process(&[1.0, 1.0], &mut out);
+        assert_eq!(out, vec![0.0, 0.5, 1.0]);
+
+        // Up by two: midpoints in between
+        let mut resampler = Resampler::new(1, 24_000, 48_000);
+        let mut out = Vec::new();
+        resampler.process(&[0.0, 1.0], &mut out);
+        resampler.process(&[1.0], &mut out);
+        assert_eq!(out, vec![0.0, 0.5, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_to_i16_clamps() {
+        assert_eq!(to_i16(0.0), 0);
+        assert_eq!(to_i16(1.5), i16::MAX);
+        assert_eq!(to_i16(-1.0), -i16::MAX);
+        assert_eq!(to_i16(0.5), 16384);
+    }
+}
@@ -0,0 +1,336 @@
+use crate::types::{CaptureWindow, Region};
+use anyhow::Result;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Child;
+use tokio::sync::mpsc;
+use windows::core::{factory, Interface};
+use windows::Graphics::Capture::{Direct3D11CaptureFramePool, GraphicsCaptureItem, GraphicsCaptureSession};
+use windows::Graphics::DirectX::Direct3D11::IDirect3DDevice;
+use windows::Graphics::DirectX::DirectXPixelFormat;
+use windows::Graphics::SizeInt32;
+use windows::Win32::Foundation::{E_FAIL, HMODULE, HWND, POINT};
+use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_HARDWARE;
+use windows::Win32::Graphics::Direct3D11::{
+    D3D11CreateDevice, ID3D11Device, ID3D11DeviceContext, ID3D11Texture2D, D3D11_BOX, D3D11_CPU_ACCESS_READ,
+    D3D11_CREATE_DEVICE_BGRA_SUPPORT, D3D11_MAPPED_SUBRESOURCE, D3D11_MAP_READ, D3D11_SDK_VERSION,
+    D3D11_TEXTURE2D_DESC, D3D11_USAGE_STAGING,
+};
+use windows::Win32::Graphics::Dxgi::Common::{DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_SAMPLE_DESC};
+use windows::Win32::Graphics::Dxgi::IDXGIDevice;
+use windows::Win32::Graphics::Gdi::{MonitorFromPoint, MONITOR_DEFAULTTONEAREST};
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_MULTITHREADED};
+use windows::Win32::System::WinRT::Direct3D11::{CreateDirect3D11DeviceFromDXGIDevice, IDirect3DDxgiInterfaceAccess};
+use windows::Win32::System::WinRT::Graphics::Capture::IGraphicsCaptureItemInterop;
+
+/// Frames waiting for FFmpeg: enough to ride out a hiccup, few enough that
+/// a stalled encoder holds the capture back instead of filling memory
+const QUEUED_FRAMES: usize = 4;
+
+/// Stop flags of the captures feeding an FFmpeg process, by its id
+static PUMPS: Mutex<Vec<(u32, Arc<AtomicBool>)>> = Mutex::new(Vec::new());
+
+/// Whether this Windows has the capture API (10 1903 and later)
+pub fn is_supported() -> bool {
+    GraphicsCaptureSession::IsSupported().unwrap_or(false)
+}
+
+/// What a capture session records
+enum Source {
+    /// A monitor, by its bounds in desktop pixels
+    Monitor(Region),
+    /// A window, wherever it is dragged
+    Window(isize),
+}
+
+/// Frames grabbed by Windows Graphics Capture, raw BGRA rows, waiting for
+/// the FFmpeg process that encodes them
+pub struct Frames {
+    receiver: mpsc::Receiver<Arc<Vec<u8>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Frames {
+    /// Start grabbing `area` of the `monitor` (both in desktop pixels) at
+    /// `framerate`. Fails when the monitor can't be captured, before any
+    /// FFmpeg process depends on it.
+    pub fn open(monitor: &Region, area: &Region, framerate: u32) -> Result<Self> {
+        // The capture item only knows the monitor's own coordinates
+        let area = Region { x: area.x - monitor.x, y: area.y - monitor.y, width: area.width, height: area.height };
+        Self::start(Source::Monitor(monitor.clone()), area, framerate)
+    }
+
+    /// Start grabbing `window` at `framerate`, following it when it moves.
+    /// Resized, it is cropped or padded with black to its starting size.
+    pub fn open_window(window: &CaptureWindow, framerate: u32) -> Result<Self> {
+        let area = Region { x: 0, y: 0, width: window.width, height: window.height };
+        Self::start(Source::Window(window.hwnd), area, framerate)
+    }
+
+    fn start(source: Source, area: Region, framerate: u32) -> Result<Self> {
+        let (sender, receiver) = mpsc::channel(QUEUED_FRAMES);
+        let stop = Arc::new(AtomicBool::new(false));
+        let (ready_tx, ready_rx) = std::sync::mpsc::sync_channel(1);
+        let flag = stop.clone();
+
+        std::thread::spawn(move || {
+            let _ = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+            match Grabber::open(&source, area) {
+                Ok(grabber) => {
+                    let _ = ready_tx.send(Ok(()));
+                    pump(&grabber, &sender, &flag, framerate);
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                }
+            }
+            if let Ok(mut pumps) = PUMPS.lock() {
+                pumps.retain(|(_, f)| !Arc::ptr_eq(f, &flag));
+            }
+            unsafe { CoUninitialize() };
+        });
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => Ok(Self { receiver, stop }),
+            Ok(Err(e)) => anyhow::bail!("Windows Graphics Capture failed: {}", e),
+            Err(_) => anyhow::bail!("Windows Graphics Capture thread stopped"),
+        }
+    }
+
+    /// Write the frames into the stdin of `ffmpeg`. Closing that pipe, once
+    /// `stop` is called with its id, is what ends its file.
+    pub fn pipe_into(self, ffmpeg: &mut Child) {
+        // Without a pipe the frames are dropped, which ends the capture
+        let Some(mut stdin) = ffmpeg.stdin.take() else {
+            return;
+        };
+        if let (Some(pid), Ok(mut pumps)) = (ffmpeg.id(), PUMPS.lock()) {
+            pumps.push((pid, self.stop.clone()));
+        }
+        let mut receiver = self.receiver;
+        tauri::async_runtime::spawn(async move {
+            while let Some(frame) = receiver.recv().await {
+                // FFmpeg has exited
+                if stdin.write_all(&frame).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Stop feeding the FFmpeg process `pid`, if a capture here does, so it
+/// finishes its file
+pub fn stop(pid: u32) {
+    if let Ok(mut pumps) = PUMPS.lock() {
+        if let Some(i) = pumps.iter().position(|(p, _)| *p == pid) {
+            pumps.swap_remove(i).1.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Frames due `elapsed` into a capture at `framerate`, the first at 0
+fn due_frames(elapsed: Duration, framerate: u32) -> u64 {
+    (elapsed.as_secs_f64() * framerate as f64) as u64 + 1
+}
+
+/// Send the latest frame `framerate` times a second until `stop`. WGC only
+/// delivers a frame when the screen changes, so the last one is repeated in
+/// between, and ticks missed while FFmpeg was busy are made up for: the
+/// video lasts as long as the recording did.
+fn pump(grabber: &Grabber, frames: &mpsc::Sender<Arc<Vec<u8>>>, stop: &AtomicBool, framerate: u32) {
+    let framerate = framerate.max(1);
+    let tick = Duration::from_secs_f64(1.0 / framerate as f64);
+    let started = Instant::now();
+    let mut latest: Option<Arc<Vec<u8>>> = None;
+    let mut sent = 0;
+
+    while !stop.load(Ordering::Relaxed) {
+        let tick_start = Instant::now();
+        match grabber.next_frame() {
+            Ok(Some(frame)) => latest = Some(Arc::new(frame)),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Windows Graphics Capture frame lost: {}", e),
+        }
+        // Nothing to show before the first frame: it stands in for those ticks
+        if let Some(frame) = &latest {
+            let due = due_frames(started.elapsed(), framerate);
+            while sent < due {
+                // FFmpeg has exited
+                if frames.blocking_send(frame.clone()).is_err() {
+                    return;
+                }
+                sent += 1;
+            }
+        }
+        std::thread::sleep(tick.saturating_sub(tick_start.elapsed()));
+    }
+}
+
+/// Part of a `texture` sized frame copied for `area`, as (left, top, width,
+/// height): the frame may be smaller than the area once the monitor changed
+/// resolution or the window was resized
+fn copy_box(area: &Region, texture: (u32, u32)) -> (u32, u32, u32, u32) {
+    let left = (area.x.max(0) as u32).min(texture.0);
+    let top = (area.y.max(0) as u32).min(texture.1);
+    let right = (left + area.width).min(texture.0);
+    let bottom = (top + area.height).min(texture.1);
+    (left, top, right - left, bottom - top)
+}
+
+/// Capture session of one monitor or window, copying `area` of each frame
+/// through a CPU-readable texture
+struct Grabber {
+    direct3d: IDirect3DDevice,
+    context: ID3D11DeviceContext,
+    staging: ID3D11Texture2D,
+    pool: Direct3D11CaptureFramePool,
+    session: GraphicsCaptureSession,
+    /// Size of the pool's frames, a window's changing as it is resized
+    size: Cell<SizeInt32>,
+    /// In the monitor's or window's coordinates
+    area: Region,
+}
+
+impl Grabber {
+    fn open(source: &Source, area: Region) -> windows::core::Result<Self> {
+        unsafe {
+            let mut device: Option<ID3D11Device> = None;
+            let mut context: Option<ID3D11DeviceContext> = None;
+            D3D11CreateDevice(
+                None,
+                D3D_DRIVER_TYPE_HARDWARE,
+                HMODULE::default(),
+                D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                None,
+                D3D11_SDK_VERSION,
+                Some(&mut device),
+                None,
+                Some(&mut context),
+            )?;
+            let (Some(device), Some(context)) = (device, context) else {
+                return Err(E_FAIL.into());
+            };
+            let direct3d: IDirect3DDevice = CreateDirect3D11DeviceFromDXGIDevice(&device.cast::<IDXGIDevice>()?)?.cast()?;
+
+            let interop = factory::<GraphicsCaptureItem, IGraphicsCaptureItemInterop>()?;
+            let item: GraphicsCaptureItem = match source {
+                Source::Monitor(monitor) => {
+                    let center = POINT { x: monitor.x + monitor.width as i32 / 2, y: monitor.y + monitor.height as i32 / 2 };
+                    interop.CreateForMonitor(MonitorFromPoint(center, MONITOR_DEFAULTTONEAREST))?
+                }
+                Source::Window(hwnd) => interop.CreateForWindow(HWND(*hwnd as *mut _))?,
+            };
+            let size = item.Size()?;
+            let pool = Direct3D11CaptureFramePool::CreateFreeThreaded(
+                &direct3d,
+                DirectXPixelFormat::B8G8R8A8UIntNormalized,
+                2,
+                size,
+            )?;
+            let session = pool.CreateCaptureSession(&item)?;
+            // Windows 11 only: no yellow frame around the recorded monitor or window
+            let _ = session.SetIsBorderRequired(false);
+
+            let desc = D3D11_TEXTURE2D_DESC {
+                Width: area.width,
+                Height: area.height,
+                MipLevels: 1,
+                ArraySize: 1,
+                Format: DXGI_FORMAT_B8G8R8A8_UNORM,
+                SampleDesc: DXGI_SAMPLE_DESC { Count: 1, Quality: 0 },
+                Usage: D3D11_USAGE_STAGING,
+                BindFlags: 0,
+                CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32,
+                MiscFlags: 0,
+            };
+            let mut staging: Option<ID3D11Texture2D> = None;
+            device.CreateTexture2D(&desc, None, Some(&mut staging))?;
+            let staging = staging.ok_or_else(|| windows::core::Error::from(E_FAIL))?;
+
+            session.StartCapture()?;
+            Ok(Self { direct3d, context, staging, pool, session, size: Cell::new(size), area })
+        }
+    }
+
+    /// `area` of the newest frame, None when the screen hasn't changed.
+    /// What the frame doesn't cover stays black.
+    fn next_frame(&self) -> windows::core::Result<Option<Vec<u8>>> {
+        // Only the newest frame matters, the older ones go back to the pool
+        let mut newest = None;
+        while let Ok(frame) = self.pool.TryGetNextFrame() {
+            newest = Some(frame);
+        }
+        let Some(frame) = newest else {
+            return Ok(None);
+        };
+
+        // A resized window's frames only fill part of the pool's buffers
+        // until it is recreated at the new size
+        let content = frame.ContentSize()?;
+        if content != self.size.get() && content.Width > 0 && content.Height > 0 {
+            self.pool.Recreate(&self.direct3d, DirectXPixelFormat::B8G8R8A8UIntNormalized, 2, content)?;
+            self.size.set(content);
+        }
+
+        unsafe {
+            let texture: ID3D11Texture2D = frame.Surface()?.cast::<IDirect3DDxgiInterfaceAccess>()?.GetInterface()?;
+            let mut desc = D3D11_TEXTURE2D_DESC::default();
+            texture.GetDesc(&mut desc);
+            let visible = (desc.Width.min(content.Width.max(0) as u32), desc.Height.min(content.Height.max(0) as u32));
+            let (left, top, width, height) = copy_box(&self.area, visible);
+            if width == 0 || height == 0 {
+                return Ok(None);
+            }
+            let bounds = D3D11_BOX { left, top, front: 0, right: left + width, bottom: top + height, back: 1 };
+            self.context.CopySubresourceRegion(&self.staging, 0, 0, 0, 0, &texture, 0, Some(&bounds));
+
+            let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+            self.context.Map(&self.staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))?;
+            let row = self.area.width as usize * 4;
+            let copied = width as usize * 4;
+            let mut pixels = vec![0u8; row * self.area.height as usize];
+            for (y, line) in pixels.chunks_exact_mut(row).take(height as usize).enumerate() {
+                let src = (mapped.pData as *const u8).add(y * mapped.RowPitch as usize);
+                line[..copied].copy_from_slice(std::slice::from_raw_parts(src, copied));
+            }
+            self.context.Unmap(&self.staging, 0);
+            Ok(Some(pixels))
+        }
+    }
+}
+
+impl Drop for Grabber {
+    fn drop(&mut self) {
+        let _ = self.session.Close();
+        let _ = self.pool.Close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_frames_follow_the_clock() {
+        assert_eq!(due_frames(Duration::ZERO, 30), 1);
+        assert_eq!(due_frames(Duration::from_millis(1000), 30), 31);
+        // A second behind at 60 fps: the backlog is due at once
+        assert_eq!(due_frames(Duration::from_millis(2010), 60), 121);
+    }
+
+    #[test]
+    fn test_copy_box_crops_and_pads() {
+        let window = Region { x: 0, y: 0, width: 800, height: 600 };
+        assert_eq!(copy_box(&window, (800, 600)), (0, 0, 800, 600));
+        // Grown: cropped to the starting size
+        assert_eq!(copy_box(&window, (1200, 900)), (0, 0, 800, 600));
+        // Shrunk: the rest stays black
+        assert_eq!(copy_box(&window, (640, 700)), (0, 0, 640, 600));
+        // Area of a monitor that lost resolution
+        assert_eq!(copy_box(&Region { x: 1000, y: 500, width: 400, height: 300 }, (1280, 720)), (1000, 500, 280, 220));
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+
+/// One mouse wheel step, a full notch is 120 (high-resolution wheels send less)
+#[derive(Debug, Clone, Copy)]
+pub struct WheelTick {
+    pub at: Instant,
+    pub horizontal: bool,
+    /// Positive when scrolling up or to the right
+    pub delta: i32,
+}
+
+// The hook procedure has no user data pointer, ticks go through here
+static TICKS: Mutex<Vec<WheelTick>> = Mutex::new(Vec::new());
+
+pub struct WheelHook {
+    stop_flag: Arc<AtomicBool>,
+    join_handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl WheelHook {
+    /// Install a low-level mouse hook. Its thread pumps messages, which is
+    /// how Windows delivers the hook calls.
+    pub fn start() -> Self {
+        if let Ok(mut ticks) = TICKS.lock() {
+            ticks.clear();
+        }
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let stop = stop_flag.clone();
+
+        let join_handle = std::thread::spawn(move || unsafe {
+            use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+            use windows::Win32::UI::WindowsAndMessaging::*;
+
+            let hook = match GetModuleHandleW(None)
+                .and_then(|module| SetWindowsHookExW(WH_MOUSE_LL, Some(hook_proc), Some(module.into()), 0))
+            {
+                Ok(hook) => hook,
+                Err(e) => {
+                    tracing::warn!("Mouse wheel hook unavailable: {}", e);
+                    return;
+                }
+            };
+            let mut msg = MSG::default();
+            while !stop.load(Ordering::Relaxed) {
+                while PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+                    let _ = TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            let _ = UnhookWindowsHookEx(hook);
+        });
+
+        WheelHook {
+            stop_flag,
+            join_handle: Some(join_handle),
+        }
+    }
+
+    /// Ticks seen since the last call
+    pub fn drain(&self) -> Vec<WheelTick> {
+        TICKS.lock().map(|mut ticks| std::mem::take(&mut *ticks)).unwrap_or_default()
+    }
+}
+
+impl Drop for WheelHook {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        if let Some(h) = self.join_handle.take() {
+            let _ = h.join();
+        }
+    }
+}
+
+unsafe extern "system" fn hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    use windows::Win32::UI::WindowsAndMessaging::{CallNextHookEx, MSLLHOOKSTRUCT, WM_MOUSEHWHEEL, WM_MOUSEWHEEL};
+
+    let msg = wparam.0 as u32;
+    if code >= 0 && (msg == WM_MOUSEWHEEL || msg == WM_MOUSEHWHEEL) {
+        let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        // The high word holds the signed distance
+        let delta = (info.mouseData >> 16) as u16 as i16 as i32;
+        if let Ok(mut ticks) = TICKS.lock() {
+            ticks.push(WheelTick { at: Instant::now(), horizontal: msg == WM_MOUSEHWHEEL, delta });
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
@@ -0,0 +1,129 @@
+use std::path::Path;
+
+/// Standard clipboard format for a list of files (Explorer, Slack, Discord, ...)
+const CF_HDROP: u32 = 15;
+const DROPEFFECT_COPY: u32 = 1;
+/// Size of the DROPFILES header: pFiles, pt.x, pt.y, fNC, fWide
+const DROPFILES_SIZE: usize = 20;
+
+/// Put `path` on the clipboard as a real file (CF_HDROP) so it can be pasted
+/// into chat apps or Explorer. PNG and GIF files are also offered as image
+/// data, for targets that only accept pictures.
+pub fn copy_file(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Err(crate::i18n::tf("file_not_found", &[&path.display()]));
+    }
+    let path = path.canonicalize().map_err(|e| e.to_string())?;
+    // canonicalize() yields a \\?\ verbatim path, which most paste targets reject
+    let display_path = path.to_string_lossy().trim_start_matches(r"\\?\").to_string();
+
+    let image = match image_format_name(&path) {
+        Some(name) => Some((name, std::fs::read(&path).map_err(|e| e.to_string())?)),
+        None => None,
+    };
+
+    unsafe { set_clipboard(&build_hdrop_payload(&[display_path]), image) }
+}
+
+/// Registered clipboard format for images that apps accept as raw file bytes
+fn image_format_name(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_string_lossy().to_ascii_lowercase().as_str() {
+        "png" => Some("PNG"),
+        "gif" => Some("GIF"),
+        _ => None,
+    }
+}
+
+/// Build a DROPFILES block followed by a double-null-terminated UTF-16 path list
+fn build_hdrop_payload(paths: &[String]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&(DROPFILES_SIZE as u32).to_le_bytes()); // pFiles
+    data.extend_from_slice(&0i32.to_le_bytes()); // pt.x
+    data.extend_from_slice(&0i32.to_le_bytes()); // pt.y
+    data.extend_from_slice(&0i32.to_le_bytes()); // fNC
+    data.extend_from_slice(&1i32.to_le_bytes()); // fWide
+    for p in paths {
+        for unit in p.encode_utf16().chain(std::iter::once(0)) {
+            data.extend_from_slice(&unit.to_le_bytes());
+        }
+    }
+    data.extend_from_slice(&0u16.to_le_bytes());
+    data
+}
+
+unsafe fn set_clipboard(hdrop: &[u8], image: Option<(&str, Vec<u8>)>) -> Result<(), String> {
+    use windows::core::HSTRING;
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, RegisterClipboardFormatW,
+    };
+
+    OpenClipboard(None).map_err(|e| crate::i18n::tf("clipboard_open_failed", &[&e]))?;
+    let result = (|| {
+        EmptyClipboard().map_err(|e| e.to_string())?;
+        set_clipboard_bytes(CF_HDROP, hdrop)?;
+
+        // Tell Explorer a paste should copy the file, not move it
+        let drop_effect = RegisterClipboardFormatW(&HSTRING::from("Preferred DropEffect"));
+        set_clipboard_bytes(drop_effect, &DROPEFFECT_COPY.to_le_bytes())?;
+
+        if let Some((name, bytes)) = image {
+            let format = RegisterClipboardFormatW(&HSTRING::from(name));
+            set_clipboard_bytes(format, &bytes)?;
+        }
+        Ok(())
+    })();
+    let _ = CloseClipboard();
+    result
+}
+
+/// Copy `bytes` into a movable global block and hand it to the clipboard
+unsafe fn set_clipboard_bytes(format: u32, bytes: &[u8]) -> Result<(), String> {
+    use windows::Win32::Foundation::{GlobalFree, HANDLE, HGLOBAL};
+    use windows::Win32::System::DataExchange::SetClipboardData;
+    use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+
+    let hmem: HGLOBAL = GlobalAlloc(GMEM_MOVEABLE, bytes.len()).map_err(|e| e.to_string())?;
+    let ptr = GlobalLock(hmem) as *mut u8;
+    if ptr.is_null() {
+        let _ = GlobalFree(Some(hmem));
+        return Err(crate::i18n::t("clipboard_lock_failed"));
+    }
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+    let _ = GlobalUnlock(hmem);
+
+    // On success the clipboard owns the memory
+    if let Err(e) = SetClipboardData(format, Some(HANDLE(hmem.0))) {
+        let _ = GlobalFree(Some(hmem));
+        return Err(e.to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hdrop_header() {
+        let data = build_hdrop_payload(&["C:\\a.mp4".to_string()]);
+        assert_eq!(&data[0..4], &(DROPFILES_SIZE as u32).to_le_bytes());
+        assert_eq!(&data[16..20], &1i32.to_le_bytes());
+    }
+
+    #[test]
+    fn test_hdrop_double_null_terminated() {
+        let data = build_hdrop_payload(&["a".to_string(), "bc".to_string()]);
+        let units: Vec<u16> = data[DROPFILES_SIZE..]
+            .chunks(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        assert_eq!(units, vec![b'a' as u16, 0, b'b' as u16, b'c' as u16, 0, 0]);
+    }
+
+    #[test]
+    fn test_image_format_name() {
+        assert_eq!(image_format_name(Path::new("x.GIF")), Some("GIF"));
+        assert_eq!(image_format_name(Path::new("x.png")), Some("PNG"));
+        assert_eq!(image_format_name(Path::new("x.mp4")), None);
+    }
+}
@@ -1,8 +1,10 @@
+use crate::events::{self, AppEvent};
 use crate::recording::manager;
 use crate::state::AppState;
 use crate::types::{
-    Annotation, AudioDevice, AudioSource, Clip, ExportFormat, ExportQuality,
-    RecordingState, Region, Subtitle, TransitionType, WindowInfo,
+    Annotation, AudioDevice, AudioSource, Clip, ClipMedia, ExportFormat, ExportQuality, Keyframe,
+    MonitorArea, PostExportSettings, RecordingQuality, UploadTarget, Webhook, RecordingState, Region, SegmentRollover, Subtitle, TimelineMarker, TransitionType,
+    WindowInfo, ZOrder,
 };
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
@@ -21,6 +23,71 @@ pub fn set_capture_region(state: State<'_, Mutex<AppState>>, region: Region) ->
     Ok(())
 }
 
+/// Window the capture region was picked from, followed when it is dragged
+/// or resized. None for a region drawn by hand.
+#[tauri::command]
+pub fn set_capture_window(state: State<'_, Mutex<AppState>>, hwnd: Option<isize>) -> Result<(), String> {
+    let window = match hwnd {
+        Some(hwnd) => Some(crate::region::selector::capture_window(hwnd).ok_or_else(|| crate::i18n::t("window_not_capturable"))?),
+        None => None,
+    };
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.capture_window = window;
+    Ok(())
+}
+
+/// Second region recorded with the next clips, None to record only the main one
+#[tauri::command]
+pub fn set_secondary_region(state: State<'_, Mutex<AppState>>, region: Option<Region>) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.secondary_region = region;
+    Ok(())
+}
+
+/// Monitors recorded at once, each to its own clip. Empty to go back to the
+/// capture region.
+#[tauri::command]
+pub fn set_capture_monitors(state: State<'_, Mutex<AppState>>, monitors: Vec<Region>) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.capture_monitors = monitors;
+    Ok(())
+}
+
+/// Record full screen and export a window of this size following the cursor,
+/// None to record the capture region as is
+#[tauri::command]
+pub fn set_follow_cursor(
+    state: State<'_, Mutex<AppState>>,
+    window: Option<crate::types::FollowWindow>,
+) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.follow_cursor = window;
+    Ok(())
+}
+
+/// Record full screen and export the foreground window, reframing when the
+/// focus moves to another window
+#[tauri::command]
+pub fn set_follow_focus(state: State<'_, Mutex<AppState>>, enabled: bool) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.follow_focus = enabled;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_clip_dual_layout(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+    layout: crate::types::DualLayout,
+) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let clip = state.clips.iter_mut().find(|c| c.id == clip_id)
+        .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+    let secondary = clip.media.secondary.as_mut().ok_or_else(|| crate::i18n::t("no_secondary_region"))?;
+    secondary.layout = layout;
+    Ok(())
+}
+
 #[tauri::command]
 pub fn toggle_audio(state: State<'_, Mutex<AppState>>) -> Result<bool, String> {
     let mut state = state.lock().map_err(|e| e.to_string())?;
@@ -64,6 +131,39 @@ pub fn get_selected_mic(state: State<'_, Mutex<AppState>>) -> Result<Option<Stri
     Ok(state.selected_mic.clone())
 }
 
+#[tauri::command]
+pub fn set_selected_output(state: State<'_, Mutex<AppState>>, device_name: Option<String>) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.selected_output = device_name;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_selected_output(state: State<'_, Mutex<AppState>>) -> Result<Option<String>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.selected_output.clone())
+}
+
+#[tauri::command]
+pub fn toggle_mic_monitoring(state: State<'_, Mutex<AppState>>) -> Result<bool, String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.mic_monitoring = !state.mic_monitoring;
+    Ok(state.mic_monitoring)
+}
+
+#[tauri::command]
+pub fn get_mic_monitoring(state: State<'_, Mutex<AppState>>) -> Result<bool, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.mic_monitoring)
+}
+
+#[tauri::command]
+pub fn set_audio_format(state: State<'_, Mutex<AppState>>, format: crate::types::AudioFormat) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.audio_format = format;
+    Ok(())
+}
+
 #[tauri::command]
 pub fn set_audio_volumes(
     state: State<'_, Mutex<AppState>>,
@@ -101,7 +201,7 @@ pub fn reorder_clips(
             .iter()
             .find(|c| &c.id == id)
             .cloned()
-            .ok_or_else(|| format!("Clip not found: {}", id))?;
+            .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&id]))?;
         reordered.push(clip);
     }
     state.clips = reordered;
@@ -112,14 +212,51 @@ pub fn reorder_clips(
 pub fn delete_clip(state: State<'_, Mutex<AppState>>, clip_id: String) -> Result<(), String> {
     let mut state = state.lock().map_err(|e| e.to_string())?;
 
-    // Delete clip files from disk
+    // Delete clip files from disk, unless a clip split off it by a
+    // freeze-frame still plays them
     if let Some(clip) = state.clips.iter().find(|c| c.id == clip_id) {
-        let _ = std::fs::remove_file(&clip.path);
-        if let Some(ref thumb) = clip.thumbnail_path {
-            let _ = std::fs::remove_file(thumb);
+        let others: Vec<&Clip> = state.clips.iter().filter(|c| c.id != clip_id).collect();
+        let remove = |path: &std::path::Path| {
+            let shared = others.iter().any(|c| {
+                c.media.path == path
+                    || c.media.thumbnail_path.as_deref() == Some(path)
+                    || c.media.secondary.as_ref().is_some_and(|s| s.path == path)
+                    || c.media.audio_paths.iter().any(|a| std::path::Path::new(a) == path)
+            });
+            if !shared {
+                let _ = std::fs::remove_file(path);
+            }
+        };
+        let remove_media = |media: &ClipMedia| {
+            remove(&media.path);
+            if let Some(ref thumb) = media.thumbnail_path {
+                remove(thumb);
+            }
+            if let Some(ref secondary) = media.secondary {
+                remove(&secondary.path);
+            }
+            for audio in &media.audio_paths {
+                remove(std::path::Path::new(audio));
+            }
+        };
+        remove_media(&clip.media);
+        // The other takes go with it
+        for take in clip.takes.iter().filter(|t| t.media.path != clip.media.path) {
+            remove_media(&take.media);
         }
     }
 
+    let take_ids: Vec<String> = state.clips.iter()
+        .filter(|c| c.id == clip_id)
+        .flat_map(|c| c.takes.iter().map(|t| t.id.clone()))
+        .collect();
+    for id in take_ids {
+        state.clip_keystrokes.remove(&id);
+        state.clip_cursor_positions.remove(&id);
+        state.clip_gamepad.remove(&id);
+        state.clip_markers.remove(&id);
+    }
+
     state.clips.retain(|c| c.id != clip_id);
     let max_transitions = state.clips.len().saturating_sub(1);
     state.transitions.truncate(max_transitions);
@@ -128,6 +265,8 @@ pub fn delete_clip(state: State<'_, Mutex<AppState>>, clip_id: String) -> Result
     state.annotations.remove(&clip_id);
     state.clip_keystrokes.remove(&clip_id);
     state.clip_cursor_positions.remove(&clip_id);
+    state.clip_gamepad.remove(&clip_id);
+    state.clip_markers.remove(&clip_id);
 
     Ok(())
 }
@@ -141,7 +280,7 @@ pub fn set_transition(
 ) -> Result<(), String> {
     let mut state = state.lock().map_err(|e| e.to_string())?;
     if index >= state.transitions.len() {
-        return Err(format!("Transition index {} out of bounds", index));
+        return Err(crate::i18n::tf("transition_out_of_range", &[&index]));
     }
     state.transitions[index].transition_type = transition_type;
     if let Some(dur) = duration_s {
@@ -171,312 +310,2011 @@ pub fn set_clip_trim(
 ) -> Result<(), String> {
     let mut state = state.lock().map_err(|e| e.to_string())?;
     let clip = state.clips.iter_mut().find(|c| c.id == clip_id)
-        .ok_or_else(|| format!("Clip not found: {}", clip_id))?;
-    clip.trim_start_ms = trim_start_ms;
-    clip.trim_end_ms = trim_end_ms;
+        .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+    clip.media.trim_start_ms = trim_start_ms;
+    clip.media.trim_end_ms = trim_end_ms;
+    // The other monitors recorded with it keep showing the same moments
+    crate::recording::group::link_trim(&mut state.clips, &clip_id);
     Ok(())
 }
 
+/// Shift `clip_id`'s audio by `ms` against its video at export, later when
+/// positive, to fix lip-sync or mic latency
 #[tauri::command]
-pub fn start_recording(state: State<'_, Mutex<AppState>>, app: AppHandle) -> Result<(), String> {
-    manager::start(&state, &app)
+pub fn set_audio_offset(state: State<'_, Mutex<AppState>>, clip_id: String, ms: i64) -> Result<(), String> {
+    if ms.abs() > 5000 {
+        return Err(crate::i18n::t("audio_offset_too_large"));
+    }
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let clip = state.clips.iter_mut().find(|c| c.id == clip_id)
+        .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+    clip.media.audio_offset_ms = ms;
+    Ok(())
 }
 
-#[tauri::command]
-pub async fn stop_recording(state: State<'_, Mutex<AppState>>) -> Result<Clip, String> {
-    manager::stop(&state).await
+async fn auto_trim_bounds(state: &Mutex<AppState>, clip_id: &str) -> Result<(u64, u64), String> {
+    let (clip, keystrokes, cursor, gamepad) = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        let clip = s.clips.iter().find(|c| c.id == clip_id).cloned()
+            .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+        (
+            clip,
+            s.clip_keystrokes.get(clip_id).cloned().unwrap_or_default(),
+            s.clip_cursor_positions.get(clip_id).cloned().unwrap_or_default(),
+            s.clip_gamepad.get(clip_id).cloned().unwrap_or_default(),
+        )
+    };
+    crate::export::autotrim::detect(&clip, &keystrokes, &cursor, &gamepad)
+        .await
+        .map_err(|e| e.to_string())
 }
 
+/// Trim suggested for a clip from its sound and input activity at the start
+/// and end, without applying it
 #[tauri::command]
-pub async fn pause_recording(state: State<'_, Mutex<AppState>>) -> Result<(), String> {
-    manager::pause(&state).await
+pub async fn detect_auto_trim(state: State<'_, Mutex<AppState>>, clip_id: String) -> Result<(u64, u64), String> {
+    auto_trim_bounds(&state, &clip_id).await
 }
 
+/// Where talking happens on a clip (source time), to jump between sentences
 #[tauri::command]
-pub fn resume_recording(state: State<'_, Mutex<AppState>>) -> Result<(), String> {
-    manager::resume(&state)
+pub async fn get_speech_segments(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+) -> Result<Vec<crate::types::SpeechSegment>, String> {
+    let (clip, key) = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        let clip = s.clips.iter().find(|c| c.id == clip_id).cloned()
+            .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+        let key = crate::export::speech::SpeechKey::of(&clip);
+        if let Some((_, segments)) = s.speech_segments.get(&clip_id).filter(|(cached, _)| *cached == key) {
+            return Ok(segments.clone());
+        }
+        (clip, key)
+    };
+    let segments = crate::export::speech::detect(&clip).await.map_err(|e| e.to_string())?;
+    let mut s = state.lock().map_err(|e| e.to_string())?;
+    s.speech_segments.insert(clip_id, (key, segments.clone()));
+    Ok(segments)
 }
 
+/// Loudness, true peak and clipping of each audio track of `clip_id`, to
+/// tell whether it needs normalizing or a retake before export
 #[tauri::command]
-pub fn cancel_recording(state: State<'_, Mutex<AppState>>) -> Result<(), String> {
-    manager::cancel(&state)
+pub async fn analyze_audio(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+) -> Result<Vec<crate::types::AudioAnalysis>, String> {
+    let clip = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        s.clips.iter().find(|c| c.id == clip_id).cloned()
+            .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?
+    };
+    crate::export::loudness::analyze(&clip).await.map_err(|e| e.to_string())
 }
 
+/// Auto-trim every clip of the timeline. Clips without any detectable
+/// activity are left as they are.
 #[tauri::command]
-pub fn get_recording_duration_ms(state: State<'_, Mutex<AppState>>) -> Result<u64, String> {
-    let state = state.lock().map_err(|e| e.to_string())?;
-    let current = match state.recording_start {
-        Some(start) => start.elapsed().as_millis() as u64,
-        None => 0,
+pub async fn auto_trim_clips(state: State<'_, Mutex<AppState>>) -> Result<Vec<Clip>, String> {
+    let clip_ids: Vec<String> = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        s.clips.iter().map(|c| c.id.clone()).collect()
     };
-    Ok(state.pause_accumulated_ms + current)
+    for clip_id in clip_ids {
+        match auto_trim_bounds(&state, &clip_id).await {
+            Ok((trim_start_ms, trim_end_ms)) => {
+                let mut s = state.lock().map_err(|e| e.to_string())?;
+                if let Some(clip) = s.clips.iter_mut().find(|c| c.id == clip_id) {
+                    clip.media.trim_start_ms = trim_start_ms;
+                    clip.media.trim_end_ms = trim_end_ms;
+                }
+            }
+            Err(e) => tracing::info!("Clip {} left untrimmed: {}", clip_id, e),
+        }
+    }
+    let s = state.lock().map_err(|e| e.to_string())?;
+    Ok(s.clips.clone())
 }
 
 #[tauri::command]
-pub fn get_thumbnail_base64(
+pub fn set_clip_zoom_keyframes(
     state: State<'_, Mutex<AppState>>,
     clip_id: String,
-) -> Result<Option<String>, String> {
-    let state = state.lock().map_err(|e| e.to_string())?;
-    let clip = state.clips.iter().find(|c| c.id == clip_id);
-    if let Some(clip) = clip {
-        if let Some(ref thumb_path) = clip.thumbnail_path {
-            if thumb_path.exists() {
-                let bytes = std::fs::read(thumb_path).map_err(|e| e.to_string())?;
-                use base64::Engine;
-                let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
-                return Ok(Some(format!("data:image/png;base64,{}", b64)));
-            }
-        }
-    }
-    Ok(None)
+    keyframes: Vec<Keyframe>,
+) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let clip = state.clips.iter_mut().find(|c| c.id == clip_id)
+        .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+    clip.zoom_keyframes = keyframes;
+    Ok(())
 }
 
 #[tauri::command]
-pub fn get_transitions(
-    state: State<'_, Mutex<AppState>>,
-) -> Result<Vec<crate::types::Transition>, String> {
-    let state = state.lock().map_err(|e| e.to_string())?;
-    Ok(state.transitions.clone())
+pub async fn start_recording(state: State<'_, Mutex<AppState>>, app: AppHandle) -> Result<bool, String> {
+    manager::start_with_countdown(&state, &app).await
 }
 
+/// Record another take of `clip_id`: once stopped it plays in the clip's
+/// place, the earlier takes staying selectable
 #[tauri::command]
-pub async fn open_region_selector(app: AppHandle) -> Result<(), String> {
-    // Close existing overlay if any
-    if let Some(w) = app.get_webview_window("overlay") {
-        let _ = w.close();
+pub async fn record_retake(state: State<'_, Mutex<AppState>>, app: AppHandle, clip_id: String) -> Result<bool, String> {
+    {
+        let mut s = state.lock().map_err(|e| e.to_string())?;
+        if s.recording_state != RecordingState::Idle {
+            return Err(crate::i18n::t("recording_in_progress"));
+        }
+        if !s.clips.iter().any(|c| c.id == clip_id) {
+            return Err(crate::i18n::tf("clip_not_found", &[&clip_id]));
+        }
+        if s.capture_monitors.len() > 1 {
+            return Err(crate::i18n::t("retake_single_monitor"));
+        }
+        s.retake_of = Some(clip_id);
+    }
+    let started = manager::start_with_countdown(&state, &app).await;
+    if !matches!(started, Ok(true)) {
+        state.lock().map_err(|e| e.to_string())?.retake_of = None;
     }
+    started
+}
 
-    // Calculate bounding box across all monitors
-    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
-    let mut min_x: i32 = 0;
-    let mut min_y: i32 = 0;
-    let mut max_x: i32 = 1920;
-    let mut max_y: i32 = 1080;
+/// Play take `take` (0 = the first recording) of `clip_id`
+#[tauri::command]
+pub fn select_take(state: State<'_, Mutex<AppState>>, clip_id: String, take: usize) -> Result<Clip, String> {
+    let mut s = state.lock().map_err(|e| e.to_string())?;
+    crate::recording::takes::select(&mut s, &clip_id, take)
+}
 
-    if !monitors.is_empty() {
-        min_x = i32::MAX;
-        min_y = i32::MAX;
-        max_x = i32::MIN;
-        max_y = i32::MIN;
-        for m in &monitors {
-            let pos = m.position();
-            let size = m.size();
-            min_x = min_x.min(pos.x);
-            min_y = min_y.min(pos.y);
-            max_x = max_x.max(pos.x + size.width as i32);
-            max_y = max_y.max(pos.y + size.height as i32);
-        }
-    }
+#[tauri::command]
+pub async fn stop_recording(state: State<'_, Mutex<AppState>>, app: AppHandle) -> Result<Clip, String> {
+    manager::stop(&state, &app).await
+}
 
-    let width = (max_x - min_x) as f64;
-    let height = (max_y - min_y) as f64;
+#[tauri::command]
+pub async fn pause_recording(state: State<'_, Mutex<AppState>>, app: AppHandle) -> Result<(), String> {
+    manager::pause(&state, &app).await
+}
 
-    WebviewWindowBuilder::new(&app, "overlay", WebviewUrl::App("/overlay".into()))
-        .title("Region Selector")
-        .position(min_x as f64, min_y as f64)
-        .inner_size(width, height)
-        .decorations(false)
-        .transparent(true)
-        .always_on_top(true)
-        .skip_taskbar(true)
-        .resizable(false)
-        .focused(true)
-        .build()
-        .map_err(|e| format!("Failed to open overlay: {}", e))?;
+#[tauri::command]
+pub fn resume_recording(state: State<'_, Mutex<AppState>>, app: AppHandle) -> Result<(), String> {
+    manager::resume(&state, &app)
+}
 
-    Ok(())
+#[tauri::command]
+pub fn cancel_recording(state: State<'_, Mutex<AppState>>, app: AppHandle) -> Result<(), String> {
+    manager::cancel(&state, &app)
 }
 
+/// Silence the microphone until toggled again; returns whether it is muted
 #[tauri::command]
-pub fn get_monitors_info(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
-    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
-    Ok(monitors.iter().map(|m| {
-        let pos = m.position();
-        let size = m.size();
-        MonitorInfo {
-            x: pos.x,
-            y: pos.y,
-            width: size.width,
-            height: size.height,
-        }
-    }).collect())
+pub fn toggle_mic_mute(state: State<'_, Mutex<AppState>>, app: AppHandle) -> Result<bool, String> {
+    let muted = manager::toggle_mic_mute(&state)?;
+    events::emit(&app, AppEvent::MicMuteChanged { muted });
+    Ok(muted)
 }
 
-#[derive(serde::Serialize)]
-pub struct MonitorInfo {
-    pub x: i32,
-    pub y: i32,
-    pub width: u32,
-    pub height: u32,
+#[tauri::command]
+pub fn get_recording_duration_ms(state: State<'_, Mutex<AppState>>) -> Result<u64, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager::elapsed_ms(&state))
 }
 
+/// Frame rate of the capture in progress, asked and achieved
 #[tauri::command]
-pub async fn close_region_selector(app: AppHandle) -> Result<(), String> {
-    if let Some(w) = app.get_webview_window("overlay") {
-        w.close().map_err(|e| e.to_string())?;
+pub fn get_recording_stats(state: State<'_, Mutex<AppState>>) -> Result<crate::types::RecordingStats, String> {
+    let s = state.lock().map_err(|e| e.to_string())?;
+    if s.recording_state == RecordingState::Idle {
+        return Err(crate::i18n::t("not_recording"));
     }
+    let achieved_fps = if s.recording_state == RecordingState::Recording {
+        crate::recording::timing::achieved_fps(&crate::recording::timing::progress_path(&s.temp_dir))
+    } else {
+        None
+    };
+    Ok(crate::types::RecordingStats { target_fps: s.capture_framerate, refresh_rate: s.capture_refresh, achieved_fps })
+}
+
+#[tauri::command]
+pub fn set_live_drawing(state: State<'_, Mutex<AppState>>, enabled: bool) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.live_drawing_enabled = enabled;
     Ok(())
 }
 
+/// Script shown on the teleprompter during recordings, None to not open it
 #[tauri::command]
-pub async fn export_video(
+pub fn set_teleprompter(
     state: State<'_, Mutex<AppState>>,
-    app: AppHandle,
-    watermark: bool,
-    format: ExportFormat,
-    quality: ExportQuality,
-) -> Result<String, String> {
-    let (clips, transitions, clip_keystrokes, subtitles, clip_annotations, clip_cursor_positions, system_volume, mic_volume) = {
-        let s = state.lock().map_err(|e| e.to_string())?;
-        (s.clips.clone(), s.transitions.clone(), s.clip_keystrokes.clone(), s.subtitles.clone(), s.annotations.clone(), s.clip_cursor_positions.clone(), s.system_volume, s.mic_volume)
-    };
+    settings: Option<crate::types::TeleprompterSettings>,
+) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.teleprompter = settings;
+    Ok(())
+}
 
-    if clips.is_empty() {
-        return Err("Aucun clip à exporter".into());
-    }
+#[tauri::command]
+pub fn get_teleprompter(state: State<'_, Mutex<AppState>>) -> Result<Option<crate::types::TeleprompterSettings>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.teleprompter.clone())
+}
 
-    eprintln!("[export_video] {} clips, {} transitions, watermark={}, format={:?}, quality={:?}", clips.len(), transitions.len(), watermark, format, quality);
-    for (i, clip) in clips.iter().enumerate() {
-        eprintln!("[export_video] Clip {}: {:?} ({}ms, trim {}..{})", i, clip.path, clip.duration_ms, clip.trim_start_ms, clip.trim_end_ms);
+/// Script line now on the teleprompter's reading line
+#[tauri::command]
+pub fn report_script_line(state: State<'_, Mutex<AppState>>, line: usize) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    if state.recording_state == RecordingState::Idle {
+        return Ok(());
     }
+    let now_ms = manager::elapsed_ms(&state);
+    crate::recording::teleprompter::record_line(&mut state.script_marks, line, now_ms);
+    Ok(())
+}
 
-    // Create output directory
-    let output_dir = dirs::video_dir()
-        .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join("Videos"))
-        .join("ClipFlow");
-    std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
-
-    // Generate filename with timestamp
-    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
-    let ext = match format {
-        ExportFormat::Mp4 => "mp4",
-        ExportFormat::Gif => "gif",
-    };
-    let output_path = output_dir.join(format!("recording_{}.{}", timestamp, ext));
-    eprintln!("[export_video] Output: {:?}", output_path);
-
-    // Run export
-    match format {
-        ExportFormat::Mp4 => {
-            crate::export::encoder::export_mp4(&clips, &transitions, &output_path, &app, watermark, &quality, &clip_keystrokes, &subtitles, &clip_annotations, &clip_cursor_positions, system_volume, mic_volume)
-                .await
-                .map_err(|e| {
-                    eprintln!("[export_video] FAILED: {}", e);
-                    format!("Export échoué : {}", e)
-                })?;
-        }
-        ExportFormat::Gif => {
-            crate::export::encoder::export_gif(&clips, &transitions, &output_path, &app, watermark, &quality, &clip_keystrokes, &subtitles, &clip_annotations, &clip_cursor_positions, system_volume, mic_volume)
-                .await
-                .map_err(|e| {
-                    eprintln!("[export_video] FAILED: {}", e);
-                    format!("Export échoué : {}", e)
-                })?;
-        }
+/// Stroke finished on the drawing overlay, kept for the clip being recorded
+#[tauri::command]
+pub fn add_live_stroke(state: State<'_, Mutex<AppState>>, stroke: crate::types::LiveStroke) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    if state.recording_state == RecordingState::Idle {
+        return Ok(());
     }
+    let now_ms = manager::elapsed_ms(&state);
+    if let Some(ann) = crate::recording::drawing::stroke_annotation(&stroke, now_ms) {
+        state.live_strokes.push(ann);
+    }
+    Ok(())
+}
 
-    // Notify user
-    let filename = output_path.file_name().unwrap_or_default().to_string_lossy().to_string();
-    let _ = app.notification()
-        .builder()
-        .title("ClipFlow")
-        .body(format!("Export terminé : {}", filename))
-        .show();
-
-    // Open output folder
-    eprintln!("[export_video] Opening folder: {:?}", output_dir);
-    let _ = opener::open(&output_dir);
-
-    Ok(output_path.to_string_lossy().to_string())
+#[tauri::command]
+pub fn clear_live_strokes(state: State<'_, Mutex<AppState>>) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let now_ms = manager::elapsed_ms(&state);
+    crate::recording::drawing::clear_strokes(&mut state.live_strokes, now_ms);
+    Ok(())
 }
 
+/// Downscaled frame of the area being recorded, so the UI can show what is captured.
+/// Returns None when no recording is in progress.
 #[tauri::command]
-pub async fn preview_video(
+pub async fn get_live_snapshot(
     state: State<'_, Mutex<AppState>>,
-    app: AppHandle,
-) -> Result<String, String> {
-    let (clips, transitions, clip_keystrokes, subtitles, clip_annotations, clip_cursor_positions) = {
+    max_width: Option<u32>,
+) -> Result<Option<String>, String> {
+    let region = {
         let s = state.lock().map_err(|e| e.to_string())?;
-        (s.clips.clone(), s.transitions.clone(), s.clip_keystrokes.clone(), s.subtitles.clone(), s.annotations.clone(), s.clip_cursor_positions.clone())
+        if s.recording_state == RecordingState::Idle {
+            return Ok(None);
+        }
+        // The area the recorder resolved, the whole desktop when it has none
+        crate::recording::manager::capture_region(&s)
+    };
+    let max_width = max_width.unwrap_or(320);
+
+    tokio::task::spawn_blocking(move || {
+        let region = region.unwrap_or_else(crate::capture::snapshot::virtual_screen_region);
+        crate::capture::snapshot::grab_screen_region(&region, max_width).map(Some)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Take the clip's thumbnail from the frame at `at_ms` (source time), or
+/// about 10% in when not given
+#[tauri::command]
+pub async fn regenerate_thumbnail(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+    at_ms: Option<u64>,
+) -> Result<(), String> {
+    let (video, duration_ms) = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        let clip = s
+            .clips
+            .iter()
+            .find(|c| c.id == clip_id)
+            .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+        (clip.media.path.clone(), clip.media.duration_ms)
+    };
+    let at_ms = at_ms
+        .unwrap_or_else(|| crate::capture::screen::default_thumbnail_ms(duration_ms))
+        .min(duration_ms.saturating_sub(1));
+    // A new file per frame, so cards showing the old one refetch it
+    let thumb = video.with_extension(format!("thumb_{}.png", at_ms));
+    let (src, dst) = (video.clone(), thumb.clone());
+    tokio::task::spawn_blocking(move || crate::capture::screen::generate_thumbnail(&src, &dst, at_ms))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    let mut s = state.lock().map_err(|e| e.to_string())?;
+    let Some(clip) = s.clips.iter_mut().find(|c| c.id == clip_id) else {
+        // Deleted in the meantime
+        let _ = std::fs::remove_file(&thumb);
+        return Err(crate::i18n::tf("clip_not_found", &[&clip_id]));
+    };
+    if let Some(old) = clip.media.thumbnail_path.replace(thumb.clone()) {
+        if old != thumb {
+            let _ = std::fs::remove_file(old);
+        }
+    }
+    Ok(())
+}
+
+/// Hold the frame at `at_ms` (source time) of a clip for `duration_ms`,
+/// annotations included, splitting the clip around it. Returns the timeline.
+#[tauri::command]
+pub async fn insert_freeze(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+    at_ms: u64,
+    duration_ms: u64,
+) -> Result<Vec<Clip>, String> {
+    let (clip, annotations, temp_dir) = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        let clip = s.clips.iter().find(|c| c.id == clip_id).cloned()
+            .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+        (clip, s.annotations.get(&clip_id).cloned().unwrap_or_default(), s.temp_dir.clone())
+    };
+    let end = if clip.media.trim_end_ms > 0 { clip.media.trim_end_ms } else { clip.media.duration_ms };
+    let at_ms = at_ms.clamp(clip.media.trim_start_ms, end.saturating_sub(1));
+    let duration_ms = duration_ms.clamp(100, 60_000);
+
+    let hold_id = uuid::Uuid::new_v4().to_string();
+    let frame = temp_dir.join(format!("{}.thumb.png", hold_id));
+    let video = temp_dir.join(format!("{}.mp4", hold_id));
+    crate::export::frame::extract(&clip, &annotations, at_ms, &frame, true)
+        .await
+        .map_err(|e| format!("{:#}", e))?;
+    if let Err(e) = crate::export::freeze::render(&frame, &video, duration_ms).await {
+        let _ = std::fs::remove_file(&frame);
+        return Err(format!("{:#}", e));
+    }
+    let (width, height, _) = crate::capture::screen::probe_video(&video).await
+        .ok_or_else(|| crate::i18n::t("freeze_frame_unreadable"))?;
+    let hold = Clip {
+        id: hold_id.clone(),
+        media: ClipMedia {
+            path: video.clone(),
+            duration_ms,
+            region: Region { x: 0, y: 0, width, height },
+            has_audio: false,
+            thumbnail_path: Some(frame.clone()),
+            trim_start_ms: 0,
+            trim_end_ms: 0,
+            audio_paths: Vec::new(),
+            hdr: false,
+            secondary: None,
+            follow_cursor: None,
+            focus_track: Vec::new(),
+            script: None,
+            audio_offset_ms: 0,
+        },
+        zoom_keyframes: Vec::new(),
+        capture_group: None,
+        takes: Vec::new(),
+    };
+
+    let mut guard = state.lock().map_err(|e| e.to_string())?;
+    let s = &mut *guard;
+    let Some(index) = s.clips.iter().position(|c| c.id == clip_id) else {
+        // Deleted in the meantime
+        let _ = std::fs::remove_file(&video);
+        let _ = std::fs::remove_file(&frame);
+        return Err(crate::i18n::tf("clip_not_found", &[&clip_id]));
+    };
+    let tail_id = uuid::Uuid::new_v4().to_string();
+    let split = crate::export::freeze::insert(
+        &mut s.clips, &mut s.transitions, &mut s.subtitles, &mut s.global_annotations, index, at_ms, hold, tail_id.clone(),
+    );
+    // The other subtitle languages move the same way
+    if let Some(hold_at) = s.clips.iter().position(|c| c.id == hold_id) {
+        let hold_start = crate::export::range::clip_spans(&s.clips, &s.transitions)[hold_at].0;
+        for track in &mut s.subtitle_tracks {
+            crate::export::freeze::hold_subtitles(&mut track.subtitles, hold_start, duration_ms);
+        }
+    }
+    if let Some(cut_ms) = split {
+        // Events are in source time and carry over as is, annotations are
+        // relative to the trimmed clip
+        if let Some(events) = s.clip_keystrokes.get(&clip_id).cloned() {
+            s.clip_keystrokes.insert(tail_id.clone(), events);
+        }
+        if let Some(positions) = s.clip_cursor_positions.get(&clip_id).cloned() {
+            s.clip_cursor_positions.insert(tail_id.clone(), positions);
+        }
+        if let Some(samples) = s.clip_gamepad.get(&clip_id).cloned() {
+            s.clip_gamepad.insert(tail_id.clone(), samples);
+        }
+        if let Some(markers) = s.clip_markers.get(&clip_id).cloned() {
+            s.clip_markers.insert(tail_id.clone(), markers);
+        }
+        if let Some(mut anns) = s.annotations.get(&clip_id).cloned() {
+            crate::export::range::shift_annotations(&mut anns, cut_ms);
+            s.annotations.insert(tail_id.clone(), anns);
+        }
+        // The held frame is also where the copy starts
+        let thumb = temp_dir.join(format!("{}.thumb.png", tail_id));
+        if let Some(tail) = s.clips.iter_mut().find(|c| c.id == tail_id) {
+            tail.media.thumbnail_path = std::fs::copy(&frame, &thumb).ok().map(|_| thumb);
+        }
+    }
+    tracing::info!("Inserted a {} ms freeze-frame at {} ms of clip {}", duration_ms, at_ms, clip_id);
+    Ok(s.clips.clone())
+}
+
+#[tauri::command]
+pub fn get_thumbnail_base64(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+) -> Result<Option<String>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    let clip = state.clips.iter().find(|c| c.id == clip_id);
+    if let Some(clip) = clip {
+        if let Some(ref thumb_path) = clip.media.thumbnail_path {
+            if thumb_path.exists() {
+                let bytes = std::fs::read(thumb_path).map_err(|e| e.to_string())?;
+                use base64::Engine;
+                let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
+                return Ok(Some(format!("data:image/png;base64,{}", b64)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Small JPEG data URLs of the frames on screen at `timestamps` (source
+/// time), for the trim editor to show where a trim starts and ends
+#[tauri::command]
+pub async fn get_frames_at(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+    timestamps: Vec<u64>,
+    width: Option<u32>,
+) -> Result<Vec<String>, String> {
+    let clip = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        s.clips.iter().find(|c| c.id == clip_id).cloned()
+            .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?
+    };
+    let width = width.unwrap_or(240).clamp(16, 1920) / 2 * 2;
+    use base64::Engine;
+    let mut frames = Vec::new();
+    for at_ms in timestamps {
+        let jpeg = crate::export::frame::preview_jpeg(&clip, at_ms, width)
+            .await
+            .map_err(|e| format!("{:#}", e))?;
+        frames.push(format!("data:image/jpeg;base64,{}", base64::engine::general_purpose::STANDARD.encode(&jpeg)));
+    }
+    Ok(frames)
+}
+
+/// Waveform of a clip's sound as a PNG data URL, None when it has no sound
+#[tauri::command]
+pub async fn render_waveform_image(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+    width: u32,
+    height: u32,
+) -> Result<Option<String>, String> {
+    let clip = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        s.clips.iter().find(|c| c.id == clip_id).cloned()
+            .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?
+    };
+    let (width, height) = (width.clamp(16, 4096), height.clamp(8, 1024));
+    let Some(path) = crate::export::waveform::render(&clip, width, height)
+        .await
+        .map_err(|e| format!("{:#}", e))?
+    else {
+        return Ok(None);
+    };
+    let bytes = std::fs::read(&path).map_err(|e| e.to_string())?;
+    use base64::Engine;
+    Ok(Some(format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(&bytes))))
+}
+
+/// Cursor heatmap of one clip in the export folder, plus the version blended
+/// onto a frame of the clip when `with_frame` is set
+#[tauri::command]
+pub async fn export_cursor_heatmap(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+    with_frame: Option<bool>,
+) -> Result<Vec<String>, String> {
+    let (clip, positions) = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        let clip = s
+            .clips
+            .iter()
+            .find(|c| c.id == clip_id)
+            .cloned()
+            .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+        (clip, s.clip_cursor_positions.get(&clip_id).cloned().unwrap_or_default())
+    };
+
+    let output_dir = export_output_dir()?;
+    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+    let output_path = output_dir.join(format!("heatmap_{}.png", timestamp));
+    let written = crate::export::heatmap::export(&clip, &positions, &output_path, with_frame.unwrap_or(false))
+        .await
+        .map_err(|e| format!("{:#}", e))?;
+    tracing::info!("Cursor heatmap written: {:?}", written);
+    Ok(written.iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+/// Save a full-resolution still of the clip at `at_ms` (source time) to
+/// `path`, or to the export folder when not given. Returns the file written.
+#[tauri::command]
+pub async fn extract_frame(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+    at_ms: u64,
+    path: Option<String>,
+    with_annotations: Option<bool>,
+) -> Result<String, String> {
+    let (clip, annotations) = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        let clip = s
+            .clips
+            .iter()
+            .find(|c| c.id == clip_id)
+            .cloned()
+            .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+        (clip, s.annotations.get(&clip_id).cloned().unwrap_or_default())
+    };
+
+    let output_path = match path {
+        Some(p) => std::path::PathBuf::from(p),
+        None => {
+            let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+            export_output_dir()?.join(format!("frame_{}.png", timestamp))
+        }
+    };
+    let at_ms = at_ms.min(clip.media.duration_ms.saturating_sub(1));
+    crate::export::frame::extract(&clip, &annotations, at_ms, &output_path, with_annotations.unwrap_or(false))
+        .await
+        .map_err(|e| format!("{:#}", e))?;
+    tracing::info!("Frame written: {:?}", output_path);
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub fn get_transitions(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<crate::types::Transition>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.transitions.clone())
+}
+
+#[tauri::command]
+pub async fn open_region_selector(app: AppHandle, secondary: Option<bool>) -> Result<(), String> {
+    // Close existing overlay if any
+    if let Some(w) = app.get_webview_window("overlay") {
+        let _ = w.close();
+    }
+
+    // Calculate bounding box across all monitors
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+    let mut min_x: i32 = 0;
+    let mut min_y: i32 = 0;
+    let mut max_x: i32 = 1920;
+    let mut max_y: i32 = 1080;
+
+    if !monitors.is_empty() {
+        min_x = i32::MAX;
+        min_y = i32::MAX;
+        max_x = i32::MIN;
+        max_y = i32::MIN;
+        for m in &monitors {
+            let pos = m.position();
+            let size = m.size();
+            min_x = min_x.min(pos.x);
+            min_y = min_y.min(pos.y);
+            max_x = max_x.max(pos.x + size.width as i32);
+            max_y = max_y.max(pos.y + size.height as i32);
+        }
+    }
+
+    let width = (max_x - min_x) as f64;
+    let height = (max_y - min_y) as f64;
+
+    // The selector reports the second region instead of the main one
+    let url = if secondary.unwrap_or(false) { "/overlay?target=secondary" } else { "/overlay" };
+    WebviewWindowBuilder::new(&app, "overlay", WebviewUrl::App(url.into()))
+        .title("Region Selector")
+        .position(min_x as f64, min_y as f64)
+        .inner_size(width, height)
+        .decorations(false)
+        .transparent(true)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .focused(true)
+        .build()
+        .map_err(|e| crate::i18n::tf("overlay_failed", &[&e]))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_monitors_info(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+    Ok(monitors.iter().map(|m| {
+        let pos = m.position();
+        let size = m.size();
+        MonitorInfo {
+            x: pos.x,
+            y: pos.y,
+            width: size.width,
+            height: size.height,
+        }
+    }).collect())
+}
+
+#[derive(serde::Serialize)]
+pub struct MonitorInfo {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[tauri::command]
+pub async fn close_region_selector(app: AppHandle) -> Result<(), String> {
+    if let Some(w) = app.get_webview_window("overlay") {
+        w.close().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Settings picked in the export panel. A preset replaces all of them but
+/// the range.
+struct ExportRequest {
+    watermark: bool,
+    format: ExportFormat,
+    quality: ExportQuality,
+    target_size_mb: Option<u32>,
+    preset: Option<String>,
+    start_ms: Option<u64>,
+    end_ms: Option<u64>,
+    /// Every clip goes to its own file
+    per_clip: bool,
+}
+
+/// The settings an export renders with, once its snapshot is prepared
+struct ExportJob {
+    watermark: bool,
+    format: ExportFormat,
+    quality: ExportQuality,
+    target_size_mb: Option<u32>,
+    output: crate::types::OutputSettings,
+    /// Languages muxed as subtitle tracks rather than drawn into the picture
+    soft_tracks: Vec<crate::types::SubtitleTrack>,
+}
+
+/// Soft subtitles: every language of `start_ms..end_ms`, added as a track
+/// once rendered and none drawn into the picture. Empty unless the user
+/// chose that mode.
+fn soft_subtitle_tracks(
+    snap: &mut crate::export::snapshot::ExportSnapshot,
+    start_ms: u64,
+    end_ms: Option<u64>,
+) -> Vec<crate::types::SubtitleTrack> {
+    if snap.subtitle_export != crate::types::SubtitleExport::Soft {
+        return Vec::new();
+    }
+    std::mem::take(&mut snap.subtitle_tracks)
+        .into_iter()
+        .map(|mut track| {
+            crate::export::range::shift_subtitles(&mut track.subtitles, start_ms, end_ms);
+            track
+        })
+        .filter(|track| !track.subtitles.is_empty())
+        .collect()
+}
+
+/// The settings of `request`, from its preset when it names one, completed
+/// with the app-wide export settings
+fn resolve_settings(
+    snap: &crate::export::snapshot::ExportSnapshot,
+    request: &ExportRequest,
+) -> Result<ExportJob, String> {
+    let (watermark, format, quality, target_size_mb, output) = match request.preset.as_deref() {
+        Some(id) => {
+            let p = crate::export::presets::find(&snap.export_presets, id)
+                .ok_or_else(|| crate::i18n::tf("preset_not_found", &[&id]))?;
+            tracing::info!("Using export preset {:?}", p.name);
+            (p.watermark, p.format, p.quality, p.target_size_mb, p.output)
+        }
+        None => (request.watermark, request.format, request.quality, request.target_size_mb, crate::types::OutputSettings::default()),
+    };
+    // A size target would apply to every file
+    let target_size_mb = target_size_mb.filter(|mb| *mb > 0 && !request.per_clip);
+    if request.per_clip && matches!(format, ExportFormat::Webm) {
+        return Err(crate::i18n::t("per_clip_format"));
+    }
+    let output = snap.output_settings(format, output);
+    Ok(ExportJob { watermark, format, quality, target_size_mb, output, soft_tracks: Vec::new() })
+}
+
+/// Refuse early with a clear message instead of an FFmpeg filter graph error
+fn ensure_export_supported(
+    snap: &crate::export::snapshot::ExportSnapshot,
+    job: &ExportJob,
+    per_clip: bool,
+) -> Result<(), String> {
+    let Some(caps) = &snap.ffmpeg_capabilities else {
+        return Ok(());
+    };
+    let uses_text = timeline_uses_text(job.watermark, snap, !job.soft_tracks.is_empty());
+    let mut needed = if per_clip {
+        crate::ffmpeg::capabilities::export_requirements(job.format, 1, &[], uses_text)
+    } else {
+        crate::ffmpeg::capabilities::export_requirements(job.format, snap.clips.len(), &snap.transitions, uses_text)
+    };
+    needed.extend(crate::ffmpeg::capabilities::encoder_requirements(snap.export_encoder));
+    crate::ffmpeg::capabilities::ensure_supported(caps, &needed)
+}
+
+/// Resolve the preset, cut the snapshot to the range and refuse what this
+/// FFmpeg can't do. Renders nothing.
+async fn resolve_export(
+    snap: &mut crate::export::snapshot::ExportSnapshot,
+    request: ExportRequest,
+) -> Result<ExportJob, String> {
+    snap.probe_hdr().await;
+    let mut job = resolve_settings(snap, &request)?;
+
+    if snap.clips.is_empty() {
+        return Err(crate::i18n::t("no_clips_to_export"));
+    }
+
+    // Only part of the timeline
+    let (start_ms, end_ms) = (request.start_ms, request.end_ms);
+    if start_ms.is_some() || end_ms.is_some() {
+        let start_ms = start_ms.unwrap_or(0);
+        if end_ms.is_some_and(|end| end <= start_ms) {
+            return Err(crate::i18n::t("range_end_before_start"));
+        }
+        if !crate::export::range::apply(&mut snap.clips, &mut snap.transitions, &mut snap.clip_annotations, &mut snap.subtitles, &mut snap.global_annotations, start_ms, end_ms) {
+            return Err(crate::i18n::t("range_empty"));
+        }
+        tracing::info!("Exporting timeline range {}ms..{:?}ms", start_ms, end_ms);
+    }
+
+    // GIFs can only burn subtitles in
+    if !matches!(job.format, ExportFormat::Gif) {
+        job.soft_tracks = soft_subtitle_tracks(snap, start_ms.unwrap_or(0), end_ms);
+    }
+    ensure_export_supported(snap, &job, request.per_clip)?;
+    Ok(job)
+}
+
+/// Run the prepasses once for all `jobs`, then fit each output to the
+/// encoder's limits and sample its watermark tone
+async fn finish_export(
+    snap: &mut crate::export::snapshot::ExportSnapshot,
+    jobs: &mut [ExportJob],
+    per_clip: bool,
+) -> Result<(), String> {
+    crate::export::run_prepasses(&mut snap.clips, &mut snap.clip_annotations, &mut snap.clip_cursor_positions, &snap.temp_dir)
+        .await
+        .map_err(|e| crate::i18n::tf("export_failed", &[&e]))?;
+    for job in jobs {
+        crate::export::limits::enforce(snap.export_downscale, snap.export_encoder, &snap.clips, &mut job.output)?;
+        // Files of a per-clip export each pick their own
+        if job.watermark && !per_clip {
+            job.output.watermark_tone = crate::export::watermark::pick_tone(&snap.clips, &snap.clip_annotations, &job.output).await;
+        }
+    }
+    Ok(())
+}
+
+/// Setup shared by every export of the timeline: `resolve_export`, then
+/// `finish_export`
+async fn prepare_export(
+    snap: &mut crate::export::snapshot::ExportSnapshot,
+    request: ExportRequest,
+) -> Result<ExportJob, String> {
+    let per_clip = request.per_clip;
+    let mut job = resolve_export(snap, request).await?;
+    finish_export(snap, std::slice::from_mut(&mut job), per_clip).await?;
+    Ok(job)
+}
+
+#[tauri::command]
+pub async fn export_video(
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+    watermark: bool,
+    format: ExportFormat,
+    quality: ExportQuality,
+    target_size_mb: Option<u32>,
+    preset: Option<String>,
+    start_ms: Option<u64>,
+    end_ms: Option<u64>,
+) -> Result<String, String> {
+    let mut snap = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        crate::export::snapshot::ExportSnapshot::from_state(&s)
+    };
+
+    let ExportJob { watermark, format, quality, target_size_mb, output, soft_tracks } = prepare_export(&mut snap, ExportRequest {
+        watermark,
+        format,
+        quality,
+        target_size_mb,
+        preset,
+        start_ms,
+        end_ms,
+        per_clip: false,
+    })
+    .await?;
+    let soft = !soft_tracks.is_empty();
+    let (clips, transitions) = (&snap.clips, &snap.transitions);
+    let ctx = crate::export::snapshot::ExportContext { subtitles: if soft { &[] } else { &snap.subtitles }, ..snap.context() };
+
+    tracing::info!("{} clips, {} transitions, watermark={}, format={:?}, quality={:?}, encoder={:?}", clips.len(), transitions.len(), watermark, format, quality, snap.export_encoder);
+    for (i, clip) in clips.iter().enumerate() {
+        tracing::info!("Clip {}: {:?} ({}ms, trim {}..{})", i, clip.media.path, clip.media.duration_ms, clip.media.trim_start_ms, clip.media.trim_end_ms);
+    }
+
+    // Generate filename with timestamp
+    let output_dir = export_output_dir()?;
+    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+    let output_path = output_dir.join(format!("recording_{}.{}", timestamp, format.extension()));
+    tracing::info!("Output: {:?}", output_path);
+
+    // Narrations of the exported range, mixed into the finished file
+    let narration = match format {
+        ExportFormat::Mp4 | ExportFormat::Webm if output.include_audio => {
+            crate::export::voiceover::placed(&snap.voiceovers, start_ms.unwrap_or(0), end_ms)
+        }
+        _ => Vec::new(),
+    };
+
+    // Run export
+    events::emit(&app, AppEvent::ExportStarted { formats: vec![format], clip_count: clips.len() });
+    let result = match format {
+        // A size target needs the whole timeline in one two-pass encode
+        ExportFormat::Mp4 if snap.export_chunk_minutes > 0 && target_size_mb.is_none() => {
+            crate::export::chunked::export(clips, transitions, &output_path, &app, watermark, &quality, &ctx, &output, snap.export_chunk_minutes, &snap.temp_dir)
+                .await
+        }
+        ExportFormat::Mp4 => {
+            crate::export::encoder::export_mp4(clips, transitions, &output_path, &app, watermark, &quality, &ctx, target_size_mb, &output)
+                .await
+        }
+        ExportFormat::Gif => {
+            crate::export::encoder::export_gif(clips, transitions, &output_path, &app, watermark, &quality, &ctx, &output)
+                .await
+        }
+        ExportFormat::Webm => {
+            // Same render-then-transcode path as a batch with a single output
+            let target = crate::export::batch::Target { format, quality, target_size_mb, output, path: output_path.clone() };
+            crate::export::batch::export_multi(clips, transitions, &app, watermark, &ctx, &[target], &snap.temp_dir)
+                .await
+        }
+    };
+    let result = match result {
+        Ok(()) => crate::export::voiceover::mix_into(&output_path, format, &narration, snap.mic_volume).await,
+        Err(e) => Err(e),
+    };
+    let result = match result {
+        Ok(()) => crate::export::soft_subtitles::mux_into(&output_path, format, &soft_tracks, &snap.temp_dir).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        tracing::error!("FAILED: {}", e);
+        let code = crate::telemetry::failure_code(&e.to_string());
+        crate::telemetry::record(&snap.telemetry, crate::telemetry::TelemetryEvent::ExportFailed { format, code });
+        events::emit(&app, AppEvent::ExportFailed { message: e.to_string() });
+        return Err(crate::i18n::tf("export_failed", &[&e]));
+    }
+    let total_ms = crate::export::estimate::timeline_duration_ms(clips);
+    crate::telemetry::record(&snap.telemetry, crate::telemetry::TelemetryEvent::ExportCompleted {
+        format,
+        clip_count: clips.len(),
+        duration_bucket: crate::telemetry::duration_bucket(total_ms),
+    });
+    if snap.export_sidecar {
+        write_sidecar(&output_path, &snap, clips, transitions, &snap.subtitles);
+    }
+    if snap.export_chapters {
+        write_chapters(&output_path, &snap);
+    }
+
+    // Notify user
+    let filename = output_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let _ = app.notification()
+        .builder()
+        .title("ClipFlow")
+        .body(crate::i18n::tf("export_done", &[&filename]))
+        .show();
+
+    // Open folder, copy, ... as configured
+    crate::export::post_export::run(&snap.post_export, &output_path);
+    crate::webhook::notify(&snap.webhooks, crate::webhook::WebhookEvent::Export {
+        path: &output_path,
+        format,
+        duration_ms: crate::export::range::timeline_length_ms(clips, transitions),
+        project_id: snap.current_project_id.as_deref(),
+    });
+
+    let path = output_path.to_string_lossy().to_string();
+    events::emit(&app, AppEvent::ExportFinished { paths: vec![path.clone()] });
+    Ok(path)
+}
+
+/// The FFmpeg commands and filter graph `export_video` would run with
+/// these settings, for reproducing a failing export by hand
+#[tauri::command]
+pub async fn debug_export_plan(
+    state: State<'_, Mutex<AppState>>,
+    format: ExportFormat,
+    quality: ExportQuality,
+    watermark: bool,
+    target_size_mb: Option<u32>,
+    preset: Option<String>,
+    start_ms: Option<u64>,
+    end_ms: Option<u64>,
+) -> Result<crate::types::ExportPlan, String> {
+    let mut snap = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        crate::export::snapshot::ExportSnapshot::from_state(&s)
+    };
+
+    // Same settings as `export_video`, without rendering the prepasses: the
+    // plan shows the recorded clips and says what it left out
+    let ExportJob { watermark, format, quality, target_size_mb, mut output, soft_tracks } = resolve_export(&mut snap, ExportRequest {
+        watermark,
+        format,
+        quality,
+        target_size_mb,
+        preset,
+        start_ms,
+        end_ms,
+        per_clip: false,
+    })
+    .await?;
+    crate::export::limits::enforce(snap.export_downscale, snap.export_encoder, &snap.clips, &mut output)?;
+    let soft = !soft_tracks.is_empty();
+    let ctx = crate::export::snapshot::ExportContext { subtitles: if soft { &[] } else { &snap.subtitles }, ..snap.context() };
+
+    let output_path = export_output_dir()?.join(format!("recording_plan.{}", format.extension()));
+    let mut plan = crate::export::plan::plan(
+        &snap.clips, &snap.transitions, &output_path, format, &quality, watermark, &ctx, target_size_mb, &output,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    if matches!(format, ExportFormat::Mp4) && snap.export_chunk_minutes > 0 && target_size_mb.is_none() {
+        plan.notes.push(crate::i18n::tf("plan_chunked", &[&snap.export_chunk_minutes]));
+    }
+    if soft {
+        plan.notes.push(crate::i18n::t("plan_soft_subtitles"));
+    }
+    if crate::export::has_prepasses(&snap.clips) {
+        plan.notes.push(crate::i18n::t("plan_prepasses_skipped"));
+    }
+    Ok(plan)
+}
+
+/// Render the timeline once and transcode it to every target
+#[tauri::command]
+pub async fn export_video_multi(
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+    watermark: bool,
+    targets: Vec<crate::types::ExportTarget>,
+) -> Result<Vec<String>, String> {
+    let mut snap = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        crate::export::snapshot::ExportSnapshot::from_state(&s)
+    };
+
+    if targets.is_empty() {
+        return Err(crate::i18n::t("no_export_targets"));
+    }
+
+    // Presets supply every setting except the watermark, which is shared by the render
+    snap.probe_hdr().await;
+    if snap.clips.is_empty() {
+        return Err(crate::i18n::t("no_clips_to_export"));
+    }
+    let tracks = soft_subtitle_tracks(&mut snap, 0, None);
+    let mut jobs = Vec::new();
+    for t in &targets {
+        let mut job = resolve_settings(&snap, &ExportRequest {
+            watermark,
+            format: t.format,
+            quality: t.quality,
+            target_size_mb: t.target_size_mb,
+            preset: t.preset.clone(),
+            start_ms: None,
+            end_ms: None,
+            per_clip: false,
+        })?;
+        job.watermark = watermark;
+        // GIFs can only burn subtitles in
+        if !matches!(job.format, ExportFormat::Gif) {
+            job.soft_tracks = tracks.clone();
+        }
+        ensure_export_supported(&snap, &job, false)?;
+        jobs.push(job);
+    }
+    finish_export(&mut snap, &mut jobs, false).await?;
+    let (clips, transitions) = (&snap.clips, &snap.transitions);
+
+    let formats: Vec<ExportFormat> = jobs.iter().map(|j| j.format).collect();
+    let output_dir = export_output_dir()?;
+    let stem = format!("recording_{}", chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"));
+    let paths = crate::export::batch::output_paths(&output_dir, &stem, &formats);
+    let (batch, soft_tracks): (Vec<crate::export::batch::Target>, Vec<Vec<crate::types::SubtitleTrack>>) = jobs
+        .into_iter()
+        .zip(paths.iter())
+        .map(|(job, path)| {
+            let target = crate::export::batch::Target {
+                format: job.format,
+                quality: job.quality,
+                target_size_mb: job.target_size_mb,
+                output: job.output,
+                path: path.clone(),
+            };
+            (target, job.soft_tracks)
+        })
+        .unzip();
+    tracing::info!("Batch export of {} clips to {} outputs", clips.len(), batch.len());
+    events::emit(&app, AppEvent::ExportStarted { formats: formats.clone(), clip_count: clips.len() });
+
+    // One render for the targets muxing the subtitles as tracks, one for
+    // those burning them in
+    let soft_ctx = crate::export::snapshot::ExportContext { subtitles: &[], ..snap.context() };
+    let burned_ctx = snap.context();
+    let (soft_batch, burned_batch): (Vec<_>, Vec<_>) =
+        batch.iter().cloned().zip(&soft_tracks).partition(|(_, tracks)| !tracks.is_empty());
+    let mut result = Ok(());
+    for (group, ctx) in [(soft_batch, &soft_ctx), (burned_batch, &burned_ctx)] {
+        let group: Vec<crate::export::batch::Target> = group.into_iter().map(|(target, _)| target).collect();
+        if result.is_ok() && !group.is_empty() {
+            result = crate::export::batch::export_multi(clips, transitions, &app, watermark, ctx, &group, &snap.temp_dir).await;
+        }
+    }
+    // Narrations go into every video output that keeps the audio
+    let narration = crate::export::voiceover::placed(&snap.voiceovers, 0, None);
+    for target in batch.iter().filter(|t| t.output.include_audio && !matches!(t.format, ExportFormat::Gif)) {
+        if result.is_ok() {
+            result = crate::export::voiceover::mix_into(&target.path, target.format, &narration, snap.mic_volume).await;
+        }
+    }
+    for (target, tracks) in batch.iter().zip(&soft_tracks) {
+        if result.is_ok() {
+            result = crate::export::soft_subtitles::mux_into(&target.path, target.format, tracks, &snap.temp_dir).await;
+        }
+    }
+    if let Err(e) = result {
+        tracing::error!("FAILED: {}", e);
+        let code = crate::telemetry::failure_code(&e.to_string());
+        for format in &formats {
+            crate::telemetry::record(&snap.telemetry, crate::telemetry::TelemetryEvent::ExportFailed { format: *format, code });
+        }
+        events::emit(&app, AppEvent::ExportFailed { message: e.to_string() });
+        return Err(crate::i18n::tf("export_failed", &[&e]));
+    }
+    let total_ms = crate::export::estimate::timeline_duration_ms(clips);
+    for format in &formats {
+        crate::telemetry::record(&snap.telemetry, crate::telemetry::TelemetryEvent::ExportCompleted {
+            format: *format,
+            clip_count: clips.len(),
+            duration_bucket: crate::telemetry::duration_bucket(total_ms),
+        });
+    }
+    if snap.export_sidecar {
+        for path in &paths {
+            write_sidecar(path, &snap, clips, transitions, &snap.subtitles);
+        }
+    }
+    if snap.export_chapters {
+        for path in &paths {
+            write_chapters(path, &snap);
+        }
+    }
+
+    let filenames: Vec<String> = paths.iter()
+        .map(|p| p.file_name().unwrap_or_default().to_string_lossy().to_string())
+        .collect();
+    let _ = app.notification()
+        .builder()
+        .title("ClipFlow")
+        .body(crate::i18n::tf("export_done", &[&filenames.join(", ")]))
+        .show();
+
+    // Post-export action on the first output; webhooks get every file
+    crate::export::post_export::run(&snap.post_export, &paths[0]);
+    let duration_ms = crate::export::range::timeline_length_ms(clips, transitions);
+    for (path, format) in paths.iter().zip(&formats) {
+        crate::webhook::notify(&snap.webhooks, crate::webhook::WebhookEvent::Export {
+            path,
+            format: *format,
+            duration_ms,
+            project_id: snap.current_project_id.as_deref(),
+        });
+    }
+
+    let paths: Vec<String> = paths.iter().map(|p| p.to_string_lossy().to_string()).collect();
+    events::emit(&app, AppEvent::ExportFinished { paths: paths.clone() });
+    Ok(paths)
+}
+
+/// Export every clip of the strip to its own MP4 or GIF
+#[tauri::command]
+pub async fn export_clips_separately(
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+    watermark: bool,
+    format: ExportFormat,
+    quality: ExportQuality,
+    preset: Option<String>,
+) -> Result<Vec<String>, String> {
+    let mut snap = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        crate::export::snapshot::ExportSnapshot::from_state(&s)
+    };
+
+    let ExportJob { watermark, format, quality, output, soft_tracks, .. } = prepare_export(&mut snap, ExportRequest {
+        watermark,
+        format,
+        quality,
+        target_size_mb: None,
+        preset,
+        start_ms: None,
+        end_ms: None,
+        per_clip: true,
+    })
+    .await?;
+    let clips = &snap.clips;
+
+    let output_dir = export_output_dir()?;
+    let stem = format!("recording_{}", chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"));
+    let paths = crate::export::batch::clip_output_paths(&output_dir, &stem, clips.len(), format);
+    tracing::info!("Per-clip export of {} clips, format={:?}, quality={:?}", clips.len(), format, quality);
+    events::emit(&app, AppEvent::ExportStarted { formats: vec![format; clips.len()], clip_count: clips.len() });
+
+    let soft = !soft_tracks.is_empty();
+    let ctx = crate::export::snapshot::ExportContext { subtitles: if soft { &[] } else { &snap.subtitles }, ..snap.context() };
+    let mut result = crate::export::batch::export_each(clips, &snap.transitions, &app, watermark, format, &quality, &ctx, &output, &paths)
+        .await;
+    // Each file gets the lines shown during its clip
+    let spans = crate::export::range::clip_spans(clips, &snap.transitions);
+    for (path, &(start_ms, end_ms)) in paths.iter().zip(&spans) {
+        if result.is_ok() && soft {
+            let clip_tracks: Vec<crate::types::SubtitleTrack> = soft_tracks
+                .iter()
+                .cloned()
+                .map(|mut track| {
+                    crate::export::range::shift_subtitles(&mut track.subtitles, start_ms, Some(end_ms));
+                    track
+                })
+                .filter(|track| !track.subtitles.is_empty())
+                .collect();
+            result = crate::export::soft_subtitles::mux_into(path, format, &clip_tracks, &snap.temp_dir).await;
+        }
+    }
+    if let Err(e) = result {
+        // The context names the clip that failed
+        let message = format!("{:#}", e);
+        tracing::error!("FAILED: {}", message);
+        let code = crate::telemetry::failure_code(&message);
+        crate::telemetry::record(&snap.telemetry, crate::telemetry::TelemetryEvent::ExportFailed { format, code });
+        events::emit(&app, AppEvent::ExportFailed { message: message.clone() });
+        return Err(crate::i18n::tf("export_failed", &[&message]));
+    }
+    let total_ms = crate::export::estimate::timeline_duration_ms(clips);
+    crate::telemetry::record(&snap.telemetry, crate::telemetry::TelemetryEvent::ExportCompleted {
+        format,
+        clip_count: clips.len(),
+        duration_bucket: crate::telemetry::duration_bucket(total_ms),
+    });
+    if snap.export_sidecar {
+        // Each file only holds its clip and the subtitles shown during it
+        for ((path, clip), &(start_ms, end_ms)) in paths.iter().zip(clips).zip(&spans) {
+            let mut clip_subtitles = snap.subtitles.clone();
+            crate::export::range::shift_subtitles(&mut clip_subtitles, start_ms, Some(end_ms));
+            write_sidecar(path, &snap, std::slice::from_ref(clip), &[], &clip_subtitles);
+        }
+    }
+
+    let _ = app.notification()
+        .builder()
+        .title("ClipFlow")
+        .body(crate::i18n::tf("export_done", &[&crate::i18n::tn("files_count", paths.len())]))
+        .show();
+
+    // Post-export action on the first output; webhooks get every file
+    crate::export::post_export::run(&snap.post_export, &paths[0]);
+    for (path, clip) in paths.iter().zip(clips) {
+        crate::webhook::notify(&snap.webhooks, crate::webhook::WebhookEvent::Export {
+            path,
+            format,
+            duration_ms: crate::export::range::timeline_length_ms(std::slice::from_ref(clip), &[]),
+            project_id: snap.current_project_id.as_deref(),
+        });
+    }
+
+    let paths: Vec<String> = paths.iter().map(|p| p.to_string_lossy().to_string()).collect();
+    events::emit(&app, AppEvent::ExportFinished { paths: paths.clone() });
+    Ok(paths)
+}
+
+/// Render the timeline's sound as a waveform or spectrum video
+#[tauri::command]
+pub async fn export_audio_visualizer(
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+    style: crate::types::VisualizerStyle,
+    title: Option<String>,
+    quality: ExportQuality,
+) -> Result<String, String> {
+    let (clips, system_volume, mic_volume, post_export) = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        (s.clips.clone(), s.system_volume, s.mic_volume, s.post_export.clone())
+    };
+    if clips.is_empty() {
+        return Err(crate::i18n::t("no_clips_to_export"));
+    }
+
+    let output_dir = export_output_dir()?;
+    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+    let output_path = output_dir.join(format!("recording_{}_audio.mp4", timestamp));
+    tracing::info!("Audio visualizer export ({:?}) to {:?}", style, output_path);
+
+    let title = title.filter(|t| !t.trim().is_empty());
+    events::emit(&app, AppEvent::ExportStarted { formats: vec![ExportFormat::Mp4], clip_count: clips.len() });
+    crate::export::visualizer::export(&clips, &output_path, &app, style, title.as_deref(), &quality, system_volume, mic_volume)
+        .await
+        .map_err(|e| {
+            tracing::error!("FAILED: {}", e);
+            events::emit(&app, AppEvent::ExportFailed { message: e.to_string() });
+            crate::i18n::tf("export_failed", &[&e])
+        })?;
+
+    let filename = output_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let _ = app.notification()
+        .builder()
+        .title("ClipFlow")
+        .body(crate::i18n::tf("export_done", &[&filename]))
+        .show();
+    crate::export::post_export::run(&post_export, &output_path);
+    let path = output_path.to_string_lossy().to_string();
+    events::emit(&app, AppEvent::ExportFinished { paths: vec![path.clone()] });
+    Ok(path)
+}
+
+#[tauri::command]
+pub fn set_watch_folder(state: State<'_, Mutex<AppState>>, settings: crate::types::WatchFolderSettings) -> Result<(), String> {
+    if let Some(path) = settings.path.as_deref().filter(|p| !p.is_empty()) {
+        if !std::path::Path::new(path).is_dir() {
+            return Err(crate::i18n::tf("folder_not_found", &[&path]));
+        }
+    }
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.watch_folder = settings;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_watch_folder(state: State<'_, Mutex<AppState>>) -> Result<crate::types::WatchFolderSettings, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.watch_folder.clone())
+}
+
+/// New videos of the watched folder waiting for confirmation
+#[tauri::command]
+pub fn get_watch_pending(state: State<'_, Mutex<AppState>>) -> Result<Vec<String>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.watch_pending.clone())
+}
+
+/// Import or dismiss queued videos of the watched folder
+#[tauri::command]
+pub fn resolve_watch_pending(
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+    paths: Vec<String>,
+    import: bool,
+) -> Result<(), String> {
+    {
+        let mut s = state.lock().map_err(|e| e.to_string())?;
+        s.watch_pending.retain(|p| !paths.contains(p));
+    }
+    if import {
+        crate::import::handle_drop(app, paths.into_iter().map(std::path::PathBuf::from).collect());
+    }
+    Ok(())
+}
+
+/// Videos already exported to the ClipFlow folder, newest first
+#[tauri::command]
+pub fn list_exported_videos() -> Result<Vec<crate::types::ExportedFile>, String> {
+    Ok(crate::export::history::list(&export_output_dir()?))
+}
+
+/// Append earlier exports to the timeline, like files dropped on the window
+#[tauri::command]
+pub fn import_exported_videos(
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+    paths: Vec<String>,
+) -> Result<(), String> {
+    {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        if s.recording_state != RecordingState::Idle {
+            return Err(crate::i18n::t("import_while_recording"));
+        }
+    }
+    let paths: Vec<std::path::PathBuf> = paths.into_iter().map(std::path::PathBuf::from).collect();
+    if let Some(missing) = paths.iter().find(|p| !p.is_file()) {
+        return Err(crate::i18n::tf("file_not_found", &[&missing.display()]));
+    }
+    crate::import::handle_drop(app, paths);
+    Ok(())
+}
+
+/// A missing sidecar doesn't fail the export it describes
+fn write_sidecar(
+    output: &std::path::Path,
+    snap: &crate::export::snapshot::ExportSnapshot,
+    clips: &[Clip],
+    transitions: &[crate::types::Transition],
+    subtitles: &[Subtitle],
+) {
+    match crate::export::sidecar::write(output, clips, transitions, &snap.clip_keystrokes, &snap.clip_markers, subtitles) {
+        Ok(path) => tracing::info!("Sidecar written to {:?}", path),
+        Err(e) => tracing::warn!("Sidecar for {:?} failed: {}", output, e),
+    }
+}
+
+/// Missing chapters don't fail the export either
+fn write_chapters(output: &std::path::Path, snap: &crate::export::snapshot::ExportSnapshot) {
+    match crate::export::chapters::write(output, &snap.clips, &snap.transitions) {
+        Ok(Some(path)) => tracing::info!("Chapters written to {:?}", path),
+        Ok(None) => tracing::warn!("Fewer than 3 chapters for {:?}, none written", output),
+        Err(e) => tracing::warn!("Chapters for {:?} failed: {}", output, e),
+    }
+}
+
+/// `%USERPROFILE%/Videos/ClipFlow`, created if needed
+fn export_output_dir() -> Result<std::path::PathBuf, String> {
+    let output_dir = export_dir_path();
+    std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
+    Ok(output_dir)
+}
+
+fn export_dir_path() -> std::path::PathBuf {
+    dirs::video_dir()
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join("Videos"))
+        .join("ClipFlow")
+}
+
+/// Whether the export draws any text (needs the drawtext filter). Soft
+/// subtitles are muxed as tracks, not drawn.
+fn timeline_uses_text(watermark: bool, snap: &crate::export::snapshot::ExportSnapshot, soft_subtitles: bool) -> bool {
+    watermark
+        || (!soft_subtitles && !snap.subtitles.is_empty())
+        || snap.clips.iter().any(|c| snap.clip_keystrokes.get(&c.id).is_some_and(|k| !k.is_empty()))
+        || snap.global_annotations.iter().chain(snap.clip_annotations.values().flatten()).any(|a| a.kind == crate::types::AnnotationKind::Text)
+}
+
+#[tauri::command]
+pub async fn estimate_export_size(
+    state: State<'_, Mutex<AppState>>,
+    format: ExportFormat,
+    quality: ExportQuality,
+    target_size_mb: Option<u32>,
+    preset: Option<String>,
+    sample: Option<bool>,
+) -> Result<crate::types::SizeEstimate, String> {
+    let (clips, temp_dir, user_presets, export_fps, export_encoder, export_downscale) = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        (s.clips.clone(), s.temp_dir.clone(), s.export_presets.clone(), s.export_fps, s.export_encoder, s.export_downscale)
+    };
+    if clips.is_empty() {
+        return Err(crate::i18n::t("no_clips_to_export"));
+    }
+
+    let (format, quality, target_size_mb, mut output) = match preset.as_deref() {
+        Some(id) => {
+            let p = crate::export::presets::find(&user_presets, id)
+                .ok_or_else(|| crate::i18n::tf("preset_not_found", &[&id]))?;
+            (p.format, p.quality, p.target_size_mb, p.output)
+        }
+        None => (format, quality, target_size_mb, crate::types::OutputSettings::default()),
+    };
+    if !matches!(format, ExportFormat::Gif) {
+        export_fps.apply_to(&mut output);
+        output = crate::export::encoder::resolve_source_fps(&clips, &output).await;
+    }
+    // Estimated at the downscaled size; sizes the encoder refuses are
+    // reported by `check_export_resolution`
+    let _ = crate::export::limits::enforce(export_downscale, export_encoder, &clips, &mut output);
+
+    if sample.unwrap_or(false) {
+        Ok(crate::export::estimate::sampled(&clips, format, &quality, target_size_mb, &output, &temp_dir).await)
+    } else {
+        Ok(crate::export::estimate::heuristic(&clips, format, &quality, target_size_mb, &output))
+    }
+}
+
+/// Issues `export_video` would run into with these settings, found before
+/// starting it: missing files, sizes, frame rates, disk space, output folder
+#[tauri::command]
+pub async fn validate_export(
+    state: State<'_, Mutex<AppState>>,
+    format: ExportFormat,
+    quality: ExportQuality,
+    target_size_mb: Option<u32>,
+    preset: Option<String>,
+    start_ms: Option<u64>,
+    end_ms: Option<u64>,
+) -> Result<Vec<crate::types::ExportIssue>, String> {
+    let (mut clips, mut transitions, mut subtitles, mut clip_annotations, user_presets, export_encoder, export_fps, export_downscale) = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        (s.clips.clone(), s.transitions.clone(), s.subtitles.clone(), s.annotations.clone(), s.export_presets.clone(), s.export_encoder, s.export_fps, s.export_downscale)
+    };
+
+    // Same settings as `export_video`
+    let (format, quality, target_size_mb, mut output) = match preset.as_deref() {
+        Some(id) => {
+            let p = crate::export::presets::find(&user_presets, id)
+                .ok_or_else(|| crate::i18n::tf("preset_not_found", &[&id]))?;
+            (p.format, p.quality, p.target_size_mb, p.output)
+        }
+        None => (format, quality, target_size_mb, crate::types::OutputSettings::default()),
+    };
+    output.encoder = export_encoder;
+    if !matches!(format, ExportFormat::Gif) {
+        export_fps.apply_to(&mut output);
+    }
+    if start_ms.is_some() || end_ms.is_some() {
+        crate::export::range::apply(&mut clips, &mut transitions, &mut clip_annotations, &mut subtitles, &mut Vec::new(), start_ms.unwrap_or(0), end_ms);
+    }
+
+    Ok(crate::export::validate::validate(
+        &clips, format, &quality, target_size_mb, &output, export_downscale, &export_dir_path(),
+    ).await)
+}
+
+/// Export size against the encoder limits, and the downscale settings
+/// offered when it is too large or slow
+#[tauri::command]
+pub fn check_export_resolution(
+    state: State<'_, Mutex<AppState>>,
+    preset: Option<String>,
+) -> Result<crate::types::ResolutionCheck, String> {
+    let s = state.lock().map_err(|e| e.to_string())?;
+    if s.clips.is_empty() {
+        return Err(crate::i18n::t("no_clips_to_export"));
+    }
+    let output = match preset.as_deref() {
+        Some(id) => crate::export::presets::find(&s.export_presets, id)
+            .ok_or_else(|| crate::i18n::tf("preset_not_found", &[&id]))?
+            .output,
+        None => crate::types::OutputSettings::default(),
+    };
+    Ok(crate::export::limits::check(s.export_downscale, s.export_encoder, &s.clips, &output))
+}
+
+// Safe areas and grids of the platforms' canvases, for the editor's guides
+#[tauri::command]
+pub fn get_safe_area_guides() -> Vec<crate::types::SafeAreaGuide> {
+    crate::export::safe_area::builtin()
+}
+
+#[tauri::command]
+pub fn get_export_presets(state: State<'_, Mutex<AppState>>) -> Result<Vec<crate::types::ExportPreset>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(crate::export::presets::all(&state.export_presets))
+}
+
+#[tauri::command]
+pub fn set_export_encoder(state: State<'_, Mutex<AppState>>, encoder: crate::types::ExportEncoder) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    if let Some(caps) = &state.ffmpeg_capabilities {
+        crate::ffmpeg::capabilities::ensure_supported(caps, &crate::ffmpeg::capabilities::encoder_requirements(encoder))?;
+    }
+    state.export_encoder = encoder;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_export_fps(state: State<'_, Mutex<AppState>>, fps: crate::types::ExportFps) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.export_fps = fps;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_export_downscale(state: State<'_, Mutex<AppState>>, downscale: crate::types::ExportDownscale) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.export_downscale = match downscale {
+        crate::types::ExportDownscale::MaxHeight(h) => crate::types::ExportDownscale::MaxHeight(h.clamp(144, 4320)),
+        other => other,
+    };
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_hdr_tonemap(state: State<'_, Mutex<AppState>>, enabled: bool) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.hdr_tonemap = enabled;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_keep_filter_scripts(state: State<'_, Mutex<AppState>>, enabled: bool) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.keep_filter_scripts = enabled;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_gif_palette(state: State<'_, Mutex<AppState>>, palette: crate::types::GifPalette) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.gif_palette = crate::types::GifPalette {
+        max_colors: palette.max_colors.clamp(2, 256),
+        ..palette
+    };
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_gif_loop(state: State<'_, Mutex<AppState>>, gif_loop: crate::types::GifLoop) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.gif_loop = gif_loop;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_export_sidecar(state: State<'_, Mutex<AppState>>, enabled: bool) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.export_sidecar = enabled;
+    Ok(())
+}
+
+/// Write the YouTube chapters of every export next to it, as a text file
+#[tauri::command]
+pub fn set_export_chapters(state: State<'_, Mutex<AppState>>, enabled: bool) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.export_chapters = enabled;
+    Ok(())
+}
+
+/// Chapters of the timeline as `00:00 Title` lines, to paste in a YouTube
+/// description. Empty when there are too few for YouTube.
+#[tauri::command]
+pub fn generate_chapter_text(state: State<'_, Mutex<AppState>>) -> Result<String, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(crate::export::chapters::generate_chapter_text(&state.clips, &state.transitions))
+}
+
+#[tauri::command]
+pub fn set_export_chunk_minutes(state: State<'_, Mutex<AppState>>, minutes: u32) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.export_chunk_minutes = minutes;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_export_fps(state: State<'_, Mutex<AppState>>) -> Result<crate::types::ExportFps, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.export_fps)
+}
+
+#[tauri::command]
+pub fn get_export_downscale(state: State<'_, Mutex<AppState>>) -> Result<crate::types::ExportDownscale, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.export_downscale)
+}
+
+#[tauri::command]
+pub fn get_export_encoder(state: State<'_, Mutex<AppState>>) -> Result<crate::types::ExportEncoder, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.export_encoder)
+}
+
+/// Replace the user presets; built-ins passed back by the frontend are ignored
+#[tauri::command]
+pub fn set_export_presets(
+    state: State<'_, Mutex<AppState>>,
+    presets: Vec<crate::types::ExportPreset>,
+) -> Result<Vec<crate::types::ExportPreset>, String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.export_presets = crate::export::presets::sanitize_user(presets);
+    Ok(crate::export::presets::all(&state.export_presets))
+}
+
+#[tauri::command]
+pub async fn preview_video(
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<String, String> {
+    let (mut clips, transitions, clip_keystrokes, keystroke_font, subtitles, global_annotations, mut clip_annotations, mut clip_cursor_positions, temp_dir) = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        (s.clips.clone(), s.transitions.clone(), s.clip_keystrokes.clone(), s.keystroke_font.clone(), s.subtitles.clone(), s.global_annotations.clone(), s.annotations.clone(), s.clip_cursor_positions.clone(), s.temp_dir.clone())
     };
 
     if clips.is_empty() {
-        return Err("Aucun clip à prévisualiser".into());
+        return Err(crate::i18n::t("no_clips_to_preview"));
     }
+    // Second regions, reframing and focus shots as the export shows them
+    crate::export::run_prepasses(&mut clips, &mut clip_annotations, &mut clip_cursor_positions, &temp_dir)
+        .await
+        .map_err(|e| crate::i18n::tf("preview_failed", &[&e]))?;
 
     // Use temp directory for preview
-    let preview_dir = std::env::temp_dir().join("clipflow_preview");
+    let preview_dir = crate::storage::preview_dir();
     std::fs::create_dir_all(&preview_dir).map_err(|e| e.to_string())?;
     let preview_path = preview_dir.join("preview.mp4");
 
-    eprintln!("[preview_video] {} clips, output: {:?}", clips.len(), preview_path);
+    tracing::info!("{} clips, output: {:?}", clips.len(), preview_path);
 
     crate::export::encoder::preview_mp4(
         &clips, &transitions, &preview_path, &app,
-        &clip_keystrokes, &subtitles, &clip_annotations, &clip_cursor_positions,
+        &clip_keystrokes, keystroke_font.as_deref(), &subtitles, &global_annotations, &clip_annotations, &clip_cursor_positions,
     )
         .await
         .map_err(|e| {
-            eprintln!("[preview_video] FAILED: {}", e);
-            format!("Prévisualisation échouée : {}", e)
+            tracing::error!("FAILED: {}", e);
+            crate::i18n::tf("preview_failed", &[&e])
         })?;
 
     Ok(preview_path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-pub async fn ensure_ffmpeg() -> Result<String, String> {
-    let ffmpeg = crate::ffmpeg_bin();
-    if ffmpeg.exists() {
-        return Ok(ffmpeg.to_string_lossy().to_string());
+pub async fn ensure_ffmpeg(app: tauri::AppHandle) -> Result<String, String> {
+    let ffmpeg = crate::ffmpeg_bin();
+    if ffmpeg.exists() {
+        return Ok(ffmpeg.to_string_lossy().to_string());
+    }
+    // Download FFmpeg to our AppData/Local/ClipFlow/ directory
+    let download_dir = dirs::data_local_dir()
+        .ok_or("Cannot find local data directory")?
+        .join("ClipFlow");
+    std::fs::create_dir_all(&download_dir).map_err(|e| e.to_string())?;
+
+    let handle = app.clone();
+    tokio::task::spawn_blocking(move || crate::ffmpeg::download::install(&handle, &download_dir))
+        .await
+        .map_err(|e| format!("FFmpeg download task failed: {}", e))?
+        .map_err(|e| crate::i18n::tf("ffmpeg_download_failed", &[&e]))?;
+
+    let ffmpeg = crate::ffmpeg_bin();
+    if ffmpeg.exists() {
+        crate::ffmpeg::capabilities::refresh(&app).await;
+        Ok(ffmpeg.to_string_lossy().to_string())
+    } else {
+        Err(crate::i18n::t("ffmpeg_missing_after_download"))
+    }
+}
+
+#[tauri::command]
+pub async fn get_ffmpeg_capabilities(
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<crate::types::FfmpegCapabilities, String> {
+    let cached = state.lock().map_err(|e| e.to_string())?.ffmpeg_capabilities.clone();
+    match cached {
+        Some(caps) => Ok(caps),
+        None => Ok(crate::ffmpeg::capabilities::refresh(&app).await),
+    }
+}
+
+// QA: generated clips instead of screen captures, appended to the timeline.
+// Debug builds only.
+#[cfg(debug_assertions)]
+#[tauri::command]
+pub async fn generate_test_clips(
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+    specs: Vec<crate::types::TestClipSpec>,
+) -> Result<Vec<Clip>, String> {
+    let temp_dir = state.lock().map_err(|e| e.to_string())?.temp_dir.clone();
+    std::fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
+    let mut clips = Vec::new();
+    for spec in &specs {
+        let clip = crate::testclips::generate(&temp_dir, spec).await?;
+        crate::import::add_clip(&app, clip.clone());
+        clips.push(clip);
+    }
+    Ok(clips)
+}
+
+// Window snapping
+#[tauri::command]
+pub async fn get_visible_windows(include_thumbnails: Option<bool>) -> Result<Vec<WindowInfo>, String> {
+    if include_thumbnails.unwrap_or(false) {
+        // PrintWindow on many windows is slow — keep it off the main thread
+        tokio::task::spawn_blocking(crate::region::selector::enumerate_visible_windows_with_thumbnails)
+            .await
+            .map_err(|e| e.to_string())?
+    } else {
+        crate::region::selector::enumerate_visible_windows()
+    }
+}
+
+#[tauri::command]
+pub fn get_monitor_work_areas() -> Result<Vec<MonitorArea>, String> {
+    crate::region::selector::enumerate_monitor_areas()
+}
+
+#[tauri::command]
+pub fn snap_region(region: Region) -> Result<Region, String> {
+    crate::region::selector::snap_region(&region)
+}
+
+// Countdown
+#[tauri::command]
+pub fn set_countdown(state: State<'_, Mutex<AppState>>, seconds: u32) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.countdown_seconds = seconds;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_countdown(state: State<'_, Mutex<AppState>>) -> Result<u32, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.countdown_seconds)
+}
+
+// Auto-pause on lock / display off
+#[tauri::command]
+pub fn set_lock_pause_settings(
+    state: State<'_, Mutex<AppState>>,
+    auto_pause: bool,
+    auto_resume: bool,
+) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.auto_pause_on_lock = auto_pause;
+    state.auto_resume_on_unlock = auto_resume;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_lock_pause_settings(state: State<'_, Mutex<AppState>>) -> Result<(bool, bool), String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok((state.auto_pause_on_lock, state.auto_resume_on_unlock))
+}
+
+// Recording quality
+#[tauri::command]
+pub fn set_recording_quality(state: State<'_, Mutex<AppState>>, quality: RecordingQuality) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    if let Some(caps) = &state.ffmpeg_capabilities {
+        crate::ffmpeg::capabilities::ensure_supported(caps, &[quality.encoder_name()])?;
+    }
+    state.recording_quality = quality.sanitized();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_recording_quality(state: State<'_, Mutex<AppState>>) -> Result<RecordingQuality, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.recording_quality.clone())
+}
+
+/// Override the capture encoder, Auto for the first GPU encoder that works
+#[tauri::command]
+pub fn set_recording_encoder(state: State<'_, Mutex<AppState>>, encoder: crate::types::RecordingEncoder) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    if let Some(caps) = &state.ffmpeg_capabilities {
+        crate::ffmpeg::capabilities::ensure_supported(caps, &[encoder.encoder_name()])?;
+    }
+    state.recording_quality.encoder = encoder;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_recording_encoders(state: State<'_, Mutex<AppState>>) -> Result<Vec<crate::types::RecordingEncoderInfo>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(crate::capture::hwaccel::describe(state.gpu_encoders.as_deref()))
+}
+
+// Capture backend
+#[tauri::command]
+pub fn set_capture_backend(state: State<'_, Mutex<AppState>>, backend: crate::types::CaptureBackendKind) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    if let (Some(caps), Some(name)) = (&state.ffmpeg_capabilities, crate::capture::backend::backend(backend).requirement()) {
+        crate::ffmpeg::capabilities::ensure_supported(caps, &[name])?;
+    }
+    state.capture_backend = backend;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_capture_backends(state: State<'_, Mutex<AppState>>) -> Result<Vec<crate::types::CaptureBackendInfo>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(crate::capture::backend::describe(state.ffmpeg_capabilities.as_ref()))
+}
+
+// Segment rollover
+#[tauri::command]
+pub fn set_segment_rollover(state: State<'_, Mutex<AppState>>, settings: SegmentRollover) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.segment_rollover = settings;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_segment_rollover(state: State<'_, Mutex<AppState>>) -> Result<SegmentRollover, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.segment_rollover.clone())
+}
+
+// Adaptive capture
+#[tauri::command]
+pub fn set_adaptive_capture(state: State<'_, Mutex<AppState>>, settings: crate::types::AdaptiveCapture) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.adaptive_capture = crate::types::AdaptiveCapture {
+        cpu_threshold: settings.cpu_threshold.clamp(30, 100),
+        ..settings
+    };
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_adaptive_capture(state: State<'_, Mutex<AppState>>) -> Result<crate::types::AdaptiveCapture, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.adaptive_capture.clone())
+}
+
+#[tauri::command]
+pub fn set_capture_rate(state: State<'_, Mutex<AppState>>, rate: crate::types::CaptureRate) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.capture_rate = rate;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_capture_rate(state: State<'_, Mutex<AppState>>) -> Result<crate::types::CaptureRate, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.capture_rate)
+}
+
+/// Refresh rate of the monitor the next recording would capture, and the
+/// capture rates dividing it
+#[tauri::command]
+pub fn get_capture_timing(state: State<'_, Mutex<AppState>>) -> Result<crate::types::CaptureTiming, String> {
+    let monitors = crate::region::selector::enumerate_monitor_areas()?;
+    let state = state.lock().map_err(|e| e.to_string())?;
+    let refresh_rate = crate::recording::timing::refresh_rate(manager::capture_region(&state).as_ref(), &monitors);
+    Ok(crate::types::CaptureTiming {
+        refresh_rate,
+        options: refresh_rate.map(crate::recording::timing::options).unwrap_or_default(),
+    })
+}
+
+// Transition at pauses
+#[tauri::command]
+pub fn set_pause_transition(state: State<'_, Mutex<AppState>>, transition: crate::types::PauseTransition) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.pause_transition = crate::types::PauseTransition {
+        duration_ms: transition.duration_ms.clamp(100, 2000),
+        ..transition
+    };
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_pause_transition(state: State<'_, Mutex<AppState>>) -> Result<crate::types::PauseTransition, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.pause_transition)
+}
+
+// Privacy guard
+#[tauri::command]
+pub fn set_privacy_blacklist(state: State<'_, Mutex<AppState>>, patterns: Vec<String>) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.privacy_blacklist = patterns
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_privacy_blacklist(state: State<'_, Mutex<AppState>>) -> Result<Vec<String>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.privacy_blacklist.clone())
+}
+
+#[tauri::command]
+pub fn get_clip_markers(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+) -> Result<Vec<TimelineMarker>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.clip_markers.get(&clip_id).cloned().unwrap_or_default())
+}
+
+// Crash recovery
+#[tauri::command]
+pub fn list_recovered_recordings(state: State<'_, Mutex<AppState>>) -> Result<Vec<Clip>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.recovered_clips.clone())
+}
+
+#[tauri::command]
+pub fn restore_recovered_recording(state: State<'_, Mutex<AppState>>, clip_id: String) -> Result<Clip, String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let index = state.recovered_clips.iter().position(|c| c.id == clip_id)
+        .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+    let clip = state.recovered_clips.remove(index);
+    if !state.clips.is_empty() {
+        state.transitions.push(crate::types::Transition::default());
+    }
+    state.clips.push(clip.clone());
+    Ok(clip)
+}
+
+#[tauri::command]
+pub fn discard_recovered_recording(state: State<'_, Mutex<AppState>>, clip_id: String) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    if let Some(clip) = state.recovered_clips.iter().find(|c| c.id == clip_id) {
+        let _ = std::fs::remove_file(&clip.media.path);
+        if let Some(ref thumb) = clip.media.thumbnail_path {
+            let _ = std::fs::remove_file(thumb);
+        }
     }
-    // Download FFmpeg to our AppData/Local/ClipFlow/ directory
-    let download_dir = dirs::data_local_dir()
-        .ok_or("Cannot find local data directory")?
-        .join("ClipFlow");
-    std::fs::create_dir_all(&download_dir).map_err(|e| e.to_string())?;
+    state.recovered_clips.retain(|c| c.id != clip_id);
+    Ok(())
+}
 
-    let dest = download_dir.clone();
-    tokio::task::spawn_blocking(move || {
-        let url = ffmpeg_sidecar::download::ffmpeg_download_url()
-            .map_err(|e| format!("Failed to get download URL: {}", e))?;
-        let archive = ffmpeg_sidecar::download::download_ffmpeg_package(url, &dest)
-            .map_err(|e| format!("Failed to download FFmpeg: {}", e))?;
-        ffmpeg_sidecar::download::unpack_ffmpeg(&archive, &dest)
-            .map_err(|e| format!("Failed to unpack FFmpeg: {}", e))?;
-        Ok::<(), String>(())
-    })
-    .await
-    .map_err(|e| format!("FFmpeg download task failed: {}", e))?
-    .map_err(|e| format!("FFmpeg download failed: {}", e))?;
+// Storage
+/// Clips and narrations whose media must stay: the timeline, recovered
+/// captures, saved projects
+fn media_in_use(state: &State<'_, Mutex<AppState>>) -> Result<(std::path::PathBuf, Vec<Clip>, Vec<crate::types::Voiceover>), String> {
+    let (temp_dir, mut clips, mut voiceovers) = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        (s.temp_dir.clone(), s.clips.iter().chain(&s.recovered_clips).cloned().collect::<Vec<_>>(), s.voiceovers.clone())
+    };
+    clips.extend(crate::project::saved_clips());
+    voiceovers.extend(crate::project::saved_voiceovers());
+    Ok((temp_dir, clips, voiceovers))
+}
 
-    let ffmpeg = crate::ffmpeg_bin();
-    if ffmpeg.exists() {
-        Ok(ffmpeg.to_string_lossy().to_string())
-    } else {
-        Err("FFmpeg downloaded but binary not found".into())
-    }
+#[tauri::command]
+pub fn get_storage_usage(state: State<'_, Mutex<AppState>>) -> Result<crate::types::StorageUsage, String> {
+    let (temp_dir, clips, voiceovers) = media_in_use(&state)?;
+    Ok(crate::storage::usage(&temp_dir, &clips, &voiceovers))
 }
 
-// Window snapping
 #[tauri::command]
-pub fn get_visible_windows() -> Result<Vec<WindowInfo>, String> {
-    crate::region::selector::enumerate_visible_windows()
+pub fn clear_previews() -> crate::types::StorageEntry {
+    crate::storage::clear_previews()
 }
 
-// Countdown
+/// Delete the temp files no clip or saved project uses anymore
 #[tauri::command]
-pub fn set_countdown(state: State<'_, Mutex<AppState>>, seconds: u32) -> Result<(), String> {
-    let mut state = state.lock().map_err(|e| e.to_string())?;
-    state.countdown_seconds = seconds;
-    Ok(())
+pub fn clear_orphaned_temp(state: State<'_, Mutex<AppState>>) -> Result<crate::types::StorageEntry, String> {
+    {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        if s.recording_state != RecordingState::Idle {
+            return Err(crate::i18n::t("cleanup_while_recording"));
+        }
+    }
+    let (temp_dir, clips, voiceovers) = media_in_use(&state)?;
+    Ok(crate::storage::clear_orphans(&temp_dir, &clips, &voiceovers))
 }
 
 #[tauri::command]
-pub fn get_countdown(state: State<'_, Mutex<AppState>>) -> Result<u32, String> {
-    let state = state.lock().map_err(|e| e.to_string())?;
-    Ok(state.countdown_seconds)
+pub fn get_storage_policy() -> crate::types::StoragePolicy {
+    crate::storage::load_policy()
+}
+
+#[tauri::command]
+pub fn set_storage_policy(policy: crate::types::StoragePolicy) -> Result<(), String> {
+    crate::storage::save_policy(&policy)
 }
 
 // Annotations
@@ -500,6 +2338,92 @@ pub fn get_clip_annotations(
     Ok(state.annotations.get(&clip_id).cloned().unwrap_or_default())
 }
 
+/// Id of the topmost annotation on screen at `at_ms` under the normalized
+/// point (x, y)
+#[tauri::command]
+pub fn hit_test_annotation(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+    x: f64,
+    y: f64,
+    at_ms: u64,
+) -> Result<Option<String>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    let annotations = state.annotations.get(&clip_id).map(Vec::as_slice).unwrap_or_default();
+    Ok(crate::annotations::hit_test(annotations, x, y, at_ms).map(|a| a.id.clone()))
+}
+
+#[tauri::command]
+pub fn reorder_annotations(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+    annotation_ids: Vec<String>,
+    change: ZOrder,
+) -> Result<Vec<Annotation>, String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let annotations = state.annotations.entry(clip_id).or_default();
+    crate::annotations::reorder(annotations, &annotation_ids, change);
+    Ok(annotations.clone())
+}
+
+#[tauri::command]
+pub fn group_annotations(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+    annotation_ids: Vec<String>,
+) -> Result<Vec<Annotation>, String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let annotations = state.annotations.entry(clip_id).or_default();
+    crate::annotations::group(annotations, &annotation_ids).ok_or_else(|| crate::i18n::t("group_needs_two_annotations"))?;
+    Ok(annotations.clone())
+}
+
+#[tauri::command]
+pub fn ungroup_annotations(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+    group: String,
+) -> Result<Vec<Annotation>, String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let annotations = state.annotations.entry(clip_id).or_default();
+    crate::annotations::ungroup(annotations, &group);
+    Ok(annotations.clone())
+}
+
+/// Scale the annotations, and the rest of their groups, around the center
+/// of their bounds, then move them. Offsets are normalized.
+#[tauri::command]
+pub fn transform_annotations(
+    state: State<'_, Mutex<AppState>>,
+    clip_id: String,
+    annotation_ids: Vec<String>,
+    dx: f64,
+    dy: f64,
+    scale: f64,
+) -> Result<Vec<Annotation>, String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let annotations = state.annotations.entry(clip_id).or_default();
+    crate::annotations::transform(annotations, &annotation_ids, dx, dy, scale);
+    Ok(annotations.clone())
+}
+
+/// Annotations over the whole timeline, timed like the subtitles
+#[tauri::command]
+pub fn set_global_annotations(
+    state: State<'_, Mutex<AppState>>,
+    annotations: Vec<Annotation>,
+) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.global_annotations = annotations;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_global_annotations(state: State<'_, Mutex<AppState>>) -> Result<Vec<Annotation>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.global_annotations.clone())
+}
+
 // Subtitles
 #[tauri::command]
 pub fn set_subtitles(
@@ -517,6 +2441,122 @@ pub fn get_subtitles(state: State<'_, Mutex<AppState>>) -> Result<Vec<Subtitle>,
     Ok(state.subtitles.clone())
 }
 
+/// Overlaps, lines too short to read and lines past the end of the timeline
+#[tauri::command]
+pub fn check_subtitles(state: State<'_, Mutex<AppState>>) -> Result<Vec<crate::types::SubtitleIssue>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    let timeline_ms = crate::export::range::timeline_length_ms(&state.clips, &state.transitions);
+    Ok(crate::subtitles::check(&state.subtitles, timeline_ms))
+}
+
+/// Fix the subtitles' timing where there is room, and list what is left
+#[tauri::command]
+pub fn fit_subtitles(state: State<'_, Mutex<AppState>>) -> Result<crate::types::SubtitleFit, String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let timeline_ms = crate::export::range::timeline_length_ms(&state.clips, &state.transitions);
+    let changed = crate::subtitles::fit(&mut state.subtitles, timeline_ms);
+    Ok(crate::types::SubtitleFit {
+        subtitles: state.subtitles.clone(),
+        changed,
+        issues: crate::subtitles::check(&state.subtitles, timeline_ms),
+    })
+}
+
+/// Reading time of each of `subtitles`, edited or saved, flagging those
+/// shown too briefly for their text
+#[tauri::command]
+pub fn suggest_subtitle_durations(subtitles: Vec<Subtitle>) -> Vec<crate::types::SubtitleReading> {
+    crate::subtitles::readings(&subtitles)
+}
+
+/// Move every subtitle `delta_ms` later (earlier when negative), in every
+/// language
+#[tauri::command]
+pub fn shift_subtitles(state: State<'_, Mutex<AppState>>, delta_ms: i64) -> Result<Vec<Subtitle>, String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    crate::subtitles::shift(&mut state.subtitles, delta_ms);
+    for track in &mut state.subtitle_tracks {
+        crate::subtitles::shift(&mut track.subtitles, delta_ms);
+    }
+    Ok(state.subtitles.clone())
+}
+
+// Subtitle languages
+#[tauri::command]
+pub fn get_subtitle_languages(state: State<'_, Mutex<AppState>>) -> Result<Vec<crate::types::SubtitleLanguage>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(crate::subtitles::languages(&state.subtitles, &state.subtitle_language, &state.subtitle_tracks))
+}
+
+/// Add a language starting from a copy of the active lines
+#[tauri::command]
+pub fn add_subtitle_language(state: State<'_, Mutex<AppState>>, language: String) -> Result<Vec<crate::types::SubtitleLanguage>, String> {
+    let mut guard = state.lock().map_err(|e| e.to_string())?;
+    let s = &mut *guard;
+    crate::subtitles::add_language(&s.subtitles, &s.subtitle_language, &mut s.subtitle_tracks, &language)?;
+    Ok(crate::subtitles::languages(&s.subtitles, &s.subtitle_language, &s.subtitle_tracks))
+}
+
+/// Edit and burn in `language`, returns its subtitles
+#[tauri::command]
+pub fn set_active_subtitle_language(state: State<'_, Mutex<AppState>>, language: String) -> Result<Vec<Subtitle>, String> {
+    let mut guard = state.lock().map_err(|e| e.to_string())?;
+    let s = &mut *guard;
+    crate::subtitles::activate(&mut s.subtitles, &mut s.subtitle_language, &mut s.subtitle_tracks, &language)?;
+    Ok(s.subtitles.clone())
+}
+
+#[tauri::command]
+pub fn remove_subtitle_language(state: State<'_, Mutex<AppState>>, language: String) -> Result<Vec<crate::types::SubtitleLanguage>, String> {
+    let mut guard = state.lock().map_err(|e| e.to_string())?;
+    let s = &mut *guard;
+    crate::subtitles::remove_language(&mut s.subtitles, &mut s.subtitle_language, &mut s.subtitle_tracks, &language)?;
+    Ok(crate::subtitles::languages(&s.subtitles, &s.subtitle_language, &s.subtitle_tracks))
+}
+
+/// Burn the active language into exports, or add every language as a track
+#[tauri::command]
+pub fn set_subtitle_export(state: State<'_, Mutex<AppState>>, mode: crate::types::SubtitleExport) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.subtitle_export = mode;
+    Ok(())
+}
+
+// Voiceovers
+/// Start recording the mic over the preview playing from `start_ms`
+#[tauri::command]
+pub fn start_voiceover(state: State<'_, Mutex<AppState>>, start_ms: u64) -> Result<(), String> {
+    let mut s = state.lock().map_err(|e| e.to_string())?;
+    crate::recording::voiceover::start(&mut s, start_ms)
+}
+
+/// Stop the narration being recorded and add it to the timeline
+#[tauri::command]
+pub fn stop_voiceover(state: State<'_, Mutex<AppState>>) -> Result<crate::types::Voiceover, String> {
+    let capture = state
+        .lock()
+        .map_err(|e| e.to_string())?
+        .voiceover_capture
+        .take()
+        .ok_or_else(|| crate::i18n::t("voiceover_not_recording"))?;
+    let voiceover = crate::recording::voiceover::finish(capture).ok_or_else(|| crate::i18n::t("voiceover_empty"))?;
+    let mut s = state.lock().map_err(|e| e.to_string())?;
+    s.voiceovers.push(voiceover.clone());
+    Ok(voiceover)
+}
+
+#[tauri::command]
+pub fn get_voiceovers(state: State<'_, Mutex<AppState>>) -> Result<Vec<crate::types::Voiceover>, String> {
+    let s = state.lock().map_err(|e| e.to_string())?;
+    Ok(s.voiceovers.clone())
+}
+
+#[tauri::command]
+pub fn delete_voiceover(state: State<'_, Mutex<AppState>>, voiceover_id: String) -> Result<(), String> {
+    let mut s = state.lock().map_err(|e| e.to_string())?;
+    crate::recording::voiceover::delete(&mut s, &voiceover_id)
+}
+
 // Keystroke toggle
 #[tauri::command]
 pub fn toggle_keystroke_display(state: State<'_, Mutex<AppState>>) -> Result<bool, String> {
@@ -531,6 +2571,34 @@ pub fn get_keystroke_enabled(state: State<'_, Mutex<AppState>>) -> Result<bool,
     Ok(state.keystroke_enabled)
 }
 
+/// Show the keystroke labels on screen while recording, over the bottom of
+/// the recorded area
+#[tauri::command]
+pub fn set_keystroke_preview(state: State<'_, Mutex<AppState>>, enabled: bool) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.keystroke_preview = enabled;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_keystroke_font(state: State<'_, Mutex<AppState>>, font_file: Option<String>) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.keystroke_font = font_file.filter(|f| !f.is_empty());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_keystroke_font(state: State<'_, Mutex<AppState>>) -> Result<Option<String>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.keystroke_font.clone())
+}
+
+// Fonts
+#[tauri::command]
+pub fn list_fonts() -> Vec<crate::types::FontFile> {
+    crate::export::fonts::list_installed()
+}
+
 // Cursor zoom toggle
 #[tauri::command]
 pub fn toggle_cursor_zoom(state: State<'_, Mutex<AppState>>) -> Result<bool, String> {
@@ -545,13 +2613,210 @@ pub fn get_cursor_zoom_enabled(state: State<'_, Mutex<AppState>>) -> Result<bool
     Ok(state.cursor_zoom_enabled)
 }
 
+// Gamepad overlay toggle
+#[tauri::command]
+pub fn toggle_gamepad(state: State<'_, Mutex<AppState>>) -> Result<bool, String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.gamepad_enabled = !state.gamepad_enabled;
+    Ok(state.gamepad_enabled)
+}
+
+#[tauri::command]
+pub fn get_gamepad_enabled(state: State<'_, Mutex<AppState>>) -> Result<bool, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.gamepad_enabled)
+}
+
+#[tauri::command]
+pub fn set_cursor_sidecar(state: State<'_, Mutex<AppState>>, enabled: bool) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.cursor_sidecar = enabled;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_post_export_settings(state: State<'_, Mutex<AppState>>, settings: PostExportSettings) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.post_export = settings;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_post_export_settings(state: State<'_, Mutex<AppState>>) -> Result<PostExportSettings, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.post_export.clone())
+}
+
+// Upload
+#[tauri::command]
+pub fn set_upload_targets(state: State<'_, Mutex<AppState>>, targets: Vec<UploadTarget>) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.upload_targets = targets;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_upload_targets(state: State<'_, Mutex<AppState>>) -> Result<Vec<UploadTarget>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.upload_targets.clone())
+}
+
+/// Upload an exported file to the named target and return its shareable URL
+#[tauri::command]
+pub async fn upload_export(
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+    path: String,
+    target: String,
+) -> Result<String, String> {
+    let (target, webhooks) = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        let target = s.upload_targets.iter().find(|t| t.name == target).cloned()
+            .ok_or_else(|| crate::i18n::tf("upload_target_not_found", &[&target]))?;
+        (target, s.webhooks.clone())
+    };
+    let file = std::path::PathBuf::from(&path);
+    let url = tokio::task::spawn_blocking(move || {
+        crate::upload::upload_file(&app, std::path::Path::new(&path), &target)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    crate::webhook::notify(&webhooks, crate::webhook::WebhookEvent::Upload { path: &file, url: &url });
+    Ok(url)
+}
+
+// Webhooks
+#[tauri::command]
+pub fn set_webhooks(state: State<'_, Mutex<AppState>>, webhooks: Vec<Webhook>) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.webhooks = webhooks;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_webhooks(state: State<'_, Mutex<AppState>>) -> Result<Vec<Webhook>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.webhooks.clone())
+}
+
+// Logs
+#[tauri::command]
+pub fn get_recent_logs(lines: Option<usize>) -> Result<Vec<String>, String> {
+    crate::logging::recent_lines(lines.unwrap_or(200).min(5000))
+}
+
+#[tauri::command]
+pub fn open_log_folder() -> Result<(), String> {
+    let dir = crate::logging::log_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    opener::open(&dir).map_err(|e| e.to_string())
+}
+
+// Updates
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<crate::types::UpdateInfo, String> {
+    let current = app.package_info().version.to_string();
+    tokio::task::spawn_blocking(move || crate::update::check(&current))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn open_download_page(url: String) -> Result<(), String> {
+    if !url.starts_with("https://") {
+        return Err(crate::i18n::t("url_invalid"));
+    }
+    opener::open(&url).map_err(|e| e.to_string())
+}
+
+// Usage metrics (opt-in)
+#[tauri::command]
+pub fn set_telemetry_settings(state: State<'_, Mutex<AppState>>, settings: crate::types::TelemetrySettings) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    if settings.enabled {
+        // Also catches up on events queued while offline
+        crate::telemetry::flush_in_background(&settings);
+    } else {
+        crate::telemetry::clear_queue();
+    }
+    state.telemetry = settings;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_telemetry_settings(state: State<'_, Mutex<AppState>>) -> Result<crate::types::TelemetrySettings, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.telemetry.clone())
+}
+
+// Locale of backend errors / notifications
+#[tauri::command]
+pub fn set_locale(lang: String) -> crate::types::Locale {
+    let locale = crate::i18n::parse_locale(&lang);
+    crate::i18n::set_locale(locale);
+    locale
+}
+
+#[tauri::command]
+pub fn get_locale() -> crate::types::Locale {
+    crate::i18n::locale()
+}
+
+// Diagnostics
+#[derive(serde::Serialize)]
+pub struct DiagnosticsResult {
+    pub report: crate::types::DiagnosticsReport,
+    /// Zip with the report and log files, when requested
+    pub archive_path: Option<String>,
+}
+
+#[tauri::command]
+pub async fn generate_diagnostics(
+    app: tauri::AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    zip: Option<bool>,
+) -> Result<DiagnosticsResult, String> {
+    let temp_dir = state.lock().map_err(|e| e.to_string())?.temp_dir.clone();
+    let version = app.package_info().version.to_string();
+    let report = crate::diagnostics::collect(version, &temp_dir).await;
+
+    let archive_path = if zip.unwrap_or(false) {
+        let report = report.clone();
+        let path = tokio::task::spawn_blocking(move || crate::diagnostics::write_bundle(&report))
+            .await
+            .map_err(|e| e.to_string())??;
+        Some(path.to_string_lossy().to_string())
+    } else {
+        None
+    };
+    Ok(DiagnosticsResult { report, archive_path })
+}
+
+// File shortcuts
+#[tauri::command]
+pub fn reveal_in_explorer(path: String) -> Result<(), String> {
+    let path = std::path::PathBuf::from(path);
+    if !path.exists() {
+        return Err(crate::i18n::tf("file_not_found", &[&path.display()]));
+    }
+    // Opens the parent folder with the file selected
+    opener::reveal(&path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn open_with_default_player(path: String) -> Result<(), String> {
+    let path = std::path::PathBuf::from(path);
+    if !path.exists() {
+        return Err(crate::i18n::tf("file_not_found", &[&path.display()]));
+    }
+    opener::open(&path).map_err(|e| e.to_string())
+}
+
 // Clipboard export
 #[tauri::command]
 pub fn copy_file_to_clipboard(path: String) -> Result<(), String> {
-    // Use arboard to copy file path to clipboard
-    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
-    clipboard.set_text(&path).map_err(|e| e.to_string())?;
-    Ok(())
+    crate::clipboard::copy_file(std::path::Path::new(&path))
 }
 
 // Project commands
@@ -569,6 +2834,12 @@ pub async fn save_project(
             s.annotations.clone(),
             s.subtitles.clone(),
             s.current_project_id.clone(),
+            s.clip_cursor_positions.clone(),
+            s.cursor_sidecar,
+            s.voiceovers.clone(),
+            s.global_annotations.clone(),
+            s.subtitle_language.clone(),
+            s.subtitle_tracks.clone(),
         )
     };
 
@@ -580,6 +2851,12 @@ pub async fn save_project(
         project_data.2,
         &project_data.3,
         &project_data.4,
+        &project_data.10,
+        &project_data.11,
+        &project_data.6,
+        project_data.7,
+        &project_data.8,
+        &project_data.9,
     )?;
 
     {
@@ -598,12 +2875,7 @@ pub fn load_project(
     let project = crate::project::load_project(&project_id)?;
 
     let mut s = state.lock().map_err(|e| e.to_string())?;
-    s.clips = project.clips;
-    s.transitions = project.transitions;
-    s.audio_source = project.settings.audio_source;
-    s.annotations = project.annotations;
-    s.subtitles = project.subtitles;
-    s.current_project_id = Some(project_id);
+    crate::project::apply_to_state(&mut s, project);
 
     Ok(())
 }
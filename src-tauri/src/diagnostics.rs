@@ -0,0 +1,176 @@
+use crate::types::{DiagnosticsReport, DiskSpace, EncoderAvailability};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// Encoders worth reporting: the software fallback plus the GPU ones
+const TRACKED_ENCODERS: &[&str] = &[
+    "libx264",
+    "libx264rgb",
+    "h264_nvenc",
+    "h264_qsv",
+    "h264_amf",
+    "hevc_nvenc",
+    "gif",
+];
+
+/// How many log lines are scanned for warnings and errors
+const ERROR_SCAN_LINES: usize = 2000;
+const MAX_RECENT_ERRORS: usize = 50;
+
+/// Collect everything support needs to triage a capture/export problem
+pub async fn collect(app_version: String, temp_dir: &Path) -> DiagnosticsReport {
+    let caps = crate::ffmpeg::capabilities::probe().await;
+    let ffprobe_version = tool_version(crate::ffprobe_command()).await;
+    let encoders = TRACKED_ENCODERS
+        .iter()
+        .map(|name| EncoderAvailability {
+            name: name.to_string(),
+            available: caps.has_encoder(name),
+        })
+        .collect();
+
+    let mut disk_paths = vec![temp_dir.to_path_buf()];
+    if let Some(videos) = dirs::video_dir() {
+        disk_paths.push(videos);
+    }
+    let disks = disk_paths.iter().filter_map(|p| disk_space(p)).collect();
+
+    DiagnosticsReport {
+        generated_at: chrono::Local::now().to_rfc3339(),
+        app_version,
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        ffmpeg_version: caps.version,
+        ffprobe_version,
+        gpus: list_gpus(),
+        encoders,
+        audio_devices: crate::capture::audio::list_audio_devices().unwrap_or_default(),
+        monitors: crate::region::selector::enumerate_monitor_areas().unwrap_or_default(),
+        disks,
+        recent_errors: crate::logging::recent_lines(ERROR_SCAN_LINES)
+            .map(|lines| filter_errors(&lines, MAX_RECENT_ERRORS))
+            .unwrap_or_default(),
+    }
+}
+
+/// Zip the report together with the log files, returns the archive path
+pub fn write_bundle(report: &DiagnosticsReport) -> Result<PathBuf, String> {
+    let dir = crate::logging::log_dir()
+        .parent()
+        .map(|p| p.join("diagnostics"))
+        .ok_or_else(|| crate::i18n::t("diagnostics_dir_missing"))?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+    let archive_path = dir.join(format!("clipflow-diagnostics_{}.zip", timestamp));
+
+    let file = std::fs::File::create(&archive_path).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+
+    let json = serde_json::to_vec_pretty(report).map_err(|e| e.to_string())?;
+    zip.start_file("diagnostics.json", options).map_err(|e| e.to_string())?;
+    zip.write_all(&json).map_err(|e| e.to_string())?;
+
+    if let Ok(entries) = std::fs::read_dir(crate::logging::log_dir()) {
+        for path in entries.flatten().map(|e| e.path()).filter(|p| p.is_file()) {
+            let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                continue;
+            };
+            // The current day's file may still be written to; a partial copy is fine
+            let Ok(data) = std::fs::read(&path) else { continue };
+            zip.start_file(format!("logs/{}", name), options).map_err(|e| e.to_string())?;
+            zip.write_all(&data).map_err(|e| e.to_string())?;
+        }
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+    tracing::info!("Diagnostics bundle written to {:?}", archive_path);
+    Ok(archive_path)
+}
+
+async fn tool_version(mut cmd: tokio::process::Command) -> Option<String> {
+    let output = cmd
+        .arg("-version")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .await
+        .ok()?;
+    crate::ffmpeg::capabilities::parse_version_line(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Keep the last `max` WARN/ERROR lines
+fn filter_errors(lines: &[String], max: usize) -> Vec<String> {
+    let matching: Vec<&String> = lines
+        .iter()
+        .filter(|l| l.contains(" ERROR ") || l.contains(" WARN "))
+        .collect();
+    let start = matching.len().saturating_sub(max);
+    matching[start..].iter().map(|l| l.to_string()).collect()
+}
+
+/// Names of the display adapters, without duplicates (one entry per output otherwise)
+fn list_gpus() -> Vec<String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Graphics::Gdi::{EnumDisplayDevicesW, DISPLAY_DEVICEW};
+
+    let mut gpus: Vec<String> = Vec::new();
+    let mut index = 0;
+    loop {
+        let mut device = DISPLAY_DEVICEW {
+            cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32,
+            ..Default::default()
+        };
+        if !unsafe { EnumDisplayDevicesW(PCWSTR::null(), index, &mut device, 0) }.as_bool() {
+            break;
+        }
+        let len = device.DeviceString.iter().position(|&c| c == 0).unwrap_or(device.DeviceString.len());
+        let name = String::from_utf16_lossy(&device.DeviceString[..len]);
+        if !name.is_empty() && !gpus.contains(&name) {
+            gpus.push(name);
+        }
+        index += 1;
+    }
+    gpus
+}
+
+pub(crate) fn disk_space(path: &Path) -> Option<DiskSpace> {
+    use windows::core::HSTRING;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let mut free: u64 = 0;
+    let mut total: u64 = 0;
+    unsafe {
+        GetDiskFreeSpaceExW(
+            &HSTRING::from(path.as_os_str()),
+            Some(&mut free),
+            Some(&mut total),
+            None,
+        )
+    }
+    .ok()?;
+    Some(DiskSpace {
+        path: path.to_path_buf(),
+        free_bytes: free,
+        total_bytes: total,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_errors_keeps_last() {
+        let lines: Vec<String> = vec![
+            "2026-01-01T10:00:00Z  INFO clipflow_lib: started".into(),
+            "2026-01-01T10:00:01Z  WARN clipflow_lib: first".into(),
+            "2026-01-01T10:00:02Z ERROR clipflow_lib: second".into(),
+            "2026-01-01T10:00:03Z ERROR clipflow_lib: third".into(),
+        ];
+        let errors = filter_errors(&lines, 2);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].ends_with("second"));
+        assert!(errors[1].ends_with("third"));
+    }
+}
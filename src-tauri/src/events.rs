@@ -0,0 +1,128 @@
+use crate::types::{Clip, DrawingTool, ExportFormat, WaveformTrack};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Recording and export lifecycle, sent to the frontend.
+///
+/// Each event is emitted under its `type` (e.g. `recording-paused`) with
+/// the whole object as payload, so `listen("recording-paused")` receives
+/// `{ "type": "recording-paused", "reason": "privacy" }`. The TypeScript side
+/// mirrors this in `AppEventMap` (src/lib/types.ts). Progress stays on the
+/// untyped `export-progress` / `preview-progress` percentages.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum AppEvent {
+    /// Capture began (after the countdown)
+    RecordingStarted,
+    /// `reason` is set when a watcher paused it: "session" (lock screen,
+    /// display off) or "privacy"
+    RecordingPaused { reason: Option<String> },
+    RecordingResumed,
+    /// The recording became the clip `clip_id`
+    RecordingStopped { clip_id: String },
+    /// Discarded without a clip
+    RecordingCancelled,
+    /// A start / pause / resume / stop the user didn't trigger from the
+    /// window failed (hotkey, rollover, watcher)
+    RecordingError { message: String },
+    /// The adaptive watcher switched the capture to `level` (0 = the chosen
+    /// settings) because of `cpu_percent` system usage
+    CaptureAdapted { level: u32, cpu_percent: u32 },
+    /// Rolling peaks of each audio track, about every 100 ms while recording
+    AudioWaveform { tracks: Vec<WaveformTrack> },
+    /// Seconds left before the capture starts, 0 when it does
+    CountdownTick { remaining: u32 },
+    /// Escape pressed during the countdown
+    CountdownAborted,
+    MicMuteChanged { muted: bool },
+    /// Pen or highlighter picked on the drawing overlay, `None` for click-through
+    DrawingTool { tool: Option<DrawingTool> },
+    /// Captures left by a crashed session wait to be restored or discarded
+    RecordingsRecovered { count: usize },
+    /// A clip was appended to the timeline by a recording or an import
+    ClipAdded { clip: Clip },
+    /// A capture finished converting to the timeline format in the
+    /// background; the clip now plays the converted file
+    ClipNormalized { clip_id: String },
+    /// A dropped file could not be imported
+    ImportError { message: String },
+    ProjectOpened { project_id: String },
+    /// `count` new videos in the watched folder wait for confirmation
+    WatchFolderPending { count: usize },
+    /// Rendering began, one entry per output file format
+    ExportStarted { formats: Vec<ExportFormat>, clip_count: usize },
+    ExportFinished { paths: Vec<String> },
+    ExportFailed { message: String },
+}
+
+impl AppEvent {
+    /// Event name, the same as the `type` of the payload
+    pub fn name(&self) -> &'static str {
+        match self {
+            AppEvent::RecordingStarted => "recording-started",
+            AppEvent::RecordingPaused { .. } => "recording-paused",
+            AppEvent::RecordingResumed => "recording-resumed",
+            AppEvent::RecordingStopped { .. } => "recording-stopped",
+            AppEvent::RecordingCancelled => "recording-cancelled",
+            AppEvent::RecordingError { .. } => "recording-error",
+            AppEvent::CaptureAdapted { .. } => "capture-adapted",
+            AppEvent::AudioWaveform { .. } => "audio-waveform",
+            AppEvent::CountdownTick { .. } => "countdown-tick",
+            AppEvent::CountdownAborted => "countdown-aborted",
+            AppEvent::MicMuteChanged { .. } => "mic-mute-changed",
+            AppEvent::DrawingTool { .. } => "drawing-tool",
+            AppEvent::RecordingsRecovered { .. } => "recordings-recovered",
+            AppEvent::ClipAdded { .. } => "clip-added",
+            AppEvent::ClipNormalized { .. } => "clip-normalized",
+            AppEvent::ImportError { .. } => "import-error",
+            AppEvent::ProjectOpened { .. } => "project-opened",
+            AppEvent::WatchFolderPending { .. } => "watch-folder-pending",
+            AppEvent::ExportStarted { .. } => "export-started",
+            AppEvent::ExportFinished { .. } => "export-finished",
+            AppEvent::ExportFailed { .. } => "export-failed",
+        }
+    }
+}
+
+pub fn emit(app: &AppHandle, event: AppEvent) {
+    if let Err(e) = app.emit(event.name(), &event) {
+        tracing::warn!("Failed to emit {}: {}", event.name(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_matches_the_payload_type() {
+        let events = [
+            AppEvent::RecordingStarted,
+            AppEvent::RecordingPaused { reason: Some("lock".into()) },
+            AppEvent::RecordingResumed,
+            AppEvent::RecordingStopped { clip_id: "a".into() },
+            AppEvent::RecordingCancelled,
+            AppEvent::RecordingError { message: "x".into() },
+            AppEvent::CaptureAdapted { level: 1, cpu_percent: 92 },
+            AppEvent::AudioWaveform { tracks: Vec::new() },
+            AppEvent::CountdownTick { remaining: 3 },
+            AppEvent::CountdownAborted,
+            AppEvent::MicMuteChanged { muted: true },
+            AppEvent::DrawingTool { tool: Some(DrawingTool::Pen) },
+            AppEvent::RecordingsRecovered { count: 1 },
+            AppEvent::ClipNormalized { clip_id: "a".into() },
+            AppEvent::ImportError { message: "x".into() },
+            AppEvent::ProjectOpened { project_id: "p".into() },
+            AppEvent::WatchFolderPending { count: 2 },
+            AppEvent::ExportStarted { formats: vec![ExportFormat::Mp4], clip_count: 2 },
+            AppEvent::ExportFinished { paths: vec!["out.mp4".into()] },
+            AppEvent::ExportFailed { message: "x".into() },
+        ];
+        for event in &events {
+            let payload = serde_json::to_value(event).unwrap();
+            assert_eq!(payload["type"], event.name());
+        }
+        let payload = serde_json::to_value(&events[1]).unwrap();
+        assert_eq!(payload, serde_json::json!({ "type": "recording-paused", "reason": "lock" }));
+    }
+}
@@ -0,0 +1,174 @@
+use super::encoder::translate_ffmpeg_error;
+use crate::types::{Clip, CursorPosition, GamepadSample, KeystrokeEvent};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// Loudness is measured over windows of this length
+const WINDOW_MS: u64 = 50;
+/// Plenty to tell speech from silence
+const SAMPLE_RATE: u64 = 8000;
+/// Sound is a window this far above the track's own noise floor...
+const ABOVE_FLOOR_DB: f32 = 12.0;
+/// ...but never quieter than this, for tracks whose floor is digital silence
+const MIN_SOUND_DB: f32 = -50.0;
+/// ...lasting this many windows, so a single click doesn't count
+const MIN_SOUND_WINDOWS: usize = 3;
+/// Kept before the first and after the last activity
+const PADDING_MS: u64 = 300;
+/// Cursor moves smaller than this (fraction of the region) are jitter
+const CURSOR_EPSILON: f64 = 0.002;
+
+/// Trim (start, end) keeping everything between the first and the last sign
+/// of activity: sound above the noise floor, key presses, cursor moves,
+/// controller input. End is 0 when nothing happens to trim at the end.
+pub async fn detect(
+    clip: &Clip,
+    keystrokes: &[KeystrokeEvent],
+    cursor: &[CursorPosition],
+    gamepad: &[GamepadSample],
+) -> Result<(u64, u64)> {
+    let mut activity = input_activity(keystrokes, cursor, gamepad);
+    for path in audio_sources(clip) {
+        let samples = decode(&path).await?;
+        activity.extend(sound_spans(&window_levels(&samples)));
+    }
+    trim_bounds(&activity, clip.media.duration_ms).with_context(|| crate::i18n::t("autotrim_no_activity"))
+}
+
+/// Separate tracks when recorded that way, else the clip's own audio
+pub(super) fn audio_sources(clip: &Clip) -> Vec<PathBuf> {
+    if !clip.media.audio_paths.is_empty() {
+        clip.media.audio_paths.iter().map(PathBuf::from).collect()
+    } else if clip.media.has_audio {
+        vec![clip.media.path.clone()]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Mono 16-bit samples at `SAMPLE_RATE`
+pub(super) async fn decode(path: &Path) -> Result<Vec<i16>> {
+    let output = crate::ffmpeg_command()
+        .args([
+            "-i", &path.to_string_lossy(),
+            "-vn", "-ac", "1", "-ar", &SAMPLE_RATE.to_string(),
+            "-f", "s16le", "-",
+        ])
+        .stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped())
+        .output().await.context("Failed to run FFmpeg")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(crate::i18n::tf("audio_analysis_failed", &[&translate_ffmpeg_error(&stderr)]));
+    }
+    Ok(output.stdout.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect())
+}
+
+/// RMS level in dBFS of each window
+pub(super) fn window_levels(samples: &[i16]) -> Vec<f32> {
+    let per_window = (SAMPLE_RATE * WINDOW_MS / 1000) as usize;
+    samples
+        .chunks(per_window)
+        .map(|window| {
+            let sum: f64 = window.iter().map(|&s| (s as f64 / i16::MAX as f64).powi(2)).sum();
+            let rms = (sum / window.len() as f64).sqrt();
+            (20.0 * rms.max(1e-6).log10()) as f32
+        })
+        .collect()
+}
+
+/// Background level of the track: its 10th percentile, as takes are mostly
+/// not silent
+fn noise_floor(levels: &[f32]) -> f32 {
+    let mut sorted = levels.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    sorted.get(sorted.len() / 10).copied().unwrap_or(f32::MIN)
+}
+
+/// Source time spans (ms) of sustained sound
+pub(super) fn sound_spans(levels: &[f32]) -> Vec<(u64, u64)> {
+    let threshold = (noise_floor(levels) + ABOVE_FLOOR_DB).max(MIN_SOUND_DB);
+    let mut spans = Vec::new();
+    let mut run_start = None;
+    // A trailing quiet window closes the last run
+    for (i, level) in levels.iter().chain(std::iter::once(&f32::MIN)).enumerate() {
+        match (run_start, *level > threshold) {
+            (None, true) => run_start = Some(i),
+            (Some(start), false) => {
+                if i - start >= MIN_SOUND_WINDOWS {
+                    spans.push((start as u64 * WINDOW_MS, i as u64 * WINDOW_MS));
+                }
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    spans
+}
+
+/// Key presses, cursor moves and controller changes, as instant spans
+fn input_activity(
+    keystrokes: &[KeystrokeEvent],
+    cursor: &[CursorPosition],
+    gamepad: &[GamepadSample],
+) -> Vec<(u64, u64)> {
+    let keys = keystrokes.iter().map(|k| k.timestamp_ms);
+    let moves = cursor
+        .windows(2)
+        .filter(|w| (w[1].x - w[0].x).abs().max((w[1].y - w[0].y).abs()) > CURSOR_EPSILON)
+        .map(|w| w[1].timestamp_ms);
+    // Samples are only recorded on change, the first one is the resting state
+    let pads = gamepad.iter().skip(1).map(|g| g.timestamp_ms);
+    keys.chain(moves).chain(pads).map(|t| (t, t)).collect()
+}
+
+fn trim_bounds(activity: &[(u64, u64)], duration_ms: u64) -> Option<(u64, u64)> {
+    let first = activity.iter().map(|a| a.0).min()?;
+    let last = activity.iter().map(|a| a.1).max()?;
+    let start = first.saturating_sub(PADDING_MS);
+    let end = (last + PADDING_MS).min(duration_ms);
+    if end <= start {
+        return None;
+    }
+    Some((start, if end == duration_ms { 0 } else { end }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sound_spans_ignore_clicks_and_noise() {
+        // Noise at -60 dB, a one-window click, then 300 ms of speech
+        let mut levels = vec![-60.0; 40];
+        levels[5] = -10.0;
+        for level in &mut levels[20..26] {
+            *level = -20.0;
+        }
+        assert_eq!(sound_spans(&levels), vec![(1000, 1300)]);
+    }
+
+    #[test]
+    fn test_sound_spans_run_to_the_end() {
+        let mut levels = vec![-70.0; 20];
+        for level in &mut levels[16..] {
+            *level = -30.0;
+        }
+        assert_eq!(sound_spans(&levels), vec![(800, 1000)]);
+    }
+
+    #[test]
+    fn test_trim_bounds_pad_the_activity() {
+        let cursor = vec![
+            CursorPosition { timestamp_ms: 0, x: 0.5, y: 0.5 },
+            CursorPosition { timestamp_ms: 1000, x: 0.5, y: 0.5 },
+            CursorPosition { timestamp_ms: 2000, x: 0.6, y: 0.5 },
+        ];
+        let keys = vec![KeystrokeEvent { timestamp_ms: 7000, key_name: "A".into() }];
+        let activity = input_activity(&keys, &cursor, &[]);
+        assert_eq!(trim_bounds(&activity, 10_000), Some((1700, 7300)));
+        // Activity up to the end leaves the end untrimmed
+        assert_eq!(trim_bounds(&activity, 7200), Some((1700, 0)));
+        assert_eq!(trim_bounds(&[], 10_000), None);
+    }
+}
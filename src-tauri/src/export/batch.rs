@@ -0,0 +1,349 @@
+use super::encoder::{
+    codec_args, encode_gif, export_gif, export_mp4, extract_time, gif_palette_cuts, output_scale_filter, output_stage_filters,
+    probe_duration, resolve_source_fps, target_video_kbps, translate_ffmpeg_error, webm_codec_args,
+    DEFAULT_FPS,
+};
+use super::snapshot::ExportContext;
+use crate::types::{BatchExportProgress, Clip, ExportFormat, ExportQuality, OutputSettings, Transition};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tauri::{AppHandle, Emitter, Listener};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// Share of the overall progress taken by the timeline render
+const RENDER_SHARE: f64 = 0.5;
+
+/// One output once its preset (if any) has been resolved
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub format: ExportFormat,
+    pub quality: ExportQuality,
+    pub target_size_mb: Option<u32>,
+    pub output: OutputSettings,
+    pub path: PathBuf,
+}
+
+fn emit_progress(app: &AppHandle, percent: f64, target_index: Option<usize>, target_count: usize) {
+    let _ = app.emit("batch-export-progress", BatchExportProgress {
+        percent: percent.clamp(0.0, 100.0) as u32,
+        target_index,
+        target_count,
+    });
+}
+
+/// Render the timeline once into a high-quality mezzanine, then transcode it
+/// to every target. Overlays, transitions and audio mixing run only once.
+pub async fn export_multi(
+    clips: &[Clip],
+    transitions: &[Transition],
+    app: &AppHandle,
+    watermark: bool,
+    ctx: &ExportContext<'_>,
+    targets: &[Target],
+    temp_dir: &Path,
+) -> Result<()> {
+    if targets.is_empty() {
+        anyhow::bail!(crate::i18n::t("no_export_targets"));
+    }
+    let count = targets.len();
+    let mut targets = targets.to_vec();
+    for target in &mut targets {
+        target.output = resolve_source_fps(clips, &target.output).await;
+    }
+    std::fs::create_dir_all(temp_dir).context("Failed to create temp dir")?;
+    let mezzanine = temp_dir.join(format!("mezzanine_{}.mp4", uuid::Uuid::new_v4()));
+
+    // Forward the render's own progress as the first part of the batch
+    let forward = app.clone();
+    let listener = app.listen("export-progress", move |event| {
+        if let Ok(p) = serde_json::from_str::<u32>(event.payload()) {
+            emit_progress(&forward, p as f64 * RENDER_SHARE, None, count);
+        }
+    });
+    let rendered = export_mp4(
+        clips, transitions, &mezzanine, app, watermark, &ExportQuality::High, ctx, None, &mezzanine_output(&targets),
+    ).await;
+    app.unlisten(listener);
+    if let Err(e) = rendered {
+        let _ = std::fs::remove_file(&mezzanine);
+        return Err(e);
+    }
+
+    let spans = super::range::clip_spans(clips, transitions);
+    let result = transcode_all(app, &mezzanine, &targets, &spans).await;
+    let _ = std::fs::remove_file(&mezzanine);
+    result?;
+
+    emit_progress(app, 100.0, None, count);
+    Ok(())
+}
+
+/// Render every clip to its own file, each with its own overlays and the
+/// subtitles and timeline annotations shown during it. Transitions between
+/// clips are left out.
+pub async fn export_each(
+    clips: &[Clip],
+    transitions: &[Transition],
+    app: &AppHandle,
+    watermark: bool,
+    format: ExportFormat,
+    quality: &ExportQuality,
+    ctx: &ExportContext<'_>,
+    output: &OutputSettings,
+    paths: &[PathBuf],
+) -> Result<()> {
+    if paths.len() != clips.len() {
+        anyhow::bail!(crate::i18n::t("output_per_clip"));
+    }
+    let count = clips.len();
+    let slice = 100.0 / count as f64;
+    let spans = super::range::clip_spans(clips, transitions);
+
+    for (i, (clip, path)) in clips.iter().zip(paths).enumerate() {
+        let base = slice * i as f64;
+        emit_progress(app, base, Some(i), count);
+        tracing::info!("Exporting clip {}/{} to {:?}", i + 1, count, path);
+
+        let mut clip_subtitles = ctx.subtitles.to_vec();
+        super::range::shift_subtitles(&mut clip_subtitles, spans[i].0, Some(spans[i].1));
+        let mut clip_globals = ctx.global_annotations.to_vec();
+        super::range::shift_global_annotations(&mut clip_globals, spans[i].0, Some(spans[i].1));
+
+        let forward = app.clone();
+        let listener = app.listen("export-progress", move |event| {
+            if let Ok(p) = serde_json::from_str::<u32>(event.payload()) {
+                emit_progress(&forward, base + slice * p as f64 / 100.0, Some(i), count);
+            }
+        });
+        let single = std::slice::from_ref(clip);
+        // Each file gets the watermark that suits its own clip
+        let mut output = output.clone();
+        if watermark {
+            output.watermark_tone = super::watermark::pick_tone(single, ctx.clip_annotations, &output).await;
+        }
+        let output = &output;
+        let clip_ctx = ExportContext { subtitles: &clip_subtitles, global_annotations: &clip_globals, ..*ctx };
+        let result = match format {
+            ExportFormat::Mp4 => export_mp4(single, &[], path, app, watermark, quality, &clip_ctx, None, output).await,
+            ExportFormat::Gif => export_gif(single, &[], path, app, watermark, quality, &clip_ctx, output).await,
+            ExportFormat::Webm => Err(anyhow::anyhow!(crate::i18n::t("per_clip_format"))),
+        };
+        app.unlisten(listener);
+        result.with_context(|| format!("Clip {}", i + 1))?;
+    }
+
+    emit_progress(app, 100.0, None, count);
+    Ok(())
+}
+
+/// Settings of the timeline render every target is transcoded from. CRF 18
+/// (High) keeps the second generation visually lossless. The heavy
+/// composition pass uses the same encoder as the targets, at the highest
+/// frame rate any of them needs, with keystrokes and subtitles clear of the
+/// UI of every target's platform.
+pub(crate) fn mezzanine_output(targets: &[Target]) -> OutputSettings {
+    OutputSettings {
+        encoder: targets[0].output.encoder,
+        tonemap_hdr: targets[0].output.tonemap_hdr,
+        keep_filter_script: targets[0].output.keep_filter_script,
+        watermark_tone: targets[0].output.watermark_tone,
+        fps: targets.iter().map(|t| t.output.fps.unwrap_or(DEFAULT_FPS)).max(),
+        safe_area: super::safe_area::union(targets.iter().map(|t| t.output.safe_area)),
+        ..Default::default()
+    }
+}
+
+async fn transcode_all(app: &AppHandle, mezzanine: &PathBuf, targets: &[Target], spans: &[(u64, u64)]) -> Result<()> {
+    let duration = probe_duration(mezzanine).await?;
+    let count = targets.len();
+    let slice = (1.0 - RENDER_SHARE) * 100.0 / count as f64;
+
+    for (i, target) in targets.iter().enumerate() {
+        let base = RENDER_SHARE * 100.0 + slice * i as f64;
+        emit_progress(app, base, Some(i), count);
+        tracing::info!("Transcoding {}/{} to {:?}", i + 1, count, target.path);
+
+        match target.format {
+            ExportFormat::Gif => {
+                let cuts = gif_palette_cuts(spans, target.output.gif_palette.per_clip);
+                encode_gif(mezzanine, &target.path, &target.quality, &target.output, &cuts, || {
+                    emit_progress(app, base + slice / 2.0, Some(i), count);
+                }).await?;
+            }
+            ExportFormat::Mp4 | ExportFormat::Webm => {
+                let args = transcode_args(mezzanine, target, duration);
+                run_with_progress(&args, duration, |fraction| {
+                    emit_progress(app, base + slice * fraction, Some(i), count);
+                }).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Video transcode of the mezzanine for MP4 and WebM targets
+pub(crate) fn transcode_args(mezzanine: &Path, target: &Target, duration: f64) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "-i".into(), mezzanine.to_string_lossy().to_string(),
+        "-map".into(), "0:v".into(),
+    ];
+    if target.output.include_audio {
+        // The render may have no audio track at all
+        args.extend(["-map".to_string(), "0:a?".to_string()]);
+    }
+    // Hardware encoders scale and upload on the GPU, VP9 stays on the CPU
+    let filters: Vec<String> = match target.format {
+        ExportFormat::Webm => output_scale_filter(&target.output).into_iter().collect(),
+        _ => {
+            let (before, after) = output_stage_filters(&target.output, false);
+            before.into_iter().chain(after).collect()
+        }
+    };
+    if !filters.is_empty() {
+        args.extend(["-vf".to_string(), filters.join(",")]);
+    }
+
+    let fps = target.output.fps.unwrap_or(DEFAULT_FPS);
+    let target_kbps = target.target_size_mb
+        .filter(|mb| *mb > 0)
+        .map(|mb| target_video_kbps(mb, duration, target.output.include_audio));
+    match target.format {
+        ExportFormat::Webm => {
+            args.extend(webm_codec_args(&target.quality, target_kbps, fps));
+            if target.output.include_audio {
+                args.extend(["-c:a", "libopus", "-b:a", "128k"].iter().map(|s| s.to_string()));
+            }
+        }
+        _ => {
+            args.extend(codec_args(&target.quality, target_kbps, &target.output));
+            if target.output.include_audio {
+                // Already AAC at the export bitrate
+                args.extend(["-c:a".to_string(), "copy".to_string()]);
+            }
+            args.extend(["-movflags".to_string(), "+faststart".to_string()]);
+        }
+    }
+    if !target.output.include_audio {
+        args.push("-an".into());
+    }
+    args.push("-y".into());
+    args.push(target.path.to_string_lossy().to_string());
+    args
+}
+
+async fn run_with_progress(args: &[String], duration: f64, on_progress: impl Fn(f64)) -> Result<()> {
+    let mut child = crate::ffmpeg_command()
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start FFmpeg transcode")?;
+
+    let mut stderr_log = String::new();
+    if let Some(stderr) = child.stderr.take() {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let Some(t) = extract_time(&line) {
+                if duration > 0.0 {
+                    on_progress((t / duration).min(1.0));
+                }
+            }
+            stderr_log.push_str(&line);
+            stderr_log.push('\n');
+        }
+    }
+
+    let status = child.wait().await.context("FFmpeg transcode failed")?;
+    if !status.success() {
+        tracing::error!("FFmpeg stderr:\n{}", stderr_log);
+        anyhow::bail!("{}", translate_ffmpeg_error(&stderr_log));
+    }
+    Ok(())
+}
+
+/// `recording_<ts>.<ext>`, numbered when several targets share an extension
+pub fn output_paths(dir: &Path, stem: &str, formats: &[ExportFormat]) -> Vec<PathBuf> {
+    formats
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let ext = f.extension();
+            let shared = formats.iter().filter(|o| o.extension() == ext).count() > 1;
+            if shared {
+                dir.join(format!("{}_{}.{}", stem, i + 1, ext))
+            } else {
+                dir.join(format!("{}.{}", stem, ext))
+            }
+        })
+        .collect()
+}
+
+/// `recording_<ts>_clip<NN>.<ext>`, one per clip in strip order
+pub fn clip_output_paths(dir: &Path, stem: &str, count: usize, format: ExportFormat) -> Vec<PathBuf> {
+    (1..=count)
+        .map(|i| dir.join(format!("{}_clip{:02}.{}", stem, i, format.extension())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ExportEncoder;
+
+    fn target(format: ExportFormat, output: OutputSettings) -> Target {
+        Target {
+            format,
+            quality: ExportQuality::Medium,
+            target_size_mb: None,
+            output,
+            path: PathBuf::from("out"),
+        }
+    }
+
+    #[test]
+    fn test_output_paths_number_shared_extensions() {
+        let paths = output_paths(Path::new("d"), "rec", &[ExportFormat::Mp4, ExportFormat::Gif, ExportFormat::Mp4]);
+        assert_eq!(paths[0], Path::new("d").join("rec_1.mp4"));
+        assert_eq!(paths[1], Path::new("d").join("rec.gif"));
+        assert_eq!(paths[2], Path::new("d").join("rec_3.mp4"));
+    }
+
+    #[test]
+    fn test_clip_output_paths_are_numbered_in_order() {
+        let paths = clip_output_paths(Path::new("d"), "rec", 2, ExportFormat::Gif);
+        assert_eq!(paths, vec![PathBuf::from("d/rec_clip01.gif"), PathBuf::from("d/rec_clip02.gif")]);
+    }
+
+    #[test]
+    fn test_mp4_transcode_copies_audio() {
+        let args = transcode_args(Path::new("m.mp4"), &target(ExportFormat::Mp4, OutputSettings::default()), 10.0);
+        assert!(args.windows(2).any(|w| w[0] == "-c:a" && w[1] == "copy"));
+        assert!(args.contains(&"libx264".to_string()));
+    }
+
+    #[test]
+    fn test_webm_transcode_uses_vp9_and_opus() {
+        let args = transcode_args(Path::new("m.mp4"), &target(ExportFormat::Webm, OutputSettings::default()), 10.0);
+        assert!(args.contains(&"libvpx-vp9".to_string()));
+        assert!(args.contains(&"libopus".to_string()));
+    }
+
+    #[test]
+    fn test_transcode_without_audio() {
+        let output = OutputSettings { include_audio: false, width: Some(1280), ..Default::default() };
+        let args = transcode_args(Path::new("m.mp4"), &target(ExportFormat::Mp4, output), 10.0);
+        assert!(args.contains(&"-an".to_string()));
+        assert!(!args.contains(&"0:a?".to_string()));
+        assert!(args.windows(2).any(|w| w[0] == "-vf" && w[1] == "scale=1280:-2"));
+    }
+
+    #[test]
+    fn test_nvenc_transcode_scales_on_gpu() {
+        let output = OutputSettings { width: Some(1280), encoder: ExportEncoder::Nvenc, ..Default::default() };
+        let args = transcode_args(Path::new("m.mp4"), &target(ExportFormat::Mp4, output), 10.0);
+        assert!(args.contains(&"h264_nvenc".to_string()));
+        assert!(args.windows(2).any(|w| w[0] == "-vf" && w[1].contains("hwupload_cuda") && w[1].contains("scale_cuda")));
+    }
+}
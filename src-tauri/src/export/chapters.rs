@@ -0,0 +1,99 @@
+use super::range::clip_spans;
+use crate::types::{Clip, Transition};
+use std::path::{Path, PathBuf};
+
+/// Shortest chapter YouTube accepts
+const MIN_CHAPTER_MS: u64 = 10_000;
+/// Fewest chapters YouTube shows
+const MIN_CHAPTERS: usize = 3;
+
+/// `75_000` -> "01:15", "1:02:03" past an hour
+fn timestamp(ms: u64) -> String {
+    let s = ms / 1000;
+    if s >= 3600 {
+        format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+    } else {
+        format!("{:02}:{:02}", s / 60, s % 60)
+    }
+}
+
+/// Start in output ms and title of each chapter, one per clip. Markers are
+/// left out: their labels are generated, and a privacy pause names the app
+/// that was hidden. Chapters under 10 s are merged into the previous one, as
+/// YouTube drops the whole list otherwise.
+fn chapters(clips: &[Clip], transitions: &[Transition]) -> Vec<(u64, String)> {
+    let spans = clip_spans(clips, transitions);
+    let duration_ms = spans.last().map(|&(_, end)| end).unwrap_or(0);
+    let mut kept: Vec<(u64, String)> = Vec::new();
+    for (i, &(at, _)) in spans.iter().enumerate() {
+        let title = format!("Clip {}", i + 1);
+        match kept.last() {
+            // YouTube wants the first chapter at 00:00
+            None => kept.push((0, title)),
+            Some(&(previous, _)) if at >= previous + MIN_CHAPTER_MS && at + MIN_CHAPTER_MS <= duration_ms => {
+                kept.push((at, title))
+            }
+            Some(_) => {}
+        }
+    }
+    kept
+}
+
+/// Chapters of the timeline as YouTube reads them in a description, one
+/// `00:00 Title` line each. Empty below 3 chapters, which YouTube ignores.
+pub fn generate_chapter_text(clips: &[Clip], transitions: &[Transition]) -> String {
+    let chapters = chapters(clips, transitions);
+    if chapters.len() < MIN_CHAPTERS {
+        return String::new();
+    }
+    chapters.iter().map(|(at, title)| format!("{} {}\n", timestamp(*at), title)).collect()
+}
+
+/// `recording.mp4` -> `recording.mp4.chapters.txt`, as for the sidecar
+pub fn chapters_path(output: &Path) -> PathBuf {
+    let name = output.file_name().unwrap_or_default().to_string_lossy();
+    output.with_file_name(format!("{}.chapters.txt", name))
+}
+
+/// Write the chapter text of `output` next to it. Nothing is written when
+/// the timeline has too few chapters.
+pub fn write(output: &Path, clips: &[Clip], transitions: &[Transition]) -> Result<Option<PathBuf>, String> {
+    let text = generate_chapter_text(clips, transitions);
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let path = chapters_path(output);
+    std::fs::write(&path, text).map_err(|e| e.to_string())?;
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TransitionType, test_clip};
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(0), "00:00");
+        assert_eq!(timestamp(75_999), "01:15");
+        assert_eq!(timestamp(3_723_000), "1:02:03");
+    }
+
+    #[test]
+    fn test_chapter_text() {
+        let mut clips = vec![test_clip("a", 60_000), test_clip("b", 5_000), test_clip("c", 120_000), test_clip("d", 60_000)];
+        clips[2].media.trim_start_ms = 10_000;
+        let cut = Transition { transition_type: TransitionType::Cut, duration_s: 0.0 };
+        // Clip 2 lasts under 10 s, clip 3 starts right after it
+        assert_eq!(
+            generate_chapter_text(&clips, &[cut.clone(), cut.clone(), cut]),
+            "00:00 Clip 1\n01:00 Clip 2\n02:55 Clip 4\n"
+        );
+    }
+
+    #[test]
+    fn test_too_few_chapters() {
+        assert_eq!(generate_chapter_text(&[test_clip("a", 60_000), test_clip("b", 60_000)], &[]), "");
+        assert_eq!(generate_chapter_text(&[], &[]), "");
+    }
+}
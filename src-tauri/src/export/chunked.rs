@@ -0,0 +1,568 @@
+use super::encoder::{
+    curve_value, effective_duration, export_mp4, probe_duration, property_curve,
+    resolve_source_fps, translate_ffmpeg_error,
+};
+use super::snapshot::ExportContext;
+use crate::types::{
+    AnimatedProperty, Annotation, Clip, Easing, ExportChunkProgress, ExportQuality, Keyframe, OutputSettings, Subtitle,
+    Transition, TransitionType,
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tauri::{AppHandle, Emitter, Listener};
+
+/// Room kept between a chunk boundary and a crossfade
+const TRANSITION_MARGIN_S: f64 = 1.0;
+/// Boundaries this close to a hard cut between clips move onto it
+const SNAP_S: f64 = 1.0;
+/// Slices shorter than this are dropped
+const MIN_SLICE_S: f64 = 0.001;
+/// Share of the overall progress taken by the chunk renders
+const RENDER_SHARE: f64 = 95.0;
+const MANIFEST: &str = "manifest.json";
+
+/// Chunks already rendered for a timeline, kept next to them
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    fingerprint: String,
+    done: Vec<usize>,
+}
+
+/// A self-contained piece of the timeline
+#[derive(Debug, Default)]
+struct Chunk {
+    clips: Vec<Clip>,
+    transitions: Vec<Transition>,
+    annotations: HashMap<String, Vec<Annotation>>,
+    subtitles: Vec<Subtitle>,
+    global_annotations: Vec<Annotation>,
+    duration: f64,
+}
+
+/// Render a long timeline in chunks of `chunk_minutes`, then join them
+/// without re-encoding. Finished chunks are recorded in a manifest so an
+/// export of the same timeline interrupted by a crash picks up where it
+/// stopped. Short timelines go through the regular export.
+pub async fn export(
+    clips: &[Clip],
+    transitions: &[Transition],
+    output_path: &PathBuf,
+    app: &AppHandle,
+    watermark: bool,
+    quality: &ExportQuality,
+    ctx: &ExportContext<'_>,
+    output: &OutputSettings,
+    chunk_minutes: u32,
+    temp_dir: &Path,
+) -> Result<()> {
+    let mut eff_durations = Vec::new();
+    for clip in clips {
+        eff_durations.push(effective_duration(clip, probe_duration(&clip.media.path).await?));
+    }
+    let overlaps = transition_overlaps(&eff_durations, transitions);
+    let boundaries = plan_boundaries(&eff_durations, &overlaps, chunk_minutes.max(1) as f64 * 60.0);
+
+    // Chunks are joined by stream copy: an audio track missing from some of them can't be
+    let has_any_audio = output.include_audio && clips.iter().any(|c| !c.media.audio_paths.is_empty());
+    let mixed_audio = has_any_audio && clips.iter().any(|c| c.media.audio_paths.is_empty());
+    if boundaries.is_empty() || mixed_audio {
+        if mixed_audio {
+            tracing::info!("Clips with and without audio, exporting in one pass");
+        }
+        let listener = forward_progress(app, 0.0, 100.0, 0, 1, 0);
+        let result = export_mp4(clips, transitions, output_path, app, watermark, quality, ctx, None, output).await;
+        app.unlisten(listener);
+        result?;
+        emit_progress(app, 100.0, None, 1, 0);
+        return Ok(());
+    }
+
+    // Same frame rate and frame size in every chunk
+    let mut output = resolve_source_fps(clips, output).await;
+    pin_canvas(clips, &mut output);
+
+    let chunks = split(clips, &eff_durations, &overlaps, transitions, ctx.clip_annotations, ctx.subtitles, ctx.global_annotations, &boundaries);
+    let key = fingerprint(clips, transitions, &eff_durations, watermark, quality, ctx, &output, chunk_minutes);
+    let dir = prepare_dir(temp_dir, &key)?;
+    let mut manifest = load_manifest(&dir, &key);
+    let paths: Vec<PathBuf> = (0..chunks.len()).map(|k| dir.join(format!("chunk{k}.mp4"))).collect();
+    let resumed = manifest.done.iter().filter(|k| paths.get(**k).is_some_and(|p| p.exists())).count();
+    tracing::info!("Chunked export: {} chunks of {} min, {} already rendered", chunks.len(), chunk_minutes, resumed);
+
+    let total: f64 = chunks.iter().map(|c| c.duration).sum();
+    let mut base = 0.0;
+    for (k, chunk) in chunks.iter().enumerate() {
+        let share = chunk.duration / total * RENDER_SHARE;
+        if manifest.done.contains(&k) && paths[k].exists() {
+            base += share;
+            continue;
+        }
+        let listener = forward_progress(app, base, share, k, chunks.len(), resumed);
+        emit_progress(app, base, Some(k), chunks.len(), resumed);
+
+        let chunk_ctx = ExportContext {
+            subtitles: &chunk.subtitles,
+            global_annotations: &chunk.global_annotations,
+            clip_annotations: &chunk.annotations,
+            ..*ctx
+        };
+        let result = export_mp4(&chunk.clips, &chunk.transitions, &paths[k], app, watermark, quality, &chunk_ctx, None, &output).await;
+        app.unlisten(listener);
+        if let Err(e) = result {
+            tracing::error!("Chunk {}/{} failed, finished chunks are kept for a retry", k + 1, chunks.len());
+            return Err(e);
+        }
+
+        manifest.done.push(k);
+        save_manifest(&dir, &manifest)?;
+        base += share;
+    }
+
+    emit_progress(app, RENDER_SHARE, None, chunks.len(), resumed);
+    join(&paths, &dir, output_path).await?;
+    let _ = std::fs::remove_dir_all(&dir);
+    emit_progress(app, 100.0, None, chunks.len(), resumed);
+    Ok(())
+}
+
+fn emit_progress(app: &AppHandle, percent: f64, chunk_index: Option<usize>, chunk_count: usize, resumed: usize) {
+    let _ = app.emit("chunked-export-progress", ExportChunkProgress {
+        percent: percent.clamp(0.0, 100.0) as u32,
+        chunk_index,
+        chunk_count,
+        resumed,
+    });
+}
+
+/// Rescale a chunk's own `export-progress` to `base..base + share` of the whole export
+fn forward_progress(
+    app: &AppHandle,
+    base: f64,
+    share: f64,
+    index: usize,
+    count: usize,
+    resumed: usize,
+) -> tauri::EventId {
+    let forward = app.clone();
+    app.listen("export-progress", move |event| {
+        if let Ok(p) = serde_json::from_str::<u32>(event.payload()) {
+            emit_progress(&forward, base + p as f64 / 100.0 * share, Some(index), count, resumed);
+        }
+    })
+}
+
+/// How long each pair of neighbouring clips overlaps (0 for a hard cut),
+/// clamped the same way as the transition chain
+fn transition_overlaps(eff_durations: &[f64], transitions: &[Transition]) -> Vec<f64> {
+    (0..eff_durations.len().saturating_sub(1))
+        .map(|i| {
+            let t = transitions.get(i).cloned().unwrap_or_default();
+            if t.transition_type == TransitionType::Cut {
+                return 0.0;
+            }
+            let max_dur = eff_durations[i].min(eff_durations[i + 1]) * 0.9;
+            t.duration_s.clamp(0.1, max_dur.max(0.1))
+        })
+        .collect()
+}
+
+/// Timeline position (seconds) where each clip starts
+fn clip_starts(eff_durations: &[f64], overlaps: &[f64]) -> Vec<f64> {
+    let mut t = 0.0;
+    eff_durations
+        .iter()
+        .enumerate()
+        .map(|(i, d)| {
+            let start = t;
+            t += d - overlaps.get(i).copied().unwrap_or(0.0);
+            start
+        })
+        .collect()
+}
+
+/// Chunk boundaries near every `chunk_s` seconds of timeline. A boundary
+/// never falls inside a crossfade, nor so close to one that the slice next
+/// to it would clamp the transition shorter. Boundaries near a hard cut
+/// move onto it, and no chunk is shorter than half a chunk.
+fn plan_boundaries(eff_durations: &[f64], overlaps: &[f64], chunk_s: f64) -> Vec<f64> {
+    let starts = clip_starts(eff_durations, overlaps);
+    let total = match (starts.last(), eff_durations.last()) {
+        (Some(s), Some(d)) => s + d,
+        _ => return Vec::new(),
+    };
+    let margin = |overlap: f64| if overlap > 0.0 { overlap / 0.9 + TRANSITION_MARGIN_S } else { 0.0 };
+    let safe: Vec<(f64, f64)> = eff_durations
+        .iter()
+        .enumerate()
+        .filter_map(|(i, d)| {
+            let from = starts[i] + if i > 0 { margin(overlaps[i - 1]) } else { 0.0 };
+            let to = starts[i] + d - overlaps.get(i).map_or(0.0, |o| margin(*o));
+            (from <= to).then_some((from, to))
+        })
+        .collect();
+    let cuts: Vec<f64> = (1..eff_durations.len())
+        .filter(|i| overlaps[i - 1] <= 0.0)
+        .map(|i| starts[i])
+        .collect();
+
+    let mut boundaries = Vec::new();
+    let mut last = 0.0;
+    let mut k = 1;
+    while (k as f64) * chunk_s < total {
+        let target = k as f64 * chunk_s;
+        k += 1;
+        let Some(mut at) = safe
+            .iter()
+            .map(|(from, to)| target.clamp(*from, *to))
+            .min_by(|a, b| (a - target).abs().total_cmp(&(b - target).abs()))
+        else {
+            break;
+        };
+        if let Some(cut) = cuts.iter().find(|c| (*c - at).abs() <= SNAP_S) {
+            at = *cut;
+        }
+        if at - last >= chunk_s / 2.0 && total - at >= chunk_s / 2.0 {
+            boundaries.push(at);
+            last = at;
+        }
+    }
+    boundaries
+}
+
+fn ms(seconds: f64) -> u64 {
+    (seconds * 1000.0).round().max(0.0) as u64
+}
+
+/// Cut the timeline at `boundaries` into sub-timelines of clip slices.
+/// Slices keep their clip id: keystrokes, cursor positions and zoom
+/// keyframes follow the new trim, annotations and subtitles are shifted.
+/// Timeline annotations are shifted like the subtitles.
+fn split(
+    clips: &[Clip],
+    eff_durations: &[f64],
+    overlaps: &[f64],
+    transitions: &[Transition],
+    clip_annotations: &HashMap<String, Vec<Annotation>>,
+    subtitles: &[Subtitle],
+    global_annotations: &[Annotation],
+    boundaries: &[f64],
+) -> Vec<Chunk> {
+    let starts = clip_starts(eff_durations, overlaps);
+    let total = starts.last().zip(eff_durations.last()).map_or(0.0, |(s, d)| s + d);
+    let mut edges = vec![0.0];
+    edges.extend_from_slice(boundaries);
+    edges.push(total);
+
+    edges
+        .windows(2)
+        .map(|w| {
+            let (start, end) = (w[0], w[1]);
+            let mut chunk = Chunk { duration: end - start, ..Default::default() };
+            for (i, clip) in clips.iter().enumerate() {
+                let from = (start - starts[i]).max(0.0);
+                let to = (end - starts[i]).min(eff_durations[i]);
+                if to - from < MIN_SLICE_S {
+                    continue;
+                }
+                if !chunk.clips.is_empty() {
+                    chunk.transitions.push(transitions.get(i - 1).cloned().unwrap_or_default());
+                }
+                if let Some(anns) = clip_annotations.get(&clip.id) {
+                    let shifted = shift_annotations(anns, ms(from), ms(to));
+                    if !shifted.is_empty() {
+                        chunk.annotations.insert(clip.id.clone(), shifted);
+                    }
+                }
+                chunk.clips.push(slice_clip(clip, from, to, eff_durations[i]));
+            }
+            chunk.subtitles = shift_subtitles(subtitles, ms(start), ms(end));
+            chunk.global_annotations = shift_annotations(global_annotations, ms(start), ms(end));
+            chunk
+        })
+        .collect()
+}
+
+/// The `from..to` seconds of a clip's trimmed range, as a clip of its own
+fn slice_clip(clip: &Clip, from: f64, to: f64, eff_duration: f64) -> Clip {
+    let mut slice = clip.clone();
+    slice.media.trim_start_ms = clip.media.trim_start_ms + ms(from);
+    // An untrimmed end keeps running to the end of the file
+    if clip.media.trim_end_ms > 0 || to < eff_duration - MIN_SLICE_S {
+        slice.media.trim_end_ms = clip.media.trim_start_ms + ms(to);
+    }
+    slice
+}
+
+fn shift_annotations(annotations: &[Annotation], from_ms: u64, to_ms: u64) -> Vec<Annotation> {
+    annotations
+        .iter()
+        .filter(|a| a.end_ms > from_ms && a.start_ms < to_ms)
+        .map(|a| Annotation {
+            start_ms: a.start_ms.saturating_sub(from_ms),
+            end_ms: a.end_ms.min(to_ms) - from_ms,
+            keyframes: shift_keyframes(&a.keyframes, from_ms),
+            ..a.clone()
+        })
+        .collect()
+}
+
+/// Move keyframes `offset_ms` earlier. A property already animating
+/// before the cut starts again from the value it had there.
+fn shift_keyframes(keyframes: &[Keyframe], offset_ms: u64) -> Vec<Keyframe> {
+    let mut shifted: Vec<Keyframe> = keyframes
+        .iter()
+        .filter(|k| k.time_ms >= offset_ms)
+        .map(|k| Keyframe { time_ms: k.time_ms - offset_ms, ..k.clone() })
+        .collect();
+    for property in [AnimatedProperty::ZoomScale, AnimatedProperty::ZoomX, AnimatedProperty::ZoomY, AnimatedProperty::Opacity] {
+        let points = property_curve(keyframes, property, offset_ms);
+        if !points.first().is_some_and(|p| p.0 < 0.0)
+            || shifted.iter().any(|k| k.property == property && k.time_ms == 0)
+        {
+            continue;
+        }
+        let Some(value) = curve_value(&points, 0.0) else { continue };
+        // The segment crossing the cut restarts linearly, a hold stays a hold
+        let easing = match points.iter().rev().find(|p| p.0 < 0.0) {
+            Some(p) if p.2 == Easing::Hold => Easing::Hold,
+            _ => Easing::Linear,
+        };
+        shifted.push(Keyframe { time_ms: 0, property, value, easing });
+    }
+    shifted
+}
+
+fn shift_subtitles(subtitles: &[Subtitle], from_ms: u64, to_ms: u64) -> Vec<Subtitle> {
+    subtitles
+        .iter()
+        .filter(|s| s.end_ms > from_ms && s.start_ms < to_ms)
+        .map(|s| Subtitle {
+            start_ms: s.start_ms.saturating_sub(from_ms),
+            end_ms: s.end_ms.min(to_ms) - from_ms,
+            ..s.clone()
+        })
+        .collect()
+}
+
+/// Fix the output size to the whole timeline's canvas: a chunk holding
+/// only smaller clips would otherwise come out at another size
+fn pin_canvas(clips: &[Clip], output: &mut OutputSettings) {
+    let w = (clips.iter().map(|c| c.media.region.width).max().unwrap_or(1920) / 2) * 2;
+    let h = (clips.iter().map(|c| c.media.region.height).max().unwrap_or(1080) / 2) * 2;
+    match (output.width, output.height) {
+        (None, None) => {
+            output.width = Some(w);
+            output.height = Some(h);
+        }
+        (Some(ow), None) => output.height = Some(super::even((ow as u64 * h as u64 / w.max(1) as u64) as f64)),
+        (None, Some(oh)) => output.width = Some(super::even((oh as u64 * w as u64 / h.max(1) as u64) as f64)),
+        (Some(_), Some(_)) => {}
+    }
+}
+
+/// Debug output is unordered for maps, sort them first
+fn sorted<V>(map: &HashMap<String, V>) -> BTreeMap<&String, &V> {
+    map.iter().collect()
+}
+
+/// Identifies everything that shapes the render, so chunks are only
+/// reused for the exact same timeline and settings
+fn fingerprint(
+    clips: &[Clip],
+    transitions: &[Transition],
+    eff_durations: &[f64],
+    watermark: bool,
+    quality: &ExportQuality,
+    ctx: &ExportContext<'_>,
+    output: &OutputSettings,
+    chunk_minutes: u32,
+) -> String {
+    let key = format!(
+        "{:?}|{:?}|{:?}|{}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{}|{}|{:?}|{}",
+        clips, transitions, eff_durations, watermark, quality, sorted(ctx.clip_keystrokes), ctx.keystroke_font,
+        ctx.subtitles, ctx.global_annotations, sorted(ctx.clip_annotations), sorted(ctx.clip_cursor_positions), sorted(ctx.clip_gamepad),
+        ctx.system_volume, ctx.mic_volume, output, chunk_minutes,
+    );
+    crate::upload::hex(&Sha256::digest(key.as_bytes()))[..16].to_string()
+}
+
+/// Chunk directory of this timeline. Only the latest timeline can be
+/// resumed, leftovers of other ones are dropped.
+fn prepare_dir(temp_dir: &Path, key: &str) -> Result<PathBuf> {
+    let name = format!("chunks_{key}");
+    if let Ok(entries) = std::fs::read_dir(temp_dir) {
+        for entry in entries.flatten() {
+            let other = entry.file_name().to_string_lossy().to_string();
+            if other.starts_with("chunks_") && other != name {
+                let _ = std::fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+    let dir = temp_dir.join(name);
+    std::fs::create_dir_all(&dir).with_context(|| crate::i18n::t("chunks_dir_failed"))?;
+    Ok(dir)
+}
+
+fn load_manifest(dir: &Path, key: &str) -> Manifest {
+    std::fs::read(dir.join(MANIFEST))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<Manifest>(&bytes).ok())
+        .filter(|m| m.fingerprint == key)
+        .unwrap_or_else(|| Manifest { fingerprint: key.to_string(), done: Vec::new() })
+}
+
+/// Written then renamed, so a crash never leaves a half-written manifest
+fn save_manifest(dir: &Path, manifest: &Manifest) -> Result<()> {
+    let tmp = dir.join(format!("{MANIFEST}.tmp"));
+    std::fs::write(&tmp, serde_json::to_vec(manifest)?).with_context(|| crate::i18n::t("chunks_manifest_failed"))?;
+    std::fs::rename(&tmp, dir.join(MANIFEST)).with_context(|| crate::i18n::t("chunks_manifest_failed"))?;
+    Ok(())
+}
+
+/// Join the chunks by stream copy
+async fn join(paths: &[PathBuf], dir: &Path, output_path: &Path) -> Result<()> {
+    let list_path = dir.join("chunks.txt");
+    let list = crate::ffmpeg::paths::concat_list(paths);
+    std::fs::write(&list_path, list).with_context(|| crate::i18n::t("chunks_list_failed"))?;
+
+    let output = crate::ffmpeg_command()
+        .args([
+            "-f", "concat",
+            "-safe", "0",
+            "-i", &list_path.to_string_lossy(),
+            "-c", "copy",
+            "-movflags", "+faststart",
+            "-y",
+            &output_path.to_string_lossy(),
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .context("Failed to run chunk concat")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{}", translate_ffmpeg_error(&stderr));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AnnotationKind, ClipMedia, SubtitlePosition, test_annotation, test_clip};
+
+    fn clip(id: &str, trim_start_ms: u64, trim_end_ms: u64) -> Clip {
+        let clip = test_clip(id, 0);
+        Clip {
+            media: ClipMedia { trim_start_ms, trim_end_ms, ..clip.media },
+            ..clip
+        }
+    }
+
+    fn fade(duration_s: f64) -> Transition {
+        Transition { transition_type: TransitionType::Fade, duration_s }
+    }
+
+    fn cut() -> Transition {
+        Transition { transition_type: TransitionType::Cut, duration_s: 0.5 }
+    }
+
+    fn subtitle(start_ms: u64, end_ms: u64) -> Subtitle {
+        Subtitle {
+            id: "s".into(),
+            text: "Bonjour".into(),
+            start_ms,
+            end_ms,
+            position: SubtitlePosition::Bottom,
+            font_size: 32,
+            color: "#ffffff".into(),
+            font_file: None,
+        }
+    }
+
+    #[test]
+    fn test_boundaries_snap_to_cuts_and_skip_short_tails() {
+        // 0-599.5 | 599.5-1300 | 1300-1400, all hard cuts
+        let eff = [599.5, 700.5, 100.0];
+        let overlaps = transition_overlaps(&eff, &[cut(), cut()]);
+        let boundaries = plan_boundaries(&eff, &overlaps, 600.0);
+        // 600 moves back onto the cut at 599.5, 1200 would leave a 200s tail
+        assert_eq!(boundaries, vec![599.5]);
+        assert!(plan_boundaries(&[700.0], &[], 600.0).is_empty());
+    }
+
+    #[test]
+    fn test_boundaries_stay_clear_of_crossfades() {
+        // Clip 1 starts at 598: the fade runs 598-600
+        let eff = [600.0, 600.0];
+        let overlaps = transition_overlaps(&eff, &[fade(2.0)]);
+        let boundaries = plan_boundaries(&eff, &overlaps, 600.0);
+        assert_eq!(boundaries.len(), 1);
+        assert!(boundaries[0] >= 598.0 + 2.0 / 0.9 + TRANSITION_MARGIN_S - 1e-9);
+    }
+
+    #[test]
+    fn test_split_trims_slices_and_keeps_inner_transitions() {
+        let clips = [clip("a", 1_000, 0), clip("b", 0, 0)];
+        let eff = [900.0, 300.0];
+        let transitions = [fade(1.0)];
+        let overlaps = transition_overlaps(&eff, &transitions);
+        let chunks = split(&clips, &eff, &overlaps, &transitions, &HashMap::new(), &[], &[], &[600.0]);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].clips.len(), 1);
+        assert_eq!((chunks[0].clips[0].media.trim_start_ms, chunks[0].clips[0].media.trim_end_ms), (1_000, 601_000));
+        // The rest of "a" runs to the end of its file, then fades into "b"
+        assert_eq!((chunks[1].clips[0].media.trim_start_ms, chunks[1].clips[0].media.trim_end_ms), (601_000, 0));
+        assert_eq!(chunks[1].clips[1].id, "b");
+        assert_eq!(chunks[1].transitions.len(), 1);
+        assert!(chunks[0].transitions.is_empty());
+    }
+
+    #[test]
+    fn test_split_shifts_annotations_and_subtitles() {
+        let clips = [clip("a", 0, 0)];
+        let annotation = Annotation {
+            keyframes: vec![
+                Keyframe { time_ms: 590_000, property: AnimatedProperty::Opacity, value: 0.0, easing: Easing::Linear },
+                Keyframe { time_ms: 610_000, property: AnimatedProperty::Opacity, value: 1.0, easing: Easing::Linear },
+            ],
+            ..test_annotation(AnnotationKind::Rectangle, 590_000, 610_000)
+        };
+        let annotations = HashMap::from([("a".to_string(), vec![annotation.clone()])]);
+        let subtitles = [subtitle(599_000, 602_000), subtitle(10_000, 12_000)];
+        // The same one on the timeline itself
+        let global = [annotation];
+        let chunks = split(&clips, &[1200.0], &[], &[], &annotations, &subtitles, &global, &[600.0]);
+
+        let first = &chunks[0].annotations["a"][0];
+        assert_eq!((first.start_ms, first.end_ms), (590_000, 600_000));
+        let second = &chunks[1].annotations["a"][0];
+        assert_eq!((second.start_ms, second.end_ms), (0, 10_000));
+        let start = second.keyframes.iter().find(|k| k.time_ms == 0).unwrap();
+        assert!((start.value - 0.5).abs() < 1e-9);
+
+        assert_eq!(chunks[0].subtitles.len(), 2);
+        assert_eq!((chunks[1].subtitles[0].start_ms, chunks[1].subtitles[0].end_ms), (0, 2_000));
+        assert_eq!(chunks[1].subtitles.len(), 1);
+        let global = &chunks[1].global_annotations[0];
+        assert_eq!((global.start_ms, global.end_ms), (0, 10_000));
+    }
+
+    #[test]
+    fn test_pin_canvas_keeps_aspect() {
+        let clips = [clip("a", 0, 0)];
+        let mut output = OutputSettings::default();
+        pin_canvas(&clips, &mut output);
+        assert_eq!((output.width, output.height), (Some(1920), Some(1080)));
+        let mut output = OutputSettings { width: Some(1280), ..Default::default() };
+        pin_canvas(&clips, &mut output);
+        assert_eq!(output.height, Some(720));
+    }
+}
@@ -0,0 +1,267 @@
+use super::encoder::translate_ffmpeg_error;
+use super::even;
+use crate::types::{AnimatedProperty, Annotation, Clip, CursorPosition, DualLayout, Region, SecondaryVideo};
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+
+/// Width of the picture-in-picture inset, as a fraction of the frame width
+const PIP_FRACTION: f64 = 0.3;
+/// Gap between the inset and the frame edges, as a fraction of the frame width
+const PIP_MARGIN: f64 = 0.02;
+
+/// Where a source frame ends up in an output frame, normalized 0-1: the
+/// main region in a composite, the source around a reframed window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub w: f64,
+    pub h: f64,
+}
+
+impl Rect {
+    pub fn point(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.x + x * self.w, self.y + y * self.h)
+    }
+}
+
+#[derive(Debug)]
+struct Composite {
+    filter: String,
+    width: u32,
+    height: u32,
+    primary: Rect,
+}
+
+/// Filter graph joining input 0 (main region) and input 1 (second region,
+/// started `offset_ms` later) into `[v]`
+fn composite(layout: DualLayout, primary: (u32, u32), secondary: (u32, u32), offset_ms: u64) -> Composite {
+    let (pw, ph) = (primary.0.max(2) as f64, primary.1.max(2) as f64);
+    let (sw, sh) = (secondary.0.max(2) as f64, secondary.1.max(2) as f64);
+    // Hold the second region's first frame until its capture actually started
+    let delay = if offset_ms > 0 {
+        format!("tpad=start_duration={:.3}:start_mode=clone,", offset_ms as f64 / 1000.0)
+    } else {
+        String::new()
+    };
+
+    match layout {
+        DualLayout::SideBySide => {
+            let height = even(ph.max(sh));
+            let (left, right) = (even(pw * height as f64 / ph), even(sw * height as f64 / sh));
+            let width = left + right;
+            Composite {
+                filter: format!(
+                    "[0:v]scale={left}:{height},setsar=1[a];[1:v]{delay}scale={right}:{height},setsar=1[b];\
+                     [a][b]hstack=inputs=2,format=yuv420p[v]"
+                ),
+                width,
+                height,
+                primary: Rect { x: 0.0, y: 0.0, w: left as f64 / width as f64, h: 1.0 },
+            }
+        }
+        DualLayout::Stacked => {
+            let width = even(pw.max(sw));
+            let (top, bottom) = (even(ph * width as f64 / pw), even(sh * width as f64 / sw));
+            let height = top + bottom;
+            Composite {
+                filter: format!(
+                    "[0:v]scale={width}:{top},setsar=1[a];[1:v]{delay}scale={width}:{bottom},setsar=1[b];\
+                     [a][b]vstack=inputs=2,format=yuv420p[v]"
+                ),
+                width,
+                height,
+                primary: Rect { x: 0.0, y: 0.0, w: 1.0, h: top as f64 / height as f64 },
+            }
+        }
+        DualLayout::Pip => {
+            let (width, height) = (even(pw), even(ph));
+            let inset_w = even(width as f64 * PIP_FRACTION);
+            let inset_h = even(sh * inset_w as f64 / sw);
+            let margin = even(width as f64 * PIP_MARGIN);
+            // Top right: the keystroke and gamepad overlays sit at the bottom
+            let x = width.saturating_sub(inset_w + margin);
+            Composite {
+                filter: format!(
+                    "[0:v]scale={width}:{height},setsar=1[a];[1:v]{delay}scale={inset_w}:{inset_h},setsar=1[b];\
+                     [a][b]overlay={x}:{margin}:eof_action=pass,format=yuv420p[v]"
+                ),
+                width,
+                height,
+                primary: Rect { x: 0.0, y: 0.0, w: 1.0, h: 1.0 },
+            }
+        }
+    }
+}
+
+fn layout_tag(layout: DualLayout) -> &'static str {
+    match layout {
+        DualLayout::SideBySide => "side",
+        DualLayout::Stacked => "stacked",
+        DualLayout::Pip => "pip",
+    }
+}
+
+/// Identifies both sources and how they line up, so a composite is only
+/// reused while neither of them changed
+fn cache_key(clip: &Clip, secondary: &SecondaryVideo) -> String {
+    let key = format!(
+        "{:?}|{}|{}|{}|{:?}|{:?}|{}",
+        clip.media.path, clip.media.duration_ms, clip.media.trim_start_ms, clip.media.trim_end_ms, secondary.path, secondary.region, secondary.offset_ms,
+    );
+    crate::upload::hex(&Sha256::digest(key.as_bytes()))[..16].to_string()
+}
+
+/// Replace every clip recorded with a second region by the two regions
+/// composited in its layout, so the export pipeline only sees single videos.
+/// Annotations, cursor paths and zoom centers drawn over the main region are
+/// moved to where that region lands in the composite. Composites are kept in
+/// `temp_dir` and reused by later exports.
+pub async fn compose_all(
+    clips: &mut [Clip],
+    annotations: &mut HashMap<String, Vec<Annotation>>,
+    cursor_positions: &mut HashMap<String, Vec<CursorPosition>>,
+    temp_dir: &Path,
+) -> Result<()> {
+    for clip in clips.iter_mut() {
+        let Some(secondary) = clip.media.secondary.take() else { continue };
+        let composite = composite(
+            secondary.layout,
+            (clip.media.region.width, clip.media.region.height),
+            (secondary.region.width, secondary.region.height),
+            secondary.offset_ms,
+        );
+        let output = temp_dir.join(format!(
+            "{}_dual_{}_{}.mp4",
+            clip.id,
+            layout_tag(secondary.layout),
+            cache_key(clip, &secondary)
+        ));
+        if !output.exists() {
+            tracing::info!("Compositing second region of clip {} ({:?})", clip.id, secondary.layout);
+            compose(clip, &secondary, &composite, &output).await?;
+        }
+
+        let rect = composite.primary;
+        for ann in annotations.get_mut(&clip.id).into_iter().flatten() {
+            move_annotation(ann, rect);
+        }
+        for pos in cursor_positions.get_mut(&clip.id).into_iter().flatten() {
+            (pos.x, pos.y) = rect.point(pos.x, pos.y);
+        }
+        for kf in &mut clip.zoom_keyframes {
+            match kf.property {
+                AnimatedProperty::ZoomX => kf.value = rect.x + kf.value * rect.w,
+                AnimatedProperty::ZoomY => kf.value = rect.y + kf.value * rect.h,
+                _ => {}
+            }
+        }
+        clip.media.path = output;
+        clip.media.region = Region { width: composite.width, height: composite.height, ..clip.media.region.clone() };
+    }
+    Ok(())
+}
+
+pub(super) fn move_annotation(ann: &mut Annotation, rect: Rect) {
+    (ann.x, ann.y) = rect.point(ann.x, ann.y);
+    ann.width *= rect.w;
+    ann.height *= rect.h;
+    if let Some(points) = ann.points.as_mut() {
+        for p in points.iter_mut() {
+            *p = rect.point(p.0, p.1);
+        }
+    }
+}
+
+async fn compose(clip: &Clip, secondary: &SecondaryVideo, composite: &Composite, output: &Path) -> Result<()> {
+    let result = crate::ffmpeg_command()
+        .args([
+            "-i", &clip.media.path.to_string_lossy(),
+            "-i", &secondary.path.to_string_lossy(),
+            "-filter_complex", &composite.filter,
+            "-map", "[v]",
+            // The main region sets the length, trims are in its time base
+            "-t", &format!("{:.3}", clip.media.duration_ms as f64 / 1000.0),
+            "-c:v", "libx264",
+            "-preset", "veryfast",
+            "-crf", "16",
+            "-an",
+            "-movflags", "+faststart",
+            "-y", &output.to_string_lossy(),
+        ])
+        .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped())
+        .output().await.context("Failed to run FFmpeg")?;
+    if !result.status.success() {
+        let _ = std::fs::remove_file(output);
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        anyhow::bail!(crate::i18n::tf("dual_failed", &[&translate_ffmpeg_error(&stderr)]));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_side_by_side_matches_heights() {
+        let c = composite(DualLayout::SideBySide, (1280, 720), (800, 1000), 0);
+        assert_eq!((c.width, c.height), (1778 + 800, 1000));
+        assert!(c.filter.contains("scale=1778:1000") && c.filter.contains("hstack"));
+        assert!(!c.filter.contains("tpad"));
+        assert!((c.primary.w - 1778.0 / 2578.0).abs() < 1e-9);
+        assert_eq!(c.primary.h, 1.0);
+    }
+
+    #[test]
+    fn test_stacked_matches_widths_and_delays_second_region() {
+        let c = composite(DualLayout::Stacked, (1920, 1080), (960, 540), 250);
+        assert_eq!((c.width, c.height), (1920, 2160));
+        assert!(c.filter.contains("[1:v]tpad=start_duration=0.250:start_mode=clone,scale=1920:1080"));
+        assert_eq!(c.primary, Rect { x: 0.0, y: 0.0, w: 1.0, h: 0.5 });
+    }
+
+    #[test]
+    fn test_pip_keeps_main_frame() {
+        let c = composite(DualLayout::Pip, (1920, 1080), (1280, 720), 0);
+        assert_eq!((c.width, c.height), (1920, 1080));
+        // 576x324 inset, 38px from the top right corner
+        assert!(c.filter.contains("scale=576:324") && c.filter.contains("overlay=1306:38"));
+        assert_eq!(c.primary, Rect { x: 0.0, y: 0.0, w: 1.0, h: 1.0 });
+    }
+
+    #[test]
+    fn test_annotations_follow_main_region() {
+        let mut ann = Annotation {
+            x: 0.5,
+            y: 0.5,
+            width: 0.2,
+            height: 0.4,
+            points: Some(vec![(1.0, 0.0)]),
+            ..crate::types::test_annotation(crate::types::AnnotationKind::Freehand, 0, 1000)
+        };
+        move_annotation(&mut ann, Rect { x: 0.0, y: 0.0, w: 0.5, h: 1.0 });
+        assert_eq!((ann.x, ann.y, ann.width, ann.height), (0.25, 0.5, 0.1, 0.4));
+        assert_eq!(ann.points, Some(vec![(0.5, 0.0)]));
+    }
+
+    #[test]
+    fn test_cache_key_follows_sources() {
+        let clip = crate::types::test_clip("c", 5000);
+        let secondary = SecondaryVideo {
+            path: "second.mp4".into(),
+            region: Region { x: 0, y: 0, width: 1280, height: 720 },
+            offset_ms: 0,
+            layout: DualLayout::Pip,
+        };
+        let key = cache_key(&clip, &secondary);
+        assert_ne!(key, cache_key(&clip, &SecondaryVideo { offset_ms: 120, ..secondary.clone() }));
+        assert_ne!(key, cache_key(&clip, &SecondaryVideo { path: "other.mp4".into(), ..secondary.clone() }));
+        let mut trimmed = clip.clone();
+        trimmed.media.trim_start_ms = 500;
+        assert_ne!(key, cache_key(&trimmed, &secondary));
+    }
+}
@@ -1,44 +1,58 @@
+use crate::annotations::paint_order;
+use crate::ffmpeg::paths;
 use crate::types::{
-    Annotation, AnnotationKind, Clip, CursorPosition, ExportQuality,
-    KeystrokeEvent, Subtitle, SubtitlePosition, Transition, TransitionType,
+    AnimatedProperty, Annotation, AnnotationKind, CanvasFit, Clip, CursorPosition, Easing,
+    ExportEncoder, ExportQuality, Keyframe, KeystrokeEvent, OutputSettings, SafeArea, Subtitle, SubtitlePosition, Transition, TransitionType, WatermarkTone,
 };
+use super::fonts;
+use super::overlay::OverlayTracks;
+use super::safe_area;
+use super::snapshot::ExportContext;
 use anyhow::{Context, Result};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tauri::{AppHandle, Emitter};
 
 const DEFAULT_TRANSITION_DURATION: f64 = 0.5;
+/// Frame rate of every export unless a preset overrides it
+pub(crate) const DEFAULT_FPS: u32 = 30;
 const CURSOR_ZOOM: f64 = 1.15;
-
-/// Translate common FFmpeg error messages to French for user-friendly display.
-fn translate_ffmpeg_error(stderr: &str) -> String {
+/// Camera path error allowed when thinning cursor zoom keyframes (share of the frame)
+const CURSOR_PATH_TOLERANCE: f64 = 0.01;
+/// Keeps the crop expressions of hour-long clips manageable
+const MAX_CURSOR_KEYFRAMES: usize = 150;
+/// Keystrokes drawn by the drawtext fallback, one filter each
+const MAX_KEYSTROKE_DRAWTEXT: usize = 150;
+
+/// Translate common FFmpeg error messages to the UI language for user-friendly display.
+pub(crate) fn translate_ffmpeg_error(stderr: &str) -> String {
     let lower = stderr.to_lowercase();
     if lower.contains("no such file") || lower.contains("does not exist") {
-        return "Fichier introuvable".to_string();
+        return crate::i18n::t("ffmpeg_file_missing");
     }
     if lower.contains("invalid data") || lower.contains("corrupt") {
-        return "Données corrompues".to_string();
+        return crate::i18n::t("ffmpeg_corrupt_data");
     }
     if lower.contains("permission denied") || lower.contains("access is denied") {
-        return "Accès refusé au fichier".to_string();
+        return crate::i18n::t("ffmpeg_access_denied");
     }
     if lower.contains("not enough frames") || lower.contains("too few frames") {
-        return "Clip trop court pour la transition demandée".to_string();
+        return crate::i18n::t("ffmpeg_clip_too_short");
     }
     if lower.contains("no space left") || lower.contains("disk full") {
-        return "Espace disque insuffisant".to_string();
+        return crate::i18n::t("ffmpeg_disk_full");
     }
     if lower.contains("codec not found") || lower.contains("unknown encoder") || lower.contains("encoder") && lower.contains("not found") {
-        return "Codec vidéo introuvable — vérifiez votre installation FFmpeg".to_string();
+        return crate::i18n::t("ffmpeg_codec_missing");
     }
     if lower.contains("invalid argument") {
-        return "Paramètre FFmpeg invalide".to_string();
+        return crate::i18n::t("ffmpeg_invalid_argument");
     }
     // Fallback: first 200 chars
     let truncated: String = stderr.chars().take(200).collect();
-    format!("Erreur FFmpeg : {}", truncated)
+    crate::i18n::tf("ffmpeg_error", &[&truncated])
 }
 
 fn xfade_name(t: &TransitionType) -> &'static str {
@@ -66,9 +80,9 @@ fn xfade_name(t: &TransitionType) -> &'static str {
     }
 }
 
-async fn probe_duration(path: &PathBuf) -> Result<f64> {
+pub(crate) async fn probe_duration(path: &PathBuf) -> Result<f64> {
     if !path.exists() {
-        anyhow::bail!("Fichier de clip introuvable : {:?}", path);
+        anyhow::bail!(crate::i18n::tf("clip_file_missing", &[&path.display()]));
     }
     let output = crate::ffprobe_command()
         .args([
@@ -86,17 +100,253 @@ async fn probe_duration(path: &PathBuf) -> Result<f64> {
     s.trim().parse::<f64>().context("Failed to parse duration")
 }
 
-fn effective_duration(clip: &Clip, probed_duration: f64) -> f64 {
-    let start = clip.trim_start_ms as f64 / 1000.0;
-    let end = if clip.trim_end_ms > 0 {
-        clip.trim_end_ms as f64 / 1000.0
+/// PQ (HDR10) or HLG, the transfer characteristics HDR video is tagged with
+fn is_hdr_transfer(transfer: &str) -> bool {
+    matches!(transfer.trim(), "smpte2084" | "arib-std-b67")
+}
+
+/// Whether the first video stream is tagged as HDR. The tonemap reads the
+/// transfer from the frames, so untagged or SDR video is left alone.
+pub(crate) async fn probe_is_hdr(path: &Path) -> bool {
+    let output = crate::ffprobe_command()
+        .args([
+            "-v", "error",
+            "-select_streams", "v:0",
+            "-show_entries", "stream=color_transfer",
+            "-of", "default=noprint_wrappers=1:nokey=1",
+            &path.to_string_lossy(),
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .await;
+    output.is_ok_and(|o| is_hdr_transfer(&String::from_utf8_lossy(&o.stdout)))
+}
+
+/// "60/1", "30000/1001" or "25" from ffprobe; "0/0" means unknown
+fn parse_frame_rate(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let fps = match s.split_once('/') {
+        Some((num, den)) => {
+            let den: f64 = den.parse().ok()?;
+            if den == 0.0 {
+                return None;
+            }
+            num.parse::<f64>().ok()? / den
+        }
+        None => s.parse().ok()?,
+    };
+    (fps > 0.0).then_some(fps)
+}
+
+/// Average frame rate of the first video stream
+pub(crate) async fn probe_fps(path: &PathBuf) -> Option<f64> {
+    let output = crate::ffprobe_command()
+        .args([
+            "-v", "error",
+            "-select_streams", "v:0",
+            "-show_entries", "stream=avg_frame_rate,r_frame_rate",
+            "-of", "default=noprint_wrappers=1:nokey=1",
+            &path.to_string_lossy(),
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .await
+        .ok()?;
+    // r_frame_rate first, then avg_frame_rate; gdigrab captures only fill the latter reliably
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_frame_rate)
+        .last()
+}
+
+/// Turn "match source" into a concrete frame rate: the highest among the clips
+pub(crate) async fn resolve_source_fps(clips: &[Clip], output: &OutputSettings) -> OutputSettings {
+    if output.fps.is_some() || !output.match_source_fps {
+        return output.clone();
+    }
+    let mut best: f64 = 0.0;
+    for clip in clips {
+        if let Some(fps) = probe_fps(&clip.media.path).await {
+            best = best.max(fps);
+        }
+    }
+    let fps = if best > 0.0 { (best.round() as u32).clamp(1, 120) } else { DEFAULT_FPS };
+    tracing::info!("Matching source frame rate: {} fps", fps);
+    OutputSettings { fps: Some(fps), match_source_fps: false, ..output.clone() }
+}
+
+pub(crate) fn effective_duration(clip: &Clip, probed_duration: f64) -> f64 {
+    let start = clip.media.trim_start_ms as f64 / 1000.0;
+    let end = if clip.media.trim_end_ms > 0 {
+        clip.media.trim_end_ms as f64 / 1000.0
     } else {
         probed_duration
     };
     (end - start).max(0.1)
 }
 
-const WATERMARK_FILTER: &str = "drawtext=text='ClipFlow':fontsize=28:fontcolor=white@0.7:shadowcolor=black@0.5:shadowx=2:shadowy=2:x=w-tw-20:y=h-th-16";
+/// AAC bitrate used for every MP4 export (kbps)
+pub(crate) const AUDIO_BITRATE_KBPS: f64 = 128.0;
+/// Share of the size budget kept for the MP4 container and muxing overhead
+const CONTAINER_OVERHEAD: f64 = 0.03;
+const MIN_VIDEO_KBPS: u32 = 100;
+
+/// Video bitrate (kbps) that makes an export of `duration_s` fit in `size_mb`
+pub(crate) fn target_video_kbps(size_mb: u32, duration_s: f64, has_audio: bool) -> u32 {
+    let budget_kbits = size_mb as f64 * 8.0 * 1024.0 * (1.0 - CONTAINER_OVERHEAD);
+    let total_kbps = budget_kbits / duration_s.max(0.1);
+    let audio_kbps = if has_audio { AUDIO_BITRATE_KBPS } else { 0.0 };
+    ((total_kbps - audio_kbps) as u32).max(MIN_VIDEO_KBPS)
+}
+
+/// libx264 arguments: CRF from the quality preset, or a capped bitrate
+/// when exporting to a target file size.
+pub(crate) fn video_codec_args(quality: &ExportQuality, target_kbps: Option<u32>, fps: u32) -> Vec<String> {
+    let mut args: Vec<String> = ["-c:v", "libx264", "-preset", quality.preset()].iter().map(|s| s.to_string()).collect();
+    match target_kbps {
+        Some(kbps) => {
+            // maxrate = bitrate keeps the encoder from overshooting the budget on busy scenes
+            args.extend([
+                "-b:v".to_string(), format!("{}k", kbps),
+                "-maxrate".to_string(), format!("{}k", kbps),
+                "-bufsize".to_string(), format!("{}k", kbps * 2),
+            ]);
+        }
+        None => args.extend(["-crf".to_string(), quality.crf().to_string()]),
+    }
+    args.extend(["-pix_fmt".to_string(), "yuv420p".to_string(), "-r".to_string(), fps.to_string()]);
+    args
+}
+
+/// libvpx-vp9 arguments for WebM: constant quality, or a capped bitrate
+/// when exporting to a target file size.
+pub(crate) fn webm_codec_args(quality: &ExportQuality, target_kbps: Option<u32>, fps: u32) -> Vec<String> {
+    let mut args: Vec<String> = ["-c:v", "libvpx-vp9", "-deadline", "good", "-cpu-used", "4", "-row-mt", "1"]
+        .iter().map(|s| s.to_string()).collect();
+    match target_kbps {
+        Some(kbps) => args.extend([
+            "-b:v".to_string(), format!("{}k", kbps),
+            "-maxrate".to_string(), format!("{}k", kbps),
+            "-bufsize".to_string(), format!("{}k", kbps * 2),
+        ]),
+        None => {
+            // VP9's CRF scale is wider than x264's
+            let crf = match quality {
+                ExportQuality::High => 31,
+                ExportQuality::Medium => 36,
+                ExportQuality::Low => 41,
+            };
+            args.extend(["-crf".to_string(), crf.to_string(), "-b:v".to_string(), "0".to_string()]);
+        }
+    }
+    args.extend(["-pix_fmt".to_string(), "yuv420p".to_string(), "-r".to_string(), fps.to_string()]);
+    args
+}
+
+/// Light unsharp mask after a lanczos upscale, screen text otherwise looks soft
+const UPSCALE_SHARPEN: &str = "unsharp=5:5:0.6:5:5:0.0";
+
+/// Final resize for a preset: fixed canvas (letterboxed or cropped), or one
+/// dimension with the other following the aspect ratio
+pub(crate) fn output_scale_filter(output: &OutputSettings) -> Option<String> {
+    let flags = if output.upscale { ":flags=lanczos" } else { "" };
+    let scale = match (output.width, output.height) {
+        (Some(w), Some(h)) => match output.fit {
+            CanvasFit::Letterbox => format!("scale={w}:{h}{flags}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1"),
+            CanvasFit::Crop => format!("scale={w}:{h}{flags}:force_original_aspect_ratio=increase,crop={w}:{h},setsar=1"),
+        },
+        (Some(w), None) => format!("scale={w}:-2{flags}"),
+        (None, Some(h)) => format!("scale=-2:{h}{flags}"),
+        (None, None) => return None,
+    };
+    Some(if output.upscale { format!("{scale},{UPSCALE_SHARPEN}") } else { scale })
+}
+
+/// Video encoder arguments for `output.encoder`
+pub(crate) fn codec_args(quality: &ExportQuality, target_kbps: Option<u32>, output: &OutputSettings) -> Vec<String> {
+    let fps = output.fps.unwrap_or(DEFAULT_FPS);
+    if output.encoder == ExportEncoder::Software {
+        return video_codec_args(quality, target_kbps, fps);
+    }
+
+    let mut args: Vec<String> = Vec::new();
+    if output.encoder == ExportEncoder::Qsv {
+        // hwupload needs an explicit QSV device; hwupload_cuda opens its own
+        args.extend(["-init_hw_device", "qsv=hw", "-filter_hw_device", "hw"].iter().map(|s| s.to_string()));
+    }
+    args.extend(["-c:v".to_string(), output.encoder.encoder_name().to_string()]);
+    match output.encoder {
+        ExportEncoder::Nvenc => {
+            let preset = match quality {
+                ExportQuality::High => "p6",
+                ExportQuality::Medium => "p4",
+                ExportQuality::Low => "p2",
+            };
+            args.extend(["-preset".to_string(), preset.to_string(), "-rc".to_string(), "vbr".to_string()]);
+            if target_kbps.is_none() {
+                args.extend(["-cq".to_string(), quality.crf().to_string(), "-b:v".to_string(), "0".to_string()]);
+            }
+        }
+        _ => {
+            args.extend(["-preset".to_string(), quality.preset().to_string()]);
+            if target_kbps.is_none() {
+                args.extend(["-global_quality".to_string(), quality.crf().to_string()]);
+            }
+        }
+    }
+    if let Some(kbps) = target_kbps {
+        args.extend([
+            "-b:v".to_string(), format!("{}k", kbps),
+            "-maxrate".to_string(), format!("{}k", kbps),
+            "-bufsize".to_string(), format!("{}k", kbps * 2),
+        ]);
+    }
+    // Frames arrive on the GPU already in NV12, so no -pix_fmt here
+    args.extend(["-r".to_string(), fps.to_string()]);
+    args
+}
+
+/// Resize and GPU upload around the text overlays: `(before, after)`.
+///
+/// With a hardware encoder the frames are uploaded at the end of the chain so
+/// the encoder reads GPU memory. The resize moves to the GPU too when nothing
+/// is drawn after it and it needs no padding or cropping (CPU-only filters).
+pub(crate) fn output_stage_filters(output: &OutputSettings, has_text_overlays: bool) -> (Vec<String>, Vec<String>) {
+    let upload = match output.encoder {
+        ExportEncoder::Software => return (output_scale_filter(output).into_iter().collect(), Vec::new()),
+        ExportEncoder::Nvenc => "format=nv12,hwupload_cuda",
+        ExportEncoder::Qsv => "format=nv12,hwupload=extra_hw_frames=64",
+    };
+    let gpu_scale = match (output.width, output.height) {
+        // The sharpening pass runs on the CPU anyway
+        _ if output.upscale => None,
+        (Some(_), Some(_)) | (None, None) => None,
+        (w, h) => {
+            let (w, h) = (w.map(|v| v as i64).unwrap_or(-2), h.map(|v| v as i64).unwrap_or(-2));
+            Some(match output.encoder {
+                ExportEncoder::Qsv => format!("scale_qsv=w={w}:h={h}"),
+                _ => format!("scale_cuda={w}:{h}"),
+            })
+        }
+    };
+    match gpu_scale {
+        Some(scale) if !has_text_overlays => (Vec::new(), vec![format!("{upload},{scale}")]),
+        _ => (output_scale_filter(output).into_iter().collect(), vec![upload.to_string()]),
+    }
+}
+
+fn watermark_filter(tone: WatermarkTone) -> String {
+    let (color, shadow) = match tone {
+        WatermarkTone::Light => ("white@0.7", "black@0.5"),
+        WatermarkTone::Dark => ("black@0.6", "white@0.4"),
+    };
+    format!(
+        "drawtext={}text='ClipFlow':fontsize=28:fontcolor={color}:shadowcolor={shadow}:shadowx=2:shadowy=2:x=w-tw-20:y=h-th-16",
+        fonts::fontfile_arg(None)
+    )
+}
 
 fn all_cuts(transitions: &[Transition]) -> bool {
     transitions.iter().all(|t| t.transition_type == TransitionType::Cut)
@@ -104,48 +354,103 @@ fn all_cuts(transitions: &[Transition]) -> bool {
 
 // ──────────────────────────────── Helper: text escaping ────────────────────────────────
 
-fn escape_drawtext(text: &str) -> String {
+pub(crate) fn escape_drawtext(text: &str) -> String {
     text.replace('\\', "\\\\")
         .replace('\'', "'\\\\\\''")
         .replace(':', "\\:")
         .replace('%', "%%")
 }
 
-// ──────────────────────────────── Helper: piecewise linear interpolation ────────────────
+// ──────────────────────────────── Keyframe animation ────────────────────────────────
 
-/// Build FFmpeg expression for piecewise linear interpolation over keyframes.
-/// Keyframes: (time_seconds, value). Commas escaped for filter_complex.
-fn build_piecewise_lerp(keyframes: &[(f64, f64)]) -> String {
-    if keyframes.is_empty() {
-        return "0".to_string();
+/// Point of an animation curve: (time_seconds, value, easing of the segment it starts)
+pub(crate) type CurvePoint = (f64, f64, Easing);
+
+/// Curve of one property, in seconds relative to `offset_ms`, sorted by time.
+pub(crate) fn property_curve(keyframes: &[Keyframe], property: AnimatedProperty, offset_ms: u64) -> Vec<CurvePoint> {
+    let offset_s = offset_ms as f64 / 1000.0;
+    let mut points: Vec<CurvePoint> = keyframes
+        .iter()
+        .filter(|k| k.property == property)
+        .map(|k| (k.time_ms as f64 / 1000.0 - offset_s, k.value, k.easing))
+        .collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    points
+}
+
+/// Eased progress for a linear 0-1 progress expression `p`.
+fn ease_expr(easing: Easing, p: &str) -> String {
+    match easing {
+        Easing::Linear => p.to_string(),
+        Easing::EaseIn => format!("{p}*{p}"),
+        Easing::EaseOut => format!("{p}*(2-{p})"),
+        Easing::EaseInOut => format!("if(lt({p}\\,0.5)\\,2*{p}*{p}\\,1-2*(1-{p})*(1-{p}))"),
+        Easing::Hold => "0".to_string(),
     }
-    if keyframes.len() == 1 {
-        return format!("{:.4}", keyframes[0].1);
+}
+
+/// Eased progress, evaluated in Rust (matches `ease_expr`).
+fn ease(easing: Easing, p: f64) -> f64 {
+    match easing {
+        Easing::Linear => p,
+        Easing::EaseIn => p * p,
+        Easing::EaseOut => p * (2.0 - p),
+        Easing::EaseInOut => if p < 0.5 { 2.0 * p * p } else { 1.0 - 2.0 * (1.0 - p) * (1.0 - p) },
+        Easing::Hold => 0.0,
     }
+}
+
+/// Build the FFmpeg expression of an animation curve over the time variable `var`.
+/// The value is held before the first and after the last point. Commas escaped for filter_complex.
+pub(crate) fn build_keyframe_expr(points: &[CurvePoint], var: &str) -> String {
+    let Some(last) = points.last() else {
+        return "0".to_string();
+    };
 
     // Build from the end (last value is the fallback)
-    let mut expr = format!("{:.4}", keyframes.last().unwrap().1);
+    let mut expr = format!("{:.4}", last.1);
 
-    for i in (0..keyframes.len() - 1).rev() {
-        let (t0, v0) = keyframes[i];
-        let (t1, _v1) = keyframes[i + 1];
+    for i in (0..points.len() - 1).rev() {
+        let (t0, v0, easing) = points[i];
+        let (t1, v1, _) = points[i + 1];
         let dt = t1 - t0;
         if dt < 0.001 { continue; }
 
-        let v_next = keyframes[i + 1].1;
-        let dv = v_next - v0;
-        let lerp = if dv.abs() < 0.0001 {
+        let dv = v1 - v0;
+        let segment = if dv.abs() < 0.0001 || easing == Easing::Hold {
             format!("{:.4}", v0)
         } else {
-            format!("{:.4}+{:.4}*(t-{:.2})/{:.2}", v0, dv, t0, dt)
+            let p = format!("(({var}-{:.3})/{:.3})", t0, dt);
+            format!("{:.4}+{:.4}*{}", v0, dv, ease_expr(easing, &p))
         };
 
-        expr = format!("if(lt(t\\,{:.2})\\,{}\\,{})", t1, lerp, expr);
+        expr = format!("if(lt({var}\\,{:.3})\\,{}\\,{})", t1, segment, expr);
+    }
+
+    // Hold the first value until the first keyframe
+    if points.len() > 1 && points[0].0 > 0.001 {
+        expr = format!("if(lt({var}\\,{:.3})\\,{:.4}\\,{})", points[0].0, points[0].1, expr);
     }
 
     expr
 }
 
+/// Value of an animation curve at `t` (same rules as `build_keyframe_expr`).
+pub(crate) fn curve_value(points: &[CurvePoint], t: f64) -> Option<f64> {
+    let first = points.first()?;
+    if t < first.0 {
+        return Some(first.1);
+    }
+    for w in points.windows(2) {
+        let (t0, v0, easing) = w[0];
+        let (t1, v1, _) = w[1];
+        if t < t1 && t1 - t0 >= 0.001 {
+            return Some(v0 + (v1 - v0) * ease(easing, (t - t0) / (t1 - t0)));
+        }
+    }
+    points.last().map(|p| p.1)
+}
+
 // ──────────────────────────────── Cursor Zoom ────────────────────────────────
 
 /// Build a crop+scale filter that follows cursor position with smooth panning.
@@ -159,8 +464,6 @@ fn build_cursor_zoom_filter(
         return None;
     }
 
-    let trim_s = trim_start_ms as f64 / 1000.0;
-
     // Check if cursor actually moves significantly (>10% of screen in X or Y)
     let min_x = positions.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
     let max_x = positions.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
@@ -173,34 +476,33 @@ fn build_cursor_zoom_filter(
     let half_inv = 1.0 / (2.0 * CURSOR_ZOOM);
     let max_frac = 1.0 - 1.0 / CURSOR_ZOOM;
 
-    // Group positions by second, average within each second
-    let mut second_groups: std::collections::BTreeMap<i64, Vec<(f64, f64)>> = std::collections::BTreeMap::new();
-    for pos in positions {
-        let t = pos.timestamp_ms as f64 / 1000.0 - trim_s;
-        if t < 0.0 { continue; }
-        let sec = t.floor() as i64;
-        second_groups.entry(sec).or_default().push((pos.x, pos.y));
-    }
-
-    let mut kf_x: Vec<(f64, f64)> = Vec::new();
-    let mut kf_y: Vec<(f64, f64)> = Vec::new();
-
-    for (sec, group) in &second_groups {
-        if kf_x.len() >= 30 { break; }
-        let avg_x = group.iter().map(|p| p.0).sum::<f64>() / group.len() as f64;
-        let avg_y = group.iter().map(|p| p.1).sum::<f64>() / group.len() as f64;
-        let x = (avg_x - half_inv).clamp(0.0, max_frac);
-        let y = (avg_y - half_inv).clamp(0.0, max_frac);
-        kf_x.push((*sec as f64, x));
-        kf_y.push((*sec as f64, y));
+    // Crop origin once per second of the trimmed clip, read off the
+    // (decimated) recorded path
+    let last_ms = positions.iter().map(|p| p.timestamp_ms).max().unwrap_or(0);
+    if last_ms < trim_start_ms {
+        return None;
     }
+    let camera: Vec<CursorPosition> = (0..=(last_ms - trim_start_ms) / 1000)
+        .filter_map(|sec| {
+            let (x, y) = crate::capture::cursor::position_at(positions, trim_start_ms + sec * 1000)?;
+            Some(CursorPosition {
+                timestamp_ms: sec * 1000,
+                x: (x - half_inv).clamp(0.0, max_frac),
+                y: (y - half_inv).clamp(0.0, max_frac),
+            })
+        })
+        .collect();
 
-    if kf_x.is_empty() {
+    // Thin the camera path until the expressions stay small
+    let kept = crate::capture::cursor::thin(&camera, CURSOR_PATH_TOLERANCE, MAX_CURSOR_KEYFRAMES);
+    if kept.is_empty() {
         return None;
     }
+    let kf_x: Vec<CurvePoint> = kept.iter().map(|p| (p.timestamp_ms as f64 / 1000.0, p.x, Easing::Linear)).collect();
+    let kf_y: Vec<CurvePoint> = kept.iter().map(|p| (p.timestamp_ms as f64 / 1000.0, p.y, Easing::Linear)).collect();
 
-    let x_expr = build_piecewise_lerp(&kf_x);
-    let y_expr = build_piecewise_lerp(&kf_y);
+    let x_expr = build_keyframe_expr(&kf_x, "t");
+    let y_expr = build_keyframe_expr(&kf_y, "t");
 
     Some(format!(
         "crop=w=iw/{z:.1}:h=ih/{z:.1}:x='({x})*iw':y='({y})*ih':exact=1,scale={w}:{h}",
@@ -208,6 +510,48 @@ fn build_cursor_zoom_filter(
     ))
 }
 
+/// Build a zoompan filter from a clip's manual zoom/pan keyframes.
+/// Needs at least one zoom_scale keyframe; the center defaults to the middle of the frame.
+fn build_manual_zoom_filter(
+    keyframes: &[Keyframe],
+    trim_start_ms: u64,
+    width: u32,
+    height: u32,
+    fps: u32,
+) -> Option<String> {
+    let scale = property_curve(keyframes, AnimatedProperty::ZoomScale, trim_start_ms);
+    if scale.is_empty() {
+        return None;
+    }
+    let center = |property| {
+        let curve = property_curve(keyframes, property, trim_start_ms);
+        if curve.is_empty() { "0.5".to_string() } else { build_keyframe_expr(&curve, "it") }
+    };
+    let z = build_keyframe_expr(&scale, "it");
+    let cx = center(AnimatedProperty::ZoomX);
+    let cy = center(AnimatedProperty::ZoomY);
+
+    Some(format!(
+        "zoompan=z='max(1\\,{z})'\
+        :x='max(0\\,min(iw-iw/zoom\\,({cx})*iw-iw/zoom/2))'\
+        :y='max(0\\,min(ih-ih/zoom\\,({cy})*ih-ih/zoom/2))'\
+        :d=1:s={width}x{height}:fps={fps}"
+    ))
+}
+
+/// Zoom of a clip: its manual keyframes when set, the cursor-following zoom otherwise.
+fn build_clip_zoom_filter(
+    clip: &Clip,
+    cursor_positions: Option<&Vec<CursorPosition>>,
+    width: u32,
+    height: u32,
+    fps: u32,
+) -> Option<String> {
+    build_manual_zoom_filter(&clip.zoom_keyframes, clip.media.trim_start_ms, width, height, fps).or_else(|| {
+        cursor_positions.and_then(|positions| build_cursor_zoom_filter(positions, clip.media.trim_start_ms, width, height))
+    })
+}
+
 // ──────────────────────────────── Annotations ────────────────────────────────
 
 /// Convert annotation color (hex or name) to FFmpeg color string.
@@ -219,6 +563,38 @@ fn annotation_color_ffmpeg(color: &str) -> String {
     }
 }
 
+/// Alpha of drawn shapes at full opacity
+pub(crate) const ANNOTATION_ALPHA: f64 = 0.8;
+/// Sampling step of animated shape opacity (drawbox colors can't be expressions)
+const OPACITY_STEP_S: f64 = 0.25;
+
+/// Time ranges (start_s, end_s, opacity) an annotation is drawn with.
+/// Without opacity keyframes the annotation is fully visible over its whole range.
+pub(crate) fn opacity_segments(ann: &Annotation) -> Vec<(f64, f64, f64)> {
+    let start_s = ann.start_ms as f64 / 1000.0;
+    let end_s = ann.end_ms as f64 / 1000.0;
+    let curve = property_curve(&ann.keyframes, AnimatedProperty::Opacity, 0);
+    if curve.is_empty() {
+        return vec![(start_s, end_s, 1.0)];
+    }
+
+    let mut segments: Vec<(f64, f64, f64)> = Vec::new();
+    let mut t = start_s;
+    while t < end_s {
+        let t_end = (t + OPACITY_STEP_S).min(end_s);
+        let opacity = curve_value(&curve, (t + t_end) / 2.0).unwrap_or(1.0).clamp(0.0, 1.0);
+        // 5% steps keep neighbouring samples mergeable
+        let opacity = (opacity * 20.0).round() / 20.0;
+        match segments.last_mut() {
+            Some(last) if (last.2 - opacity).abs() < 1e-9 => last.1 = t_end,
+            _ => segments.push((t, t_end, opacity)),
+        }
+        t = t_end;
+    }
+    segments.retain(|seg| seg.2 > 0.0);
+    segments
+}
+
 /// Build drawbox / drawtext filters for annotations on a single clip.
 fn build_annotation_draw_filters(
     annotations: &[Annotation],
@@ -227,57 +603,69 @@ fn build_annotation_draw_filters(
 ) -> Vec<String> {
     let mut filters = Vec::new();
 
-    for ann in annotations {
-        let start_s = ann.start_ms as f64 / 1000.0;
-        let end_s = ann.end_ms as f64 / 1000.0;
-        let enable = format!("enable='between(t\\,{:.3}\\,{:.3})'", start_s, end_s);
-
+    for ann in paint_order(annotations) {
         let px = (ann.x * width as f64) as i32;
         let py = (ann.y * height as f64) as i32;
         let pw = (ann.width * width as f64).max(4.0) as u32;
         let ph = (ann.height * height as f64).max(4.0) as u32;
         let stroke = ann.stroke_width.max(2.0) as u32;
-        let color = annotation_color_ffmpeg(&ann.color);
 
-        match ann.kind {
-            AnnotationKind::Rectangle => {
-                filters.push(format!(
-                    "drawbox=x={px}:y={py}:w={pw}:h={ph}:color={color}@0.8:t={stroke}:{enable}"
-                ));
-            }
-            AnnotationKind::Circle => {
-                // Approximate circle with a square outline
-                let cx = px + pw as i32 / 2;
-                let cy = py + ph as i32 / 2;
-                let r = pw.min(ph) / 2;
-                let bx = (cx - r as i32).max(0) as u32;
-                let by = (cy - r as i32).max(0) as u32;
+        // drawtext takes the opacity curve as an expression
+        if ann.kind == AnnotationKind::Text {
+            if let Some(ref text) = ann.text {
+                let start_s = ann.start_ms as f64 / 1000.0;
+                let end_s = ann.end_ms as f64 / 1000.0;
+                let enable = format!("enable='between(t\\,{:.3}\\,{:.3})'", start_s, end_s);
+                let escaped = escape_drawtext(text);
+                let fontsize = (ann.height * height as f64 * 0.7).max(16.0) as u32;
+                let font = fonts::fontfile_arg(None);
+                let color = annotation_color_ffmpeg(&ann.color);
+                let curve = property_curve(&ann.keyframes, AnimatedProperty::Opacity, 0);
+                let alpha = if curve.is_empty() {
+                    String::new()
+                } else {
+                    format!(":alpha='clip({}\\,0\\,1)'", build_keyframe_expr(&curve, "t"))
+                };
                 filters.push(format!(
-                    "drawbox=x={bx}:y={by}:w={d}:h={d}:color={color}@0.8:t={stroke}:{enable}",
-                    d = r * 2
+                    "drawtext={font}text='{escaped}':x={px}:y={py}:fontsize={fontsize}:fontcolor={color}{alpha}:{enable}"
                 ));
             }
-            AnnotationKind::Text => {
-                if let Some(ref text) = ann.text {
-                    let escaped = escape_drawtext(text);
-                    let fontsize = (ann.height * height as f64 * 0.7).max(16.0) as u32;
+            continue;
+        }
+
+        // Shapes are drawn once per constant-opacity range
+        for (start_s, end_s, opacity) in opacity_segments(ann) {
+            let enable = format!("enable='between(t\\,{:.3}\\,{:.3})'", start_s, end_s);
+            let color = format!("{}@{:.2}", annotation_color_ffmpeg(&ann.color), ANNOTATION_ALPHA * opacity);
+
+            match ann.kind {
+                AnnotationKind::Rectangle => {
                     filters.push(format!(
-                        "drawtext=text='{escaped}':x={px}:y={py}:fontsize={fontsize}:fontcolor={color}:{enable}"
+                        "drawbox=x={px}:y={py}:w={pw}:h={ph}:color={color}:t={stroke}:{enable}"
                     ));
                 }
-            }
-            AnnotationKind::Arrow | AnnotationKind::Freehand => {
-                // Arrow: draw a thick line approximation using drawbox
-                if ann.kind == AnnotationKind::Arrow {
+                AnnotationKind::Circle => {
+                    // Approximate circle with a square outline
+                    let cx = px + pw as i32 / 2;
+                    let cy = py + ph as i32 / 2;
+                    let r = pw.min(ph) / 2;
+                    let bx = (cx - r as i32).max(0) as u32;
+                    let by = (cy - r as i32).max(0) as u32;
+                    filters.push(format!(
+                        "drawbox=x={bx}:y={by}:w={d}:h={d}:color={color}:t={stroke}:{enable}",
+                        d = r * 2
+                    ));
+                }
+                AnnotationKind::Arrow => {
                     // Draw the shaft as a thin horizontal/diagonal box
                     let thick = stroke.max(3);
                     filters.push(format!(
-                        "drawbox=x={px}:y={}:w={pw}:h={thick}:color={color}@0.8:t=fill:{enable}",
+                        "drawbox=x={px}:y={}:w={pw}:h={thick}:color={color}:t=fill:{enable}",
                         py + ph as i32 / 2 - thick as i32 / 2
                     ));
                 }
-                // Freehand: draw connected segments as small boxes along the path
-                if ann.kind == AnnotationKind::Freehand {
+                AnnotationKind::Freehand => {
+                    // Draw connected segments as small boxes along the path
                     if let Some(ref points) = ann.points {
                         let dot_size = stroke.max(3);
                         // Draw every 3rd point to keep filter manageable
@@ -285,11 +673,12 @@ fn build_annotation_draw_filters(
                             let dx = (pt.0 * width as f64) as i32;
                             let dy = (pt.1 * height as f64) as i32;
                             filters.push(format!(
-                                "drawbox=x={dx}:y={dy}:w={dot_size}:h={dot_size}:color={color}@0.8:t=fill:{enable}"
+                                "drawbox=x={dx}:y={dy}:w={dot_size}:h={dot_size}:color={color}:t=fill:{enable}"
                             ));
                         }
                     }
                 }
+                AnnotationKind::Text => {}
             }
         }
     }
@@ -299,41 +688,58 @@ fn build_annotation_draw_filters(
 
 // ──────────────────────────────── Keystrokes / Subtitles ────────────────────────────────
 
-fn build_keystroke_filters(
-    events: &[KeystrokeEvent],
-    time_offset: f64,
-    trim_start_ms: u64,
-) -> Vec<String> {
-    let mut filters = Vec::new();
-    let trim_offset = trim_start_ms as f64 / 1000.0;
-
-    let events_to_use = if events.len() > 150 { &events[..150] } else { events };
+/// How long a keystroke label stays on screen
+pub(crate) const KEYSTROKE_DISPLAY_S: f64 = 1.8;
 
-    // Group rapid keystrokes (within 80ms) into combos displayed together
+/// Group rapid keystrokes (within 80ms) into combos displayed together: (time, label)
+pub(crate) fn keystroke_groups(events: &[KeystrokeEvent], time_offset: f64, trim_start_ms: u64) -> Vec<(f64, String)> {
+    let trim_offset = trim_start_ms as f64 / 1000.0;
     let mut groups: Vec<(f64, String)> = Vec::new();
-    for event in events_to_use {
+    for event in events {
         let t = event.timestamp_ms as f64 / 1000.0 - trim_offset + time_offset;
         if t < 0.0 { continue; }
-        let key = escape_drawtext(&event.key_name);
         if let Some(last) = groups.last_mut() {
             if (t - last.0) < 0.08 {
                 last.1.push_str("  ");
-                last.1.push_str(&key);
+                last.1.push_str(&event.key_name);
                 continue;
             }
         }
-        groups.push((t, key));
+        groups.push((t, event.key_name.clone()));
     }
+    groups
+}
 
-    for (t, label) in &groups {
-        let end_t = t + 1.8;
+fn build_keystroke_filters(
+    events: &[KeystrokeEvent],
+    time_offset: f64,
+    trim_start_ms: u64,
+    font_file: Option<&str>,
+    safe_area: SafeArea,
+) -> Vec<String> {
+    let mut filters = Vec::new();
+    let font = fonts::fontfile_arg(font_file);
+    let x = safe_area::inset("30", '+', safe_area.left, "w");
+    let y = safe_area::inset("h-80", '-', safe_area.bottom, "h");
+
+    // One drawtext per label: cap them, the overlay renderer has no such limit
+    let events_to_use = if events.len() > MAX_KEYSTROKE_DRAWTEXT {
+        tracing::warn!("Drawing the first {} of {} keystrokes only", MAX_KEYSTROKE_DRAWTEXT, events.len());
+        &events[..MAX_KEYSTROKE_DRAWTEXT]
+    } else {
+        events
+    };
+
+    for (t, label) in keystroke_groups(events_to_use, time_offset, trim_start_ms) {
+        let label = escape_drawtext(&label);
+        let end_t = t + KEYSTROKE_DISPLAY_S;
         // Modern style: larger font, semi-transparent dark pill, bottom-left, subtle shadow
         filters.push(format!(
-            "drawtext=text='{label}'\
+            "drawtext={font}text='{label}'\
             :fontsize=36\
             :fontcolor=white\
             :box=1:boxcolor=black@0.55:boxborderw=16\
-            :x=30:y=h-80\
+            :x={x}:y={y}\
             :shadowcolor=black@0.4:shadowx=2:shadowy=2\
             :enable='between(t\\,{t:.3}\\,{end_t:.3})'"
         ));
@@ -341,8 +747,9 @@ fn build_keystroke_filters(
     filters
 }
 
-fn build_subtitle_filters(subtitles: &[Subtitle]) -> Vec<String> {
+fn build_subtitle_filters(subtitles: &[Subtitle], safe_area: SafeArea) -> Vec<String> {
     let mut filters = Vec::new();
+    let x_expr = safe_area::centered("w", "tw", safe_area.left, safe_area.right);
 
     for sub in subtitles {
         let start_s = sub.start_ms as f64 / 1000.0;
@@ -350,15 +757,16 @@ fn build_subtitle_filters(subtitles: &[Subtitle]) -> Vec<String> {
         let escaped = escape_drawtext(&sub.text);
 
         let y_expr = match sub.position {
-            SubtitlePosition::Top => "30",
-            SubtitlePosition::Center => "(h-th)/2",
-            SubtitlePosition::Bottom => "h-th-30",
+            SubtitlePosition::Top => safe_area::inset("30", '+', safe_area.top, "h"),
+            SubtitlePosition::Center => safe_area::centered("h", "th", safe_area.top, safe_area.bottom),
+            SubtitlePosition::Bottom => safe_area::inset("h-th-30", '-', safe_area.bottom, "h"),
         };
         let fontsize = if sub.font_size > 0 { sub.font_size } else { 32 };
         let color = annotation_color_ffmpeg(&sub.color);
+        let font = fonts::fontfile_arg(sub.font_file.as_deref());
 
         filters.push(format!(
-            "drawtext=text='{escaped}':fontsize={fontsize}:fontcolor={color}:box=1:boxcolor=black@0.6:boxborderw=8:x=(w-tw)/2:y={y_expr}:enable='between(t\\,{start_s:.3}\\,{end_s:.3})'"
+            "drawtext={font}text='{escaped}':fontsize={fontsize}:fontcolor={color}:box=1:boxcolor=black@0.6:boxborderw=8:x={x_expr}:y={y_expr}:enable='between(t\\,{start_s:.3}\\,{end_s:.3})'"
         ));
     }
     filters
@@ -366,9 +774,25 @@ fn build_subtitle_filters(subtitles: &[Subtitle]) -> Vec<String> {
 
 // ──────────────────────────────── Audio helpers ────────────────────────────────
 
-fn build_audio_concat_filter(
+/// Shift of a clip's audio against its video, in source time: a positive
+/// offset delays the audio, a negative one starts it further in its file
+fn audio_offset_filter(offset_ms: i64) -> Option<String> {
+    if offset_ms > 0 {
+        Some(format!("adelay={offset_ms}:all=1"))
+    } else if offset_ms < 0 {
+        Some(format!("atrim=start={:.3},asetpts=PTS-STARTPTS", offset_ms.unsigned_abs() as f64 / 1000.0))
+    } else {
+        None
+    }
+}
+
+/// Audio of each clip, its tracks mixed, one after the other into `[aout]`.
+/// `offsets_ms` holds each clip's audio offset; a shifted track is padded
+/// or cut back to its clip's duration so the next clip stays in sync.
+pub(crate) fn build_audio_concat_filter(
     audio_input_map: &[(usize, Vec<usize>)],
     eff_durations: &[f64],
+    offsets_ms: &[i64],
     system_volume: f32,
     mic_volume: f32,
 ) -> String {
@@ -377,8 +801,24 @@ fn build_audio_concat_filter(
     let n = audio_input_map.len();
 
     for (ci, (_clip_idx, indices)) in audio_input_map.iter().enumerate() {
+        let mut sources = Vec::new();
+        let dur = eff_durations.get(ci).copied().unwrap_or(1.0);
+        let shift = offsets_ms
+            .get(ci)
+            .copied()
+            .and_then(audio_offset_filter)
+            .map(|shift| format!("{shift},apad,atrim=duration={dur:.3}"));
+        for (j, idx) in indices.iter().enumerate() {
+            match &shift {
+                Some(shift) => {
+                    filters.push(format!("[{idx}:a]{shift}[ao{ci}_{j}]"));
+                    sources.push(format!("[ao{ci}_{j}]"));
+                }
+                None => sources.push(format!("[{idx}:a]")),
+            }
+        }
+
         if indices.is_empty() {
-            let dur = eff_durations.get(ci).copied().unwrap_or(1.0);
             filters.push(format!(
                 "anullsrc=channel_layout=stereo:sample_rate=44100,atrim=duration={dur:.3}[a{ci}]"
             ));
@@ -387,22 +827,22 @@ fn build_audio_concat_filter(
             // First audio track is usually system, second is mic
             let vol = if ci == 0 || indices[0] % 2 == 1 { system_volume } else { mic_volume };
             if (vol - 1.0).abs() > 0.01 {
-                filters.push(format!("[{}:a]volume={:.2}[a{ci}]", indices[0], vol));
+                filters.push(format!("{}volume={:.2}[a{ci}]", sources[0], vol));
             } else {
-                filters.push(format!("[{}:a]anull[a{ci}]", indices[0]));
+                filters.push(format!("{}anull[a{ci}]", sources[0]));
             }
             has_any = true;
         } else {
             // Multiple audio sources (system + mic) — apply per-source volume before mixing
             let mut vol_inputs = Vec::new();
-            for (j, idx) in indices.iter().enumerate() {
+            for (j, src) in sources.iter().enumerate() {
                 let vol = if j == 0 { system_volume } else { mic_volume };
                 if (vol - 1.0).abs() > 0.01 {
                     let label = format!("av{ci}_{j}");
-                    filters.push(format!("[{}:a]volume={:.2}[{label}]", idx, vol));
+                    filters.push(format!("{src}volume={:.2}[{label}]", vol));
                     vol_inputs.push(format!("[{label}]"));
                 } else {
-                    vol_inputs.push(format!("[{}:a]", idx));
+                    vol_inputs.push(src.clone());
                 }
             }
             let mix_inputs: String = vol_inputs.join("");
@@ -425,44 +865,50 @@ fn build_audio_concat_filter(
 
 // ──────────────────────────────── Per-clip filter chain ────────────────────────────────
 
+/// Decode-accurate trim of a clip's video (or audio) stream, timestamps reset to 0
+fn trim_filter(clip: &Clip, audio: bool) -> Option<String> {
+    let (trim, setpts) = if audio { ("atrim", "asetpts") } else { ("trim", "setpts") };
+    let start_s = clip.media.trim_start_ms as f64 / 1000.0;
+    let end_s = clip.media.trim_end_ms as f64 / 1000.0;
+    let range = match (clip.media.trim_start_ms > 0, clip.media.trim_end_ms > 0) {
+        (true, true) => format!("start={start_s:.3}:end={end_s:.3}"),
+        (true, false) => format!("start={start_s:.3}"),
+        (false, true) => format!("end={end_s:.3}"),
+        (false, false) => return None,
+    };
+    Some(format!("{trim}={range},{setpts}=PTS-STARTPTS"))
+}
+
+/// gdigrab on an HDR desktop gets washed-out SDR frames: linearize them,
+/// tonemap back the contrast and return to BT.709
+pub(crate) const HDR_TONEMAP: &str = "zscale=t=linear:npl=100,format=gbrpf32le,tonemap=hable:desat=0,zscale=t=bt709:m=bt709:r=tv,format=yuv420p";
+
 /// Build the filter chain for a single clip inside filter_complex:
-/// trim → scale+pad → cursor_zoom → annotations → [si]
-fn build_clip_chain(
+/// trim → hdr tonemap → scale+pad → zoom → annotations or overlay track → [si]
+pub(crate) fn build_clip_chain(
     i: usize,
     clip: &Clip,
     max_w: u32,
     max_h: u32,
+    output: &OutputSettings,
     cursor_positions: Option<&Vec<CursorPosition>>,
     annotations: Option<&Vec<Annotation>>,
+    overlay_input: Option<usize>,
 ) -> String {
-    let has_trim = clip.trim_start_ms > 0 || clip.trim_end_ms > 0;
-
-    // 1. Trim
-    let trim_part = if has_trim {
-        let start_s = clip.trim_start_ms as f64 / 1000.0;
-        let end_s = clip.trim_end_ms as f64 / 1000.0;
-        if clip.trim_start_ms > 0 && clip.trim_end_ms > 0 {
-            format!("trim=start={start_s:.3}:end={end_s:.3},setpts=PTS-STARTPTS,")
-        } else if clip.trim_start_ms > 0 {
-            format!("trim=start={start_s:.3},setpts=PTS-STARTPTS,")
-        } else {
-            format!("trim=end={end_s:.3},setpts=PTS-STARTPTS,")
-        }
-    } else {
-        String::new()
-    };
+    // 1. Trim, then HDR correction while still in the capture's colors
+    let trim_part = trim_filter(clip, false).map(|t| format!("{t},")).unwrap_or_default();
+    let hdr_part = if output.tonemap_hdr && clip.media.hdr { format!("{HDR_TONEMAP},") } else { String::new() };
+    let fps = output.fps.unwrap_or(DEFAULT_FPS);
 
     // 2. Scale + pad + setsar + fps
     let mut chain = format!(
-        "[{i}:v]{trim_part}scale={max_w}:{max_h}:force_original_aspect_ratio=decrease,pad={max_w}:{max_h}:(ow-iw)/2:(oh-ih)/2,setsar=1,fps=30"
+        "[{i}:v]{trim_part}{hdr_part}scale={max_w}:{max_h}:force_original_aspect_ratio=decrease,pad={max_w}:{max_h}:(ow-iw)/2:(oh-ih)/2,setsar=1,fps={fps}"
     );
 
-    // 3. Cursor zoom (crop + scale)
-    if let Some(positions) = cursor_positions {
-        if let Some(zoom_filter) = build_cursor_zoom_filter(positions, clip.trim_start_ms, max_w, max_h) {
-            chain.push(',');
-            chain.push_str(&zoom_filter);
-        }
+    // 3. Manual zoom/pan or cursor zoom
+    if let Some(zoom_filter) = build_clip_zoom_filter(clip, cursor_positions, max_w, max_h, fps) {
+        chain.push(',');
+        chain.push_str(&zoom_filter);
     }
 
     // 4. Annotations (drawbox, drawtext)
@@ -473,10 +919,68 @@ fn build_clip_chain(
         }
     }
 
-    chain.push_str(&format!("[s{i}]"));
+    // 5. Rendered overlay track, gone once its last frame has played
+    match overlay_input {
+        Some(k) => chain.push_str(&format!("[pre{i}];[pre{i}][{k}:v]overlay=0:0:eof_action=pass:format=auto[s{i}]")),
+        None => chain.push_str(&format!("[s{i}]")),
+    }
     chain
 }
 
+// ──────────────────────────────── Filter graph script ────────────────────────────────
+
+/// Filter graph passed through `-filter_complex_script` instead of the command line,
+/// which long timelines push past the Windows length limit.
+/// The file is deleted on drop unless kept for debugging.
+pub(crate) struct FilterScript {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl FilterScript {
+    /// Write the `-filter_complex` graph of `args` to `path` and point FFmpeg at the file.
+    pub(crate) fn spill(args: &mut [String], path: PathBuf, keep: bool) -> Result<Option<Self>> {
+        let Some(pos) = args.iter().position(|a| a == "-filter_complex") else {
+            return Ok(None);
+        };
+        let Some(graph) = args.get(pos + 1) else {
+            return Ok(None);
+        };
+        std::fs::write(&path, graph)
+            .with_context(|| crate::i18n::tf("filter_script_write_failed", &[&path.display()]))?;
+        if keep {
+            tracing::info!("Filter graph kept at {:?}", path);
+        }
+        args[pos] = "-filter_complex_script".into();
+        args[pos + 1] = path.to_string_lossy().to_string();
+        Ok(Some(Self { path, keep }))
+    }
+}
+
+impl Drop for FilterScript {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Scratch file `<stem>_<id>.<extension>` for an intermediate of `output_path`,
+/// unique per call so concurrent exports of the same name don't collide.
+pub(crate) fn scratch_path(output_path: &Path, extension: &str) -> PathBuf {
+    let dir = crate::storage::export_dir();
+    let _ = std::fs::create_dir_all(&dir);
+    let stem = output_path.file_stem().unwrap_or_default().to_string_lossy();
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    dir.join(format!("{}_{}.{}", stem, &id[..8], extension))
+}
+
+/// Script file of the filter graph rendering `output_path`, in the temp dir
+/// (its path is logged when kept).
+pub(crate) fn filter_script_path(output_path: &Path) -> PathBuf {
+    scratch_path(output_path, "filtergraph.txt")
+}
+
 // ──────────────────────────────── Filter complex builder ────────────────────────────────
 
 fn build_filter_complex_with_trim(
@@ -485,21 +989,29 @@ fn build_filter_complex_with_trim(
     transitions: &[Transition],
     max_w: u32,
     max_h: u32,
+    output: &OutputSettings,
     clip_annotations: &HashMap<String, Vec<Annotation>>,
     clip_cursor_positions: &HashMap<String, Vec<CursorPosition>>,
+    overlay_inputs: &HashMap<String, usize>,
 ) -> String {
-    let n = clips.len();
     let mut filters = Vec::new();
 
     // Per-clip processing chains
-    for i in 0..n {
-        let clip = &clips[i];
+    for (i, clip) in clips.iter().enumerate() {
         let cursor = clip_cursor_positions.get(&clip.id);
         let anns = clip_annotations.get(&clip.id);
-        filters.push(build_clip_chain(i, clip, max_w, max_h, cursor, anns));
+        let overlay = overlay_inputs.get(&clip.id).copied();
+        filters.push(build_clip_chain(i, clip, max_w, max_h, output, cursor, anns, overlay));
     }
 
-    // Chain xfade / concat transitions
+    filters.extend(build_transition_chain(eff_durations, transitions));
+    filters.join(";")
+}
+
+/// Chain the `[s0]..[sN-1]` clip streams with xfade / concat, ending on `[v{N-2}]`
+pub(crate) fn build_transition_chain(eff_durations: &[f64], transitions: &[Transition]) -> Vec<String> {
+    let n = eff_durations.len();
+    let mut filters = Vec::new();
     let mut prev_label = "[s0]".to_string();
     let mut cumulative_offset: f64 = 0.0;
 
@@ -537,7 +1049,69 @@ fn build_filter_complex_with_trim(
         prev_label = out_label;
     }
 
-    filters.join(";")
+    filters
+}
+
+/// Timeline annotations, keystrokes, subtitles and watermark over the whole
+/// timeline, drawn on the `canvas` sized output with the keystrokes and
+/// subtitles inside `safe_area`
+pub(crate) fn timeline_text_filters(
+    clips: &[Clip],
+    eff_durations: &[f64],
+    transitions: &[Transition],
+    watermark: Option<WatermarkTone>,
+    clip_keystrokes: &HashMap<String, Vec<KeystrokeEvent>>,
+    keystroke_font: Option<&str>,
+    subtitles: &[Subtitle],
+    global_annotations: &[Annotation],
+    canvas: (u32, u32),
+    safe_area: SafeArea,
+) -> Vec<String> {
+    let mut text_filters: Vec<String> = build_annotation_draw_filters(global_annotations, canvas.0, canvas.1);
+    let mut cumulative_time = 0.0;
+    for (i, clip) in clips.iter().enumerate() {
+        if let Some(events) = clip_keystrokes.get(&clip.id) {
+            text_filters.extend(build_keystroke_filters(events, cumulative_time, clip.media.trim_start_ms, keystroke_font, safe_area));
+        }
+        if i < transitions.len() {
+            if transitions[i].transition_type == TransitionType::Cut {
+                cumulative_time += eff_durations[i];
+            } else {
+                let raw_dur = transitions[i].duration_s;
+                let max_dur = if i + 1 < eff_durations.len() {
+                    eff_durations[i].min(eff_durations[i + 1]) * 0.9
+                } else {
+                    eff_durations[i] * 0.9
+                };
+                let trans_dur = raw_dur.clamp(0.1, max_dur.max(0.1));
+                cumulative_time += eff_durations[i] - trans_dur;
+            }
+        } else {
+            cumulative_time += eff_durations[i];
+        }
+    }
+    text_filters.extend(build_subtitle_filters(subtitles, safe_area));
+    if let Some(tone) = watermark {
+        text_filters.push(watermark_filter(tone));
+    }
+    text_filters
+}
+
+/// Output length once xfades have overlapped their clips
+pub(crate) fn timeline_duration(eff_durations: &[f64], transitions: &[Transition]) -> f64 {
+    let sum: f64 = eff_durations.iter().sum();
+    let trans_sum: f64 = transitions.iter().enumerate()
+        .filter(|(_, t)| t.transition_type != TransitionType::Cut)
+        .map(|(i, t)| {
+            let max_dur = if i + 1 < eff_durations.len() {
+                eff_durations[i].min(eff_durations[i + 1]) * 0.9
+            } else {
+                eff_durations[i] * 0.9
+            };
+            t.duration_s.clamp(0.1, max_dur.max(0.1))
+        })
+        .sum();
+    sum - trans_sum
 }
 
 // ──────────────────────────────── Export MP4 (multi-clip) ────────────────────────────────
@@ -549,55 +1123,152 @@ pub async fn export_mp4(
     app: &AppHandle,
     watermark: bool,
     quality: &ExportQuality,
-    clip_keystrokes: &HashMap<String, Vec<KeystrokeEvent>>,
-    subtitles: &[Subtitle],
-    clip_annotations: &HashMap<String, Vec<Annotation>>,
-    clip_cursor_positions: &HashMap<String, Vec<CursorPosition>>,
-    system_volume: f32,
-    mic_volume: f32,
+    ctx: &ExportContext<'_>,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
 ) -> Result<()> {
     if clips.is_empty() {
-        anyhow::bail!("Aucun clip à exporter");
+        anyhow::bail!(crate::i18n::t("no_clips_to_export"));
     }
     for (i, clip) in clips.iter().enumerate() {
-        if !clip.path.exists() {
-            anyhow::bail!("Fichier du clip {} introuvable : {:?}", i + 1, clip.path);
+        if !clip.media.path.exists() {
+            anyhow::bail!(crate::i18n::tf("clip_n_file_missing", &[&(i + 1), &clip.media.path.display()]));
         }
     }
 
+    let output = &resolve_source_fps(clips, output).await;
+
     if clips.len() == 1 {
-        return export_single_clip(
-            &clips[0], output_path, app, watermark, quality,
-            clip_keystrokes, subtitles, clip_annotations, clip_cursor_positions,
-            system_volume, mic_volume,
-        ).await;
+        return export_single_clip(&clips[0], output_path, app, watermark, quality, ctx, target_size_mb, output).await;
     }
 
     let mut durations = Vec::new();
     for clip in clips {
-        durations.push(probe_duration(&clip.path).await?);
+        durations.push(probe_duration(&clip.media.path).await?);
     }
     let eff_durations: Vec<f64> = clips.iter().zip(durations.iter())
         .map(|(c, d)| effective_duration(c, *d))
         .collect();
 
-    if all_cuts(transitions) {
-        return export_with_concat(
-            clips, &eff_durations, output_path, app, watermark, quality,
-            clip_keystrokes, subtitles, clip_annotations, clip_cursor_positions,
-            system_volume, mic_volume,
+    // Long timelines: one FFmpeg per clip, then a light final pass
+    if super::parallel::worth_it(&eff_durations) {
+        return super::parallel::export(
+            clips, &eff_durations, transitions, output_path, app, watermark, quality, ctx, target_size_mb, output,
         ).await;
     }
 
-    let max_w = (clips.iter().map(|c| c.region.width).max().unwrap_or(1920) / 2) * 2;
-    let max_h = (clips.iter().map(|c| c.region.height).max().unwrap_or(1080) / 2) * 2;
+    if all_cuts(transitions) {
+        return export_with_concat(clips, &eff_durations, output_path, app, watermark, quality, ctx, target_size_mb, output).await;
+    }
+
+    // Annotations and keystrokes as one rasterized track per clip
+    let max_w = (clips.iter().map(|c| c.media.region.width).max().unwrap_or(1920) / 2) * 2;
+    let max_h = (clips.iter().map(|c| c.media.region.height).max().unwrap_or(1080) / 2) * 2;
+    let overlays = OverlayTracks::render(
+        clips, ctx.clip_annotations, ctx.clip_keystrokes, ctx.keystroke_font, output.safe_area, ctx.clip_gamepad, max_w, max_h, output_path,
+    ).await;
+    let mut args = xfade_args(clips, &eff_durations, transitions, output_path, watermark, quality, ctx, target_size_mb, output, &overlays);
+    let total_duration = timeline_duration(&eff_durations, transitions);
+
+    tracing::info!("Output: {:?}", output_path);
+
+    let _script = FilterScript::spill(&mut args, filter_script_path(output_path), output.keep_filter_script)?;
+    let mut child = crate::ffmpeg_command()
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start FFmpeg export")?;
+
+    let mut stderr_log = String::new();
+    if let Some(stderr) = child.stderr.take() {
+        let reader = BufReader::new(stderr);
+        let mut lines = reader.lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            tracing::debug!("{}", line);
+            stderr_log.push_str(&line);
+            stderr_log.push('\n');
+            if let Some(time_str) = extract_time(&line) {
+                let progress = (time_str / total_duration * 100.0).min(100.0);
+                let _ = app.emit("export-progress", progress as u32);
+            }
+        }
+    }
+
+    let status = child.wait().await.context("FFmpeg export failed")?;
+    if !status.success() {
+        tracing::error!("FFmpeg stderr:\n{}", stderr_log);
+        anyhow::bail!("{}", translate_ffmpeg_error(&stderr_log));
+    }
+
+    let _ = app.emit("export-progress", 100u32);
+    Ok(())
+}
+
+/// Arguments `export_mp4` runs when the timeline is rendered in one pass,
+/// found without running anything but ffprobe. The overlay tracks are not
+/// rendered: annotations and keystrokes are drawn with filters instead, as
+/// when their rendering fails.
+pub(crate) async fn plan_mp4(
+    clips: &[Clip],
+    transitions: &[Transition],
+    output_path: &Path,
+    watermark: bool,
+    quality: &ExportQuality,
+    ctx: &ExportContext<'_>,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
+) -> Result<Vec<String>> {
+    if clips.is_empty() {
+        anyhow::bail!(crate::i18n::t("no_clips_to_export"));
+    }
+    let output = &resolve_source_fps(clips, output).await;
+    let overlays = OverlayTracks::default();
 
-    let has_any_audio = clips.iter().any(|c| !c.audio_paths.is_empty());
+    if clips.len() == 1 {
+        return single_clip_args(&clips[0], output_path, watermark, quality, ctx, target_size_mb, output, &overlays).await;
+    }
+
+    let mut eff_durations = Vec::new();
+    for clip in clips {
+        eff_durations.push(effective_duration(clip, probe_duration(&clip.media.path).await?));
+    }
+    let args = if all_cuts(transitions) {
+        concat_args(clips, &eff_durations, output_path, watermark, quality, ctx, target_size_mb, output, &overlays)
+    } else {
+        xfade_args(clips, &eff_durations, transitions, output_path, watermark, quality, ctx, target_size_mb, output, &overlays)
+    };
+    Ok(args)
+}
+
+/// Arguments of the one-pass export chaining `clips` with `transitions`,
+/// annotations and keystrokes coming from `overlays` when it was rendered
+fn xfade_args(
+    clips: &[Clip],
+    eff_durations: &[f64],
+    transitions: &[Transition],
+    output_path: &Path,
+    watermark: bool,
+    quality: &ExportQuality,
+    ctx: &ExportContext<'_>,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
+    overlays: &OverlayTracks,
+) -> Vec<String> {
+    let ExportContext {
+        clip_keystrokes, keystroke_font, subtitles, global_annotations, clip_annotations, clip_cursor_positions,
+        system_volume, mic_volume, ..
+    } = *ctx;
+    let max_w = (clips.iter().map(|c| c.media.region.width).max().unwrap_or(1920) / 2) * 2;
+    let max_h = (clips.iter().map(|c| c.media.region.height).max().unwrap_or(1080) / 2) * 2;
+
+    let has_any_audio = output.include_audio && clips.iter().any(|c| !c.media.audio_paths.is_empty());
 
     let mut args: Vec<String> = Vec::new();
     for clip in clips {
         args.push("-i".into());
-        args.push(clip.path.to_string_lossy().to_string());
+        args.push(clip.media.path.to_string_lossy().to_string());
     }
 
     // Audio inputs
@@ -607,7 +1278,7 @@ pub async fn export_mp4(
         let mut input_idx = num_video_inputs;
         for (ci, clip) in clips.iter().enumerate() {
             let mut indices = Vec::new();
-            for audio_path in &clip.audio_paths {
+            for audio_path in &clip.media.audio_paths {
                 if std::path::Path::new(audio_path).exists() {
                     args.push("-i".into());
                     args.push(audio_path.clone());
@@ -619,9 +1290,18 @@ pub async fn export_mp4(
         }
     }
 
+    // Annotations and keystrokes from their rasterized tracks, when rendered
+    let overlay_inputs = overlays.push_inputs(clips, &mut args);
+    let (no_annotations, no_keystrokes) = (HashMap::new(), HashMap::new());
+    let (clip_annotations, clip_keystrokes) = if overlays.is_active() {
+        (&no_annotations, &no_keystrokes)
+    } else {
+        (clip_annotations, clip_keystrokes)
+    };
+
     let mut filter = build_filter_complex_with_trim(
-        clips, &eff_durations, transitions, max_w, max_h,
-        clip_annotations, clip_cursor_positions,
+        clips, eff_durations, transitions, max_w, max_h, output,
+        clip_annotations, clip_cursor_positions, &overlay_inputs,
     );
 
     let video_final_label = if clips.len() == 2 {
@@ -630,34 +1310,11 @@ pub async fn export_mp4(
         format!("[v{}]", clips.len() - 2)
     };
 
-    // Global overlays: keystrokes, subtitles, watermark
-    let mut overlay_filters = Vec::new();
-    let mut cumulative_time = 0.0;
-    for (i, clip) in clips.iter().enumerate() {
-        if let Some(events) = clip_keystrokes.get(&clip.id) {
-            overlay_filters.extend(build_keystroke_filters(events, cumulative_time, clip.trim_start_ms));
-        }
-        if i < transitions.len() {
-            if transitions[i].transition_type == TransitionType::Cut {
-                cumulative_time += eff_durations[i];
-            } else {
-                let raw_dur = transitions[i].duration_s;
-                let max_dur = if i + 1 < eff_durations.len() {
-                    eff_durations[i].min(eff_durations[i + 1]) * 0.9
-                } else {
-                    eff_durations[i] * 0.9
-                };
-                let trans_dur = raw_dur.clamp(0.1, max_dur.max(0.1));
-                cumulative_time += eff_durations[i] - trans_dur;
-            }
-        } else {
-            cumulative_time += eff_durations[i];
-        }
-    }
-    overlay_filters.extend(build_subtitle_filters(subtitles));
-    if watermark {
-        overlay_filters.push(WATERMARK_FILTER.to_string());
-    }
+    // Global overlays: timeline annotations, keystrokes, subtitles, watermark (drawn at the output size)
+    let canvas = super::estimate::canvas_size(clips, output);
+    let text_filters = timeline_text_filters(clips, eff_durations, transitions, watermark.then_some(output.watermark_tone), clip_keystrokes, keystroke_font, subtitles, global_annotations, canvas, output.safe_area);
+    let (before, after) = output_stage_filters(output, !text_filters.is_empty());
+    let overlay_filters: Vec<String> = before.into_iter().chain(text_filters).chain(after).collect();
 
     let output_label = if overlay_filters.is_empty() {
         video_final_label.clone()
@@ -669,7 +1326,8 @@ pub async fn export_mp4(
 
     // Audio filter
     let audio_output_label = if has_any_audio {
-        let af = build_audio_concat_filter(&audio_input_map, &eff_durations, system_volume, mic_volume);
+        let offsets: Vec<i64> = clips.iter().map(|c| c.media.audio_offset_ms).collect();
+        let af = build_audio_concat_filter(&audio_input_map, eff_durations, &offsets, system_volume, mic_volume);
         if !af.is_empty() {
             filter.push_str(&format!(";{}", af));
             Some("[aout]".to_string())
@@ -685,8 +1343,9 @@ pub async fn export_mp4(
         args.push(al.clone());
     }
 
-    let crf_str = quality.crf().to_string();
-    args.extend(["-c:v", "libx264", "-preset", quality.preset(), "-crf", &crf_str, "-pix_fmt", "yuv420p", "-r", "30"].iter().map(|s| s.to_string()));
+    let total_duration = timeline_duration(eff_durations, transitions);
+    let target_kbps = target_size_mb.map(|mb| target_video_kbps(mb, total_duration, audio_output_label.is_some()));
+    args.extend(codec_args(quality, target_kbps, output));
     if audio_output_label.is_some() {
         args.extend(["-c:a", "aac", "-b:a", "128k"].iter().map(|s| s.to_string()));
     } else {
@@ -694,83 +1353,73 @@ pub async fn export_mp4(
     }
     args.extend(["-shortest", "-y"].iter().map(|s| s.to_string()));
     args.push(output_path.to_string_lossy().to_string());
+    args
+}
 
-    eprintln!("[export] Output: {:?}", output_path);
+// ──────────────────────────────── Export with concat (Cut transitions) ────────────────────
 
-    let mut child = crate::ffmpeg_command()
+async fn export_with_concat(
+    clips: &[Clip],
+    eff_durations: &[f64],
+    output_path: &PathBuf,
+    app: &AppHandle,
+    watermark: bool,
+    quality: &ExportQuality,
+    ctx: &ExportContext<'_>,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
+) -> Result<()> {
+    // Annotations and keystrokes as one rasterized track per clip
+    let max_w = (clips.iter().map(|c| c.media.region.width).max().unwrap_or(1920) / 2) * 2;
+    let max_h = (clips.iter().map(|c| c.media.region.height).max().unwrap_or(1080) / 2) * 2;
+    let overlays = OverlayTracks::render(
+        clips, ctx.clip_annotations, ctx.clip_keystrokes, ctx.keystroke_font, output.safe_area, ctx.clip_gamepad, max_w, max_h, output_path,
+    ).await;
+    let mut args = concat_args(clips, eff_durations, output_path, watermark, quality, ctx, target_size_mb, output, &overlays);
+
+    let _script = FilterScript::spill(&mut args, filter_script_path(output_path), output.keep_filter_script)?;
+    let output = crate::ffmpeg_command()
         .args(&args)
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .stderr(Stdio::piped())
-        .spawn()
-        .context("Failed to start FFmpeg export")?;
-
-    let total_duration: f64 = {
-        let sum: f64 = eff_durations.iter().sum();
-        let trans_sum: f64 = transitions.iter().enumerate()
-            .filter(|(_, t)| t.transition_type != TransitionType::Cut)
-            .map(|(i, t)| {
-                let max_dur = if i + 1 < eff_durations.len() {
-                    eff_durations[i].min(eff_durations[i + 1]) * 0.9
-                } else {
-                    eff_durations[i] * 0.9
-                };
-                t.duration_s.clamp(0.1, max_dur.max(0.1))
-            })
-            .sum();
-        sum - trans_sum
-    };
-
-    let mut stderr_log = String::new();
-    if let Some(stderr) = child.stderr.take() {
-        let reader = BufReader::new(stderr);
-        let mut lines = reader.lines();
-        while let Ok(Some(line)) = lines.next_line().await {
-            eprintln!("[ffmpeg] {}", line);
-            stderr_log.push_str(&line);
-            stderr_log.push('\n');
-            if let Some(time_str) = extract_time(&line) {
-                let progress = (time_str / total_duration * 100.0).min(100.0);
-                let _ = app.emit("export-progress", progress as u32);
-            }
-        }
-    }
+        .output()
+        .await
+        .context("Failed to run concat export")?;
 
-    let status = child.wait().await.context("FFmpeg export failed")?;
-    if !status.success() {
-        eprintln!("[export] FFmpeg stderr:\n{}", stderr_log);
-        anyhow::bail!("{}", translate_ffmpeg_error(&stderr_log));
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{}", translate_ffmpeg_error(&stderr));
     }
-
     let _ = app.emit("export-progress", 100u32);
     Ok(())
 }
 
-// ──────────────────────────────── Export with concat (Cut transitions) ────────────────────
-
-async fn export_with_concat(
+/// Arguments of the one-pass export joining `clips` with cuts
+fn concat_args(
     clips: &[Clip],
     eff_durations: &[f64],
-    output_path: &PathBuf,
-    app: &AppHandle,
+    output_path: &Path,
     watermark: bool,
     quality: &ExportQuality,
-    clip_keystrokes: &HashMap<String, Vec<KeystrokeEvent>>,
-    subtitles: &[Subtitle],
-    clip_annotations: &HashMap<String, Vec<Annotation>>,
-    clip_cursor_positions: &HashMap<String, Vec<CursorPosition>>,
-    system_volume: f32,
-    mic_volume: f32,
-) -> Result<()> {
-    let max_w = (clips.iter().map(|c| c.region.width).max().unwrap_or(1920) / 2) * 2;
-    let max_h = (clips.iter().map(|c| c.region.height).max().unwrap_or(1080) / 2) * 2;
-    let has_any_audio = clips.iter().any(|c| !c.audio_paths.is_empty());
+    ctx: &ExportContext<'_>,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
+    overlays: &OverlayTracks,
+) -> Vec<String> {
+    let ExportContext {
+        clip_keystrokes, keystroke_font, subtitles, global_annotations, clip_annotations, clip_cursor_positions,
+        system_volume, mic_volume, ..
+    } = *ctx;
+    let max_w = (clips.iter().map(|c| c.media.region.width).max().unwrap_or(1920) / 2) * 2;
+    let max_h = (clips.iter().map(|c| c.media.region.height).max().unwrap_or(1080) / 2) * 2;
+    let has_any_audio = output.include_audio && clips.iter().any(|c| !c.media.audio_paths.is_empty());
     let n = clips.len();
 
     let mut args: Vec<String> = Vec::new();
     for clip in clips {
         args.push("-i".into());
-        args.push(clip.path.to_string_lossy().to_string());
+        args.push(clip.media.path.to_string_lossy().to_string());
     }
 
     let num_video_inputs = n;
@@ -779,7 +1428,7 @@ async fn export_with_concat(
         let mut input_idx = num_video_inputs;
         for (ci, clip) in clips.iter().enumerate() {
             let mut indices = Vec::new();
-            for audio_path in &clip.audio_paths {
+            for audio_path in &clip.media.audio_paths {
                 if std::path::Path::new(audio_path).exists() {
                     args.push("-i".into());
                     args.push(audio_path.clone());
@@ -791,12 +1440,22 @@ async fn export_with_concat(
         }
     }
 
+    // Annotations and keystrokes from their rasterized tracks, when rendered
+    let overlay_inputs = overlays.push_inputs(clips, &mut args);
+    let (no_annotations, no_keystrokes) = (HashMap::new(), HashMap::new());
+    let (clip_annotations, clip_keystrokes) = if overlays.is_active() {
+        (&no_annotations, &no_keystrokes)
+    } else {
+        (clip_annotations, clip_keystrokes)
+    };
+
     let mut filters = Vec::new();
     for i in 0..n {
         let clip = &clips[i];
         let cursor = clip_cursor_positions.get(&clip.id);
         let anns = clip_annotations.get(&clip.id);
-        filters.push(build_clip_chain(i, clip, max_w, max_h, cursor, anns));
+        let overlay = overlay_inputs.get(&clip.id).copied();
+        filters.push(build_clip_chain(i, clip, max_w, max_h, output, cursor, anns, overlay));
     }
 
     let inputs: String = (0..n).map(|i| format!("[s{i}]")).collect::<Vec<_>>().join("");
@@ -804,16 +1463,19 @@ async fn export_with_concat(
     filters.push(format!("{inputs}concat=n={n}:v=1:a=0{concat_label}"));
 
     // Global overlays
-    let mut overlay_parts = Vec::new();
+    let (canvas_w, canvas_h) = super::estimate::canvas_size(clips, output);
+    let mut text_parts: Vec<String> = build_annotation_draw_filters(global_annotations, canvas_w, canvas_h);
     let mut cumulative_time = 0.0;
     for (i, clip) in clips.iter().enumerate() {
         if let Some(events) = clip_keystrokes.get(&clip.id) {
-            overlay_parts.extend(build_keystroke_filters(events, cumulative_time, clip.trim_start_ms));
+            text_parts.extend(build_keystroke_filters(events, cumulative_time, clip.media.trim_start_ms, keystroke_font, output.safe_area));
         }
         cumulative_time += eff_durations[i];
     }
-    overlay_parts.extend(build_subtitle_filters(subtitles));
-    if watermark { overlay_parts.push(WATERMARK_FILTER.to_string()); }
+    text_parts.extend(build_subtitle_filters(subtitles, output.safe_area));
+    if watermark { text_parts.push(watermark_filter(output.watermark_tone)); }
+    let (before, after) = output_stage_filters(output, !text_parts.is_empty());
+    let overlay_parts: Vec<String> = before.into_iter().chain(text_parts).chain(after).collect();
 
     let video_output = if overlay_parts.is_empty() {
         concat_label.to_string()
@@ -825,7 +1487,8 @@ async fn export_with_concat(
 
     // Audio
     let audio_output = if has_any_audio {
-        let af = build_audio_concat_filter(&audio_input_map, eff_durations, system_volume, mic_volume);
+        let offsets: Vec<i64> = clips.iter().map(|c| c.media.audio_offset_ms).collect();
+        let af = build_audio_concat_filter(&audio_input_map, eff_durations, &offsets, system_volume, mic_volume);
         if !af.is_empty() {
             filters.push(af);
             Some("[aout]".to_string())
@@ -842,64 +1505,85 @@ async fn export_with_concat(
         args.push(al.clone());
     }
 
-    let crf_str = quality.crf().to_string();
-    args.extend(["-c:v", "libx264", "-preset", quality.preset(), "-crf", &crf_str, "-pix_fmt", "yuv420p", "-r", "30"].iter().map(|s| s.to_string()));
+    let target_kbps = target_size_mb.map(|mb| target_video_kbps(mb, eff_durations.iter().sum(), audio_output.is_some()));
+    args.extend(codec_args(quality, target_kbps, output));
     if audio_output.is_some() {
         args.extend(["-c:a", "aac", "-b:a", "128k"].iter().map(|s| s.to_string()));
     } else { args.push("-an".into()); }
     args.extend(["-shortest", "-y"].iter().map(|s| s.to_string()));
     args.push(output_path.to_string_lossy().to_string());
+    args
+}
+
+// ──────────────────────────────── Export single clip ────────────────────────────────
+
+async fn export_single_clip(
+    clip: &Clip,
+    output_path: &PathBuf,
+    app: &AppHandle,
+    watermark: bool,
+    quality: &ExportQuality,
+    ctx: &ExportContext<'_>,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
+) -> Result<()> {
+    let _ = app.emit("export-progress", 10u32);
 
+    // Annotations and keystrokes as one rasterized track
+    let overlays = OverlayTracks::render(
+        std::slice::from_ref(clip), ctx.clip_annotations, ctx.clip_keystrokes, ctx.keystroke_font, output.safe_area, ctx.clip_gamepad,
+        clip.media.region.width, clip.media.region.height, output_path,
+    ).await;
+    let mut cmd_args = single_clip_args(clip, output_path, watermark, quality, ctx, target_size_mb, output, &overlays).await?;
 
+    let _script = FilterScript::spill(&mut cmd_args, filter_script_path(output_path), output.keep_filter_script)?;
     let output = crate::ffmpeg_command()
-        .args(&args)
+        .args(&cmd_args)
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .stderr(Stdio::piped())
         .output()
         .await
-        .context("Failed to run concat export")?;
+        .context("Failed to export single clip")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         anyhow::bail!("{}", translate_ffmpeg_error(&stderr));
     }
+
     let _ = app.emit("export-progress", 100u32);
     Ok(())
 }
 
-// ──────────────────────────────── Export single clip ────────────────────────────────
-
-async fn export_single_clip(
+/// Arguments of the export of a single clip, which needs no canvas
+async fn single_clip_args(
     clip: &Clip,
-    output_path: &PathBuf,
-    app: &AppHandle,
+    output_path: &Path,
     watermark: bool,
     quality: &ExportQuality,
-    clip_keystrokes: &HashMap<String, Vec<KeystrokeEvent>>,
-    subtitles: &[Subtitle],
-    clip_annotations: &HashMap<String, Vec<Annotation>>,
-    clip_cursor_positions: &HashMap<String, Vec<CursorPosition>>,
-    system_volume: f32,
-    mic_volume: f32,
-) -> Result<()> {
-    let _ = app.emit("export-progress", 10u32);
-
-    let has_audio = !clip.audio_paths.is_empty();
+    ctx: &ExportContext<'_>,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
+    overlays: &OverlayTracks,
+) -> Result<Vec<String>> {
+    let ExportContext {
+        clip_keystrokes, keystroke_font, subtitles, global_annotations, clip_annotations, clip_cursor_positions,
+        system_volume, mic_volume, ..
+    } = *ctx;
+    let has_audio = output.include_audio && !clip.media.audio_paths.is_empty();
 
     let mut cmd_args: Vec<String> = Vec::new();
 
-    if clip.trim_start_ms > 0 {
-        cmd_args.push("-ss".into());
-        cmd_args.push(format!("{:.3}", clip.trim_start_ms as f64 / 1000.0));
-    }
-
+    // Trims go through trim/atrim below: input seeking snaps to the sparse
+    // keyframes of the capture and left the audio tracks untrimmed
     cmd_args.push("-i".into());
-    cmd_args.push(clip.path.to_string_lossy().to_string());
+    cmd_args.push(clip.media.path.to_string_lossy().to_string());
 
     let mut audio_input_indices = Vec::new();
     let mut next_input = 1;
-    for audio_path in &clip.audio_paths {
+    // Audio-less presets skip the tracks entirely
+    let audio_paths: &[String] = if has_audio { &clip.media.audio_paths } else { &[] };
+    for audio_path in audio_paths {
         if std::path::Path::new(audio_path).exists() {
             cmd_args.push("-i".into());
             cmd_args.push(audio_path.clone());
@@ -908,71 +1592,114 @@ async fn export_single_clip(
         }
     }
 
-    if clip.trim_end_ms > 0 {
-        cmd_args.push("-to".into());
-        let end = clip.trim_end_ms as f64 / 1000.0 - clip.trim_start_ms as f64 / 1000.0;
-        cmd_args.push(format!("{:.3}", end.max(0.1)));
-    }
+    // Annotations and keystrokes from their rasterized track, when rendered
+    let overlay_input = overlays.push_input(&clip.id, &mut cmd_args);
+    let (no_annotations, no_keystrokes) = (HashMap::new(), HashMap::new());
+    let (clip_annotations, clip_keystrokes) = if overlays.is_active() {
+        (&no_annotations, &no_keystrokes)
+    } else {
+        (clip_annotations, clip_keystrokes)
+    };
 
     // Build video filter chain
-    let mut vf_parts: Vec<String> = Vec::new();
+    let mut vf_parts: Vec<String> = trim_filter(clip, false).into_iter().collect();
+    if output.tonemap_hdr && clip.media.hdr {
+        vf_parts.push(HDR_TONEMAP.to_string());
+    }
+    // Shifted by the clip's audio offset, then cut to the video's range
+    let audio_chain = [audio_offset_filter(clip.media.audio_offset_ms), trim_filter(clip, true)]
+        .into_iter()
+        .flatten()
+        .reduce(|shift, trim| format!("{shift},{trim}"));
 
-    // Cursor zoom
-    if let Some(positions) = clip_cursor_positions.get(&clip.id) {
-        if let Some(zoom) = build_cursor_zoom_filter(positions, clip.trim_start_ms, clip.region.width, clip.region.height) {
-            vf_parts.push(zoom);
-        }
+    // Manual zoom/pan or cursor zoom
+    let fps = output.fps.unwrap_or(DEFAULT_FPS);
+    let cursor = clip_cursor_positions.get(&clip.id);
+    if let Some(zoom) = build_clip_zoom_filter(clip, cursor, clip.media.region.width, clip.media.region.height, fps) {
+        vf_parts.push(zoom);
     }
 
     // Annotations
     if let Some(anns) = clip_annotations.get(&clip.id) {
-        vf_parts.extend(build_annotation_draw_filters(anns, clip.region.width, clip.region.height));
+        vf_parts.extend(build_annotation_draw_filters(anns, clip.media.region.width, clip.media.region.height));
     }
+    // The overlay track goes where the annotations would be drawn
+    let overlay_at = vf_parts.len();
+
+    // Timeline annotations, at the output size
+    let (canvas_w, canvas_h) = super::estimate::canvas_size(std::slice::from_ref(clip), output);
+    let mut text_parts: Vec<String> = build_annotation_draw_filters(global_annotations, canvas_w, canvas_h);
 
     // Keystrokes
     if let Some(events) = clip_keystrokes.get(&clip.id) {
-        vf_parts.extend(build_keystroke_filters(events, 0.0, clip.trim_start_ms));
+        text_parts.extend(build_keystroke_filters(events, 0.0, clip.media.trim_start_ms, keystroke_font, output.safe_area));
     }
 
     // Subtitles
-    vf_parts.extend(build_subtitle_filters(subtitles));
+    text_parts.extend(build_subtitle_filters(subtitles, output.safe_area));
 
     // Watermark
     if watermark {
-        vf_parts.push(WATERMARK_FILTER.to_string());
+        text_parts.push(watermark_filter(output.watermark_tone));
     }
 
+    // Preset resize before the text so it keeps its size, GPU upload last
+    let (before, after) = output_stage_filters(output, !text_parts.is_empty());
+    vf_parts.extend(before);
+    vf_parts.extend(text_parts);
+    vf_parts.extend(after);
+
     let has_volume_adj = (system_volume - 1.0).abs() > 0.01 || (mic_volume - 1.0).abs() > 0.01;
-    let need_filter_complex = !vf_parts.is_empty() || audio_input_indices.len() > 1 || (has_volume_adj && !audio_input_indices.is_empty());
+    let audio_filtered = audio_input_indices.len() > 1 || (!audio_input_indices.is_empty() && (has_volume_adj || audio_chain.is_some()));
+    let need_filter_complex = !vf_parts.is_empty() || audio_filtered || overlay_input.is_some();
 
     if need_filter_complex {
         let mut fc_parts = Vec::new();
 
-        let video_chain = if vf_parts.is_empty() {
-            "[0:v]null[vout]".to_string()
-        } else {
-            format!("[0:v]{}[vout]", vf_parts.join(","))
+        let chain = |parts: &[String]| if parts.is_empty() { "null".to_string() } else { parts.join(",") };
+        let video_chain = match overlay_input {
+            Some(k) => {
+                let (pre, post) = vf_parts.split_at(overlay_at);
+                format!(
+                    "[0:v]{}[pre];[pre][{k}:v]overlay=0:0:eof_action=pass:format=auto[ovl];[ovl]{}[vout]",
+                    chain(pre), chain(post)
+                )
+            }
+            None => format!("[0:v]{}[vout]", chain(&vf_parts)),
         };
         fc_parts.push(video_chain);
 
-        if audio_input_indices.len() > 1 {
+        // Same range as the video
+        let mut audio_sources = Vec::new();
+        for (j, idx) in audio_input_indices.iter().enumerate() {
+            match &audio_chain {
+                Some(filters) => {
+                    fc_parts.push(format!("[{idx}:a]{filters}[at{j}]"));
+                    audio_sources.push(format!("[at{j}]"));
+                }
+                None => audio_sources.push(format!("[{idx}:a]")),
+            }
+        }
+
+        if audio_sources.len() > 1 {
             // Multiple audio sources — apply volume to each before mixing
             let mut vol_inputs = Vec::new();
-            for (j, idx) in audio_input_indices.iter().enumerate() {
+            for (j, src) in audio_sources.iter().enumerate() {
                 let vol = if j == 0 { system_volume } else { mic_volume };
                 if (vol - 1.0).abs() > 0.01 {
                     let label = format!("av{j}");
-                    fc_parts.push(format!("[{}:a]volume={:.2}[{label}]", idx, vol));
+                    fc_parts.push(format!("{src}volume={:.2}[{label}]", vol));
                     vol_inputs.push(format!("[{label}]"));
                 } else {
-                    vol_inputs.push(format!("[{}:a]", idx));
+                    vol_inputs.push(src.clone());
                 }
             }
             let mix: String = vol_inputs.join("");
-            fc_parts.push(format!("{mix}amix=inputs={}:duration=first[aout]", audio_input_indices.len()));
-        } else if audio_input_indices.len() == 1 && has_volume_adj {
-            let vol = system_volume; // single source defaults to system volume
-            fc_parts.push(format!("[{}:a]volume={:.2}[aout]", audio_input_indices[0], vol));
+            fc_parts.push(format!("{mix}amix=inputs={}:duration=first[aout]", audio_sources.len()));
+        } else if audio_sources.len() == 1 && audio_filtered {
+            // single source defaults to system volume
+            let vol = if has_volume_adj { format!("volume={:.2}", system_volume) } else { "anull".to_string() };
+            fc_parts.push(format!("{}{vol}[aout]", audio_sources[0]));
         }
 
         cmd_args.push("-filter_complex".into());
@@ -980,7 +1707,7 @@ async fn export_single_clip(
         cmd_args.push("-map".into());
         cmd_args.push("[vout]".into());
 
-        if audio_input_indices.len() > 1 || (audio_input_indices.len() == 1 && has_volume_adj) {
+        if audio_filtered {
             cmd_args.push("-map".into());
             cmd_args.push("[aout]".into());
         } else if audio_input_indices.len() == 1 {
@@ -994,34 +1721,33 @@ async fn export_single_clip(
         cmd_args.push(format!("{}:a", audio_input_indices[0]));
     }
 
-    let crf_str = quality.crf().to_string();
-    cmd_args.extend(["-c:v", "libx264", "-preset", quality.preset(), "-crf", &crf_str, "-pix_fmt", "yuv420p", "-r", "30"].iter().map(|s| s.to_string()));
+    let target_kbps = match target_size_mb {
+        Some(mb) => {
+            let duration = effective_duration(clip, probe_duration(&clip.media.path).await?);
+            Some(target_video_kbps(mb, duration, has_audio))
+        }
+        None => None,
+    };
+    cmd_args.extend(codec_args(quality, target_kbps, output));
     if has_audio {
         cmd_args.extend(["-c:a", "aac", "-b:a", "128k"].iter().map(|s| s.to_string()));
     } else { cmd_args.push("-an".into()); }
     cmd_args.extend(["-shortest", "-y"].iter().map(|s| s.to_string()));
     cmd_args.push(output_path.to_string_lossy().to_string());
+    Ok(cmd_args)
+}
 
-    let output = crate::ffmpeg_command()
-        .args(&cmd_args)
-        .stdin(Stdio::null())
-        .stdout(Stdio::null())
-        .stderr(Stdio::piped())
-        .output()
-        .await
-        .context("Failed to export single clip")?;
+// ──────────────────────────────── Export GIF ────────────────────────────────
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("{}", translate_ffmpeg_error(&stderr));
+/// GIF frame rate and maximum width for a quality preset
+pub(crate) fn gif_settings(quality: &ExportQuality) -> (u32, u32) {
+    match quality {
+        ExportQuality::High => (15, 640),
+        ExportQuality::Medium => (12, 480),
+        ExportQuality::Low => (8, 320),
     }
-
-    let _ = app.emit("export-progress", 100u32);
-    Ok(())
 }
 
-// ──────────────────────────────── Export GIF ────────────────────────────────
-
 pub async fn export_gif(
     clips: &[Clip],
     transitions: &[Transition],
@@ -1029,66 +1755,215 @@ pub async fn export_gif(
     app: &AppHandle,
     watermark: bool,
     quality: &ExportQuality,
-    clip_keystrokes: &HashMap<String, Vec<KeystrokeEvent>>,
-    subtitles: &[Subtitle],
-    clip_annotations: &HashMap<String, Vec<Annotation>>,
-    clip_cursor_positions: &HashMap<String, Vec<CursorPosition>>,
-    system_volume: f32,
-    mic_volume: f32,
+    ctx: &ExportContext<'_>,
+    output: &OutputSettings,
 ) -> Result<()> {
     if clips.is_empty() {
-        anyhow::bail!("Aucun clip à exporter en GIF");
+        anyhow::bail!(crate::i18n::t("no_clips_to_export_gif"));
     }
     for (i, clip) in clips.iter().enumerate() {
-        if !clip.path.exists() {
-            anyhow::bail!("Fichier du clip {} introuvable : {:?}", i + 1, clip.path);
+        if !clip.media.path.exists() {
+            anyhow::bail!(crate::i18n::tf("clip_n_file_missing", &[&(i + 1), &clip.media.path.display()]));
         }
     }
 
     let _ = app.emit("export-progress", 5u32);
     let temp_mp4 = output_path.with_extension("tmp.mp4");
     let temp_quality = ExportQuality::Low;
+    // The canvas is applied on the intermediate MP4, the frame rate on the GIF passes
+    let temp_output = OutputSettings { fps: None, include_audio: false, match_source_fps: false, ..output.clone() };
 
     if clips.len() == 1 {
-        export_single_clip(&clips[0], &temp_mp4, app, watermark, &temp_quality, clip_keystrokes, subtitles, clip_annotations, clip_cursor_positions, system_volume, mic_volume).await?;
+        export_single_clip(&clips[0], &temp_mp4, app, watermark, &temp_quality, ctx, None, &temp_output).await?;
     } else {
-        export_mp4(clips, transitions, &temp_mp4, app, watermark, &temp_quality, clip_keystrokes, subtitles, clip_annotations, clip_cursor_positions, system_volume, mic_volume).await?;
+        export_mp4(clips, transitions, &temp_mp4, app, watermark, &temp_quality, ctx, None, &temp_output).await?;
     }
     let _ = app.emit("export-progress", 50u32);
 
-    let fps = match quality { ExportQuality::High => 15, ExportQuality::Medium => 12, ExportQuality::Low => 8 };
-    let max_width = match quality { ExportQuality::High => 640, ExportQuality::Medium => 480, ExportQuality::Low => 320 };
+    let cuts = gif_palette_cuts(&super::range::clip_spans(clips, transitions), output.gif_palette.per_clip);
+    let result = encode_gif(&temp_mp4, output_path, quality, output, &cuts, || {
+        let _ = app.emit("export-progress", 75u32);
+    }).await;
+    let _ = std::fs::remove_file(&temp_mp4);
+    result?;
+
+    let _ = app.emit("export-progress", 100u32);
+    Ok(())
+}
+
+/// Where a new palette starts, in seconds of the composed video: at every
+/// clip but the first when `per_clip`, nowhere otherwise
+pub(crate) fn gif_palette_cuts(spans: &[(u64, u64)], per_clip: bool) -> Vec<f64> {
+    if !per_clip {
+        return Vec::new();
+    }
+    let mut cuts: Vec<f64> = Vec::new();
+    for &(start_ms, _) in spans.iter().skip(1) {
+        let at = start_ms as f64 / 1000.0;
+        if at > cuts.last().copied().unwrap_or(0.0) {
+            cuts.push(at);
+        }
+    }
+    cuts
+}
+
+/// Palette pass and GIF pass filter graphs. With cuts, each scene is
+/// quantized on its own: pass 1 outputs `[pal0]`..`[palN]`, pass 2 expects
+/// them as inputs 1 to N+1 and joins the scenes, whose local palettes the
+/// GIF encoder keeps. A boomerang appends the quantized frames reversed, so
+/// the way back reuses the palettes of the way there.
+fn gif_filters(base: &str, max_colors: u32, cuts: &[f64], boomerang: bool) -> (String, String) {
+    let palettegen = format!("palettegen=max_colors={max_colors}:stats_mode=diff");
+    let paletteuse = "paletteuse=dither=bayer:bayer_scale=5";
+    // The last frame is not shown twice at the turn
+    let back = if boomerang {
+        ",split[fw][bw];[bw]reverse,trim=start_frame=1,setpts=PTS-STARTPTS[rv];[fw][rv]concat=n=2:v=1:a=0"
+    } else {
+        ""
+    };
+    if cuts.is_empty() {
+        return (
+            format!("{base},{palettegen}"),
+            format!("{base}[x];[x][1:v]{paletteuse}{back}"),
+        );
+    }
+
+    let count = cuts.len() + 1;
+    let labels: String = (0..count).map(|i| format!("[s{i}]")).collect();
+    let split = format!("[0:v]{base},split={count}{labels}");
+    let trim = |i: usize| {
+        let mut bounds = Vec::new();
+        if i > 0 {
+            bounds.push(format!("start={:.3}", cuts[i - 1]));
+        }
+        if let Some(end) = cuts.get(i) {
+            bounds.push(format!("end={end:.3}"));
+        }
+        format!("trim={}", bounds.join(":"))
+    };
+
+    let mut pass1 = vec![split.clone()];
+    let mut pass2 = vec![split];
+    let mut joined = String::new();
+    for i in 0..count {
+        pass1.push(format!("[s{i}]{},{palettegen}[pal{i}]", trim(i)));
+        pass2.push(format!("[s{i}]{},setpts=PTS-STARTPTS[v{i}];[v{i}][{}:v]{paletteuse}[g{i}]", trim(i), i + 1));
+        joined.push_str(&format!("[g{i}]"));
+    }
+    pass2.push(format!("{joined}concat=n={count}:v=1:a=0{back}"));
+    (pass1.join(";"), pass2.join(";"))
+}
+
+/// GIF muxer `-loop` value for a GIF played `plays` times: 0 loops forever,
+/// -1 plays once, N repeats N more times
+fn gif_loop_arg(plays: u32) -> i64 {
+    match plays {
+        0 => 0,
+        1 => -1,
+        n => i64::from(n) - 1,
+    }
+}
+
+/// Palette pass and GIF pass of `encode_gif`, and the palettes the first
+/// writes for the second
+pub(crate) struct GifPasses {
+    pub palette: Vec<String>,
+    pub gif: Vec<String>,
+    pub palette_paths: Vec<PathBuf>,
+}
+
+pub(crate) fn gif_passes(
+    input: &Path,
+    output_path: &Path,
+    quality: &ExportQuality,
+    output: &OutputSettings,
+    cuts: &[f64],
+) -> GifPasses {
+    let (default_fps, max_width) = gif_settings(quality);
+    let fps = output.fps.unwrap_or(default_fps);
+    let scale = match (output.width, output.height) {
+        (Some(_), Some(_)) => output_scale_filter(output).unwrap_or_default(),
+        (Some(w), None) => format!("scale={w}:-1:flags=lanczos"),
+        (None, Some(h)) => format!("scale=-1:{h}:flags=lanczos"),
+        (None, None) => format!("scale={max_width}:-1:flags=lanczos"),
+    };
+
+    let (pf, gf) = gif_filters(
+        &format!("fps={fps},{scale}"),
+        output.gif_palette.max_colors.clamp(2, 256),
+        cuts,
+        output.gif_loop.boomerang,
+    );
+    let palette_paths: Vec<PathBuf> = if cuts.is_empty() {
+        vec![output_path.with_extension("palette.png")]
+    } else {
+        (0..=cuts.len()).map(|i| output_path.with_extension(format!("palette{i}.png"))).collect()
+    };
+
+    // Pass 1: palette(s), named after the output: single images whatever
+    // its name holds
+    let mut palette: Vec<String> = vec!["-i".into(), input.to_string_lossy().to_string()];
+    if cuts.is_empty() {
+        palette.extend(["-vf".into(), pf]);
+        palette.extend(paths::image_output(&palette_paths[0]));
+    } else {
+        palette.extend(["-filter_complex".into(), pf]);
+        for (i, path) in palette_paths.iter().enumerate() {
+            palette.extend(["-map".into(), format!("[pal{i}]")]);
+            palette.extend(paths::image_output(path));
+        }
+    }
+
+    // Pass 2: GIF
+    let mut gif: Vec<String> = vec!["-i".into(), input.to_string_lossy().to_string()];
+    for path in &palette_paths {
+        gif.extend(paths::image_input(path));
+    }
+    gif.extend(["-filter_complex".into(), gf]);
+    gif.extend(["-loop".into(), gif_loop_arg(output.gif_loop.plays).to_string()]);
+    gif.extend(["-y".into(), output_path.to_string_lossy().to_string()]);
 
-    let palette_path = output_path.with_extension("palette.png");
+    GifPasses { palette, gif, palette_paths }
+}
 
+/// Two-pass palette GIF from an already composed video, with a new palette
+/// at each of `cuts` (seconds)
+pub(crate) async fn encode_gif(
+    input: &PathBuf,
+    output_path: &PathBuf,
+    quality: &ExportQuality,
+    output: &OutputSettings,
+    cuts: &[f64],
+    on_palette_done: impl FnOnce(),
+) -> Result<()> {
+    let passes = gif_passes(input, output_path, quality, output, cuts);
+    let remove_palettes = || {
+        for path in &passes.palette_paths {
+            let _ = std::fs::remove_file(path);
+        }
+    };
 
-    // Pass 1: palette
-    let pf = format!("fps={fps},scale={max_width}:-1:flags=lanczos,palettegen=stats_mode=diff");
     let output = crate::ffmpeg_command()
-        .args(["-i", &temp_mp4.to_string_lossy(), "-vf", &pf, "-y", &palette_path.to_string_lossy()])
+        .args(&passes.palette)
         .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped())
         .output().await.context("Failed to generate GIF palette")?;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Échec de la génération de palette : {}", translate_ffmpeg_error(&stderr));
+        remove_palettes();
+        anyhow::bail!(crate::i18n::tf("palette_failed", &[&translate_ffmpeg_error(&stderr)]));
     }
-    let _ = app.emit("export-progress", 75u32);
+    on_palette_done();
 
-    // Pass 2: GIF
-    let gf = format!("fps={fps},scale={max_width}:-1:flags=lanczos[x];[x][1:v]paletteuse=dither=bayer:bayer_scale=5");
     let output = crate::ffmpeg_command()
-        .args(["-i", &temp_mp4.to_string_lossy(), "-i", &palette_path.to_string_lossy(), "-filter_complex", &gf, "-y", &output_path.to_string_lossy()])
+        .args(&passes.gif)
         .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped())
         .output().await.context("Failed to generate GIF")?;
-
-    let _ = std::fs::remove_file(&temp_mp4);
-    let _ = std::fs::remove_file(&palette_path);
+    remove_palettes();
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Échec de la génération GIF : {}", translate_ffmpeg_error(&stderr));
+        anyhow::bail!(crate::i18n::tf("gif_failed", &[&translate_ffmpeg_error(&stderr)]));
     }
-    let _ = app.emit("export-progress", 100u32);
     Ok(())
 }
 
@@ -1100,41 +1975,43 @@ pub async fn preview_mp4(
     output_path: &PathBuf,
     app: &AppHandle,
     clip_keystrokes: &HashMap<String, Vec<KeystrokeEvent>>,
+    keystroke_font: Option<&str>,
     subtitles: &[Subtitle],
+    global_annotations: &[Annotation],
     clip_annotations: &HashMap<String, Vec<Annotation>>,
     clip_cursor_positions: &HashMap<String, Vec<CursorPosition>>,
 ) -> Result<()> {
     if clips.is_empty() {
-        anyhow::bail!("Aucun clip à prévisualiser");
+        anyhow::bail!(crate::i18n::t("no_clips_to_preview"));
     }
     if clips.len() == 1 {
         return preview_single_clip(
             &clips[0], output_path, app,
-            clip_keystrokes, subtitles, clip_annotations, clip_cursor_positions,
+            clip_keystrokes, keystroke_font, subtitles, global_annotations, clip_annotations, clip_cursor_positions,
         ).await;
     }
 
     let mut durations = Vec::new();
     for clip in clips {
-        durations.push(probe_duration(&clip.path).await?);
+        durations.push(probe_duration(&clip.media.path).await?);
     }
     let eff_durations: Vec<f64> = clips.iter().zip(durations.iter())
         .map(|(c, d)| effective_duration(c, *d)).collect();
 
-    let max_w = clips.iter().map(|c| c.region.width).max().unwrap_or(1920);
-    let max_h = clips.iter().map(|c| c.region.height).max().unwrap_or(1080);
+    let max_w = clips.iter().map(|c| c.media.region.width).max().unwrap_or(1920);
+    let max_h = clips.iter().map(|c| c.media.region.height).max().unwrap_or(1080);
     let prev_w = ((max_w / 2) / 2 * 2).max(320);
     let prev_h = ((max_h / 2) / 2 * 2).max(240);
 
     let mut args: Vec<String> = Vec::new();
     for clip in clips {
         args.push("-i".into());
-        args.push(clip.path.to_string_lossy().to_string());
+        args.push(clip.media.path.to_string_lossy().to_string());
     }
 
     let mut filter = build_filter_complex_with_trim(
-        clips, &eff_durations, transitions, prev_w, prev_h,
-        clip_annotations, clip_cursor_positions,
+        clips, &eff_durations, transitions, prev_w, prev_h, &OutputSettings::default(),
+        clip_annotations, clip_cursor_positions, &HashMap::new(),
     );
 
     let video_final_label = if clips.len() == 2 {
@@ -1143,12 +2020,12 @@ pub async fn preview_mp4(
         format!("[v{}]", clips.len() - 2)
     };
 
-    // Global overlays: keystrokes, subtitles (same logic as export)
-    let mut overlay_filters = Vec::new();
+    // Global overlays: timeline annotations, keystrokes, subtitles (same logic as export)
+    let mut overlay_filters = build_annotation_draw_filters(global_annotations, prev_w, prev_h);
     let mut cumulative_time = 0.0;
     for (i, clip) in clips.iter().enumerate() {
         if let Some(events) = clip_keystrokes.get(&clip.id) {
-            overlay_filters.extend(build_keystroke_filters(events, cumulative_time, clip.trim_start_ms));
+            overlay_filters.extend(build_keystroke_filters(events, cumulative_time, clip.media.trim_start_ms, keystroke_font, SafeArea::default()));
         }
         if i < transitions.len() {
             if transitions[i].transition_type == TransitionType::Cut {
@@ -1167,7 +2044,7 @@ pub async fn preview_mp4(
             cumulative_time += eff_durations[i];
         }
     }
-    overlay_filters.extend(build_subtitle_filters(subtitles));
+    overlay_filters.extend(build_subtitle_filters(subtitles, SafeArea::default()));
 
     let output_label = if overlay_filters.is_empty() {
         video_final_label.clone()
@@ -1184,27 +2061,13 @@ pub async fn preview_mp4(
     args.extend(["-c:v", "libx264", "-preset", "ultrafast", "-crf", "30", "-pix_fmt", "yuv420p", "-r", "24", "-an", "-y"].iter().map(|s| s.to_string()));
     args.push(output_path.to_string_lossy().to_string());
 
-
+    let _script = FilterScript::spill(&mut args, filter_script_path(output_path), false)?;
     let mut child = crate::ffmpeg_command()
         .args(&args)
         .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped())
         .spawn().context("Failed to start FFmpeg preview")?;
 
-    let total_duration: f64 = {
-        let sum: f64 = eff_durations.iter().sum();
-        let trans_sum: f64 = transitions.iter().enumerate()
-            .filter(|(_, t)| t.transition_type != TransitionType::Cut)
-            .map(|(i, t)| {
-                let max_dur = if i + 1 < eff_durations.len() {
-                    eff_durations[i].min(eff_durations[i + 1]) * 0.9
-                } else {
-                    eff_durations[i] * 0.9
-                };
-                t.duration_s.clamp(0.1, max_dur.max(0.1))
-            })
-            .sum();
-        sum - trans_sum
-    };
+    let total_duration = timeline_duration(&eff_durations, transitions);
 
     let mut stderr_log = String::new();
     if let Some(stderr) = child.stderr.take() {
@@ -1222,7 +2085,7 @@ pub async fn preview_mp4(
 
     let status = child.wait().await.context("FFmpeg preview failed")?;
     if !status.success() {
-        anyhow::bail!("Échec de la prévisualisation : {}", translate_ffmpeg_error(&stderr_log));
+        anyhow::bail!(crate::i18n::tf("preview_render_failed", &[&translate_ffmpeg_error(&stderr_log)]));
     }
     let _ = app.emit("preview-progress", 100u32);
     Ok(())
@@ -1233,36 +2096,36 @@ async fn preview_single_clip(
     output_path: &PathBuf,
     app: &AppHandle,
     clip_keystrokes: &HashMap<String, Vec<KeystrokeEvent>>,
+    keystroke_font: Option<&str>,
     subtitles: &[Subtitle],
+    global_annotations: &[Annotation],
     clip_annotations: &HashMap<String, Vec<Annotation>>,
     clip_cursor_positions: &HashMap<String, Vec<CursorPosition>>,
 ) -> Result<()> {
     let _ = app.emit("preview-progress", 10u32);
 
-    let prev_w = ((clip.region.width / 2) / 2 * 2).max(320);
-    let prev_h = ((clip.region.height / 2) / 2 * 2).max(240);
+    let prev_w = ((clip.media.region.width / 2) / 2 * 2).max(320);
+    let prev_h = ((clip.media.region.height / 2) / 2 * 2).max(240);
 
     let mut cmd_args: Vec<String> = Vec::new();
-    if clip.trim_start_ms > 0 {
+    if clip.media.trim_start_ms > 0 {
         cmd_args.push("-ss".into());
-        cmd_args.push(format!("{:.3}", clip.trim_start_ms as f64 / 1000.0));
+        cmd_args.push(format!("{:.3}", clip.media.trim_start_ms as f64 / 1000.0));
     }
     cmd_args.push("-i".into());
-    cmd_args.push(clip.path.to_string_lossy().to_string());
-    if clip.trim_end_ms > 0 {
+    cmd_args.push(clip.media.path.to_string_lossy().to_string());
+    if clip.media.trim_end_ms > 0 {
         cmd_args.push("-to".into());
-        let end = clip.trim_end_ms as f64 / 1000.0 - clip.trim_start_ms as f64 / 1000.0;
+        let end = clip.media.trim_end_ms as f64 / 1000.0 - clip.media.trim_start_ms as f64 / 1000.0;
         cmd_args.push(format!("{:.3}", end.max(0.1)));
     }
 
     // Build video filter chain with effects
     let mut vf_parts: Vec<String> = vec![format!("scale={}:{}", prev_w, prev_h)];
 
-    // Cursor zoom
-    if let Some(positions) = clip_cursor_positions.get(&clip.id) {
-        if let Some(zoom) = build_cursor_zoom_filter(positions, clip.trim_start_ms, prev_w, prev_h) {
-            vf_parts.push(zoom);
-        }
+    // Manual zoom/pan or cursor zoom
+    if let Some(zoom) = build_clip_zoom_filter(clip, clip_cursor_positions.get(&clip.id), prev_w, prev_h, 24) {
+        vf_parts.push(zoom);
     }
 
     // Annotations
@@ -1270,13 +2133,16 @@ async fn preview_single_clip(
         vf_parts.extend(build_annotation_draw_filters(anns, prev_w, prev_h));
     }
 
+    // Timeline annotations
+    vf_parts.extend(build_annotation_draw_filters(global_annotations, prev_w, prev_h));
+
     // Keystrokes
     if let Some(events) = clip_keystrokes.get(&clip.id) {
-        vf_parts.extend(build_keystroke_filters(events, 0.0, clip.trim_start_ms));
+        vf_parts.extend(build_keystroke_filters(events, 0.0, clip.media.trim_start_ms, keystroke_font, SafeArea::default()));
     }
 
     // Subtitles
-    vf_parts.extend(build_subtitle_filters(subtitles));
+    vf_parts.extend(build_subtitle_filters(subtitles, SafeArea::default()));
 
     let vf = vf_parts.join(",");
     cmd_args.extend(["-vf", &vf, "-c:v", "libx264", "-preset", "ultrafast", "-crf", "30", "-pix_fmt", "yuv420p", "-r", "24", "-an", "-y"].iter().map(|s| s.to_string()));
@@ -1285,10 +2151,10 @@ async fn preview_single_clip(
     let output = crate::ffmpeg_command()
         .args(&cmd_args)
         .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped())
-        .output().await.context("Échec de la prévisualisation")?;
+        .output().await.with_context(|| crate::i18n::t("preview_run_failed"))?;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Échec de la prévisualisation : {}", translate_ffmpeg_error(&stderr));
+        anyhow::bail!(crate::i18n::tf("preview_render_failed", &[&translate_ffmpeg_error(&stderr)]));
     }
     let _ = app.emit("preview-progress", 100u32);
     Ok(())
@@ -1296,7 +2162,7 @@ async fn preview_single_clip(
 
 // ──────────────────────────────── Utils ────────────────────────────────
 
-fn extract_time(line: &str) -> Option<f64> {
+pub(crate) fn extract_time(line: &str) -> Option<f64> {
     let idx = line.find("time=")?;
     let time_str = &line[idx + 5..];
     let end = time_str.find(' ').unwrap_or(time_str.len());
@@ -1315,19 +2181,13 @@ fn extract_time(line: &str) -> Option<f64> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::Region;
+    use crate::types::{ClipMedia, ExportFps, test_annotation, test_clip};
 
     fn make_clip(trim_start: u64, trim_end: u64) -> Clip {
+        let clip = test_clip("test", 10000);
         Clip {
-            id: "test".into(),
-            path: PathBuf::from("test.mp4"),
-            duration_ms: 10000,
-            region: Region { x: 0, y: 0, width: 1920, height: 1080 },
-            has_audio: false,
-            thumbnail_path: None,
-            trim_start_ms: trim_start,
-            trim_end_ms: trim_end,
-            audio_paths: vec![],
+            media: ClipMedia { trim_start_ms: trim_start, trim_end_ms: trim_end, ..clip.media },
+            ..clip
         }
     }
 
@@ -1340,6 +2200,7 @@ mod tests {
             position: pos,
             font_size,
             color: color.into(),
+            font_file: None,
         }
     }
 
@@ -1373,33 +2234,184 @@ mod tests {
         assert!(result.contains("%%"));
     }
 
-    // ── build_piecewise_lerp ──
+    // ── build_keyframe_expr ──
+
+    const L: Easing = Easing::Linear;
+
+    fn kf(time_ms: u64, property: AnimatedProperty, value: f64, easing: Easing) -> Keyframe {
+        Keyframe { time_ms, property, value, easing }
+    }
 
     #[test]
     fn test_lerp_empty() {
-        assert_eq!(build_piecewise_lerp(&[]), "0");
+        assert_eq!(build_keyframe_expr(&[], "t"), "0");
     }
 
     #[test]
     fn test_lerp_single() {
-        let result = build_piecewise_lerp(&[(0.0, 0.5)]);
+        let result = build_keyframe_expr(&[(0.0, 0.5, L)], "t");
         assert_eq!(result, "0.5000");
     }
 
     #[test]
     fn test_lerp_two_keyframes() {
-        let result = build_piecewise_lerp(&[(0.0, 0.0), (1.0, 1.0)]);
+        let result = build_keyframe_expr(&[(0.0, 0.0, L), (1.0, 1.0, L)], "t");
         assert!(result.contains("if(lt(t"));
     }
 
     #[test]
     fn test_lerp_three_keyframes() {
-        let result = build_piecewise_lerp(&[(0.0, 0.0), (1.0, 0.5), (2.0, 1.0)]);
+        let result = build_keyframe_expr(&[(0.0, 0.0, L), (1.0, 0.5, L), (2.0, 1.0, L)], "t");
         // Should have nested if expressions
         let if_count = result.matches("if(lt(t").count();
         assert_eq!(if_count, 2);
     }
 
+    #[test]
+    fn test_keyframe_expr_holds_before_first_keyframe() {
+        let result = build_keyframe_expr(&[(2.0, 1.0, L), (3.0, 2.0, L)], "it");
+        assert!(result.starts_with("if(lt(it\\,2.000)\\,1.0000"));
+        assert!(!result.contains("(t-"));
+    }
+
+    #[test]
+    fn test_keyframe_expr_easing_and_hold() {
+        let eased = build_keyframe_expr(&[(0.0, 0.0, Easing::EaseIn), (1.0, 1.0, L)], "t");
+        assert!(eased.contains("((t-0.000)/1.000)*((t-0.000)/1.000)"));
+        let held = build_keyframe_expr(&[(0.0, 0.2, Easing::Hold), (1.0, 1.0, L)], "t");
+        assert_eq!(held, "if(lt(t\\,1.000)\\,0.2000\\,1.0000)");
+    }
+
+    #[test]
+    fn test_curve_value() {
+        let curve = [(1.0, 0.0, Easing::EaseInOut), (3.0, 1.0, L)];
+        assert_eq!(curve_value(&curve, 0.0), Some(0.0));
+        assert!((curve_value(&curve, 1.5).unwrap() - 0.125).abs() < 1e-9);
+        assert!((curve_value(&curve, 2.0).unwrap() - 0.5).abs() < 1e-9);
+        assert_eq!(curve_value(&curve, 5.0), Some(1.0));
+        assert_eq!(curve_value(&[], 1.0), None);
+    }
+
+    #[test]
+    fn test_property_curve_filters_and_offsets() {
+        let keyframes = vec![
+            kf(4000, AnimatedProperty::ZoomScale, 2.0, L),
+            kf(3000, AnimatedProperty::Opacity, 0.0, L),
+            kf(2000, AnimatedProperty::ZoomScale, 1.0, Easing::EaseOut),
+        ];
+        let curve = property_curve(&keyframes, AnimatedProperty::ZoomScale, 1000);
+        assert_eq!(curve, vec![(1.0, 1.0, Easing::EaseOut), (3.0, 2.0, L)]);
+    }
+
+    #[test]
+    fn test_manual_zoom_overrides_cursor_zoom() {
+        let positions = vec![
+            CursorPosition { timestamp_ms: 0, x: 0.1, y: 0.5 },
+            CursorPosition { timestamp_ms: 2000, x: 0.9, y: 0.5 },
+        ];
+        let mut clip = make_clip(0, 0);
+        let cursor = build_clip_zoom_filter(&clip, Some(&positions), 1920, 1080, 30).unwrap();
+        assert!(cursor.starts_with("crop="));
+
+        // Pan without a zoom factor does nothing on its own
+        clip.zoom_keyframes = vec![kf(0, AnimatedProperty::ZoomX, 0.2, L)];
+        assert!(build_manual_zoom_filter(&clip.zoom_keyframes, 0, 1920, 1080, 30).is_none());
+
+        clip.zoom_keyframes.push(kf(0, AnimatedProperty::ZoomScale, 1.0, L));
+        clip.zoom_keyframes.push(kf(1000, AnimatedProperty::ZoomScale, 2.0, L));
+        let manual = build_clip_zoom_filter(&clip, Some(&positions), 1920, 1080, 30).unwrap();
+        assert!(manual.starts_with("zoompan=z='max(1\\,if(lt(it"));
+        assert!(manual.contains("(0.2000)*iw"));
+        assert!(manual.contains("(0.5)*ih"));
+        assert!(manual.ends_with(":d=1:s=1920x1080:fps=30"));
+    }
+
+    // ── filter graph script ──
+
+    #[test]
+    fn test_filter_script_spill() {
+        let dir = std::env::temp_dir();
+        let output = dir.join(format!("clipflow_test_{}.mp4", uuid::Uuid::new_v4()));
+        let path = filter_script_path(&output);
+        assert!(path.starts_with(crate::storage::export_dir()));
+        let mut args: Vec<String> = ["-i", "a.mp4", "-filter_complex", "[0:v]fps=30[s0]", "-map", "[s0]"]
+            .iter().map(|s| s.to_string()).collect();
+
+        let script = FilterScript::spill(&mut args, path.clone(), false).unwrap();
+        assert!(script.is_some());
+        assert_eq!(args[2], "-filter_complex_script");
+        assert_eq!(args[3], path.to_string_lossy());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[0:v]fps=30[s0]");
+        drop(script);
+        assert!(!path.exists());
+
+        // Kept for debugging
+        let mut args = vec!["-filter_complex".to_string(), "null".to_string()];
+        drop(FilterScript::spill(&mut args, path.clone(), true).unwrap());
+        assert!(path.exists());
+        let _ = std::fs::remove_file(&path);
+
+        let mut plain = vec!["-vf".to_string(), "scale=640:-2".to_string()];
+        assert!(FilterScript::spill(&mut plain, path, false).unwrap().is_none());
+        assert_eq!(plain[0], "-vf");
+    }
+
+    // ── annotation opacity ──
+
+    fn make_annotation(kind: AnnotationKind, keyframes: Vec<Keyframe>) -> Annotation {
+        Annotation {
+            id: "a1".into(),
+            height: 0.1,
+            color: "#ff0000".into(),
+            stroke_width: 3.0,
+            text: Some("Hi".into()),
+            keyframes,
+            ..test_annotation(kind, 0, 2000)
+        }
+    }
+
+    #[test]
+    fn test_opacity_segments() {
+        let ann = make_annotation(AnnotationKind::Rectangle, vec![]);
+        assert_eq!(opacity_segments(&ann), vec![(0.0, 2.0, 1.0)]);
+
+        // Fade in over the first second, then fully visible
+        let ann = make_annotation(AnnotationKind::Rectangle, vec![
+            kf(0, AnimatedProperty::Opacity, 0.0, L),
+            kf(1000, AnimatedProperty::Opacity, 1.0, L),
+        ]);
+        let segments = opacity_segments(&ann);
+        assert_eq!(segments.len(), 5);
+        assert_eq!(segments[0], (0.0, 0.25, 0.15));
+        assert_eq!(segments[4], (1.0, 2.0, 1.0));
+    }
+
+    #[test]
+    fn test_annotation_opacity_filters() {
+        let fade = vec![
+            kf(0, AnimatedProperty::Opacity, 0.0, L),
+            kf(1000, AnimatedProperty::Opacity, 1.0, L),
+        ];
+        let boxes = build_annotation_draw_filters(&[make_annotation(AnnotationKind::Rectangle, fade.clone())], 1920, 1080);
+        assert_eq!(boxes.len(), 5);
+        assert!(boxes[4].contains("color=0xff0000@0.80"));
+
+        let text = build_annotation_draw_filters(&[make_annotation(AnnotationKind::Text, fade)], 1920, 1080);
+        assert_eq!(text.len(), 1);
+        assert!(text[0].contains(":alpha='clip(if(lt(t"));
+    }
+
+    #[test]
+    fn test_annotation_filters_follow_z_index() {
+        let mut over = make_annotation(AnnotationKind::Rectangle, Vec::new());
+        over.z_index = 1;
+        let under = make_annotation(AnnotationKind::Text, Vec::new());
+        let filters = build_annotation_draw_filters(&[over, under], 1920, 1080);
+        assert_eq!(filters.len(), 2);
+        assert!(filters[0].starts_with("drawtext="));
+        assert!(filters[1].starts_with("drawbox="));
+    }
+
     // ── annotation_color_ffmpeg ──
 
     #[test]
@@ -1449,6 +2461,87 @@ mod tests {
         assert!(effective_duration(&clip, 5.0) >= 0.1);
     }
 
+    // ── frame rate ──
+
+    #[test]
+    fn test_parse_frame_rate() {
+        assert_eq!(parse_frame_rate("60/1"), Some(60.0));
+        assert!((parse_frame_rate("30000/1001").unwrap() - 29.97).abs() < 0.01);
+        assert_eq!(parse_frame_rate("25"), Some(25.0));
+        assert_eq!(parse_frame_rate("0/0"), None);
+        assert_eq!(parse_frame_rate("N/A"), None);
+    }
+
+    #[test]
+    fn test_export_fps_keeps_explicit_rate() {
+        let mut preset = OutputSettings { fps: Some(12), ..Default::default() };
+        ExportFps::Fixed(60).apply_to(&mut preset);
+        assert_eq!(preset.fps, Some(12));
+        let mut plain = OutputSettings::default();
+        ExportFps::Source.apply_to(&mut plain);
+        assert!(plain.match_source_fps && plain.fps.is_none());
+        let mut plain = OutputSettings::default();
+        ExportFps::Fixed(500).apply_to(&mut plain);
+        assert_eq!(plain.fps, Some(120));
+    }
+
+    // ── HDR ──
+
+    #[test]
+    fn test_clip_chain_composites_overlay_track() {
+        let output = OutputSettings::default();
+        let chain = build_clip_chain(1, &make_clip(0, 0), 1920, 1080, &output, None, None, Some(4));
+        assert!(chain.starts_with("[1:v]scale=1920:1080"));
+        assert!(chain.ends_with("[pre1];[pre1][4:v]overlay=0:0:eof_action=pass:format=auto[s1]"));
+        let plain = build_clip_chain(1, &make_clip(0, 0), 1920, 1080, &output, None, None, None);
+        assert!(plain.ends_with("fps=30[s1]"));
+    }
+
+    #[test]
+    fn test_clip_chain_tonemaps_hdr_clips_only() {
+        let output = OutputSettings { tonemap_hdr: true, ..Default::default() };
+        let sdr = build_clip_chain(0, &make_clip(0, 0), 1920, 1080, &output, None, None, None);
+        assert!(!sdr.contains("tonemap"));
+        let mut hdr_clip = make_clip(1000, 0);
+        hdr_clip.media.hdr = true;
+        let chain = build_clip_chain(0, &hdr_clip, 1920, 1080, &output, None, None, None);
+        assert!(chain.starts_with(&format!("[0:v]trim=start=1.000,setpts=PTS-STARTPTS,{HDR_TONEMAP},scale=")));
+        let off = build_clip_chain(0, &hdr_clip, 1920, 1080, &OutputSettings::default(), None, None, None);
+        assert!(!off.contains("tonemap"));
+    }
+
+    #[test]
+    fn test_hdr_transfers() {
+        assert!(is_hdr_transfer("smpte2084\n"));
+        assert!(is_hdr_transfer("arib-std-b67"));
+        assert!(!is_hdr_transfer("bt709"));
+        assert!(!is_hdr_transfer("unknown"));
+        assert!(!is_hdr_transfer(""));
+    }
+
+    // ── trim_filter ──
+
+    #[test]
+    fn test_trim_filter_ranges() {
+        assert!(trim_filter(&make_clip(0, 0), false).is_none());
+        assert_eq!(trim_filter(&make_clip(1500, 4000), false).unwrap(), "trim=start=1.500:end=4.000,setpts=PTS-STARTPTS");
+        assert_eq!(trim_filter(&make_clip(1500, 0), true).unwrap(), "atrim=start=1.500,asetpts=PTS-STARTPTS");
+        assert_eq!(trim_filter(&make_clip(0, 4000), true).unwrap(), "atrim=end=4.000,asetpts=PTS-STARTPTS");
+    }
+
+    #[test]
+    fn test_audio_offset_shifts_each_track() {
+        assert_eq!(audio_offset_filter(0), None);
+        assert_eq!(audio_offset_filter(120).unwrap(), "adelay=120:all=1");
+        assert_eq!(audio_offset_filter(-80).unwrap(), "atrim=start=0.080,asetpts=PTS-STARTPTS");
+
+        // Second clip only, both of its tracks
+        let af = build_audio_concat_filter(&[(0, vec![2]), (1, vec![3, 4])], &[1.0, 2.5], &[0, -250], 1.0, 1.0);
+        assert!(af.starts_with("[2:a]anull[a0];"));
+        assert!(af.contains("[3:a]atrim=start=0.250,asetpts=PTS-STARTPTS,apad,atrim=duration=2.500[ao1_0];[4:a]atrim=start=0.250,asetpts=PTS-STARTPTS,apad,atrim=duration=2.500[ao1_1]"));
+        assert!(af.contains("[ao1_0][ao1_1]amix=inputs=2:duration=first[a1]"));
+    }
+
     // ── xfade_name ──
 
     #[test]
@@ -1498,7 +2591,7 @@ mod tests {
     #[test]
     fn test_subtitle_basic() {
         let subs = vec![make_subtitle("Hello", 0, 3000, SubtitlePosition::Bottom, 32, "#ffffff")];
-        let filters = build_subtitle_filters(&subs);
+        let filters = build_subtitle_filters(&subs, SafeArea::default());
         assert_eq!(filters.len(), 1);
         assert!(filters[0].contains("drawtext="));
         assert!(filters[0].contains("Hello"));
@@ -1508,48 +2601,60 @@ mod tests {
     #[test]
     fn test_subtitle_color() {
         let subs = vec![make_subtitle("Red", 0, 1000, SubtitlePosition::Bottom, 32, "#ff0000")];
-        let filters = build_subtitle_filters(&subs);
+        let filters = build_subtitle_filters(&subs, SafeArea::default());
         assert!(filters[0].contains("fontcolor=0xff0000"));
     }
 
     #[test]
     fn test_subtitle_position_top() {
         let subs = vec![make_subtitle("Top", 0, 1000, SubtitlePosition::Top, 32, "#ffffff")];
-        let filters = build_subtitle_filters(&subs);
+        let filters = build_subtitle_filters(&subs, SafeArea::default());
         assert!(filters[0].contains("y=30"));
     }
 
     #[test]
     fn test_subtitle_position_center() {
         let subs = vec![make_subtitle("Center", 0, 1000, SubtitlePosition::Center, 32, "#ffffff")];
-        let filters = build_subtitle_filters(&subs);
+        let filters = build_subtitle_filters(&subs, SafeArea::default());
         assert!(filters[0].contains("y=(h-th)/2"));
     }
 
     #[test]
     fn test_subtitle_position_bottom() {
         let subs = vec![make_subtitle("Bottom", 0, 1000, SubtitlePosition::Bottom, 32, "#ffffff")];
-        let filters = build_subtitle_filters(&subs);
+        let filters = build_subtitle_filters(&subs, SafeArea::default());
         assert!(filters[0].contains("y=h-th-30"));
     }
 
     #[test]
     fn test_subtitle_custom_fontsize() {
         let subs = vec![make_subtitle("Big", 0, 1000, SubtitlePosition::Bottom, 56, "#ffffff")];
-        let filters = build_subtitle_filters(&subs);
+        let filters = build_subtitle_filters(&subs, SafeArea::default());
         assert!(filters[0].contains("fontsize=56"));
     }
 
     #[test]
     fn test_subtitle_default_fontsize() {
         let subs = vec![make_subtitle("Default", 0, 1000, SubtitlePosition::Bottom, 0, "#ffffff")];
-        let filters = build_subtitle_filters(&subs);
+        let filters = build_subtitle_filters(&subs, SafeArea::default());
         assert!(filters[0].contains("fontsize=32"));
     }
 
     #[test]
     fn test_subtitle_empty() {
-        assert!(build_subtitle_filters(&[]).is_empty());
+        assert!(build_subtitle_filters(&[], SafeArea::default()).is_empty());
+    }
+
+    #[test]
+    fn test_subtitle_kept_in_safe_area() {
+        let safe = SafeArea { top: 0.1, right: 0.15, bottom: 0.2, left: 0.05 };
+        let subs = vec![
+            make_subtitle("Bottom", 0, 1000, SubtitlePosition::Bottom, 32, "#ffffff"),
+            make_subtitle("Top", 0, 1000, SubtitlePosition::Top, 32, "#ffffff"),
+        ];
+        let filters = build_subtitle_filters(&subs, safe);
+        assert!(filters[0].contains("x=w*0.0500+(w*0.8000-tw)/2:y=h-th-30-h*0.2000:"));
+        assert!(filters[1].contains("y=30+h*0.1000:"));
     }
 
     // ── build_cursor_zoom_filter ──
@@ -1598,8 +2703,110 @@ mod tests {
         assert!(result.is_some());
     }
 
+    #[test]
+    fn test_cursor_zoom_long_clip_stays_bounded() {
+        // An hour of sweeping, one sample every 100 ms
+        let positions: Vec<CursorPosition> = (0..36_000u64)
+            .map(|i| CursorPosition { timestamp_ms: i * 100, x: 0.5 + 0.4 * (i as f64 / 37.0).sin(), y: 0.5 })
+            .collect();
+        let filter = build_cursor_zoom_filter(&positions, 0, 1920, 1080).unwrap();
+        assert!(filter.matches("if(lt(t").count() <= 2 * MAX_CURSOR_KEYFRAMES);
+        // The camera keeps following past the first seconds
+        assert!(filter.contains("3599.000"));
+    }
+
     // ── extract_time ──
 
+    // ── target size ──
+
+    #[test]
+    fn test_target_kbps_video_only() {
+        // 10 MB over 60s: 10*8*1024*0.97/60 ≈ 1324 kbps
+        assert_eq!(target_video_kbps(10, 60.0, false), 1324);
+    }
+
+    #[test]
+    fn test_target_kbps_reserves_audio() {
+        assert_eq!(target_video_kbps(10, 60.0, true), 1324 - 128);
+    }
+
+    #[test]
+    fn test_target_kbps_floor() {
+        assert_eq!(target_video_kbps(1, 3600.0, true), MIN_VIDEO_KBPS);
+    }
+
+    #[test]
+    fn test_codec_args_crf_by_default() {
+        let args = video_codec_args(&ExportQuality::High, None, 30);
+        assert!(args.windows(2).any(|w| w[0] == "-crf" && w[1] == "18"));
+        assert!(!args.contains(&"-b:v".to_string()));
+    }
+
+    #[test]
+    fn test_codec_args_target_bitrate() {
+        let args = video_codec_args(&ExportQuality::Medium, Some(800), 30);
+        assert!(args.windows(2).any(|w| w[0] == "-b:v" && w[1] == "800k"));
+        assert!(args.windows(2).any(|w| w[0] == "-maxrate" && w[1] == "800k"));
+        assert!(!args.contains(&"-crf".to_string()));
+    }
+
+    #[test]
+    fn test_codec_args_fps() {
+        let args = video_codec_args(&ExportQuality::Low, None, 60);
+        assert!(args.windows(2).any(|w| w[0] == "-r" && w[1] == "60"));
+    }
+
+    // ── output settings ──
+
+    #[test]
+    fn test_output_scale_filter() {
+        assert!(output_scale_filter(&OutputSettings::default()).is_none());
+        let fixed = OutputSettings { width: Some(1080), height: Some(1920), ..Default::default() };
+        assert!(output_scale_filter(&fixed).unwrap().contains("pad=1080:1920"));
+        let crop = OutputSettings { fit: CanvasFit::Crop, ..fixed };
+        assert!(output_scale_filter(&crop).unwrap().contains("crop=1080:1920"));
+        let width_only = OutputSettings { width: Some(800), ..Default::default() };
+        assert_eq!(output_scale_filter(&width_only).unwrap(), "scale=800:-2");
+    }
+
+    #[test]
+    fn test_output_scale_filter_upscale() {
+        let uhd = OutputSettings { width: Some(3840), height: Some(2160), upscale: true, ..Default::default() };
+        let filter = output_scale_filter(&uhd).unwrap();
+        assert!(filter.starts_with("scale=3840:2160:flags=lanczos:force_original_aspect_ratio=decrease"));
+        assert!(filter.ends_with(UPSCALE_SHARPEN));
+        let nvenc = OutputSettings { height: Some(1440), upscale: true, encoder: ExportEncoder::Nvenc, ..Default::default() };
+        let (before, after) = output_stage_filters(&nvenc, false);
+        assert_eq!(before, vec![format!("scale=-2:1440:flags=lanczos,{UPSCALE_SHARPEN}")]);
+        assert_eq!(after, vec!["format=nv12,hwupload_cuda".to_string()]);
+    }
+
+    #[test]
+    fn test_output_stage_filters_gpu() {
+        let width_only = OutputSettings { width: Some(800), encoder: ExportEncoder::Nvenc, ..Default::default() };
+        let (before, after) = output_stage_filters(&width_only, false);
+        assert!(before.is_empty());
+        assert_eq!(after, vec!["format=nv12,hwupload_cuda,scale_cuda=800:-2".to_string()]);
+        // Text is drawn on the CPU at the final size, then uploaded
+        let (before, after) = output_stage_filters(&width_only, true);
+        assert_eq!(before, vec!["scale=800:-2".to_string()]);
+        assert_eq!(after, vec!["format=nv12,hwupload_cuda".to_string()]);
+        let software = OutputSettings { width: Some(800), ..Default::default() };
+        assert!(output_stage_filters(&software, false).1.is_empty());
+    }
+
+    #[test]
+    fn test_codec_args_hardware() {
+        let nvenc = OutputSettings { encoder: ExportEncoder::Nvenc, ..Default::default() };
+        let args = codec_args(&ExportQuality::High, None, &nvenc);
+        assert!(args.contains(&"h264_nvenc".to_string()));
+        assert!(!args.contains(&"-pix_fmt".to_string()));
+        let qsv = OutputSettings { encoder: ExportEncoder::Qsv, ..Default::default() };
+        let args = codec_args(&ExportQuality::Medium, Some(800), &qsv);
+        assert!(args.contains(&"-filter_hw_device".to_string()));
+        assert!(args.contains(&"800k".to_string()));
+    }
+
     #[test]
     fn test_extract_time_valid() {
         let line = "frame=  100 fps=30 time=00:01:23.45 bitrate=1234kbits/s";
@@ -1623,7 +2830,7 @@ mod tests {
     #[test]
     fn test_keystroke_filter_basic() {
         let events = vec![KeystrokeEvent { timestamp_ms: 1000, key_name: "A".into() }];
-        let filters = build_keystroke_filters(&events, 0.0, 0);
+        let filters = build_keystroke_filters(&events, 0.0, 0, None, SafeArea::default());
         assert_eq!(filters.len(), 1);
         assert!(filters[0].contains("drawtext="));
         assert!(filters[0].contains("A"));
@@ -1632,7 +2839,7 @@ mod tests {
     #[test]
     fn test_keystroke_filter_time_offset() {
         let events = vec![KeystrokeEvent { timestamp_ms: 1000, key_name: "B".into() }];
-        let filters = build_keystroke_filters(&events, 5.0, 0);
+        let filters = build_keystroke_filters(&events, 5.0, 0, None, SafeArea::default());
         assert_eq!(filters.len(), 1);
         // Event at 1.0s + offset 5.0s = 6.0s
         assert!(filters[0].contains("6.0"));
@@ -1641,7 +2848,7 @@ mod tests {
     #[test]
     fn test_keystroke_filter_trim_offset() {
         let events = vec![KeystrokeEvent { timestamp_ms: 3000, key_name: "C".into() }];
-        let filters = build_keystroke_filters(&events, 0.0, 2000);
+        let filters = build_keystroke_filters(&events, 0.0, 2000, None, SafeArea::default());
         assert_eq!(filters.len(), 1);
         // Event at 3.0s - trim 2.0s = 1.0s
         assert!(filters[0].contains("1.0"));
@@ -1650,8 +2857,58 @@ mod tests {
     #[test]
     fn test_keystroke_filter_skips_negative_time() {
         let events = vec![KeystrokeEvent { timestamp_ms: 500, key_name: "D".into() }];
-        let filters = build_keystroke_filters(&events, 0.0, 2000);
+        let filters = build_keystroke_filters(&events, 0.0, 2000, None, SafeArea::default());
         // Event at 0.5s - trim 2.0s = -1.5s → skipped
         assert!(filters.is_empty());
     }
+
+    #[test]
+    fn test_keystroke_filter_safe_area() {
+        let events = vec![KeystrokeEvent { timestamp_ms: 1000, key_name: "E".into() }];
+        let filters = build_keystroke_filters(&events, 0.0, 0, None, SafeArea::default());
+        assert!(filters[0].contains(":x=30:y=h-80:"));
+        let safe = SafeArea { bottom: 0.2, left: 0.05, ..Default::default() };
+        let filters = build_keystroke_filters(&events, 0.0, 0, None, safe);
+        assert!(filters[0].contains(":x=30+w*0.0500:y=h-80-h*0.2000:"));
+    }
+
+    #[test]
+    fn test_gif_palette_cuts_at_clip_starts() {
+        let spans = vec![(0, 5000), (4000, 9000), (9000, 12_000)];
+        assert!(gif_palette_cuts(&spans, false).is_empty());
+        assert_eq!(gif_palette_cuts(&spans, true), vec![4.0, 9.0]);
+    }
+
+    #[test]
+    fn test_gif_filters_one_palette_per_scene() {
+        let (pass1, pass2) = gif_filters("fps=12,scale=480:-1", 64, &[4.0], false);
+        assert!(pass1.contains("split=2[s0][s1]"));
+        assert!(pass1.contains("[s0]trim=end=4.000,palettegen=max_colors=64:stats_mode=diff[pal0]"));
+        assert!(pass1.contains("[s1]trim=start=4.000,palettegen=max_colors=64:stats_mode=diff[pal1]"));
+        assert!(pass2.contains("[v1][2:v]paletteuse"));
+        assert!(pass2.ends_with("[g0][g1]concat=n=2:v=1:a=0"));
+
+        let (pass1, _) = gif_filters("fps=12", 256, &[], false);
+        assert_eq!(pass1, "fps=12,palettegen=max_colors=256:stats_mode=diff");
+    }
+
+    #[test]
+    fn test_gif_boomerang_reverses_the_quantized_frames() {
+        let (pass1, pass2) = gif_filters("fps=12", 256, &[], true);
+        assert_eq!(pass1, "fps=12,palettegen=max_colors=256:stats_mode=diff");
+        assert_eq!(
+            pass2,
+            "fps=12[x];[x][1:v]paletteuse=dither=bayer:bayer_scale=5,split[fw][bw];\
+             [bw]reverse,trim=start_frame=1,setpts=PTS-STARTPTS[rv];[fw][rv]concat=n=2:v=1:a=0"
+        );
+        let (_, pass2) = gif_filters("fps=12", 256, &[4.0], true);
+        assert!(pass2.contains("[g0][g1]concat=n=2:v=1:a=0,split[fw][bw]"));
+    }
+
+    #[test]
+    fn test_gif_loop_arg() {
+        assert_eq!(gif_loop_arg(0), 0);
+        assert_eq!(gif_loop_arg(1), -1);
+        assert_eq!(gif_loop_arg(3), 2);
+    }
 }
@@ -0,0 +1,232 @@
+use super::encoder::{gif_settings, video_codec_args, AUDIO_BITRATE_KBPS, DEFAULT_FPS};
+use crate::types::{Clip, ExportFormat, ExportQuality, OutputSettings, SizeEstimate, SizeEstimateMethod};
+use std::process::Stdio;
+
+/// Length of the optional sample encode
+const SAMPLE_SECONDS: f64 = 5.0;
+
+/// Timeline length after trims, in milliseconds
+pub fn timeline_duration_ms(clips: &[Clip]) -> u64 {
+    clips.iter()
+        .map(|c| {
+            let end = if c.media.trim_end_ms > 0 { c.media.trim_end_ms } else { c.media.duration_ms };
+            end.saturating_sub(c.media.trim_start_ms)
+        })
+        .sum()
+}
+
+/// Canvas size of the export: every clip is padded to the largest one,
+/// then resized by the preset
+pub(crate) fn canvas_size(clips: &[Clip], output: &OutputSettings) -> (u32, u32) {
+    let w = (clips.iter().map(|c| c.media.region.width).max().unwrap_or(1920) / 2) * 2;
+    let h = (clips.iter().map(|c| c.media.region.height).max().unwrap_or(1080) / 2) * 2;
+    match (output.width, output.height) {
+        (Some(ow), Some(oh)) => (ow, oh),
+        (Some(ow), None) => scale_to_width(w, h, ow),
+        (None, Some(oh)) => {
+            let (sh, sw) = scale_to_width(h, w, oh);
+            (sw, sh)
+        }
+        (None, None) => (w, h),
+    }
+}
+
+/// Bits per pixel per frame libx264 spends on typical screen content.
+/// Screen captures are mostly static, so these sit well below camera footage.
+fn mp4_bits_per_pixel(quality: &ExportQuality) -> f64 {
+    match quality {
+        ExportQuality::High => 0.09,
+        ExportQuality::Medium => 0.05,
+        ExportQuality::Low => 0.03,
+    }
+}
+
+/// VP9 needs roughly this share of x264's bitrate for the same quality
+const WEBM_SIZE_RATIO: f64 = 0.75;
+
+/// GIF bytes per pixel per frame with `stats_mode=diff` palettes
+const GIF_BYTES_PER_PIXEL: f64 = 0.08;
+
+/// Size from duration, resolution and the quality heuristics above
+pub fn heuristic(
+    clips: &[Clip],
+    format: ExportFormat,
+    quality: &ExportQuality,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
+) -> SizeEstimate {
+    let duration_ms = timeline_duration_ms(clips);
+    let duration_s = duration_ms as f64 / 1000.0;
+    let (width, height) = canvas_size(clips, output);
+    let has_audio = output.include_audio && clips.iter().any(|c| !c.media.audio_paths.is_empty());
+
+    let (bytes, width, height) = match format {
+        ExportFormat::Mp4 | ExportFormat::Webm => {
+            let ratio = if matches!(format, ExportFormat::Webm) { WEBM_SIZE_RATIO } else { 1.0 };
+            let bytes = match target_size_mb {
+                // Target-size mode aims just under the budget
+                Some(mb) if mb > 0 => mb as u64 * 1024 * 1024,
+                _ => {
+                    let fps = output.fps.unwrap_or(DEFAULT_FPS) as f64;
+                    let video_bits = width as f64 * height as f64 * fps * mp4_bits_per_pixel(quality) * duration_s * ratio;
+                    let audio_bits = if has_audio { AUDIO_BITRATE_KBPS * 1000.0 * duration_s } else { 0.0 };
+                    ((video_bits + audio_bits) / 8.0) as u64
+                }
+            };
+            (bytes, width, height)
+        }
+        ExportFormat::Gif => {
+            let (default_fps, max_width) = gif_settings(quality);
+            let fps = output.fps.unwrap_or(default_fps);
+            // A preset size was already applied by canvas_size
+            let (w, h) = if output.width.is_some() || output.height.is_some() {
+                (width, height)
+            } else {
+                scale_to_width(width, height, max_width)
+            };
+            let bytes = w as f64 * h as f64 * fps as f64 * duration_s * GIF_BYTES_PER_PIXEL;
+            (bytes as u64, w, h)
+        }
+    };
+
+    SizeEstimate {
+        bytes,
+        duration_ms,
+        width,
+        height,
+        method: SizeEstimateMethod::Heuristic,
+    }
+}
+
+/// Encode the first seconds of the first clip with the real MP4 settings and
+/// extrapolate to the whole timeline. Falls back to the heuristic for GIF,
+/// target-size exports, or when the sample fails.
+pub async fn sampled(
+    clips: &[Clip],
+    format: ExportFormat,
+    quality: &ExportQuality,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
+    temp_dir: &std::path::Path,
+) -> SizeEstimate {
+    let mut estimate = heuristic(clips, format, quality, target_size_mb, output);
+    let Some(first) = clips.first() else {
+        return estimate;
+    };
+    if !matches!(format, ExportFormat::Mp4) || target_size_mb.is_some_and(|mb| mb > 0) {
+        return estimate;
+    }
+
+    let sample_path = temp_dir.join(format!("size_sample_{}.mp4", uuid::Uuid::new_v4()));
+    let first_ms = timeline_duration_ms(std::slice::from_ref(first));
+    let sample_s = SAMPLE_SECONDS.min(first_ms as f64 / 1000.0).max(0.5);
+
+    let mut args: Vec<String> = vec![
+        "-ss".into(), format!("{:.3}", first.media.trim_start_ms as f64 / 1000.0),
+        "-t".into(), format!("{:.3}", sample_s),
+        "-i".into(), first.media.path.to_string_lossy().to_string(),
+        "-vf".into(), format!("scale={}:{}:force_original_aspect_ratio=decrease,pad={}:{}:(ow-iw)/2:(oh-ih)/2", estimate.width, estimate.height, estimate.width, estimate.height),
+    ];
+    args.extend(video_codec_args(quality, None, output.fps.unwrap_or(DEFAULT_FPS)));
+    args.extend(["-an", "-y"].iter().map(|s| s.to_string()));
+    args.push(sample_path.to_string_lossy().to_string());
+
+    let status = crate::ffmpeg_command()
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await;
+    let sample_bytes = std::fs::metadata(&sample_path).map(|m| m.len()).ok();
+    let _ = std::fs::remove_file(&sample_path);
+
+    match (status, sample_bytes) {
+        (Ok(s), Some(bytes)) if s.success() && bytes > 0 => {
+            let duration_s = estimate.duration_ms as f64 / 1000.0;
+            let video_bytes = bytes as f64 * duration_s / sample_s;
+            let has_audio = output.include_audio && clips.iter().any(|c| !c.media.audio_paths.is_empty());
+            let audio_bytes = if has_audio { AUDIO_BITRATE_KBPS * 1000.0 * duration_s / 8.0 } else { 0.0 };
+            estimate.bytes = (video_bytes + audio_bytes) as u64;
+            estimate.method = SizeEstimateMethod::Sample;
+        }
+        _ => tracing::warn!("Sample encode failed, keeping the heuristic estimate"),
+    }
+    estimate
+}
+
+/// Same as the GIF `scale={max_width}:-1` filter: fixed width, aspect kept
+fn scale_to_width(width: u32, height: u32, max_width: u32) -> (u32, u32) {
+    if width == 0 {
+        return (max_width, height);
+    }
+    let h = (height as f64 * max_width as f64 / width as f64).round() as u32;
+    (max_width, h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ClipMedia, Region, test_clip};
+
+    fn clip(duration_ms: u64, w: u32, h: u32, audio: bool) -> Clip {
+        let clip = test_clip("c", duration_ms);
+        Clip {
+            media: ClipMedia {
+                region: Region { x: 0, y: 0, width: w, height: h },
+                has_audio: audio,
+                audio_paths: if audio { vec!["a.wav".into()] } else { Vec::new() },
+                ..clip.media
+            },
+            ..clip
+        }
+    }
+
+    #[test]
+    fn test_timeline_duration_respects_trims() {
+        let mut c = clip(10_000, 1920, 1080, false);
+        c.media.trim_start_ms = 2_000;
+        c.media.trim_end_ms = 8_000;
+        assert_eq!(timeline_duration_ms(&[c, clip(1_000, 1920, 1080, false)]), 7_000);
+    }
+
+    #[test]
+    fn test_higher_quality_is_bigger() {
+        let clips = [clip(60_000, 1920, 1080, true)];
+        let high = heuristic(&clips, ExportFormat::Mp4, &ExportQuality::High, None, &OutputSettings::default()).bytes;
+        let low = heuristic(&clips, ExportFormat::Mp4, &ExportQuality::Low, None, &OutputSettings::default()).bytes;
+        assert!(high > low);
+    }
+
+    #[test]
+    fn test_target_size_is_the_estimate() {
+        let clips = [clip(60_000, 1920, 1080, true)];
+        let est = heuristic(&clips, ExportFormat::Mp4, &ExportQuality::High, Some(10), &OutputSettings::default());
+        assert_eq!(est.bytes, 10 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_gif_uses_scaled_size() {
+        let clips = [clip(10_000, 1920, 1080, false)];
+        let est = heuristic(&clips, ExportFormat::Gif, &ExportQuality::Medium, None, &OutputSettings::default());
+        assert_eq!(est.width, 480);
+        assert_eq!(est.height, 270);
+    }
+
+    #[test]
+    fn test_preset_canvas_overrides_timeline_size() {
+        let clips = [clip(10_000, 2560, 1440, false)];
+        let output = OutputSettings { width: Some(1080), height: Some(1920), ..Default::default() };
+        let est = heuristic(&clips, ExportFormat::Mp4, &ExportQuality::High, None, &output);
+        assert_eq!((est.width, est.height), (1080, 1920));
+        let output = OutputSettings { width: Some(1280), ..Default::default() };
+        let est = heuristic(&clips, ExportFormat::Mp4, &ExportQuality::High, None, &output);
+        assert_eq!((est.width, est.height), (1280, 720));
+    }
+
+    #[test]
+    fn test_scale_to_width_keeps_aspect() {
+        assert_eq!(scale_to_width(300, 200, 600), (600, 400));
+        assert_eq!(scale_to_width(1280, 720, 640), (640, 360));
+    }
+}
@@ -0,0 +1,203 @@
+use super::dual::{move_annotation, Rect};
+use super::encoder::translate_ffmpeg_error;
+use super::even;
+use crate::types::{Annotation, Clip, CursorPosition, FocusChange, Region};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::Stdio;
+
+/// One stretch of the clip framed on one window, in source pixels and seconds
+#[derive(Debug, Clone, PartialEq)]
+struct Shot {
+    start_s: f64,
+    end_s: Option<f64>,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// Cut the clip at each focus change. Windows are rounded to even sizes
+/// and kept inside the `width`x`height` source.
+fn shots(track: &[FocusChange], width: u32, height: u32) -> Vec<Shot> {
+    let (w, h) = (width as f64, height as f64);
+    track
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let shot_w = even(f.width * w).min(width & !1);
+            let shot_h = even(f.height * h).min(height & !1);
+            Shot {
+                // The first window is framed from the start whatever its timestamp
+                start_s: if i == 0 { 0.0 } else { f.timestamp_ms as f64 / 1000.0 },
+                end_s: track.get(i + 1).map(|next| next.timestamp_ms as f64 / 1000.0),
+                x: ((f.x * w).round() as u32).min(width - shot_w),
+                y: ((f.y * h).round() as u32).min(height - shot_h),
+                width: shot_w,
+                height: shot_h,
+            }
+        })
+        .collect()
+}
+
+/// Output size: large enough for the biggest window
+fn canvas(shots: &[Shot]) -> (u32, u32) {
+    let width = shots.iter().map(|s| s.width).max().unwrap_or(2);
+    let height = shots.iter().map(|s| s.height).max().unwrap_or(2);
+    (width, height)
+}
+
+/// Where the source lands on the canvas while `shot` is on screen: the
+/// window is scaled to fit and centered
+fn placement(shot: &Shot, source: (u32, u32), canvas: (u32, u32)) -> Rect {
+    let (cw, ch) = (canvas.0 as f64, canvas.1 as f64);
+    let scale = (cw / shot.width as f64).min(ch / shot.height as f64);
+    let offset_x = (cw - shot.width as f64 * scale) / 2.0;
+    let offset_y = (ch - shot.height as f64 * scale) / 2.0;
+    Rect {
+        x: (offset_x - shot.x as f64 * scale) / cw,
+        y: (offset_y - shot.y as f64 * scale) / ch,
+        w: source.0 as f64 * scale / cw,
+        h: source.1 as f64 * scale / ch,
+    }
+}
+
+/// Filter graph cutting input 0 into its shots, each cropped to its window,
+/// fitted to the canvas and joined back into `[v]`
+fn shots_filter(shots: &[Shot], canvas: (u32, u32)) -> String {
+    let (cw, ch) = canvas;
+    let n = shots.len();
+    let mut filter = String::new();
+    if n > 1 {
+        let _ = write!(filter, "[0:v]split={n}");
+        for i in 0..n {
+            let _ = write!(filter, "[in{i}]");
+        }
+        filter.push(';');
+    }
+    for (i, shot) in shots.iter().enumerate() {
+        let input = if n > 1 { format!("[in{i}]") } else { "[0:v]".to_string() };
+        let end = shot.end_s.map(|e| format!(":end={e:.3}")).unwrap_or_default();
+        let _ = write!(
+            filter,
+            "{input}trim=start={start:.3}{end},setpts=PTS-STARTPTS,crop={w}:{h}:{x}:{y},\
+             scale={cw}:{ch}:force_original_aspect_ratio=decrease,pad={cw}:{ch}:(ow-iw)/2:(oh-ih)/2,setsar=1[s{i}];",
+            start = shot.start_s, w = shot.width, h = shot.height, x = shot.x, y = shot.y,
+        );
+    }
+    for i in 0..n {
+        let _ = write!(filter, "[s{i}]");
+    }
+    let _ = write!(filter, "concat=n={n}:v=1:a=0,format=yuv420p[v]");
+    filter
+}
+
+/// Replace every clip recorded in follow-focus mode by its focused windows,
+/// one after the other, so the export pipeline sees a plain clip. Annotations
+/// keep their place over the window on screen when they appear; the cursor
+/// path is dropped so no cursor zoom stacks on top. Renders are kept in
+/// `temp_dir` and reused by later exports.
+pub async fn prepare_all(
+    clips: &mut [Clip],
+    annotations: &mut HashMap<String, Vec<Annotation>>,
+    cursor_positions: &mut HashMap<String, Vec<CursorPosition>>,
+    temp_dir: &Path,
+) -> Result<()> {
+    for clip in clips.iter_mut() {
+        let track = std::mem::take(&mut clip.media.focus_track);
+        if track.is_empty() {
+            continue;
+        }
+        let source = (clip.media.region.width, clip.media.region.height);
+        let shots = shots(&track, source.0, source.1);
+        let (width, height) = canvas(&shots);
+
+        let output = temp_dir.join(format!("{}_focus_{}x{}.mp4", clip.id, width, height));
+        if !output.exists() {
+            tracing::info!("Framing clip {} on {} focused windows", clip.id, shots.len());
+            render(clip, &shots_filter(&shots, (width, height)), &output).await?;
+        }
+
+        for ann in annotations.get_mut(&clip.id).into_iter().flatten() {
+            let at_s = (clip.media.trim_start_ms + ann.start_ms) as f64 / 1000.0;
+            let shot = shots.iter().rev().find(|s| s.start_s <= at_s).unwrap_or(&shots[0]);
+            move_annotation(ann, placement(shot, source, (width, height)));
+        }
+        cursor_positions.remove(&clip.id);
+        clip.media.path = output;
+        clip.media.region = Region { width, height, ..clip.media.region.clone() };
+    }
+    Ok(())
+}
+
+async fn render(clip: &Clip, filter: &str, output: &Path) -> Result<()> {
+    let result = crate::ffmpeg_command()
+        .args([
+            "-i", &clip.media.path.to_string_lossy(),
+            "-filter_complex", filter,
+            "-map", "[v]",
+            "-c:v", "libx264",
+            "-preset", "veryfast",
+            "-crf", "16",
+            "-an",
+            "-movflags", "+faststart",
+            "-y", &output.to_string_lossy(),
+        ])
+        .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped())
+        .output().await.context("Failed to run FFmpeg")?;
+    if !result.status.success() {
+        let _ = std::fs::remove_file(output);
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        anyhow::bail!(crate::i18n::tf("focus_failed", &[&translate_ffmpeg_error(&stderr)]));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn focus(timestamp_ms: u64, x: f64, y: f64, width: f64, height: f64) -> FocusChange {
+        FocusChange { timestamp_ms, x, y, width, height }
+    }
+
+    #[test]
+    fn test_shots_follow_focus_changes() {
+        let track = [focus(400, 0.0, 0.0, 0.5, 1.0), focus(5000, 0.5, 0.25, 0.5, 0.5)];
+        let shots = shots(&track, 1920, 1080);
+        assert_eq!(shots.len(), 2);
+        assert_eq!((shots[0].start_s, shots[0].end_s), (0.0, Some(5.0)));
+        assert_eq!((shots[0].width, shots[0].height), (960, 1080));
+        assert_eq!((shots[1].x, shots[1].y, shots[1].end_s), (960, 270, None));
+        assert_eq!(canvas(&shots), (960, 1080));
+
+        let filter = shots_filter(&shots, (960, 1080));
+        assert!(filter.starts_with("[0:v]split=2[in0][in1];"));
+        assert!(filter.contains("[in0]trim=start=0.000:end=5.000,setpts=PTS-STARTPTS,crop=960:1080:0:0,"));
+        assert!(filter.contains("[in1]trim=start=5.000,setpts"));
+        assert!(filter.ends_with("[s0][s1]concat=n=2:v=1:a=0,format=yuv420p[v]"));
+    }
+
+    #[test]
+    fn test_single_window_needs_no_split() {
+        let shots = shots(&[focus(0, 0.9, 0.9, 0.5, 0.5)], 1000, 1000);
+        // Pushed back inside the source
+        assert_eq!((shots[0].x, shots[0].y), (500, 500));
+        let filter = shots_filter(&shots, canvas(&shots));
+        assert!(filter.starts_with("[0:v]trim=start=0.000,"));
+        assert!(filter.ends_with("[s0]concat=n=1:v=1:a=0,format=yuv420p[v]"));
+    }
+
+    #[test]
+    fn test_placement_maps_window_onto_canvas() {
+        let shot = Shot { start_s: 0.0, end_s: None, x: 960, y: 270, width: 960, height: 540 };
+        // A 960x540 window on a 960x1080 canvas: full width, centered vertically
+        let rect = placement(&shot, (1920, 1080), (960, 1080));
+        let (x, y) = rect.point(0.5, 0.25);
+        assert!(x.abs() < 1e-9 && (y - 0.25).abs() < 1e-9);
+        let (x, y) = rect.point(1.0, 0.75);
+        assert!((x - 1.0).abs() < 1e-9 && (y - 0.75).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,176 @@
+use super::encoder::{build_keyframe_expr, translate_ffmpeg_error, CurvePoint};
+use super::even;
+use crate::capture::cursor::{position_at, thin};
+use crate::types::{Annotation, Clip, CursorPosition, Easing, FollowWindow, Region};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+
+/// The cursor path is read this often
+const SAMPLE_MS: u64 = 100;
+/// The window follows the average cursor position over this span, so it
+/// glides instead of shaking with every small move
+const SMOOTHING_MS: u64 = 1200;
+/// Camera path error allowed when thinning keyframes (share of the frame)
+const PATH_TOLERANCE: f64 = 0.005;
+/// Keeps the crop expressions of long clips manageable
+const MAX_KEYFRAMES: usize = 300;
+
+/// Top-left corner of a window of `size` (normalized to the frame) centered
+/// on the smoothed cursor path, over `duration_ms` of source time
+fn camera_path(positions: &[CursorPosition], duration_ms: u64, size: (f64, f64)) -> Vec<CursorPosition> {
+    let samples: Vec<(f64, f64)> = (0..=duration_ms / SAMPLE_MS)
+        .map(|i| position_at(positions, i * SAMPLE_MS).unwrap_or((0.5, 0.5)))
+        .collect();
+    let half_span = (SMOOTHING_MS / SAMPLE_MS / 2) as usize;
+    let (max_x, max_y) = ((1.0 - size.0).max(0.0), (1.0 - size.1).max(0.0));
+
+    let camera: Vec<CursorPosition> = (0..samples.len())
+        .map(|i| {
+            let window = &samples[i.saturating_sub(half_span)..(i + half_span + 1).min(samples.len())];
+            let n = window.len() as f64;
+            let cx = window.iter().map(|p| p.0).sum::<f64>() / n;
+            let cy = window.iter().map(|p| p.1).sum::<f64>() / n;
+            CursorPosition {
+                timestamp_ms: i as u64 * SAMPLE_MS,
+                x: (cx - size.0 / 2.0).clamp(0.0, max_x),
+                y: (cy - size.1 / 2.0).clamp(0.0, max_y),
+            }
+        })
+        .collect();
+
+    thin(&camera, PATH_TOLERANCE, MAX_KEYFRAMES)
+}
+
+/// Crop filter moving a `width`x`height` window along `camera`
+fn crop_filter(camera: &[CursorPosition], width: u32, height: u32) -> String {
+    let curve = |value: fn(&CursorPosition) -> f64| -> Vec<CurvePoint> {
+        camera.iter().map(|p| (p.timestamp_ms as f64 / 1000.0, value(p), Easing::Linear)).collect()
+    };
+    let x = build_keyframe_expr(&curve(|p| p.x), "t");
+    let y = build_keyframe_expr(&curve(|p| p.y), "t");
+    format!("crop=w={width}:h={height}:x='({x})*iw':y='({y})*ih':exact=1,format=yuv420p")
+}
+
+/// Replace every clip recorded in follow-cursor mode by the window cropped
+/// around its cursor path, so the export pipeline sees a plain clip of the
+/// window's size. Annotations keep their place in the window as it was when
+/// they appear; the cursor path is dropped so no cursor zoom stacks on top.
+/// Crops are kept in `temp_dir` and reused by later exports.
+pub async fn prepare_all(
+    clips: &mut [Clip],
+    annotations: &mut HashMap<String, Vec<Annotation>>,
+    cursor_positions: &mut HashMap<String, Vec<CursorPosition>>,
+    temp_dir: &Path,
+) -> Result<()> {
+    for clip in clips.iter_mut() {
+        let Some(window) = clip.media.follow_cursor.take() else { continue };
+        let Some(positions) = cursor_positions.remove(&clip.id).filter(|p| !p.is_empty()) else {
+            tracing::warn!("Clip {} has no cursor path to follow, exported whole", clip.id);
+            continue;
+        };
+
+        let FollowWindow { width, height } = window;
+        let (width, height) = (even(width.min(clip.media.region.width) as f64), even(height.min(clip.media.region.height) as f64));
+        let size = (width as f64 / clip.media.region.width.max(1) as f64, height as f64 / clip.media.region.height.max(1) as f64);
+        let camera = camera_path(&positions, clip.media.duration_ms, size);
+
+        let output = temp_dir.join(format!("{}_follow_{}x{}.mp4", clip.id, width, height));
+        if !output.exists() {
+            tracing::info!("Cropping clip {} around the cursor ({}x{})", clip.id, width, height);
+            render(clip, &crop_filter(&camera, width, height), &output).await?;
+        }
+
+        for ann in annotations.get_mut(&clip.id).into_iter().flatten() {
+            let at = position_at(&camera, clip.media.trim_start_ms + ann.start_ms).unwrap_or((0.0, 0.0));
+            move_annotation(ann, at, size);
+        }
+        clip.media.path = output;
+        clip.media.region = Region { width, height, ..clip.media.region.clone() };
+    }
+    Ok(())
+}
+
+/// Express an annotation in the coordinates of a window at `origin` of `size`
+fn move_annotation(ann: &mut Annotation, origin: (f64, f64), size: (f64, f64)) {
+    let map = |x: f64, y: f64| ((x - origin.0) / size.0, (y - origin.1) / size.1);
+    (ann.x, ann.y) = map(ann.x, ann.y);
+    ann.width /= size.0;
+    ann.height /= size.1;
+    if let Some(points) = ann.points.as_mut() {
+        for p in points.iter_mut() {
+            *p = map(p.0, p.1);
+        }
+    }
+}
+
+async fn render(clip: &Clip, filter: &str, output: &Path) -> Result<()> {
+    let result = crate::ffmpeg_command()
+        .args([
+            "-i", &clip.media.path.to_string_lossy(),
+            "-vf", filter,
+            "-c:v", "libx264",
+            "-preset", "veryfast",
+            "-crf", "16",
+            "-an",
+            "-movflags", "+faststart",
+            "-y", &output.to_string_lossy(),
+        ])
+        .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped())
+        .output().await.context("Failed to run FFmpeg")?;
+    if !result.status.success() {
+        let _ = std::fs::remove_file(output);
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        anyhow::bail!(crate::i18n::tf("follow_failed", &[&translate_ffmpeg_error(&stderr)]));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(timestamp_ms: u64, x: f64, y: f64) -> CursorPosition {
+        CursorPosition { timestamp_ms, x, y }
+    }
+
+    #[test]
+    fn test_camera_centers_and_stays_inside_frame() {
+        let path = [at(0, 0.5, 0.5), at(5000, 0.5, 0.5), at(5001, 0.99, 0.01), at(10_000, 0.99, 0.01)];
+        let camera = camera_path(&path, 10_000, (0.5, 0.5));
+        let (x, y) = position_at(&camera, 0).unwrap();
+        assert!((x - 0.25).abs() < 1e-6 && (y - 0.25).abs() < 1e-6);
+        // Pushed against the top right corner, never past it
+        let (x, y) = position_at(&camera, 10_000).unwrap();
+        assert!((x - 0.5).abs() < 1e-6 && y.abs() < 1e-6);
+        assert!(camera.iter().all(|p| (0.0..=0.5).contains(&p.x) && (0.0..=0.5).contains(&p.y)));
+    }
+
+    #[test]
+    fn test_camera_glides_over_jumps() {
+        let path = [at(0, 0.2, 0.5), at(3000, 0.2, 0.5), at(3001, 0.8, 0.5), at(6000, 0.8, 0.5)];
+        let camera = camera_path(&path, 6000, (0.2, 0.2));
+        // Halfway through the smoothing span when the cursor jumps
+        let (x, _) = position_at(&camera, 3000).unwrap();
+        assert!(x > 0.15 && x < 0.65, "{x}");
+        let (x, _) = position_at(&camera, 5000).unwrap();
+        assert!((x - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_crop_filter_and_annotation_mapping() {
+        let filter = crop_filter(&[at(0, 0.25, 0.25)], 960, 540);
+        assert!(filter.starts_with("crop=w=960:h=540:x='("));
+
+        let mut ann = Annotation {
+            x: 0.5,
+            y: 0.5,
+            width: 0.25,
+            height: 0.25,
+            ..crate::types::test_annotation(crate::types::AnnotationKind::Rectangle, 0, 1000)
+        };
+        move_annotation(&mut ann, (0.25, 0.25), (0.5, 0.5));
+        assert_eq!((ann.x, ann.y, ann.width, ann.height), (0.5, 0.5, 0.5, 0.5));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::types::FontFile;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// DejaVu Sans, shipped so drawtext never depends on fontconfig finding a font
+const DEFAULT_FONT: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+const DEFAULT_FONT_NAME: &str = "DejaVuSans.ttf";
+
+static DEFAULT_FONT_PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Extract the bundled font to `%LOCALAPPDATA%/ClipFlow/fonts` once per run
+fn default_font_path() -> Option<&'static Path> {
+    DEFAULT_FONT_PATH
+        .get_or_init(|| extract_to(&dirs::data_local_dir()?.join("ClipFlow").join("fonts")))
+        .as_deref()
+}
+
+/// Write the bundled font into `dir` unless it is already there
+fn extract_to(dir: &Path) -> Option<PathBuf> {
+    let path = dir.join(DEFAULT_FONT_NAME);
+    let up_to_date = std::fs::metadata(&path)
+        .map(|m| m.len() == DEFAULT_FONT.len() as u64)
+        .unwrap_or(false);
+    if !up_to_date {
+        if let Err(e) = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, DEFAULT_FONT)) {
+            tracing::warn!("Could not extract bundled font: {}", e);
+            return None;
+        }
+    }
+    Some(path)
+}
+
+/// The user's font when it exists, None for the bundled one
+fn custom_font(custom: Option<&str>) -> Option<&Path> {
+    let path = custom.filter(|p| !p.is_empty()).map(Path::new)?;
+    if !path.is_file() {
+        tracing::warn!("Font {:?} not found, using the bundled font", path);
+        return None;
+    }
+    Some(path)
+}
+
+/// `fontfile='...':` prefix for drawtext options: the user's font when it
+/// exists, the bundled one otherwise. Empty if neither is available.
+pub fn fontfile_arg(custom: Option<&str>) -> String {
+    let path = match custom_font(custom) {
+        Some(p) => Some(p),
+        None => default_font_path(),
+    };
+    match path {
+        Some(p) => format!("fontfile={}:", crate::ffmpeg::paths::filter_value(p)),
+        None => String::new(),
+    }
+}
+
+/// Font data for rasterizing text ourselves: the user's font when it can be
+/// read, the bundled one otherwise
+pub fn font_bytes(custom: Option<&str>) -> Vec<u8> {
+    custom
+        .filter(|p| !p.is_empty())
+        .and_then(|p| std::fs::read(p).ok())
+        .unwrap_or_else(|| DEFAULT_FONT.to_vec())
+}
+
+/// Fonts installed system-wide and per-user, sorted by name
+pub fn list_installed() -> Vec<FontFile> {
+    let mut dirs_to_scan: Vec<PathBuf> = Vec::new();
+    if let Some(windir) = std::env::var_os("WINDIR") {
+        dirs_to_scan.push(PathBuf::from(windir).join("Fonts"));
+    }
+    if let Some(local) = dirs::data_local_dir() {
+        dirs_to_scan.push(local.join("Microsoft").join("Windows").join("Fonts"));
+    }
+
+    let mut fonts: Vec<FontFile> = dirs_to_scan
+        .iter()
+        .filter_map(|d| std::fs::read_dir(d).ok())
+        .flat_map(|entries| entries.flatten().map(|e| e.path()))
+        .filter(|p| is_font_file(p))
+        .map(|p| FontFile {
+            name: p.file_stem().unwrap_or_default().to_string_lossy().to_string(),
+            path: p.to_string_lossy().to_string(),
+        })
+        .collect();
+    fonts.sort_by_key(|f| f.name.to_lowercase());
+    fonts.dedup_by(|a, b| a.path.eq_ignore_ascii_case(&b.path));
+    fonts
+}
+
+fn is_font_file(path: &Path) -> bool {
+    path.extension()
+        .map(|e| matches!(e.to_string_lossy().to_lowercase().as_str(), "ttf" | "otf" | "ttc"))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_custom_font_falls_back() {
+        assert!(custom_font(Some("Z:/nope/missing.ttf")).is_none());
+        assert!(custom_font(Some("")).is_none());
+    }
+
+    #[test]
+    fn test_extract_once() {
+        let dir = std::env::temp_dir().join(format!("clipflow-fonts-{}", uuid::Uuid::new_v4()));
+        let path = extract_to(&dir).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap().len(), DEFAULT_FONT.len());
+        assert_eq!(extract_to(&dir), Some(path));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_font_file() {
+        assert!(is_font_file(Path::new("a/Segoe UI.TTF")));
+        assert!(is_font_file(Path::new("b.otf")));
+        assert!(!is_font_file(Path::new("c.fon")));
+    }
+}
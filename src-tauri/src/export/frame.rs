@@ -0,0 +1,89 @@
+use super::encoder::translate_ffmpeg_error;
+use super::overlay;
+use crate::types::{Annotation, Clip};
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Stdio;
+
+/// Frames older than this before the requested time are not looked at
+const LOOKBACK_MS: u64 = 1000;
+
+/// Small JPEG of the frame on screen at `at_ms` (source time): the last one
+/// starting at or before it, so times past the end give the last frame.
+/// Trim end minus 1 ms is the last frame a trim keeps.
+pub async fn preview_jpeg(clip: &Clip, at_ms: u64, width: u32) -> Result<Vec<u8>> {
+    let (start_ms, length_ms) = lookback(at_ms);
+    let output = crate::ffmpeg_command()
+        .args([
+            "-ss", &format!("{:.3}", start_ms as f64 / 1000.0),
+            "-t", &format!("{:.3}", length_ms as f64 / 1000.0),
+            "-i", &clip.media.path.to_string_lossy(),
+            // Reversed, the wanted frame comes out first
+            "-vf", &format!("scale={width}:-2,reverse"),
+            "-frames:v", "1", "-c:v", "mjpeg", "-q:v", "5",
+            "-f", "image2pipe", "-",
+        ])
+        .stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped())
+        .output().await.context("Failed to run FFmpeg")?;
+    if !output.status.success() || output.stdout.is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(crate::i18n::tf("frame_extract_failed", &[&translate_ffmpeg_error(&stderr)]));
+    }
+    Ok(output.stdout)
+}
+
+/// Source span (start, length) whose last frame is the one on screen at
+/// `at_ms`, in ms. The extra ms keeps a frame starting exactly at `at_ms`.
+fn lookback(at_ms: u64) -> (u64, u64) {
+    let start = at_ms.saturating_sub(LOOKBACK_MS);
+    (start, at_ms - start + 1)
+}
+
+/// Save the frame at `at_ms` (source time) of the clip to `output` as a PNG,
+/// at the clip's full resolution. With `with_annotations`, the annotations
+/// on screen at that moment are drawn over it as the export would.
+pub async fn extract(
+    clip: &Clip,
+    annotations: &[Annotation],
+    at_ms: u64,
+    output: &Path,
+    with_annotations: bool,
+) -> Result<()> {
+    let result = crate::ffmpeg_command()
+        .args([
+            "-ss", &format!("{:.3}", at_ms as f64 / 1000.0),
+            "-i", &clip.media.path.to_string_lossy(),
+            "-frames:v", "1",
+        ])
+        .args(crate::ffmpeg::paths::image_output(output))
+        .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped())
+        .output().await.context("Failed to run FFmpeg")?;
+    if !result.status.success() || !output.exists() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        anyhow::bail!(crate::i18n::tf("frame_extract_failed", &[&translate_ffmpeg_error(&stderr)]));
+    }
+    if !with_annotations || annotations.is_empty() {
+        return Ok(());
+    }
+
+    let mut frame = image::open(output).with_context(|| crate::i18n::t("frame_unreadable"))?.to_rgba8();
+    // Annotation times are relative to the trimmed clip
+    let at_s = (at_ms as f64 - clip.media.trim_start_ms as f64) / 1000.0;
+    let layer = overlay::annotations_at(annotations, at_s, frame.width(), frame.height())?;
+    let png = layer.encode_png().with_context(|| crate::i18n::t("annotations_encode_failed"))?;
+    let layer = image::load_from_memory(&png).with_context(|| crate::i18n::t("annotations_unreadable"))?.to_rgba8();
+    image::imageops::overlay(&mut frame, &layer, 0, 0);
+    frame.save(output).with_context(|| crate::i18n::t("frame_write_failed"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookback_ends_at_the_requested_time() {
+        assert_eq!(lookback(5000), (4000, 1001));
+        assert_eq!(lookback(300), (0, 301));
+    }
+}
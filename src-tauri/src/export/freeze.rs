@@ -0,0 +1,156 @@
+use super::encoder::translate_ffmpeg_error;
+use super::range::clip_spans;
+use crate::types::{Annotation, Clip, Subtitle, Transition, TransitionType};
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Stdio;
+
+/// A clip part shorter than this is not worth keeping on its own
+const EDGE_MS: u64 = 100;
+
+/// Encode `frame` as a still clip of `duration_ms`, the size of the frame
+pub async fn render(frame: &Path, output: &Path, duration_ms: u64) -> Result<()> {
+    let result = crate::ffmpeg_command()
+        .args(["-loop", "1"])
+        .args(crate::ffmpeg::paths::image_input(frame))
+        .args([
+            "-t", &format!("{:.3}", duration_ms as f64 / 1000.0),
+            // libx264 yuv420p needs even dimensions
+            "-vf", "scale=trunc(iw/2)*2:trunc(ih/2)*2",
+            "-c:v", "libx264", "-preset", "veryfast", "-crf", "18", "-tune", "stillimage",
+            "-pix_fmt", "yuv420p", "-r", "30", "-movflags", "+faststart",
+            "-y", &output.to_string_lossy(),
+        ])
+        .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped())
+        .output().await.context("Failed to run FFmpeg")?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        anyhow::bail!(crate::i18n::tf("freeze_frame_failed", &[&translate_ffmpeg_error(&stderr)]));
+    }
+    Ok(())
+}
+
+/// Put `hold` in the timeline at `at_ms` (source time) of clip `index`.
+/// Within the kept part the clip is split: it ends at `at_ms` and a copy
+/// named `tail_id` picks up from there after the hold. Near the trim bounds
+/// the hold simply goes before or after it. Both sides of the hold are cuts
+/// and subtitles or timeline annotations past it move by its length, so the
+/// rest of the timeline plays as before. Returns how far into the clip the
+/// copy starts, in ms, when it was split.
+pub fn insert(
+    clips: &mut Vec<Clip>,
+    transitions: &mut Vec<Transition>,
+    subtitles: &mut [Subtitle],
+    global_annotations: &mut [Annotation],
+    index: usize,
+    at_ms: u64,
+    hold: Clip,
+    tail_id: String,
+) -> Option<u64> {
+    let clip = &clips[index];
+    let end = if clip.media.trim_end_ms > 0 { clip.media.trim_end_ms } else { clip.media.duration_ms };
+    let before = at_ms < clip.media.trim_start_ms + EDGE_MS;
+    let split = !before && at_ms + EDGE_MS <= end;
+    let hold_ms = hold.media.duration_ms;
+
+    let cut = Transition { transition_type: TransitionType::Cut, ..Transition::default() };
+    let transition_at = index.min(transitions.len());
+    let hold_at = if before { index } else { index + 1 };
+    let mut tail_start = None;
+    if split {
+        let mut tail = clips[index].clone();
+        tail_start = Some(at_ms - tail.media.trim_start_ms);
+        tail.id = tail_id;
+        tail.media.trim_start_ms = at_ms;
+        tail.media.thumbnail_path = None;
+        // Other takes stay with the first part
+        tail.takes.clear();
+        clips[index].media.trim_end_ms = at_ms;
+        clips.insert(index + 1, tail);
+        transitions.insert(transition_at, cut.clone());
+    }
+    clips.insert(hold_at, hold);
+    transitions.insert(transition_at, cut);
+
+    let hold_start = clip_spans(clips, transitions)[hold_at].0;
+    hold_subtitles(subtitles, hold_start, hold_ms);
+    // Same for timeline annotations, with their animation
+    for ann in global_annotations.iter_mut().filter(|a| a.end_ms > hold_start) {
+        if ann.start_ms >= hold_start {
+            ann.start_ms += hold_ms;
+        }
+        ann.end_ms += hold_ms;
+        for kf in ann.keyframes.iter_mut().filter(|k| k.time_ms >= hold_start) {
+            kf.time_ms += hold_ms;
+        }
+    }
+    tail_start
+}
+
+/// Make room for a hold of `hold_ms` at `hold_start` of the timeline.
+/// Subtitles on screen when the hold starts stay up during it.
+pub fn hold_subtitles(subtitles: &mut [Subtitle], hold_start: u64, hold_ms: u64) {
+    for sub in subtitles.iter_mut() {
+        if sub.start_ms >= hold_start {
+            sub.start_ms += hold_ms;
+            sub.end_ms += hold_ms;
+        } else if sub.end_ms > hold_start {
+            sub.end_ms += hold_ms;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SubtitlePosition, test_clip};
+
+    fn subtitle(start_ms: u64, end_ms: u64) -> Subtitle {
+        Subtitle {
+            id: String::new(),
+            text: String::new(),
+            start_ms,
+            end_ms,
+            position: SubtitlePosition::Bottom,
+            font_size: 32,
+            color: "#fff".into(),
+            font_file: None,
+        }
+    }
+
+    fn ids(clips: &[Clip]) -> Vec<&str> {
+        clips.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_split_around_the_hold() {
+        let mut clips = vec![test_clip("a", 10_000), test_clip("b", 10_000)];
+        clips[0].media.trim_start_ms = 1000;
+        let mut transitions = vec![Transition::default()];
+        let mut subtitles = vec![subtitle(1000, 2000), subtitle(3500, 5000), subtitle(6000, 7000)];
+
+        let tail = insert(&mut clips, &mut transitions, &mut subtitles, &mut [], 0, 5000, test_clip("hold", 2000), "tail".into());
+        assert_eq!(tail, Some(4000));
+        assert_eq!(ids(&clips), vec!["a", "hold", "tail", "b"]);
+        assert_eq!((clips[0].media.trim_start_ms, clips[0].media.trim_end_ms), (1000, 5000));
+        assert_eq!((clips[2].media.trim_start_ms, clips[2].media.trim_end_ms), (5000, 0));
+        let types: Vec<TransitionType> = transitions.iter().map(|t| t.transition_type).collect();
+        assert_eq!(types, vec![TransitionType::Cut, TransitionType::Cut, TransitionType::Fade]);
+        // The hold starts 4 s into the timeline
+        let spans: Vec<(u64, u64)> = subtitles.iter().map(|s| (s.start_ms, s.end_ms)).collect();
+        assert_eq!(spans, vec![(1000, 2000), (3500, 7000), (8000, 9000)]);
+    }
+
+    #[test]
+    fn test_hold_at_the_edges() {
+        let mut clips = vec![test_clip("a", 10_000)];
+        let mut transitions = Vec::new();
+        let tail = insert(&mut clips, &mut transitions, &mut [], &mut [], 0, 9950, test_clip("end", 1000), "t1".into());
+        assert_eq!(tail, None);
+        let tail = insert(&mut clips, &mut transitions, &mut [], &mut [], 0, 0, test_clip("start", 1000), "t2".into());
+        assert_eq!(tail, None);
+        assert_eq!(ids(&clips), vec!["start", "a", "end"]);
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(clips[1].media.trim_end_ms, 0);
+    }
+}
@@ -0,0 +1,233 @@
+use super::encoder::translate_ffmpeg_error;
+use crate::capture::cursor::position_at;
+use crate::types::{Clip, CursorPosition};
+use anyhow::{Context, Result};
+use image::{Rgba, RgbaImage};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// The cursor path is sampled this often, so resting spots weigh by how long
+/// the cursor stayed there
+const SAMPLE_MS: u64 = 100;
+/// Width of the density grid, the PNG is interpolated from it
+const GRID_WIDTH: usize = 256;
+/// Blur radius (sigma) as a fraction of the grid width
+const BLUR_FRACTION: f64 = 0.025;
+/// Longest side of the PNG
+const MAX_SIDE: u32 = 1920;
+/// Opacity of the hottest spots once blended onto a frame
+const FRAME_ALPHA: f32 = 0.7;
+/// Cells below this stay transparent
+const COLD: f32 = 0.02;
+
+/// Where the cursor spent its time, normalized so the hottest cell is 1
+#[derive(Debug)]
+pub struct Heatmap {
+    width: usize,
+    height: usize,
+    cells: Vec<f32>,
+}
+
+impl Heatmap {
+    /// Density of the cursor path between `from_ms` and `to_ms` (source
+    /// time) over a region of `aspect` width/height. None without samples.
+    pub fn from_path(points: &[CursorPosition], from_ms: u64, to_ms: u64, aspect: f64) -> Option<Self> {
+        if points.is_empty() || to_ms <= from_ms {
+            return None;
+        }
+        let width = GRID_WIDTH;
+        let height = ((GRID_WIDTH as f64 / aspect.max(0.1)).round() as usize).clamp(1, GRID_WIDTH * 10);
+        let mut cells = vec![0.0f32; width * height];
+        for t in (from_ms..to_ms).step_by(SAMPLE_MS as usize) {
+            if let Some((x, y)) = position_at(points, t) {
+                let cx = ((x * width as f64) as usize).min(width - 1);
+                let cy = ((y * height as f64) as usize).min(height - 1);
+                cells[cy * width + cx] += 1.0;
+            }
+        }
+
+        blur(&mut cells, width, height, GRID_WIDTH as f64 * BLUR_FRACTION);
+        let max = cells.iter().cloned().fold(0.0f32, f32::max);
+        if max <= 0.0 {
+            return None;
+        }
+        // Square root so short visits still show next to long rests
+        for c in &mut cells {
+            *c = (*c / max).sqrt();
+        }
+        Some(Self { width, height, cells })
+    }
+
+    /// Bilinear value at (u, v), both 0-1
+    fn at(&self, u: f64, v: f64) -> f32 {
+        let x = (u * self.width as f64 - 0.5).clamp(0.0, (self.width - 1) as f64);
+        let y = (v * self.height as f64 - 0.5).clamp(0.0, (self.height - 1) as f64);
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (fx, fy) = ((x - x0 as f64) as f32, (y - y0 as f64) as f32);
+        let cell = |x: usize, y: usize| self.cells[y * self.width + x];
+        let top = cell(x0, y0) * (1.0 - fx) + cell(x1, y0) * fx;
+        let bottom = cell(x0, y1) * (1.0 - fx) + cell(x1, y1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+
+    /// Colored heatmap on a transparent background, `alpha` for the hottest spots
+    pub fn to_image(&self, width: u32, height: u32, alpha: f32) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, y| {
+            let v = self.at((x as f64 + 0.5) / width as f64, (y as f64 + 0.5) / height as f64);
+            ramp(v, alpha)
+        })
+    }
+}
+
+/// Separable gaussian blur, in place
+fn blur(cells: &mut [f32], width: usize, height: usize, sigma: f64) {
+    let radius = (sigma * 3.0).ceil() as isize;
+    let kernel: Vec<f32> =
+        (-radius..=radius).map(|i| (-(i * i) as f64 / (2.0 * sigma * sigma)).exp() as f32).collect();
+    let pass = |src: &[f32], len: usize, stride: usize, count: usize, step: usize| {
+        let mut out = vec![0.0f32; src.len()];
+        for line in 0..count {
+            let base = line * step;
+            for i in 0..len {
+                let mut sum = 0.0;
+                for (k, w) in kernel.iter().enumerate() {
+                    let j = i as isize + k as isize - radius;
+                    if j >= 0 && (j as usize) < len {
+                        sum += src[base + j as usize * stride] * w;
+                    }
+                }
+                out[base + i * stride] = sum;
+            }
+        }
+        out
+    };
+    let rows = pass(cells, width, 1, height, width);
+    let cols = pass(&rows, height, width, width, 1);
+    cells.copy_from_slice(&cols);
+}
+
+/// Blue (rarely visited) through green and yellow to red (most visited)
+fn ramp(v: f32, alpha: f32) -> Rgba<u8> {
+    if v < COLD {
+        return Rgba([0, 0, 0, 0]);
+    }
+    const STOPS: [[f32; 3]; 5] = [
+        [0.0, 0.0, 255.0],
+        [0.0, 255.0, 255.0],
+        [0.0, 255.0, 0.0],
+        [255.0, 255.0, 0.0],
+        [255.0, 0.0, 0.0],
+    ];
+    let pos = v.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let i = (pos.floor() as usize).min(STOPS.len() - 2);
+    let f = pos - i as f32;
+    let channel = |c: usize| (STOPS[i][c] + (STOPS[i + 1][c] - STOPS[i][c]) * f).round() as u8;
+    // Fade in from the cold edge instead of a hard outline
+    let a = (v * 2.0).min(1.0) * alpha;
+    Rgba([channel(0), channel(1), channel(2), (a * 255.0).round() as u8])
+}
+
+/// PNG size for a capture region: its own size, capped at `MAX_SIDE`
+fn image_size(width: u32, height: u32) -> (u32, u32) {
+    let scale = (MAX_SIDE as f64 / width.max(height).max(1) as f64).min(1.0);
+    (((width as f64 * scale).round() as u32).max(1), ((height as f64 * scale).round() as u32).max(1))
+}
+
+/// Write the clip's cursor heatmap to `output_path` and, with `with_frame`,
+/// a second PNG blended onto the frame in the middle of the trimmed clip.
+/// Returns the written files.
+pub async fn export(
+    clip: &Clip,
+    positions: &[CursorPosition],
+    output_path: &Path,
+    with_frame: bool,
+) -> Result<Vec<PathBuf>> {
+    let end_ms = if clip.media.trim_end_ms > 0 { clip.media.trim_end_ms } else { clip.media.duration_ms };
+    let aspect = clip.media.region.width as f64 / clip.media.region.height.max(1) as f64;
+    let heatmap = Heatmap::from_path(positions, clip.media.trim_start_ms, end_ms, aspect)
+        .with_context(|| crate::i18n::t("heatmap_no_cursor"))?;
+    let (width, height) = image_size(clip.media.region.width, clip.media.region.height);
+
+    heatmap
+        .to_image(width, height, 1.0)
+        .save(output_path)
+        .with_context(|| crate::i18n::t("heatmap_write_failed"))?;
+    let mut written = vec![output_path.to_path_buf()];
+
+    if with_frame {
+        let frame_path = output_path.with_extension("frame.png");
+        let mid_s = (clip.media.trim_start_ms + end_ms) as f64 / 2000.0;
+        let mut frame = extract_frame(&clip.media.path, mid_s, width, height, &frame_path).await?;
+        image::imageops::overlay(&mut frame, &heatmap.to_image(width, height, FRAME_ALPHA), 0, 0);
+        let blended_path = output_path.with_extension("overlay.png");
+        frame.save(&blended_path).with_context(|| crate::i18n::t("heatmap_write_failed"))?;
+        written.push(blended_path);
+    }
+    Ok(written)
+}
+
+/// One frame of the clip at `at_s`, scaled to `width`x`height`
+async fn extract_frame(video: &Path, at_s: f64, width: u32, height: u32, temp_path: &Path) -> Result<RgbaImage> {
+    let output = crate::ffmpeg_command()
+        .args([
+            "-ss", &format!("{at_s:.3}"),
+            "-i", &video.to_string_lossy(),
+            "-frames:v", "1",
+            "-vf", &format!("scale={width}:{height}"),
+        ])
+        .args(crate::ffmpeg::paths::image_output(temp_path))
+        .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped())
+        .output().await.context("Failed to extract frame")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(crate::i18n::tf("frame_extract_failed", &[&translate_ffmpeg_error(&stderr)]));
+    }
+    let frame = image::open(temp_path).with_context(|| crate::i18n::t("frame_unreadable"));
+    let _ = std::fs::remove_file(temp_path);
+    Ok(frame?.to_rgba8())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rest(from_ms: u64, to_ms: u64, x: f64, y: f64) -> [CursorPosition; 2] {
+        [CursorPosition { timestamp_ms: from_ms, x, y }, CursorPosition { timestamp_ms: to_ms, x, y }]
+    }
+
+    #[test]
+    fn test_heatmap_weighs_by_dwell_time() {
+        let mut path = rest(0, 9000, 0.25, 0.5).to_vec();
+        path.extend(rest(9001, 10_000, 0.75, 0.5));
+        let heatmap = Heatmap::from_path(&path, 0, 10_000, 16.0 / 9.0).unwrap();
+        assert_eq!(heatmap.width, GRID_WIDTH);
+        assert_eq!(heatmap.height, 144);
+
+        let (long, short) = (heatmap.at(0.25, 0.5), heatmap.at(0.75, 0.5));
+        assert!((long - 1.0).abs() < 0.05, "{long}");
+        assert!(short > COLD && short < long, "{short}");
+        assert!(heatmap.at(0.02, 0.02) < COLD);
+    }
+
+    #[test]
+    fn test_heatmap_respects_trim() {
+        let mut path = rest(0, 5000, 0.1, 0.1).to_vec();
+        path.extend(rest(5001, 10_000, 0.9, 0.9));
+        let heatmap = Heatmap::from_path(&path, 6000, 10_000, 1.0).unwrap();
+        assert!(heatmap.at(0.1, 0.1) < COLD);
+        assert!(heatmap.at(0.9, 0.9) > 0.9);
+        assert!(Heatmap::from_path(&[], 0, 1000, 1.0).is_none());
+        assert!(Heatmap::from_path(&path, 5000, 5000, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_ramp_and_size() {
+        assert_eq!(ramp(0.0, 1.0), Rgba([0, 0, 0, 0]));
+        assert_eq!(ramp(1.0, 1.0), Rgba([255, 0, 0, 255]));
+        assert_eq!(ramp(0.5, 0.5).0[..3], [0, 255, 0]);
+        assert_eq!(ramp(0.5, 0.5).0[3], 128);
+        assert_eq!(image_size(3840, 2160), (1920, 1080));
+        assert_eq!(image_size(800, 600), (800, 600));
+    }
+}
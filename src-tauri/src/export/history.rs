@@ -0,0 +1,58 @@
+use crate::types::ExportedFile;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Exports the drop import accepts. The folder also holds WebM exports, PNG
+/// stills and heatmaps, which are left out.
+const VIDEO_EXTENSIONS: [&str; 2] = ["mp4", "gif"];
+
+/// Videos in the export folder, newest first
+pub fn list(dir: &Path) -> Vec<ExportedFile> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<ExportedFile> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+            if !VIDEO_EXTENSIONS.contains(&ext.as_str()) {
+                return None;
+            }
+            let meta = entry.metadata().ok().filter(|m| m.is_file())?;
+            let modified_ms = meta.modified().ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            Some(ExportedFile {
+                name: path.file_name()?.to_string_lossy().to_string(),
+                path: path.to_string_lossy().to_string(),
+                size_bytes: meta.len(),
+                modified_ms,
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| b.modified_ms.cmp(&a.modified_ms).then_with(|| b.name.cmp(&a.name)));
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_keeps_videos_only() {
+        let dir = std::env::temp_dir().join(format!("clipflow_history_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("sub.mp4")).unwrap();
+        for name in ["a.mp4", "b.GIF", "frame.png", "notes.txt"] {
+            std::fs::write(dir.join(name), b"x").unwrap();
+        }
+
+        let mut names: Vec<String> = list(&dir).into_iter().map(|f| f.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["a.mp4", "b.GIF"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(list(&dir).is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use super::estimate::canvas_size;
+use crate::types::{Clip, ExportDownscale, ExportEncoder, OutputSettings, ResolutionCheck, ResolutionOption};
+
+/// Above this many pixels per frame (4K UHD) exports get slow and heavy
+const SLOW_PIXELS: u64 = 3840 * 2160;
+/// Heights offered for bringing a large timeline down
+const STANDARD_HEIGHTS: [u32; 3] = [2160, 1440, 1080];
+
+/// Largest frame the H.264 encoder of the export accepts. Every format goes
+/// through it: GIFs are made from an MP4, WebM from an MP4 mezzanine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EncoderLimit {
+    pub max_side: u32,
+    pub max_pixels: u64,
+}
+
+impl EncoderLimit {
+    /// x264 stops at level 6.2 (139 264 macroblocks), NVENC and Quick Sync
+    /// at 4096 pixels per side
+    pub fn of(encoder: ExportEncoder) -> Self {
+        match encoder {
+            ExportEncoder::Software => EncoderLimit { max_side: 16384, max_pixels: 139_264 * 256 },
+            ExportEncoder::Nvenc | ExportEncoder::Qsv => EncoderLimit { max_side: 4096, max_pixels: 4096 * 4096 },
+        }
+    }
+
+    pub fn fits(&self, (width, height): (u32, u32)) -> bool {
+        width <= self.max_side && height <= self.max_side && width as u64 * height as u64 <= self.max_pixels
+    }
+
+    /// Scale factor bringing `size` within the limit, 1 when it fits
+    fn scale(&self, (width, height): (u32, u32)) -> f64 {
+        let (w, h) = (width.max(1) as f64, height.max(1) as f64);
+        let side = self.max_side as f64 / w.max(h);
+        let area = (self.max_pixels as f64 / (w * h)).sqrt();
+        side.min(area).min(1.0)
+    }
+}
+
+/// `size` scaled by `factor`, aspect kept, even for yuv420p
+fn scaled((width, height): (u32, u32), factor: f64) -> (u32, u32) {
+    // The epsilon keeps 7680 × (4096 / 7680) from landing on 4095
+    let even = |v: u32| ((v as f64 * factor + 1e-6) as u32 / 2 * 2).max(2);
+    (even(width), even(height))
+}
+
+/// Size `downscale` brings a `size` export to
+fn downscaled(downscale: ExportDownscale, limit: &EncoderLimit, size: (u32, u32)) -> (u32, u32) {
+    let factor = match downscale {
+        ExportDownscale::Off => return size,
+        ExportDownscale::Auto => limit.scale(size),
+        ExportDownscale::MaxHeight(max) => limit.scale(size).min(max as f64 / size.1.max(1) as f64),
+    };
+    if factor < 1.0 { scaled(size, factor) } else { size }
+}
+
+/// Apply `downscale` to an output that doesn't set its own size, then
+/// refuse sizes the encoder would fail on after minutes of work. A timeline
+/// too large is only ever brought down whole, never split into tiles.
+pub fn enforce(
+    downscale: ExportDownscale,
+    encoder: ExportEncoder,
+    clips: &[Clip],
+    output: &mut OutputSettings,
+) -> Result<(), String> {
+    let limit = EncoderLimit::of(encoder);
+    if output.width.is_none() && output.height.is_none() {
+        let size = canvas_size(clips, output);
+        let (width, _) = downscaled(downscale, &limit, size);
+        if width < size.0 {
+            tracing::info!("Export downscaled from {}x{} to width {}", size.0, size.1, width);
+            // The height follows with the aspect ratio
+            output.width = Some(width);
+        }
+    }
+    let size = canvas_size(clips, output);
+    if !limit.fits(size) {
+        return Err(crate::i18n::tf("export_resolution_too_large", &[&size.0, &size.1, &limit.max_side]));
+    }
+    Ok(())
+}
+
+/// Size of the export with and without `downscale`, and the sizes it could
+/// be brought down to when it is too large for the encoder or slow
+pub fn check(downscale: ExportDownscale, encoder: ExportEncoder, clips: &[Clip], output: &OutputSettings) -> ResolutionCheck {
+    let limit = EncoderLimit::of(encoder);
+    let (width, height) = canvas_size(clips, output);
+    let sets_size = output.width.is_some() || output.height.is_some();
+    let (export_width, export_height) = if sets_size { (width, height) } else { downscaled(downscale, &limit, (width, height)) };
+
+    let exceeds_limit = !limit.fits((width, height));
+    let slow = width as u64 * height as u64 > SLOW_PIXELS;
+    let mut options = Vec::new();
+    if !sets_size && (exceeds_limit || slow) {
+        if exceeds_limit {
+            let (w, h) = downscaled(ExportDownscale::Auto, &limit, (width, height));
+            options.push(ResolutionOption { downscale: ExportDownscale::Auto, width: w, height: h });
+        }
+        for max in STANDARD_HEIGHTS {
+            let (w, h) = downscaled(ExportDownscale::MaxHeight(max), &limit, (width, height));
+            if h < height && !options.iter().any(|o| o.height == h) {
+                options.push(ResolutionOption { downscale: ExportDownscale::MaxHeight(max), width: w, height: h });
+            }
+        }
+    }
+
+    ResolutionCheck {
+        width,
+        height,
+        export_width,
+        export_height,
+        max_side: limit.max_side,
+        exceeds_limit,
+        fits: limit.fits((export_width, export_height)),
+        slow,
+        options,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ClipMedia, Region, test_clip};
+
+    fn clip(width: u32, height: u32) -> Clip {
+        let clip = test_clip("a", 1000);
+        Clip {
+            media: ClipMedia { region: Region { x: 0, y: 0, width, height }, ..clip.media },
+            ..clip
+        }
+    }
+
+    #[test]
+    fn test_encoder_limits() {
+        let x264 = EncoderLimit::of(ExportEncoder::Software);
+        assert!(x264.fits((7680, 4320)));
+        // Under 16384 per side but too many macroblocks
+        assert!(!x264.fits((12000, 3200)));
+        let nvenc = EncoderLimit::of(ExportEncoder::Nvenc);
+        assert!(nvenc.fits((3840, 2160)));
+        assert!(!nvenc.fits((5120, 1440)));
+    }
+
+    #[test]
+    fn test_auto_downscale_fits_the_encoder() {
+        let clips = [clip(5120, 1440)];
+        let mut output = OutputSettings::default();
+        enforce(ExportDownscale::Auto, ExportEncoder::Nvenc, &clips, &mut output).unwrap();
+        assert_eq!(output.width, Some(4096));
+
+        // Small enough: left alone
+        let mut output = OutputSettings::default();
+        enforce(ExportDownscale::Auto, ExportEncoder::Software, &clips, &mut output).unwrap();
+        assert_eq!(output.width, None);
+    }
+
+    #[test]
+    fn test_too_large_without_downscale_is_refused() {
+        let clips = [clip(5120, 1440)];
+        let mut output = OutputSettings::default();
+        assert!(enforce(ExportDownscale::Off, ExportEncoder::Nvenc, &clips, &mut output).is_err());
+        // A preset's own size is never changed
+        let mut output = OutputSettings { width: Some(1920), ..Default::default() };
+        enforce(ExportDownscale::Auto, ExportEncoder::Nvenc, &clips, &mut output).unwrap();
+        assert_eq!(output.width, Some(1920));
+    }
+
+    #[test]
+    fn test_max_height_keeps_the_aspect_ratio() {
+        let limit = EncoderLimit::of(ExportEncoder::Software);
+        assert_eq!(downscaled(ExportDownscale::MaxHeight(1080), &limit, (3840, 2160)), (1920, 1080));
+        assert_eq!(downscaled(ExportDownscale::MaxHeight(1080), &limit, (1280, 720)), (1280, 720));
+    }
+
+    #[test]
+    fn test_check_offers_smaller_sizes() {
+        let check = check(ExportDownscale::Off, ExportEncoder::Nvenc, &[clip(7680, 2160)], &OutputSettings::default());
+        assert!(check.exceeds_limit && !check.fits && check.slow);
+        assert_eq!((check.export_width, check.export_height), (7680, 2160));
+        let sizes: Vec<(u32, u32)> = check.options.iter().map(|o| (o.width, o.height)).collect();
+        assert_eq!(sizes, vec![(4096, 1152), (3840, 1080)]);
+        assert_eq!(check.options[0].downscale, ExportDownscale::Auto);
+    }
+}
@@ -0,0 +1,202 @@
+use super::autotrim::audio_sources;
+use super::encoder::translate_ffmpeg_error;
+use crate::types::{AudioAdvice, AudioAnalysis, Clip};
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncReadExt;
+
+/// A sample this close to full scale was clipped, allowing for the rounding
+/// of 16 and 24-bit sources
+const CLIP_LEVEL: f32 = 0.999;
+/// ebur128 reports silence at its gate, -70 LUFS
+const SILENCE_LUFS: f64 = -70.0;
+/// Usual loudness of speech in online video...
+const TARGET_LUFS: f64 = -16.0;
+/// ...and how far a track may stray before it wants normalizing
+const LOUDNESS_TOLERANCE: f64 = 4.0;
+/// Headroom lossy encoders need to not clip on decode
+const MAX_TRUE_PEAK: f64 = -1.0;
+/// More clipped samples than this share is audible distortion
+const MAX_CLIPPED_RATIO: f64 = 1e-4;
+
+/// Loudness, true peak and clipping of each audio source of the clip: its
+/// separate tracks, or its own audio for imported videos
+pub async fn analyze(clip: &Clip) -> Result<Vec<AudioAnalysis>> {
+    let sources = audio_sources(clip);
+    if sources.is_empty() {
+        anyhow::bail!(crate::i18n::t("clip_has_no_audio"));
+    }
+    let mut analyses = Vec::new();
+    for path in &sources {
+        analyses.push(analyze_source(path).await?);
+    }
+    Ok(analyses)
+}
+
+/// One pass of FFmpeg: ebur128 measures and logs its summary, the samples
+/// it passes through are counted for clipping
+async fn analyze_source(path: &Path) -> Result<AudioAnalysis> {
+    let mut child = crate::ffmpeg_command()
+        .args([
+            "-nostats",
+            "-i", &path.to_string_lossy(),
+            "-vn",
+            "-af", "ebur128=peak=true:framelog=quiet",
+            "-f", "f32le", "-",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run FFmpeg")?;
+    let mut stdout = child.stdout.take().context("FFmpeg stdout unavailable")?;
+    let mut stderr = child.stderr.take().context("FFmpeg stderr unavailable")?;
+
+    // Both pipes drained together so neither fills up and blocks FFmpeg
+    let count = async {
+        let mut counter = ClipCounter::default();
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = stdout.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            counter.feed(&buf[..n]);
+        }
+        Ok::<_, std::io::Error>(counter)
+    };
+    let log = async {
+        let mut bytes = Vec::new();
+        stderr.read_to_end(&mut bytes).await.map(|_| String::from_utf8_lossy(&bytes).to_string())
+    };
+    let (counter, log) = tokio::join!(count, log);
+    let status = child.wait().await.context("FFmpeg audio analysis failed")?;
+    let log = log.unwrap_or_default();
+    if !status.success() {
+        anyhow::bail!(crate::i18n::tf("audio_analysis_failed", &[&translate_ffmpeg_error(&log)]));
+    }
+    let counter = counter.with_context(|| crate::i18n::t("audio_read_failed"))?;
+
+    let summary = parse_summary(&log);
+    let integrated_lufs = summary.integrated.filter(|i| *i > SILENCE_LUFS);
+    Ok(AudioAnalysis {
+        path: path.to_string_lossy().to_string(),
+        integrated_lufs,
+        loudness_range_lu: summary.range,
+        true_peak_dbtp: summary.true_peak,
+        clipped_samples: counter.clipped,
+        sample_count: counter.samples,
+        advice: advice(integrated_lufs, summary.true_peak, counter.clipped, counter.samples),
+    })
+}
+
+/// Full-scale samples of a stream of little-endian f32, fed in any chunks
+#[derive(Default)]
+struct ClipCounter {
+    pending: Vec<u8>,
+    samples: u64,
+    clipped: u64,
+}
+
+impl ClipCounter {
+    fn feed(&mut self, bytes: &[u8]) {
+        self.pending.extend_from_slice(bytes);
+        let whole = self.pending.len() / 4 * 4;
+        for b in self.pending[..whole].chunks_exact(4) {
+            self.samples += 1;
+            if f32::from_le_bytes([b[0], b[1], b[2], b[3]]).abs() >= CLIP_LEVEL {
+                self.clipped += 1;
+            }
+        }
+        self.pending.drain(..whole);
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Summary {
+    integrated: Option<f64>,
+    range: Option<f64>,
+    true_peak: Option<f64>,
+}
+
+/// Values of the summary ebur128 logs when it closes; `-inf` (silence)
+/// counts as missing
+fn parse_summary(log: &str) -> Summary {
+    let Some(start) = log.rfind("Summary:") else {
+        return Summary::default();
+    };
+    let value = |key: &str| {
+        log[start..]
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(key))
+            .and_then(|rest| rest.split_whitespace().next()?.parse::<f64>().ok())
+            .filter(|v| v.is_finite())
+    };
+    Summary { integrated: value("I:"), range: value("LRA:"), true_peak: value("Peak:") }
+}
+
+fn advice(integrated: Option<f64>, true_peak: Option<f64>, clipped: u64, samples: u64) -> AudioAdvice {
+    let Some(integrated) = integrated else {
+        return AudioAdvice::Silent;
+    };
+    if samples > 0 && clipped as f64 / samples as f64 > MAX_CLIPPED_RATIO {
+        AudioAdvice::Retake
+    } else if (integrated - TARGET_LUFS).abs() > LOUDNESS_TOLERANCE || true_peak.is_some_and(|p| p > MAX_TRUE_PEAK) {
+        AudioAdvice::Normalize
+    } else {
+        AudioAdvice::Good
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+[Parsed_ebur128_0 @ 000001] Summary:
+
+  Integrated loudness:
+    I:         -19.4 LUFS
+    Threshold: -29.6 LUFS
+
+  Loudness range:
+    LRA:         6.1 LU
+    Threshold: -39.7 LUFS
+    LRA low:   -23.5 LUFS
+    LRA high:  -17.4 LUFS
+
+  True peak:
+    Peak:       -0.3 dBFS
+";
+
+    #[test]
+    fn test_parse_summary() {
+        assert_eq!(
+            parse_summary(LOG),
+            Summary { integrated: Some(-19.4), range: Some(6.1), true_peak: Some(-0.3) }
+        );
+        let silent = LOG.replace("-19.4", "-70.0").replace("-0.3", "-inf");
+        assert_eq!(parse_summary(&silent).true_peak, None);
+        assert_eq!(parse_summary("no summary"), Summary::default());
+    }
+
+    #[test]
+    fn test_clip_counter_across_chunks() {
+        let bytes: Vec<u8> = [0.5f32, -1.0, 0.9995, 0.2].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let mut counter = ClipCounter::default();
+        // Split inside a sample
+        counter.feed(&bytes[..6]);
+        counter.feed(&bytes[6..]);
+        assert_eq!((counter.samples, counter.clipped), (4, 2));
+    }
+
+    #[test]
+    fn test_advice() {
+        assert_eq!(advice(None, None, 0, 0), AudioAdvice::Silent);
+        assert_eq!(advice(Some(-16.5), Some(-1.5), 0, 48_000), AudioAdvice::Good);
+        assert_eq!(advice(Some(-27.0), Some(-9.0), 0, 48_000), AudioAdvice::Normalize);
+        assert_eq!(advice(Some(-15.0), Some(-0.2), 0, 48_000), AudioAdvice::Normalize);
+        assert_eq!(advice(Some(-15.0), Some(0.5), 500, 48_000), AudioAdvice::Retake);
+    }
+}
@@ -1 +1,62 @@
+pub mod autotrim;
+pub mod batch;
+pub mod chapters;
+pub mod chunked;
+pub mod dual;
 pub mod encoder;
+pub mod estimate;
+pub mod focus;
+pub mod follow;
+pub mod fonts;
+pub mod frame;
+pub mod freeze;
+pub mod heatmap;
+pub mod history;
+pub mod limits;
+pub mod loudness;
+pub mod overlay;
+pub mod parallel;
+pub mod plan;
+pub mod post_export;
+pub mod presets;
+pub mod range;
+pub mod safe_area;
+pub mod sidecar;
+pub mod snapshot;
+pub mod soft_subtitles;
+pub mod speech;
+pub mod validate;
+pub mod visualizer;
+pub mod voiceover;
+pub mod watermark;
+pub mod waveform;
+
+use crate::types::{Annotation, Clip, CursorPosition};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Round to the nearest even size of at least 2: libx264 with yuv420p needs
+/// even dimensions
+pub(crate) fn even(v: f64) -> u32 {
+    ((v.round() as u32) & !1).max(2)
+}
+
+/// Whether `run_prepasses` has anything to render for these clips
+pub fn has_prepasses(clips: &[Clip]) -> bool {
+    clips.iter().any(|c| c.media.follow_cursor.is_some() || !c.media.focus_track.is_empty() || c.media.secondary.is_some())
+}
+
+/// Swap in the clips the pipeline renders before encoding: follow-cursor
+/// clips become their moving crop, follow-focus clips their focused
+/// windows, clips recorded with a second region their composite.
+/// Annotations and cursor paths are moved along.
+pub async fn run_prepasses(
+    clips: &mut [Clip],
+    annotations: &mut HashMap<String, Vec<Annotation>>,
+    cursor_positions: &mut HashMap<String, Vec<CursorPosition>>,
+    temp_dir: &Path,
+) -> anyhow::Result<()> {
+    follow::prepare_all(clips, annotations, cursor_positions, temp_dir).await?;
+    focus::prepare_all(clips, annotations, cursor_positions, temp_dir).await?;
+    dual::compose_all(clips, annotations, cursor_positions, temp_dir).await
+}
@@ -0,0 +1,699 @@
+use super::encoder::{keystroke_groups, opacity_segments, ANNOTATION_ALPHA, KEYSTROKE_DISPLAY_S};
+use super::fonts;
+use crate::annotations::paint_order;
+use crate::capture::gamepad;
+use crate::ffmpeg::paths::concat_entry;
+use crate::types::{Annotation, AnnotationKind, Clip, GamepadSample, KeystrokeEvent, SafeArea};
+use ab_glyph::{point, Font, FontArc, GlyphId, PxScale, ScaleFont};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tiny_skia::{
+    FillRule, LineCap, LineJoin, Paint, Path as SkPath, PathBuilder, Pixmap, PremultipliedColorU8,
+    Rect, Stroke, Transform,
+};
+
+/// Keystroke labels, same look as the drawtext version
+const KEYSTROKE_FONT_SIZE: f32 = 36.0;
+const KEYSTROKE_PADDING: f32 = 16.0;
+const KEYSTROKE_MARGIN_X: f32 = 30.0;
+const KEYSTROKE_BOTTOM: f32 = 80.0;
+/// Controller widget, bottom-right, sized for 1080p and scaled with the canvas
+const PAD_WIDTH: f32 = 300.0;
+const PAD_HEIGHT: f32 = 190.0;
+const PAD_MARGIN: f32 = 30.0;
+/// How long the widget stays once the controller is left alone
+const PAD_IDLE_S: f64 = 3.0;
+
+/// Overlay tracks of one export: annotations, keystrokes and the controller
+/// widget rasterized into
+/// transparent PNG frames, played back through the concat demuxer and
+/// composited with a single `overlay` filter per clip. The frames live next
+/// to the output and are removed on drop.
+#[derive(Default)]
+pub(crate) struct OverlayTracks {
+    dir: Option<PathBuf>,
+    scripts: HashMap<String, PathBuf>,
+}
+
+impl OverlayTracks {
+    /// Render every clip's annotations, keystrokes and controller input at
+    /// `width`x`height`, the keystrokes inside `safe_area`. On failure the
+    /// export keeps the drawbox/drawtext filters instead, without the
+    /// controller.
+    pub(crate) async fn render(
+        clips: &[Clip],
+        clip_annotations: &HashMap<String, Vec<Annotation>>,
+        clip_keystrokes: &HashMap<String, Vec<KeystrokeEvent>>,
+        keystroke_font: Option<&str>,
+        safe_area: SafeArea,
+        clip_gamepad: &HashMap<String, Vec<GamepadSample>>,
+        width: u32,
+        height: u32,
+        output_path: &Path,
+    ) -> Self {
+        let jobs: Vec<ClipOverlay> = clips
+            .iter()
+            .map(|c| ClipOverlay {
+                clip_id: c.id.clone(),
+                // In paint order, which the spans keep
+                annotations: clip_annotations
+                    .get(&c.id)
+                    .map(|a| paint_order(a).into_iter().cloned().collect())
+                    .unwrap_or_default(),
+                keys: clip_keystrokes
+                    .get(&c.id)
+                    .map(|events| keystroke_groups(events, 0.0, c.media.trim_start_ms))
+                    .unwrap_or_default(),
+                pad: clip_gamepad
+                    .get(&c.id)
+                    .map(|samples| gamepad_ranges(samples, c.media.trim_start_ms))
+                    .unwrap_or_default(),
+            })
+            .filter(|job| !job.annotations.is_empty() || !job.keys.is_empty() || !job.pad.is_empty())
+            .collect();
+        if jobs.is_empty() {
+            return Self::default();
+        }
+
+        let dir = super::encoder::scratch_path(output_path, "overlay");
+        let key_font = fonts::font_bytes(keystroke_font);
+        let task_dir = dir.clone();
+        let rendered =
+            tokio::task::spawn_blocking(move || render_all(&jobs, key_font, safe_area, width, height, &task_dir)).await;
+        match rendered {
+            Ok(Ok(scripts)) => {
+                tracing::info!("Overlay tracks rendered for {} clips", scripts.len());
+                Self { dir: Some(dir), scripts }
+            }
+            Ok(Err(e)) => {
+                tracing::warn!("Overlay rendering failed, using drawtext filters: {:#}", e);
+                let _ = std::fs::remove_dir_all(&dir);
+                Self::default()
+            }
+            Err(e) => {
+                tracing::warn!("Overlay rendering task failed, using drawtext filters: {}", e);
+                let _ = std::fs::remove_dir_all(&dir);
+                Self::default()
+            }
+        }
+    }
+
+    /// Whether annotations and keystrokes come from the tracks (and must not
+    /// be drawn again with filters)
+    pub(crate) fn is_active(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// Add every clip's track as an input; returns the input indices by clip id
+    pub(crate) fn push_inputs(&self, clips: &[Clip], args: &mut Vec<String>) -> HashMap<String, usize> {
+        clips
+            .iter()
+            .filter_map(|c| self.push_input(&c.id, args).map(|index| (c.id.clone(), index)))
+            .collect()
+    }
+
+    /// Add the clip's track as the next input; returns its input index
+    pub(crate) fn push_input(&self, clip_id: &str, args: &mut Vec<String>) -> Option<usize> {
+        let script = self.scripts.get(clip_id)?;
+        let index = input_count(args);
+        args.extend(["-f", "concat", "-safe", "0", "-i"].iter().map(|s| s.to_string()));
+        args.push(script.to_string_lossy().to_string());
+        Some(index)
+    }
+}
+
+impl Drop for OverlayTracks {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.dir {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Number of inputs already on an FFmpeg command line
+pub(crate) fn input_count(args: &[String]) -> usize {
+    args.iter().filter(|a| *a == "-i").count()
+}
+
+struct ClipOverlay {
+    clip_id: String,
+    annotations: Vec<Annotation>,
+    /// Keystroke labels, in seconds from the clip start
+    keys: Vec<(f64, String)>,
+    /// Controller states with their [start, end) in seconds from the clip start
+    pad: Vec<(f64, f64, GamepadSample)>,
+}
+
+/// A state lasts until the next one. Idle states, and the last one, hide the
+/// widget after `PAD_IDLE_S`.
+fn gamepad_ranges(samples: &[GamepadSample], trim_start_ms: u64) -> Vec<(f64, f64, GamepadSample)> {
+    let secs = |s: &GamepadSample| (s.timestamp_ms as f64 - trim_start_ms as f64) / 1000.0;
+    samples
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let start = secs(s);
+            let end = match samples.get(i + 1).map(secs) {
+                Some(next) if !gamepad::is_idle(s) => next,
+                Some(next) => next.min(start + PAD_IDLE_S),
+                None => start + PAD_IDLE_S,
+            };
+            (start, end, *s)
+        })
+        // Before the trim point
+        .filter(|(start, end, _)| *end > 0.0 && end > start)
+        .collect()
+}
+
+struct Fonts {
+    annotation: FontArc,
+    keystroke: FontArc,
+}
+
+fn render_all(
+    jobs: &[ClipOverlay],
+    key_font: Vec<u8>,
+    safe_area: SafeArea,
+    width: u32,
+    height: u32,
+    dir: &Path,
+) -> Result<HashMap<String, PathBuf>> {
+    std::fs::create_dir_all(dir).with_context(|| crate::i18n::t("overlay_dir_failed"))?;
+    let fonts = Fonts {
+        annotation: FontArc::try_from_vec(fonts::font_bytes(None)).with_context(|| crate::i18n::t("annotation_font_invalid"))?,
+        keystroke: FontArc::try_from_vec(key_font).with_context(|| crate::i18n::t("keystroke_font_invalid"))?,
+    };
+
+    let mut scripts = HashMap::new();
+    for (i, job) in jobs.iter().enumerate() {
+        if let Some(script) = render_clip(job, &fonts, safe_area, width, height, dir, &format!("clip{i}"))? {
+            scripts.insert(job.clip_id.clone(), script);
+        }
+    }
+    Ok(scripts)
+}
+
+/// Something drawn on the overlay during a span
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Item {
+    /// Annotation index and opacity in 5% steps
+    Annotation(usize, u8),
+    /// Keystroke label index
+    Keys(usize),
+    /// Controller state index
+    Gamepad(usize),
+}
+
+/// Time span [start, end) and what is visible during it
+#[derive(Debug, PartialEq)]
+struct Span {
+    start: f64,
+    end: f64,
+    items: Vec<Item>,
+}
+
+/// Cut the clip's time into spans where the overlay doesn't change, from 0
+/// to the moment the last element disappears.
+fn spans(annotations: &[Annotation], keys: &[(f64, String)], pad: &[(f64, f64, GamepadSample)]) -> Vec<Span> {
+    let mut ranges: Vec<(f64, f64, Item)> = Vec::new();
+    for (i, ann) in annotations.iter().enumerate() {
+        for (start, end, opacity) in opacity_segments(ann) {
+            ranges.push((start, end, Item::Annotation(i, (opacity * 20.0).round() as u8)));
+        }
+    }
+    for (i, (t, _)) in keys.iter().enumerate() {
+        ranges.push((*t, t + KEYSTROKE_DISPLAY_S, Item::Keys(i)));
+    }
+    for (i, (start, end, _)) in pad.iter().enumerate() {
+        ranges.push((*start, *end, Item::Gamepad(i)));
+    }
+
+    let mut cuts: Vec<f64> = vec![0.0];
+    cuts.extend(ranges.iter().flat_map(|r| [r.0.max(0.0), r.1.max(0.0)]));
+    cuts.sort_by(|a, b| a.total_cmp(b));
+    cuts.dedup_by(|a, b| (*a - *b).abs() < 0.001);
+
+    let mut spans: Vec<Span> = Vec::new();
+    for w in cuts.windows(2) {
+        let mid = (w[0] + w[1]) / 2.0;
+        let items: Vec<Item> = ranges.iter().filter(|r| r.0 <= mid && mid < r.1).map(|r| r.2).collect();
+        match spans.last_mut() {
+            Some(last) if last.items == items => last.end = w[1],
+            _ => spans.push(Span { start: w[0], end: w[1], items }),
+        }
+    }
+    spans
+}
+
+/// Frames and concat script of one clip, None if nothing is ever visible
+fn render_clip(
+    job: &ClipOverlay,
+    fonts: &Fonts,
+    safe_area: SafeArea,
+    width: u32,
+    height: u32,
+    dir: &Path,
+    prefix: &str,
+) -> Result<Option<PathBuf>> {
+    let spans = spans(&job.annotations, &job.keys, &job.pad);
+    if spans.iter().all(|s| s.items.is_empty()) {
+        return Ok(None);
+    }
+
+    let mut script = String::from("ffconcat version 1.0\n");
+    let mut last_file = String::new();
+    for (n, span) in spans.iter().enumerate() {
+        let mut pixmap = Pixmap::new(width, height).with_context(|| crate::i18n::t("overlay_size_invalid"))?;
+        for item in &span.items {
+            match *item {
+                Item::Annotation(i, opacity) => {
+                    draw_annotation(&mut pixmap, &job.annotations[i], opacity as f32 / 20.0, &fonts.annotation)
+                }
+                Item::Keys(i) => draw_keystroke_label(&mut pixmap, &job.keys[i].1, &fonts.keystroke, safe_area),
+                Item::Gamepad(i) => draw_gamepad(&mut pixmap, &job.pad[i].2),
+            }
+        }
+        let file = format!("{prefix}_{n:06}.png");
+        pixmap.save_png(dir.join(&file)).with_context(|| crate::i18n::t("overlay_image_write_failed"))?;
+        script.push_str(&concat_entry(&file));
+        script.push_str(&format!("duration {:.3}\n", span.end - span.start));
+        last_file = file;
+    }
+    // The demuxer only honors the last duration when the file is listed again
+    script.push_str(&concat_entry(&last_file));
+
+    let path = dir.join(format!("{prefix}.ffconcat"));
+    std::fs::write(&path, script).with_context(|| crate::i18n::t("overlay_list_write_failed"))?;
+    Ok(Some(path))
+}
+
+/// Annotations on screen `at_s` seconds into the trimmed clip, drawn on a
+/// transparent `width`x`height` layer, for stills taken from the clip
+pub(crate) fn annotations_at(annotations: &[Annotation], at_s: f64, width: u32, height: u32) -> Result<Pixmap> {
+    let font = FontArc::try_from_vec(fonts::font_bytes(None)).with_context(|| crate::i18n::t("annotation_font_invalid"))?;
+    let mut pixmap = Pixmap::new(width, height).with_context(|| crate::i18n::t("overlay_size_invalid"))?;
+    for ann in paint_order(annotations) {
+        let visible = opacity_segments(ann).into_iter().find(|(start, end, _)| *start <= at_s && at_s < *end);
+        if let Some((_, _, opacity)) = visible {
+            draw_annotation(&mut pixmap, ann, opacity as f32, &font);
+        }
+    }
+    Ok(pixmap)
+}
+
+// ──────────────────────────────── Drawing ────────────────────────────────
+
+/// `#rrggbb` to RGB, white for anything else
+fn parse_color(color: &str) -> [u8; 3] {
+    let hex = color.strip_prefix('#').filter(|h| h.len() == 6);
+    let channel = |i: usize| hex.and_then(|h| h.get(i..i + 2)).and_then(|c| u8::from_str_radix(c, 16).ok());
+    match (channel(0), channel(2), channel(4)) {
+        (Some(r), Some(g), Some(b)) => [r, g, b],
+        _ => [255, 255, 255],
+    }
+}
+
+fn paint(rgb: [u8; 3], alpha: f32) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(rgb[0], rgb[1], rgb[2], (alpha.clamp(0.0, 1.0) * 255.0).round() as u8);
+    paint.anti_alias = true;
+    paint
+}
+
+fn draw_annotation(pixmap: &mut Pixmap, ann: &Annotation, opacity: f32, font: &FontArc) {
+    let (w, h) = (pixmap.width() as f32, pixmap.height() as f32);
+    let (x, y) = (ann.x as f32 * w, ann.y as f32 * h);
+    let (aw, ah) = ((ann.width as f32 * w).max(4.0), (ann.height as f32 * h).max(4.0));
+    let rgb = parse_color(&ann.color);
+    let shape_paint = paint(rgb, ANNOTATION_ALPHA as f32 * opacity);
+    let stroke = Stroke {
+        width: ann.stroke_width.max(2.0) as f32,
+        line_cap: LineCap::Round,
+        line_join: LineJoin::Round,
+        ..Default::default()
+    };
+    let t = Transform::identity();
+
+    match ann.kind {
+        AnnotationKind::Rectangle => {
+            if let Some(rect) = Rect::from_xywh(x, y, aw, ah) {
+                pixmap.stroke_path(&PathBuilder::from_rect(rect), &shape_paint, &stroke, t, None);
+            }
+        }
+        AnnotationKind::Circle => {
+            if let Some(oval) = Rect::from_xywh(x, y, aw, ah).and_then(PathBuilder::from_oval) {
+                pixmap.stroke_path(&oval, &shape_paint, &stroke, t, None);
+            }
+        }
+        AnnotationKind::Arrow => {
+            // Same direction as the editor: top-left to bottom-right
+            let (x2, y2) = (x + ann.width as f32 * w, y + ann.height as f32 * h);
+            if let Some(shaft) = line(&[(x, y), (x2, y2)]) {
+                pixmap.stroke_path(&shaft, &shape_paint, &stroke, t, None);
+            }
+            if let Some(head) = arrow_head(x, y, x2, y2, (stroke.width * 4.0).max(12.0)) {
+                pixmap.fill_path(&head, &shape_paint, FillRule::Winding, t, None);
+            }
+        }
+        AnnotationKind::Freehand => {
+            let points: Vec<(f32, f32)> = ann
+                .points
+                .iter()
+                .flatten()
+                .map(|p| (p.0 as f32 * w, p.1 as f32 * h))
+                .collect();
+            if let Some(path) = line(&points) {
+                pixmap.stroke_path(&path, &shape_paint, &stroke, t, None);
+            }
+        }
+        AnnotationKind::Text => {
+            if let Some(text) = &ann.text {
+                let size = (ann.height as f32 * h * 0.7).max(16.0);
+                draw_text(pixmap, font, text, size, x, y, rgb, opacity);
+            }
+        }
+    }
+}
+
+fn line(points: &[(f32, f32)]) -> Option<SkPath> {
+    let (first, rest) = points.split_first()?;
+    let mut pb = PathBuilder::new();
+    pb.move_to(first.0, first.1);
+    for p in rest {
+        pb.line_to(p.0, p.1);
+    }
+    pb.finish()
+}
+
+/// Filled triangle pointing at (x2, y2)
+fn arrow_head(x1: f32, y1: f32, x2: f32, y2: f32, size: f32) -> Option<SkPath> {
+    let angle = (y2 - y1).atan2(x2 - x1);
+    let wing = |da: f32| (x2 - size * (angle + da).cos(), y2 - size * (angle + da).sin());
+    let (l, r) = (wing(0.45), wing(-0.45));
+    let mut pb = PathBuilder::new();
+    pb.move_to(x2, y2);
+    pb.line_to(l.0, l.1);
+    pb.line_to(r.0, r.1);
+    pb.close();
+    pb.finish()
+}
+
+fn rounded_rect(x: f32, y: f32, w: f32, h: f32, radius: f32) -> Option<SkPath> {
+    let r = radius.min(w / 2.0).min(h / 2.0);
+    let mut pb = PathBuilder::new();
+    pb.move_to(x + r, y);
+    pb.line_to(x + w - r, y);
+    pb.quad_to(x + w, y, x + w, y + r);
+    pb.line_to(x + w, y + h - r);
+    pb.quad_to(x + w, y + h, x + w - r, y + h);
+    pb.line_to(x + r, y + h);
+    pb.quad_to(x, y + h, x, y + h - r);
+    pb.line_to(x, y + r);
+    pb.quad_to(x, y, x + r, y);
+    pb.close();
+    pb.finish()
+}
+
+/// Bottom-left pill, like the drawtext keystrokes
+fn draw_keystroke_label(pixmap: &mut Pixmap, label: &str, font: &FontArc, safe_area: SafeArea) {
+    let size = KEYSTROKE_FONT_SIZE;
+    let text_w = text_width(font, label, size);
+    let text_h = font.as_scaled(PxScale::from(size)).height();
+    let (width, height) = (pixmap.width() as f32, pixmap.height() as f32);
+    let x = KEYSTROKE_MARGIN_X + width * safe_area.left as f32;
+    let y = height - KEYSTROKE_BOTTOM - height * safe_area.bottom as f32;
+
+    let pad = KEYSTROKE_PADDING;
+    if let Some(pill) = rounded_rect(x - pad, y - pad, text_w + 2.0 * pad, text_h + 2.0 * pad, 12.0) {
+        pixmap.fill_path(&pill, &paint([0, 0, 0], 0.55), FillRule::Winding, Transform::identity(), None);
+    }
+    draw_text(pixmap, font, label, size, x + 2.0, y + 2.0, [0, 0, 0], 0.4);
+    draw_text(pixmap, font, label, size, x, y, [255, 255, 255], 1.0);
+}
+
+/// Bottom-right controller: lit buttons, stick positions and trigger travel.
+/// Coordinates are for a 1080p canvas.
+fn draw_gamepad(pixmap: &mut Pixmap, pad: &GamepadSample) {
+    let s = pixmap.height() as f32 / 1080.0;
+    let x0 = pixmap.width() as f32 - (PAD_MARGIN + PAD_WIDTH) * s;
+    let y0 = pixmap.height() as f32 - (PAD_MARGIN + PAD_HEIGHT) * s;
+    let t = Transform::identity();
+    let white = [255, 255, 255];
+    let pressed = |flag: u16| pad.buttons & flag != 0;
+
+    if let Some(body) = rounded_rect(x0, y0, PAD_WIDTH * s, PAD_HEIGHT * s, 18.0 * s) {
+        pixmap.fill_path(&body, &paint([0, 0, 0], 0.55), FillRule::Winding, t, None);
+    }
+
+    // Triggers fill up with travel, bumpers sit below them
+    for (x, value, bumper) in [
+        (20.0, pad.left_trigger, gamepad::LEFT_SHOULDER),
+        (190.0, pad.right_trigger, gamepad::RIGHT_SHOULDER),
+    ] {
+        if let Some(track) = rounded_rect(x0 + x * s, y0 + 12.0 * s, 90.0 * s, 8.0 * s, 4.0 * s) {
+            pixmap.fill_path(&track, &paint(white, 0.2), FillRule::Winding, t, None);
+        }
+        if value > 0 {
+            let travel = 90.0 * value as f32 / 10.0;
+            if let Some(fill) = rounded_rect(x0 + x * s, y0 + 12.0 * s, travel * s, 8.0 * s, 4.0 * s) {
+                pixmap.fill_path(&fill, &paint(white, 0.95), FillRule::Winding, t, None);
+            }
+        }
+        let shape = rounded_rect(x0 + x * s, y0 + 26.0 * s, 90.0 * s, 10.0 * s, 5.0 * s);
+        draw_button(pixmap, shape, white, pressed(bumper), s);
+    }
+
+    // Sticks: ring for the range, knob where the stick is (up is positive)
+    for (cx, cy, (sx, sy), thumb) in [
+        (70.0, 80.0, (pad.left_x, pad.left_y), gamepad::LEFT_THUMB),
+        (190.0, 145.0, (pad.right_x, pad.right_y), gamepad::RIGHT_THUMB),
+    ] {
+        let (cx, cy) = (x0 + cx * s, y0 + cy * s);
+        if let Some(ring) = PathBuilder::from_circle(cx, cy, 30.0 * s) {
+            let stroke = Stroke { width: 2.0 * s, ..Default::default() };
+            pixmap.stroke_path(&ring, &paint(white, 0.45), &stroke, t, None);
+        }
+        let (kx, ky) = (cx + sx as f32 / 10.0 * 17.0 * s, cy - sy as f32 / 10.0 * 17.0 * s);
+        if let Some(knob) = PathBuilder::from_circle(kx, ky, 13.0 * s) {
+            let alpha = if pressed(thumb) { 0.95 } else { 0.6 };
+            pixmap.fill_path(&knob, &paint(white, alpha), FillRule::Winding, t, None);
+        }
+    }
+
+    for (dx, dy, flag) in [
+        (0.0, -18.0, gamepad::DPAD_UP),
+        (0.0, 18.0, gamepad::DPAD_DOWN),
+        (-18.0, 0.0, gamepad::DPAD_LEFT),
+        (18.0, 0.0, gamepad::DPAD_RIGHT),
+    ] {
+        let (x, y) = (x0 + (105.0 + dx - 8.0) * s, y0 + (145.0 + dy - 8.0) * s);
+        draw_button(pixmap, rounded_rect(x, y, 16.0 * s, 16.0 * s, 3.0 * s), white, pressed(flag), s);
+    }
+
+    for (cx, flag) in [(130.0, gamepad::BACK), (170.0, gamepad::START)] {
+        draw_button(pixmap, PathBuilder::from_circle(x0 + cx * s, y0 + 80.0 * s, 6.0 * s), white, pressed(flag), s);
+    }
+
+    // Face buttons in their usual colors
+    for (dx, dy, rgb, flag) in [
+        (0.0, 22.0, [34, 197, 94], gamepad::BUTTON_A),
+        (22.0, 0.0, [239, 68, 68], gamepad::BUTTON_B),
+        (-22.0, 0.0, [59, 130, 246], gamepad::BUTTON_X),
+        (0.0, -22.0, [234, 179, 8], gamepad::BUTTON_Y),
+    ] {
+        let shape = PathBuilder::from_circle(x0 + (240.0 + dx) * s, y0 + (80.0 + dy) * s, 10.0 * s);
+        draw_button(pixmap, shape, rgb, pressed(flag), s);
+    }
+}
+
+/// Filled when pressed, outlined otherwise
+fn draw_button(pixmap: &mut Pixmap, shape: Option<SkPath>, rgb: [u8; 3], pressed: bool, scale: f32) {
+    let Some(shape) = shape else { return };
+    if pressed {
+        pixmap.fill_path(&shape, &paint(rgb, 0.95), FillRule::Winding, Transform::identity(), None);
+    } else {
+        let stroke = Stroke { width: 2.0 * scale, ..Default::default() };
+        pixmap.stroke_path(&shape, &paint(rgb, 0.45), &stroke, Transform::identity(), None);
+    }
+}
+
+/// Glyph ids of `text` with their pen x offsets, kerning applied
+fn layout(font: &FontArc, text: &str, size: f32) -> (Vec<(GlyphId, f32)>, f32) {
+    let scaled = font.as_scaled(PxScale::from(size));
+    let mut glyphs = Vec::new();
+    let mut caret = 0.0;
+    let mut prev: Option<GlyphId> = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(p) = prev {
+            caret += scaled.kern(p, id);
+        }
+        glyphs.push((id, caret));
+        caret += scaled.h_advance(id);
+        prev = Some(id);
+    }
+    (glyphs, caret)
+}
+
+fn text_width(font: &FontArc, text: &str, size: f32) -> f32 {
+    layout(font, text, size).1
+}
+
+/// Draw `text` with the top-left of its line box at (x, y)
+fn draw_text(pixmap: &mut Pixmap, font: &FontArc, text: &str, size: f32, x: f32, y: f32, rgb: [u8; 3], alpha: f32) {
+    let ascent = font.as_scaled(PxScale::from(size)).ascent();
+    for (id, dx) in layout(font, text, size).0 {
+        let glyph = id.with_scale_and_position(size, point(x + dx, y + ascent));
+        if let Some(outlined) = font.outline_glyph(glyph) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let px = bounds.min.x as i32 + gx as i32;
+                let py = bounds.min.y as i32 + gy as i32;
+                blend(pixmap, px, py, rgb, coverage * alpha);
+            });
+        }
+    }
+}
+
+/// Source-over one pixel of premultiplied RGBA
+fn blend(pixmap: &mut Pixmap, x: i32, y: i32, rgb: [u8; 3], alpha: f32) {
+    let (w, h) = (pixmap.width() as i32, pixmap.height() as i32);
+    if x < 0 || y < 0 || x >= w || y >= h {
+        return;
+    }
+    let a = alpha.clamp(0.0, 1.0);
+    let idx = (y * w + x) as usize;
+    let dst = pixmap.pixels()[idx];
+    let out_a = (255.0 * a + dst.alpha() as f32 * (1.0 - a)).round() as u8;
+    let mix = |s: u8, d: u8| ((s as f32 * a + d as f32 * (1.0 - a)).round() as u8).min(out_a);
+    if let Some(c) = PremultipliedColorU8::from_rgba(
+        mix(rgb[0], dst.red()),
+        mix(rgb[1], dst.green()),
+        mix(rgb[2], dst.blue()),
+        out_a,
+    ) {
+        pixmap.pixels_mut()[idx] = c;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AnimatedProperty, Easing, Keyframe, test_annotation};
+
+    fn rect(start_ms: u64, end_ms: u64) -> Annotation {
+        Annotation {
+            id: "r".into(),
+            width: 0.5,
+            height: 0.5,
+            color: "#ef4444".into(),
+            stroke_width: 4.0,
+            ..test_annotation(AnnotationKind::Rectangle, start_ms, end_ms)
+        }
+    }
+
+    #[test]
+    fn test_spans_merge_unchanged_ranges() {
+        let keys = vec![(1.0, "Ctrl".to_string()), (5.0, "A".to_string())];
+        let spans = spans(&[rect(0, 2000)], &keys, &[]);
+        let items: Vec<(f64, f64, Vec<Item>)> = spans.into_iter().map(|s| (s.start, s.end, s.items)).collect();
+        assert_eq!(
+            items,
+            vec![
+                (0.0, 1.0, vec![Item::Annotation(0, 20)]),
+                (1.0, 2.0, vec![Item::Annotation(0, 20), Item::Keys(0)]),
+                (2.0, 1.0 + KEYSTROKE_DISPLAY_S, vec![Item::Keys(0)]),
+                (1.0 + KEYSTROKE_DISPLAY_S, 5.0, vec![]),
+                (5.0, 5.0 + KEYSTROKE_DISPLAY_S, vec![Item::Keys(1)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spans_follow_opacity() {
+        let mut ann = rect(0, 1000);
+        ann.keyframes = vec![
+            Keyframe { time_ms: 0, property: AnimatedProperty::Opacity, value: 0.0, easing: Easing::Hold },
+            Keyframe { time_ms: 500, property: AnimatedProperty::Opacity, value: 1.0, easing: Easing::Linear },
+        ];
+        let spans = spans(&[ann], &[], &[]);
+        assert_eq!(spans.len(), 2);
+        assert!(spans[0].items.is_empty());
+        assert_eq!(spans[1].items, vec![Item::Annotation(0, 20)]);
+    }
+
+    #[test]
+    fn test_gamepad_ranges_hold_until_idle() {
+        let press = gamepad::sample(1000, gamepad::BUTTON_A, [0; 4], [0; 2]);
+        let samples = vec![
+            gamepad::sample(0, 0, [0; 4], [0; 2]),
+            press,
+            gamepad::sample(10_000, 0, [0; 4], [0; 2]),
+            gamepad::sample(20_000, 0, [32767, 0, 0, 0], [0; 2]),
+        ];
+        // Trimmed by 0.5 s: the first idle state still shows until 0.5 s
+        let ranges: Vec<(f64, f64)> = gamepad_ranges(&samples, 500).iter().map(|r| (r.0, r.1)).collect();
+        assert_eq!(ranges, vec![(-0.5, 0.5), (0.5, 9.5), (9.5, 9.5 + PAD_IDLE_S), (19.5, 19.5 + PAD_IDLE_S)]);
+        // Past the trim point entirely
+        assert!(gamepad_ranges(&samples[..1], 5000).is_empty());
+
+        let pad = gamepad_ranges(&samples, 500);
+        let spans = spans(&[], &[], &pad);
+        assert_eq!(spans[0].items, vec![Item::Gamepad(0)]);
+        assert_eq!(spans[1].items, vec![Item::Gamepad(1)]);
+        assert_eq!(spans[3].items, vec![]);
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#ef4444"), [0xef, 0x44, 0x44]);
+        assert_eq!(parse_color("red"), [255, 255, 255]);
+    }
+
+    #[test]
+    fn test_draw_shapes_and_labels() {
+        let font = FontArc::try_from_vec(fonts::font_bytes(None)).unwrap();
+        let mut pixmap = Pixmap::new(200, 200).unwrap();
+        draw_annotation(&mut pixmap, &rect(0, 1000), 1.0, &font);
+        // Stroke on the left edge, transparent inside
+        let at = |p: &Pixmap, x: u32, y: u32| p.pixels()[(y * p.width() + x) as usize];
+        assert!(at(&pixmap, 20, 60).alpha() > 150);
+        assert_eq!(at(&pixmap, 60, 60).alpha(), 0);
+
+        draw_keystroke_label(&mut pixmap, "Ctrl  C", &font, SafeArea::default());
+        assert!(at(&pixmap, 20, 125).alpha() > 0);
+        // Raised above the bottom margin
+        let mut raised = Pixmap::new(200, 200).unwrap();
+        draw_keystroke_label(&mut raised, "Ctrl  C", &font, SafeArea { bottom: 0.4, ..Default::default() });
+        assert_eq!(at(&raised, 20, 125).alpha(), 0);
+        assert!(at(&raised, 20, 75).alpha() > 0);
+
+        // A pressed: its button is solid green
+        let mut pixmap = Pixmap::new(1920, 1080).unwrap();
+        draw_gamepad(&mut pixmap, &gamepad::sample(0, gamepad::BUTTON_A, [0; 4], [0; 2]));
+        let a = at(&pixmap, 1920 - 330 + 240, 1080 - 220 + 102);
+        assert!(a.green() > 150 && a.red() < 100);
+        let b = at(&pixmap, 1920 - 330 + 262, 1080 - 220 + 80);
+        assert!(b.red() < 150);
+    }
+
+    #[test]
+    fn test_annotations_at_only_draws_visible_ones() {
+        let layer = annotations_at(&[rect(0, 1000), rect(2000, 3000)], 0.5, 200, 200).unwrap();
+        assert!(layer.pixels()[(60 * 200 + 20) as usize].alpha() > 150);
+        let empty = annotations_at(&[rect(0, 1000), rect(2000, 3000)], 1.5, 200, 200).unwrap();
+        assert!(empty.pixels().iter().all(|p| p.alpha() == 0));
+    }
+
+    #[test]
+    fn test_push_input_indexes_after_existing_inputs() {
+        let mut tracks = OverlayTracks::default();
+        tracks.scripts.insert("a".into(), PathBuf::from("clip0.ffconcat"));
+        let mut args: Vec<String> = ["-i", "a.mp4", "-i", "a.wav"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(tracks.push_input("a", &mut args), Some(2));
+        assert_eq!(&args[4..], ["-f", "concat", "-safe", "0", "-i", "clip0.ffconcat"]);
+        assert_eq!(tracks.push_input("b", &mut args), None);
+        assert!(!tracks.is_active());
+    }
+}
@@ -0,0 +1,342 @@
+use super::encoder::{
+    build_audio_concat_filter, build_clip_chain, build_transition_chain, codec_args, extract_time,
+    filter_script_path, output_stage_filters, scratch_path, target_video_kbps, timeline_duration,
+    timeline_text_filters, translate_ffmpeg_error, FilterScript,
+};
+use super::overlay::{input_count, OverlayTracks};
+use super::snapshot::ExportContext;
+use crate::types::{Annotation, Clip, CursorPosition, ExportQuality, OutputSettings, Transition};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Below this many clips the single filter_complex is just as fast
+const MIN_CLIPS: usize = 3;
+/// Short timelines don't pay back the extra encode
+const MIN_TIMELINE_SECONDS: f64 = 60.0;
+/// Share of the overall progress taken by the per-clip renders
+const RENDER_SHARE: f64 = 0.8;
+
+/// Concurrent FFmpeg processes: one per core, at most one per clip
+fn job_count(clip_count: usize) -> usize {
+    let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    cpus.min(clip_count).max(1)
+}
+
+/// Whether splitting the timeline into per-clip renders is worth it
+pub fn worth_it(eff_durations: &[f64]) -> bool {
+    eff_durations.len() >= MIN_CLIPS
+        && eff_durations.iter().sum::<f64>() >= MIN_TIMELINE_SECONDS
+        && job_count(eff_durations.len()) > 1
+}
+
+/// Render each clip (trim, canvas, cursor zoom, annotations, audio mix) in
+/// its own FFmpeg process, then chain the intermediates with the timeline's
+/// transitions and global overlays in a final pass.
+pub async fn export(
+    clips: &[Clip],
+    eff_durations: &[f64],
+    transitions: &[Transition],
+    output_path: &PathBuf,
+    app: &AppHandle,
+    watermark: bool,
+    quality: &ExportQuality,
+    ctx: &ExportContext<'_>,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
+) -> Result<()> {
+    let ExportContext {
+        clip_keystrokes, keystroke_font, subtitles, global_annotations, clip_annotations, clip_cursor_positions, clip_gamepad,
+        system_volume, mic_volume,
+    } = *ctx;
+    let max_w = (clips.iter().map(|c| c.media.region.width).max().unwrap_or(1920) / 2) * 2;
+    let max_h = (clips.iter().map(|c| c.media.region.height).max().unwrap_or(1080) / 2) * 2;
+    let has_any_audio = output.include_audio && clips.iter().any(|c| !c.media.audio_paths.is_empty());
+
+    let parts: Vec<PathBuf> = (0..clips.len())
+        .map(|i| scratch_path(output_path, &format!("part{}.mkv", i)))
+        .collect();
+    let jobs = job_count(clips.len());
+    let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let threads = (cpus / jobs).max(1);
+    tracing::info!("Parallel export: {} clips, {} jobs, {} threads each", clips.len(), jobs, threads);
+
+    let _ = app.emit("export-progress", 0u32);
+    // Annotations and keystrokes as one rasterized track per clip
+    let overlays = OverlayTracks::render(
+        clips, clip_annotations, clip_keystrokes, keystroke_font, output.safe_area, clip_gamepad, max_w, max_h, output_path,
+    ).await;
+    let (no_annotations, no_keystrokes) = (HashMap::new(), HashMap::new());
+    let (clip_annotations, clip_keystrokes) = if overlays.is_active() {
+        (&no_annotations, &no_keystrokes)
+    } else {
+        (clip_annotations, clip_keystrokes)
+    };
+
+    let rendered = render_parts(
+        clips, eff_durations, &parts, app, max_w, max_h, output, has_any_audio, jobs, threads,
+        clip_annotations, clip_cursor_positions, &overlays, system_volume, mic_volume,
+    ).await;
+    let result = match rendered {
+        Ok(()) => {
+            let canvas = super::estimate::canvas_size(clips, output);
+            let text_filters = timeline_text_filters(clips, eff_durations, transitions, watermark.then_some(output.watermark_tone), clip_keystrokes, keystroke_font, subtitles, global_annotations, canvas, output.safe_area);
+            let mut args = final_args(&parts, eff_durations, transitions, has_any_audio, text_filters, quality, target_size_mb, output, output_path);
+            match FilterScript::spill(&mut args, filter_script_path(output_path), output.keep_filter_script) {
+                Ok(_script) => run_final(&args, timeline_duration(eff_durations, transitions), app).await,
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    };
+    for part in &parts {
+        let _ = std::fs::remove_file(part);
+    }
+    result?;
+
+    let _ = app.emit("export-progress", 100u32);
+    Ok(())
+}
+
+async fn render_parts(
+    clips: &[Clip],
+    eff_durations: &[f64],
+    parts: &[PathBuf],
+    app: &AppHandle,
+    max_w: u32,
+    max_h: u32,
+    output: &OutputSettings,
+    has_any_audio: bool,
+    jobs: usize,
+    threads: usize,
+    clip_annotations: &HashMap<String, Vec<Annotation>>,
+    clip_cursor_positions: &HashMap<String, Vec<CursorPosition>>,
+    overlays: &OverlayTracks,
+    system_volume: f32,
+    mic_volume: f32,
+) -> Result<()> {
+    let total: f64 = eff_durations.iter().sum();
+    let semaphore = Arc::new(Semaphore::new(jobs));
+    let mut set = JoinSet::new();
+
+    for (i, clip) in clips.iter().enumerate() {
+        let mut args = part_args(
+            clip, eff_durations[i], &parts[i], max_w, max_h, output, has_any_audio, threads,
+            clip_annotations.get(&clip.id), clip_cursor_positions.get(&clip.id), overlays,
+            system_volume, mic_volume,
+        );
+        let script = FilterScript::spill(&mut args, filter_script_path(&parts[i]), output.keep_filter_script)?;
+        let semaphore = semaphore.clone();
+        set.spawn(async move {
+            let _script = script;
+            let _permit = semaphore.acquire_owned().await?;
+            let out = crate::ffmpeg_command()
+                .args(&args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true)
+                .output()
+                .await
+                .context("Failed to start FFmpeg clip render")?;
+            if !out.status.success() {
+                let stderr = String::from_utf8_lossy(&out.stderr);
+                tracing::error!("FFmpeg stderr (clip {}):\n{}", i + 1, stderr);
+                anyhow::bail!("{}", translate_ffmpeg_error(&stderr));
+            }
+            Ok(i)
+        });
+    }
+
+    // Dropping the set on error aborts the other renders and kills their FFmpeg
+    let mut done = 0.0;
+    while let Some(joined) = set.join_next().await {
+        let i = joined.context("Clip render task failed")??;
+        done += eff_durations[i];
+        let _ = app.emit("export-progress", (done / total * RENDER_SHARE * 100.0) as u32);
+    }
+    Ok(())
+}
+
+/// One clip through the per-clip chain into a near-lossless intermediate
+fn part_args(
+    clip: &Clip,
+    eff_duration: f64,
+    part: &PathBuf,
+    max_w: u32,
+    max_h: u32,
+    output: &OutputSettings,
+    has_any_audio: bool,
+    threads: usize,
+    annotations: Option<&Vec<Annotation>>,
+    cursor_positions: Option<&Vec<CursorPosition>>,
+    overlays: &OverlayTracks,
+    system_volume: f32,
+    mic_volume: f32,
+) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-i".into(), clip.media.path.to_string_lossy().to_string()];
+    let overlay = overlays.push_input(&clip.id, &mut args);
+    let mut filter = build_clip_chain(0, clip, max_w, max_h, output, cursor_positions, annotations, overlay);
+
+    if has_any_audio {
+        let mut indices = Vec::new();
+        for audio_path in &clip.media.audio_paths {
+            if std::path::Path::new(audio_path).exists() {
+                indices.push(input_count(&args));
+                args.extend(["-i".to_string(), audio_path.clone()]);
+            }
+        }
+        // Silent clips still get a track so the final concat lines up
+        let af = build_audio_concat_filter(&[(0, indices)], &[eff_duration], &[clip.media.audio_offset_ms], system_volume, mic_volume);
+        if af.is_empty() {
+            filter.push_str(&format!(";anullsrc=channel_layout=stereo:sample_rate=44100,atrim=duration={eff_duration:.3}[aout]"));
+        } else {
+            filter.push_str(&format!(";{}", af));
+        }
+    }
+
+    args.extend(["-filter_complex".to_string(), filter, "-map".to_string(), "[s0]".to_string()]);
+    if has_any_audio {
+        args.extend(["-map".to_string(), "[aout]".to_string(), "-c:a".to_string(), "pcm_s16le".to_string()]);
+    } else {
+        args.push("-an".into());
+    }
+    // Visually lossless, the final pass encodes again at the export quality
+    args.extend(["-c:v", "libx264", "-preset", "veryfast", "-crf", "14", "-pix_fmt", "yuv420p"].iter().map(|s| s.to_string()));
+    args.extend(["-threads".to_string(), threads.to_string(), "-shortest".to_string(), "-y".to_string()]);
+    args.push(part.to_string_lossy().to_string());
+    args
+}
+
+/// Transitions, global overlays and the real encode over the intermediates
+fn final_args(
+    parts: &[PathBuf],
+    eff_durations: &[f64],
+    transitions: &[Transition],
+    has_any_audio: bool,
+    text_filters: Vec<String>,
+    quality: &ExportQuality,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
+    output_path: &PathBuf,
+) -> Vec<String> {
+    let n = parts.len();
+    let mut args: Vec<String> = Vec::new();
+    for part in parts {
+        args.extend(["-i".to_string(), part.to_string_lossy().to_string()]);
+    }
+
+    let mut filters: Vec<String> = (0..n).map(|i| format!("[{i}:v]setpts=PTS-STARTPTS[s{i}]")).collect();
+    filters.extend(build_transition_chain(eff_durations, transitions));
+    let video_final_label = format!("[v{}]", n - 2);
+
+    let (before, after) = output_stage_filters(output, !text_filters.is_empty());
+    let overlay_filters: Vec<String> = before.into_iter().chain(text_filters).chain(after).collect();
+    let video_output = if overlay_filters.is_empty() {
+        video_final_label
+    } else {
+        filters.push(format!("{}{}[vfinal]", video_final_label, overlay_filters.join(",")));
+        "[vfinal]".to_string()
+    };
+    if has_any_audio {
+        let inputs: String = (0..n).map(|i| format!("[{i}:a]")).collect();
+        filters.push(format!("{inputs}concat=n={n}:v=0:a=1[aout]"));
+    }
+
+    args.extend(["-filter_complex".to_string(), filters.join(";"), "-map".to_string(), video_output]);
+    if has_any_audio {
+        args.extend(["-map".to_string(), "[aout]".to_string()]);
+    }
+    let total_duration = timeline_duration(eff_durations, transitions);
+    let target_kbps = target_size_mb.map(|mb| target_video_kbps(mb, total_duration, has_any_audio));
+    args.extend(codec_args(quality, target_kbps, output));
+    if has_any_audio {
+        args.extend(["-c:a", "aac", "-b:a", "128k"].iter().map(|s| s.to_string()));
+    } else {
+        args.push("-an".into());
+    }
+    args.extend(["-shortest", "-y"].iter().map(|s| s.to_string()));
+    args.push(output_path.to_string_lossy().to_string());
+    args
+}
+
+async fn run_final(args: &[String], total_duration: f64, app: &AppHandle) -> Result<()> {
+    let mut child = crate::ffmpeg_command()
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start FFmpeg export")?;
+
+    let mut stderr_log = String::new();
+    if let Some(stderr) = child.stderr.take() {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            stderr_log.push_str(&line);
+            stderr_log.push('\n');
+            if let Some(t) = extract_time(&line) {
+                let fraction = (t / total_duration).min(1.0);
+                let _ = app.emit("export-progress", ((RENDER_SHARE + (1.0 - RENDER_SHARE) * fraction) * 100.0) as u32);
+            }
+        }
+    }
+
+    let status = child.wait().await.context("FFmpeg export failed")?;
+    if !status.success() {
+        tracing::error!("FFmpeg stderr:\n{}", stderr_log);
+        anyhow::bail!("{}", translate_ffmpeg_error(&stderr_log));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ClipMedia, TransitionType, test_clip};
+
+    fn clip(id: &str) -> Clip {
+        let clip = test_clip(id, 30_000);
+        Clip {
+            media: ClipMedia { trim_start_ms: 1_000, ..clip.media },
+            ..clip
+        }
+    }
+
+    #[test]
+    fn test_worth_it_needs_long_multi_clip_timelines() {
+        assert!(!worth_it(&[100.0, 100.0]));
+        assert!(!worth_it(&[5.0, 5.0, 5.0]));
+    }
+
+    #[test]
+    fn test_part_args_trim_and_silent_track() {
+        let args = part_args(&clip("a"), 29.0, &PathBuf::from("a.part0.mkv"), 1920, 1080, &OutputSettings { fps: Some(60), ..Default::default() }, true, 2, None, None, &OverlayTracks::default(), 1.0, 1.0);
+        let filter = &args[args.iter().position(|a| a == "-filter_complex").unwrap() + 1];
+        assert!(filter.starts_with("[0:v]trim=start=1.000"));
+        assert!(filter.contains("setsar=1,fps=60"));
+        assert!(filter.contains("anullsrc") && filter.contains("duration=29.000[aout]"));
+        assert!(args.windows(2).any(|w| w[0] == "-threads" && w[1] == "2"));
+    }
+
+    #[test]
+    fn test_final_args_chain_transitions() {
+        let parts: Vec<PathBuf> = (0..3).map(|i| PathBuf::from(format!("p{i}.mkv"))).collect();
+        let transitions = vec![
+            Transition { transition_type: TransitionType::Fade, duration_s: 1.0 },
+            Transition { transition_type: TransitionType::Cut, duration_s: 0.0 },
+        ];
+        let args = final_args(&parts, &[20.0, 20.0, 20.0], &transitions, true, Vec::new(), &ExportQuality::Medium, None, &OutputSettings::default(), &PathBuf::from("out.mp4"));
+        let filter = &args[args.iter().position(|a| a == "-filter_complex").unwrap() + 1];
+        assert!(filter.contains("xfade=transition=fade:duration=1.000:offset=19.000[v0]"));
+        assert!(filter.contains("[v0][s2]concat=n=2:v=1:a=0[v1]"));
+        assert!(filter.contains("[0:a][1:a][2:a]concat=n=3:v=0:a=1[aout]"));
+        assert!(args.windows(2).any(|w| w[0] == "-map" && w[1] == "[v1]"));
+    }
+}
@@ -0,0 +1,108 @@
+use super::batch::{self, Target};
+use super::encoder::{effective_duration, gif_palette_cuts, gif_passes, plan_mp4, resolve_source_fps};
+use super::snapshot::ExportContext;
+use crate::types::{
+    Annotation, Clip, ExportFormat, ExportPlan, ExportQuality, GamepadSample, KeystrokeEvent, OutputSettings, Transition,
+};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// The FFmpeg runs `export_video` would make for `format`, without making
+/// them. Only ffprobe is run, for the clip durations and frame rates.
+pub async fn plan(
+    clips: &[Clip],
+    transitions: &[Transition],
+    output_path: &Path,
+    format: ExportFormat,
+    quality: &ExportQuality,
+    watermark: bool,
+    ctx: &ExportContext<'_>,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
+) -> Result<ExportPlan> {
+    let mut commands = Vec::new();
+    match format {
+        ExportFormat::Mp4 => {
+            commands.push(plan_mp4(clips, transitions, output_path, watermark, quality, ctx, target_size_mb, output).await?);
+        }
+        // Same intermediate MP4 and palette passes as `export_gif`
+        ExportFormat::Gif => {
+            let temp_mp4 = output_path.with_extension("tmp.mp4");
+            let temp_output = OutputSettings { fps: None, include_audio: false, match_source_fps: false, ..output.clone() };
+            commands.push(plan_mp4(clips, transitions, &temp_mp4, watermark, &ExportQuality::Low, ctx, None, &temp_output).await?);
+            let cuts = gif_palette_cuts(&super::range::clip_spans(clips, transitions), output.gif_palette.per_clip);
+            let passes = gif_passes(&temp_mp4, output_path, quality, output, &cuts);
+            commands.extend([passes.palette, passes.gif]);
+        }
+        // Same mezzanine render and transcode as a batch of one
+        ExportFormat::Webm => {
+            let target = Target {
+                format,
+                quality: *quality,
+                target_size_mb,
+                output: resolve_source_fps(clips, output).await,
+                path: output_path.to_path_buf(),
+            };
+            let mezzanine = output_path.with_extension("mezzanine.mp4");
+            commands.push(plan_mp4(
+                clips, transitions, &mezzanine, watermark, &ExportQuality::High, ctx, None,
+                &batch::mezzanine_output(std::slice::from_ref(&target)),
+            ).await?);
+            let duration = super::range::timeline_length_ms(clips, transitions) as f64 / 1000.0;
+            commands.push(batch::transcode_args(&mezzanine, &target, duration));
+        }
+    }
+
+    let filter_graph = commands.first().and_then(|args| filter_graph(args));
+    Ok(ExportPlan {
+        ffmpeg: crate::ffmpeg_bin().to_string_lossy().to_string(),
+        commands,
+        filter_graph,
+        notes: notes(clips, ctx.clip_keystrokes, ctx.clip_annotations, ctx.clip_gamepad),
+    })
+}
+
+/// The `-filter_complex` graph of a command line
+fn filter_graph(args: &[String]) -> Option<String> {
+    let pos = args.iter().position(|a| a == "-filter_complex")?;
+    args.get(pos + 1).cloned()
+}
+
+/// Steps of the export the plan doesn't show, because they run FFmpeg or
+/// render files before the timeline render
+fn notes(
+    clips: &[Clip],
+    clip_keystrokes: &HashMap<String, Vec<KeystrokeEvent>>,
+    clip_annotations: &HashMap<String, Vec<Annotation>>,
+    clip_gamepad: &HashMap<String, Vec<GamepadSample>>,
+) -> Vec<String> {
+    let mut notes = Vec::new();
+    let drawn = clips.iter().any(|c| {
+        clip_annotations.get(&c.id).is_some_and(|a| !a.is_empty()) || clip_keystrokes.get(&c.id).is_some_and(|k| !k.is_empty())
+    });
+    if drawn {
+        notes.push(crate::i18n::t("plan_overlay_tracks"));
+    }
+    if clips.iter().any(|c| clip_gamepad.get(&c.id).is_some_and(|g| !g.is_empty())) {
+        notes.push(crate::i18n::t("plan_gamepad_tracks"));
+    }
+    let durations: Vec<f64> = clips.iter().map(|c| effective_duration(c, c.media.duration_ms as f64 / 1000.0)).collect();
+    if clips.len() > 1 && super::parallel::worth_it(&durations) {
+        notes.push(crate::i18n::t("plan_parallel"));
+    }
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_graph_of_the_command() {
+        let args: Vec<String> = ["-i", "a.mp4", "-filter_complex", "[0:v]null[v]", "-map", "[v]", "out.mp4"]
+            .iter().map(|s| s.to_string()).collect();
+        assert_eq!(filter_graph(&args).as_deref(), Some("[0:v]null[v]"));
+        assert_eq!(filter_graph(&args[..2]), None);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::types::{PostExportAction, PostExportSettings};
+use std::path::Path;
+
+/// Apply the configured post-export action to `output_path`.
+/// Failures are logged only: the export itself already succeeded.
+pub fn run(settings: &PostExportSettings, output_path: &Path) {
+    let result = match settings.action {
+        PostExportAction::OpenFolder => match output_path.parent() {
+            Some(dir) => opener::open(dir).map_err(|e| e.to_string()),
+            None => Ok(()),
+        },
+        PostExportAction::CopyToClipboard => crate::clipboard::copy_file(output_path),
+        PostExportAction::OpenInPlayer => opener::open(output_path).map_err(|e| e.to_string()),
+        PostExportAction::RunCommand => run_user_command(&settings.command, output_path),
+        PostExportAction::Nothing => Ok(()),
+    };
+    if let Err(e) = result {
+        tracing::warn!("{:?} failed: {}", settings.action, e);
+    }
+}
+
+/// Spawn the user's command through the shell without waiting for it
+fn run_user_command(template: &str, output_path: &Path) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err(crate::i18n::t("post_export_no_command"));
+    }
+    let command_line = expand_command(template, output_path);
+    tracing::info!("Running: {}", command_line);
+
+    let mut cmd = std::process::Command::new("cmd");
+    cmd.arg("/C");
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // Pass the line untouched: cmd.exe does its own quote parsing
+        cmd.raw_arg(&command_line);
+        cmd.creation_flags(crate::CREATE_NO_WINDOW);
+    }
+    #[cfg(not(windows))]
+    cmd.arg(&command_line);
+    cmd.spawn().map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Substitute `{path}` with the quoted output path
+fn expand_command(template: &str, output_path: &Path) -> String {
+    template.replace("{path}", &format!("\"{}\"", output_path.to_string_lossy()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_command_quotes_path() {
+        let cmd = expand_command("upload.exe {path} --public", Path::new("C:\\out\\my video.mp4"));
+        assert_eq!(cmd, "upload.exe \"C:\\out\\my video.mp4\" --public");
+    }
+
+    #[test]
+    fn test_expand_command_without_placeholder() {
+        assert_eq!(expand_command("notify.bat", Path::new("a.mp4")), "notify.bat");
+    }
+}
@@ -0,0 +1,162 @@
+use crate::types::{ExportFormat, ExportPreset, ExportQuality, OutputSettings};
+
+fn preset(
+    id: &str,
+    name: &str,
+    format: ExportFormat,
+    quality: ExportQuality,
+    target_size_mb: Option<u32>,
+    output: OutputSettings,
+) -> ExportPreset {
+    ExportPreset {
+        id: id.into(),
+        name: name.into(),
+        format,
+        quality,
+        watermark: false,
+        target_size_mb,
+        output,
+        builtin: true,
+    }
+}
+
+/// Presets shipped with the app
+pub fn builtin() -> Vec<ExportPreset> {
+    vec![
+        preset(
+            "youtube-1080p",
+            "YouTube 1080p",
+            ExportFormat::Mp4,
+            ExportQuality::High,
+            None,
+            OutputSettings { width: Some(1920), height: Some(1080), fps: Some(30), ..Default::default() },
+        ),
+        // Platforms give 1440p/4K uploads a higher bitrate than 1080p
+        preset(
+            "youtube-1440p",
+            &crate::i18n::t("preset_youtube_1440p"),
+            ExportFormat::Mp4,
+            ExportQuality::High,
+            None,
+            OutputSettings { width: Some(2560), height: Some(1440), fps: Some(30), upscale: true, ..Default::default() },
+        ),
+        preset(
+            "youtube-4k",
+            &crate::i18n::t("preset_youtube_4k"),
+            ExportFormat::Mp4,
+            ExportQuality::High,
+            None,
+            OutputSettings { width: Some(3840), height: Some(2160), fps: Some(30), upscale: true, ..Default::default() },
+        ),
+        preset(
+            "shorts-9x16",
+            "Shorts 9:16",
+            ExportFormat::Mp4,
+            ExportQuality::High,
+            None,
+            OutputSettings {
+                width: Some(1080),
+                height: Some(1920),
+                fps: Some(30),
+                safe_area: super::safe_area::find("youtube-shorts").map(|g| g.safe_area).unwrap_or_default(),
+                ..Default::default()
+            },
+        ),
+        preset(
+            "discord-10mb",
+            &crate::i18n::t("preset_discord_10mb"),
+            ExportFormat::Mp4,
+            ExportQuality::Medium,
+            Some(10),
+            OutputSettings { width: Some(1280), height: Some(720), fps: Some(30), ..Default::default() },
+        ),
+        preset(
+            "gif-readme",
+            &crate::i18n::t("preset_gif_readme"),
+            ExportFormat::Gif,
+            ExportQuality::Medium,
+            None,
+            OutputSettings { width: Some(800), fps: Some(12), include_audio: false, ..Default::default() },
+        ),
+    ]
+}
+
+/// Built-in presets followed by the user's
+pub fn all(user: &[ExportPreset]) -> Vec<ExportPreset> {
+    let mut presets = builtin();
+    presets.extend(user.iter().cloned());
+    presets
+}
+
+pub fn find(user: &[ExportPreset], id: &str) -> Option<ExportPreset> {
+    all(user).into_iter().find(|p| p.id == id)
+}
+
+/// Keep user presets only, dropping any that would shadow a built-in id
+pub fn sanitize_user(presets: Vec<ExportPreset>) -> Vec<ExportPreset> {
+    let builtin_ids: Vec<String> = builtin().into_iter().map(|p| p.id).collect();
+    let mut out: Vec<ExportPreset> = Vec::new();
+    for mut p in presets {
+        if p.builtin || builtin_ids.contains(&p.id) || out.iter().any(|o| o.id == p.id) {
+            continue;
+        }
+        p.output.width = p.output.width.map(|w| super::even(w.max(16) as f64));
+        p.output.height = p.output.height.map(|h| super::even(h.max(16) as f64));
+        p.output.fps = p.output.fps.map(|f| f.clamp(1, 120));
+        p.output.safe_area = super::safe_area::clamp(p.output.safe_area);
+        out.push(p);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: &str) -> ExportPreset {
+        ExportPreset {
+            id: id.into(),
+            name: id.into(),
+            format: ExportFormat::Mp4,
+            quality: ExportQuality::Low,
+            watermark: true,
+            target_size_mb: None,
+            output: OutputSettings { width: Some(1281), height: Some(721), fps: Some(500), ..Default::default() },
+            builtin: false,
+        }
+    }
+
+    #[test]
+    fn test_builtin_ids_are_unique() {
+        let presets = builtin();
+        for p in &presets {
+            assert_eq!(presets.iter().filter(|o| o.id == p.id).count(), 1, "{}", p.id);
+        }
+    }
+
+    #[test]
+    fn test_sanitize_user_drops_shadowing_and_duplicates() {
+        let presets = sanitize_user(vec![user("youtube-1080p"), user("mine"), user("mine")]);
+        assert_eq!(presets.len(), 1);
+        assert_eq!(presets[0].id, "mine");
+    }
+
+    #[test]
+    fn test_sanitize_user_clamps_output() {
+        let mut mine = user("mine");
+        mine.output.safe_area.bottom = 0.9;
+        let p = &sanitize_user(vec![mine])[0];
+        assert_eq!(p.output.width, Some(1280));
+        assert_eq!(p.output.height, Some(720));
+        assert_eq!(p.output.fps, Some(120));
+        assert_eq!(p.output.safe_area.bottom, 0.45);
+    }
+
+    #[test]
+    fn test_find_checks_builtin_then_user() {
+        let users = vec![user("mine")];
+        assert!(find(&users, "gif-readme").unwrap().builtin);
+        assert!(!find(&users, "mine").unwrap().builtin);
+        assert!(find(&users, "nope").is_none());
+    }
+}
@@ -0,0 +1,222 @@
+use crate::types::{Annotation, Clip, Subtitle, Transition, TransitionType};
+use std::collections::HashMap;
+
+/// Transition length used when the timeline has none for a clip pair, as in
+/// the xfade chain
+const DEFAULT_TRANSITION_S: f64 = 0.5;
+
+/// Length of the trimmed clip, in seconds
+fn clip_length(clip: &Clip) -> f64 {
+    let end = if clip.media.trim_end_ms > 0 { clip.media.trim_end_ms } else { clip.media.duration_ms };
+    (end.saturating_sub(clip.media.trim_start_ms) as f64 / 1000.0).max(0.1)
+}
+
+/// Where each clip starts on the assembled timeline, in seconds. Clips joined
+/// by an xfade overlap by the transition, clamped like the export does.
+fn clip_offsets(lengths: &[f64], transitions: &[Transition]) -> Vec<f64> {
+    let mut offsets = vec![0.0];
+    for i in 0..lengths.len().saturating_sub(1) {
+        let overlap = match transitions.get(i) {
+            Some(t) if t.transition_type == TransitionType::Cut => 0.0,
+            t => {
+                let raw = t.map(|t| t.duration_s).unwrap_or(DEFAULT_TRANSITION_S);
+                raw.clamp(0.1, (lengths[i].min(lengths[i + 1]) * 0.9).max(0.1))
+            }
+        };
+        offsets.push(offsets[i] + lengths[i] - overlap);
+    }
+    offsets
+}
+
+/// Cut the timeline down to `[start_ms, end_ms)` of its assembled output:
+/// clips outside are dropped with their transitions, the first and last kept
+/// clips are trimmed at the boundaries and annotations of a clip trimmed at
+/// its start move with it. Subtitles and timeline annotations are shifted to
+/// the range. Returns false when the range holds no clip.
+pub fn apply(
+    clips: &mut Vec<Clip>,
+    transitions: &mut Vec<Transition>,
+    clip_annotations: &mut HashMap<String, Vec<Annotation>>,
+    subtitles: &mut Vec<Subtitle>,
+    global_annotations: &mut Vec<Annotation>,
+    start_ms: u64,
+    end_ms: Option<u64>,
+) -> bool {
+    let lengths: Vec<f64> = clips.iter().map(clip_length).collect();
+    let offsets = clip_offsets(&lengths, transitions);
+    let start = start_ms as f64 / 1000.0;
+    let end = end_ms.map(|ms| ms as f64 / 1000.0).unwrap_or(f64::INFINITY);
+
+    let kept: Vec<usize> = (0..clips.len())
+        .filter(|&i| offsets[i] < end && offsets[i] + lengths[i] > start)
+        .collect();
+    let (Some(&first), Some(&last)) = (kept.first(), kept.last()) else {
+        return false;
+    };
+
+    for i in first..=last {
+        let clip = &mut clips[i];
+        let source_end = if clip.media.trim_end_ms > 0 { clip.media.trim_end_ms } else { clip.media.duration_ms };
+        if end < offsets[i] + lengths[i] {
+            let cut_end = clip.media.trim_start_ms + ((end - offsets[i]) * 1000.0).round() as u64;
+            clip.media.trim_end_ms = cut_end.min(source_end);
+        }
+        if start > offsets[i] {
+            let cut = ((start - offsets[i]) * 1000.0).round() as u64;
+            clip.media.trim_start_ms += cut;
+            if let Some(anns) = clip_annotations.get_mut(&clip.id) {
+                shift_annotations(anns, cut);
+            }
+        }
+    }
+    clips.truncate(last + 1);
+    clips.drain(..first);
+    transitions.truncate(last);
+    transitions.drain(..first.min(transitions.len()));
+
+    shift_subtitles(subtitles, start_ms, end_ms);
+    shift_global_annotations(global_annotations, start_ms, end_ms);
+    true
+}
+
+/// Start and end of each clip on the assembled timeline, in ms
+pub fn clip_spans(clips: &[Clip], transitions: &[Transition]) -> Vec<(u64, u64)> {
+    let lengths: Vec<f64> = clips.iter().map(clip_length).collect();
+    clip_offsets(&lengths, transitions)
+        .into_iter()
+        .zip(&lengths)
+        .map(|(offset, length)| ((offset * 1000.0).round() as u64, ((offset + length) * 1000.0).round() as u64))
+        .collect()
+}
+
+/// Output time of `source_ms` of `clip` playing from `clip_start_ms`, None
+/// when trimmed away
+pub fn output_time(clip: &Clip, clip_start_ms: u64, source_ms: u64) -> Option<u64> {
+    let trim_end_ms = if clip.media.trim_end_ms > 0 { clip.media.trim_end_ms } else { clip.media.duration_ms };
+    (clip.media.trim_start_ms..trim_end_ms)
+        .contains(&source_ms)
+        .then(|| clip_start_ms + source_ms - clip.media.trim_start_ms)
+}
+
+/// Length of the assembled timeline, in ms
+pub fn timeline_length_ms(clips: &[Clip], transitions: &[Transition]) -> u64 {
+    clip_spans(clips, transitions).last().map(|&(_, end)| end).unwrap_or(0)
+}
+
+/// Keep the subtitles shown within `[start_ms, end_ms)`, moved to start at 0
+/// and cut at the end
+pub fn shift_subtitles(subtitles: &mut Vec<Subtitle>, start_ms: u64, end_ms: Option<u64>) {
+    let range_end_ms = end_ms.map(|ms| ms - start_ms).unwrap_or(u64::MAX);
+    subtitles.retain(|s| s.end_ms > start_ms && s.start_ms.saturating_sub(start_ms) < range_end_ms);
+    for sub in subtitles.iter_mut() {
+        sub.start_ms = sub.start_ms.saturating_sub(start_ms);
+        sub.end_ms = (sub.end_ms - start_ms).min(range_end_ms);
+    }
+}
+
+/// Keep the timeline annotations shown within `[start_ms, end_ms)`, moved to
+/// start at 0 and cut at the end
+pub fn shift_global_annotations(annotations: &mut Vec<Annotation>, start_ms: u64, end_ms: Option<u64>) {
+    if let Some(end_ms) = end_ms {
+        annotations.retain(|a| a.start_ms < end_ms);
+        for ann in annotations.iter_mut() {
+            ann.end_ms = ann.end_ms.min(end_ms);
+        }
+    }
+    shift_annotations(annotations, start_ms);
+}
+
+/// Move annotations `cut_ms` earlier, dropping those that end before 0
+pub fn shift_annotations(annotations: &mut Vec<Annotation>, cut_ms: u64) {
+    annotations.retain(|a| a.end_ms > cut_ms);
+    for ann in annotations.iter_mut() {
+        ann.start_ms = ann.start_ms.saturating_sub(cut_ms);
+        ann.end_ms -= cut_ms;
+        for kf in &mut ann.keyframes {
+            kf.time_ms = kf.time_ms.saturating_sub(cut_ms);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AnnotationKind, SubtitlePosition, test_annotation, test_clip};
+
+    fn transition(transition_type: TransitionType, duration_s: f64) -> Transition {
+        Transition { transition_type, duration_s }
+    }
+
+    fn ann(start_ms: u64, end_ms: u64) -> Annotation {
+        Annotation { id: format!("{start_ms}"), ..test_annotation(AnnotationKind::Rectangle, start_ms, end_ms) }
+    }
+
+    #[test]
+    fn test_offsets_overlap_on_xfades_only() {
+        let lengths = [10.0, 10.0, 10.0];
+        let transitions = [transition(TransitionType::Fade, 1.0), transition(TransitionType::Cut, 1.0)];
+        assert_eq!(clip_offsets(&lengths, &transitions), vec![0.0, 9.0, 19.0]);
+        // Missing transitions fall back to a default fade
+        assert_eq!(clip_offsets(&lengths, &[]), vec![0.0, 9.5, 19.0]);
+    }
+
+    #[test]
+    fn test_range_trims_boundary_clips_and_drops_the_rest() {
+        let mut clips = vec![test_clip("a", 10_000), test_clip("b", 10_000), test_clip("c", 10_000)];
+        let mut transitions = vec![transition(TransitionType::Cut, 0.5), transition(TransitionType::Cut, 0.5)];
+        let mut annotations = HashMap::from([("b".to_string(), vec![ann(1000, 3000), ann(4000, 8000)])]);
+        let mut subtitles = Vec::new();
+        let mut globals = vec![ann(12_000, 20_000), ann(0, 2000)];
+
+        // 13 s to 18 s: inside clip b only
+        assert!(apply(&mut clips, &mut transitions, &mut annotations, &mut subtitles, &mut globals, 13_000, Some(18_000)));
+        assert_eq!(clips.len(), 1);
+        assert_eq!((clips[0].id.as_str(), clips[0].media.trim_start_ms, clips[0].media.trim_end_ms), ("b", 3000, 8000));
+        assert!(transitions.is_empty());
+        let kept: Vec<(u64, u64)> = annotations["b"].iter().map(|a| (a.start_ms, a.end_ms)).collect();
+        assert_eq!(kept, vec![(1000, 5000)]);
+        let kept: Vec<(u64, u64)> = globals.iter().map(|a| (a.start_ms, a.end_ms)).collect();
+        assert_eq!(kept, vec![(0, 5000)]);
+    }
+
+    #[test]
+    fn test_range_keeps_transition_across_the_cut() {
+        let mut clips = vec![test_clip("a", 10_000), test_clip("b", 10_000), test_clip("c", 10_000)];
+        clips[0].media.trim_start_ms = 2000;
+        let mut transitions = vec![transition(TransitionType::Fade, 1.0), transition(TransitionType::Fade, 1.0)];
+        let mut subtitles = vec![Subtitle {
+            id: "s".into(),
+            text: "Bonjour".into(),
+            start_ms: 5000,
+            end_ms: 9000,
+            position: SubtitlePosition::Bottom,
+            font_size: 32,
+            color: "#fff".into(),
+            font_file: None,
+        }];
+
+        // a lasts 8 s, b starts at 7 s: 6 s to 10 s spans both
+        assert!(apply(&mut clips, &mut transitions, &mut HashMap::new(), &mut subtitles, &mut Vec::new(), 6000, Some(10_000)));
+        assert_eq!(clips.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!((clips[0].media.trim_start_ms, clips[0].media.trim_end_ms), (8000, 0));
+        assert_eq!((clips[1].media.trim_start_ms, clips[1].media.trim_end_ms), (0, 3000));
+        assert_eq!(transitions.len(), 1);
+        assert_eq!((subtitles[0].start_ms, subtitles[0].end_ms), (0, 3000));
+    }
+
+    #[test]
+    fn test_range_past_the_end_is_empty() {
+        let mut clips = vec![test_clip("a", 5000)];
+        let mut transitions = Vec::new();
+        assert!(!apply(&mut clips, &mut transitions, &mut HashMap::new(), &mut Vec::new(), &mut Vec::new(), 6000, None));
+    }
+
+    #[test]
+    fn test_clip_spans_follow_trims_and_overlaps() {
+        let mut clips = vec![test_clip("a", 10_000), test_clip("b", 4000)];
+        clips[0].media.trim_end_ms = 6000;
+        let transitions = vec![transition(TransitionType::Fade, 1.0)];
+        assert_eq!(clip_spans(&clips, &transitions), vec![(0, 6000), (5000, 9000)]);
+        assert_eq!(timeline_length_ms(&clips, &transitions), 9000);
+    }
+}
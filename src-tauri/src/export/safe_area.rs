@@ -0,0 +1,100 @@
+use crate::types::{SafeArea, SafeAreaGuide};
+
+/// Largest margin on a side, so the safe area never collapses
+const MAX_MARGIN: f64 = 0.45;
+
+fn guide(id: &str, name: impl Into<String>, width: u32, height: u32, safe_area: SafeArea) -> SafeAreaGuide {
+    SafeAreaGuide {
+        id: id.into(),
+        name: name.into(),
+        width,
+        height,
+        safe_area,
+        // Rule of thirds
+        grid_columns: 3,
+        grid_rows: 3,
+    }
+}
+
+fn margins(top: f64, right: f64, bottom: f64, left: f64) -> SafeArea {
+    SafeArea { top, right, bottom, left }
+}
+
+/// Guides shipped with the app. The vertical ones leave out the caption and
+/// the buttons each platform draws over the video.
+pub fn builtin() -> Vec<SafeAreaGuide> {
+    vec![
+        guide("tiktok", "TikTok", 1080, 1920, margins(0.08, 0.12, 0.2, 0.05)),
+        guide("youtube-shorts", "YouTube Shorts", 1080, 1920, margins(0.08, 0.13, 0.17, 0.05)),
+        guide("instagram-reels", "Instagram Reels", 1080, 1920, margins(0.11, 0.1, 0.18, 0.05)),
+        // Title-safe area of broadcast video
+        guide("title-16x9", crate::i18n::t("guide_title_safe"), 1920, 1080, margins(0.05, 0.05, 0.05, 0.05)),
+    ]
+}
+
+pub fn find(id: &str) -> Option<SafeAreaGuide> {
+    builtin().into_iter().find(|g| g.id == id)
+}
+
+/// Every margin within [0, `MAX_MARGIN`], NaN read as none
+pub fn clamp(area: SafeArea) -> SafeArea {
+    let side = |v: f64| if v.is_nan() { 0.0 } else { v.clamp(0.0, MAX_MARGIN) };
+    margins(side(area.top), side(area.right), side(area.bottom), side(area.left))
+}
+
+/// Widest margin on each side, clear of every platform's UI at once
+pub fn union(areas: impl IntoIterator<Item = SafeArea>) -> SafeArea {
+    areas.into_iter().map(clamp).fold(SafeArea::default(), |a, b| {
+        margins(a.top.max(b.top), a.right.max(b.right), a.bottom.max(b.bottom), a.left.max(b.left))
+    })
+}
+
+/// Drawtext position `base` moved in by `fraction` of `size` (`w` or `h`):
+/// `sign` is '+' for a position measured from the left or top, '-' from the
+/// right or bottom. Left as is without a margin.
+pub(crate) fn inset(base: &str, sign: char, fraction: f64, size: &str) -> String {
+    if fraction > 0.0 {
+        format!("{base}{sign}{size}*{fraction:.4}")
+    } else {
+        base.into()
+    }
+}
+
+/// Drawtext position centering `text` (`tw` or `th`) between the margins
+/// `start` and `end` of `size`
+pub(crate) fn centered(size: &str, text: &str, start: f64, end: f64) -> String {
+    if start > 0.0 || end > 0.0 {
+        format!("{size}*{start:.4}+({size}*{:.4}-{text})/2", 1.0 - start - end)
+    } else {
+        format!("({size}-{text})/2")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_guides_leave_room() {
+        for g in builtin() {
+            assert_eq!(clamp(g.safe_area), g.safe_area, "{}", g.id);
+            assert!(find(&g.id).is_some());
+        }
+        assert!(find("unknown").is_none());
+    }
+
+    #[test]
+    fn test_union_and_clamp() {
+        let wide = union([margins(0.1, 0.0, 0.2, 0.0), margins(0.05, 0.9, f64::NAN, 0.03)]);
+        assert_eq!(wide, margins(0.1, MAX_MARGIN, 0.2, 0.03));
+        assert_eq!(union([]), SafeArea::default());
+    }
+
+    #[test]
+    fn test_expressions_unchanged_without_margins() {
+        assert_eq!(inset("h-80", '-', 0.0, "h"), "h-80");
+        assert_eq!(inset("h-80", '-', 0.2, "h"), "h-80-h*0.2000");
+        assert_eq!(centered("w", "tw", 0.0, 0.0), "(w-tw)/2");
+        assert_eq!(centered("w", "tw", 0.05, 0.15), "w*0.0500+(w*0.8000-tw)/2");
+    }
+}
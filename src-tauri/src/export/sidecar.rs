@@ -0,0 +1,167 @@
+use super::range::{clip_spans, output_time};
+use crate::types::{Clip, KeystrokeEvent, MarkerKind, Subtitle, TimelineMarker, Transition};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Bumped when a field changes meaning
+const SIDECAR_VERSION: u32 = 1;
+
+/// Description of an exported timeline, every timestamp in ms of the output
+#[derive(Debug, Serialize)]
+struct Sidecar {
+    version: u32,
+    output: String,
+    duration_ms: u64,
+    clips: Vec<SidecarClip>,
+    transitions: Vec<Transition>,
+    subtitles: Vec<Subtitle>,
+}
+
+#[derive(Debug, Serialize)]
+struct SidecarClip {
+    id: String,
+    source: String,
+    /// Where the clip plays in the output
+    start_ms: u64,
+    end_ms: u64,
+    /// Part of the source that was kept
+    trim_start_ms: u64,
+    trim_end_ms: u64,
+    markers: Vec<SidecarMarker>,
+    keystrokes: Vec<SidecarKeystroke>,
+}
+
+#[derive(Debug, Serialize)]
+struct SidecarMarker {
+    timestamp_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_ms: Option<u64>,
+    label: String,
+    kind: MarkerKind,
+}
+
+#[derive(Debug, Serialize)]
+struct SidecarKeystroke {
+    timestamp_ms: u64,
+    key: String,
+}
+
+/// `recording.mp4` -> `recording.mp4.json`, so outputs sharing a stem keep
+/// their own sidecar
+pub fn sidecar_path(output: &Path) -> PathBuf {
+    let name = output.file_name().unwrap_or_default().to_string_lossy();
+    output.with_file_name(format!("{}.json", name))
+}
+
+/// Write the sidecar of `output` next to it
+pub fn write(
+    output: &Path,
+    clips: &[Clip],
+    transitions: &[Transition],
+    clip_keystrokes: &HashMap<String, Vec<KeystrokeEvent>>,
+    clip_markers: &HashMap<String, Vec<TimelineMarker>>,
+    subtitles: &[Subtitle],
+) -> Result<PathBuf, String> {
+    let sidecar = build(output, clips, transitions, clip_keystrokes, clip_markers, subtitles);
+    let json = serde_json::to_string_pretty(&sidecar).map_err(|e| e.to_string())?;
+    let path = sidecar_path(output);
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+fn build(
+    output: &Path,
+    clips: &[Clip],
+    transitions: &[Transition],
+    clip_keystrokes: &HashMap<String, Vec<KeystrokeEvent>>,
+    clip_markers: &HashMap<String, Vec<TimelineMarker>>,
+    subtitles: &[Subtitle],
+) -> Sidecar {
+    let spans = clip_spans(clips, transitions);
+    let clips: Vec<SidecarClip> = clips
+        .iter()
+        .zip(&spans)
+        .map(|(clip, &(start_ms, end_ms))| {
+            let trim_end_ms = if clip.media.trim_end_ms > 0 { clip.media.trim_end_ms } else { clip.media.duration_ms };
+            let place = |source_ms: u64| output_time(clip, start_ms, source_ms);
+            // Spans are kept when they overlap the kept part, cut to it
+            let place_marker = |m: &TimelineMarker| match m.end_ms {
+                Some(end) if end > clip.media.trim_start_ms && m.timestamp_ms < trim_end_ms => {
+                    let from = m.timestamp_ms.max(clip.media.trim_start_ms);
+                    let to = end.min(trim_end_ms);
+                    Some((start_ms + from - clip.media.trim_start_ms, Some(start_ms + to - clip.media.trim_start_ms)))
+                }
+                Some(_) => None,
+                None => place(m.timestamp_ms).map(|at| (at, None)),
+            };
+            SidecarClip {
+                id: clip.id.clone(),
+                source: clip.media.path.to_string_lossy().to_string(),
+                start_ms,
+                end_ms,
+                trim_start_ms: clip.media.trim_start_ms,
+                trim_end_ms,
+                markers: clip_markers.get(&clip.id).into_iter().flatten()
+                    .filter_map(|m| {
+                        let (timestamp_ms, end_ms) = place_marker(m)?;
+                        Some(SidecarMarker { timestamp_ms, end_ms, label: m.label.clone(), kind: m.kind })
+                    })
+                    .collect(),
+                keystrokes: clip_keystrokes.get(&clip.id).into_iter().flatten()
+                    .filter_map(|k| Some(SidecarKeystroke { timestamp_ms: place(k.timestamp_ms)?, key: k.key_name.clone() }))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Sidecar {
+        version: SIDECAR_VERSION,
+        output: output.to_string_lossy().to_string(),
+        duration_ms: spans.last().map(|&(_, end)| end).unwrap_or(0),
+        clips,
+        transitions: transitions.to_vec(),
+        subtitles: subtitles.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TransitionType, test_clip};
+
+    fn key(timestamp_ms: u64, key_name: &str) -> KeystrokeEvent {
+        KeystrokeEvent { timestamp_ms, key_name: key_name.into() }
+    }
+
+    #[test]
+    fn test_sidecar_path_keeps_the_extension() {
+        assert_eq!(sidecar_path(Path::new("d/rec.gif")), PathBuf::from("d/rec.gif.json"));
+    }
+
+    #[test]
+    fn test_events_are_placed_on_the_output_timeline() {
+        let mut clips = vec![test_clip("a", 10_000), test_clip("b", 10_000)];
+        clips[1].media.trim_start_ms = 2000;
+        let transitions = vec![Transition { transition_type: TransitionType::Fade, duration_s: 1.0 }];
+        let keystrokes = HashMap::from([
+            ("a".to_string(), vec![key(500, "Ctrl+S")]),
+            ("b".to_string(), vec![key(1000, "A"), key(3000, "B")]),
+        ]);
+        let markers = HashMap::from([("b".to_string(), vec![
+            TimelineMarker { timestamp_ms: 2500, label: "Pause".into(), kind: MarkerKind::Privacy, end_ms: None },
+            TimelineMarker { timestamp_ms: 1000, label: "Micro coupé".into(), kind: MarkerKind::MicMute, end_ms: Some(4000) },
+        ])]);
+
+        let sidecar = build(Path::new("out.mp4"), &clips, &transitions, &keystrokes, &markers, &[]);
+        assert_eq!(sidecar.duration_ms, 17_000);
+        assert_eq!((sidecar.clips[1].start_ms, sidecar.clips[1].end_ms), (9000, 17_000));
+        assert_eq!(sidecar.clips[0].keystrokes[0].timestamp_ms, 500);
+        // The first key of b was trimmed away
+        let b_keys: Vec<(u64, &str)> = sidecar.clips[1].keystrokes.iter().map(|k| (k.timestamp_ms, k.key.as_str())).collect();
+        assert_eq!(b_keys, vec![(10_000, "B")]);
+        assert_eq!(sidecar.clips[1].markers[0].timestamp_ms, 9500);
+        // The mute span starts before the trim and is cut to it
+        assert_eq!((sidecar.clips[1].markers[1].timestamp_ms, sidecar.clips[1].markers[1].end_ms), (9000, Some(11_000)));
+    }
+}
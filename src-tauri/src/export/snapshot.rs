@@ -0,0 +1,164 @@
+use crate::state::AppState;
+use crate::types::{
+    Annotation, Clip, CursorPosition, ExportDownscale, ExportEncoder, ExportFormat, ExportFps, ExportPreset,
+    FfmpegCapabilities, GamepadSample, GifLoop, GifPalette, KeystrokeEvent, OutputSettings, PostExportSettings,
+    Subtitle, SubtitleExport, SubtitleTrack, TelemetrySettings, TimelineMarker, Transition, Voiceover, Webhook,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// What an export reads from the app state, copied once so the lock is not
+/// held while rendering. The range and the prepasses rewrite it in place.
+pub struct ExportSnapshot {
+    pub clips: Vec<Clip>,
+    pub transitions: Vec<Transition>,
+    pub clip_keystrokes: HashMap<String, Vec<KeystrokeEvent>>,
+    pub keystroke_font: Option<String>,
+    pub subtitles: Vec<Subtitle>,
+    /// Every language, the active one first
+    pub subtitle_tracks: Vec<SubtitleTrack>,
+    pub subtitle_export: SubtitleExport,
+    pub global_annotations: Vec<Annotation>,
+    pub clip_annotations: HashMap<String, Vec<Annotation>>,
+    pub clip_cursor_positions: HashMap<String, Vec<CursorPosition>>,
+    pub clip_gamepad: HashMap<String, Vec<GamepadSample>>,
+    pub clip_markers: HashMap<String, Vec<TimelineMarker>>,
+    pub voiceovers: Vec<Voiceover>,
+    pub system_volume: f32,
+    pub mic_volume: f32,
+    pub export_presets: Vec<ExportPreset>,
+    pub export_encoder: ExportEncoder,
+    pub export_fps: ExportFps,
+    pub export_downscale: ExportDownscale,
+    pub hdr_tonemap: bool,
+    pub keep_filter_scripts: bool,
+    pub gif_palette: GifPalette,
+    pub gif_loop: GifLoop,
+    pub export_chunk_minutes: u32,
+    pub export_sidecar: bool,
+    pub export_chapters: bool,
+    pub ffmpeg_capabilities: Option<FfmpegCapabilities>,
+    pub post_export: PostExportSettings,
+    pub webhooks: Vec<Webhook>,
+    pub telemetry: TelemetrySettings,
+    pub current_project_id: Option<String>,
+    pub temp_dir: PathBuf,
+}
+
+impl ExportSnapshot {
+    pub fn from_state(s: &AppState) -> Self {
+        Self {
+            clips: s.clips.clone(),
+            transitions: s.transitions.clone(),
+            clip_keystrokes: s.clip_keystrokes.clone(),
+            keystroke_font: s.keystroke_font.clone(),
+            subtitles: s.subtitles.clone(),
+            subtitle_tracks: crate::subtitles::all_tracks(&s.subtitles, &s.subtitle_language, &s.subtitle_tracks),
+            subtitle_export: s.subtitle_export,
+            global_annotations: s.global_annotations.clone(),
+            clip_annotations: s.annotations.clone(),
+            clip_cursor_positions: s.clip_cursor_positions.clone(),
+            clip_gamepad: s.clip_gamepad.clone(),
+            clip_markers: s.clip_markers.clone(),
+            voiceovers: s.voiceovers.clone(),
+            system_volume: s.system_volume,
+            mic_volume: s.mic_volume,
+            export_presets: s.export_presets.clone(),
+            export_encoder: s.export_encoder,
+            export_fps: s.export_fps,
+            export_downscale: s.export_downscale,
+            hdr_tonemap: s.hdr_tonemap,
+            keep_filter_scripts: s.keep_filter_scripts,
+            gif_palette: s.gif_palette,
+            gif_loop: s.gif_loop,
+            export_chunk_minutes: s.export_chunk_minutes,
+            export_sidecar: s.export_sidecar,
+            export_chapters: s.export_chapters,
+            ffmpeg_capabilities: s.ffmpeg_capabilities.clone(),
+            post_export: s.post_export.clone(),
+            webhooks: s.webhooks.clone(),
+            telemetry: s.telemetry.clone(),
+            current_project_id: s.current_project_id.clone(),
+            temp_dir: s.temp_dir.clone(),
+        }
+    }
+
+    /// The overlays and levels the encoders read, with the timeline's subtitles
+    pub fn context(&self) -> ExportContext<'_> {
+        ExportContext {
+            clip_keystrokes: &self.clip_keystrokes,
+            keystroke_font: self.keystroke_font.as_deref(),
+            subtitles: &self.subtitles,
+            global_annotations: &self.global_annotations,
+            clip_annotations: &self.clip_annotations,
+            clip_cursor_positions: &self.clip_cursor_positions,
+            clip_gamepad: &self.clip_gamepad,
+            system_volume: self.system_volume,
+            mic_volume: self.mic_volume,
+        }
+    }
+
+    /// `output` completed with the app-wide export settings. GIFs keep
+    /// their own low frame rate.
+    pub fn output_settings(&self, format: ExportFormat, output: OutputSettings) -> OutputSettings {
+        let mut output = OutputSettings {
+            encoder: self.export_encoder,
+            tonemap_hdr: self.tonemap_hdr(),
+            keep_filter_script: self.keep_filter_scripts,
+            gif_palette: self.gif_palette,
+            gif_loop: self.gif_loop,
+            ..output
+        };
+        if !matches!(format, ExportFormat::Gif) {
+            self.export_fps.apply_to(&mut output);
+        }
+        output
+    }
+
+    /// Keep the HDR mark only on clips whose video is tagged PQ or HLG: one
+    /// recorded while an HDR display was on may still hold SDR frames
+    pub async fn probe_hdr(&mut self) {
+        if !self.hdr_tonemap {
+            return;
+        }
+        for clip in self.clips.iter_mut().filter(|c| c.media.hdr) {
+            if !crate::export::encoder::probe_is_hdr(&clip.media.path).await {
+                tracing::info!("Clip {} holds SDR video, not tonemapped", clip.id);
+                clip.media.hdr = false;
+            }
+        }
+    }
+
+    /// Whether HDR clips get tonemapped; builds without zimg export them as captured
+    fn tonemap_hdr(&self) -> bool {
+        if !self.hdr_tonemap || !self.clips.iter().any(|c| c.media.hdr) {
+            return false;
+        }
+        match &self.ffmpeg_capabilities {
+            Some(caps) => match crate::ffmpeg::capabilities::ensure_supported(caps, &["zscale", "tonemap"]) {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!("HDR clips exported without tonemapping: {}", e);
+                    false
+                }
+            },
+            None => true,
+        }
+    }
+}
+
+/// Overlays and mix levels every encoder reads, borrowed from an
+/// [`ExportSnapshot`]. Chunks and single clips swap in their own subtitles
+/// and annotations with struct update syntax.
+#[derive(Clone, Copy)]
+pub struct ExportContext<'a> {
+    pub clip_keystrokes: &'a HashMap<String, Vec<KeystrokeEvent>>,
+    pub keystroke_font: Option<&'a str>,
+    pub subtitles: &'a [Subtitle],
+    pub global_annotations: &'a [Annotation],
+    pub clip_annotations: &'a HashMap<String, Vec<Annotation>>,
+    pub clip_cursor_positions: &'a HashMap<String, Vec<CursorPosition>>,
+    pub clip_gamepad: &'a HashMap<String, Vec<GamepadSample>>,
+    pub system_volume: f32,
+    pub mic_volume: f32,
+}
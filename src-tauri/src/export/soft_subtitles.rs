@@ -0,0 +1,153 @@
+use super::encoder::translate_ffmpeg_error;
+use crate::types::{ExportFormat, Subtitle, SubtitleTrack};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// 639-1 codes of the common languages with their 639-2 form, the one MP4
+/// stores. Others are passed as given.
+const ISO_639_2: &[(&str, &str)] = &[
+    ("ar", "ara"),
+    ("de", "deu"),
+    ("en", "eng"),
+    ("es", "spa"),
+    ("fr", "fra"),
+    ("hi", "hin"),
+    ("it", "ita"),
+    ("ja", "jpn"),
+    ("ko", "kor"),
+    ("nl", "nld"),
+    ("pl", "pol"),
+    ("pt", "por"),
+    ("ru", "rus"),
+    ("tr", "tur"),
+    ("uk", "ukr"),
+    ("zh", "zho"),
+];
+
+/// "pt-br" -> "por"
+fn iso_639_2(language: &str) -> &str {
+    let base = language.split('-').next().unwrap_or(language);
+    ISO_639_2.iter().find(|(short, _)| *short == base).map(|(_, long)| *long).unwrap_or(base)
+}
+
+fn srt_time(ms: u64) -> String {
+    format!("{:02}:{:02}:{:02},{:03}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000)
+}
+
+/// SubRip text of `subtitles`, in the order they are shown
+pub fn to_srt(subtitles: &[Subtitle]) -> String {
+    let mut sorted: Vec<&Subtitle> = subtitles.iter().filter(|s| s.end_ms > s.start_ms).collect();
+    sorted.sort_by_key(|s| s.start_ms);
+    sorted
+        .iter()
+        .enumerate()
+        .map(|(i, s)| format!("{}\n{} --> {}\n{}\n\n", i + 1, srt_time(s.start_ms), srt_time(s.end_ms), s.text.trim()))
+        .collect()
+}
+
+/// Remux of `video` with each of `srt_files` (language, path) as a
+/// subtitle track, to `output`. Audio and video are copied.
+fn mux_args(video: &Path, srt_files: &[(String, PathBuf)], format: ExportFormat, output: &Path) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-i".into(), video.to_string_lossy().to_string()];
+    for (_, path) in srt_files {
+        args.extend(["-i".to_string(), path.to_string_lossy().to_string()]);
+    }
+    args.extend(["-map".into(), "0".into()]);
+    for i in 1..=srt_files.len() {
+        args.extend(["-map".into(), i.to_string()]);
+    }
+    let codec = if matches!(format, ExportFormat::Webm) { "webvtt" } else { "mov_text" };
+    args.extend(["-c", "copy", "-c:s", codec].iter().map(|s| s.to_string()));
+    for (i, (language, _)) in srt_files.iter().enumerate() {
+        args.extend([format!("-metadata:s:s:{i}"), format!("language={}", iso_639_2(language))]);
+    }
+    if !matches!(format, ExportFormat::Webm) {
+        args.extend(["-movflags".into(), "+faststart".into()]);
+    }
+    args.push("-y".into());
+    args.push(output.to_string_lossy().to_string());
+    args
+}
+
+/// Add every subtitle language of `tracks` to the finished MP4 or WebM
+/// export at `path` as a track of its own. The SubRip files and the remux
+/// are written to `temp_dir`, clear of the user's files next to the export.
+pub async fn mux_into(path: &Path, format: ExportFormat, tracks: &[SubtitleTrack], temp_dir: &Path) -> Result<()> {
+    if tracks.is_empty() {
+        return Ok(());
+    }
+    let id = uuid::Uuid::new_v4();
+    let mut srt_files = Vec::new();
+    for track in tracks {
+        let srt = temp_dir.join(format!("subtitles_{}.{}.srt", id, track.language));
+        std::fs::write(&srt, to_srt(&track.subtitles)).with_context(|| crate::i18n::t("subtitles_write_failed"))?;
+        srt_files.push((track.language.clone(), srt));
+    }
+    let muxed = temp_dir.join(format!("subtitles_{}.{}", id, format.extension()));
+    let args = mux_args(path, &srt_files, format, &muxed);
+    tracing::info!("Adding {} subtitle track(s) to {:?}", tracks.len(), path);
+
+    let output = crate::ffmpeg_command()
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .context("Failed to run subtitle mux");
+    for (_, srt) in &srt_files {
+        let _ = std::fs::remove_file(srt);
+    }
+    let output = output?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&muxed);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        tracing::error!("FFmpeg stderr:\n{}", stderr);
+        anyhow::bail!("{}", translate_ffmpeg_error(&stderr));
+    }
+    // The temp dir may sit on another drive than the export
+    let replaced = std::fs::rename(&muxed, path).or_else(|_| std::fs::copy(&muxed, path).map(|_| ()));
+    let _ = std::fs::remove_file(&muxed);
+    replaced.with_context(|| crate::i18n::t("soft_subtitles_replace_failed"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SubtitlePosition;
+
+    fn subtitle(text: &str, start_ms: u64, end_ms: u64) -> Subtitle {
+        Subtitle {
+            id: text.into(),
+            text: text.into(),
+            start_ms,
+            end_ms,
+            position: SubtitlePosition::Bottom,
+            font_size: 32,
+            color: "#ffffff".into(),
+            font_file: None,
+        }
+    }
+
+    #[test]
+    fn test_to_srt() {
+        let srt = to_srt(&[subtitle("Deux", 61_500, 3_723_004), subtitle("Un ", 0, 1200), subtitle("vide", 5000, 5000)]);
+        assert_eq!(srt, "1\n00:00:00,000 --> 00:00:01,200\nUn\n\n2\n00:01:01,500 --> 01:02:03,004\nDeux\n\n");
+    }
+
+    #[test]
+    fn test_mux_args_tag_each_language() {
+        let files = [("fr".to_string(), PathBuf::from("a.fr.srt")), ("pt-br".to_string(), PathBuf::from("a.pt-br.srt"))];
+        let args = mux_args(Path::new("a.mp4"), &files, ExportFormat::Mp4, Path::new("b.mp4"));
+        assert!(args.windows(2).any(|w| w[0] == "-c:s" && w[1] == "mov_text"));
+        assert!(args.windows(2).any(|w| w[0] == "-map" && w[1] == "2"));
+        assert!(args.windows(2).any(|w| w[0] == "-metadata:s:s:0" && w[1] == "language=fra"));
+        assert!(args.windows(2).any(|w| w[0] == "-metadata:s:s:1" && w[1] == "language=por"));
+
+        let webm = mux_args(Path::new("a.webm"), &files[..1], ExportFormat::Webm, Path::new("b.webm"));
+        assert!(webm.windows(2).any(|w| w[0] == "-c:s" && w[1] == "webvtt"));
+        assert!(!webm.contains(&"-movflags".to_string()));
+    }
+}
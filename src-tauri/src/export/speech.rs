@@ -0,0 +1,76 @@
+use super::autotrim::{audio_sources, decode, sound_spans, window_levels};
+use crate::types::{Clip, SpeechSegment};
+use anyhow::Result;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Pauses shorter than this stay inside a sentence
+const MAX_PAUSE_MS: u64 = 500;
+/// Shorter bursts are breaths or mouth noise
+const MIN_SPEECH_MS: u64 = 250;
+
+/// What a clip's segments were detected from. They are detected again once
+/// the clip plays another file, one of its files changed or its trim moved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeechKey {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    trim_start_ms: u64,
+    trim_end_ms: u64,
+}
+
+impl SpeechKey {
+    pub fn of(clip: &Clip) -> Self {
+        let modified = std::iter::once(clip.media.path.clone())
+            .chain(clip.media.audio_paths.iter().map(PathBuf::from))
+            .filter_map(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok())
+            .max();
+        Self {
+            path: clip.media.path.clone(),
+            modified,
+            trim_start_ms: clip.media.trim_start_ms,
+            trim_end_ms: clip.media.trim_end_ms,
+        }
+    }
+}
+
+/// Speech segments of the clip's mic track, or of all its audio when the
+/// voice wasn't recorded separately (imported videos)
+pub async fn detect(clip: &Clip) -> Result<Vec<SpeechSegment>> {
+    let mic: Vec<PathBuf> = clip.media.audio_paths.iter()
+        .filter(|p| p.ends_with("_mic.wav"))
+        .map(PathBuf::from)
+        .collect();
+    let sources = if mic.is_empty() { audio_sources(clip) } else { mic };
+
+    let mut spans = Vec::new();
+    for path in &sources {
+        spans.extend(sound_spans(&window_levels(&decode(path).await?)));
+    }
+    Ok(sentences(spans))
+}
+
+/// Merge sound spans into sentences and drop the short leftovers
+fn sentences(mut spans: Vec<(u64, u64)>) -> Vec<SpeechSegment> {
+    spans.sort();
+    let mut merged: Vec<SpeechSegment> = Vec::new();
+    for (start_ms, end_ms) in spans {
+        match merged.last_mut() {
+            Some(last) if start_ms <= last.end_ms + MAX_PAUSE_MS => last.end_ms = last.end_ms.max(end_ms),
+            _ => merged.push(SpeechSegment { start_ms, end_ms }),
+        }
+    }
+    merged.retain(|s| s.end_ms - s.start_ms >= MIN_SPEECH_MS);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sentences_merge_short_pauses() {
+        let spans = vec![(3000, 3200), (0, 800), (1000, 1500), (5000, 5100)];
+        assert_eq!(sentences(spans), vec![SpeechSegment { start_ms: 0, end_ms: 1500 }]);
+    }
+}
@@ -0,0 +1,214 @@
+use super::encoder::{probe_fps, DEFAULT_FPS};
+use crate::types::{
+    Clip, ExportDownscale, ExportFormat, ExportIssue, ExportIssueKind, ExportQuality, IssueSeverity,
+    OutputSettings,
+};
+use std::path::Path;
+
+/// Free space wanted on top of the estimated size: intermediate files
+/// (GIF source, WebM mezzanine, parts) and the estimate's own error
+const DISK_MARGIN: f64 = 1.5;
+/// A source this many frames per second under the export rate shows as
+/// repeated frames
+const FPS_TOLERANCE: f64 = 1.0;
+
+/// Everything that would make the export fail or come out wrong, checked in
+/// seconds before an encode that may take minutes: missing or unreadable
+/// files, sizes and frame rates, disk space, output folder. Empty when the
+/// export can go ahead.
+pub async fn validate(
+    clips: &[Clip],
+    format: ExportFormat,
+    quality: &ExportQuality,
+    target_size_mb: Option<u32>,
+    output: &OutputSettings,
+    downscale: ExportDownscale,
+    output_dir: &Path,
+) -> Vec<ExportIssue> {
+    let mut issues = Vec::new();
+    if clips.is_empty() {
+        issues.push(error(ExportIssueKind::NoClips, None, crate::i18n::t("no_clips_to_export")));
+        return issues;
+    }
+
+    let mut output = output.clone();
+    if let Err(e) = super::limits::enforce(downscale, output.encoder, clips, &mut output) {
+        issues.push(error(ExportIssueKind::Resolution, None, e));
+    }
+    let output = super::encoder::resolve_source_fps(clips, &output).await;
+    let export_fps = output.fps.unwrap_or(DEFAULT_FPS) as f64;
+
+    for (i, clip) in clips.iter().enumerate() {
+        let file_issues = file_issues(i + 1, clip, output.include_audio);
+        let missing_video = file_issues.iter().any(|issue| issue.kind == ExportIssueKind::MissingClip);
+        issues.extend(file_issues);
+        if missing_video {
+            continue;
+        }
+
+        let Some((width, height, _)) = crate::capture::screen::probe_video(&clip.media.path).await else {
+            issues.push(error(
+                ExportIssueKind::UnreadableClip,
+                Some(clip),
+                crate::i18n::tf("clip_unreadable", &[&(i + 1)]),
+            ));
+            continue;
+        };
+        if width.abs_diff(clip.media.region.width) > 1 || height.abs_diff(clip.media.region.height) > 1 {
+            issues.push(warning(
+                ExportIssueKind::SizeMismatch,
+                Some(clip),
+                crate::i18n::tf(
+                    "clip_size_mismatch",
+                    &[&(i + 1), &width, &height, &clip.media.region.width, &clip.media.region.height],
+                ),
+            ));
+        }
+        if !matches!(format, ExportFormat::Gif) {
+            if let Some(issue) = probe_fps(&clip.media.path).await.and_then(|fps| frame_rate_issue(i + 1, clip, fps, export_fps)) {
+                issues.push(issue);
+            }
+        }
+    }
+
+    let estimate = super::estimate::heuristic(clips, format, quality, target_size_mb, &output);
+    match writable(output_dir) {
+        Err(e) => issues.push(error(
+            ExportIssueKind::OutputNotWritable,
+            None,
+            crate::i18n::tf("output_not_writable", &[&output_dir.display(), &e]),
+        )),
+        Ok(()) => {
+            if let Some(space) = crate::diagnostics::disk_space(output_dir) {
+                issues.extend(disk_issue(estimate.bytes, space.free_bytes));
+            }
+        }
+    }
+    issues
+}
+
+fn error(kind: ExportIssueKind, clip: Option<&Clip>, message: String) -> ExportIssue {
+    ExportIssue { severity: IssueSeverity::Error, kind, clip_id: clip.map(|c| c.id.clone()), message }
+}
+
+fn warning(kind: ExportIssueKind, clip: Option<&Clip>, message: String) -> ExportIssue {
+    ExportIssue { severity: IssueSeverity::Warning, kind, clip_id: clip.map(|c| c.id.clone()), message }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().to_string()
+}
+
+/// Video and audio files of clip number `n` that are gone
+fn file_issues(n: usize, clip: &Clip, include_audio: bool) -> Vec<ExportIssue> {
+    let mut issues = Vec::new();
+    if !clip.media.path.exists() {
+        issues.push(error(
+            ExportIssueKind::MissingClip,
+            Some(clip),
+            crate::i18n::tf("clip_missing_file", &[&n, &file_name(&clip.media.path)]),
+        ));
+    }
+    if include_audio {
+        for audio in clip.media.audio_paths.iter().map(Path::new).filter(|p| !p.exists()) {
+            issues.push(error(
+                ExportIssueKind::MissingAudio,
+                Some(clip),
+                crate::i18n::tf("clip_missing_audio", &[&n, &file_name(audio)]),
+            ));
+        }
+    }
+    issues
+}
+
+/// Clip number `n` recorded at `fps`, well under the export rate
+fn frame_rate_issue(n: usize, clip: &Clip, fps: f64, export_fps: f64) -> Option<ExportIssue> {
+    (fps + FPS_TOLERANCE < export_fps).then(|| {
+        warning(
+            ExportIssueKind::FrameRate,
+            Some(clip),
+            crate::i18n::tf("clip_frame_rate", &[&n, &format!("{:.0}", fps), &format!("{:.0}", export_fps)]),
+        )
+    })
+}
+
+/// Not enough room for an export of about `bytes`
+fn disk_issue(bytes: u64, free_bytes: u64) -> Option<ExportIssue> {
+    let mb = |b: u64| b / (1024 * 1024);
+    let message = crate::i18n::tf("disk_estimate", &[&mb(bytes), &mb(free_bytes)]);
+    if free_bytes < bytes {
+        Some(error(ExportIssueKind::DiskSpace, None, crate::i18n::tf("disk_full", &[&message])))
+    } else if (free_bytes as f64) < bytes as f64 * DISK_MARGIN {
+        Some(warning(ExportIssueKind::DiskSpace, None, crate::i18n::tf("disk_tight", &[&message])))
+    } else {
+        None
+    }
+}
+
+/// Whether a file can be created in `dir`, creating the folder if needed
+fn writable(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(".clipflow-write-test");
+    std::fs::OpenOptions::new().write(true).create(true).truncate(true).open(&probe)?;
+    std::fs::remove_file(&probe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ClipMedia, Region, test_clip};
+
+    fn clip(path: &str, audio_paths: Vec<String>) -> Clip {
+        let clip = test_clip("c1", 1000);
+        Clip {
+            media: ClipMedia {
+                path: path.into(),
+                region: Region { x: 0, y: 0, width: 1280, height: 720 },
+                has_audio: !audio_paths.is_empty(),
+                audio_paths,
+                ..clip.media
+            },
+            ..clip
+        }
+    }
+
+    #[test]
+    fn test_missing_files_are_errors() {
+        let c = clip("Z:/nope/clip.mp4", vec!["Z:/nope/clip_mic.wav".into()]);
+        let issues = file_issues(2, &c, true);
+        let kinds: Vec<_> = issues.iter().map(|i| (i.kind, i.severity)).collect();
+        assert_eq!(
+            kinds,
+            vec![(ExportIssueKind::MissingClip, IssueSeverity::Error), (ExportIssueKind::MissingAudio, IssueSeverity::Error)]
+        );
+        assert_eq!(issues[0].message, "Clip 2 : fichier introuvable (clip.mp4)");
+        assert_eq!(issues[0].clip_id.as_deref(), Some("c1"));
+        // Audio left out of the export
+        assert_eq!(file_issues(2, &c, false).len(), 1);
+    }
+
+    #[test]
+    fn test_low_frame_rate_sources_are_flagged() {
+        let c = clip("a.mp4", Vec::new());
+        assert!(frame_rate_issue(1, &c, 29.97, 30.0).is_none());
+        assert!(frame_rate_issue(1, &c, 60.0, 30.0).is_none());
+        let issue = frame_rate_issue(1, &c, 15.0, 30.0).unwrap();
+        assert_eq!(issue.severity, IssueSeverity::Warning);
+    }
+
+    #[test]
+    fn test_disk_space() {
+        const MB: u64 = 1024 * 1024;
+        assert_eq!(disk_issue(100 * MB, 50 * MB).map(|i| i.severity), Some(IssueSeverity::Error));
+        assert_eq!(disk_issue(100 * MB, 120 * MB).map(|i| i.severity), Some(IssueSeverity::Warning));
+        assert!(disk_issue(100 * MB, 1000 * MB).is_none());
+    }
+
+    #[test]
+    fn test_output_folder_writable() {
+        let dir = std::env::temp_dir().join(format!("clipflow-validate-{}", uuid::Uuid::new_v4()));
+        assert!(writable(&dir).is_ok());
+        assert!(!dir.join(".clipflow-write-test").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,186 @@
+use super::autotrim::audio_sources;
+use super::encoder::{escape_drawtext, extract_time, translate_ffmpeg_error, video_codec_args};
+use super::fonts;
+use crate::types::{Clip, ExportQuality, VisualizerStyle};
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Stdio;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+const WIDTH: u32 = 1280;
+const HEIGHT: u32 = 720;
+const FPS: u32 = 30;
+
+/// Audio of one clip: its input indices, with the mic ones flagged
+struct ClipAudio {
+    inputs: Vec<(usize, bool)>,
+    trim_start_s: f64,
+    length_s: f64,
+}
+
+/// Render the timeline's sound as a waveform or spectrum video, `title`
+/// drawn above it. The picture of the clips is not used, which suits voice
+/// notes and podcasts recorded with nothing to show.
+pub async fn export(
+    clips: &[Clip],
+    output_path: &Path,
+    app: &AppHandle,
+    style: VisualizerStyle,
+    title: Option<&str>,
+    quality: &ExportQuality,
+    system_volume: f32,
+    mic_volume: f32,
+) -> Result<()> {
+    let mut args: Vec<String> = Vec::new();
+    let mut audio = Vec::new();
+    let mut next_input = 0;
+    for clip in clips {
+        let mut inputs = Vec::new();
+        for path in audio_sources(clip).into_iter().filter(|p| p.exists()) {
+            inputs.push((next_input, is_mic(&path)));
+            args.extend(["-i".into(), path.to_string_lossy().to_string()]);
+            next_input += 1;
+        }
+        let end = if clip.media.trim_end_ms > 0 { clip.media.trim_end_ms } else { clip.media.duration_ms };
+        audio.push(ClipAudio {
+            inputs,
+            trim_start_s: clip.media.trim_start_ms as f64 / 1000.0,
+            length_s: end.saturating_sub(clip.media.trim_start_ms) as f64 / 1000.0,
+        });
+    }
+    if next_input == 0 {
+        anyhow::bail!(crate::i18n::t("visualizer_no_audio"));
+    }
+    let total_s: f64 = audio.iter().map(|a| a.length_s).sum();
+
+    let filter = format!(
+        "{};{}",
+        audio_graph(&audio, system_volume, mic_volume),
+        picture_graph(style, title),
+    );
+    args.extend(["-filter_complex".into(), filter, "-map".into(), "[v]".into(), "-map".into(), "[a]".into()]);
+    args.extend(video_codec_args(quality, None, FPS));
+    args.extend(["-c:a", "aac", "-b:a", "192k", "-movflags", "+faststart", "-y"].iter().map(|s| s.to_string()));
+    args.push(output_path.to_string_lossy().to_string());
+
+    let _ = app.emit("export-progress", 5u32);
+    let mut child = crate::ffmpeg_command()
+        .args(&args)
+        .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped())
+        .spawn().context("Failed to start FFmpeg export")?;
+    let mut stderr_log = String::new();
+    if let Some(stderr) = child.stderr.take() {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let Some(t) = extract_time(&line) {
+                let _ = app.emit("export-progress", (t / total_s * 100.0).min(100.0) as u32);
+            }
+            stderr_log.push_str(&line);
+            stderr_log.push('\n');
+        }
+    }
+    let status = child.wait().await.context("FFmpeg export failed")?;
+    if !status.success() {
+        tracing::error!("FFmpeg stderr:\n{}", stderr_log);
+        anyhow::bail!("{}", translate_ffmpeg_error(&stderr_log));
+    }
+    let _ = app.emit("export-progress", 100u32);
+    Ok(())
+}
+
+fn is_mic(path: &Path) -> bool {
+    path.file_stem().is_some_and(|s| s.to_string_lossy().ends_with("_mic"))
+}
+
+/// Each clip's trimmed tracks at their volume, mixed, then played in a row
+/// into `[a]`. Clips without sound keep their place as silence.
+fn audio_graph(clips: &[ClipAudio], system_volume: f32, mic_volume: f32) -> String {
+    let mut parts = Vec::new();
+    let mut joined = String::new();
+    for (ci, clip) in clips.iter().enumerate() {
+        let trim = format!("atrim=start={:.3}:duration={:.3},asetpts=PTS-STARTPTS", clip.trim_start_s, clip.length_s);
+        let format = "aresample=48000,aformat=channel_layouts=stereo";
+        match clip.inputs.as_slice() {
+            [] => parts.push(format!(
+                "anullsrc=channel_layout=stereo:sample_rate=48000,atrim=duration={:.3}[c{ci}]",
+                clip.length_s
+            )),
+            inputs => {
+                let mut labels = String::new();
+                for (j, &(input, mic)) in inputs.iter().enumerate() {
+                    let volume = if mic { mic_volume } else { system_volume };
+                    parts.push(format!("[{input}:a]{trim},volume={volume:.2},{format}[c{ci}_{j}]"));
+                    labels.push_str(&format!("[c{ci}_{j}]"));
+                }
+                if inputs.len() == 1 {
+                    parts.push(format!("{labels}anull[c{ci}]"));
+                } else {
+                    parts.push(format!("{labels}amix=inputs={}:duration=longest:normalize=0[c{ci}]", inputs.len()));
+                }
+            }
+        }
+        joined.push_str(&format!("[c{ci}]"));
+    }
+    parts.push(format!("{joined}concat=n={}:v=0:a=1,asplit[a][vis]", clips.len()));
+    parts.join(";")
+}
+
+/// The visualization of `[vis]` with the title on top, into `[v]`
+fn picture_graph(style: VisualizerStyle, title: Option<&str>) -> String {
+    let title_h = if title.is_some() { HEIGHT / 5 } else { 0 };
+    let size = format!("{WIDTH}x{}", HEIGHT - title_h);
+    let visual = match style {
+        VisualizerStyle::Waves => format!("showwaves=s={size}:mode=cline:rate={FPS}:colors=0x3b82f6"),
+        VisualizerStyle::Spectrum => format!("showspectrum=s={size}:mode=combined:color=intensity:slide=scroll:fps={FPS}"),
+    };
+    let mut graph = format!(
+        "[vis]{visual},format=yuv420p[wave];color=c=0x18181b:s={WIDTH}x{HEIGHT}:r={FPS}[bg];\
+         [bg][wave]overlay=0:{title_h}:shortest=1"
+    );
+    if let Some(title) = title {
+        graph.push_str(&format!(
+            ",drawtext={}text='{}':fontsize=48:fontcolor=white:x=(w-tw)/2:y=({title_h}-th)/2",
+            fonts::fontfile_arg(None),
+            escape_drawtext(title)
+        ));
+    }
+    graph.push_str("[v]");
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_graph_mixes_and_keeps_silent_clips() {
+        let clips = [
+            ClipAudio { inputs: vec![(0, false), (1, true)], trim_start_s: 1.0, length_s: 4.0 },
+            ClipAudio { inputs: Vec::new(), trim_start_s: 0.0, length_s: 2.0 },
+        ];
+        let graph = audio_graph(&clips, 1.0, 0.5);
+        assert!(graph.contains("[1:a]atrim=start=1.000:duration=4.000,asetpts=PTS-STARTPTS,volume=0.50,"));
+        assert!(graph.contains("[c0_0][c0_1]amix=inputs=2:duration=longest:normalize=0[c0]"));
+        assert!(graph.contains("anullsrc=channel_layout=stereo:sample_rate=48000,atrim=duration=2.000[c1]"));
+        assert!(graph.ends_with("[c0][c1]concat=n=2:v=0:a=1,asplit[a][vis]"));
+    }
+
+    #[test]
+    fn test_picture_graph_leaves_room_for_the_title() {
+        let graph = picture_graph(VisualizerStyle::Waves, None);
+        assert!(graph.starts_with("[vis]showwaves=s=1280x720:"));
+        assert!(graph.ends_with("overlay=0:0:shortest=1[v]"));
+
+        let graph = picture_graph(VisualizerStyle::Spectrum, Some("Épisode 3"));
+        assert!(graph.starts_with("[vis]showspectrum=s=1280x576:"));
+        assert!(graph.contains("overlay=0:144:shortest=1,drawtext="));
+        assert!(graph.contains("text='Épisode 3'"));
+    }
+
+    #[test]
+    fn test_mic_tracks_by_name() {
+        assert!(is_mic(Path::new("t/abc_mic.wav")));
+        assert!(!is_mic(Path::new("t/abc_system.wav")));
+    }
+}
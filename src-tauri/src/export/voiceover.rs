@@ -0,0 +1,177 @@
+use super::encoder::translate_ffmpeg_error;
+use crate::types::{ExportFormat, Voiceover};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// A narration as it lands in an export: `delay_ms` into the output,
+/// playing its file from `skip_ms`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placed {
+    pub path: PathBuf,
+    pub delay_ms: u64,
+    pub skip_ms: u64,
+}
+
+/// The narrations heard in an export of the timeline from `start_ms` to
+/// `end_ms` (the end when None). One cut by the range start plays its rest.
+pub fn placed(voiceovers: &[Voiceover], start_ms: u64, end_ms: Option<u64>) -> Vec<Placed> {
+    voiceovers
+        .iter()
+        .filter(|v| v.start_ms + v.duration_ms > start_ms && !end_ms.is_some_and(|end| v.start_ms >= end))
+        .map(|v| Placed {
+            path: v.path.clone(),
+            delay_ms: v.start_ms.saturating_sub(start_ms),
+            skip_ms: start_ms.saturating_sub(v.start_ms),
+        })
+        .collect()
+}
+
+/// Filter graph mixing the narrations (inputs 1..) into `[aout]`, over the
+/// export's own audio (input 0) when it has some. Kept at full level: the
+/// timeline mix is already done, `normalize` would halve it.
+fn filter_graph(placed: &[Placed], has_audio: bool, volume: f32) -> String {
+    let mut parts = Vec::new();
+    let mut labels = String::new();
+    if has_audio {
+        labels.push_str("[0:a]");
+    }
+    for (i, p) in placed.iter().enumerate() {
+        let skip = if p.skip_ms > 0 {
+            format!("atrim=start={:.3},asetpts=PTS-STARTPTS,", p.skip_ms as f64 / 1000.0)
+        } else {
+            String::new()
+        };
+        parts.push(format!("[{}:a]{skip}adelay={}:all=1,volume={volume:.2}[vo{i}]", i + 1, p.delay_ms));
+        labels.push_str(&format!("[vo{i}]"));
+    }
+    let inputs = placed.len() + usize::from(has_audio);
+    // Without audio of its own the export ends with its video, `-shortest`
+    // cutting the padded narration
+    let (duration, pad) = if has_audio { ("first", "") } else { ("longest", ",apad") };
+    parts.push(format!("{labels}amix=inputs={inputs}:duration={duration}:normalize=0{pad}[aout]"));
+    parts.join(";")
+}
+
+/// Remux of `video` with the narrations mixed into its audio, to `output`.
+/// The video stream is copied.
+fn mix_args(video: &Path, placed: &[Placed], has_audio: bool, format: ExportFormat, volume: f32, output: &Path) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-i".into(), video.to_string_lossy().to_string()];
+    for p in placed {
+        args.extend(["-i".to_string(), p.path.to_string_lossy().to_string()]);
+    }
+    args.extend([
+        "-filter_complex".to_string(),
+        filter_graph(placed, has_audio, volume),
+        "-map".into(),
+        "0:v".into(),
+        "-map".into(),
+        "[aout]".into(),
+        "-c:v".into(),
+        "copy".into(),
+    ]);
+    match format {
+        ExportFormat::Webm => args.extend(["-c:a", "libopus", "-b:a", "128k"].iter().map(|s| s.to_string())),
+        _ => args.extend(["-c:a", "aac", "-b:a", "128k", "-movflags", "+faststart"].iter().map(|s| s.to_string())),
+    }
+    if !has_audio {
+        args.push("-shortest".into());
+    }
+    args.push("-y".into());
+    args.push(output.to_string_lossy().to_string());
+    args
+}
+
+/// Whether `path` has an audio stream
+async fn has_audio(path: &Path) -> Result<bool> {
+    let output = crate::ffprobe_command()
+        .args([
+            "-v", "error",
+            "-select_streams", "a:0",
+            "-show_entries", "stream=index",
+            "-of", "csv=p=0",
+            &path.to_string_lossy(),
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .await
+        .context("Failed to run ffprobe")?;
+    Ok(!String::from_utf8_lossy(&output.stdout).trim().is_empty())
+}
+
+/// Mix the narrations into the finished MP4 or WebM export at `path`
+pub async fn mix_into(path: &Path, format: ExportFormat, placed: &[Placed], volume: f32) -> Result<()> {
+    if placed.is_empty() {
+        return Ok(());
+    }
+    if let Some(missing) = placed.iter().find(|p| !p.path.exists()) {
+        anyhow::bail!(crate::i18n::tf("voiceover_not_found", &[&missing.path.display()]));
+    }
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mixed = path.with_file_name(format!("{}_voiceover.{}", stem, format.extension()));
+    let args = mix_args(path, placed, has_audio(path).await?, format, volume, &mixed);
+    tracing::info!("Mixing {} voiceover(s) into {:?}", placed.len(), path);
+
+    let output = crate::ffmpeg_command()
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .context("Failed to run voiceover mix")?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&mixed);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        tracing::error!("FFmpeg stderr:\n{}", stderr);
+        anyhow::bail!("{}", translate_ffmpeg_error(&stderr));
+    }
+    std::fs::rename(&mixed, path).with_context(|| crate::i18n::t("voiceover_replace_failed"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voiceover(start_ms: u64, duration_ms: u64) -> Voiceover {
+        Voiceover { id: format!("v{start_ms}"), path: format!("v{start_ms}.wav").into(), start_ms, duration_ms }
+    }
+
+    #[test]
+    fn test_placed_in_a_range() {
+        let voiceovers = [voiceover(0, 2000), voiceover(5000, 3000), voiceover(12_000, 1000)];
+        let all = placed(&voiceovers, 0, None);
+        assert_eq!(all.iter().map(|p| p.delay_ms).collect::<Vec<_>>(), vec![0, 5000, 12_000]);
+
+        // The second one is cut by the range start, the third starts after its end
+        let range = placed(&voiceovers, 6000, Some(12_000));
+        assert_eq!(range, vec![Placed { path: "v5000.wav".into(), delay_ms: 0, skip_ms: 1000 }]);
+    }
+
+    #[test]
+    fn test_filter_graph_over_the_export_audio() {
+        let p = [
+            Placed { path: "a.wav".into(), delay_ms: 1500, skip_ms: 0 },
+            Placed { path: "b.wav".into(), delay_ms: 0, skip_ms: 250 },
+        ];
+        assert_eq!(
+            filter_graph(&p, true, 1.0),
+            "[1:a]adelay=1500:all=1,volume=1.00[vo0];\
+             [2:a]atrim=start=0.250,asetpts=PTS-STARTPTS,adelay=0:all=1,volume=1.00[vo1];\
+             [0:a][vo0][vo1]amix=inputs=3:duration=first:normalize=0[aout]"
+        );
+    }
+
+    #[test]
+    fn test_silent_export_gets_the_narration_alone() {
+        let p = [Placed { path: "a.wav".into(), delay_ms: 0, skip_ms: 0 }];
+        let graph = filter_graph(&p, false, 0.8);
+        assert!(graph.ends_with("[vo0]amix=inputs=1:duration=longest:normalize=0,apad[aout]"));
+        let args = mix_args(Path::new("out.webm"), &p, false, ExportFormat::Webm, 0.8, Path::new("mixed.webm"));
+        assert!(args.windows(2).any(|w| w[0] == "-c:a" && w[1] == "libopus"));
+        assert!(args.contains(&"-shortest".to_string()));
+        assert!(args.windows(2).any(|w| w[0] == "-c:v" && w[1] == "copy"));
+    }
+}
@@ -0,0 +1,140 @@
+use super::encoder::{output_scale_filter, HDR_TONEMAP};
+use crate::types::{Annotation, Clip, OutputSettings, WatermarkTone};
+use std::collections::HashMap;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+
+/// Corner the watermark is drawn over, as fractions of the frame
+const CORNER_W: f64 = 0.25;
+const CORNER_H: f64 = 0.12;
+/// Corners brighter than this (0-255) get the dark watermark
+const BRIGHT_LUMA: f64 = 140.0;
+/// Frames sampled at most, spread over the clips
+const MAX_SAMPLES: usize = 12;
+
+/// Watermark tone that stands out from the bottom right corner of the
+/// timeline, from its average luma halfway through the clips. The frames
+/// are sampled as exported: padded to the canvas, under the clip's
+/// annotations and resized to `output`.
+pub async fn pick_tone(clips: &[Clip], clip_annotations: &HashMap<String, Vec<Annotation>>, output: &OutputSettings) -> WatermarkTone {
+    // Every clip is padded to the largest one, as in the encoder
+    let canvas = (
+        (clips.iter().map(|c| c.media.region.width).max().unwrap_or(1920) / 2) * 2,
+        (clips.iter().map(|c| c.media.region.height).max().unwrap_or(1080) / 2) * 2,
+    );
+    let step = clips.len().div_ceil(MAX_SAMPLES).max(1);
+    let mut lumas = Vec::new();
+    for clip in clips.iter().step_by(step) {
+        let annotations = clip_annotations.get(&clip.id).map(Vec::as_slice).unwrap_or_default();
+        if let Some(luma) = corner_luma(clip, midpoint_ms(clip), annotations, canvas, output).await {
+            lumas.push(luma);
+        }
+    }
+    let tone = tone_for(&lumas);
+    tracing::info!("Watermark tone {:?} from {} sample(s)", tone, lumas.len());
+    tone
+}
+
+/// Middle of the kept part, in source time
+fn midpoint_ms(clip: &Clip) -> u64 {
+    let end = if clip.media.trim_end_ms > 0 { clip.media.trim_end_ms } else { clip.media.duration_ms };
+    clip.media.trim_start_ms + end.saturating_sub(clip.media.trim_start_ms) / 2
+}
+
+/// Average luma of the exported corner at `at_ms`, scaled down to a single
+/// gray pixel. The annotations on screen then reach FFmpeg as a PNG layer
+/// on its stdin.
+async fn corner_luma(
+    clip: &Clip,
+    at_ms: u64,
+    annotations: &[Annotation],
+    (width, height): (u32, u32),
+    output: &OutputSettings,
+) -> Option<f64> {
+    // Annotation times are relative to the trimmed clip
+    let at_s = at_ms.saturating_sub(clip.media.trim_start_ms) as f64 / 1000.0;
+    let layer = if annotations.is_empty() {
+        None
+    } else {
+        super::overlay::annotations_at(annotations, at_s, width, height)
+            .ok()
+            .and_then(|pixmap| pixmap.encode_png().ok())
+    };
+    let filter = corner_filter(clip.media.hdr && output.tonemap_hdr, (width, height), layer.is_some(), output);
+
+    let mut command = crate::ffmpeg_command();
+    command.args(["-ss", &format!("{:.3}", at_ms as f64 / 1000.0), "-i", &clip.media.path.to_string_lossy()]);
+    if layer.is_some() {
+        command.args(["-f", "png_pipe", "-i", "-"]);
+    }
+    let mut child = command
+        .args(["-frames:v", "1", "-filter_complex", &filter, "-f", "rawvideo", "-"])
+        .stdin(if layer.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped()).stderr(Stdio::null())
+        .spawn().ok()?;
+    if let (Some(png), Some(mut stdin)) = (layer, child.stdin.take()) {
+        stdin.write_all(&png).await.ok()?;
+    }
+    let result = child.wait_with_output().await.ok()?;
+    if !result.status.success() {
+        return None;
+    }
+    result.stdout.first().map(|&luma| luma as f64)
+}
+
+/// The frame as the export draws it (HDR correction, padding to the canvas,
+/// annotation layer on input 1, final resize), then its corner
+fn corner_filter(tonemap: bool, (width, height): (u32, u32), with_layer: bool, output: &OutputSettings) -> String {
+    let mut filter = String::from("[0:v]");
+    if tonemap {
+        filter.push_str(HDR_TONEMAP);
+        filter.push(',');
+    }
+    filter.push_str(&format!(
+        "scale={width}:{height}:force_original_aspect_ratio=decrease,pad={width}:{height}:(ow-iw)/2:(oh-ih)/2,setsar=1"
+    ));
+    if with_layer {
+        filter.push_str("[frame];[frame][1:v]overlay=0:0");
+    }
+    if let Some(scale) = output_scale_filter(output) {
+        filter.push(',');
+        filter.push_str(&scale);
+    }
+    filter.push_str(&format!(",crop=iw*{CORNER_W}:ih*{CORNER_H}:iw-ow:ih-oh,scale=1:1:flags=area,format=gray"));
+    filter
+}
+
+/// Light unless the corners are bright on average
+fn tone_for(lumas: &[f64]) -> WatermarkTone {
+    if lumas.is_empty() {
+        return WatermarkTone::Light;
+    }
+    let average = lumas.iter().sum::<f64>() / lumas.len() as f64;
+    if average > BRIGHT_LUMA { WatermarkTone::Dark } else { WatermarkTone::Light }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_follows_the_corner_brightness() {
+        // Dark terminal, white document, a mix leaning dark
+        assert_eq!(tone_for(&[20.0, 35.0]), WatermarkTone::Light);
+        assert_eq!(tone_for(&[240.0, 250.0]), WatermarkTone::Dark);
+        assert_eq!(tone_for(&[250.0, 20.0, 30.0]), WatermarkTone::Light);
+        assert_eq!(tone_for(&[]), WatermarkTone::Light);
+    }
+
+    #[test]
+    fn test_corner_sampled_after_overlays_and_scale() {
+        let output = OutputSettings { width: Some(1280), ..Default::default() };
+        let filter = corner_filter(false, (1920, 1080), true, &output);
+        assert_eq!(
+            filter,
+            "[0:v]scale=1920:1080:force_original_aspect_ratio=decrease,pad=1920:1080:(ow-iw)/2:(oh-ih)/2,setsar=1\
+             [frame];[frame][1:v]overlay=0:0,scale=1280:-2,\
+             crop=iw*0.25:ih*0.12:iw-ow:ih-oh,scale=1:1:flags=area,format=gray"
+        );
+    }
+}
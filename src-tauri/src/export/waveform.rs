@@ -0,0 +1,69 @@
+use super::autotrim::audio_sources;
+use super::encoder::translate_ffmpeg_error;
+use crate::types::Clip;
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::process::Stdio;
+
+/// Where the waveform of `clip` at this size is cached, next to its video
+/// like the thumbnails
+pub fn cache_path(clip: &Clip, width: u32, height: u32) -> PathBuf {
+    clip.media.path.with_extension(format!("wave_{width}x{height}.png"))
+}
+
+/// Waveform PNG of the whole clip, every track mixed, or None when it has
+/// no sound. Rendered once per size.
+pub async fn render(clip: &Clip, width: u32, height: u32) -> Result<Option<PathBuf>> {
+    let output = cache_path(clip, width, height);
+    if output.exists() {
+        return Ok(Some(output));
+    }
+    let sources: Vec<PathBuf> = audio_sources(clip).into_iter().filter(|p| p.exists()).collect();
+    if sources.is_empty() {
+        return Ok(None);
+    }
+
+    let mut args: Vec<String> = Vec::new();
+    for source in &sources {
+        args.extend(["-i".into(), source.to_string_lossy().to_string()]);
+    }
+    args.extend([
+        "-filter_complex".into(), graph(sources.len(), width, height),
+        "-frames:v".into(), "1".into(),
+    ]);
+    args.extend(crate::ffmpeg::paths::image_output(&output));
+    let result = crate::ffmpeg_command()
+        .args(&args)
+        .stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped())
+        .output().await.context("Failed to run FFmpeg")?;
+    if !result.status.success() {
+        let _ = std::fs::remove_file(&output);
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        anyhow::bail!(crate::i18n::tf("waveform_failed", &[&translate_ffmpeg_error(&stderr)]));
+    }
+    Ok(Some(output))
+}
+
+/// Mix of the `inputs` tracks drawn as one waveform
+fn graph(inputs: usize, width: u32, height: u32) -> String {
+    let wave = format!("showwavespic=s={width}x{height}:colors=0x3b82f6:scale=sqrt");
+    if inputs == 1 {
+        return format!("[0:a]{wave}");
+    }
+    let labels: String = (0..inputs).map(|i| format!("[{i}:a]")).collect();
+    format!("{labels}amix=inputs={inputs}:duration=longest:normalize=0,{wave}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_mixes_the_tracks() {
+        assert_eq!(graph(1, 400, 40), "[0:a]showwavespic=s=400x40:colors=0x3b82f6:scale=sqrt");
+        assert_eq!(
+            graph(2, 400, 40),
+            "[0:a][1:a]amix=inputs=2:duration=longest:normalize=0,showwavespic=s=400x40:colors=0x3b82f6:scale=sqrt"
+        );
+    }
+}
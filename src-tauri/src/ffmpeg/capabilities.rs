@@ -0,0 +1,262 @@
+use crate::state::AppState;
+use crate::types::{ExportEncoder, ExportFormat, FfmpegCapabilities, Transition, TransitionType};
+use std::process::Stdio;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// What a missing filter / encoder breaks, as i18n keys for user-facing errors
+const FEATURES: &[(&str, &str)] = &[
+    ("xfade", "feature_transitions"),
+    ("drawtext", "feature_text"),
+    ("palettegen", "feature_gif"),
+    ("paletteuse", "feature_gif"),
+    ("libx264", "feature_mp4"),
+    ("libx264rgb", "feature_lossless"),
+    ("utvideo", "feature_utvideo"),
+    ("ddagrab", "feature_ddagrab"),
+    ("h264_nvenc", "feature_nvenc"),
+    ("hwupload_cuda", "feature_nvenc"),
+    ("scale_cuda", "feature_nvenc"),
+    ("hevc_nvenc", "feature_nvenc"),
+    ("h264_qsv", "feature_qsv"),
+    ("h264_amf", "feature_amf"),
+    ("hwupload", "feature_qsv"),
+    ("scale_qsv", "feature_qsv"),
+    ("zscale", "feature_hdr"),
+    ("tonemap", "feature_hdr"),
+];
+
+/// Probe the installed FFmpeg in the background and cache the result in state
+pub fn start(app: AppHandle) {
+    if !crate::ffmpeg_bin().exists() {
+        // ensure_ffmpeg refreshes the cache once the download has finished
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        refresh(&app).await;
+    });
+}
+
+/// Re-run the probe and store it, returns the fresh capabilities
+pub async fn refresh(app: &AppHandle) -> FfmpegCapabilities {
+    let caps = probe().await;
+    tracing::info!(
+        "FFmpeg {}: {} filters, {} encoders",
+        caps.version.as_deref().unwrap_or("?"),
+        caps.filters.len(),
+        caps.encoders.len()
+    );
+    let state = app.state::<Mutex<AppState>>();
+    if let Ok(mut s) = state.lock() {
+        s.ffmpeg_capabilities = Some(caps.clone());
+    }
+    let gpu_encoders = crate::capture::hwaccel::detect(&caps).await;
+    if let Ok(mut s) = state.lock() {
+        s.gpu_encoders = Some(gpu_encoders);
+    }
+    caps
+}
+
+/// `ffmpeg -version`, `-filters` and `-encoders` in one go
+pub async fn probe() -> FfmpegCapabilities {
+    let version = run(&["-version"]).await.and_then(|out| parse_version_line(&out));
+    let filters = run(&["-hide_banner", "-filters"]).await.map(|out| parse_filter_names(&out)).unwrap_or_default();
+    let encoders = run(&["-hide_banner", "-encoders"]).await.map(|out| parse_encoder_names(&out)).unwrap_or_default();
+    FfmpegCapabilities { version, filters, encoders }
+}
+
+async fn run(args: &[&str]) -> Option<String> {
+    match crate::ffmpeg_command()
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .await
+    {
+        Ok(output) => Some(String::from_utf8_lossy(&output.stdout).to_string()),
+        Err(e) => {
+            tracing::warn!("ffmpeg {} failed: {}", args.join(" "), e);
+            None
+        }
+    }
+}
+
+/// Filters and encoders needed to render this timeline in `format`
+pub fn export_requirements(
+    format: ExportFormat,
+    clip_count: usize,
+    transitions: &[Transition],
+    uses_text: bool,
+) -> Vec<&'static str> {
+    let mut needed = Vec::new();
+    // A missing transition entry defaults to a fade
+    let has_xfade = (0..clip_count.saturating_sub(1)).any(|i| {
+        transitions.get(i).map(|t| t.transition_type != TransitionType::Cut).unwrap_or(true)
+    });
+    if has_xfade {
+        needed.push("xfade");
+    }
+    if uses_text {
+        needed.push("drawtext");
+    }
+    match format {
+        ExportFormat::Mp4 => needed.push("libx264"),
+        ExportFormat::Gif => needed.extend(["palettegen", "paletteuse"]),
+        // Rendered to an H.264 mezzanine first
+        ExportFormat::Webm => needed.extend(["libx264", "libvpx-vp9", "libopus"]),
+    }
+    needed
+}
+
+/// Hardware encoder and GPU filters of an export encoder, nothing for x264
+pub fn encoder_requirements(encoder: ExportEncoder) -> Vec<&'static str> {
+    if encoder == ExportEncoder::Software {
+        return Vec::new();
+    }
+    let mut needed = vec![encoder.encoder_name()];
+    needed.extend(encoder.gpu_filters());
+    needed
+}
+
+/// Fail with a readable message when the build lacks one of `needed`.
+///
+/// An empty probe (FFmpeg missing or unreadable output) gates nothing: the
+/// FFmpeg run itself reports the real error in that case.
+pub fn ensure_supported(caps: &FfmpegCapabilities, needed: &[&str]) -> Result<(), String> {
+    if caps.filters.is_empty() && caps.encoders.is_empty() {
+        return Ok(());
+    }
+    let missing: Vec<&str> = needed
+        .iter()
+        .copied()
+        .filter(|name| !caps.has_filter(name) && !caps.has_encoder(name))
+        .collect();
+    let Some(first) = missing.first() else {
+        return Ok(());
+    };
+    let feature = FEATURES
+        .iter()
+        .find(|(name, _)| name == first)
+        .map(|(_, feature)| *feature)
+        .unwrap_or("feature_unknown");
+    Err(crate::i18n::tf("ffmpeg_unsupported", &[&crate::i18n::t(feature), &missing.join(", ")]))
+}
+
+/// "ffmpeg version 7.1-full_build-www.gyan.dev Copyright ..." -> "7.1-full_build-www.gyan.dev"
+pub fn parse_version_line(output: &str) -> Option<String> {
+    let first = output.lines().next()?;
+    let mut words = first.split_whitespace();
+    words.find(|w| *w == "version")?;
+    words.next().map(|v| v.to_string())
+}
+
+/// Encoder names from `ffmpeg -encoders`, skipping the legend above the `------` line
+pub fn parse_encoder_names(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|l| !l.trim_start().starts_with("------"))
+        .skip(1)
+        .filter_map(|l| {
+            let mut parts = l.split_whitespace();
+            let flags = parts.next()?;
+            if flags.len() != 6 {
+                return None;
+            }
+            parts.next().map(|n| n.to_string())
+        })
+        .collect()
+}
+
+/// Filter names from `ffmpeg -filters` (` TSC xfade  VV->V  Cross fade ...`)
+pub fn parse_filter_names(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|l| {
+            let mut parts = l.split_whitespace();
+            let flags = parts.next()?;
+            let name = parts.next()?;
+            let io = parts.next()?;
+            // Legend lines look like "T.. = Timeline support"
+            (flags.len() == 3 && io.contains("->")).then(|| name.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps(filters: &[&str], encoders: &[&str]) -> FfmpegCapabilities {
+        FfmpegCapabilities {
+            version: None,
+            filters: filters.iter().map(|s| s.to_string()).collect(),
+            encoders: encoders.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn transition(t: TransitionType) -> Transition {
+        Transition { transition_type: t, duration_s: 0.5 }
+    }
+
+    #[test]
+    fn test_parse_version_line() {
+        let out = "ffmpeg version 7.1-full_build-www.gyan.dev Copyright (c) 2000-2024\nbuilt with gcc";
+        assert_eq!(parse_version_line(out).as_deref(), Some("7.1-full_build-www.gyan.dev"));
+        assert_eq!(parse_version_line(""), None);
+        assert_eq!(parse_version_line("garbage"), None);
+    }
+
+    #[test]
+    fn test_parse_encoder_names() {
+        let out = "Encoders:\n V..... = Video\n A..... = Audio\n ------\n V....D libx264              libx264 H.264\n V....D h264_nvenc           NVIDIA NVENC H.264 encoder\n A....D aac                  AAC\n";
+        assert_eq!(parse_encoder_names(out), vec!["libx264", "h264_nvenc", "aac"]);
+        assert!(parse_encoder_names("no table here").is_empty());
+    }
+
+    #[test]
+    fn test_parse_filter_names() {
+        let out = "Filters:\n  T.. = Timeline support\n  .S. = Slice threading\n  A = Audio input/output\n ... acopy             A->A       Copy the input audio unchanged.\n TSC xfade             VV->V      Cross fade one video with another video.\n T.C drawtext          V->V       Draw text on top of video frames.\n";
+        assert_eq!(parse_filter_names(out), vec!["acopy", "xfade", "drawtext"]);
+    }
+
+    #[test]
+    fn test_requirements_cut_only_skips_xfade() {
+        let needed = export_requirements(ExportFormat::Mp4, 2, &[transition(TransitionType::Cut)], false);
+        assert_eq!(needed, vec!["libx264"]);
+    }
+
+    #[test]
+    fn test_requirements_missing_transition_defaults_to_fade() {
+        let needed = export_requirements(ExportFormat::Gif, 2, &[], true);
+        assert_eq!(needed, vec!["xfade", "drawtext", "palettegen", "paletteuse"]);
+    }
+
+    #[test]
+    fn test_requirements_single_clip_no_xfade() {
+        let needed = export_requirements(ExportFormat::Mp4, 1, &[transition(TransitionType::Fade)], false);
+        assert!(!needed.contains(&"xfade"));
+    }
+
+    #[test]
+    fn test_encoder_requirements() {
+        assert!(encoder_requirements(ExportEncoder::Software).is_empty());
+        let needed = encoder_requirements(ExportEncoder::Nvenc);
+        assert_eq!(needed, vec!["h264_nvenc", "hwupload_cuda", "scale_cuda"]);
+        let err = ensure_supported(&caps(&["drawtext"], &["libx264"]), &needed).unwrap_err();
+        assert!(err.contains("NVIDIA"));
+    }
+
+    #[test]
+    fn test_ensure_supported_reports_feature() {
+        let c = caps(&["drawtext"], &["libx264"]);
+        let err = ensure_supported(&c, &["xfade", "libx264"]).unwrap_err();
+        assert!(err.contains("transitions"));
+        assert!(err.contains("xfade"));
+        assert!(ensure_supported(&c, &["drawtext", "libx264"]).is_ok());
+    }
+
+    #[test]
+    fn test_ensure_supported_empty_probe_allows_all() {
+        assert!(ensure_supported(&caps(&[], &[]), &["xfade"]).is_ok());
+    }
+}
@@ -0,0 +1,256 @@
+use crate::types::{FfmpegDownloadProgress, FfmpegDownloadStage};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter};
+
+const MAX_ATTEMPTS: u32 = 4;
+const CHUNK_SIZE: usize = 64 * 1024;
+/// Emit progress at most every 512 KiB to keep the event channel quiet
+const PROGRESS_STEP: u64 = 512 * 1024;
+
+/// Set while a download is running so a second call can't race on the `.part` file
+static IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+struct InProgressGuard;
+
+impl Drop for InProgressGuard {
+    fn drop(&mut self) {
+        IN_PROGRESS.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Download, verify and unpack FFmpeg into `dest`.
+///
+/// The archive is streamed to a `.part` file that survives failed attempts,
+/// so retries resume with a Range request instead of starting over. Nothing
+/// is unpacked until the SHA-256 matches the manifest published next to it.
+pub fn install(app: &AppHandle, dest: &Path) -> Result<(), String> {
+    if IN_PROGRESS.swap(true, Ordering::SeqCst) {
+        return Err(crate::i18n::t("ffmpeg_download_in_progress"));
+    }
+    let _guard = InProgressGuard;
+
+    let url = download_url()?;
+    let file_name = url.rsplit('/').next().unwrap_or("ffmpeg.zip");
+    let archive = dest.join(file_name);
+    let partial = dest.join(format!("{}.part", file_name));
+
+    let mut last_error = String::new();
+    for attempt in 1..=MAX_ATTEMPTS {
+        if attempt > 1 {
+            emit(app, FfmpegDownloadStage::Retrying, 0, 0, attempt);
+            std::thread::sleep(std::time::Duration::from_secs(2 * (attempt - 1) as u64));
+        }
+        if let Err(e) = try_download(app, url, &partial, attempt) {
+            tracing::warn!("FFmpeg download attempt {} failed: {}", attempt, e);
+            last_error = e;
+            continue;
+        }
+
+        emit(app, FfmpegDownloadStage::Verifying, 0, 0, attempt);
+        let expected = match fetch_expected_hash(url) {
+            Ok(h) => h,
+            Err(e) => {
+                tracing::warn!("FFmpeg checksum manifest unavailable: {}", e);
+                last_error = e;
+                continue;
+            }
+        };
+        let actual = sha256_file(&partial)?;
+        if actual != expected {
+            // Corrupt or tampered archive: resuming would only extend it
+            tracing::warn!("FFmpeg checksum mismatch (expected {}, got {})", expected, actual);
+            let _ = std::fs::remove_file(&partial);
+            last_error = crate::i18n::t("ffmpeg_checksum_invalid");
+            continue;
+        }
+
+        emit(app, FfmpegDownloadStage::Extracting, 0, 0, attempt);
+        std::fs::rename(&partial, &archive).map_err(|e| e.to_string())?;
+        let unpacked = unpack(&archive, dest).map_err(|e| format!("Failed to unpack FFmpeg: {}", e));
+        let _ = std::fs::remove_file(&archive);
+        return unpacked;
+    }
+    Err(crate::i18n::tf("ffmpeg_download_retries", &[&MAX_ATTEMPTS, &last_error]))
+}
+
+/// Release build of the gyan.dev essentials, the one the `.sha256` manifest covers
+fn download_url() -> Result<&'static str, String> {
+    if cfg!(windows) {
+        Ok("https://www.gyan.dev/ffmpeg/builds/ffmpeg-release-essentials.zip")
+    } else {
+        Err("No FFmpeg build to download for this platform".into())
+    }
+}
+
+/// Copy `ffmpeg` and `ffprobe` out of the archive's `bin/` folder into `dest`
+fn unpack(archive: &Path, dest: &Path) -> Result<(), String> {
+    let file = std::fs::File::open(archive).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let wanted = [crate::ffmpeg_name(), crate::ffprobe_name()];
+    let mut found = Vec::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| e.to_string())?;
+        let Some(name) = entry.enclosed_name().and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string())) else {
+            continue;
+        };
+        if entry.is_dir() || !wanted.contains(&name.as_str()) {
+            continue;
+        }
+        let mut out = std::fs::File::create(dest.join(&name)).map_err(|e| e.to_string())?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+        found.push(name);
+    }
+    match wanted.iter().find(|w| !found.iter().any(|f| f == *w)) {
+        Some(missing) => Err(format!("{} missing from the archive", missing)),
+        None => Ok(()),
+    }
+}
+
+fn try_download(app: &AppHandle, url: &str, partial: &Path, attempt: u32) -> Result<(), String> {
+    let existing = std::fs::metadata(partial).map(|m| m.len()).unwrap_or(0);
+    let mut request = ureq::get(url);
+    if existing > 0 {
+        request = request.set("Range", &format!("bytes={}-", existing));
+    }
+
+    let response = match request.call() {
+        Ok(r) => r,
+        // The .part file already holds the whole archive
+        Err(ureq::Error::Status(416, _)) if existing > 0 => return Ok(()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    // A plain 200 means the server ignored the Range header: start over
+    let resumed = response.status() == 206;
+    let offset = if resumed { existing } else { 0 };
+    let total = response
+        .header("Content-Length")
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|len| len + offset)
+        .unwrap_or(0);
+    if resumed {
+        tracing::info!("Resuming FFmpeg download at {} bytes", offset);
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(partial)
+        .map_err(|e| e.to_string())?;
+
+    let mut reader = response.into_reader();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut downloaded = offset;
+    let mut last_emit = 0u64;
+    emit(app, FfmpegDownloadStage::Downloading, downloaded, total, attempt);
+    loop {
+        let n = reader.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buf[..n]).map_err(|e| e.to_string())?;
+        downloaded += n as u64;
+        if downloaded - last_emit >= PROGRESS_STEP {
+            last_emit = downloaded;
+            emit(app, FfmpegDownloadStage::Downloading, downloaded, total, attempt);
+        }
+    }
+    file.flush().map_err(|e| e.to_string())?;
+    emit(app, FfmpegDownloadStage::Downloading, downloaded, total, attempt);
+
+    if total > 0 && downloaded < total {
+        return Err(format!("Connection closed at {} of {} bytes", downloaded, total));
+    }
+    Ok(())
+}
+
+/// The build server publishes `<archive>.sha256` next to each archive
+fn fetch_expected_hash(url: &str) -> Result<String, String> {
+    let body = ureq::get(&format!("{}.sha256", url))
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+    parse_sha256_manifest(&body).ok_or_else(|| crate::i18n::t("ffmpeg_manifest_invalid"))
+}
+
+/// Accepts a bare hash or the `sha256sum` format (`<hash>  <file>`)
+fn parse_sha256_manifest(body: &str) -> Option<String> {
+    let hash = body.split_whitespace().next()?.to_ascii_lowercase();
+    (hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())).then_some(hash)
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(crate::upload::hex(&hasher.finalize()))
+}
+
+fn emit(app: &AppHandle, stage: FfmpegDownloadStage, downloaded_bytes: u64, total_bytes: u64, attempt: u32) {
+    let _ = app.emit(
+        "ffmpeg-download-progress",
+        FfmpegDownloadProgress { stage, downloaded_bytes, total_bytes, attempt },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    #[test]
+    fn test_parse_sha256_manifest() {
+        assert_eq!(parse_sha256_manifest(EMPTY_SHA256).as_deref(), Some(EMPTY_SHA256));
+        let sum = format!("{}  ffmpeg-release-essentials.zip\n", EMPTY_SHA256.to_uppercase());
+        assert_eq!(parse_sha256_manifest(&sum).as_deref(), Some(EMPTY_SHA256));
+        assert_eq!(parse_sha256_manifest("<html>404</html>"), None);
+        assert_eq!(parse_sha256_manifest(""), None);
+    }
+
+    #[test]
+    fn test_unpack_keeps_the_binaries() {
+        let dir = std::env::temp_dir().join(format!("clipflow_unpack_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let archive = dir.join("ffmpeg.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for name in [crate::ffmpeg_name(), crate::ffprobe_name(), "README.txt"] {
+            zip.start_file(format!("ffmpeg-7.1-essentials_build/bin/{}", name), options).unwrap();
+            zip.write_all(name.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        unpack(&archive, &dir).unwrap();
+        assert_eq!(std::fs::read(dir.join(crate::ffmpeg_name())).unwrap(), crate::ffmpeg_name().as_bytes());
+        assert!(dir.join(crate::ffprobe_name()).exists());
+        assert!(!dir.join("README.txt").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sha256_file() {
+        let path = std::env::temp_dir().join(format!("clipflow_sha_{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(sha256_file(&path).unwrap(), EMPTY_SHA256);
+        std::fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod capabilities;
+pub mod download;
+pub mod paths;
@@ -0,0 +1,78 @@
+use std::path::Path;
+
+// Paths handed to FFmpeg. A path is safest as an argument of its own (`-i`
+// input, output last), which FFmpeg takes as is whatever its characters;
+// the helpers here cover the places where FFmpeg parses it out of a larger
+// string or reads more into it than a file name. Folders of user names like
+// "O'Brien" or "Vidéos [2024]" go through all of them.
+
+/// Concat demuxer list playing `paths` in order, each quoted with its
+/// apostrophes escaped. The list needs `-safe 0` for absolute paths.
+pub fn concat_list<P: AsRef<Path>>(paths: &[P]) -> String {
+    paths.iter().map(|p| concat_entry(&p.as_ref().to_string_lossy())).collect()
+}
+
+/// `file` line of a concat demuxer list. Inside single quotes everything is
+/// literal but the quote itself, closed, escaped and reopened.
+pub fn concat_entry(file: &str) -> String {
+    format!("file '{}'\n", file.replace('\'', "'\\''"))
+}
+
+/// `path` as a quoted filter option value (`fontfile=`), in a form that
+/// survives both the filter graph parser and the option parser. Forward
+/// slashes avoid escaping every separator; the drive colon is escaped for
+/// the option parser; an apostrophe leaves the quotes, escaped for both
+/// parsers. Brackets, commas and semicolons stay protected by the quotes.
+pub fn filter_value(path: &Path) -> String {
+    let escaped = path
+        .to_string_lossy()
+        .replace('\\', "/")
+        .replace('\'', "'\\\\\\''")
+        .replace(':', "\\:");
+    format!("'{escaped}'")
+}
+
+/// Input options reading the single image `path`: the image demuxer would
+/// otherwise take a `%` in its name for a numbered sequence pattern
+pub fn image_input(path: &Path) -> [String; 4] {
+    ["-pattern_type".into(), "none".into(), "-i".into(), path.to_string_lossy().to_string()]
+}
+
+/// Output options writing one image to `path`, for the same reason as
+/// `image_input`
+pub fn image_output(path: &Path) -> [String; 4] {
+    ["-update".into(), "1".into(), "-y".into(), path.to_string_lossy().to_string()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concat_list_escapes_apostrophes() {
+        let list = concat_list(&[Path::new("C:\\Users\\O'Brien\\a.mkv"), Path::new("C:\\Vidéos [1]\\b.mkv")]);
+        assert_eq!(list, "file 'C:\\Users\\O'\\''Brien\\a.mkv'\nfile 'C:\\Vidéos [1]\\b.mkv'\n");
+    }
+
+    #[test]
+    fn test_filter_value_windows() {
+        let value = filter_value(Path::new("C:\\Windows\\Fonts\\arial.ttf"));
+        assert_eq!(value, "'C\\:/Windows/Fonts/arial.ttf'");
+    }
+
+    #[test]
+    fn test_filter_value_exotic_names() {
+        assert_eq!(
+            filter_value(Path::new("C:\\Users\\O'Brien\\font.ttf")),
+            "'C\\:/Users/O'\\\\\\''Brien/font.ttf'"
+        );
+        // Protected by the quotes
+        assert_eq!(filter_value(Path::new("D:\\Polices [é];a,b.ttf")), "'D\\:/Polices [é];a,b.ttf'");
+    }
+
+    #[test]
+    fn test_single_image_options() {
+        assert_eq!(image_input(Path::new("100%.png")).join(" "), "-pattern_type none -i 100%.png");
+        assert_eq!(image_output(Path::new("100%.png")).join(" "), "-update 1 -y 100%.png");
+    }
+}
@@ -1,8 +1,9 @@
+use crate::events::{self, AppEvent};
 use crate::recording::manager;
 use crate::state::AppState;
-use crate::types::RecordingState;
+use crate::types::{DrawingTool, RecordingState};
 use std::sync::Mutex;
-use tauri::{Emitter, Manager};
+use tauri::Manager;
 use tauri_plugin_global_shortcut::{Code, Shortcut, ShortcutState};
 
 pub fn handler(app: &tauri::AppHandle, shortcut: &Shortcut, event: tauri_plugin_global_shortcut::ShortcutEvent) {
@@ -11,6 +12,9 @@ pub fn handler(app: &tauri::AppHandle, shortcut: &Shortcut, event: tauri_plugin_
     }
 
     match shortcut.key {
+        Code::F6 => handle_f6(app),
+        Code::F7 => crate::recording::drawing::toggle_tool(app, DrawingTool::Highlighter),
+        Code::F8 => crate::recording::drawing::toggle_tool(app, DrawingTool::Pen),
         Code::F9 => handle_f9(app),
         Code::F10 => handle_f10(app),
         Code::Escape => handle_escape(app),
@@ -22,7 +26,7 @@ fn handle_f9(app: &tauri::AppHandle) {
     let state = app.state::<Mutex<AppState>>();
     let current_state = {
         let Ok(s) = state.lock() else {
-            eprintln!("[hotkey] Failed to lock state");
+            tracing::warn!("Failed to lock state");
             return;
         };
         s.recording_state
@@ -30,25 +34,34 @@ fn handle_f9(app: &tauri::AppHandle) {
 
     match current_state {
         RecordingState::Idle => {
-            if let Err(e) = manager::start(&state, app) {
-                eprintln!("[hotkey] Start recording failed: {}", e);
-            } else {
-                eprintln!("[hotkey] Recording started via F9");
-                // Notify frontend of state change
-                let _ = app.emit("recording-state-changed", "recording");
-            }
+            let app_clone = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app_clone.state::<Mutex<AppState>>();
+                match manager::start_with_countdown(&state, &app_clone).await {
+                    Ok(true) => {
+                        tracing::info!("Recording started via F9");
+                    }
+                    Ok(false) => {
+                        tracing::info!("Countdown aborted");
+                    }
+                    Err(e) => {
+                        tracing::warn!("Start recording failed: {}", e);
+                        events::emit(&app_clone, AppEvent::RecordingError { message: e });
+                    }
+                }
+            });
         }
         RecordingState::Recording | RecordingState::Paused => {
             let app_clone = app.clone();
             tauri::async_runtime::spawn(async move {
                 let state = app_clone.state::<Mutex<AppState>>();
-                match manager::stop(&state).await {
+                match manager::stop(&state, &app_clone).await {
                     Ok(_clip) => {
-                        eprintln!("[hotkey] Recording stopped via F9");
-                        let _ = app_clone.emit("recording-state-changed", "idle");
+                        tracing::info!("Recording stopped via F9");
                     }
                     Err(e) => {
-                        eprintln!("[hotkey] Stop recording failed: {}", e);
+                        tracing::warn!("Stop recording failed: {}", e);
+                        events::emit(&app_clone, AppEvent::RecordingError { message: e });
                     }
                 }
             });
@@ -56,11 +69,22 @@ fn handle_f9(app: &tauri::AppHandle) {
     }
 }
 
+fn handle_f6(app: &tauri::AppHandle) {
+    let state = app.state::<Mutex<AppState>>();
+    match manager::toggle_mic_mute(&state) {
+        Ok(muted) => {
+            tracing::info!("Mic {} via F6", if muted { "muted" } else { "unmuted" });
+            events::emit(app, AppEvent::MicMuteChanged { muted });
+        }
+        Err(e) => tracing::debug!("Mic mute ignored: {}", e),
+    }
+}
+
 fn handle_f10(app: &tauri::AppHandle) {
     let state = app.state::<Mutex<AppState>>();
     let current_state = {
         let Ok(s) = state.lock() else {
-            eprintln!("[hotkey] Failed to lock state");
+            tracing::warn!("Failed to lock state");
             return;
         };
         s.recording_state
@@ -71,23 +95,23 @@ fn handle_f10(app: &tauri::AppHandle) {
             let app_clone = app.clone();
             tauri::async_runtime::spawn(async move {
                 let state = app_clone.state::<Mutex<AppState>>();
-                match manager::pause(&state).await {
+                match manager::pause(&state, &app_clone).await {
                     Ok(()) => {
-                        eprintln!("[hotkey] Recording paused via F10");
-                        let _ = app_clone.emit("recording-state-changed", "paused");
+                        tracing::info!("Recording paused via F10");
                     }
                     Err(e) => {
-                        eprintln!("[hotkey] Pause recording failed: {}", e);
+                        tracing::warn!("Pause recording failed: {}", e);
+                        events::emit(&app_clone, AppEvent::RecordingError { message: e });
                     }
                 }
             });
         }
         RecordingState::Paused => {
-            if let Err(e) = manager::resume(&state) {
-                eprintln!("[hotkey] Resume recording failed: {}", e);
+            if let Err(e) = manager::resume(&state, app) {
+                tracing::warn!("Resume recording failed: {}", e);
+                events::emit(app, AppEvent::RecordingError { message: e });
             } else {
-                eprintln!("[hotkey] Recording resumed via F10");
-                let _ = app.emit("recording-state-changed", "recording");
+                tracing::info!("Recording resumed via F10");
             }
         }
         _ => {}
@@ -96,20 +120,27 @@ fn handle_f10(app: &tauri::AppHandle) {
 
 fn handle_escape(app: &tauri::AppHandle) {
     let state = app.state::<Mutex<AppState>>();
+
+    // Escape during the countdown aborts it before capture starts
+    if crate::recording::countdown::abort(&state) {
+        tracing::info!("Countdown aborted via ESC");
+        return;
+    }
+
     let current_state = {
         let Ok(s) = state.lock() else {
-            eprintln!("[hotkey] Failed to lock state");
+            tracing::warn!("Failed to lock state");
             return;
         };
         s.recording_state
     };
 
     if current_state == RecordingState::Recording || current_state == RecordingState::Paused {
-        if let Err(e) = manager::cancel(&state) {
-            eprintln!("[hotkey] Cancel recording failed: {}", e);
+        if let Err(e) = manager::cancel(&state, app) {
+            tracing::warn!("Cancel recording failed: {}", e);
+            events::emit(app, AppEvent::RecordingError { message: e });
         } else {
-            eprintln!("[hotkey] Recording cancelled via ESC");
-            let _ = app.emit("recording-state-changed", "idle");
+            tracing::info!("Recording cancelled via ESC");
         }
     }
 }
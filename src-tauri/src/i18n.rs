@@ -0,0 +1,318 @@
+use crate::types::Locale;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Process-wide so deep helpers (manager, encoder) can translate without state
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// (key, French, English). `{}` placeholders are filled in order by `tf`.
+const MESSAGES: &[(&str, &str, &str)] = &[
+    ("clip_not_found", "Clip introuvable : {}", "Clip not found: {}"),
+    ("transition_out_of_range", "Transition {} hors limites", "Transition index {} out of bounds"),
+    ("overlay_failed", "Impossible d'ouvrir le sélecteur de zone : {}", "Failed to open region selector: {}"),
+    ("no_clips_to_export", "Aucun clip à exporter", "No clips to export"),
+    ("no_export_targets", "Aucun format de sortie sélectionné", "No output format selected"),
+    ("export_failed", "Export échoué : {}", "Export failed: {}"),
+    ("export_done", "Export terminé : {}", "Export finished: {}"),
+    ("preset_not_found", "Préréglage d'export introuvable : {}", "Export preset not found: {}"),
+    ("no_clips_to_preview", "Aucun clip à prévisualiser", "No clips to preview"),
+    ("preview_failed", "Prévisualisation échouée : {}", "Preview failed: {}"),
+    ("ffmpeg_download_failed", "Téléchargement de FFmpeg échoué : {}", "FFmpeg download failed: {}"),
+    ("ffmpeg_missing_after_download", "FFmpeg téléchargé mais introuvable", "FFmpeg downloaded but binary not found"),
+    ("upload_target_not_found", "Cible d'upload introuvable : {}", "Upload target not found: {}"),
+    ("file_not_found", "Fichier introuvable : {}", "File not found: {}"),
+    ("recording_in_progress", "Enregistrement déjà en cours", "A recording is already in progress"),
+    ("not_recording", "Pas d'enregistrement en cours", "No recording in progress"),
+    ("not_paused", "L'enregistrement n'est pas en pause", "The recording is not paused"),
+    ("voiceover_in_progress", "Une voix off est en cours d'enregistrement", "A voiceover is being recorded"),
+    ("capture_start_failed", "Impossible de démarrer la capture : {}", "Failed to start capture: {}"),
+    ("capture_resume_failed", "Impossible de reprendre la capture : {}", "Failed to resume capture: {}"),
+    ("capture_stop_failed", "Impossible d'arrêter la capture : {}", "Failed to stop capture: {}"),
+    (
+        "capture_file_missing",
+        "Capture échouée : le fichier vidéo n'a pas été créé. Vérifiez que la zone de capture est valide.",
+        "Capture failed: the video file was not created. Check that the capture region is valid.",
+    ),
+    ("remux_failed", "Échec du remux MP4 : {}", "MP4 remux failed: {}"),
+    ("no_segments", "Aucun segment à concaténer", "No segments to concatenate"),
+    ("concat_failed", "Échec de la concaténation : {}", "Concatenation failed: {}"),
+    ("invalid_language_code", "Code de langue invalide : {}", "Invalid language code: {}"),
+    ("subtitles_language_exists", "Les sous-titres existent déjà en « {} »", "Subtitles already exist in \"{}\""),
+    ("subtitles_write_failed", "Impossible d'écrire les sous-titres", "Failed to write the subtitles"),
+    ("audio_offset_too_large", "Le décalage audio est limité à 5 secondes", "The audio offset is limited to 5 seconds"),
+    ("current_take_not_found", "Prise actuelle introuvable", "Current take not found"),
+    ("take_not_found", "Prise {} introuvable", "Take {} not found"),
+    ("retake_single_monitor", "Une prise se refait sur un seul écran", "A take is recorded on a single screen"),
+    ("no_secondary_region", "Ce clip n'a pas de seconde zone", "This clip has no second region"),
+    ("voiceover_not_recording", "Pas de voix off en cours", "No voiceover is being recorded"),
+    ("voiceover_empty", "Aucun son enregistré", "No sound was recorded"),
+    ("voiceover_not_found", "Voix off introuvable : {}", "Voiceover not found: {}"),
+    ("subtitle_after_end", "Sous-titre {} : commence après la fin de la vidéo", "Subtitle {}: starts after the end of the video"),
+    ("subtitle_cut_by_end", "Sous-titre {} : coupé par la fin de la vidéo", "Subtitle {}: cut by the end of the video"),
+    ("subtitle_too_short", "Sous-titre {} : affiché moins de {} s", "Subtitle {}: shown for less than {} s"),
+    ("subtitle_too_fast", "Sous-titre {} : trop rapide à lire, {} s conseillées", "Subtitle {}: too fast to read, {} s advised"),
+    ("subtitle_overlap", "Sous-titre {} : encore affiché quand le suivant apparaît au même endroit", "Subtitle {}: still shown when the next one appears at the same place"),
+    ("range_end_before_start", "La fin de la plage doit être après son début", "The end of the range must come after its start"),
+    ("range_empty", "La plage choisie ne contient aucun clip", "The chosen range contains no clip"),
+    ("per_clip_format", "L'export par clip accepte le MP4 et le GIF", "Exporting each clip supports MP4 and GIF"),
+    ("folder_not_found", "Dossier introuvable : {}", "Folder not found: {}"),
+    ("mic_not_recorded", "Le micro n'est pas enregistré", "The microphone is not being recorded"),
+    ("mic_muted_marker", "Micro coupé", "Microphone muted"),
+    ("cleanup_while_recording", "Nettoyage impossible pendant un enregistrement", "Cannot clean up while recording"),
+    ("export_resolution_too_large", "Résolution d'export trop grande ({}×{}) : l'encodeur accepte au plus {} pixels de côté. Activez la réduction automatique ou choisissez une taille plus petite.", "Export resolution too large ({}×{}): the encoder accepts at most {} pixels per side. Turn on automatic downscaling or pick a smaller size."),
+    ("clip_has_no_audio", "Ce clip n'a pas de piste audio", "This clip has no audio track"),
+    ("group_needs_two_annotations", "Sélectionnez au moins deux annotations", "Select at least two annotations"),
+    ("window_not_capturable", "Fenêtre introuvable ou réduite", "Window not found or minimized"),
+    ("subtitles_language_missing", "Aucun sous-titre en « {} »", "No subtitles in \"{}\""),
+    ("guide_title_safe", "Zone titre 16:9", "16:9 title safe"),
+    ("import_while_recording", "Import impossible pendant un enregistrement", "Cannot import while recording"),
+    ("unsupported_format", "Format non pris en charge", "Unsupported format"),
+    ("import_file_failed", "{} : {}", "{}: {}"),
+    ("freeze_frame_unreadable", "Impossible de lire l'arrêt sur image", "Failed to read the freeze frame"),
+    ("freeze_frame_failed", "Échec de l'arrêt sur image : {}", "Freeze frame failed: {}"),
+    ("files_count_one", "{} fichier", "{} file"),
+    ("files_count", "{} fichiers", "{} files"),
+    ("ffmpeg_file_missing", "Fichier introuvable", "File not found"),
+    ("ffmpeg_corrupt_data", "Données corrompues", "Corrupt data"),
+    ("ffmpeg_access_denied", "Accès refusé au fichier", "Access to the file was denied"),
+    ("ffmpeg_clip_too_short", "Clip trop court pour la transition demandée", "Clip too short for the requested transition"),
+    ("ffmpeg_disk_full", "Espace disque insuffisant", "Not enough disk space"),
+    ("ffmpeg_codec_missing", "Codec vidéo introuvable — vérifiez votre installation FFmpeg", "Video codec not found — check your FFmpeg installation"),
+    ("ffmpeg_invalid_argument", "Paramètre FFmpeg invalide", "Invalid FFmpeg parameter"),
+    ("ffmpeg_error", "Erreur FFmpeg : {}", "FFmpeg error: {}"),
+    ("clip_file_missing", "Fichier de clip introuvable : {}", "Clip file not found: {}"),
+    ("clip_n_file_missing", "Fichier du clip {} introuvable : {}", "File of clip {} not found: {}"),
+    ("filter_script_write_failed", "Impossible d'écrire le graphe de filtres {}", "Failed to write the filter graph {}"),
+    ("no_clips_to_export_gif", "Aucun clip à exporter en GIF", "No clips to export as GIF"),
+    ("palette_failed", "Échec de la génération de palette : {}", "Palette generation failed: {}"),
+    ("gif_failed", "Échec de la génération GIF : {}", "GIF generation failed: {}"),
+    ("preview_render_failed", "Échec de la prévisualisation : {}", "Preview rendering failed: {}"),
+    ("preview_run_failed", "Échec de la prévisualisation", "Preview rendering failed"),
+    ("dual_failed", "Échec de la composition des deux zones : {}", "Combining the two regions failed: {}"),
+    ("focus_failed", "Échec du cadrage sur les fenêtres actives : {}", "Framing the active windows failed: {}"),
+    ("follow_failed", "Échec du recadrage autour du curseur : {}", "Cropping around the cursor failed: {}"),
+    ("waveform_failed", "Échec du rendu de la forme d'onde : {}", "Waveform rendering failed: {}"),
+    ("frame_extract_failed", "Échec de l'extraction de l'image : {}", "Frame extraction failed: {}"),
+    ("frame_unreadable", "Image extraite illisible", "Extracted frame is unreadable"),
+    ("frame_write_failed", "Impossible d'écrire l'image", "Failed to write the image"),
+    ("annotations_encode_failed", "Impossible d'encoder les annotations", "Failed to encode the annotations"),
+    ("annotations_unreadable", "Annotations illisibles", "Annotations are unreadable"),
+    ("heatmap_no_cursor", "Aucune position du curseur enregistrée pour ce clip", "No cursor position was recorded for this clip"),
+    ("heatmap_write_failed", "Impossible d'écrire la carte de chaleur", "Failed to write the heatmap"),
+    ("output_per_clip", "Un fichier de sortie est attendu par clip", "One output file is expected per clip"),
+    ("post_export_no_command", "Aucune commande configurée", "No command configured"),
+    ("visualizer_no_audio", "Aucun son à visualiser dans la timeline", "No sound to visualize in the timeline"),
+    ("overlay_dir_failed", "Impossible de créer le dossier des calques", "Failed to create the overlay folder"),
+    ("annotation_font_invalid", "Police des annotations invalide", "Invalid annotation font"),
+    ("keystroke_font_invalid", "Police des touches invalide", "Invalid keystroke font"),
+    ("overlay_size_invalid", "Taille de calque invalide", "Invalid overlay size"),
+    ("overlay_image_write_failed", "Impossible d'écrire une image du calque", "Failed to write an overlay image"),
+    ("overlay_list_write_failed", "Impossible d'écrire la liste du calque", "Failed to write the overlay list"),
+    ("soft_subtitles_replace_failed", "Impossible de remplacer l'export par sa version sous-titrée", "Failed to replace the export with its subtitled version"),
+    ("voiceover_replace_failed", "Impossible de remplacer l'export par sa version avec voix off", "Failed to replace the export with its voiceover version"),
+    ("autotrim_no_activity", "Aucune activité détectée dans le clip", "No activity detected in the clip"),
+    ("audio_analysis_failed", "Analyse audio impossible : {}", "Audio analysis failed: {}"),
+    ("audio_read_failed", "Lecture de l'audio impossible", "Failed to read the audio"),
+    ("clip_unreadable", "Clip {} : fichier vidéo illisible", "Clip {}: unreadable video file"),
+    (
+        "clip_size_mismatch",
+        "Clip {} : l'image fait {}×{} au lieu des {}×{} enregistrés, elle sera mise à l'échelle",
+        "Clip {}: the picture is {}×{} instead of the recorded {}×{}, it will be scaled",
+    ),
+    ("output_not_writable", "Dossier d'export inaccessible en écriture ({}) : {}", "Export folder is not writable ({}): {}"),
+    ("clip_missing_file", "Clip {} : fichier introuvable ({})", "Clip {}: file not found ({})"),
+    ("clip_missing_audio", "Clip {} : piste audio introuvable ({})", "Clip {}: audio track not found ({})"),
+    ("clip_frame_rate", "Clip {} : {} i/s exporté à {} i/s, des images seront répétées", "Clip {}: {} fps exported at {} fps, frames will be repeated"),
+    ("disk_estimate", "~{} Mo nécessaires, {} Mo libres sur le disque d'export", "~{} MB needed, {} MB free on the export disk"),
+    ("disk_full", "Espace disque insuffisant : {}", "Not enough disk space: {}"),
+    ("disk_tight", "Espace disque juste : {}", "Disk space is tight: {}"),
+    ("chunks_dir_failed", "Impossible de créer le dossier des parties", "Failed to create the parts folder"),
+    ("chunks_manifest_failed", "Impossible d'écrire le manifeste des parties", "Failed to write the parts manifest"),
+    ("chunks_list_failed", "Impossible d'écrire la liste des parties", "Failed to write the parts list"),
+    ("ffmpeg_download_in_progress", "Téléchargement de FFmpeg déjà en cours", "FFmpeg is already being downloaded"),
+    ("ffmpeg_checksum_invalid", "Somme de contrôle FFmpeg invalide", "Invalid FFmpeg checksum"),
+    ("ffmpeg_download_retries", "Échec du téléchargement de FFmpeg après {} tentatives : {}", "FFmpeg download failed after {} attempts: {}"),
+    ("ffmpeg_manifest_invalid", "Manifeste SHA-256 invalide", "Invalid SHA-256 manifest"),
+    ("countdown_in_progress", "Compte à rebours déjà en cours", "A countdown is already running"),
+    ("recovered_video_unreadable", "Impossible de lire la vidéo récupérée", "Failed to read the recovered video"),
+    ("screen_capture_failed", "Capture de l'écran impossible", "Failed to capture the screen"),
+    ("png_encode_failed", "Encodage PNG échoué", "PNG encoding failed"),
+    ("privacy_marker", "Pause confidentialité ({})", "Privacy pause ({})"),
+    ("update_check_failed", "Vérification des mises à jour impossible : {}", "Failed to check for updates: {}"),
+    ("clipboard_open_failed", "Impossible d'ouvrir le presse-papiers : {}", "Failed to open the clipboard: {}"),
+    ("clipboard_lock_failed", "GlobalLock a échoué", "GlobalLock failed"),
+    ("project_invalid", "Projet invalide : {}", "Invalid project: {}"),
+    ("cursor_file_truncated", "Fichier curseur tronqué", "Truncated cursor file"),
+    ("cursor_file_invalid", "Fichier curseur invalide", "Invalid cursor file"),
+    ("file_name_invalid", "Nom de fichier invalide", "Invalid file name"),
+    ("upload_rejected", "Upload refusé (HTTP {}) : {}", "Upload rejected (HTTP {}): {}"),
+    ("upload_failed", "Upload échoué : {}", "Upload failed: {}"),
+    ("imported_video_unreadable", "Impossible de lire la vidéo importée", "Failed to read the imported video"),
+    ("import_failed", "Échec de l'import : {}", "Import failed: {}"),
+    ("archive_invalid", "Archive invalide : {}", "Invalid archive: {}"),
+    ("archive_no_project", "Aucun projet ClipFlow dans l'archive", "No ClipFlow project in the archive"),
+    ("conversion_failed", "Échec de la conversion : {}", "Conversion failed: {}"),
+    ("test_clip_failed", "Échec de la génération du clip de test : {}", "Test clip generation failed: {}"),
+    ("segments_too_short", "Segments trop courts pour une transition", "Segments too short for a transition"),
+    ("ffmpeg_process_missing", "Processus FFmpeg introuvable", "FFmpeg process not found"),
+    ("clip_path_missing", "Chemin du clip introuvable", "Clip path not found"),
+    ("diagnostics_dir_missing", "Dossier de diagnostic introuvable", "Diagnostics folder not found"),
+    ("url_invalid", "URL invalide", "Invalid URL"),
+    ("ffmpeg_unsupported", "Cette version de FFmpeg ne prend pas en charge {} ({} manquant)", "This FFmpeg build does not support {} ({} missing)"),
+    ("feature_transitions", "les transitions", "transitions"),
+    ("feature_text", "les textes (sous-titres, touches, filigrane)", "text (subtitles, keystrokes, watermark)"),
+    ("feature_gif", "l'export GIF", "GIF export"),
+    ("feature_mp4", "l'export MP4", "MP4 export"),
+    ("feature_lossless", "la capture sans perte", "lossless capture"),
+    ("feature_utvideo", "la capture UtVideo", "UtVideo capture"),
+    ("feature_ddagrab", "la capture Desktop Duplication", "Desktop Duplication capture"),
+    ("feature_nvenc", "l'encodage NVIDIA", "NVIDIA encoding"),
+    ("feature_qsv", "l'encodage Intel QSV", "Intel QSV encoding"),
+    ("feature_amf", "l'encodage AMD", "AMD encoding"),
+    ("feature_hdr", "la correction HDR", "HDR correction"),
+    ("feature_unknown", "cette opération", "this operation"),
+    ("webhook_export_size", "Nouvel export ClipFlow : {} ({} Mo)", "New ClipFlow export: {} ({} MB)"),
+    ("webhook_export", "Nouvel export ClipFlow : {}", "New ClipFlow export: {}"),
+    ("webhook_upload", "{} est en ligne : {}", "{} is online: {}"),
+    ("scroll_wheel", "Molette", "Scroll"),
+    ("preset_youtube_1440p", "YouTube 1440p (agrandi)", "YouTube 1440p (upscaled)"),
+    ("preset_youtube_4k", "YouTube 4K (agrandi)", "YouTube 4K (upscaled)"),
+    ("preset_discord_10mb", "Discord (10 Mo)", "Discord (10 MB)"),
+    ("preset_gif_readme", "GIF pour README", "GIF for README"),
+    (
+        "plan_overlay_tracks",
+        "Annotations et touches dessinées ici par des filtres : l'export les rend d'abord en pistes d'images superposées",
+        "Annotations and keystrokes are drawn by filters here: the export first renders them as overlaid image tracks",
+    ),
+    ("plan_gamepad_tracks", "La manette n'apparaît que dans les pistes d'images rendues à l'export", "The gamepad only appears in the image tracks rendered at export"),
+    (
+        "plan_parallel",
+        "Timeline longue : l'export rend chaque clip dans son propre FFmpeg avant de les enchaîner, ce plan montre le rendu en une passe",
+        "Long timeline: the export renders each clip in its own FFmpeg before joining them, this plan shows a single-pass render",
+    ),
+    ("plan_chunked", "L'export découpe la timeline en morceaux de {} min rendus séparément", "The export splits the timeline into {} min parts rendered separately"),
+    ("plan_soft_subtitles", "Les sous-titres sont ajoutés ensuite en pistes, sans réencodage", "Subtitles are added afterwards as tracks, without re-encoding"),
+    (
+        "plan_prepasses_skipped",
+        "Suivi du curseur, cadrage des fenêtres et seconde zone non rendus : ce plan part des clips enregistrés",
+        "Cursor follow, window focus and second region are not rendered: this plan starts from the recorded clips",
+    ),
+    ("thumbnail_failed", "FFmpeg n'a pas pu extraire l'image à {} ms", "FFmpeg could not extract the frame at {} ms"),
+];
+
+pub fn set_locale(locale: Locale) {
+    CURRENT.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Locale::En,
+        _ => Locale::Fr,
+    }
+}
+
+/// "en", "en-US", "EN_gb" -> En; anything else falls back to French
+pub fn parse_locale(lang: &str) -> Locale {
+    if lang.to_ascii_lowercase().starts_with("en") {
+        Locale::En
+    } else {
+        Locale::Fr
+    }
+}
+
+/// Message for `key` in the current locale (the key itself if unknown)
+pub fn t(key: &str) -> String {
+    lookup(key, locale()).to_string()
+}
+
+/// `t` with `{}` placeholders replaced by `args`, in order
+pub fn tf(key: &str, args: &[&dyn Display]) -> String {
+    fill(lookup(key, locale()), args)
+}
+
+/// `tf` with `count`, using the `<key>_one` message when it is singular
+pub fn tn(key: &str, count: usize) -> String {
+    let locale = locale();
+    fill(lookup(&plural_key(key, count, locale), locale), &[&count])
+}
+
+/// French keeps 0 singular, English does not
+fn plural_key(key: &str, count: usize, locale: Locale) -> String {
+    let singular = match locale {
+        Locale::Fr => count <= 1,
+        Locale::En => count == 1,
+    };
+    if singular { format!("{}_one", key) } else { key.to_string() }
+}
+
+fn lookup(key: &str, locale: Locale) -> &str {
+    MESSAGES
+        .iter()
+        .find(|(k, _, _)| *k == key)
+        .map(|(_, fr, en)| match locale {
+            Locale::Fr => *fr,
+            Locale::En => *en,
+        })
+        .unwrap_or(key)
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}").peekable();
+    while let Some(part) = parts.next() {
+        out.push_str(part);
+        if parts.peek().is_some() {
+            if let Some(arg) = args.next() {
+                out.push_str(&arg.to_string());
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_both_locales() {
+        assert_eq!(lookup("no_clips_to_export", Locale::Fr), "Aucun clip à exporter");
+        assert_eq!(lookup("no_clips_to_export", Locale::En), "No clips to export");
+    }
+
+    #[test]
+    fn test_lookup_unknown_key_returns_key() {
+        assert_eq!(lookup("does_not_exist", Locale::En), "does_not_exist");
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        assert_eq!(fill("Export failed: {}", &[&"disk full"]), "Export failed: disk full");
+        assert_eq!(fill("{} of {}", &[&1, &2]), "1 of 2");
+        assert_eq!(fill("no args {}", &[]), "no args ");
+    }
+
+    #[test]
+    fn test_plural_key() {
+        assert_eq!(lookup(&plural_key("files_count", 1, Locale::En), Locale::En), "{} file");
+        assert_eq!(lookup(&plural_key("files_count", 0, Locale::En), Locale::En), "{} files");
+        assert_eq!(lookup(&plural_key("files_count", 0, Locale::Fr), Locale::Fr), "{} fichier");
+        assert_eq!(lookup(&plural_key("files_count", 3, Locale::Fr), Locale::Fr), "{} fichiers");
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!(parse_locale("en-US"), Locale::En);
+        assert_eq!(parse_locale("EN"), Locale::En);
+        assert_eq!(parse_locale("fr"), Locale::Fr);
+        assert_eq!(parse_locale("de"), Locale::Fr);
+    }
+
+    #[test]
+    fn test_every_message_has_matching_placeholders() {
+        for (key, fr, en) in MESSAGES {
+            assert_eq!(fr.matches("{}").count(), en.matches("{}").count(), "{}", key);
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use crate::events::{self, AppEvent};
+use crate::state::AppState;
+use crate::types::{Clip, ClipMedia, Project, RecordingState, Region, Transition};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Length of the still clip created from a dropped image
+const IMAGE_CLIP_SECONDS: u32 = 3;
+
+#[derive(Debug, PartialEq)]
+enum DropKind {
+    Video,
+    Gif,
+    Image,
+    Project,
+    Bundle,
+    Unsupported,
+}
+
+fn classify(path: &Path) -> DropKind {
+    let ext = path.extension().map(|e| e.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+    match ext.as_str() {
+        "mp4" | "mov" | "mkv" => DropKind::Video,
+        "gif" => DropKind::Gif,
+        "png" => DropKind::Image,
+        "json" => DropKind::Project,
+        "zip" => DropKind::Bundle,
+        _ => DropKind::Unsupported,
+    }
+}
+
+/// Handle files dropped on the main window: media become clips,
+/// project files (.json, or a .zip bundle with its media) are opened.
+pub fn handle_drop(app: AppHandle, paths: Vec<PathBuf>) {
+    tauri::async_runtime::spawn(async move {
+        let temp_dir = {
+            let state = app.state::<Mutex<AppState>>();
+            let Ok(s) = state.lock() else { return };
+            if s.recording_state != RecordingState::Idle {
+                events::emit(&app, AppEvent::ImportError { message: crate::i18n::t("import_while_recording") });
+                return;
+            }
+            s.temp_dir.clone()
+        };
+        let _ = std::fs::create_dir_all(&temp_dir);
+
+        for path in paths {
+            let result = match classify(&path) {
+                DropKind::Video | DropKind::Gif | DropKind::Image => {
+                    match import_media(&app, &temp_dir, &path).await {
+                        Ok(clip) => {
+                            add_clip(&app, clip);
+                            Ok(())
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+                DropKind::Project => crate::project::load_project_file(&path).map(|p| open_project(&app, p)),
+                DropKind::Bundle => load_bundle(&temp_dir, &path).map(|p| open_project(&app, p)),
+                DropKind::Unsupported => Err(crate::i18n::t("unsupported_format")),
+            };
+            if let Err(e) = result {
+                tracing::warn!("Import of {:?} failed: {}", path, e);
+                let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                events::emit(&app, AppEvent::ImportError { message: crate::i18n::tf("import_file_failed", &[&name, &e]) });
+            }
+        }
+    });
+}
+
+pub(crate) fn add_clip(app: &AppHandle, clip: Clip) {
+    {
+        let state = app.state::<Mutex<AppState>>();
+        let Ok(mut s) = state.lock() else { return };
+        if !s.clips.is_empty() {
+            s.transitions.push(Transition::default());
+        }
+        s.clips.push(clip.clone());
+    }
+    events::emit(app, AppEvent::ClipAdded { clip });
+}
+
+fn open_project(app: &AppHandle, project: Project) {
+    let (id, name) = (project.id.clone(), project.name.clone());
+    {
+        let state = app.state::<Mutex<AppState>>();
+        let Ok(mut s) = state.lock() else { return };
+        crate::project::apply_to_state(&mut s, project);
+    }
+    tracing::info!("Opened project '{}'", name);
+    events::emit(app, AppEvent::ProjectOpened { project_id: id });
+}
+
+/// Convert a dropped file into an MP4 clip in the temp dir, in the
+/// timeline format (see `normalize`). The source is never referenced
+/// directly: deleting a clip removes its files.
+async fn import_media(app: &AppHandle, temp_dir: &Path, source: &Path) -> Result<Clip, String> {
+    let clip_id = uuid::Uuid::new_v4().to_string();
+    let output = temp_dir.join(format!("{}.mp4", clip_id));
+    let src = source.to_string_lossy().to_string();
+    let out = output.to_string_lossy().to_string();
+    let kind = classify(source);
+    let name = source.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let filters = crate::normalize::filters(crate::normalize::FPS);
+    let seconds = IMAGE_CLIP_SECONDS.to_string();
+    match kind {
+        // Other codec, frame rate, pixel format...
+        DropKind::Video if crate::normalize::needs_normalizing(source, crate::normalize::FPS).await => {
+            crate::normalize::transcode(app, source, &output, &name, crate::normalize::FPS).await?;
+        }
+        DropKind::Video => {
+            let copy = ["-i", src.as_str(), "-map", "0:v:0", "-c", "copy", "-movflags", "+faststart"];
+            if run_ffmpeg(&copy, &out).await.is_err() {
+                // Codec that MP4 can't carry (ProRes in a MOV, ...): re-encode instead
+                crate::normalize::transcode(app, source, &output, &name, crate::normalize::FPS).await?;
+            }
+        }
+        DropKind::Gif => {
+            run_ffmpeg(&["-i", &src, "-vf", &filters, "-c:v", "libx264", "-preset", "veryfast", "-crf", "18"], &out).await?;
+        }
+        _ => {
+            let input = crate::ffmpeg::paths::image_input(source);
+            let mut args = vec!["-loop", "1"];
+            args.extend(input.iter().map(String::as_str));
+            args.extend(["-t", seconds.as_str(), "-vf", filters.as_str(), "-c:v", "libx264", "-preset", "veryfast", "-crf", "18"]);
+            run_ffmpeg(&args, &out).await?;
+        }
+    }
+
+    let (width, height, duration_s) = crate::capture::screen::probe_video(&output).await
+        .ok_or_else(|| crate::i18n::t("imported_video_unreadable"))?;
+
+    // Embedded audio goes next to the clip, like a recorded system track
+    let mut audio_paths = Vec::new();
+    if kind == DropKind::Video {
+        let wav = temp_dir.join(format!("{}_system.wav", clip_id));
+        let wav_str = wav.to_string_lossy().to_string();
+        if run_ffmpeg(&["-i", &src, "-vn", "-ac", "2", "-ar", "48000"], &wav_str).await.is_ok()
+            && std::fs::metadata(&wav).map(|m| m.len() > 44).unwrap_or(false)
+        {
+            audio_paths.push(wav_str);
+        } else {
+            let _ = std::fs::remove_file(&wav);
+        }
+    }
+
+    let thumbnail_path = output.with_extension("thumb.png");
+    let thumb_ms = crate::capture::screen::default_thumbnail_ms((duration_s * 1000.0) as u64);
+    let thumb = if crate::capture::screen::generate_thumbnail(&output, &thumbnail_path, thumb_ms).is_ok() {
+        Some(thumbnail_path)
+    } else {
+        None
+    };
+
+    Ok(Clip {
+        id: clip_id,
+        media: ClipMedia {
+            path: output,
+            duration_ms: (duration_s * 1000.0) as u64,
+            region: Region { x: 0, y: 0, width, height },
+            has_audio: !audio_paths.is_empty(),
+            thumbnail_path: thumb,
+            trim_start_ms: 0,
+            trim_end_ms: 0,
+            audio_paths,
+            hdr: false,
+            secondary: None,
+            follow_cursor: None,
+            focus_track: Vec::new(),
+            script: None,
+            audio_offset_ms: 0,
+        },
+        zoom_keyframes: Vec::new(),
+        capture_group: None,
+        takes: Vec::new(),
+    })
+}
+
+async fn run_ffmpeg(args: &[&str], output: &str) -> Result<(), String> {
+    let result = crate::ffmpeg_command()
+        .args(args)
+        .args(["-y", output])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(crate::i18n::tf("import_failed", &[&stderr.chars().take(300).collect::<String>()]));
+    }
+    Ok(())
+}
+
+/// Extract a project bundle (project JSON + media files) and point
+/// the project's clips at the extracted media.
+fn load_bundle(temp_dir: &Path, zip_path: &Path) -> Result<Project, String> {
+    let file = std::fs::File::open(zip_path).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| crate::i18n::tf("archive_invalid", &[&e]))?;
+    let dest = temp_dir.join(format!("import_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dest).map_err(|e| e.to_string())?;
+
+    let mut project: Option<Project> = None;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        // enclosed_name() rejects absolute paths and `..` components
+        let Some(name) = entry.enclosed_name().and_then(|p| p.file_name().map(PathBuf::from)) else {
+            continue;
+        };
+        let out_path = dest.join(&name);
+        let mut out = std::fs::File::create(&out_path).map_err(|e| e.to_string())?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+
+        if project.is_none() && name.extension().map(|e| e == "json").unwrap_or(false) {
+            project = std::fs::read_to_string(&out_path).ok()
+                .and_then(|json| serde_json::from_str::<Project>(&json).ok());
+        }
+    }
+
+    let mut project = project.ok_or_else(|| crate::i18n::t("archive_no_project"))?;
+    relink_media(&mut project, &dest);
+    Ok(project)
+}
+
+/// Re-point clip media at files of the same name in `dir`, when present
+fn relink_media(project: &mut Project, dir: &Path) {
+    for clip in &mut project.clips {
+        relink_clip(clip, dir);
+    }
+    for voiceover in &mut project.voiceovers {
+        if let Some(p) = relink(&voiceover.path, dir) {
+            voiceover.path = p;
+        }
+    }
+}
+
+/// The played media and every take, second captures included
+fn relink_clip(clip: &mut Clip, dir: &Path) {
+    for media in std::iter::once(&mut clip.media).chain(clip.takes.iter_mut().map(|t| &mut t.media)) {
+        if let Some(p) = relink(&media.path, dir) {
+            media.path = p;
+        }
+        if let Some(p) = media.thumbnail_path.as_deref().and_then(|t| relink(t, dir)) {
+            media.thumbnail_path = Some(p);
+        }
+        for audio in &mut media.audio_paths {
+            if let Some(p) = relink(Path::new(audio), dir) {
+                *audio = p.to_string_lossy().to_string();
+            }
+        }
+        if let Some(secondary) = &mut media.secondary {
+            if let Some(p) = relink(&secondary.path, dir) {
+                secondary.path = p;
+            }
+        }
+    }
+}
+
+/// The file of the same name in `dir`, if there is one
+fn relink(path: &Path, dir: &Path) -> Option<PathBuf> {
+    let candidate = dir.join(path.file_name()?);
+    candidate.exists().then_some(candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(Path::new("a.MP4")), DropKind::Video);
+        assert_eq!(classify(Path::new("a.mov")), DropKind::Video);
+        assert_eq!(classify(Path::new("a.mkv")), DropKind::Video);
+        assert_eq!(classify(Path::new("a.gif")), DropKind::Gif);
+        assert_eq!(classify(Path::new("a.png")), DropKind::Image);
+        assert_eq!(classify(Path::new("p.json")), DropKind::Project);
+        assert_eq!(classify(Path::new("p.zip")), DropKind::Bundle);
+        assert_eq!(classify(Path::new("a.txt")), DropKind::Unsupported);
+        assert_eq!(classify(Path::new("noext")), DropKind::Unsupported);
+    }
+
+    #[test]
+    fn test_relink_clip_secondary_and_takes() {
+        use crate::types::{test_clip, DualLayout, SecondaryVideo, Take};
+        let dir = std::env::temp_dir().join(format!("clipflow-relink-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["a.mp4", "a_second.mp4", "take.mp4", "take_second.mp4"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        let secondary = |path: &str| SecondaryVideo {
+            path: PathBuf::from("C:/old").join(path),
+            region: Region { x: 0, y: 0, width: 640, height: 480 },
+            offset_ms: 0,
+            layout: DualLayout::default(),
+        };
+        let mut clip = test_clip("a", 1000);
+        clip.media.secondary = Some(secondary("a_second.mp4"));
+        let mut take = test_clip("take", 1000).media;
+        take.secondary = Some(secondary("take_second.mp4"));
+        clip.takes.push(Take { id: "t1".into(), media: take });
+
+        relink_clip(&mut clip, &dir);
+
+        assert_eq!(clip.media.path, dir.join("a.mp4"));
+        assert_eq!(clip.media.secondary.as_ref().unwrap().path, dir.join("a_second.mp4"));
+        assert_eq!(clip.takes[0].media.path, dir.join("take.mp4"));
+        assert_eq!(clip.takes[0].media.secondary.as_ref().unwrap().path, dir.join("take_second.mp4"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,12 +1,32 @@
+mod annotations;
 mod capture;
+mod clipboard;
 mod commands;
+mod diagnostics;
+mod events;
 mod export;
+mod ffmpeg;
 mod hotkeys;
+mod i18n;
+mod import;
+mod logging;
+mod normalize;
+mod privacy;
 mod project;
 mod recording;
 mod region;
+mod session;
 mod state;
+mod storage;
+mod subtitles;
+mod telemetry;
+#[cfg(debug_assertions)]
+mod testclips;
 mod types;
+mod update;
+mod upload;
+mod watch_folder;
+mod webhook;
 
 use state::AppState;
 use std::path::PathBuf;
@@ -15,26 +35,35 @@ use tauri::Manager;
 
 /// Windows flag to prevent spawning a visible console window.
 #[cfg(windows)]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
+pub(crate) const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+pub(crate) fn ffmpeg_name() -> &'static str {
+    if cfg!(windows) { "ffmpeg.exe" } else { "ffmpeg" }
+}
+
+pub(crate) fn ffprobe_name() -> &'static str {
+    if cfg!(windows) { "ffprobe.exe" } else { "ffprobe" }
+}
 
 /// Return the path to FFmpeg, checking our AppData location first,
 /// then the sidecar location, then PATH fallback.
 pub fn ffmpeg_bin() -> PathBuf {
     // 1. Check our custom AppData/Local/ClipFlow/ location
     if let Some(data_dir) = dirs::data_local_dir() {
-        let custom = data_dir.join("ClipFlow").join(if cfg!(windows) { "ffmpeg.exe" } else { "ffmpeg" });
+        let custom = data_dir.join("ClipFlow").join(ffmpeg_name());
         if custom.exists() {
             return custom;
         }
     }
-    // 2. Fall back to sidecar location (next to exe)
-    ffmpeg_sidecar::paths::ffmpeg_path()
+    // 2. Sidecar location (next to exe)
+    let sidecar = std::env::current_exe().ok().and_then(|exe| Some(exe.parent()?.join(ffmpeg_name())));
+    // 3. Resolved through PATH
+    sidecar.filter(|p| p.exists()).unwrap_or_else(|| PathBuf::from(ffmpeg_name()))
 }
 
 /// Return the path to FFprobe (same directory as FFmpeg).
 pub fn ffprobe_bin() -> PathBuf {
-    let name = if cfg!(windows) { "ffprobe.exe" } else { "ffprobe" };
-    ffmpeg_bin().with_file_name(name)
+    ffmpeg_bin().with_file_name(ffprobe_name())
 }
 
 /// Create an async FFmpeg command with hidden console window on Windows.
@@ -66,6 +95,8 @@ pub fn ffprobe_command() -> tokio::process::Command {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    logging::init();
+
     // Set FFMPEG_DOWNLOAD_DIR so ffmpeg-sidecar downloads to a writable location
     // (not next to the exe, which may be in Program Files)
     if let Some(data_dir) = dirs::data_local_dir() {
@@ -87,50 +118,56 @@ pub fn run() {
                 }
             }
 
-            // Cleanup old temp files on startup (> 24h)
-            let temp_dir = dirs::data_local_dir()
-                .unwrap_or_else(|| std::path::PathBuf::from("."))
-                .join("ClipFlow")
-                .join("temp");
-            if temp_dir.exists() {
-                if let Ok(entries) = std::fs::read_dir(&temp_dir) {
-                    let cutoff = std::time::SystemTime::now() - std::time::Duration::from_secs(24 * 3600);
-                    for entry in entries.flatten() {
-                        if let Ok(meta) = entry.metadata() {
-                            if let Ok(modified) = meta.modified() {
-                                if modified < cutoff {
-                                    let _ = std::fs::remove_file(entry.path());
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+            // Recover captures from a crashed session, then cleanup old temp files per the storage policy
+            recording::recovery::start(app.handle().clone());
+
+            // Cache which filters / encoders the installed FFmpeg provides
+            ffmpeg::capabilities::start(app.handle().clone());
+
+            // Auto-pause recording on screen lock / display off
+            session::start_watcher(app.handle().clone());
+
+            // Auto-pause while a blacklisted app is in the foreground
+            privacy::start_watcher(app.handle().clone());
+
+            // Import new videos of the watched folder (OBS, ShareX...)
+            watch_folder::start_watcher(app.handle().clone());
 
             // Cleanup preview temp files
-            let preview_dir = std::env::temp_dir().join("clipflow_preview");
-            if preview_dir.exists() {
-                let _ = std::fs::remove_dir_all(&preview_dir);
-            }
+            storage::clear_previews();
 
             Ok(())
         })
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
-                .with_shortcuts(["F9", "F10", "Escape"])
+                .with_shortcuts(["F6", "F7", "F8", "F9", "F10", "Escape"])
                 .unwrap_or_else(|e| {
-                    eprintln!("[init] Failed to register shortcuts: {}", e);
+                    tracing::warn!("Failed to register shortcuts: {}", e);
                     tauri_plugin_global_shortcut::Builder::new()
                 })
                 .with_handler(hotkeys::handler)
                 .build(),
         )
+        .on_window_event(|window, event| {
+            // Files dropped on the main window are imported as clips / opened as projects
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                if window.label() == "main" {
+                    import::handle_drop(window.app_handle().clone(), paths.clone());
+                }
+            }
+        })
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_notification::init())
         .manage(Mutex::new(AppState::default()))
         .invoke_handler(tauri::generate_handler![
             commands::get_recording_state,
             commands::set_capture_region,
+            commands::set_capture_window,
+            commands::set_secondary_region,
+            commands::set_capture_monitors,
+            commands::set_follow_cursor,
+            commands::set_follow_focus,
+            commands::set_clip_dual_layout,
             commands::toggle_audio,
             commands::set_audio_source,
             commands::get_audio_source,
@@ -143,31 +180,162 @@ pub fn run() {
             commands::set_transition,
             commands::set_all_transitions,
             commands::set_clip_trim,
+            commands::set_audio_offset,
+            commands::detect_auto_trim,
+            commands::auto_trim_clips,
+            commands::get_speech_segments,
+            commands::analyze_audio,
+            commands::set_clip_zoom_keyframes,
             commands::start_recording,
             commands::stop_recording,
+            commands::record_retake,
+            commands::select_take,
             commands::pause_recording,
             commands::resume_recording,
             commands::cancel_recording,
             commands::get_recording_duration_ms,
+            commands::get_recording_stats,
             commands::get_thumbnail_base64,
+            commands::regenerate_thumbnail,
+            commands::insert_freeze,
+            commands::get_frames_at,
+            commands::render_waveform_image,
+            commands::get_live_snapshot,
             commands::get_transitions,
             commands::open_region_selector,
             commands::close_region_selector,
             commands::get_monitors_info,
             commands::export_video,
+            commands::debug_export_plan,
+            commands::export_video_multi,
+            commands::export_clips_separately,
+            commands::export_audio_visualizer,
+            commands::toggle_mic_mute,
+            commands::set_watch_folder,
+            commands::get_watch_folder,
+            commands::get_watch_pending,
+            commands::resolve_watch_pending,
+            commands::list_exported_videos,
+            commands::import_exported_videos,
+            commands::estimate_export_size,
+            commands::check_export_resolution,
+            commands::validate_export,
+            commands::get_export_presets,
+            commands::set_export_presets,
+            commands::get_safe_area_guides,
+            commands::set_export_encoder,
+            commands::get_export_encoder,
+            commands::set_export_fps,
+            commands::get_export_fps,
+            commands::set_export_downscale,
+            commands::get_export_downscale,
+            commands::set_hdr_tonemap,
+            commands::set_keep_filter_scripts,
+            commands::set_gif_palette,
+            commands::set_gif_loop,
+            commands::set_export_sidecar,
+            commands::set_export_chapters,
+            commands::generate_chapter_text,
+            commands::set_export_chunk_minutes,
             commands::preview_video,
+            commands::set_post_export_settings,
+            commands::get_post_export_settings,
+            commands::set_upload_targets,
+            commands::get_upload_targets,
+            commands::upload_export,
+            commands::set_webhooks,
+            commands::get_webhooks,
             commands::ensure_ffmpeg,
+            commands::get_ffmpeg_capabilities,
+            #[cfg(debug_assertions)]
+            commands::generate_test_clips,
             commands::get_visible_windows,
+            commands::get_monitor_work_areas,
+            commands::snap_region,
             commands::set_countdown,
             commands::get_countdown,
+            commands::set_lock_pause_settings,
+            commands::get_lock_pause_settings,
+            commands::set_recording_quality,
+            commands::get_recording_quality,
+            commands::set_recording_encoder,
+            commands::get_recording_encoders,
+            commands::set_capture_backend,
+            commands::get_capture_backends,
+            commands::set_segment_rollover,
+            commands::get_segment_rollover,
+            commands::set_adaptive_capture,
+            commands::get_adaptive_capture,
+            commands::set_capture_rate,
+            commands::get_capture_rate,
+            commands::get_capture_timing,
+            commands::set_pause_transition,
+            commands::get_pause_transition,
+            commands::set_privacy_blacklist,
+            commands::get_privacy_blacklist,
+            commands::get_clip_markers,
+            commands::list_recovered_recordings,
+            commands::restore_recovered_recording,
+            commands::discard_recovered_recording,
+            commands::get_storage_usage,
+            commands::clear_previews,
+            commands::clear_orphaned_temp,
+            commands::get_storage_policy,
+            commands::set_storage_policy,
             commands::set_clip_annotations,
             commands::get_clip_annotations,
+            commands::hit_test_annotation,
+            commands::reorder_annotations,
+            commands::group_annotations,
+            commands::ungroup_annotations,
+            commands::transform_annotations,
+            commands::set_global_annotations,
+            commands::get_global_annotations,
             commands::set_subtitles,
             commands::get_subtitles,
+            commands::check_subtitles,
+            commands::fit_subtitles,
+            commands::suggest_subtitle_durations,
+            commands::shift_subtitles,
+            commands::get_subtitle_languages,
+            commands::add_subtitle_language,
+            commands::set_active_subtitle_language,
+            commands::remove_subtitle_language,
+            commands::set_subtitle_export,
+            commands::start_voiceover,
+            commands::stop_voiceover,
+            commands::get_voiceovers,
+            commands::delete_voiceover,
             commands::toggle_keystroke_display,
+            commands::set_keystroke_preview,
             commands::get_keystroke_enabled,
+            commands::set_keystroke_font,
+            commands::get_keystroke_font,
+            commands::list_fonts,
             commands::toggle_cursor_zoom,
             commands::get_cursor_zoom_enabled,
+            commands::set_cursor_sidecar,
+            commands::toggle_gamepad,
+            commands::get_gamepad_enabled,
+            commands::export_cursor_heatmap,
+            commands::extract_frame,
+            commands::set_teleprompter,
+            commands::get_teleprompter,
+            commands::report_script_line,
+            commands::set_live_drawing,
+            commands::add_live_stroke,
+            commands::clear_live_strokes,
+            commands::get_recent_logs,
+            commands::open_log_folder,
+            commands::generate_diagnostics,
+            commands::check_for_updates,
+            commands::open_download_page,
+            commands::set_telemetry_settings,
+            commands::get_telemetry_settings,
+            commands::set_locale,
+            commands::get_locale,
+            commands::reveal_in_explorer,
+            commands::open_with_default_player,
             commands::copy_file_to_clipboard,
             commands::save_project,
             commands::load_project,
@@ -175,6 +343,11 @@ pub fn run() {
             commands::delete_project,
             commands::set_selected_mic,
             commands::get_selected_mic,
+            commands::set_selected_output,
+            commands::get_selected_output,
+            commands::toggle_mic_monitoring,
+            commands::get_mic_monitoring,
+            commands::set_audio_format,
         ])
         .run(tauri::generate_context!())
         .expect("error while running ClipFlow");
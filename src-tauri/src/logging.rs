@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt;
+use tracing_subscriber::prelude::*;
+
+const LOG_FILE_PREFIX: &str = "clipflow";
+const MAX_LOG_FILES: usize = 7;
+
+/// Keeps the background log writer alive for the whole process
+static WRITER_GUARD: OnceLock<tracing_appender::non_blocking::WorkerGuard> = OnceLock::new();
+
+/// `%LOCALAPPDATA%/ClipFlow/logs`
+pub fn log_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("ClipFlow")
+        .join("logs")
+}
+
+/// Log to stderr and to a daily-rotated file (last 7 days kept)
+pub fn init() {
+    let stderr_layer = fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_filter(if cfg!(debug_assertions) { LevelFilter::DEBUG } else { LevelFilter::INFO });
+
+    let file_layer = tracing_appender::rolling::Builder::new()
+        .rotation(tracing_appender::rolling::Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir())
+        .map(|appender| {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let _ = WRITER_GUARD.set(guard);
+            fmt::layer()
+                .with_writer(writer)
+                .with_ansi(false)
+                .with_filter(LevelFilter::INFO)
+        })
+        .map_err(|e| eprintln!("[logging] File logging disabled: {}", e))
+        .ok();
+
+    let _ = tracing_subscriber::registry()
+        .with(stderr_layer)
+        .with(file_layer)
+        .try_init();
+}
+
+/// Last `count` lines across the most recent log files, oldest first
+pub fn recent_lines(count: usize) -> Result<Vec<String>, String> {
+    let dir = log_dir();
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy().starts_with(LOG_FILE_PREFIX))
+                .unwrap_or(false)
+        })
+        .collect();
+    // Daily files are named clipflow.YYYY-MM-DD.log, so name order is date order
+    files.sort();
+
+    let mut lines: Vec<String> = Vec::new();
+    for file in files.iter().rev() {
+        if lines.len() >= count {
+            break;
+        }
+        let text = std::fs::read_to_string(file).unwrap_or_default();
+        let mut older = tail_lines(&text, count - lines.len());
+        older.append(&mut lines);
+        lines = older;
+    }
+    Ok(lines)
+}
+
+fn tail_lines(text: &str, count: usize) -> Vec<String> {
+    let all: Vec<&str> = text.lines().collect();
+    all[all.len().saturating_sub(count)..].iter().map(|s| s.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail_lines() {
+        assert_eq!(tail_lines("a\nb\nc\n", 2), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_tail_lines_short_text() {
+        assert_eq!(tail_lines("a\n", 5), vec!["a"]);
+        assert!(tail_lines("", 5).is_empty());
+    }
+}
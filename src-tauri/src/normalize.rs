@@ -0,0 +1,184 @@
+use crate::types::NormalizeProgress;
+use std::path::Path;
+use std::process::Stdio;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// Frame rate of the timeline, the one of the captures unless they follow
+/// the monitor's refresh rate
+pub const FPS: u32 = 30;
+
+/// Filters bringing any video to the timeline format at `fps`: constant
+/// frame rate, even size (libx264 yuv420p), square pixels
+pub fn filters(fps: u32) -> String {
+    format!("fps={fps},scale=trunc(iw/2)*2:trunc(ih/2)*2,setsar=1,format=yuv420p")
+}
+
+/// What decides whether a video joins the timeline as is
+#[derive(Debug, Default, PartialEq)]
+struct VideoFormat {
+    codec: String,
+    pix_fmt: String,
+    width: u32,
+    height: u32,
+    /// Declared and average frame rates, which differ for variable rate files
+    frame_rate: f64,
+    avg_frame_rate: f64,
+    sample_aspect_ratio: String,
+}
+
+impl VideoFormat {
+    /// H.264 4:2:0 at `fps`, like the captures: clips in this format
+    /// concatenate and crossfade without surprises
+    fn conforms(&self, fps: u32) -> bool {
+        self.codec == "h264"
+            && self.pix_fmt == "yuv420p"
+            && self.width % 2 == 0
+            && self.height % 2 == 0
+            && (self.frame_rate - fps as f64).abs() < 0.01
+            && (self.avg_frame_rate - fps as f64).abs() < 0.5
+            && matches!(self.sample_aspect_ratio.as_str(), "1:1" | "0:1" | "N/A" | "")
+    }
+}
+
+/// Whether `path` has to go through `transcode` at `fps` before joining
+/// the timeline. Unreadable files are left to the step that reads them.
+pub async fn needs_normalizing(path: &Path, fps: u32) -> bool {
+    match probe(path).await {
+        Some(format) if !format.conforms(fps) => {
+            tracing::info!("{:?} needs normalizing: {:?}", path.file_name().unwrap_or_default(), format);
+            true
+        }
+        _ => false,
+    }
+}
+
+async fn probe(path: &Path) -> Option<VideoFormat> {
+    let output = crate::ffprobe_command()
+        .args([
+            "-v", "error",
+            "-select_streams", "v:0",
+            "-show_entries", "stream=codec_name,pix_fmt,width,height,r_frame_rate,avg_frame_rate,sample_aspect_ratio",
+            "-of", "default=noprint_wrappers=1",
+            &path.to_string_lossy(),
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .await
+        .ok()?;
+    parse_format(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_format(s: &str) -> Option<VideoFormat> {
+    let mut format = VideoFormat::default();
+    for line in s.lines() {
+        match line.trim().split_once('=') {
+            Some(("codec_name", v)) => format.codec = v.to_string(),
+            Some(("pix_fmt", v)) => format.pix_fmt = v.to_string(),
+            Some(("width", v)) => format.width = v.parse().ok()?,
+            Some(("height", v)) => format.height = v.parse().ok()?,
+            Some(("r_frame_rate", v)) => format.frame_rate = parse_rate(v),
+            Some(("avg_frame_rate", v)) => format.avg_frame_rate = parse_rate(v),
+            Some(("sample_aspect_ratio", v)) => format.sample_aspect_ratio = v.to_string(),
+            _ => {}
+        }
+    }
+    (!format.codec.is_empty()).then_some(format)
+}
+
+/// "30000/1001" → 29.97, 0 when unknown
+fn parse_rate(s: &str) -> f64 {
+    match s.split_once('/') {
+        Some((n, d)) => match (n.parse::<f64>(), d.parse::<f64>()) {
+            (Ok(n), Ok(d)) if d > 0.0 => n / d,
+            _ => 0.0,
+        },
+        None => s.parse().unwrap_or(0.0),
+    }
+}
+
+/// Re-encode the video of `input` to `output` in the timeline format at
+/// `fps`, reporting progress under `name`. Sound is left out: clips keep
+/// theirs in separate tracks.
+pub async fn transcode(app: &AppHandle, input: &Path, output: &Path, name: &str, fps: u32) -> Result<(), String> {
+    let duration_s = crate::export::encoder::probe_duration(&input.to_path_buf()).await.unwrap_or(0.0);
+    let progress = |percent: u32| {
+        let _ = app.emit("normalize-progress", NormalizeProgress { name: name.to_string(), percent });
+    };
+    progress(0);
+
+    let mut child = crate::ffmpeg_command()
+        .args([
+            "-i", &input.to_string_lossy(),
+            "-map", "0:v:0",
+            "-vf", &filters(fps),
+            "-c:v", "libx264", "-preset", "veryfast", "-crf", "18",
+            "-an", "-movflags", "+faststart",
+            "-y", &output.to_string_lossy(),
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+    let mut stderr_log = String::new();
+    if let Some(stderr) = child.stderr.take() {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let Some(t) = crate::export::encoder::extract_time(&line).filter(|_| duration_s > 0.0) {
+                progress((t / duration_s * 100.0).min(99.0) as u32);
+            }
+            stderr_log.push_str(&line);
+            stderr_log.push('\n');
+        }
+    }
+    let status = child.wait().await.map_err(|e| e.to_string())?;
+    progress(100);
+    if !status.success() {
+        let _ = std::fs::remove_file(output);
+        return Err(crate::i18n::tf(
+            "conversion_failed",
+            &[&crate::export::encoder::translate_ffmpeg_error(&stderr_log)],
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_conforms() {
+        let out = "codec_name=h264\nwidth=1920\nheight=1080\npix_fmt=yuv420p\nsample_aspect_ratio=1:1\n\
+                   r_frame_rate=30/1\navg_frame_rate=30/1\n";
+        assert!(parse_format(out).unwrap().conforms(FPS));
+        // Refresh-aware captures conform at their own rate
+        let fast = out.replace("30/1", "72/1");
+        assert!(parse_format(&fast).unwrap().conforms(72));
+        assert!(!parse_format(&fast).unwrap().conforms(FPS));
+    }
+
+    #[test]
+    fn test_other_sources_need_normalizing() {
+        let phone = "codec_name=hevc\nwidth=1080\nheight=1920\npix_fmt=yuv420p10le\n\
+                     r_frame_rate=60/1\navg_frame_rate=59.8/1\n";
+        assert!(!parse_format(phone).unwrap().conforms(FPS));
+        // Variable frame rate: declared 30, averaging far less
+        let vfr = "codec_name=h264\nwidth=1280\nheight=720\npix_fmt=yuv420p\n\
+                   r_frame_rate=30/1\navg_frame_rate=1200/71\n";
+        assert!(!parse_format(vfr).unwrap().conforms(FPS));
+        let lossless = "codec_name=h264\nwidth=1280\nheight=720\npix_fmt=gbrp\n\
+                        r_frame_rate=30/1\navg_frame_rate=30/1\n";
+        assert!(!parse_format(lossless).unwrap().conforms(FPS));
+        assert_eq!(parse_format("width=2\n"), None);
+    }
+
+    #[test]
+    fn test_parse_rate() {
+        assert!((parse_rate("30000/1001") - 29.97).abs() < 0.01);
+        assert_eq!(parse_rate("0/0"), 0.0);
+        assert_eq!(parse_rate("25"), 25.0);
+    }
+}
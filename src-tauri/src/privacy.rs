@@ -0,0 +1,160 @@
+use crate::events::{self, AppEvent};
+use crate::recording::manager;
+use crate::state::AppState;
+use crate::types::{MarkerKind, RecordingState, TimelineMarker};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const AUTO_PAUSE_REASON: &str = "privacy";
+const POLL_INTERVAL_MS: u64 = 500;
+
+/// Start a background thread that pauses the recording whenever a
+/// blacklisted process or window title is in the foreground.
+pub fn start_watcher(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+
+        let state = app.state::<Mutex<AppState>>();
+        let (recording_state, paused_by_us, patterns) = match state.lock() {
+            Ok(s) => (
+                s.recording_state,
+                s.auto_pause_reason.as_deref() == Some(AUTO_PAUSE_REASON),
+                s.privacy_blacklist.clone(),
+            ),
+            Err(_) => continue,
+        };
+
+        if recording_state == RecordingState::Idle || (patterns.is_empty() && !paused_by_us) {
+            continue;
+        }
+
+        let (process, title) = foreground_window_info();
+        let matched = match_blacklist(&process, &title, &patterns);
+
+        match (recording_state, matched) {
+            (RecordingState::Recording, Some(pattern)) => {
+                let app = app.clone();
+                tauri::async_runtime::block_on(async move {
+                    let state = app.state::<Mutex<AppState>>();
+                    match manager::auto_pause(&state, &app, AUTO_PAUSE_REASON).await {
+                        Ok(true) => {
+                            tracing::info!("Paused: '{}' in foreground", pattern);
+                            add_gap_marker(&state, &pattern);
+                        }
+                        Ok(false) => {}
+                        Err(e) => {
+                            tracing::warn!("Auto-pause failed: {}", e);
+                            events::emit(&app, AppEvent::RecordingError { message: e });
+                        }
+                    }
+                });
+            }
+            (RecordingState::Paused, None) if paused_by_us => {
+                match manager::auto_resume(&state, &app, AUTO_PAUSE_REASON) {
+                    Ok(true) => tracing::info!("Resumed"),
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::warn!("Auto-resume failed: {}", e);
+                        events::emit(&app, AppEvent::RecordingError { message: e });
+                    }
+                }
+            }
+            _ => {}
+        }
+    });
+}
+
+/// Mark the cut in the timeline so the user knows footage was skipped
+fn add_gap_marker(state: &Mutex<AppState>, pattern: &str) {
+    if let Ok(mut s) = state.lock() {
+        let timestamp_ms = crate::recording::manager::elapsed_ms(&s);
+        s.pending_markers.push(TimelineMarker {
+            timestamp_ms,
+            label: crate::i18n::tf("privacy_marker", &[&pattern]),
+            kind: MarkerKind::Privacy,
+            end_ms: None,
+        });
+    }
+}
+
+/// Return the first pattern matching the process name or window title
+/// (case-insensitive substring match).
+fn match_blacklist(process: &str, title: &str, patterns: &[String]) -> Option<String> {
+    let process = process.to_lowercase();
+    let title = title.to_lowercase();
+    patterns
+        .iter()
+        .find(|p| {
+            let p = p.to_lowercase();
+            !p.is_empty() && (process.contains(&p) || title.contains(&p))
+        })
+        .cloned()
+}
+
+/// Executable name and title of the current foreground window
+fn foreground_window_info() -> (String, String) {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_invalid() {
+            return (String::new(), String::new());
+        }
+
+        let mut title_buf = [0u16; 256];
+        let len = GetWindowTextW(hwnd, &mut title_buf);
+        let title = String::from_utf16_lossy(&title_buf[..len.max(0) as usize]);
+
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid as *mut u32));
+        let mut process = String::new();
+        if let Ok(handle) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) {
+            let mut buf = [0u16; 260];
+            let mut size = buf.len() as u32;
+            if QueryFullProcessImageNameW(handle, PROCESS_NAME_WIN32, windows::core::PWSTR(buf.as_mut_ptr()), &mut size).is_ok() {
+                let path = String::from_utf16_lossy(&buf[..size as usize]);
+                process = std::path::Path::new(&path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or(path);
+            }
+            let _ = CloseHandle(handle);
+        }
+
+        (process, title)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_process_name() {
+        let patterns = vec!["KeePass".to_string()];
+        assert_eq!(match_blacklist("KeePass.exe", "Database", &patterns), Some("KeePass".to_string()));
+    }
+
+    #[test]
+    fn test_match_title_case_insensitive() {
+        let patterns = vec!["ma banque".to_string()];
+        assert!(match_blacklist("chrome.exe", "Ma Banque - Comptes", &patterns).is_some());
+    }
+
+    #[test]
+    fn test_no_match() {
+        let patterns = vec!["1password".to_string()];
+        assert!(match_blacklist("code.exe", "main.rs", &patterns).is_none());
+    }
+
+    #[test]
+    fn test_empty_pattern_ignored() {
+        let patterns = vec![String::new()];
+        assert!(match_blacklist("code.exe", "main.rs", &patterns).is_none());
+    }
+}
@@ -1,6 +1,10 @@
 use crate::types::*;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Start of a `.cursor` sidecar: then, per clip, its id and samples
+/// (u32 ms, x and y as u16 fractions), all little-endian
+const CURSOR_MAGIC: &[u8; 4] = b"CFC1";
 
 fn projects_dir() -> Result<PathBuf, String> {
     let dir = dirs::data_local_dir()
@@ -19,6 +23,12 @@ pub fn save_project(
     audio_source: AudioSource,
     annotations: &HashMap<String, Vec<Annotation>>,
     subtitles: &[Subtitle],
+    subtitle_language: &str,
+    subtitle_tracks: &[SubtitleTrack],
+    cursor_positions: &HashMap<String, Vec<CursorPosition>>,
+    cursor_sidecar: bool,
+    voiceovers: &[Voiceover],
+    global_annotations: &[Annotation],
 ) -> Result<String, String> {
     let now = chrono::Local::now().to_rfc3339();
     let project_id = existing_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
@@ -38,10 +48,21 @@ pub fn save_project(
         },
         annotations: annotations.clone(),
         subtitles: subtitles.to_vec(),
+        subtitle_language: subtitle_language.to_string(),
+        subtitle_tracks: subtitle_tracks.to_vec(),
+        cursor_positions: if cursor_sidecar { HashMap::new() } else { cursor_positions.clone() },
+        voiceovers: voiceovers.to_vec(),
+        global_annotations: global_annotations.to_vec(),
     };
 
     let dir = projects_dir()?;
     let file_path = dir.join(format!("{}.json", project_id));
+    let sidecar_path = file_path.with_extension("cursor");
+    if cursor_sidecar && !cursor_positions.is_empty() {
+        std::fs::write(&sidecar_path, encode_cursor_sidecar(cursor_positions)).map_err(|e| e.to_string())?;
+    } else if sidecar_path.exists() {
+        std::fs::remove_file(&sidecar_path).map_err(|e| e.to_string())?;
+    }
     let json = serde_json::to_string_pretty(&project).map_err(|e| e.to_string())?;
     std::fs::write(&file_path, json).map_err(|e| e.to_string())?;
 
@@ -57,13 +78,102 @@ pub fn load_project(project_id: &str) -> Result<Project, String> {
     }
 
     let json = std::fs::read_to_string(&file_path).map_err(|e| e.to_string())?;
-    let project: Project = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    let mut project: Project = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    read_cursor_sidecar(&file_path, &mut project)?;
     Ok(project)
 }
 
-pub fn list_projects() -> Result<Vec<ProjectSummary>, String> {
+/// Read a project JSON from an arbitrary location (e.g. a dropped file)
+pub fn load_project_file(path: &Path) -> Result<Project, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut project: Project = serde_json::from_str(&json).map_err(|e| crate::i18n::tf("project_invalid", &[&e]))?;
+    read_cursor_sidecar(path, &mut project)?;
+    Ok(project)
+}
+
+/// Cursor paths from the `.cursor` file next to a project JSON, if any
+fn read_cursor_sidecar(project_path: &Path, project: &mut Project) -> Result<(), String> {
+    let sidecar_path = project_path.with_extension("cursor");
+    if project.cursor_positions.is_empty() && sidecar_path.exists() {
+        let bytes = std::fs::read(&sidecar_path).map_err(|e| e.to_string())?;
+        project.cursor_positions = decode_cursor_sidecar(&bytes)?;
+    }
+    Ok(())
+}
+
+fn encode_cursor_sidecar(data: &HashMap<String, Vec<CursorPosition>>) -> Vec<u8> {
+    let mut ids: Vec<&String> = data.keys().collect();
+    ids.sort();
+    let mut out = CURSOR_MAGIC.to_vec();
+    out.extend_from_slice(&(ids.len() as u32).to_le_bytes());
+    for id in ids {
+        let positions = &data[id];
+        out.extend_from_slice(&(id.len() as u16).to_le_bytes());
+        out.extend_from_slice(id.as_bytes());
+        out.extend_from_slice(&(positions.len() as u32).to_le_bytes());
+        for p in positions {
+            out.extend_from_slice(&u32::try_from(p.timestamp_ms).unwrap_or(u32::MAX).to_le_bytes());
+            out.extend_from_slice(&((p.x.clamp(0.0, 1.0) * 65535.0).round() as u16).to_le_bytes());
+            out.extend_from_slice(&((p.y.clamp(0.0, 1.0) * 65535.0).round() as u16).to_le_bytes());
+        }
+    }
+    out
+}
+
+/// Next `n` bytes of a sidecar
+fn take<'a>(bytes: &'a [u8], pos: &mut usize, n: usize) -> Result<&'a [u8], String> {
+    let chunk = bytes.get(*pos..*pos + n).ok_or_else(|| crate::i18n::t("cursor_file_truncated"))?;
+    *pos += n;
+    Ok(chunk)
+}
+
+fn decode_cursor_sidecar(bytes: &[u8]) -> Result<HashMap<String, Vec<CursorPosition>>, String> {
+    let mut pos = 0;
+    let mut next = |n: usize| take(bytes, &mut pos, n);
+    if next(4)? != CURSOR_MAGIC {
+        return Err(crate::i18n::t("cursor_file_invalid"));
+    }
+    let u16_at = |b: &[u8]| u16::from_le_bytes([b[0], b[1]]);
+    let u32_at = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+
+    let mut data = HashMap::new();
+    for _ in 0..u32_at(next(4)?) {
+        let id_len = u16_at(next(2)?) as usize;
+        let id = String::from_utf8(next(id_len)?.to_vec()).map_err(|e| e.to_string())?;
+        let count = u32_at(next(4)?) as usize;
+        let mut positions = Vec::with_capacity(count.min(bytes.len() / 8));
+        for _ in 0..count {
+            let b = next(8)?;
+            positions.push(CursorPosition {
+                timestamp_ms: u32_at(&b[0..4]) as u64,
+                x: u16_at(&b[4..6]) as f64 / 65535.0,
+                y: u16_at(&b[6..8]) as f64 / 65535.0,
+            });
+        }
+        data.insert(id, positions);
+    }
+    Ok(data)
+}
+
+/// Replace the current timeline with `project`
+pub fn apply_to_state(s: &mut crate::state::AppState, project: Project) {
+    s.clips = project.clips;
+    s.transitions = project.transitions;
+    s.audio_source = project.settings.audio_source;
+    s.annotations = project.annotations;
+    s.subtitles = project.subtitles;
+    s.subtitle_language = project.subtitle_language;
+    s.subtitle_tracks = project.subtitle_tracks;
+    s.clip_cursor_positions = project.cursor_positions;
+    s.voiceovers = project.voiceovers;
+    s.global_annotations = project.global_annotations;
+    s.current_project_id = Some(project.id);
+}
+
+/// Every readable project of the projects folder, unsorted
+fn read_projects() -> Result<Vec<Project>, String> {
     let dir = projects_dir()?;
-    let mut summaries = Vec::new();
+    let mut projects = Vec::new();
 
     if let Ok(entries) = std::fs::read_dir(&dir) {
         for entry in entries.flatten() {
@@ -71,28 +181,51 @@ pub fn list_projects() -> Result<Vec<ProjectSummary>, String> {
             if path.extension().map(|e| e == "json").unwrap_or(false) {
                 if let Ok(json) = std::fs::read_to_string(&path) {
                     if let Ok(project) = serde_json::from_str::<Project>(&json) {
-                        let total_duration_ms: u64 = project.clips.iter()
-                            .map(|c| c.duration_ms)
-                            .sum();
-                        summaries.push(ProjectSummary {
-                            id: project.id,
-                            name: project.name,
-                            created_at: project.created_at,
-                            updated_at: project.updated_at,
-                            clip_count: project.clips.len(),
-                            total_duration_ms,
-                        });
+                        projects.push(project);
                     }
                 }
             }
         }
     }
+    Ok(projects)
+}
+
+pub fn list_projects() -> Result<Vec<ProjectSummary>, String> {
+    let mut summaries: Vec<ProjectSummary> = read_projects()?
+        .into_iter()
+        .map(|project| ProjectSummary {
+            total_duration_ms: project.clips.iter().map(|c| c.media.duration_ms).sum(),
+            clip_count: project.clips.len(),
+            id: project.id,
+            name: project.name,
+            created_at: project.created_at,
+            updated_at: project.updated_at,
+        })
+        .collect();
 
     // Sort by updated_at descending
     summaries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
     Ok(summaries)
 }
 
+/// Clips of all saved projects, whose media live in the temp dir
+pub fn saved_clips() -> Vec<Clip> {
+    read_projects()
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|project| project.clips)
+        .collect()
+}
+
+/// Narrations of all saved projects, recorded in the temp dir
+pub fn saved_voiceovers() -> Vec<Voiceover> {
+    read_projects()
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|project| project.voiceovers)
+        .collect()
+}
+
 pub fn delete_project(project_id: &str) -> Result<(), String> {
     let dir = projects_dir()?;
     let file_path = dir.join(format!("{}.json", project_id));
@@ -100,6 +233,40 @@ pub fn delete_project(project_id: &str) -> Result<(), String> {
     if file_path.exists() {
         std::fs::remove_file(&file_path).map_err(|e| e.to_string())?;
     }
+    let _ = std::fs::remove_file(file_path.with_extension("cursor"));
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_sidecar_round_trip() {
+        let data = HashMap::from([
+            ("a".to_string(), vec![
+                CursorPosition { timestamp_ms: 0, x: 0.0, y: 1.0 },
+                CursorPosition { timestamp_ms: 3_600_000, x: 0.25, y: 0.5 },
+            ]),
+            ("b".to_string(), Vec::new()),
+        ]);
+        let bytes = encode_cursor_sidecar(&data);
+        // 4 magic + 4 count, then 2 + 1 + 4 + 2 * 8 and 2 + 1 + 4
+        assert_eq!(bytes.len(), 8 + 23 + 7);
+
+        let decoded = decode_cursor_sidecar(&bytes).unwrap();
+        assert!(decoded["b"].is_empty());
+        let a = &decoded["a"];
+        assert_eq!(a[1].timestamp_ms, 3_600_000);
+        assert!((a[1].x - 0.25).abs() < 1e-4 && (a[1].y - 0.5).abs() < 1e-4);
+        assert!((a[0].y - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cursor_sidecar_rejects_bad_input() {
+        assert!(decode_cursor_sidecar(b"nope").is_err());
+        let bytes = encode_cursor_sidecar(&HashMap::from([("a".to_string(), vec![CursorPosition { timestamp_ms: 5, x: 0.5, y: 0.5 }])]));
+        assert!(decode_cursor_sidecar(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use crate::events::{self, AppEvent};
+use crate::recording::manager;
+use crate::state::AppState;
+use crate::types::{RecordingCodec, RecordingQuality, RecordingState};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const CHECK_INTERVAL_MS: u64 = 2000;
+/// Samples in a row over the threshold before lightening the capture (6 s)
+const BUSY_SAMPLES: u32 = 3;
+/// Samples in a row well under it before going back up a level (30 s)
+const CALM_SAMPLES: u32 = 15;
+/// How far under the threshold counts as calm, so a level that just brought
+/// usage under the threshold isn't undone on the next sample
+const CALM_MARGIN: u32 = 20;
+/// Lightest level
+pub const MAX_LEVEL: u32 = 3;
+/// CRF added at each level
+const CRF_STEP: u32 = 4;
+
+/// Capture settings `level` steps lighter than the chosen ones. Only the
+/// CRF moves: segments are joined by stream copy, which needs the same
+/// preset and frame rate in all of them.
+pub fn settings(quality: &RecordingQuality, level: u32) -> RecordingQuality {
+    let mut quality = quality.clone();
+    if adjustable(&quality) {
+        quality.crf = (quality.crf + CRF_STEP * level.min(MAX_LEVEL)).min(51);
+    }
+    quality
+}
+
+/// Whether `settings` can lighten this capture: the lossless codecs have no CRF
+pub fn adjustable(quality: &RecordingQuality) -> bool {
+    quality.codec == RecordingCodec::X264
+}
+
+/// Watch system CPU usage while recording and switch the capture to a higher
+/// or lower CRF, so recording doesn't slow down the application being demoed. Each switch starts a new segment, concatenated on stop like a
+/// pause. Exits when the session ends.
+pub fn spawn_watcher(app: AppHandle, session: u64) {
+    tauri::async_runtime::spawn(async move {
+        watch(app, session).await;
+    });
+}
+
+async fn watch(app: AppHandle, session: u64) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut load = Load::default();
+    let mut previous = cpu_times();
+    loop {
+        tokio::time::sleep(Duration::from_millis(CHECK_INTERVAL_MS)).await;
+        let times = cpu_times();
+        let usage = previous.zip(times).and_then(|(a, b)| usage_percent(a, b));
+        previous = times;
+
+        let (threshold, level) = {
+            let Ok(s) = state.lock() else {
+                return;
+            };
+            if s.recording_session != session || s.recording_state == RecordingState::Idle {
+                return;
+            }
+            if s.recording_state != RecordingState::Recording {
+                load = Load::default();
+                continue;
+            }
+            (s.adaptive_capture.cpu_threshold, s.capture_level)
+        };
+        let Some(usage) = usage else {
+            continue;
+        };
+        let Some(next) = load.next_level(usage, threshold, level) else {
+            continue;
+        };
+        tracing::info!("CPU at {}%, capture level {} -> {}", usage, level, next);
+
+        if let Err(e) = manager::close_segment(&state).await {
+            tracing::warn!("Failed to close segment: {}", e);
+            continue;
+        }
+        match state.lock() {
+            Ok(mut s) => s.capture_level = next,
+            Err(_) => return,
+        }
+        if let Err(e) = manager::open_segment(&state) {
+            tracing::warn!("Failed to open next segment: {}", e);
+            events::emit(&app, AppEvent::RecordingError { message: e });
+            continue;
+        }
+        events::emit(&app, AppEvent::CaptureAdapted { level: next, cpu_percent: usage });
+    }
+}
+
+/// Samples in a row above and well under the threshold
+#[derive(Debug, Default)]
+struct Load {
+    busy: u32,
+    calm: u32,
+}
+
+impl Load {
+    /// Level to switch to after a `usage` sample at `level`, if any
+    fn next_level(&mut self, usage: u32, threshold: u32, level: u32) -> Option<u32> {
+        if usage > threshold {
+            self.busy += 1;
+            self.calm = 0;
+        } else if usage + CALM_MARGIN < threshold {
+            self.calm += 1;
+            self.busy = 0;
+        } else {
+            self.busy = 0;
+            self.calm = 0;
+        }
+
+        let next = if self.busy >= BUSY_SAMPLES && level < MAX_LEVEL {
+            level + 1
+        } else if self.calm >= CALM_SAMPLES && level > 0 {
+            level - 1
+        } else {
+            return None;
+        };
+        // Starting the new capture weighs on the CPU too: count afresh
+        *self = Load::default();
+        Some(next)
+    }
+}
+
+/// (idle, total) CPU time of every core since boot, in 100 ns units
+fn cpu_times() -> Option<(u64, u64)> {
+    use windows::Win32::Foundation::FILETIME;
+    use windows::Win32::System::Threading::GetSystemTimes;
+
+    let (mut idle, mut kernel, mut user) = (FILETIME::default(), FILETIME::default(), FILETIME::default());
+    unsafe {
+        GetSystemTimes(
+            Some(&mut idle as *mut FILETIME),
+            Some(&mut kernel as *mut FILETIME),
+            Some(&mut user as *mut FILETIME),
+        )
+        .ok()?;
+    }
+    let ticks = |t: FILETIME| ((t.dwHighDateTime as u64) << 32) | t.dwLowDateTime as u64;
+    // Kernel time includes the idle time
+    Some((ticks(idle), ticks(kernel) + ticks(user)))
+}
+
+/// Busy share of the CPU between two `cpu_times` samples, 0-100
+fn usage_percent(before: (u64, u64), after: (u64, u64)) -> Option<u32> {
+    let idle = after.0.checked_sub(before.0)?;
+    let total = after.1.checked_sub(before.1)?;
+    if total == 0 {
+        return None;
+    }
+    Some((100 - (idle * 100 / total).min(100)) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_only_raise_crf() {
+        let quality = RecordingQuality { crf: 18, preset: "veryfast".into(), ..Default::default() };
+        assert_eq!(settings(&quality, 0).crf, 18);
+        let q = settings(&quality, 1);
+        assert_eq!((q.crf, q.preset.as_str()), (22, "veryfast"));
+        assert_eq!(settings(&quality, MAX_LEVEL).crf, 30);
+        assert_eq!(settings(&RecordingQuality { crf: 45, ..quality }, MAX_LEVEL).crf, 51);
+
+        let lossless = RecordingQuality { codec: RecordingCodec::UtVideo, ..Default::default() };
+        assert_eq!(settings(&lossless, 1).crf, lossless.crf);
+        assert!(!adjustable(&lossless));
+    }
+
+    #[test]
+    fn test_steps_down_only_when_busy_for_a_while() {
+        let mut load = Load::default();
+        assert_eq!(load.next_level(95, 85, 0), None);
+        assert_eq!(load.next_level(70, 85, 0), None);
+        assert_eq!(load.next_level(95, 85, 0), None);
+        assert_eq!(load.next_level(95, 85, 0), None);
+        assert_eq!(load.next_level(95, 85, 0), Some(1));
+        // Counting starts over after a switch
+        assert_eq!(load.next_level(95, 85, 1), None);
+
+        let mut load = Load::default();
+        for _ in 0..BUSY_SAMPLES {
+            assert_eq!(load.next_level(100, 85, MAX_LEVEL), None);
+        }
+    }
+
+    #[test]
+    fn test_steps_back_up_when_calm() {
+        let mut load = Load::default();
+        for _ in 1..CALM_SAMPLES {
+            assert_eq!(load.next_level(40, 85, 2), None);
+        }
+        assert_eq!(load.next_level(40, 85, 2), Some(1));
+
+        // Just under the threshold is not calm
+        let mut load = Load::default();
+        for _ in 0..CALM_SAMPLES * 2 {
+            assert_eq!(load.next_level(80, 85, 2), None);
+        }
+    }
+
+    #[test]
+    fn test_usage_percent() {
+        assert_eq!(usage_percent((100, 1000), (150, 1200)), Some(75));
+        assert_eq!(usage_percent((100, 1000), (100, 1000)), None);
+        assert_eq!(usage_percent((100, 1000), (50, 1200)), None);
+    }
+}
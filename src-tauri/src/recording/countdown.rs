@@ -0,0 +1,113 @@
+use crate::events::{self, AppEvent};
+use crate::state::AppState;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+const COUNTDOWN_WINDOW: &str = "countdown";
+
+/// Run the pre-recording countdown.
+/// Shows a fullscreen overlay, emits one `countdown-tick` per second and
+/// returns `Ok(true)` at zero, or `Ok(false)` if it was aborted (Escape).
+pub async fn run(state: &Mutex<AppState>, app: &AppHandle, seconds: u32) -> Result<bool, String> {
+    let abort_flag = Arc::new(AtomicBool::new(false));
+    {
+        let mut s = state.lock().map_err(|e| e.to_string())?;
+        if s.countdown_abort.is_some() {
+            return Err(crate::i18n::t("countdown_in_progress"));
+        }
+        s.countdown_abort = Some(abort_flag.clone());
+    }
+
+    open_overlay(app);
+
+    let mut remaining = seconds;
+    while remaining > 0 && !abort_flag.load(Ordering::Relaxed) {
+        events::emit(app, AppEvent::CountdownTick { remaining });
+        // Sleep in small steps so an abort is picked up quickly
+        for _ in 0..10 {
+            if abort_flag.load(Ordering::Relaxed) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        remaining -= 1;
+    }
+
+    // Close the overlay before capture starts so it never ends up in the video
+    close_overlay(app);
+    if let Ok(mut s) = state.lock() {
+        s.countdown_abort = None;
+    }
+
+    if abort_flag.load(Ordering::Relaxed) {
+        tracing::info!("Aborted");
+        events::emit(app, AppEvent::CountdownAborted);
+        return Ok(false);
+    }
+
+    events::emit(app, AppEvent::CountdownTick { remaining: 0 });
+    Ok(true)
+}
+
+/// Abort a running countdown. Returns true if one was active.
+pub fn abort(state: &Mutex<AppState>) -> bool {
+    let Ok(s) = state.lock() else {
+        return false;
+    };
+    match s.countdown_abort {
+        Some(ref flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+fn open_overlay(app: &AppHandle) {
+    if let Some(w) = app.get_webview_window(COUNTDOWN_WINDOW) {
+        let _ = w.close();
+    }
+
+    let (x, y, width, height) = match app.primary_monitor() {
+        Ok(Some(m)) => {
+            let scale = m.scale_factor();
+            let pos = m.position();
+            let size = m.size();
+            (
+                pos.x as f64 / scale,
+                pos.y as f64 / scale,
+                size.width as f64 / scale,
+                size.height as f64 / scale,
+            )
+        }
+        _ => (0.0, 0.0, 1920.0, 1080.0),
+    };
+
+    let window = WebviewWindowBuilder::new(app, COUNTDOWN_WINDOW, WebviewUrl::App("/countdown".into()))
+        .title("Countdown")
+        .position(x, y)
+        .inner_size(width, height)
+        .decorations(false)
+        .transparent(true)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .focused(false)
+        .build();
+
+    match window {
+        // Let clicks pass through to whatever is being recorded
+        Ok(w) => {
+            let _ = w.set_ignore_cursor_events(true);
+        }
+        Err(e) => tracing::warn!("Failed to open overlay: {}", e),
+    }
+}
+
+fn close_overlay(app: &AppHandle) {
+    if let Some(w) = app.get_webview_window(COUNTDOWN_WINDOW) {
+        let _ = w.close();
+    }
+}
@@ -0,0 +1,200 @@
+use crate::events::{self, AppEvent};
+use crate::state::AppState;
+use crate::types::{
+    AnimatedProperty, Annotation, AnnotationKind, DrawingTool, Easing, Keyframe, LiveStroke, RecordingState, Region,
+};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder};
+
+const DRAWING_WINDOW: &str = "drawing";
+/// Stroke widths in pixels of the recorded area
+const PEN_WIDTH: f64 = 4.0;
+const HIGHLIGHTER_WIDTH: f64 = 18.0;
+/// Highlighter strokes stay see-through in the export
+const HIGHLIGHTER_OPACITY: f64 = 0.45;
+/// End time of strokes still on screen
+const OPEN_END: u64 = u64::MAX;
+
+/// Open the drawing overlay over the recorded area, click-through until a
+/// tool is picked. Hidden from the capture itself: strokes reach the video
+/// as annotations, not as captured pixels.
+pub fn open_overlay(app: &AppHandle, region: Option<Region>) {
+    if let Some(w) = app.get_webview_window(DRAWING_WINDOW) {
+        let _ = w.close();
+    }
+
+    let (x, y, width, height) = match region {
+        Some(r) => (r.x, r.y, r.width, r.height),
+        None => match app.primary_monitor() {
+            Ok(Some(m)) => (m.position().x, m.position().y, m.size().width, m.size().height),
+            _ => (0, 0, 1920, 1080),
+        },
+    };
+
+    let window = WebviewWindowBuilder::new(app, DRAWING_WINDOW, WebviewUrl::App("/drawing".into()))
+        .title("Drawing")
+        .decorations(false)
+        .transparent(true)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .focused(false)
+        .visible(false)
+        .build();
+    let window = match window {
+        Ok(w) => w,
+        Err(e) => {
+            tracing::warn!("Failed to open drawing overlay: {}", e);
+            return;
+        }
+    };
+    // The region is in physical pixels
+    let _ = window.set_position(PhysicalPosition::new(x, y));
+    let _ = window.set_size(PhysicalSize::new(width, height));
+    let _ = window.set_ignore_cursor_events(true);
+    exclude_from_capture(&window);
+    let _ = window.show();
+
+    let state = app.state::<Mutex<AppState>>();
+    let Ok(mut s) = state.lock() else { return };
+    // The recording may have ended while the window was being built
+    if s.recording_state == RecordingState::Idle {
+        let _ = window.close();
+        return;
+    }
+    s.drawing_tool = None;
+    s.drawing_window = Some(window);
+}
+
+/// Keep one of our windows out of every screen capture
+pub(super) fn exclude_from_capture(window: &tauri::WebviewWindow) {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE};
+
+    match window.hwnd() {
+        Ok(hwnd) => {
+            if let Err(e) = unsafe { SetWindowDisplayAffinity(HWND(hwnd.0), WDA_EXCLUDEFROMCAPTURE) } {
+                tracing::warn!("Window {} will be visible in the capture: {}", window.label(), e);
+            }
+        }
+        Err(e) => tracing::warn!("Window {} handle unavailable: {}", window.label(), e),
+    }
+}
+
+/// Close the overlay and forget the tool; strokes are left to the caller
+pub fn close_overlay(s: &mut AppState) {
+    if let Some(w) = s.drawing_window.take() {
+        let _ = w.close();
+    }
+    s.drawing_tool = None;
+}
+
+/// Pick `tool`, or go back to click-through when it is already the active one
+pub fn toggle_tool(app: &AppHandle, tool: DrawingTool) {
+    let state = app.state::<Mutex<AppState>>();
+    let Ok(mut s) = state.lock() else { return };
+    let Some(window) = s.drawing_window.clone() else { return };
+    let active = if s.drawing_tool == Some(tool) { None } else { Some(tool) };
+    s.drawing_tool = active;
+    drop(s);
+
+    let _ = window.set_ignore_cursor_events(active.is_none());
+    if active.is_some() {
+        let _ = window.set_focus();
+    }
+    events::emit(app, AppEvent::DrawingTool { tool: active });
+}
+
+/// Freehand annotation for a stroke that ended at `now_ms` (recording time),
+/// on screen until the strokes are cleared
+pub fn stroke_annotation(stroke: &LiveStroke, now_ms: u64) -> Option<Annotation> {
+    if stroke.points.len() < 2 {
+        return None;
+    }
+    let start_ms = now_ms.saturating_sub(stroke.duration_ms);
+    let xs = stroke.points.iter().map(|p| p.0);
+    let ys = stroke.points.iter().map(|p| p.1);
+    let (min_x, max_x) = (xs.clone().fold(1.0f64, f64::min), xs.fold(0.0f64, f64::max));
+    let (min_y, max_y) = (ys.clone().fold(1.0f64, f64::min), ys.fold(0.0f64, f64::max));
+    let highlighter = stroke.tool == DrawingTool::Highlighter;
+    Some(Annotation {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind: AnnotationKind::Freehand,
+        x: min_x,
+        y: min_y,
+        width: (max_x - min_x).max(0.0),
+        height: (max_y - min_y).max(0.0),
+        color: stroke.color.clone(),
+        stroke_width: if highlighter { HIGHLIGHTER_WIDTH } else { PEN_WIDTH },
+        text: None,
+        points: Some(stroke.points.clone()),
+        start_ms,
+        end_ms: OPEN_END,
+        keyframes: if highlighter {
+            vec![Keyframe {
+                time_ms: start_ms,
+                property: AnimatedProperty::Opacity,
+                value: HIGHLIGHTER_OPACITY,
+                easing: Easing::Hold,
+            }]
+        } else {
+            Vec::new()
+        },
+        z_index: 0,
+        group: None,
+    })
+}
+
+/// End every stroke still on screen at `now_ms`
+pub fn clear_strokes(strokes: &mut [Annotation], now_ms: u64) {
+    for stroke in strokes.iter_mut().filter(|s| s.end_ms == OPEN_END) {
+        stroke.end_ms = now_ms.max(stroke.start_ms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stroke(tool: DrawingTool) -> LiveStroke {
+        LiveStroke {
+            points: vec![(0.2, 0.6), (0.4, 0.3), (0.5, 0.5)],
+            tool,
+            color: "#ef4444".into(),
+            duration_ms: 800,
+        }
+    }
+
+    #[test]
+    fn test_stroke_annotation_starts_when_pen_went_down() {
+        let ann = stroke_annotation(&stroke(DrawingTool::Pen), 5000).unwrap();
+        assert_eq!(ann.kind, AnnotationKind::Freehand);
+        assert_eq!((ann.start_ms, ann.end_ms), (4200, OPEN_END));
+        assert_eq!((ann.x, ann.y), (0.2, 0.3));
+        assert!((ann.width - 0.3).abs() < 1e-9 && (ann.height - 0.3).abs() < 1e-9);
+        assert!(ann.keyframes.is_empty());
+
+        let single = LiveStroke { points: vec![(0.5, 0.5)], ..stroke(DrawingTool::Pen) };
+        assert!(stroke_annotation(&single, 5000).is_none());
+    }
+
+    #[test]
+    fn test_highlighter_is_wide_and_translucent() {
+        let mut ann = stroke_annotation(&stroke(DrawingTool::Highlighter), 5000).unwrap();
+        clear_strokes(std::slice::from_mut(&mut ann), 7000);
+        assert_eq!(ann.stroke_width, HIGHLIGHTER_WIDTH);
+        let segments = crate::export::encoder::opacity_segments(&ann);
+        assert_eq!(segments.len(), 1);
+        assert!((segments[0].2 - HIGHLIGHTER_OPACITY).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_clear_only_ends_open_strokes() {
+        let mut strokes: Vec<Annotation> =
+            [4000, 6000].iter().filter_map(|now| stroke_annotation(&stroke(DrawingTool::Pen), *now)).collect();
+        clear_strokes(&mut strokes[..1], 4500);
+        clear_strokes(&mut strokes, 9000);
+        assert_eq!(strokes[0].end_ms, 4500);
+        assert_eq!(strokes[1].end_ms, 9000);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::types::Clip;
+use std::time::Instant;
+
+/// How long after the lead capture of its group a capture started, 0 when
+/// it started first
+pub fn offset_ms(lead_started: Instant, started: Instant) -> u64 {
+    started.saturating_duration_since(lead_started).as_millis() as u64
+}
+
+/// Give the other clips recorded with `clip_id` its trim, moved by their
+/// start offsets, so every monitor of the group keeps the same moments.
+/// Returns how many clips were changed.
+pub fn link_trim(clips: &mut [Clip], clip_id: &str) -> usize {
+    let Some(source) = clips.iter().find(|c| c.id == clip_id) else {
+        return 0;
+    };
+    let Some(group) = source.capture_group.clone() else {
+        return 0;
+    };
+    // In the lead clip's time; an end of 0 keeps clips whole
+    let start = source.media.trim_start_ms + group.offset_ms;
+    let end = (source.media.trim_end_ms > 0).then(|| source.media.trim_end_ms + group.offset_ms);
+
+    let mut changed = 0;
+    for clip in clips.iter_mut().filter(|c| c.id != clip_id) {
+        let Some(offset) = clip.capture_group.as_ref().filter(|g| g.id == group.id).map(|g| g.offset_ms) else {
+            continue;
+        };
+        let duration = clip.media.duration_ms;
+        clip.media.trim_start_ms = start.saturating_sub(offset).min(duration);
+        clip.media.trim_end_ms = end.map_or(0, |end| end.saturating_sub(offset).clamp(clip.media.trim_start_ms, duration));
+        changed += 1;
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{test_clip, CaptureGroup};
+    use std::time::Duration;
+
+    fn grouped(id: &str, duration_ms: u64, offset_ms: u64) -> Clip {
+        Clip { capture_group: Some(CaptureGroup { id: "lead".into(), offset_ms }), ..test_clip(id, duration_ms) }
+    }
+
+    #[test]
+    fn test_start_offset_from_the_lead() {
+        let lead = Instant::now();
+        assert_eq!(offset_ms(lead, lead + Duration::from_millis(40)), 40);
+        // Started before the lead
+        assert_eq!(offset_ms(lead + Duration::from_millis(40), lead), 0);
+    }
+
+    #[test]
+    fn test_trim_follows_the_group() {
+        let mut clips = vec![grouped("lead", 10_000, 0), grouped("monitor1", 9850, 150), test_clip("alone", 5000)];
+        clips[0].media.trim_start_ms = 1000;
+        clips[0].media.trim_end_ms = 8000;
+        assert_eq!(link_trim(&mut clips, "lead"), 1);
+        assert_eq!((clips[1].media.trim_start_ms, clips[1].media.trim_end_ms), (850, 7850));
+        assert_eq!((clips[2].media.trim_start_ms, clips[2].media.trim_end_ms), (0, 0));
+
+        // From a monitor back to the lead, kept whole at the end
+        clips[1].media.trim_start_ms = 0;
+        clips[1].media.trim_end_ms = 0;
+        link_trim(&mut clips, "monitor1");
+        assert_eq!((clips[0].media.trim_start_ms, clips[0].media.trim_end_ms), (150, 0));
+        assert_eq!(link_trim(&mut clips, "alone"), 0);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::types::{PauseTransition, PauseTransitionStyle, RecordingQuality};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// Join the segments of a paused recording with a short transition at each
+/// pause. The result keeps the summed length of the segments, so keystrokes,
+/// cursor and audio stay in sync.
+pub async fn join_with_transition(
+    segments: &[PathBuf],
+    output: &Path,
+    transition: &PauseTransition,
+    quality: &RecordingQuality,
+) -> Result<(), String> {
+    let mut durations = Vec::new();
+    for segment in segments {
+        let duration = crate::export::encoder::probe_duration(segment)
+            .await
+            .map_err(|e| e.to_string())?;
+        durations.push(duration);
+    }
+    let filter = transition_filter(&durations, transition)
+        .ok_or_else(|| crate::i18n::t("segments_too_short"))?;
+
+    let mut args: Vec<String> = Vec::new();
+    for segment in segments {
+        args.extend(["-i".into(), segment.to_string_lossy().to_string()]);
+    }
+    args.extend(["-filter_complex".into(), filter, "-map".into(), "[v]".into()]);
+    args.extend(quality.encoder_args());
+    args.extend(["-movflags".into(), "+faststart".into(), "-y".into(), output.to_string_lossy().to_string()]);
+
+    let result = crate::ffmpeg_command()
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run concat: {}", e))?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(crate::i18n::tf("concat_failed", &[&stderr.chars().take(500).collect::<String>()]));
+    }
+    Ok(())
+}
+
+/// Filter graph joining segments of `durations` (seconds) into `[v]`, or
+/// None when there is nothing to draw or a segment is too short for it
+fn transition_filter(durations: &[f64], transition: &PauseTransition) -> Option<String> {
+    let d = transition.duration_ms as f64 / 1000.0;
+    let shortest = durations.iter().copied().fold(f64::INFINITY, f64::min);
+    if durations.len() < 2 || d <= 0.0 || shortest < d * 2.0 {
+        return None;
+    }
+    let last = durations.len() - 1;
+
+    let mut parts = Vec::new();
+    match transition.style {
+        PauseTransitionStyle::Cut => return None,
+        // Each segment but the last is held on its last frame for the
+        // length of the fade, which blends into the start of the next one
+        PauseTransitionStyle::Crossfade => {
+            for i in 0..=last {
+                let hold = if i < last { format!(",tpad=stop_mode=clone:stop_duration={d:.3}") } else { String::new() };
+                parts.push(format!("[{i}:v]settb=AVTB,setpts=PTS-STARTPTS{hold}[s{i}]"));
+            }
+            let mut previous = "s0".to_string();
+            let mut offset = 0.0;
+            for (i, duration) in durations.iter().enumerate().take(last) {
+                let next = i + 1;
+                offset += duration;
+                let out = if next == last { "v".to_string() } else { format!("x{next}") };
+                parts.push(format!("[{previous}][s{next}]xfade=transition=fade:duration={d:.3}:offset={offset:.3}[{out}]"));
+                previous = out;
+            }
+        }
+        // Half the length fading out before the pause, half fading in after
+        PauseTransitionStyle::Dip => {
+            let half = d / 2.0;
+            let mut inputs = String::new();
+            for (i, duration) in durations.iter().enumerate() {
+                let mut fades = Vec::new();
+                if i > 0 {
+                    fades.push(format!("fade=t=in:st=0:d={half:.3}"));
+                }
+                if i < last {
+                    fades.push(format!("fade=t=out:st={:.3}:d={half:.3}", duration - half));
+                }
+                parts.push(format!("[{i}:v]setpts=PTS-STARTPTS,{}[s{i}]", fades.join(",")));
+                inputs.push_str(&format!("[s{i}]"));
+            }
+            parts.push(format!("{inputs}concat=n={}:v=1:a=0[v]", durations.len()));
+        }
+    }
+    Some(parts.join(";"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transition(style: PauseTransitionStyle) -> PauseTransition {
+        PauseTransition { style, duration_ms: 400 }
+    }
+
+    #[test]
+    fn test_crossfade_keeps_the_total_length() {
+        let filter = transition_filter(&[5.0, 3.0, 4.0], &transition(PauseTransitionStyle::Crossfade)).unwrap();
+        assert!(filter.contains("[0:v]settb=AVTB,setpts=PTS-STARTPTS,tpad=stop_mode=clone:stop_duration=0.400[s0]"));
+        assert!(filter.contains("[2:v]settb=AVTB,setpts=PTS-STARTPTS[s2]"));
+        // Offsets fall where each held frame starts
+        assert!(filter.contains("[s0][s1]xfade=transition=fade:duration=0.400:offset=5.000[x1]"));
+        assert!(filter.ends_with("[x1][s2]xfade=transition=fade:duration=0.400:offset=8.000[v]"));
+    }
+
+    #[test]
+    fn test_dip_fades_around_each_pause() {
+        let filter = transition_filter(&[5.0, 3.0], &transition(PauseTransitionStyle::Dip)).unwrap();
+        assert_eq!(
+            filter,
+            "[0:v]setpts=PTS-STARTPTS,fade=t=out:st=4.800:d=0.200[s0];\
+             [1:v]setpts=PTS-STARTPTS,fade=t=in:st=0:d=0.200[s1];\
+             [s0][s1]concat=n=2:v=1:a=0[v]"
+        );
+    }
+
+    #[test]
+    fn test_no_transition_when_not_needed() {
+        assert!(transition_filter(&[5.0, 3.0], &transition(PauseTransitionStyle::Cut)).is_none());
+        assert!(transition_filter(&[5.0], &transition(PauseTransitionStyle::Dip)).is_none());
+        assert!(transition_filter(&[5.0, 0.5], &transition(PauseTransitionStyle::Dip)).is_none());
+    }
+}
@@ -0,0 +1,89 @@
+use super::drawing::exclude_from_capture;
+use crate::state::AppState;
+use crate::types::{RecordingState, Region};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder};
+
+const KEYSTROKE_PREVIEW_WINDOW: &str = "keystroke-preview";
+/// Height in physical pixels of the strip along the bottom of the recorded
+/// area, enough for the label the export draws 80 px above the bottom
+const HEIGHT: u32 = 140;
+
+/// Strip along the bottom of `region` where the keystroke labels land
+fn strip(region: &Region) -> Region {
+    let height = HEIGHT.min(region.height);
+    Region {
+        x: region.x,
+        y: region.y + (region.height - height) as i32,
+        width: region.width,
+        height,
+    }
+}
+
+/// Open the live keystroke preview over the bottom of the recorded area,
+/// showing each key where and as the export will burn it in. Click-through
+/// and hidden from the capture.
+pub fn open_window(app: &AppHandle, region: Option<Region>) {
+    if let Some(w) = app.get_webview_window(KEYSTROKE_PREVIEW_WINDOW) {
+        let _ = w.close();
+    }
+
+    let region = region.unwrap_or_else(|| match app.primary_monitor() {
+        Ok(Some(m)) => Region { x: m.position().x, y: m.position().y, width: m.size().width, height: m.size().height },
+        _ => Region { x: 0, y: 0, width: 1920, height: 1080 },
+    });
+    let bounds = strip(&region);
+
+    let window = WebviewWindowBuilder::new(app, KEYSTROKE_PREVIEW_WINDOW, WebviewUrl::App("/keystrokes".into()))
+        .title("Touches")
+        .decorations(false)
+        .transparent(true)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .focused(false)
+        .visible(false)
+        .build();
+    let window = match window {
+        Ok(w) => w,
+        Err(e) => {
+            tracing::warn!("Failed to open keystroke preview: {}", e);
+            return;
+        }
+    };
+    // The region is in physical pixels
+    let _ = window.set_position(PhysicalPosition::new(bounds.x, bounds.y));
+    let _ = window.set_size(PhysicalSize::new(bounds.width, bounds.height));
+    let _ = window.set_ignore_cursor_events(true);
+    exclude_from_capture(&window);
+    let _ = window.show();
+
+    let state = app.state::<Mutex<AppState>>();
+    let Ok(mut s) = state.lock() else { return };
+    // The recording may have ended while the window was being built
+    if s.recording_state == RecordingState::Idle {
+        let _ = window.close();
+        return;
+    }
+    s.keystroke_preview_window = Some(window);
+}
+
+pub fn close(s: &mut AppState) {
+    if let Some(w) = s.keystroke_preview_window.take() {
+        let _ = w.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_along_the_bottom() {
+        let strip_of = strip(&Region { x: -1920, y: 100, width: 1280, height: 720 });
+        assert_eq!((strip_of.x, strip_of.y, strip_of.width, strip_of.height), (-1920, 680, 1280, HEIGHT));
+        // A region lower than the strip is covered whole
+        let small = strip(&Region { x: 0, y: 0, width: 300, height: 90 });
+        assert_eq!((small.y, small.height), (0, 90));
+    }
+}
@@ -1,20 +1,78 @@
+use crate::capture::audio::PeakMeter;
 use crate::capture::screen;
+use crate::events::{self, AppEvent};
+use crate::recording::secondary::SecondaryCapture;
 use crate::state::{AppState, AudioCaptureHandle};
-use crate::types::{AudioSource, Clip, RecordingState, Region, Transition};
-use std::path::PathBuf;
+use crate::types::{AudioSource, CaptureGroup, CaptureWindow, Clip, ClipMedia, MarkerKind, PauseTransitionStyle, RecordingCodec, RecordingQuality, RecordingState, Region, TimelineMarker, Transition};
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::atomic::Ordering;
 use std::sync::Mutex;
 use std::time::Instant;
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 
-const FRAMERATE: u32 = 30;
+/// Recording time so far, pauses excluded
+pub fn elapsed_ms(s: &AppState) -> u64 {
+    s.recording_clock.as_ref().map_or(0, |clock| clock.elapsed_ms())
+}
+
+/// Region of the main capture: the first selected monitor when recording
+/// several, else the selected region (None = full screen), where its window
+/// has moved to when it was picked from one. Follow-cursor and follow-focus
+/// modes always record the full screen.
+pub(crate) fn capture_region(s: &AppState) -> Option<Region> {
+    if s.follow_cursor.is_some() || s.follow_focus {
+        return None;
+    }
+    if let Some(region) = s.capture_monitors.first() {
+        return Some(region.clone());
+    }
+    capture_window(s)
+        .and_then(|w| crate::region::selector::window_region(&w))
+        .or_else(|| s.current_region.clone())
+}
+
+/// Window the main capture follows, when the region was picked from one
+fn capture_window(s: &AppState) -> Option<CaptureWindow> {
+    if s.follow_cursor.is_some() || s.follow_focus || !s.capture_monitors.is_empty() {
+        return None;
+    }
+    s.capture_window
+}
+
+/// Chosen recording quality with the encoder it records with here
+pub(crate) fn capture_quality(s: &AppState) -> RecordingQuality {
+    let encoder = crate::capture::hwaccel::resolve(s.recording_quality.encoder, s.gpu_encoders.as_deref());
+    RecordingQuality { encoder, ..s.recording_quality.clone() }
+}
+
+/// Start recording once the configured countdown has elapsed.
+/// Returns `Ok(false)` if the countdown was aborted before capture began.
+pub async fn start_with_countdown(state: &Mutex<AppState>, app: &AppHandle) -> Result<bool, String> {
+    let seconds = {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        if s.recording_state != RecordingState::Idle {
+            return Err(crate::i18n::t("recording_in_progress"));
+        }
+        s.countdown_seconds
+    };
+
+    if seconds > 0 && !crate::recording::countdown::run(state, app, seconds).await? {
+        return Ok(false);
+    }
+
+    start(state, app)?;
+    Ok(true)
+}
 
 pub fn start(state: &Mutex<AppState>, app: &AppHandle) -> Result<(), String> {
     let mut s = state.lock().map_err(|e| e.to_string())?;
 
     if s.recording_state != RecordingState::Idle {
-        return Err("Enregistrement déjà en cours".into());
+        return Err(crate::i18n::t("recording_in_progress"));
+    }
+    if s.voiceover_capture.is_some() {
+        return Err(crate::i18n::t("voiceover_in_progress"));
     }
 
     // Ensure temp dir exists
@@ -22,44 +80,141 @@ pub fn start(state: &Mutex<AppState>, app: &AppHandle) -> Result<(), String> {
 
     // Reset segment tracking
     s.recording_segments = Vec::new();
-    s.pause_accumulated_ms = 0;
     s.segment_index = 0;
+    s.capture_level = 0;
+    s.pending_markers.clear();
+    s.mic_muted.store(false, Ordering::Relaxed);
+    s.live_strokes.clear();
+    s.script_marks.clear();
+    s.recording_session += 1;
 
     let clip_id = uuid::Uuid::new_v4().to_string();
-    let clip_path = s.temp_dir.join(format!("{}.mp4", clip_id));
-
-    let child = if let Some(ref region) = s.current_region {
-        screen::start_capture(region, &clip_path, FRAMERATE)
-    } else {
-        screen::start_fullscreen_capture(&clip_path, FRAMERATE)
+    // Capture to MKV: unlike MP4 it stays playable if FFmpeg is killed (no moov atom)
+    let clip_path = s.temp_dir.join(format!("{}.mkv", clip_id));
+
+    // The video takes the bound window's size at the start of the recording
+    if let Some(window) = s.capture_window {
+        s.capture_window = crate::region::selector::capture_window(window.hwnd);
+        if s.capture_window.is_none() {
+            tracing::info!("Bound window is gone, recording the selected region");
+        }
     }
-    .map_err(|e| format!("Failed to start capture: {}", e))?;
+
+    // Refresh-aware rate, settled for the whole recording
+    let monitors = crate::region::selector::enumerate_monitor_areas().unwrap_or_default();
+    s.capture_refresh = crate::recording::timing::refresh_rate(capture_region(&s).as_ref(), &monitors);
+    s.capture_framerate = crate::recording::timing::framerate(s.capture_rate, s.capture_refresh);
+    let framerate = s.capture_framerate;
+    let progress = crate::recording::timing::progress_path(&s.temp_dir);
+    let quality = capture_quality(&s);
+    tracing::info!("Recording with {}", quality.encoder_name());
+
+    let child = screen::start_capture(s.capture_backend, capture_region(&s).as_ref(), capture_window(&s).as_ref(), &clip_path, framerate, &quality, Some(&progress))
+        .map_err(|e| crate::i18n::tf("capture_start_failed", &[&e]))?;
 
     s.ffmpeg_process = Some(child);
     let start_time = Instant::now();
     s.recording_start = Some(start_time);
+    let clock = crate::recording::timing::RecordingClock::new(start_time);
+    s.recording_clock = Some(clock.clone());
     s.current_clip_path = Some(clip_path);
     s.recording_state = RecordingState::Recording;
 
+    // Every other selected monitor, each to its own clip, started right
+    // after the main capture so the group stays close; the offset each one
+    // records lines them up
+    let monitors: Vec<Region> = s.capture_monitors.iter().skip(1).cloned().collect();
+    for (i, region) in monitors.into_iter().enumerate() {
+        let name = format!("monitor{}", i + 1);
+        match SecondaryCapture::start(region, &s.temp_dir, &clip_id, &name, s.capture_backend, framerate, &quality, start_time) {
+            Ok(capture) => s.monitor_captures.push(capture),
+            Err(e) => tracing::warn!("Failed to start capture of monitor {}: {}", i + 2, e),
+        }
+    }
+
+    // Second region, in its own FFmpeg. The clip is still recorded without it.
+    if let Some(region) = s.secondary_region.clone() {
+        match SecondaryCapture::start(region, &s.temp_dir, &clip_id, "secondary", s.capture_backend, framerate, &quality, start_time) {
+            Ok(capture) => s.secondary_capture = Some(capture),
+            Err(e) => tracing::warn!("Failed to start second region capture: {}", e),
+        }
+    }
+
     // Start audio capture
     start_audio_captures(&mut s, &clip_id);
 
     // Start keystroke capture (with live emission if enabled)
     if s.keystroke_enabled {
-        match crate::capture::keystroke::start_capture_with_emitter(start_time, app.clone()) {
+        match crate::capture::keystroke::start_capture_with_emitter(clock.clone(), app.clone()) {
             Ok(handle) => s.keystroke_handle = Some(handle),
-            Err(e) => eprintln!("[recording] Failed to start keystroke capture: {}", e),
+            Err(e) => tracing::warn!("Failed to start keystroke capture: {}", e),
         }
     }
 
-    // Start cursor tracking
-    if s.cursor_zoom_enabled {
-        let region = s.current_region.clone().unwrap_or(Region {
+    // Start cursor tracking, follow-cursor mode needs the path
+    if s.cursor_zoom_enabled || s.follow_cursor.is_some() {
+        let region = capture_region(&s).unwrap_or(Region {
+            x: 0, y: 0, width: 1920, height: 1080,
+        });
+        let progress = crate::recording::timing::progress_path(&s.temp_dir);
+        s.cursor_handle = Some(crate::capture::cursor::start_tracking(&region, clock, progress));
+    }
+
+    // Follow the foreground window, unless the cursor is already followed
+    if s.follow_focus && s.follow_cursor.is_none() {
+        let region = capture_region(&s).unwrap_or(Region {
             x: 0, y: 0, width: 1920, height: 1080,
         });
-        s.cursor_handle = Some(crate::capture::cursor::start_tracking(&region, start_time));
+        s.focus_handle = Some(crate::capture::focus::start_tracking(&region, start_time));
+    }
+
+    // Start gamepad polling
+    if s.gamepad_enabled {
+        s.gamepad_handle = Some(crate::capture::gamepad::start_polling(start_time));
+    }
+
+    // Drawing overlay, built off this thread: window creation waits on the event loop
+    if s.live_drawing_enabled {
+        let app = app.clone();
+        let region = capture_region(&s);
+        tauri::async_runtime::spawn(async move {
+            crate::recording::drawing::open_overlay(&app, region);
+        });
+    }
+
+    // Keystroke preview, built off this thread like the drawing overlay
+    if s.keystroke_handle.is_some() && s.keystroke_preview {
+        let app = app.clone();
+        let region = capture_region(&s);
+        tauri::async_runtime::spawn(async move {
+            crate::recording::keystroke_preview::open_window(&app, region);
+        });
+    }
+
+    // Teleprompter, built off this thread like the drawing overlay
+    if s.teleprompter.as_ref().is_some_and(|t| !t.script.trim().is_empty()) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            crate::recording::teleprompter::open_window(&app);
+        });
+    }
+
+    // Watch segment size/duration for automatic rollover
+    if s.segment_rollover.enabled {
+        crate::recording::rollover::spawn_watcher(app.clone(), s.recording_session);
     }
 
+    // Watch CPU usage to lighten the capture when the machine is busy
+    if s.adaptive_capture.enabled && crate::recording::adaptive::adjustable(&s.recording_quality) {
+        crate::recording::adaptive::spawn_watcher(app.clone(), s.recording_session);
+    }
+
+    // Levels of the audio tracks for the live waveform
+    if s.audio_source != AudioSource::None {
+        crate::recording::waveform::spawn_watcher(app.clone(), s.recording_session);
+    }
+
+    events::emit(app, AppEvent::RecordingStarted);
     Ok(())
 }
 
@@ -75,15 +230,18 @@ fn start_audio_captures(s: &mut AppState, clip_id: &str) {
     if matches!(audio_source, AudioSource::System | AudioSource::Both) {
         let path = s.temp_dir.join(format!("{}_system.wav", clip_id));
         let stop_flag = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
-        match crate::capture::audio::start_system_capture(&path, stop_flag.clone()) {
+        let meter = std::sync::Arc::new(PeakMeter::default());
+        match crate::capture::loopback::start_capture(&path, stop_flag.clone(), s.selected_output.as_deref(), s.audio_format, meter.clone()) {
             Ok(handle) => {
                 s.audio_handles.push(AudioCaptureHandle {
                     join_handle: Some(handle),
                     stop_flag,
+                    source: AudioSource::System,
+                    meter,
                 });
                 s.audio_temp_paths.push(path);
             }
-            Err(e) => eprintln!("[recording] Failed to start system audio: {}", e),
+            Err(e) => tracing::warn!("Failed to start system audio: {}", e),
         }
     }
 
@@ -91,15 +249,18 @@ fn start_audio_captures(s: &mut AppState, clip_id: &str) {
         let path = s.temp_dir.join(format!("{}_mic.wav", clip_id));
         let stop_flag = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
         let mic_name = s.selected_mic.as_deref();
-        match crate::capture::audio::start_mic_capture_device(&path, stop_flag.clone(), mic_name) {
+        let meter = std::sync::Arc::new(PeakMeter::default());
+        match crate::capture::audio::start_mic_capture_device(&path, stop_flag.clone(), mic_name, s.mic_monitoring, s.mic_muted.clone(), s.audio_format, meter.clone()) {
             Ok(handle) => {
                 s.audio_handles.push(AudioCaptureHandle {
                     join_handle: Some(handle),
                     stop_flag,
+                    source: AudioSource::Microphone,
+                    meter,
                 });
                 s.audio_temp_paths.push(path);
             }
-            Err(e) => eprintln!("[recording] Failed to start mic audio: {}", e),
+            Err(e) => tracing::warn!("Failed to start mic audio: {}", e),
         }
     }
 }
@@ -113,118 +274,235 @@ fn stop_audio_captures(audio_handles: &mut Vec<AudioCaptureHandle>) {
     }
 }
 
-pub async fn pause(state: &Mutex<AppState>) -> Result<(), String> {
-    let (mut child, start_time, segment_path, mut audio_handles) = {
+pub async fn pause(state: &Mutex<AppState>, app: &AppHandle) -> Result<(), String> {
+    close_segment(state).await?;
+    events::emit(app, AppEvent::RecordingPaused { reason: None });
+    Ok(())
+}
+
+/// Stop the capture and keep what it recorded as a segment, without
+/// telling the frontend: rollover uses it to start a new file
+pub(super) async fn close_segment(state: &Mutex<AppState>) -> Result<(), String> {
+    let (mut child, segment_path, mut audio_handles, mut secondary, mut monitors) = {
         let mut s = state.lock().map_err(|e| e.to_string())?;
 
         if s.recording_state != RecordingState::Recording {
-            return Err("Pas d'enregistrement en cours".into());
+            return Err(crate::i18n::t("not_recording"));
         }
 
         let child = s.ffmpeg_process.take()
-            .ok_or("Processus FFmpeg introuvable")?;
-        let start_time = s.recording_start.take()
-            .ok_or("Heure de début introuvable")?;
+            .ok_or_else(|| crate::i18n::t("ffmpeg_process_missing"))?;
+        s.recording_start = None;
         let segment_path = s.current_clip_path.clone()
-            .ok_or("Chemin du clip introuvable")?;
+            .ok_or_else(|| crate::i18n::t("clip_path_missing"))?;
         let audio_handles = std::mem::take(&mut s.audio_handles);
+        let secondary = s.secondary_capture.take();
+        let monitors = std::mem::take(&mut s.monitor_captures);
 
         s.recording_state = RecordingState::Paused;
-        (child, start_time, segment_path, audio_handles)
+        // Input threads stop where this segment of video does, not once
+        // FFmpeg is done writing it
+        if let Some(clock) = &s.recording_clock {
+            clock.pause();
+        }
+        (child, segment_path, audio_handles, secondary, monitors)
     };
 
     // Stop the current FFmpeg segment gracefully
-    screen::stop_capture(&mut child).await
-        .map_err(|e| format!("Failed to stop capture segment: {}", e))?;
+    let stopped = screen::stop_capture(&mut child).await;
+    for capture in secondary.iter_mut().chain(monitors.iter_mut()) {
+        capture.pause().await;
+    }
+    stopped.map_err(|e| crate::i18n::tf("capture_stop_failed", &[&e]))?;
 
     // Stop audio captures during pause to avoid timing issues
     stop_audio_captures(&mut audio_handles);
 
-    // Track accumulated time and save segment
-    let segment_ms = start_time.elapsed().as_millis() as u64;
+    // Save the segment; the clock already holds its time
     {
         let mut s = state.lock().map_err(|e| e.to_string())?;
-        s.pause_accumulated_ms += segment_ms;
         s.recording_segments.push(segment_path);
         s.segment_index += 1;
+        s.secondary_capture = secondary;
+        s.monitor_captures = monitors;
     }
 
     Ok(())
 }
 
-pub fn resume(state: &Mutex<AppState>) -> Result<(), String> {
+/// Pause on behalf of a watcher (lock screen, ...).
+/// Returns true if this call actually paused the recording.
+pub async fn auto_pause(state: &Mutex<AppState>, app: &AppHandle, reason: &str) -> Result<bool, String> {
+    {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        if s.recording_state != RecordingState::Recording {
+            return Ok(false);
+        }
+    }
+    close_segment(state).await?;
+    state.lock().map_err(|e| e.to_string())?.auto_pause_reason = Some(reason.to_string());
+    events::emit(app, AppEvent::RecordingPaused { reason: Some(reason.to_string()) });
+    Ok(true)
+}
+
+/// Resume a recording that `auto_pause` paused for the same reason.
+/// Manual pauses and pauses for other reasons are left untouched.
+pub fn auto_resume(state: &Mutex<AppState>, app: &AppHandle, reason: &str) -> Result<bool, String> {
+    {
+        let s = state.lock().map_err(|e| e.to_string())?;
+        if s.recording_state != RecordingState::Paused || s.auto_pause_reason.as_deref() != Some(reason) {
+            return Ok(false);
+        }
+    }
+    resume(state, app)?;
+    Ok(true)
+}
+
+pub fn resume(state: &Mutex<AppState>, app: &AppHandle) -> Result<(), String> {
+    open_segment(state)?;
+    events::emit(app, AppEvent::RecordingResumed);
+    Ok(())
+}
+
+/// Start capturing the next segment of a paused recording, without telling
+/// the frontend
+pub(super) fn open_segment(state: &Mutex<AppState>) -> Result<(), String> {
     let mut s = state.lock().map_err(|e| e.to_string())?;
 
     if s.recording_state != RecordingState::Paused {
-        return Err("L'enregistrement n'est pas en pause".into());
+        return Err(crate::i18n::t("not_paused"));
     }
+    s.auto_pause_reason = None;
 
     // Create a new segment file
     let clip_id = s.current_clip_path.as_ref()
         .and_then(|p| p.file_stem())
         .map(|s| s.to_string_lossy().to_string())
         .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-    let segment_path = s.temp_dir.join(format!("{}_seg{}.mp4", clip_id, s.segment_index));
+    let segment_path = s.temp_dir.join(format!("{}_seg{}.mkv", clip_id, s.segment_index));
 
-    let child = if let Some(ref region) = s.current_region {
-        screen::start_capture(region, &segment_path, FRAMERATE)
-    } else {
-        screen::start_fullscreen_capture(&segment_path, FRAMERATE)
-    }
-    .map_err(|e| format!("Failed to resume capture: {}", e))?;
+    // Lighter settings when the adaptive watcher stepped down
+    let quality = crate::recording::adaptive::settings(&capture_quality(&s), s.capture_level);
+    let framerate = s.capture_framerate;
+    let progress = crate::recording::timing::progress_path(&s.temp_dir);
+    let child = screen::start_capture(s.capture_backend, capture_region(&s).as_ref(), capture_window(&s).as_ref(), &segment_path, framerate, &quality, Some(&progress))
+        .map_err(|e| crate::i18n::tf("capture_resume_failed", &[&e]))?;
 
     s.ffmpeg_process = Some(child);
     s.recording_start = Some(Instant::now());
+    if let Some(clock) = &s.recording_clock {
+        clock.resume();
+    }
     s.current_clip_path = Some(segment_path);
     s.recording_state = RecordingState::Recording;
 
+    let s = &mut *s;
+    for capture in s.secondary_capture.iter_mut().chain(s.monitor_captures.iter_mut()) {
+        if let Err(e) = capture.resume(s.segment_index, &quality) {
+            tracing::warn!("Failed to resume extra capture: {}", e);
+        }
+    }
+
     // Restart audio captures
     // Use the base clip_id (without _seg suffix) for audio file naming
     let base_id = clip_id.split("_seg").next().unwrap_or(&clip_id).to_string();
     let audio_id = format!("{}_seg{}", base_id, s.segment_index);
-    start_audio_captures(&mut s, &audio_id);
+    start_audio_captures(s, &audio_id);
 
     Ok(())
 }
 
-pub async fn stop(state: &Mutex<AppState>) -> Result<Clip, String> {
+/// Mute or unmute the microphone mid-recording. The track goes on with
+/// silence and the muted span is kept as a marker. Returns the new state.
+pub fn toggle_mic_mute(state: &Mutex<AppState>) -> Result<bool, String> {
+    let mut s = state.lock().map_err(|e| e.to_string())?;
+    if s.recording_state == RecordingState::Idle {
+        return Err(crate::i18n::t("not_recording"));
+    }
+    if !matches!(s.audio_source, AudioSource::Microphone | AudioSource::Both) {
+        return Err(crate::i18n::t("mic_not_recorded"));
+    }
+
+    let muted = !s.mic_muted.load(Ordering::Relaxed);
+    s.mic_muted.store(muted, Ordering::Relaxed);
+    let now_ms = elapsed_ms(&s);
+    if muted {
+        s.pending_markers.push(TimelineMarker {
+            timestamp_ms: now_ms,
+            label: crate::i18n::t("mic_muted_marker"),
+            kind: MarkerKind::MicMute,
+            end_ms: None,
+        });
+    } else {
+        close_mic_mute(&mut s.pending_markers, now_ms);
+    }
+    Ok(muted)
+}
+
+/// End the mute span still open, if any
+fn close_mic_mute(markers: &mut [TimelineMarker], now_ms: u64) {
+    if let Some(m) = markers.iter_mut().rev().find(|m| m.kind == MarkerKind::MicMute && m.end_ms.is_none()) {
+        m.end_ms = Some(now_ms);
+    }
+}
+
+pub async fn stop(state: &Mutex<AppState>, app: &AppHandle) -> Result<Clip, String> {
     // First lock: extract all handles and state
     let (
-        mut child, start_time, clip_path, region,
-        segments, accumulated_ms,
+        mut child, clip_path, region,
+        segments, total_duration_ms,
         mut audio_handles, audio_temp_paths,
-        keystroke_handle, cursor_handle,
+        keystroke_handle, cursor_handle, gamepad_handle, focus_handle, markers, mut strokes,
+        secondary_capture, monitor_captures, follow_cursor, script,
+        pause_transition, recording_quality, framerate,
     ) = {
         let mut s = state.lock().map_err(|e| e.to_string())?;
 
         if s.recording_state != RecordingState::Recording && s.recording_state != RecordingState::Paused {
-            return Err("Pas d'enregistrement en cours".into());
+            return Err(crate::i18n::t("not_recording"));
         }
 
+        // A mute still on ends with the clip
+        let now_ms = elapsed_ms(&s);
+        close_mic_mute(&mut s.pending_markers, now_ms);
+        s.mic_muted.store(false, Ordering::Relaxed);
+
         let child = s.ffmpeg_process.take();
-        let start_time = s.recording_start.take();
+        s.recording_start = None;
+        s.recording_clock = None;
         let clip_path = s.current_clip_path.take()
-            .ok_or("Chemin du clip introuvable")?;
+            .ok_or_else(|| crate::i18n::t("clip_path_missing"))?;
 
         // Mark idle immediately to prevent concurrent stop attempts
         s.recording_state = RecordingState::Idle;
-        let region = s.current_region.clone().unwrap_or(Region {
+        s.auto_pause_reason = None;
+        let region = capture_region(&s).unwrap_or(Region {
             x: 0, y: 0, width: 1920, height: 1080,
         });
         let segments = std::mem::take(&mut s.recording_segments);
-        let accumulated_ms = s.pause_accumulated_ms;
 
         // Take capture handles
         let audio_handles = std::mem::take(&mut s.audio_handles);
         let audio_temp_paths = std::mem::take(&mut s.audio_temp_paths);
         let keystroke_handle = s.keystroke_handle.take();
         let cursor_handle = s.cursor_handle.take();
+        let gamepad_handle = s.gamepad_handle.take();
+        let focus_handle = s.focus_handle.take();
+        let markers = std::mem::take(&mut s.pending_markers);
+        crate::recording::drawing::close_overlay(&mut s);
+        let strokes = std::mem::take(&mut s.live_strokes);
+        crate::recording::keystroke_preview::close(&mut s);
+        let secondary_capture = s.secondary_capture.take();
+        let monitor_captures = std::mem::take(&mut s.monitor_captures);
+        let script = crate::recording::teleprompter::finish(&mut s);
 
         (
-            child, start_time, clip_path, region,
-            segments, accumulated_ms,
+            child, clip_path, region,
+            segments, now_ms,
             audio_handles, audio_temp_paths,
-            keystroke_handle, cursor_handle,
+            keystroke_handle, cursor_handle, gamepad_handle, focus_handle, markers, strokes,
+            secondary_capture, monitor_captures, s.follow_cursor, script,
+            s.pause_transition, capture_quality(&s), s.capture_framerate,
         )
     };
     // Mutex is now unlocked — safe to do blocking operations
@@ -232,12 +510,25 @@ pub async fn stop(state: &Mutex<AppState>) -> Result<Clip, String> {
     // Stop FFmpeg gracefully if still running (not paused)
     if let Some(ref mut c) = child {
         screen::stop_capture(c).await
-            .map_err(|e| format!("Failed to stop capture: {}", e))?;
+            .map_err(|e| crate::i18n::tf("capture_stop_failed", &[&e]))?;
+    }
+    if let Some(dir) = clip_path.parent() {
+        let _ = std::fs::remove_file(crate::recording::timing::progress_path(dir));
     }
 
     // Stop audio captures
     stop_audio_captures(&mut audio_handles);
 
+    // Stop the second region and assemble its segments
+    let secondary = match secondary_capture {
+        Some(capture) => capture.finish().await,
+        None => None,
+    };
+    let mut monitor_videos = Vec::new();
+    for capture in monitor_captures {
+        monitor_videos.extend(capture.finish().await);
+    }
+
     // Stop keystroke capture
     let keystroke_events = if let Some(mut handle) = keystroke_handle {
         crate::capture::keystroke::stop_capture(&mut handle)
@@ -252,17 +543,39 @@ pub async fn stop(state: &Mutex<AppState>) -> Result<Clip, String> {
         Vec::new()
     };
 
-    let last_segment_ms = start_time.map(|s| s.elapsed().as_millis() as u64).unwrap_or(0);
-    let total_duration_ms = accumulated_ms + last_segment_ms;
+    // Stop gamepad polling
+    let gamepad_samples = if let Some(mut handle) = gamepad_handle {
+        crate::capture::gamepad::stop_polling(&mut handle)
+    } else {
+        Vec::new()
+    };
+
+    // Stop following the foreground window
+    let focus_track = if let Some(mut handle) = focus_handle {
+        crate::capture::focus::stop_tracking(&mut handle)
+    } else {
+        Vec::new()
+    };
 
     // Determine final output path
     let final_path = if segments.is_empty() {
-        // No pause was used — single file, just use clip_path as-is
+        // No pause was used — single file, remux the MKV capture to MP4
         if !clip_path.exists() {
-            eprintln!("[recording] Clip file not created: {:?}. FFmpeg capture may have failed.", clip_path);
-            return Err(format!("Capture échouée : le fichier vidéo n'a pas été créé. Vérifiez que la zone de capture est valide."));
+            tracing::error!("Clip file not created: {:?}. FFmpeg capture may have failed.", clip_path);
+            return Err(crate::i18n::t("capture_file_missing"));
+        }
+        let mp4_path = clip_path.with_extension("mp4");
+        match remux_to_mp4(&clip_path, &mp4_path).await {
+            Ok(()) => {
+                let _ = std::fs::remove_file(&clip_path);
+                mp4_path
+            }
+            Err(e) => {
+                // The MKV is still a valid clip — keep it rather than losing the recording
+                tracing::warn!("Remux failed, keeping MKV: {}", e);
+                clip_path
+            }
         }
-        clip_path
     } else {
         // Multiple segments — concat them
         let mut all_segments = segments;
@@ -272,7 +585,17 @@ pub async fn stop(state: &Mutex<AppState>) -> Result<Clip, String> {
         }
 
         let concat_output = clip_path.with_extension("concat.mp4");
-        concat_segments(&all_segments, &concat_output).await?;
+        let transitioned = pause_transition.style != PauseTransitionStyle::Cut
+            && match crate::recording::join::join_with_transition(&all_segments, &concat_output, &pause_transition, &recording_quality).await {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!("Pause transition skipped, joining with a cut: {}", e);
+                    false
+                }
+            };
+        if !transitioned {
+            concat_segments(&all_segments, &concat_output).await?;
+        }
 
         // Cleanup individual segment files
         for seg in &all_segments {
@@ -280,13 +603,23 @@ pub async fn stop(state: &Mutex<AppState>) -> Result<Clip, String> {
         }
 
         // Rename to final path
-        let final_out = if clip_path.exists() {
-            concat_output
+        let mp4_path = clip_path.with_extension("mp4");
+        if std::fs::rename(&concat_output, &mp4_path).is_ok() {
+            mp4_path
         } else {
-            let _ = std::fs::rename(&concat_output, &clip_path);
-            clip_path
-        };
-        final_out
+            concat_output
+        }
+    };
+
+    // Captures are normally in the timeline format already. The others are
+    // converted once the clips are on the timeline; a lossless codec is kept
+    // as chosen, for the export to start from.
+    let to_normalize: Vec<PathBuf> = if recording_quality.codec == RecordingCodec::X264 {
+        std::iter::once(final_path.clone())
+            .chain(monitor_videos.iter().map(|video| video.path.clone()))
+            .collect()
+    } else {
+        Vec::new()
     };
 
     let clip_id = final_path.file_stem()
@@ -298,7 +631,8 @@ pub async fn stop(state: &Mutex<AppState>) -> Result<Clip, String> {
 
     // Generate thumbnail
     let thumbnail_path = final_path.with_extension("thumb.png");
-    let thumb = if screen::generate_thumbnail(&final_path, &thumbnail_path).is_ok() {
+    let thumb_ms = screen::default_thumbnail_ms(total_duration_ms);
+    let thumb = if screen::generate_thumbnail(&final_path, &thumbnail_path, thumb_ms).is_ok() {
         Some(thumbnail_path)
     } else {
         None
@@ -310,29 +644,90 @@ pub async fn stop(state: &Mutex<AppState>) -> Result<Clip, String> {
         .map(|p| p.to_string_lossy().to_string())
         .collect();
     let has_audio = !audio_paths.is_empty();
+    let hdr = crate::capture::hdr::region_is_hdr(&region);
 
     let clip = Clip {
         id: clip_id.clone(),
-        path: final_path,
-        duration_ms: total_duration_ms,
-        region,
-        has_audio,
-        thumbnail_path: thumb,
-        trim_start_ms: 0,
-        trim_end_ms: 0,
-        audio_paths,
+        media: ClipMedia {
+            path: final_path,
+            duration_ms: total_duration_ms,
+            region,
+            has_audio,
+            thumbnail_path: thumb,
+            trim_start_ms: 0,
+            trim_end_ms: 0,
+            audio_paths,
+            hdr,
+            secondary,
+            follow_cursor,
+            focus_track,
+            script,
+            audio_offset_ms: 0,
+        },
+        zoom_keyframes: Vec::new(),
+        capture_group: None,
+        takes: Vec::new(),
+    };
+
+    // The other monitors become clips of their own, linked to this one
+    let monitor_clips: Vec<Clip> = monitor_videos
+        .into_iter()
+        .map(|video| {
+            let duration_ms = total_duration_ms.saturating_sub(video.offset_ms);
+            let thumbnail_path = video.path.with_extension("thumb.png");
+            let thumb = screen::generate_thumbnail(&video.path, &thumbnail_path, screen::default_thumbnail_ms(duration_ms))
+                .ok()
+                .map(|_| thumbnail_path);
+            Clip {
+                id: uuid::Uuid::new_v4().to_string(),
+                media: ClipMedia {
+                    duration_ms,
+                    hdr: crate::capture::hdr::region_is_hdr(&video.region),
+                    path: video.path,
+                    region: video.region,
+                    has_audio: false,
+                    thumbnail_path: thumb,
+                    trim_start_ms: 0,
+                    trim_end_ms: 0,
+                    audio_paths: Vec::new(),
+                    secondary: None,
+                    follow_cursor: None,
+                    focus_track: Vec::new(),
+                    script: None,
+                    audio_offset_ms: 0,
+                },
+                zoom_keyframes: Vec::new(),
+                capture_group: Some(CaptureGroup { id: clip_id.clone(), offset_ms: video.offset_ms }),
+                takes: Vec::new(),
+            }
+        })
+        .collect();
+    let clip = if monitor_clips.is_empty() {
+        clip
+    } else {
+        Clip { capture_group: Some(CaptureGroup { id: clip_id.clone(), offset_ms: 0 }), ..clip }
     };
 
     // Second lock: store clip and associated data
-    {
+    let (clip, added) = {
         let mut s = state.lock().map_err(|e| e.to_string())?;
-        // Add a default transition if there's already at least one clip
-        if !s.clips.is_empty() {
+        // A retake goes in its clip's slot, unless that clip was deleted meanwhile
+        let retake_of = s.retake_of.take().filter(|id| s.clips.iter().any(|c| &c.id == id));
+        let mut added = Vec::new();
+        if retake_of.is_none() {
+            // Add a default transition if there's already at least one clip
+            if !s.clips.is_empty() {
+                s.transitions.push(Transition::default());
+            }
+            s.clips.push(clip.clone());
+            added.push(clip.clone());
+        }
+        for monitor_clip in monitor_clips {
             s.transitions.push(Transition::default());
+            s.clips.push(monitor_clip.clone());
+            added.push(monitor_clip);
         }
-        s.clips.push(clip.clone());
         // Reset pause state
-        s.pause_accumulated_ms = 0;
         s.segment_index = 0;
 
         // Store keystroke and cursor data
@@ -340,19 +735,45 @@ pub async fn stop(state: &Mutex<AppState>) -> Result<Clip, String> {
             s.clip_keystrokes.insert(clip_id.clone(), keystroke_events);
         }
         if !cursor_positions.is_empty() {
-            s.clip_cursor_positions.insert(clip_id, cursor_positions);
+            s.clip_cursor_positions.insert(clip_id.clone(), cursor_positions);
         }
-    }
+        if !gamepad_samples.is_empty() {
+            s.clip_gamepad.insert(clip_id.clone(), gamepad_samples);
+        }
+        if !strokes.is_empty() {
+            // Strokes still on screen last until the end of the clip
+            crate::recording::drawing::clear_strokes(&mut strokes, total_duration_ms);
+            let on = retake_of.clone().unwrap_or_else(|| clip_id.clone());
+            s.annotations.entry(on).or_default().extend(strokes);
+        }
+        if !markers.is_empty() {
+            s.clip_markers.insert(clip_id, markers);
+        }
+
+        let clip = match retake_of {
+            Some(slot) => crate::recording::takes::add(&mut s, &slot, &clip)?,
+            None => clip,
+        };
+        (clip, added)
+    };
 
+    events::emit(app, AppEvent::RecordingStopped { clip_id: clip.id.clone() });
+    for clip in added {
+        events::emit(app, AppEvent::ClipAdded { clip });
+    }
+    if !to_normalize.is_empty() {
+        spawn_normalize(app.clone(), to_normalize, framerate);
+    }
     Ok(clip)
 }
 
-pub fn cancel(state: &Mutex<AppState>) -> Result<(), String> {
+pub fn cancel(state: &Mutex<AppState>, app: &AppHandle) -> Result<(), String> {
     let mut s = state.lock().map_err(|e| e.to_string())?;
 
     if let Some(mut child) = s.ffmpeg_process.take() {
         let _ = child.start_kill();
     }
+    let _ = std::fs::remove_file(crate::recording::timing::progress_path(&s.temp_dir));
 
     // Clean up the current temp file
     if let Some(ref path) = s.current_clip_path.take() {
@@ -385,32 +806,143 @@ pub fn cancel(state: &Mutex<AppState>) -> Result<(), String> {
         let _ = crate::capture::cursor::stop_tracking(&mut handle);
     }
 
+    // Stop gamepad polling (discard data)
+    if let Some(mut handle) = s.gamepad_handle.take() {
+        let _ = crate::capture::gamepad::stop_polling(&mut handle);
+    }
+
+    // Stop following the foreground window (discard data)
+    if let Some(mut handle) = s.focus_handle.take() {
+        let _ = crate::capture::focus::stop_tracking(&mut handle);
+    }
+
+    // Stop the second region and other monitors (discard data)
+    if let Some(capture) = s.secondary_capture.take() {
+        capture.cancel();
+    }
+    for capture in s.monitor_captures.drain(..) {
+        capture.cancel();
+    }
+
+    s.pending_markers.clear();
+    s.mic_muted.store(false, Ordering::Relaxed);
+    crate::recording::drawing::close_overlay(&mut s);
+    s.live_strokes.clear();
+    crate::recording::keystroke_preview::close(&mut s);
+    let _ = crate::recording::teleprompter::finish(&mut s);
     s.recording_start = None;
+    s.recording_clock = None;
+    s.retake_of = None;
     s.recording_state = RecordingState::Idle;
-    s.pause_accumulated_ms = 0;
+    s.auto_pause_reason = None;
     s.segment_index = 0;
 
+    events::emit(app, AppEvent::RecordingCancelled);
+    Ok(())
+}
+
+/// Convert the captures that don't match the timeline format off the stop,
+/// then point the clips and takes still using them at the converted files
+fn spawn_normalize(app: AppHandle, paths: Vec<PathBuf>, fps: u32) {
+    tauri::async_runtime::spawn(async move {
+        for path in paths {
+            let Some(converted) = normalize_capture(&app, &path, fps).await else {
+                continue;
+            };
+            let state = app.state::<Mutex<AppState>>();
+            let updated = match state.lock() {
+                Ok(mut s) => swap_capture(&mut s.clips, &path, &converted),
+                Err(_) => Vec::new(),
+            };
+            // Deleted while converting: nothing plays either file anymore
+            if updated.is_empty() {
+                let _ = std::fs::remove_file(&converted);
+                continue;
+            }
+            let _ = std::fs::remove_file(&path);
+            for clip_id in updated {
+                events::emit(&app, AppEvent::ClipNormalized { clip_id });
+            }
+        }
+    });
+}
+
+/// Point every clip and take recorded to `old` at `new`, returning the
+/// clips that changed
+fn swap_capture(clips: &mut [Clip], old: &Path, new: &Path) -> Vec<String> {
+    let mut updated = Vec::new();
+    for clip in clips {
+        let mut changed = false;
+        let media = std::iter::once(&mut clip.media).chain(clip.takes.iter_mut().map(|t| &mut t.media));
+        for media in media {
+            if media.path == old {
+                media.path = new.to_path_buf();
+                changed = true;
+            }
+        }
+        if changed {
+            updated.push(clip.id.clone());
+        }
+    }
+    updated
+}
+
+/// Convert a capture that doesn't match the timeline format (see
+/// `normalize`) at the rate it was recorded at, next to it. `None` when it
+/// already matches or the conversion fails, keeping it as recorded.
+async fn normalize_capture(app: &AppHandle, path: &Path, fps: u32) -> Option<PathBuf> {
+    if !crate::normalize::needs_normalizing(path, fps).await {
+        return None;
+    }
+    let converted = path.with_extension("norm.mp4");
+    let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    if let Err(e) = crate::normalize::transcode(app, path, &converted, &name, fps).await {
+        tracing::warn!("Keeping the capture as recorded: {}", e);
+        let _ = std::fs::remove_file(&converted);
+        return None;
+    }
+    Some(converted)
+}
+
+/// Remux a capture into MP4 without re-encoding
+pub(crate) async fn remux_to_mp4(input: &PathBuf, output: &PathBuf) -> Result<(), String> {
+    let result = crate::ffmpeg_command()
+        .args([
+            "-i", &input.to_string_lossy(),
+            "-c", "copy",
+            "-movflags", "+faststart",
+            "-y",
+            &output.to_string_lossy(),
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run remux: {}", e))?;
+
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(crate::i18n::tf("remux_failed", &[&stderr.chars().take(500).collect::<String>()]));
+    }
+
     Ok(())
 }
 
 /// Concatenate multiple video segments using FFmpeg concat demuxer
-async fn concat_segments(segments: &[PathBuf], output: &PathBuf) -> Result<(), String> {
+pub(crate) async fn concat_segments(segments: &[PathBuf], output: &PathBuf) -> Result<(), String> {
     if segments.is_empty() {
-        return Err("Aucun segment à concaténer".into());
+        return Err(crate::i18n::t("no_segments"));
     }
     if segments.len() == 1 {
-        std::fs::copy(&segments[0], output).map_err(|e| e.to_string())?;
-        return Ok(());
+        // Segments are MKV captures — a plain copy would leave a mislabeled container
+        return remux_to_mp4(&segments[0], output).await;
     }
 
     // Create concat list file
     let list_path = output.with_extension("txt");
-    let mut list_content = String::new();
-    for seg in segments {
-        if seg.exists() {
-            list_content.push_str(&format!("file '{}'\n", seg.to_string_lossy().replace('\'', "'\\''")));
-        }
-    }
+    let existing: Vec<&PathBuf> = segments.iter().filter(|seg| seg.exists()).collect();
+    let list_content = crate::ffmpeg::paths::concat_list(&existing);
     std::fs::write(&list_path, &list_content).map_err(|e| e.to_string())?;
 
     let result = crate::ffmpeg_command()
@@ -433,7 +965,7 @@ async fn concat_segments(segments: &[PathBuf], output: &PathBuf) -> Result<(), S
 
     if !result.status.success() {
         let stderr = String::from_utf8_lossy(&result.stderr);
-        return Err(format!("Échec de la concaténation : {}", stderr.chars().take(500).collect::<String>()));
+        return Err(crate::i18n::tf("concat_failed", &[&stderr.chars().take(500).collect::<String>()]));
     }
 
     Ok(())
@@ -1 +1,15 @@
+pub mod adaptive;
+pub mod countdown;
+pub mod drawing;
+pub mod group;
+pub mod join;
+pub mod keystroke_preview;
 pub mod manager;
+pub mod recovery;
+pub mod rollover;
+pub mod secondary;
+pub mod takes;
+pub mod teleprompter;
+pub mod timing;
+pub mod voiceover;
+pub mod waveform;
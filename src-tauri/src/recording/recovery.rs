@@ -0,0 +1,257 @@
+use crate::events::{self, AppEvent};
+use crate::state::AppState;
+use crate::types::{Clip, ClipMedia, Region};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Scan the temp dir for captures left behind by a crashed session,
+/// remux them to MP4 and keep them aside until the user restores or discards them.
+/// Stale temp files are cleaned up afterwards per the storage policy, sparing
+/// what was recovered.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let temp_dir = {
+            let state = app.state::<Mutex<AppState>>();
+            let Ok(s) = state.lock() else { return };
+            s.temp_dir.clone()
+        };
+
+        let recovered = if temp_dir.exists() {
+            recover_orphans(&temp_dir).await
+        } else {
+            Vec::new()
+        };
+
+        let policy = crate::storage::load_policy();
+        let mut keep = recovered.clone();
+        let mut keep_voiceovers = Vec::new();
+        if policy.keep_project_media {
+            keep.extend(crate::project::saved_clips());
+            keep_voiceovers = crate::project::saved_voiceovers();
+        }
+        crate::storage::cleanup_stale(&temp_dir, &policy, &keep, &keep_voiceovers);
+
+        if recovered.is_empty() {
+            return;
+        }
+        tracing::info!("Recovered {} interrupted recording(s)", recovered.len());
+        let count = recovered.len();
+        {
+            let state = app.state::<Mutex<AppState>>();
+            let Ok(mut s) = state.lock() else { return };
+            s.recovered_clips = recovered;
+        }
+        events::emit(&app, AppEvent::RecordingsRecovered { count });
+    });
+}
+
+/// Audio tracks written next to each capture segment, in `audio_paths` order
+const AUDIO_TRACKS: [&str; 2] = ["system", "mic"];
+
+/// Remux every group of orphaned MKV captures into a single MP4 clip
+async fn recover_orphans(temp_dir: &Path) -> Vec<Clip> {
+    let Ok(entries) = std::fs::read_dir(temp_dir) else {
+        return Vec::new();
+    };
+    let files: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+
+    let mut clips = Vec::new();
+    for (clip_id, segments) in group_orphan_segments(&files) {
+        match recover_clip(temp_dir, &clip_id, &segments).await {
+            Ok(clip) => clips.push(clip),
+            Err(e) => tracing::warn!("Could not salvage {}: {}", clip_id, e),
+        }
+    }
+    clips
+}
+
+async fn recover_clip(temp_dir: &Path, clip_id: &str, segments: &[PathBuf]) -> Result<Clip, String> {
+    let output = temp_dir.join(format!("{}.mp4", clip_id));
+    // A crashed capture may have a truncated tail — remux whatever FFmpeg can still read
+    crate::recording::manager::concat_segments(segments, &output).await?;
+    for seg in segments {
+        let _ = std::fs::remove_file(seg);
+    }
+
+    let mut audio_paths = Vec::new();
+    for track in AUDIO_TRACKS {
+        let Some(sources) = segment_audio(segments, track) else {
+            continue;
+        };
+        let path = temp_dir.join(format!("{}_recovered_{}.wav", clip_id, track));
+        match recover_audio(&sources, &path).await {
+            Ok(()) => {
+                for source in &sources {
+                    let _ = std::fs::remove_file(source);
+                }
+                audio_paths.push(path.to_string_lossy().to_string());
+            }
+            Err(e) => tracing::warn!("Could not salvage the {} audio of {}: {}", track, clip_id, e),
+        }
+    }
+
+    let (width, height, duration_s) = crate::capture::screen::probe_video(&output).await
+        .ok_or_else(|| crate::i18n::t("recovered_video_unreadable"))?;
+
+    let thumbnail_path = output.with_extension("thumb.png");
+    let thumb_ms = crate::capture::screen::default_thumbnail_ms((duration_s * 1000.0) as u64);
+    let thumb = if crate::capture::screen::generate_thumbnail(&output, &thumbnail_path, thumb_ms).is_ok() {
+        Some(thumbnail_path)
+    } else {
+        None
+    };
+
+    Ok(Clip {
+        id: clip_id.to_string(),
+        media: ClipMedia {
+            path: output,
+            duration_ms: (duration_s * 1000.0) as u64,
+            region: Region { x: 0, y: 0, width, height },
+            has_audio: !audio_paths.is_empty(),
+            thumbnail_path: thumb,
+            trim_start_ms: 0,
+            trim_end_ms: 0,
+            audio_paths,
+            hdr: false,
+            secondary: None,
+            follow_cursor: None,
+            focus_track: Vec::new(),
+            script: None,
+            audio_offset_ms: 0,
+        },
+        zoom_keyframes: Vec::new(),
+        capture_group: None,
+        takes: Vec::new(),
+    })
+}
+
+/// The `track` WAV of every segment (`{segment}_{track}.wav`), `None` unless
+/// all of them have one — a partial track would drift off the video
+fn segment_audio(segments: &[PathBuf], track: &str) -> Option<Vec<PathBuf>> {
+    segments
+        .iter()
+        .map(|seg| {
+            let stem = seg.file_stem()?.to_string_lossy();
+            let wav = seg.with_file_name(format!("{}_{}.wav", stem, track));
+            wav.exists().then_some(wav)
+        })
+        .collect()
+}
+
+/// Join the WAV segments into `output`. A crashed capture never wrote its
+/// header sizes, so each input is read to its end instead.
+async fn recover_audio(sources: &[PathBuf], output: &Path) -> Result<(), String> {
+    let mut args: Vec<String> = Vec::new();
+    for source in sources {
+        args.extend(["-ignore_length".into(), "1".into(), "-i".into(), source.to_string_lossy().to_string()]);
+    }
+    let inputs: String = (0..sources.len()).map(|i| format!("[{}:a]", i)).collect();
+    args.extend([
+        "-filter_complex".into(),
+        format!("{}concat=n={}:v=0:a=1[a]", inputs, sources.len()),
+        "-map".into(),
+        "[a]".into(),
+        "-y".into(),
+        output.to_string_lossy().to_string(),
+    ]);
+
+    let result = crate::ffmpeg_command()
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !result.status.success() {
+        let _ = std::fs::remove_file(output);
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(stderr.chars().take(500).collect());
+    }
+    Ok(())
+}
+
+/// Group `{id}.mkv` / `{id}_seg{n}.mkv` captures by clip id, segments in recording order
+fn group_orphan_segments(files: &[PathBuf]) -> Vec<(String, Vec<PathBuf>)> {
+    let mut groups: Vec<(String, Vec<(u32, PathBuf)>)> = Vec::new();
+    for path in files {
+        if path.extension().map(|e| e != "mkv").unwrap_or(true) {
+            continue;
+        }
+        let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let (id, index) = match stem.rsplit_once("_seg") {
+            Some((id, n)) => match n.parse::<u32>() {
+                Ok(n) => (id.to_string(), n),
+                Err(_) => continue,
+            },
+            None => (stem, 0),
+        };
+        match groups.iter_mut().find(|(g, _)| *g == id) {
+            Some((_, segs)) => segs.push((index, path.clone())),
+            None => groups.push((id, vec![(index, path.clone())])),
+        }
+    }
+
+    groups.sort_by(|a, b| a.0.cmp(&b.0));
+    groups
+        .into_iter()
+        .map(|(id, mut segs)| {
+            segs.sort_by_key(|(i, _)| *i);
+            (id, segs.into_iter().map(|(_, p)| p).collect())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_orphans_orders_segments() {
+        let files = vec![
+            PathBuf::from("abc_seg2.mkv"),
+            PathBuf::from("abc.mkv"),
+            PathBuf::from("abc_seg1.mkv"),
+            PathBuf::from("abc.mp4"),
+            PathBuf::from("abc_mic.wav"),
+        ];
+        let groups = group_orphan_segments(&files);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].0, "abc");
+        assert_eq!(
+            groups[0].1,
+            vec![PathBuf::from("abc.mkv"), PathBuf::from("abc_seg1.mkv"), PathBuf::from("abc_seg2.mkv")]
+        );
+    }
+
+    #[test]
+    fn test_segment_audio_needs_every_segment() {
+        let dir = std::env::temp_dir().join(format!("clipflow-recovery-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let segments = vec![dir.join("abc.mkv"), dir.join("abc_seg1.mkv")];
+        for name in ["abc_system.wav", "abc_seg1_system.wav", "abc_mic.wav"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        assert_eq!(
+            segment_audio(&segments, "system"),
+            Some(vec![dir.join("abc_system.wav"), dir.join("abc_seg1_system.wav")])
+        );
+        // The second segment has no mic track
+        assert_eq!(segment_audio(&segments, "mic"), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_group_orphans_separates_clips() {
+        let files = vec![PathBuf::from("b.mkv"), PathBuf::from("a_seg1.mkv")];
+        let groups = group_orphan_segments(&files);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, "a");
+        assert_eq!(groups[1].0, "b");
+    }
+}
@@ -0,0 +1,113 @@
+use crate::events::{self, AppEvent};
+use crate::recording::manager;
+use crate::state::AppState;
+use crate::types::{RecordingState, SegmentRollover};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const CHECK_INTERVAL_MS: u64 = 1000;
+
+/// Watch the current recording session and roll over to a new segment once
+/// the configured duration or file size is reached. Exits when the session ends.
+pub fn spawn_watcher(app: AppHandle, session: u64) {
+    tauri::async_runtime::spawn(async move {
+        watch(app, session).await;
+    });
+}
+
+async fn watch(app: AppHandle, session: u64) {
+    let state = app.state::<Mutex<AppState>>();
+    loop {
+        tokio::time::sleep(Duration::from_millis(CHECK_INTERVAL_MS)).await;
+
+        let (settings, elapsed_ms, size_bytes) = {
+            let Ok(s) = state.lock() else {
+                return;
+            };
+            if s.recording_session != session || s.recording_state == RecordingState::Idle {
+                return;
+            }
+            if s.recording_state != RecordingState::Recording {
+                continue;
+            }
+            let elapsed_ms = s.recording_start.map(|t| t.elapsed().as_millis() as u64).unwrap_or(0);
+            let size_bytes = s.current_clip_path.as_ref()
+                .and_then(|p| std::fs::metadata(p).ok())
+                .map(|m| m.len())
+                .unwrap_or(0);
+            (s.segment_rollover.clone(), elapsed_ms, size_bytes)
+        };
+
+        if !should_roll_over(&settings, elapsed_ms, size_bytes) {
+            continue;
+        }
+        tracing::info!("Segment limit reached ({}ms, {} bytes)", elapsed_ms, size_bytes);
+
+        if settings.split_clips {
+            // Finalize as its own clip, then start a fresh recording (which spawns a new watcher)
+            if let Err(e) = manager::stop(&state, &app).await {
+                tracing::warn!("Failed to finalize segment: {}", e);
+                events::emit(&app, AppEvent::RecordingError { message: e });
+                return;
+            }
+            if let Err(e) = manager::start(&state, &app) {
+                tracing::warn!("Failed to start next segment: {}", e);
+                events::emit(&app, AppEvent::RecordingError { message: e });
+            }
+            return;
+        }
+
+        // Same clip: close the current segment file, concatenated on stop
+        if let Err(e) = manager::close_segment(&state).await {
+            tracing::warn!("Failed to close segment: {}", e);
+            continue;
+        }
+        if let Err(e) = manager::open_segment(&state) {
+            tracing::warn!("Failed to open next segment: {}", e);
+            events::emit(&app, AppEvent::RecordingError { message: e });
+        }
+    }
+}
+
+/// Whether the current segment exceeds the configured duration or size limit.
+fn should_roll_over(settings: &SegmentRollover, elapsed_ms: u64, size_bytes: u64) -> bool {
+    if !settings.enabled {
+        return false;
+    }
+    let over_time = settings.max_minutes > 0 && elapsed_ms >= settings.max_minutes as u64 * 60_000;
+    let over_size = settings.max_megabytes > 0 && size_bytes >= settings.max_megabytes * 1024 * 1024;
+    over_time || over_size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(max_minutes: u32, max_megabytes: u64) -> SegmentRollover {
+        SegmentRollover { enabled: true, max_minutes, max_megabytes, split_clips: false }
+    }
+
+    #[test]
+    fn test_rollover_disabled() {
+        let s = SegmentRollover { enabled: false, ..settings(1, 1) };
+        assert!(!should_roll_over(&s, u64::MAX, u64::MAX));
+    }
+
+    #[test]
+    fn test_rollover_by_time() {
+        assert!(!should_roll_over(&settings(30, 0), 29 * 60_000, 0));
+        assert!(should_roll_over(&settings(30, 0), 30 * 60_000, 0));
+    }
+
+    #[test]
+    fn test_rollover_by_size() {
+        assert!(!should_roll_over(&settings(0, 100), 0, 99 * 1024 * 1024));
+        assert!(should_roll_over(&settings(0, 100), 0, 100 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_rollover_zero_limits_never_trigger() {
+        assert!(!should_roll_over(&settings(0, 0), u64::MAX, u64::MAX));
+    }
+}
@@ -0,0 +1,112 @@
+use super::manager::{concat_segments, remux_to_mp4};
+use crate::capture::screen;
+use crate::types::{CaptureBackendKind, DualLayout, RecordingQuality, Region, SecondaryVideo};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::process::Child;
+
+/// Extra FFmpeg capture (second region, other monitors), paused, resumed and
+/// stopped along with the main one
+pub struct SecondaryCapture {
+    region: Region,
+    backend: CaptureBackendKind,
+    framerate: u32,
+    temp_dir: PathBuf,
+    /// File name stem, `{clip_id}_{name}`
+    stem: String,
+    child: Option<Child>,
+    current_path: PathBuf,
+    segments: Vec<PathBuf>,
+    offset_ms: u64,
+}
+
+impl SecondaryCapture {
+    /// Start capturing `region` for the clip `clip_id`, whose own capture
+    /// started at `main_started`. `name` tells the files of each capture apart.
+    pub fn start(
+        region: Region,
+        temp_dir: &Path,
+        clip_id: &str,
+        name: &str,
+        backend: CaptureBackendKind,
+        framerate: u32,
+        quality: &RecordingQuality,
+        main_started: Instant,
+    ) -> Result<Self, String> {
+        let stem = format!("{}_{}", clip_id, name);
+        let current_path = temp_dir.join(format!("{}.mkv", stem));
+        let child = screen::start_capture(backend, Some(&region), None, &current_path, framerate, quality, None).map_err(|e| e.to_string())?;
+        Ok(Self {
+            region,
+            backend,
+            framerate,
+            temp_dir: temp_dir.to_path_buf(),
+            stem,
+            child: Some(child),
+            current_path,
+            segments: Vec::new(),
+            offset_ms: super::group::offset_ms(main_started, Instant::now()),
+        })
+    }
+
+    /// Close the current segment
+    pub async fn pause(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            if let Err(e) = screen::stop_capture(&mut child).await {
+                tracing::warn!("Failed to stop capture {}: {}", self.stem, e);
+            }
+            self.segments.push(self.current_path.clone());
+        }
+    }
+
+    /// Open segment number `index`, as the main capture does on resume
+    pub fn resume(&mut self, index: u32, quality: &RecordingQuality) -> Result<(), String> {
+        let path = self.temp_dir.join(format!("{}_seg{}.mkv", self.stem, index));
+        let child = screen::start_capture(self.backend, Some(&self.region), None, &path, self.framerate, quality, None).map_err(|e| e.to_string())?;
+        self.child = Some(child);
+        self.current_path = path;
+        Ok(())
+    }
+
+    /// Stop and assemble the segments into one MP4. None when nothing usable
+    /// was recorded: the main clip is then kept without it.
+    pub async fn finish(mut self) -> Option<SecondaryVideo> {
+        self.pause().await;
+        let segments: Vec<PathBuf> = self.segments.into_iter().filter(|p| p.exists()).collect();
+        if segments.is_empty() {
+            tracing::warn!("Capture {} produced no file", self.stem);
+            return None;
+        }
+
+        let output = self.temp_dir.join(format!("{}.mp4", self.stem));
+        let result = if segments.len() == 1 {
+            remux_to_mp4(&segments[0], &output).await
+        } else {
+            concat_segments(&segments, &output).await
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to assemble capture {}: {}", self.stem, e);
+            return None;
+        }
+        for seg in &segments {
+            let _ = std::fs::remove_file(seg);
+        }
+
+        Some(SecondaryVideo {
+            path: output,
+            region: self.region,
+            offset_ms: self.offset_ms,
+            layout: DualLayout::default(),
+        })
+    }
+
+    /// Kill the capture and delete what it wrote
+    pub fn cancel(mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.start_kill();
+        }
+        for path in self.segments.iter().chain(std::iter::once(&self.current_path)) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::state::AppState;
+use crate::types::{Clip, Take};
+use std::collections::HashMap;
+
+/// `clip`'s media as the take `id`
+fn take_of(clip: &Clip, id: String) -> Take {
+    Take { id, media: clip.media.clone() }
+}
+
+/// Take `clip` plays, None when it was never retaken
+pub fn selected(clip: &Clip) -> Option<usize> {
+    clip.takes.iter().position(|t| t.media.path == clip.media.path)
+}
+
+/// Move the data recorded with a take from the key `from` to `to`
+fn rekey<T>(map: &mut HashMap<String, T>, from: &str, to: &str) {
+    match map.remove(from) {
+        Some(value) => {
+            map.insert(to.to_string(), value);
+        }
+        None => {
+            map.remove(to);
+        }
+    }
+}
+
+fn move_recorded_data(s: &mut AppState, from: &str, to: &str) {
+    rekey(&mut s.clip_keystrokes, from, to);
+    rekey(&mut s.clip_cursor_positions, from, to);
+    rekey(&mut s.clip_gamepad, from, to);
+    rekey(&mut s.clip_markers, from, to);
+}
+
+/// Add the new recording `recorded` as the last take of `clip_id` and play
+/// it. What was recorded with it is under `recorded.id`.
+pub fn add(s: &mut AppState, clip_id: &str, recorded: &Clip) -> Result<Clip, String> {
+    let clip = s
+        .clips
+        .iter_mut()
+        .find(|c| c.id == clip_id)
+        .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+    if clip.takes.is_empty() {
+        // The first recording becomes take 0, its data keyed anew when
+        // it is no longer selected
+        let first = take_of(clip, uuid::Uuid::new_v4().to_string());
+        clip.takes.push(first);
+    }
+    clip.takes.push(take_of(recorded, recorded.id.clone()));
+    let index = clip.takes.len() - 1;
+    select(s, clip_id, index)
+}
+
+/// Play take `index` of `clip_id`. The take left keeps its trims, and its
+/// cursor path, keystrokes and markers under its own id until chosen again.
+pub fn select(s: &mut AppState, clip_id: &str, index: usize) -> Result<Clip, String> {
+    let clip = s
+        .clips
+        .iter_mut()
+        .find(|c| c.id == clip_id)
+        .ok_or_else(|| crate::i18n::tf("clip_not_found", &[&clip_id]))?;
+    let current = match selected(clip) {
+        Some(current) => current,
+        // Never retaken: the only take is the clip itself
+        None if clip.takes.is_empty() && index == 0 => return Ok(clip.clone()),
+        None => return Err(crate::i18n::t("current_take_not_found")),
+    };
+    if index >= clip.takes.len() {
+        return Err(crate::i18n::tf("take_not_found", &[&(index + 1)]));
+    }
+    if index == current {
+        return Ok(clip.clone());
+    }
+
+    let left_id = clip.takes[current].id.clone();
+    let left = take_of(clip, left_id.clone());
+    clip.takes[current] = left;
+    let chosen = clip.takes[index].clone();
+    // Its id, zooms and place in the timeline stay
+    clip.media = chosen.media.clone();
+    let clip = clip.clone();
+
+    move_recorded_data(s, clip_id, &left_id);
+    move_recorded_data(s, &chosen.id, clip_id);
+    Ok(clip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ClipMedia, CursorPosition, Region, test_clip};
+
+    fn clip(id: &str, path: &str, duration_ms: u64) -> Clip {
+        let clip = test_clip(id, duration_ms);
+        Clip {
+            media: ClipMedia { path: path.into(), region: Region { x: 0, y: 0, width: 1280, height: 720 }, ..clip.media },
+            ..clip
+        }
+    }
+
+    fn cursor_at(timestamp_ms: u64) -> Vec<CursorPosition> {
+        vec![CursorPosition { timestamp_ms, x: 0.5, y: 0.5 }]
+    }
+
+    #[test]
+    fn test_retake_is_shown_and_the_first_take_kept() {
+        let mut s = AppState::default();
+        let mut first = clip("slot", "first.mp4", 5000);
+        first.media.trim_start_ms = 800;
+        s.clips.push(first);
+        s.clip_cursor_positions.insert("slot".into(), cursor_at(1));
+        s.clip_cursor_positions.insert("rec2".into(), cursor_at(2));
+
+        let shown = add(&mut s, "slot", &clip("rec2", "second.mp4", 4000)).unwrap();
+        assert_eq!((shown.id.as_str(), shown.media.duration_ms, shown.media.trim_start_ms), ("slot", 4000, 0));
+        assert_eq!(shown.takes.len(), 2);
+        assert_eq!(selected(&shown), Some(1));
+        assert_eq!(s.clip_cursor_positions["slot"][0].timestamp_ms, 2);
+        let first_id = shown.takes[0].id.clone();
+        assert_eq!(s.clip_cursor_positions[&first_id][0].timestamp_ms, 1);
+
+        // Back to the first take, with its trim and cursor path
+        let shown = select(&mut s, "slot", 0).unwrap();
+        assert_eq!((shown.media.path.to_str(), shown.media.trim_start_ms), (Some("first.mp4"), 800));
+        assert_eq!(s.clip_cursor_positions["slot"][0].timestamp_ms, 1);
+        assert_eq!(s.clip_cursor_positions["rec2"][0].timestamp_ms, 2);
+        assert!(!s.clip_cursor_positions.contains_key(&first_id));
+    }
+
+    #[test]
+    fn test_select_out_of_range() {
+        let mut s = AppState::default();
+        s.clips.push(clip("slot", "first.mp4", 5000));
+        assert!(select(&mut s, "slot", 0).is_ok());
+        assert!(select(&mut s, "slot", 1).is_err());
+        add(&mut s, "slot", &clip("rec2", "second.mp4", 4000)).unwrap();
+        assert!(select(&mut s, "slot", 2).is_err());
+        assert!(select(&mut s, "nope", 0).is_err());
+    }
+}
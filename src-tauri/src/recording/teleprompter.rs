@@ -0,0 +1,110 @@
+use super::drawing::exclude_from_capture;
+use crate::state::AppState;
+use crate::types::{RecordingState, ScriptMark, ScriptTrack};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder};
+
+const TELEPROMPTER_WINDOW: &str = "teleprompter";
+/// Window size in physical pixels, above the top center of the primary monitor
+const WIDTH: u32 = 760;
+const HEIGHT: u32 = 260;
+const TOP_MARGIN: i32 = 40;
+
+/// Lines shown by the teleprompter, blank ones dropped. The window splits
+/// the script the same way, so mark line numbers index into this.
+pub fn script_lines(script: &str) -> Vec<String> {
+    script.lines().map(str::trim).filter(|l| !l.is_empty()).map(String::from).collect()
+}
+
+/// Open the teleprompter over the top of the primary monitor. Hidden from
+/// the capture; it scrolls with the recording time, so it stops on pause.
+pub fn open_window(app: &AppHandle) {
+    if let Some(w) = app.get_webview_window(TELEPROMPTER_WINDOW) {
+        let _ = w.close();
+    }
+
+    let x = match app.primary_monitor() {
+        Ok(Some(m)) => m.position().x + (m.size().width.saturating_sub(WIDTH) / 2) as i32,
+        _ => 0,
+    };
+    let y = match app.primary_monitor() {
+        Ok(Some(m)) => m.position().y + TOP_MARGIN,
+        _ => TOP_MARGIN,
+    };
+
+    let window = WebviewWindowBuilder::new(app, TELEPROMPTER_WINDOW, WebviewUrl::App("/teleprompter".into()))
+        .title("Prompteur")
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(true)
+        .focused(false)
+        .visible(false)
+        .build();
+    let window = match window {
+        Ok(w) => w,
+        Err(e) => {
+            tracing::warn!("Failed to open teleprompter: {}", e);
+            return;
+        }
+    };
+    let _ = window.set_position(PhysicalPosition::new(x, y));
+    let _ = window.set_size(PhysicalSize::new(WIDTH, HEIGHT));
+    exclude_from_capture(&window);
+    let _ = window.show();
+
+    let state = app.state::<Mutex<AppState>>();
+    let Ok(mut s) = state.lock() else { return };
+    // The recording may have ended while the window was being built
+    if s.recording_state == RecordingState::Idle {
+        let _ = window.close();
+        return;
+    }
+    s.teleprompter_window = Some(window);
+}
+
+/// Note that `line` reached the reading line at `now_ms`. Repeated reports
+/// of the same line are dropped; going back (the reader scrolled up to
+/// retake a sentence) is kept.
+pub fn record_line(marks: &mut Vec<ScriptMark>, line: usize, now_ms: u64) {
+    if marks.last().is_some_and(|m| m.line == line) {
+        return;
+    }
+    marks.push(ScriptMark { timestamp_ms: now_ms, line });
+}
+
+/// Close the window and hand the marks of the recording that just ended,
+/// with the script they refer to. None when no teleprompter was used.
+pub fn finish(s: &mut AppState) -> Option<ScriptTrack> {
+    if let Some(w) = s.teleprompter_window.take() {
+        let _ = w.close();
+    }
+    let marks = std::mem::take(&mut s.script_marks);
+    let settings = s.teleprompter.as_ref()?;
+    if marks.is_empty() {
+        return None;
+    }
+    Some(ScriptTrack { lines: script_lines(&settings.script), marks })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_lines_skip_blank_lines() {
+        let lines = script_lines("  Bonjour à tous\n\n\r\nAujourd'hui on installe ClipFlow  \r\n");
+        assert_eq!(lines, vec!["Bonjour à tous", "Aujourd'hui on installe ClipFlow"]);
+    }
+
+    #[test]
+    fn test_record_line_keeps_changes_only() {
+        let mut marks = Vec::new();
+        record_line(&mut marks, 0, 0);
+        record_line(&mut marks, 0, 400);
+        record_line(&mut marks, 1, 2500);
+        record_line(&mut marks, 0, 4000);
+        let lines: Vec<(u64, usize)> = marks.iter().map(|m| (m.timestamp_ms, m.line)).collect();
+        assert_eq!(lines, vec![(0, 0), (2500, 1), (4000, 0)]);
+    }
+}
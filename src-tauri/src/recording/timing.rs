@@ -0,0 +1,353 @@
+use crate::types::{CaptureRate, CaptureRateOption, MonitorArea, Region};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Slowest capture offered from a refresh rate divisor
+const MIN_FPS: u32 = 24;
+/// Fastest capture, whatever the monitor
+const MAX_FPS: u32 = 240;
+/// FFmpeg writes its capture progress here, overwritten by every segment
+const PROGRESS_FILE: &str = "capture.progress";
+
+/// Refresh rate of the monitor recording `region`: the one holding it, the
+/// primary one for the whole desktop or a region across monitors
+pub fn refresh_rate(region: Option<&Region>, monitors: &[MonitorArea]) -> Option<u32> {
+    let monitor = crate::capture::backend::monitor_of(region, monitors)
+        .and_then(|i| monitors.get(i))
+        .or_else(|| monitors.iter().find(|m| m.is_primary))?;
+    (monitor.refresh_rate > 0).then_some(monitor.refresh_rate)
+}
+
+/// Capture rates dividing `refresh` exactly, fastest first. Frames then
+/// land on every n-th refresh instead of drifting against it, which is
+/// what makes 30 fps on a 144 Hz monitor judder.
+pub fn options(refresh: u32) -> Vec<CaptureRateOption> {
+    (1..=refresh)
+        .filter(|d| refresh % d == 0)
+        .map(|divisor| CaptureRateOption { divisor, fps: refresh / divisor })
+        .filter(|o| (MIN_FPS..=MAX_FPS).contains(&o.fps))
+        .collect()
+}
+
+/// Frame rate to capture at: the timeline rate by default, else the
+/// refresh rate divided as asked. Back to the default when the monitor's
+/// rate is unknown or the divisor doesn't give a usable rate.
+pub fn framerate(rate: CaptureRate, refresh: Option<u32>) -> u32 {
+    match (rate, refresh) {
+        (CaptureRate::RefreshDivisor(d), Some(refresh)) => {
+            let d = d.max(1);
+            options(refresh)
+                .into_iter()
+                .find(|o| o.divisor == d)
+                .map(|o| o.fps)
+                .unwrap_or(crate::normalize::FPS)
+        }
+        _ => crate::normalize::FPS,
+    }
+}
+
+pub fn progress_path(temp_dir: &Path) -> PathBuf {
+    temp_dir.join(PROGRESS_FILE)
+}
+
+/// Frame rate FFmpeg reports achieving in its `-progress` output
+pub fn achieved_fps(progress: &Path) -> Option<f64> {
+    parse_progress_fps(&progress_tail(progress)?)
+}
+
+/// End of the progress file: only the last report matters
+fn progress_tail(progress: &Path) -> Option<String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(progress).ok()?;
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(1024))).ok()?;
+    let mut tail = String::new();
+    file.take(1024).read_to_string(&mut tail).ok()?;
+    Some(tail)
+}
+
+/// Last value of `key` in progress reports
+fn last_value<'a>(progress: &'a str, key: &str) -> Option<&'a str> {
+    progress
+        .lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix(key)?.strip_prefix('='))
+}
+
+fn parse_progress_fps(progress: &str) -> Option<f64> {
+    last_value(progress, "fps")
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|fps| *fps > 0.0)
+}
+
+/// Timestamp of the last frame written, in ms of video. "N/A" until the
+/// first frame is out.
+fn parse_progress_video_ms(progress: &str) -> Option<u64> {
+    last_value(progress, "out_time_us")
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|us| *us >= 0)
+        .map(|us| us as u64 / 1000)
+}
+
+/// Time of the recording shared with the capture threads: wall-clock time
+/// since the start less the pauses, which is where the concatenated video
+/// is. Stopped while paused.
+#[derive(Clone)]
+pub struct RecordingClock {
+    start: Instant,
+    pauses: Arc<Mutex<Pauses>>,
+}
+
+#[derive(Default)]
+struct Pauses {
+    total: Duration,
+    since: Option<Instant>,
+}
+
+impl RecordingClock {
+    pub fn new(start: Instant) -> Self {
+        RecordingClock { start, pauses: Arc::default() }
+    }
+
+    pub fn pause(&self) {
+        self.pause_at(Instant::now());
+    }
+
+    pub fn resume(&self) {
+        self.resume_at(Instant::now());
+    }
+
+    fn pause_at(&self, at: Instant) {
+        if let Ok(mut p) = self.pauses.lock() {
+            p.since.get_or_insert(at);
+        }
+    }
+
+    fn resume_at(&self, at: Instant) {
+        if let Ok(mut p) = self.pauses.lock() {
+            if let Some(since) = p.since.take() {
+                p.total += at.saturating_duration_since(since);
+            }
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pauses.lock().map(|p| p.since.is_some()).unwrap_or(false)
+    }
+
+    /// Recording time at the wall-clock `at`, held during a pause
+    pub fn at(&self, at: Instant) -> Duration {
+        let paused = self
+            .pauses
+            .lock()
+            .map(|p| p.total + p.since.map(|since| at.saturating_duration_since(since)).unwrap_or_default())
+            .unwrap_or_default();
+        at.saturating_duration_since(self.start).saturating_sub(paused)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.at(Instant::now())
+    }
+
+    pub fn elapsed_ms(&self) -> u64 {
+        self.elapsed().as_millis() as u64
+    }
+}
+
+/// How far the video's timestamps are ahead of (positive) or behind the
+/// recording clock at `wall_ms`, relative to where the recording started
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyncPoint {
+    pub wall_ms: u64,
+    pub drift_ms: i64,
+}
+
+/// Follows FFmpeg's progress reports to measure how the captured video
+/// drifts from the wall clock: frames dropped or stamped late by a busy
+/// capture make the video shorter than the time it took to record.
+///
+/// Only the drift is kept, not the offset between both clocks: reports
+/// come out after the encoder's delay, which would otherwise shift every
+/// sample by that much.
+pub struct DriftTracker {
+    progress: PathBuf,
+    last_modified: Option<SystemTime>,
+    last_len: u64,
+    last_video_ms: u64,
+    /// Clock offset the current segment started at, less the drift carried
+    /// from earlier segments
+    segment_base: Option<i64>,
+    points: Vec<SyncPoint>,
+}
+
+impl DriftTracker {
+    pub fn new(progress: PathBuf) -> Self {
+        DriftTracker { progress, last_modified: None, last_len: 0, last_video_ms: 0, segment_base: None, points: Vec::new() }
+    }
+
+    /// Take the last report if FFmpeg wrote one since the previous call
+    pub fn poll(&mut self, clock: &RecordingClock) {
+        let Ok(meta) = std::fs::metadata(&self.progress) else {
+            return;
+        };
+        let Ok(modified) = meta.modified() else {
+            return;
+        };
+        if self.last_modified == Some(modified) {
+            return;
+        }
+        self.last_modified = Some(modified);
+        // Each pause starts a new FFmpeg, which rewrites the file
+        if meta.len() < self.last_len {
+            self.segment_base = None;
+        }
+        self.last_len = meta.len();
+
+        let Some(video_ms) = progress_tail(&self.progress).as_deref().and_then(parse_progress_video_ms) else {
+            return;
+        };
+        // The report was written when the file was, maybe a poll ago, maybe
+        // just before a pause
+        let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+        let now = Instant::now();
+        let written = now.checked_sub(age).unwrap_or(now);
+        self.record(clock.at(written).as_millis() as u64, video_ms);
+    }
+
+    fn record(&mut self, wall_ms: u64, video_ms: u64) {
+        if self.points.last().is_some_and(|p| p.wall_ms >= wall_ms) {
+            return;
+        }
+        if video_ms < self.last_video_ms {
+            self.segment_base = None;
+        }
+        self.last_video_ms = video_ms;
+        let offset = video_ms as i64 - wall_ms as i64;
+        let carried = self.points.last().map_or(0, |p| p.drift_ms);
+        let base = *self.segment_base.get_or_insert(offset - carried);
+        self.points.push(SyncPoint { wall_ms, drift_ms: offset - base });
+    }
+
+    pub fn points(&self) -> &[SyncPoint] {
+        &self.points
+    }
+}
+
+/// Video timestamp of the wall-clock `wall_ms`, the drift interpolated
+/// between sync points and held outside them
+pub fn video_ms(points: &[SyncPoint], wall_ms: u64) -> u64 {
+    let drift = match points.partition_point(|p| p.wall_ms <= wall_ms) {
+        0 => points.first().map_or(0.0, |p| p.drift_ms as f64),
+        next if next == points.len() => points[next - 1].drift_ms as f64,
+        next => {
+            let (a, b) = (&points[next - 1], &points[next]);
+            let f = (wall_ms - a.wall_ms) as f64 / (b.wall_ms - a.wall_ms).max(1) as f64;
+            a.drift_ms as f64 + (b.drift_ms - a.drift_ms) as f64 * f
+        }
+    };
+    (wall_ms as f64 + drift).round().max(0.0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(x: i32, refresh_rate: u32, is_primary: bool) -> MonitorArea {
+        let bounds = Region { x, y: 0, width: 1920, height: 1080 };
+        MonitorArea { bounds: bounds.clone(), work_area: bounds, is_primary, refresh_rate }
+    }
+
+    #[test]
+    fn test_options_divide_the_refresh_rate() {
+        let fps: Vec<u32> = options(144).iter().map(|o| o.fps).collect();
+        assert_eq!(fps, vec![144, 72, 48, 36, 24]);
+        let fps: Vec<u32> = options(60).iter().map(|o| o.fps).collect();
+        assert_eq!(fps, vec![60, 30]);
+    }
+
+    #[test]
+    fn test_framerate() {
+        assert_eq!(framerate(CaptureRate::Default, Some(144)), 30);
+        assert_eq!(framerate(CaptureRate::RefreshDivisor(3), Some(144)), 48);
+        assert_eq!(framerate(CaptureRate::RefreshDivisor(1), Some(60)), 60);
+        // Unknown refresh rate, or a divisor giving a rate out of range
+        assert_eq!(framerate(CaptureRate::RefreshDivisor(2), None), 30);
+        assert_eq!(framerate(CaptureRate::RefreshDivisor(5), Some(60)), 30);
+    }
+
+    #[test]
+    fn test_refresh_rate_of_the_recorded_monitor() {
+        let monitors = [monitor(0, 60, true), monitor(1920, 144, false)];
+        let region = Region { x: 2000, y: 100, width: 800, height: 600 };
+        assert_eq!(refresh_rate(Some(&region), &monitors), Some(144));
+        assert_eq!(refresh_rate(None, &monitors), Some(60));
+        assert_eq!(refresh_rate(None, &[monitor(0, 0, true)]), None);
+    }
+
+    #[test]
+    fn test_parse_progress_fps() {
+        let progress = "frame=120\nfps=29.97\nprogress=continue\nframe=150\nfps=47.5\nprogress=continue\n";
+        assert_eq!(parse_progress_fps(progress), Some(47.5));
+        assert_eq!(parse_progress_fps("frame=0\nfps=0.00\n"), None);
+        assert_eq!(parse_progress_fps(""), None);
+    }
+
+    #[test]
+    fn test_parse_progress_video_ms() {
+        let progress = "frame=0\nout_time_us=N/A\nprogress=continue\nframe=60\nout_time_us=2000000\nout_time_ms=2000000\n";
+        assert_eq!(parse_progress_video_ms(progress), Some(2000));
+        assert_eq!(parse_progress_video_ms("out_time_us=N/A\n"), None);
+    }
+
+    fn tracker() -> DriftTracker {
+        DriftTracker::new(PathBuf::from("capture.progress"))
+    }
+
+    #[test]
+    fn test_drift_ignores_the_encoder_delay() {
+        let mut t = tracker();
+        // 400 ms behind from the first report: only the loss afterwards counts
+        t.record(1400, 1000);
+        t.record(2400, 2000);
+        t.record(10_400, 9900);
+        let drifts: Vec<i64> = t.points().iter().map(|p| p.drift_ms).collect();
+        assert_eq!(drifts, vec![0, 0, -100]);
+        assert_eq!(video_ms(t.points(), 500), 500);
+        assert_eq!(video_ms(t.points(), 6400), 6350);
+        assert_eq!(video_ms(t.points(), 20_000), 19_900);
+    }
+
+    #[test]
+    fn test_drift_carries_over_segments() {
+        let mut t = tracker();
+        t.record(1000, 1000);
+        t.record(5000, 4800);
+        // After a pause the next FFmpeg counts from zero again
+        t.record(20_000, 500);
+        t.record(24_000, 4400);
+        let drifts: Vec<i64> = t.points().iter().map(|p| p.drift_ms).collect();
+        assert_eq!(drifts, vec![0, -200, -200, -300]);
+    }
+
+    #[test]
+    fn test_clock_leaves_out_pauses() {
+        let start = Instant::now();
+        let clock = RecordingClock::new(start);
+        let s = |secs: u64| start + Duration::from_secs(secs);
+        clock.pause_at(s(10));
+        assert!(clock.is_paused());
+        // Held during the pause, including for what happened before it
+        assert_eq!(clock.at(s(15)), Duration::from_secs(10));
+        assert_eq!(clock.at(s(8)), Duration::from_secs(8));
+        clock.resume_at(s(25));
+        assert!(!clock.is_paused());
+        assert_eq!(clock.at(s(30)), Duration::from_secs(15));
+    }
+
+    #[test]
+    fn test_video_ms_without_sync_points() {
+        assert_eq!(video_ms(&[], 1234), 1234);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::capture::audio::PeakMeter;
+use crate::state::{AppState, AudioCaptureHandle};
+use crate::types::{AudioSource, RecordingState, Voiceover};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Microphone recording while the preview plays from `voiceover.start_ms`
+pub struct VoiceoverCapture {
+    pub voiceover: Voiceover,
+    pub started: Instant,
+    pub audio: AudioCaptureHandle,
+}
+
+/// Start recording a narration that begins at `start_ms` in the timeline.
+/// Only the mic is captured, whatever the recording's audio source.
+pub fn start(s: &mut AppState, start_ms: u64) -> Result<(), String> {
+    if s.recording_state != RecordingState::Idle {
+        return Err(crate::i18n::t("recording_in_progress"));
+    }
+    if s.voiceover_capture.is_some() {
+        return Err(crate::i18n::t("voiceover_in_progress"));
+    }
+    std::fs::create_dir_all(&s.temp_dir).map_err(|e| e.to_string())?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    // Named after its id so storage cleanup knows it
+    let path = s.temp_dir.join(format!("{}_voiceover.wav", id));
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let meter = Arc::new(PeakMeter::default());
+    // Never monitored: the mic would pick the playback up again, and the
+    // mute toggle belongs to screen recordings
+    let handle = crate::capture::audio::start_mic_capture_device(
+        &path,
+        stop_flag.clone(),
+        s.selected_mic.as_deref(),
+        false,
+        Arc::new(AtomicBool::new(false)),
+        s.audio_format,
+        meter.clone(),
+    )?;
+    tracing::info!("Voiceover started at {}ms: {:?}", start_ms, path);
+    s.voiceover_capture = Some(VoiceoverCapture {
+        voiceover: Voiceover { id, path, start_ms, duration_ms: 0 },
+        started: Instant::now(),
+        audio: AudioCaptureHandle { join_handle: Some(handle), stop_flag, source: AudioSource::Microphone, meter },
+    });
+    Ok(())
+}
+
+/// Stop the mic of `capture` and return the narration, None when nothing
+/// was recorded. Joins the capture thread: call it outside the state lock.
+pub fn finish(mut capture: VoiceoverCapture) -> Option<Voiceover> {
+    capture.audio.stop_flag.store(true, Ordering::Relaxed);
+    if let Some(handle) = capture.audio.join_handle.take() {
+        let _ = handle.join();
+    }
+    let mut voiceover = capture.voiceover;
+    voiceover.duration_ms = wav_duration_ms(&voiceover.path)
+        .unwrap_or_else(|| capture.started.elapsed().as_millis() as u64);
+    if voiceover.duration_ms == 0 || !voiceover.path.exists() {
+        let _ = std::fs::remove_file(&voiceover.path);
+        return None;
+    }
+    Some(voiceover)
+}
+
+/// Remove the narration `id` and its file
+pub fn delete(s: &mut AppState, id: &str) -> Result<(), String> {
+    let index = s
+        .voiceovers
+        .iter()
+        .position(|v| v.id == id)
+        .ok_or_else(|| crate::i18n::tf("voiceover_not_found", &[&id]))?;
+    let voiceover = s.voiceovers.remove(index);
+    let _ = std::fs::remove_file(&voiceover.path);
+    Ok(())
+}
+
+/// Length of the WAV at `path`, from its frame count
+fn wav_duration_ms(path: &Path) -> Option<u64> {
+    let reader = hound::WavReader::open(path).ok()?;
+    let rate = reader.spec().sample_rate as u64;
+    (rate > 0).then(|| reader.duration() as u64 * 1000 / rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wav_duration() {
+        let path = std::env::temp_dir().join(format!("clipflow-voiceover-{}.wav", uuid::Uuid::new_v4()));
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 48_000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        // 1.5 s of stereo
+        for _ in 0..72_000 * 2 {
+            writer.write_sample(0.0f32).unwrap();
+        }
+        writer.finalize().unwrap();
+        assert_eq!(wav_duration_ms(&path), Some(1500));
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(wav_duration_ms(&path), None);
+    }
+
+    #[test]
+    fn test_delete_unknown() {
+        let mut s = AppState::default();
+        assert!(delete(&mut s, "nope").is_err());
+    }
+}
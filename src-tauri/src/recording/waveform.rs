@@ -0,0 +1,99 @@
+use crate::events::{self, AppEvent};
+use crate::state::AppState;
+use crate::types::{AudioSource, RecordingState, WaveformTrack};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const INTERVAL_MS: u64 = 100;
+/// Peaks sent per track, the last 6 s
+const HISTORY: usize = 60;
+
+/// Rolling peaks of the tracks being recorded
+#[derive(Default)]
+struct Waveform {
+    tracks: Vec<(AudioSource, VecDeque<u8>)>,
+}
+
+impl Waveform {
+    /// Add the latest `peak` of `source`, in [0, 1]
+    fn push(&mut self, source: AudioSource, peak: f32) {
+        let index = match self.tracks.iter().position(|(s, _)| *s == source) {
+            Some(index) => index,
+            None => {
+                self.tracks.push((source, VecDeque::with_capacity(HISTORY)));
+                self.tracks.len() - 1
+            }
+        };
+        let peaks = &mut self.tracks[index].1;
+        peaks.push_back((peak.clamp(0.0, 1.0) * 255.0).round() as u8);
+        while peaks.len() > HISTORY {
+            peaks.pop_front();
+        }
+    }
+
+    fn tracks(&self) -> Vec<WaveformTrack> {
+        self.tracks
+            .iter()
+            .map(|(source, peaks)| WaveformTrack { source: *source, peaks: peaks.iter().copied().collect() })
+            .collect()
+    }
+}
+
+/// Send the levels of the audio tracks every `INTERVAL_MS` while recording,
+/// so a silent mic shows at once. Pauses keep the last waveform. Exits when
+/// the session ends.
+pub fn spawn_watcher(app: AppHandle, session: u64) {
+    tauri::async_runtime::spawn(async move {
+        watch(app, session).await;
+    });
+}
+
+async fn watch(app: AppHandle, session: u64) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut waveform = Waveform::default();
+    loop {
+        tokio::time::sleep(Duration::from_millis(INTERVAL_MS)).await;
+        let meters: Vec<_> = {
+            let Ok(s) = state.lock() else {
+                return;
+            };
+            if s.recording_session != session || s.recording_state == RecordingState::Idle {
+                return;
+            }
+            if s.recording_state != RecordingState::Recording {
+                continue;
+            }
+            s.audio_handles.iter().map(|h| (h.source, h.meter.clone())).collect()
+        };
+        // Between two segments
+        if meters.is_empty() {
+            continue;
+        }
+        for (source, meter) in meters {
+            waveform.push(source, meter.take());
+        }
+        events::emit(&app, AppEvent::AudioWaveform { tracks: waveform.tracks() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waveform_keeps_the_latest_peaks_per_track() {
+        let mut waveform = Waveform::default();
+        for i in 0..HISTORY + 5 {
+            waveform.push(AudioSource::Microphone, if i == HISTORY + 4 { 1.0 } else { 0.0 });
+        }
+        waveform.push(AudioSource::System, 0.5);
+        let tracks = waveform.tracks();
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].source, AudioSource::Microphone);
+        assert_eq!(tracks[0].peaks.len(), HISTORY);
+        assert_eq!(tracks[0].peaks.last(), Some(&255));
+        assert_eq!(tracks[1].peaks, vec![128]);
+    }
+}
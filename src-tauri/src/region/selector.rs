@@ -1,29 +1,51 @@
-use crate::types::WindowInfo;
+use crate::capture::snapshot::{bgra_to_png_data_url, read_bitmap_pixels};
+use crate::types::{CaptureWindow, MonitorArea, Region, WindowInfo};
 use windows::core::BOOL;
-use windows::Win32::Foundation::{HWND, LPARAM, RECT};
+use windows::Win32::Foundation::{HWND, LPARAM, POINT, RECT};
+use windows::Win32::Graphics::Gdi::{HDC, HMONITOR};
+
+/// Maximum distance (in physical pixels) at which an edge snaps to a target.
+const SNAP_THRESHOLD: i32 = 8;
+
+/// Width of window picker thumbnails (height follows aspect ratio).
+const THUMBNAIL_WIDTH: u32 = 240;
+
+struct EnumContext {
+    windows: Vec<WindowInfo>,
+    with_thumbnails: bool,
+}
 
 /// Enumerate visible windows using Win32 EnumWindows
 pub fn enumerate_visible_windows() -> Result<Vec<WindowInfo>, String> {
-    let mut windows: Vec<WindowInfo> = Vec::new();
+    enumerate_windows(false)
+}
+
+/// Enumerate visible windows, capturing a small thumbnail of each one
+pub fn enumerate_visible_windows_with_thumbnails() -> Result<Vec<WindowInfo>, String> {
+    enumerate_windows(true)
+}
+
+fn enumerate_windows(with_thumbnails: bool) -> Result<Vec<WindowInfo>, String> {
+    let mut ctx = EnumContext { windows: Vec::new(), with_thumbnails };
 
     unsafe {
         let result = windows::Win32::UI::WindowsAndMessaging::EnumWindows(
             Some(enum_callback),
-            LPARAM(&mut windows as *mut Vec<WindowInfo> as isize),
+            LPARAM(&mut ctx as *mut EnumContext as isize),
         );
         if result.is_err() {
             return Err("EnumWindows failed".into());
         }
     }
 
-    Ok(windows)
+    Ok(ctx.windows)
 }
 
 unsafe extern "system" fn enum_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
     use windows::Win32::UI::WindowsAndMessaging::*;
     use windows::Win32::Graphics::Dwm::*;
 
-    let windows = &mut *(lparam.0 as *mut Vec<WindowInfo>);
+    let ctx = &mut *(lparam.0 as *mut EnumContext);
 
     // Skip invisible windows
     if !IsWindowVisible(hwnd).as_bool() {
@@ -74,13 +96,289 @@ unsafe extern "system" fn enum_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
         return BOOL(1);
     }
 
-    windows.push(WindowInfo {
+    let thumbnail = if ctx.with_thumbnails {
+        capture_window_thumbnail(hwnd, width, height)
+    } else {
+        None
+    };
+
+    ctx.windows.push(WindowInfo {
         title,
         x: rect.left,
         y: rect.top,
         width,
         height,
+        client_rect: client_rect_on_screen(hwnd),
+        hwnd: hwnd.0 as isize,
+        thumbnail,
     });
 
     BOOL(1)
 }
+
+/// Render a window into an offscreen bitmap with PrintWindow and return a
+/// downscaled PNG data URL. Works for occluded windows, unlike a screen grab.
+unsafe fn capture_window_thumbnail(hwnd: HWND, width: u32, height: u32) -> Option<String> {
+    use windows::Win32::Graphics::Gdi::*;
+    use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS};
+
+    let screen_dc = GetDC(None);
+    let mem_dc = CreateCompatibleDC(Some(screen_dc));
+    let bitmap = CreateCompatibleBitmap(screen_dc, width as i32, height as i32);
+    let previous = SelectObject(mem_dc, bitmap.into());
+
+    // PW_RENDERFULLCONTENT (2) also captures GPU-composited windows (browsers, Electron)
+    let printed = PrintWindow(hwnd, mem_dc, PRINT_WINDOW_FLAGS(2)).as_bool();
+    let pixels = if printed { read_bitmap_pixels(mem_dc, bitmap, width, height) } else { None };
+
+    SelectObject(mem_dc, previous);
+    let _ = DeleteObject(bitmap.into());
+    let _ = DeleteDC(mem_dc);
+    ReleaseDC(None, screen_dc);
+
+    bgra_to_png_data_url(pixels?, width, height, THUMBNAIL_WIDTH)
+}
+
+/// Client area of a window in screen coordinates (excludes title bar and borders).
+unsafe fn client_rect_on_screen(hwnd: HWND) -> Option<Region> {
+    use windows::Win32::Graphics::Gdi::ClientToScreen;
+    use windows::Win32::UI::WindowsAndMessaging::GetClientRect;
+
+    let mut rect = RECT::default();
+    GetClientRect(hwnd, &mut rect).ok()?;
+    let mut origin = POINT { x: 0, y: 0 };
+    if !ClientToScreen(hwnd, &mut origin).as_bool() {
+        return None;
+    }
+    let width = (rect.right - rect.left).max(0) as u32;
+    let height = (rect.bottom - rect.top).max(0) as u32;
+    if width == 0 || height == 0 {
+        return None;
+    }
+    Some(Region { x: origin.x, y: origin.y, width, height })
+}
+
+/// Where window `hwnd` is on screen, without the drop shadow GetWindowRect
+/// includes: the frame Windows Graphics Capture records. None once it is
+/// closed or minimized.
+pub fn window_bounds(hwnd: isize) -> Option<Region> {
+    use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS};
+    use windows::Win32::UI::WindowsAndMessaging::{IsIconic, IsWindow};
+
+    let hwnd = HWND(hwnd as *mut _);
+    unsafe {
+        if !IsWindow(Some(hwnd)).as_bool() || IsIconic(hwnd).as_bool() {
+            return None;
+        }
+        let mut rect = RECT::default();
+        DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut rect as *mut _ as *mut _,
+            std::mem::size_of::<RECT>() as u32,
+        )
+        .ok()?;
+        let bounds = rect_to_region(&rect);
+        (bounds.width >= 2 && bounds.height >= 2).then_some(bounds)
+    }
+}
+
+/// Bind the capture to window `hwnd`, at its current size rounded down to
+/// even for yuv420p
+pub fn capture_window(hwnd: isize) -> Option<CaptureWindow> {
+    let bounds = window_bounds(hwnd)?;
+    Some(CaptureWindow { hwnd, width: bounds.width & !1, height: bounds.height & !1 })
+}
+
+/// Region recording `window` where it is now, at the size the recording
+/// started with. None once it is closed or minimized.
+pub fn window_region(window: &CaptureWindow) -> Option<Region> {
+    let bounds = window_bounds(window.hwnd)?;
+    Some(Region { width: window.width, height: window.height, ..bounds })
+}
+
+/// Enumerate monitors with their full bounds and work area (minus taskbar)
+pub fn enumerate_monitor_areas() -> Result<Vec<MonitorArea>, String> {
+    use windows::Win32::Graphics::Gdi::EnumDisplayMonitors;
+
+    let mut monitors: Vec<MonitorArea> = Vec::new();
+    let ok = unsafe {
+        EnumDisplayMonitors(
+            None,
+            None,
+            Some(monitor_callback),
+            LPARAM(&mut monitors as *mut Vec<MonitorArea> as isize),
+        )
+    };
+    if !ok.as_bool() {
+        return Err("EnumDisplayMonitors failed".into());
+    }
+    Ok(monitors)
+}
+
+unsafe extern "system" fn monitor_callback(
+    hmonitor: HMONITOR,
+    _hdc: HDC,
+    _rect: *mut RECT,
+    lparam: LPARAM,
+) -> BOOL {
+    use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, MONITORINFO, MONITORINFOEXW};
+
+    let monitors = &mut *(lparam.0 as *mut Vec<MonitorArea>);
+
+    // The extended info names the display device, for its refresh rate
+    let mut info = MONITORINFOEXW {
+        monitorInfo: MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFOEXW>() as u32,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    if GetMonitorInfoW(hmonitor, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO).as_bool() {
+        let m = &info.monitorInfo;
+        monitors.push(MonitorArea {
+            bounds: rect_to_region(&m.rcMonitor),
+            work_area: rect_to_region(&m.rcWork),
+            // MONITORINFOF_PRIMARY
+            is_primary: m.dwFlags & 1 != 0,
+            refresh_rate: refresh_rate(&info.szDevice),
+        });
+    }
+
+    BOOL(1)
+}
+
+/// Current refresh rate of display `device`, 0 when unknown
+unsafe fn refresh_rate(device: &[u16; 32]) -> u32 {
+    use windows::core::PCWSTR;
+    use windows::Win32::Graphics::Gdi::{EnumDisplaySettingsW, DEVMODEW, ENUM_CURRENT_SETTINGS};
+
+    let mut mode = DEVMODEW {
+        dmSize: std::mem::size_of::<DEVMODEW>() as u16,
+        ..Default::default()
+    };
+    if !EnumDisplaySettingsW(PCWSTR(device.as_ptr()), ENUM_CURRENT_SETTINGS, &mut mode).as_bool() {
+        return 0;
+    }
+    // 0 and 1 stand for the hardware default rate
+    if mode.dmDisplayFrequency > 1 { mode.dmDisplayFrequency } else { 0 }
+}
+
+fn rect_to_region(rect: &RECT) -> Region {
+    Region {
+        x: rect.left,
+        y: rect.top,
+        width: (rect.right - rect.left).max(0) as u32,
+        height: (rect.bottom - rect.top).max(0) as u32,
+    }
+}
+
+/// Snap a dragged region to nearby window edges and monitor boundaries.
+pub fn snap_region(region: &Region) -> Result<Region, String> {
+    let windows = enumerate_visible_windows()?;
+    let monitors = enumerate_monitor_areas()?;
+
+    let mut edges_x = Vec::new();
+    let mut edges_y = Vec::new();
+    let mut push_rect = |r: &Region| {
+        edges_x.push(r.x);
+        edges_x.push(r.x + r.width as i32);
+        edges_y.push(r.y);
+        edges_y.push(r.y + r.height as i32);
+    };
+
+    for w in &windows {
+        push_rect(&Region { x: w.x, y: w.y, width: w.width, height: w.height });
+        if let Some(ref client) = w.client_rect {
+            push_rect(client);
+        }
+    }
+    for m in &monitors {
+        push_rect(&m.bounds);
+        push_rect(&m.work_area);
+    }
+
+    Ok(snap_to_edges(region, &edges_x, &edges_y, SNAP_THRESHOLD))
+}
+
+/// Return the candidate closest to `value` within `threshold`, if any.
+fn nearest_edge(value: i32, candidates: &[i32], threshold: i32) -> Option<i32> {
+    candidates
+        .iter()
+        .copied()
+        .filter(|c| (c - value).abs() <= threshold)
+        .min_by_key(|c| (c - value).abs())
+}
+
+/// Snap each side of the region independently to the nearest candidate edge.
+fn snap_to_edges(region: &Region, edges_x: &[i32], edges_y: &[i32], threshold: i32) -> Region {
+    let left = region.x;
+    let top = region.y;
+    let right = region.x + region.width as i32;
+    let bottom = region.y + region.height as i32;
+
+    let new_left = nearest_edge(left, edges_x, threshold).unwrap_or(left);
+    let new_right = nearest_edge(right, edges_x, threshold).unwrap_or(right);
+    let new_top = nearest_edge(top, edges_y, threshold).unwrap_or(top);
+    let new_bottom = nearest_edge(bottom, edges_y, threshold).unwrap_or(bottom);
+
+    // Never collapse the selection: fall back to the original axis if snapping inverts it
+    let (x, width) = if new_right - new_left >= 2 {
+        (new_left, (new_right - new_left) as u32)
+    } else {
+        (region.x, region.width)
+    };
+    let (y, height) = if new_bottom - new_top >= 2 {
+        (new_top, (new_bottom - new_top) as u32)
+    } else {
+        (region.y, region.height)
+    };
+
+    Region { x, y, width, height }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_edge_within_threshold() {
+        assert_eq!(nearest_edge(103, &[0, 100, 200], 8), Some(100));
+    }
+
+    #[test]
+    fn test_nearest_edge_out_of_range() {
+        assert_eq!(nearest_edge(150, &[0, 100, 200], 8), None);
+    }
+
+    #[test]
+    fn test_nearest_edge_picks_closest() {
+        assert_eq!(nearest_edge(105, &[100, 107], 8), Some(107));
+    }
+
+    #[test]
+    fn test_snap_to_edges_all_sides() {
+        let r = snap_to_edges(
+            &Region { x: 3, y: 98, width: 1910, height: 500 },
+            &[0, 1920],
+            &[100, 600],
+            8,
+        );
+        assert_eq!(r, Region { x: 0, y: 100, width: 1920, height: 500 });
+    }
+
+    #[test]
+    fn test_snap_to_edges_no_candidates() {
+        let original = Region { x: 50, y: 50, width: 300, height: 200 };
+        assert_eq!(snap_to_edges(&original, &[], &[], 8), original);
+    }
+
+    #[test]
+    fn test_snap_to_edges_never_collapses() {
+        // Both sides would snap to the same edge
+        let original = Region { x: 98, y: 0, width: 4, height: 100 };
+        let r = snap_to_edges(&original, &[100], &[], 8);
+        assert_eq!(r.x, 98);
+        assert_eq!(r.width, 4);
+    }
+}
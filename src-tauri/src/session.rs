@@ -0,0 +1,163 @@
+use crate::events::{self, AppEvent};
+use crate::recording::manager;
+use crate::state::AppState;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+use windows::core::{w, GUID};
+use windows::Win32::Foundation::{HANDLE, HWND, LPARAM, LRESULT, WPARAM};
+
+const AUTO_PAUSE_REASON: &str = "session";
+
+// Window messages / event codes (see WinUser.h and WtsApi32.h)
+const WM_WTSSESSION_CHANGE: u32 = 0x02B1;
+const WM_POWERBROADCAST: u32 = 0x0218;
+const WTS_SESSION_LOCK: usize = 0x7;
+const WTS_SESSION_UNLOCK: usize = 0x8;
+const PBT_POWERSETTINGCHANGE: usize = 0x8013;
+
+/// GUID_CONSOLE_DISPLAY_STATE: 0 = off, 1 = on, 2 = dimmed
+const GUID_CONSOLE_DISPLAY_STATE: GUID = GUID::from_u128(0x6fe69556_704a_47a0_8f24_c28d936fda47);
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy)]
+enum SessionEvent {
+    Locked,
+    Unlocked,
+    DisplayOff,
+    DisplayOn,
+}
+
+/// Start a background thread that listens for workstation lock/unlock and
+/// display power changes, auto-pausing the recording while the user is away.
+pub fn start_watcher(app: AppHandle) {
+    if APP_HANDLE.set(app).is_err() {
+        return;
+    }
+    std::thread::spawn(|| unsafe {
+        if let Err(e) = run_message_loop() {
+            tracing::warn!("Watcher failed: {}", e);
+        }
+    });
+}
+
+unsafe fn run_message_loop() -> windows::core::Result<()> {
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::System::Power::RegisterPowerSettingNotification;
+    use windows::Win32::System::RemoteDesktop::{WTSRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION};
+    use windows::Win32::UI::WindowsAndMessaging::*;
+
+    let instance = GetModuleHandleW(None)?;
+    let class_name = w!("ClipFlowSessionWatcher");
+
+    let wc = WNDCLASSW {
+        lpfnWndProc: Some(wnd_proc),
+        hInstance: instance.into(),
+        lpszClassName: class_name,
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+
+    // Message-only window: never shown, only receives notifications
+    let hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        class_name,
+        w!(""),
+        WINDOW_STYLE::default(),
+        0,
+        0,
+        0,
+        0,
+        Some(HWND_MESSAGE),
+        None,
+        Some(instance.into()),
+        None,
+    )?;
+
+    WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION)?;
+    RegisterPowerSettingNotification(
+        HANDLE(hwnd.0),
+        &GUID_CONSOLE_DISPLAY_STATE,
+        DEVICE_NOTIFY_WINDOW_HANDLE,
+    )?;
+
+    let mut msg = MSG::default();
+    while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+        let _ = TranslateMessage(&msg);
+        DispatchMessageW(&msg);
+    }
+    Ok(())
+}
+
+unsafe extern "system" fn wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    use windows::Win32::System::Power::POWERBROADCAST_SETTING;
+    use windows::Win32::UI::WindowsAndMessaging::DefWindowProcW;
+
+    match msg {
+        WM_WTSSESSION_CHANGE => match wparam.0 {
+            WTS_SESSION_LOCK => handle_event(SessionEvent::Locked),
+            WTS_SESSION_UNLOCK => handle_event(SessionEvent::Unlocked),
+            _ => {}
+        },
+        WM_POWERBROADCAST if wparam.0 == PBT_POWERSETTINGCHANGE => {
+            let setting = &*(lparam.0 as *const POWERBROADCAST_SETTING);
+            if setting.PowerSetting == GUID_CONSOLE_DISPLAY_STATE && setting.DataLength >= 4 {
+                // Data is declared as [u8; 1] but holds DataLength bytes
+                let data = std::slice::from_raw_parts(setting.Data.as_ptr(), 4);
+                let display_state = u32::from_ne_bytes([data[0], data[1], data[2], data[3]]);
+                match display_state {
+                    0 => handle_event(SessionEvent::DisplayOff),
+                    1 => handle_event(SessionEvent::DisplayOn),
+                    _ => {} // dimmed: keep recording
+                }
+            }
+        }
+        _ => {}
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+fn handle_event(event: SessionEvent) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    let app = app.clone();
+    tracing::info!("{:?}", event);
+
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<Mutex<AppState>>();
+        let (pause_enabled, resume_enabled) = match state.lock() {
+            Ok(s) => (s.auto_pause_on_lock, s.auto_resume_on_unlock),
+            Err(_) => return,
+        };
+
+        match event {
+            SessionEvent::Locked | SessionEvent::DisplayOff => {
+                if !pause_enabled {
+                    return;
+                }
+                match manager::auto_pause(&state, &app, AUTO_PAUSE_REASON).await {
+                    Ok(true) => tracing::info!("Recording auto-paused"),
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::warn!("Auto-pause failed: {}", e);
+                        events::emit(&app, AppEvent::RecordingError { message: e });
+                    }
+                }
+            }
+            SessionEvent::Unlocked | SessionEvent::DisplayOn => {
+                if !resume_enabled {
+                    return;
+                }
+                match manager::auto_resume(&state, &app, AUTO_PAUSE_REASON) {
+                    Ok(true) => tracing::info!("Recording auto-resumed"),
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::warn!("Auto-resume failed: {}", e);
+                        events::emit(&app, AppEvent::RecordingError { message: e });
+                    }
+                }
+            }
+        }
+    });
+}
@@ -11,33 +11,148 @@ pub struct AppState {
     pub current_region: Option<Region>,
     pub audio_source: AudioSource,
     pub selected_mic: Option<String>,
+    // Output device whose audio is recorded as system audio
+    pub selected_output: Option<String>,
+    pub mic_monitoring: bool,
+    pub audio_format: crate::types::AudioFormat,
+    // Read by the mic capture callback: silence while set
+    pub mic_muted: std::sync::Arc<std::sync::atomic::AtomicBool>,
     pub temp_dir: PathBuf,
     pub ffmpeg_process: Option<Child>,
     pub recording_start: Option<Instant>,
+    // Clip whose slot the recording in progress is another take of
+    pub retake_of: Option<String>,
+    // Recording time without the pauses, for the input capture threads
+    pub recording_clock: Option<crate::recording::timing::RecordingClock>,
     pub current_clip_path: Option<PathBuf>,
     // Pause support: segmented recording
     pub recording_segments: Vec<PathBuf>,
-    pub pause_accumulated_ms: u64,
     pub segment_index: u32,
+    // Incremented on every start so background watchers can detect a new session
+    pub recording_session: u64,
+    pub segment_rollover: crate::types::SegmentRollover,
+    pub adaptive_capture: crate::types::AdaptiveCapture,
+    // Steps of lighter capture settings the adaptive watcher applied, reset on start
+    pub capture_level: u32,
+    pub capture_rate: crate::types::CaptureRate,
+    /// Frame rate of the recording in progress, before adaptive capture
+    pub capture_framerate: u32,
+    /// Refresh rate of the monitor being recorded
+    pub capture_refresh: Option<u32>,
+    // Crossfade or dip to black where the recording was paused
+    pub pause_transition: crate::types::PauseTransition,
+    pub recording_quality: crate::types::RecordingQuality,
+    // Screen grab used by every capture, gdigrab where it can't record the region
+    pub capture_backend: crate::types::CaptureBackendKind,
+    // Set when a watcher (lock screen, ...) paused the recording, cleared on resume
+    pub auto_pause_reason: Option<String>,
+    pub auto_pause_on_lock: bool,
+    pub auto_resume_on_unlock: bool,
+    // Privacy guard: process names / window titles that pause the recording
+    pub privacy_blacklist: Vec<String>,
+    // Second region recorded by its own FFmpeg alongside the main one
+    pub secondary_region: Option<Region>,
+    pub secondary_capture: Option<crate::recording::secondary::SecondaryCapture>,
+    // Monitors recorded at once, each to its own clip (empty = region or full screen)
+    pub capture_monitors: Vec<Region>,
+    pub monitor_captures: Vec<crate::recording::secondary::SecondaryCapture>,
+    // Window the capture region was picked from, followed when it moves
+    pub capture_window: Option<crate::types::CaptureWindow>,
+    // Record full screen, export a window of this size following the cursor
+    pub follow_cursor: Option<crate::types::FollowWindow>,
+    // Record full screen, export framed on the foreground window
+    pub follow_focus: bool,
+    pub focus_handle: Option<crate::capture::focus::FocusTrackingHandle>,
+    // Teleprompter opened with each recording, and the script lines it
+    // reached during the current one, moved to the clip on stop
+    pub teleprompter: Option<crate::types::TeleprompterSettings>,
+    pub teleprompter_window: Option<tauri::WebviewWindow>,
+    pub script_marks: Vec<crate::types::ScriptMark>,
+    // Live drawing: overlay opened with each recording, active tool (None =
+    // click-through) and strokes of the current recording, moved to the
+    // clip's annotations on stop
+    pub live_drawing_enabled: bool,
+    pub drawing_tool: Option<crate::types::DrawingTool>,
+    pub drawing_window: Option<tauri::WebviewWindow>,
+    pub live_strokes: Vec<crate::types::Annotation>,
+    // Markers collected during the current recording, moved to the clip on stop
+    pub pending_markers: Vec<crate::types::TimelineMarker>,
+    pub clip_markers: HashMap<String, Vec<crate::types::TimelineMarker>>,
+    // Speech detected on each clip's voice track, computed on first request
+    // and again once the files or the trim it was detected from change
+    pub speech_segments: HashMap<String, (crate::export::speech::SpeechKey, Vec<crate::types::SpeechSegment>)>,
+    // Clips salvaged from a crashed session, waiting to be restored or discarded
+    pub recovered_clips: Vec<Clip>,
     // Audio capture handles
     pub audio_handles: Vec<AudioCaptureHandle>,
     pub audio_temp_paths: Vec<PathBuf>,
     // Countdown
     pub countdown_seconds: u32,
+    pub countdown_abort: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
     // Keystroke capture
     pub keystroke_enabled: bool,
+    pub keystroke_font: Option<String>,
     pub keystroke_handle: Option<crate::capture::keystroke::KeystrokeCaptureHandle>,
+    // On-screen preview of the keystroke labels while recording
+    pub keystroke_preview: bool,
+    pub keystroke_preview_window: Option<tauri::WebviewWindow>,
     pub clip_keystrokes: HashMap<String, Vec<crate::types::KeystrokeEvent>>,
     // Cursor tracking
     pub cursor_zoom_enabled: bool,
     pub cursor_handle: Option<crate::capture::cursor::CursorTrackingHandle>,
     pub clip_cursor_positions: HashMap<String, Vec<crate::types::CursorPosition>>,
+    // Save cursor paths in a binary file next to the project instead of its JSON
+    pub cursor_sidecar: bool,
+    // Gamepad polling
+    pub gamepad_enabled: bool,
+    pub gamepad_handle: Option<crate::capture::gamepad::GamepadPollingHandle>,
+    pub clip_gamepad: HashMap<String, Vec<crate::types::GamepadSample>>,
     // Annotations & Subtitles
     pub annotations: HashMap<String, Vec<crate::types::Annotation>>,
     pub subtitles: Vec<crate::types::Subtitle>,
+    // Language of `subtitles`, the one burned into exports, and the others
+    pub subtitle_language: String,
+    pub subtitle_tracks: Vec<crate::types::SubtitleTrack>,
+    pub subtitle_export: crate::types::SubtitleExport,
+    // Annotations over the whole timeline (logo, banner), timed like subtitles
+    pub global_annotations: Vec<crate::types::Annotation>,
+    // Narrations over the timeline, and the one being recorded
+    pub voiceovers: Vec<crate::types::Voiceover>,
+    pub voiceover_capture: Option<crate::recording::voiceover::VoiceoverCapture>,
     // Audio volume
     pub system_volume: f32,
     pub mic_volume: f32,
+    // Export
+    pub post_export: crate::types::PostExportSettings,
+    pub upload_targets: Vec<crate::types::UploadTarget>,
+    pub webhooks: Vec<crate::types::Webhook>,
+    // User-defined presets (built-ins live in export::presets)
+    pub export_presets: Vec<crate::types::ExportPreset>,
+    pub export_encoder: crate::types::ExportEncoder,
+    pub export_fps: crate::types::ExportFps,
+    pub export_downscale: crate::types::ExportDownscale,
+    // Tonemap clips recorded from HDR displays
+    pub hdr_tonemap: bool,
+    // Debug: keep the FFmpeg filter graph scripts in the temp dir, their path logged
+    pub keep_filter_scripts: bool,
+    // Color count and per-clip palettes of GIF exports
+    pub gif_palette: crate::types::GifPalette,
+    // Boomerang and loop count of GIF exports
+    pub gif_loop: crate::types::GifLoop,
+    // Watched folder and the new files waiting for confirmation
+    pub watch_folder: crate::types::WatchFolderSettings,
+    pub watch_pending: Vec<String>,
+    // Write a JSON description of the timeline next to every export
+    pub export_sidecar: bool,
+    // Write the YouTube chapter text next to every export
+    pub export_chapters: bool,
+    // Long MP4 exports: render in chunks of N minutes and resume after a crash (0 = off)
+    pub export_chunk_minutes: u32,
+    pub telemetry: crate::types::TelemetrySettings,
+    // FFmpeg build features, probed at startup
+    pub ffmpeg_capabilities: Option<crate::types::FfmpegCapabilities>,
+    // GPU capture encoders that work here, probed with the capabilities
+    pub gpu_encoders: Option<Vec<crate::types::RecordingEncoder>>,
     // Project
     pub current_project_id: Option<String>,
 }
@@ -45,6 +160,9 @@ pub struct AppState {
 pub struct AudioCaptureHandle {
     pub join_handle: Option<std::thread::JoinHandle<()>>,
     pub stop_flag: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    // Track it records, System or Microphone, and its level
+    pub source: crate::types::AudioSource,
+    pub meter: std::sync::Arc<crate::capture::audio::PeakMeter>,
 }
 
 impl Default for AppState {
@@ -61,26 +179,97 @@ impl Default for AppState {
             current_region: None,
             audio_source: AudioSource::None,
             selected_mic: None,
+            selected_output: None,
+            mic_monitoring: false,
+            audio_format: crate::types::AudioFormat::default(),
+            mic_muted: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
             temp_dir,
             ffmpeg_process: None,
             recording_start: None,
+            recording_clock: None,
+            retake_of: None,
             current_clip_path: None,
             recording_segments: Vec::new(),
-            pause_accumulated_ms: 0,
             segment_index: 0,
+            recording_session: 0,
+            segment_rollover: crate::types::SegmentRollover::default(),
+            adaptive_capture: crate::types::AdaptiveCapture::default(),
+            capture_level: 0,
+            capture_rate: crate::types::CaptureRate::default(),
+            capture_framerate: crate::normalize::FPS,
+            capture_refresh: None,
+            pause_transition: crate::types::PauseTransition::default(),
+            recording_quality: crate::types::RecordingQuality::default(),
+            capture_backend: crate::types::CaptureBackendKind::default(),
+            auto_pause_reason: None,
+            auto_pause_on_lock: true,
+            auto_resume_on_unlock: true,
+            privacy_blacklist: Vec::new(),
+            secondary_region: None,
+            secondary_capture: None,
+            capture_monitors: Vec::new(),
+            monitor_captures: Vec::new(),
+            capture_window: None,
+            follow_cursor: None,
+            follow_focus: false,
+            focus_handle: None,
+            teleprompter: None,
+            teleprompter_window: None,
+            script_marks: Vec::new(),
+            live_drawing_enabled: false,
+            drawing_tool: None,
+            drawing_window: None,
+            live_strokes: Vec::new(),
+            pending_markers: Vec::new(),
+            clip_markers: HashMap::new(),
+            speech_segments: HashMap::new(),
+            recovered_clips: Vec::new(),
             audio_handles: Vec::new(),
             audio_temp_paths: Vec::new(),
             countdown_seconds: 3,
+            countdown_abort: None,
             keystroke_enabled: false,
+            keystroke_font: None,
             keystroke_handle: None,
+            keystroke_preview: false,
+            keystroke_preview_window: None,
             clip_keystrokes: HashMap::new(),
             cursor_zoom_enabled: false,
             cursor_handle: None,
             clip_cursor_positions: HashMap::new(),
+            cursor_sidecar: true,
+            gamepad_enabled: false,
+            gamepad_handle: None,
+            clip_gamepad: HashMap::new(),
             annotations: HashMap::new(),
             subtitles: Vec::new(),
+            subtitle_language: crate::types::default_subtitle_language(),
+            subtitle_tracks: Vec::new(),
+            subtitle_export: crate::types::SubtitleExport::default(),
+            global_annotations: Vec::new(),
+            voiceovers: Vec::new(),
+            voiceover_capture: None,
             system_volume: 1.0,
             mic_volume: 1.0,
+            post_export: crate::types::PostExportSettings::default(),
+            upload_targets: Vec::new(),
+            webhooks: Vec::new(),
+            export_presets: Vec::new(),
+            export_encoder: crate::types::ExportEncoder::default(),
+            export_fps: crate::types::ExportFps::default(),
+            export_downscale: crate::types::ExportDownscale::default(),
+            hdr_tonemap: true,
+            keep_filter_scripts: false,
+            gif_palette: crate::types::GifPalette::default(),
+            gif_loop: crate::types::GifLoop::default(),
+            export_sidecar: false,
+            export_chapters: false,
+            watch_folder: crate::types::WatchFolderSettings::default(),
+            watch_pending: Vec::new(),
+            export_chunk_minutes: 0,
+            telemetry: crate::types::TelemetrySettings::default(),
+            ffmpeg_capabilities: None,
+            gpu_encoders: None,
             current_project_id: None,
         }
     }
@@ -0,0 +1,299 @@
+use crate::types::{Clip, StorageEntry, StoragePolicy, StorageUsage, Voiceover};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Files written this recently may belong to an export still running
+/// (mezzanine, chunk), so cleanup leaves them alone
+const RECENT_SECS: u64 = 5 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Clip,
+    Thumbnail,
+    Proxy,
+}
+
+struct TempFile {
+    path: PathBuf,
+    bytes: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Paths and ids of the clips and narrations still in use, by the timeline,
+/// the recovered captures or a saved project
+struct References {
+    paths: HashSet<PathBuf>,
+    ids: Vec<String>,
+}
+
+impl References {
+    fn new(clips: &[Clip], voiceovers: &[Voiceover]) -> Self {
+        let mut paths = HashSet::new();
+        // Takes not selected are kept as long as their clip
+        let media = clips.iter().flat_map(|c| std::iter::once(&c.media).chain(c.takes.iter().map(|t| &t.media)));
+        for media in media {
+            paths.insert(media.path.clone());
+            paths.extend(media.thumbnail_path.clone());
+            paths.extend(media.audio_paths.iter().map(PathBuf::from));
+            paths.extend(media.secondary.as_ref().map(|s| s.path.clone()));
+        }
+        paths.extend(voiceovers.iter().map(|v| v.path.clone()));
+        Self {
+            paths,
+            ids: clips
+                .iter()
+                .flat_map(|c| std::iter::once(&c.id).chain(c.takes.iter().map(|t| &t.id)))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Render caches and thumbnails are named after the clip id
+    fn uses(&self, path: &Path) -> bool {
+        if self.paths.contains(path) {
+            return true;
+        }
+        let name = file_name(path);
+        self.ids.iter().any(|id| {
+            name.strip_prefix(id.as_str())
+                .is_some_and(|rest| rest.starts_with('_') || rest.starts_with('.'))
+        })
+    }
+}
+
+fn policy_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("ClipFlow")
+        .join("storage.json")
+}
+
+/// Kept on disk rather than synced by the frontend: the startup cleanup
+/// runs before the window loads
+pub fn load_policy() -> StoragePolicy {
+    std::fs::read(policy_path())
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_policy(policy: &StoragePolicy) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(policy).map_err(|e| e.to_string())?;
+    std::fs::write(policy_path(), json).map_err(|e| e.to_string())
+}
+
+/// Where `preview_video` renders, wiped at startup
+pub fn preview_dir() -> PathBuf {
+    std::env::temp_dir().join("clipflow_preview")
+}
+
+/// Where exports write their intermediates, away from the output folder
+pub fn export_dir() -> PathBuf {
+    std::env::temp_dir().join("clipflow_export")
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+/// Chunked exports keep their parts in `chunks_<key>/` to resume later
+fn in_chunks_dir(path: &Path, temp_dir: &Path) -> bool {
+    path.strip_prefix(temp_dir)
+        .ok()
+        .and_then(|rel| rel.components().next())
+        .is_some_and(|first| first.as_os_str().to_string_lossy().starts_with("chunks_"))
+}
+
+fn categorize(path: &Path, temp_dir: &Path) -> Category {
+    let name = file_name(path).to_ascii_lowercase();
+    if in_chunks_dir(path, temp_dir)
+        || name.starts_with("mezzanine_")
+        || ["_follow_", "_focus_", "_dual_"].iter().any(|tag| name.contains(tag))
+    {
+        Category::Proxy
+    } else if name.ends_with(".png") {
+        Category::Thumbnail
+    } else {
+        Category::Clip
+    }
+}
+
+fn is_orphan(file: &Path, temp_dir: &Path, refs: &References) -> bool {
+    !in_chunks_dir(file, temp_dir) && !refs.uses(file)
+}
+
+fn is_recent(file: &TempFile) -> bool {
+    let cutoff = SystemTime::now() - Duration::from_secs(RECENT_SECS);
+    file.modified.is_some_and(|m| m > cutoff)
+}
+
+/// Every file under `dir`, recursively
+fn walk(dir: &Path, out: &mut Vec<TempFile>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(meta) = entry.metadata() else { continue };
+        if meta.is_dir() {
+            walk(&entry.path(), out);
+        } else {
+            out.push(TempFile {
+                path: entry.path(),
+                bytes: meta.len(),
+                modified: meta.modified().ok(),
+            });
+        }
+    }
+}
+
+fn add(entry: &mut StorageEntry, file: &TempFile) {
+    entry.bytes += file.bytes;
+    entry.files += 1;
+}
+
+fn measure(dir: &Path) -> StorageEntry {
+    let mut files = Vec::new();
+    walk(dir, &mut files);
+    let mut entry = StorageEntry::default();
+    for file in &files {
+        add(&mut entry, file);
+    }
+    entry
+}
+
+/// Disk usage of the temp dir by category, plus the preview renders
+pub fn usage(temp_dir: &Path, in_use: &[Clip], voiceovers: &[Voiceover]) -> StorageUsage {
+    let refs = References::new(in_use, voiceovers);
+    let mut files = Vec::new();
+    walk(temp_dir, &mut files);
+
+    let mut usage = StorageUsage {
+        previews: measure(&preview_dir()),
+        ..Default::default()
+    };
+    for file in &files {
+        let entry = match categorize(&file.path, temp_dir) {
+            Category::Clip => &mut usage.clips,
+            Category::Thumbnail => &mut usage.thumbnails,
+            Category::Proxy => &mut usage.proxies,
+        };
+        add(entry, file);
+        if is_orphan(&file.path, temp_dir, &refs) {
+            add(&mut usage.orphaned, file);
+        }
+    }
+    usage
+}
+
+/// Remove `dir`'s subfolders left empty (imported bundles whose clips are gone)
+fn remove_empty_dirs(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            remove_empty_dirs(&path);
+            // Fails unless empty
+            let _ = std::fs::remove_dir(&path);
+        }
+    }
+}
+
+/// Delete the temp files no clip, narration or saved project uses, returns
+/// what was freed
+pub fn clear_orphans(temp_dir: &Path, in_use: &[Clip], voiceovers: &[Voiceover]) -> StorageEntry {
+    let refs = References::new(in_use, voiceovers);
+    let mut files = Vec::new();
+    walk(temp_dir, &mut files);
+
+    let mut freed = StorageEntry::default();
+    for file in files.iter().filter(|f| is_orphan(&f.path, temp_dir, &refs) && !is_recent(f)) {
+        if std::fs::remove_file(&file.path).is_ok() {
+            add(&mut freed, file);
+        }
+    }
+    remove_empty_dirs(temp_dir);
+    tracing::info!("Cleared {} orphaned temp file(s), {} bytes", freed.files, freed.bytes);
+    freed
+}
+
+/// Delete the preview renders, returns what was freed
+pub fn clear_previews() -> StorageEntry {
+    let dir = preview_dir();
+    let freed = measure(&dir);
+    if dir.exists() {
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+    freed
+}
+
+/// Remove the top-level temp files older than the policy allows, sparing
+/// the media of `keep` and the narrations `keep_voiceovers`
+pub fn cleanup_stale(temp_dir: &Path, policy: &StoragePolicy, keep: &[Clip], keep_voiceovers: &[Voiceover]) {
+    if policy.max_age_hours == 0 {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(temp_dir) else {
+        return;
+    };
+    let refs = References::new(keep, keep_voiceovers);
+    let cutoff = SystemTime::now() - Duration::from_secs(u64::from(policy.max_age_hours) * 3600);
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if refs.uses(&path) {
+            continue;
+        }
+        if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+            if modified < cutoff {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ClipMedia, test_clip};
+
+    fn clip(id: &str, temp_dir: &Path) -> Clip {
+        let clip = test_clip(id, 1000);
+        Clip {
+            media: ClipMedia {
+                path: temp_dir.join(format!("{id}.mp4")),
+                thumbnail_path: Some(temp_dir.join(format!("{id}.thumb.png"))),
+                ..clip.media
+            },
+            ..clip
+        }
+    }
+
+    #[test]
+    fn test_categorize() {
+        let temp = Path::new("temp");
+        assert_eq!(categorize(&temp.join("abc.mp4"), temp), Category::Clip);
+        assert_eq!(categorize(&temp.join("abc_mic.wav"), temp), Category::Clip);
+        assert_eq!(categorize(&temp.join("abc.thumb_1500.png"), temp), Category::Thumbnail);
+        assert_eq!(categorize(&temp.join("abc_follow_1920x1080.mp4"), temp), Category::Proxy);
+        assert_eq!(categorize(&temp.join("mezzanine_x.mp4"), temp), Category::Proxy);
+        assert_eq!(categorize(&temp.join("chunks_k").join("part0.mp4"), temp), Category::Proxy);
+    }
+
+    #[test]
+    fn test_orphans_spare_what_clips_use() {
+        let temp = Path::new("temp");
+        let voiceover = Voiceover { id: "v1".into(), path: temp.join("v1_voiceover.wav"), start_ms: 0, duration_ms: 1000 };
+        let refs = References::new(&[clip("abc", temp)], &[voiceover]);
+        assert!(!is_orphan(&temp.join("abc.mp4"), temp, &refs));
+        assert!(!is_orphan(&temp.join("abc.thumb_1500.png"), temp, &refs));
+        assert!(!is_orphan(&temp.join("abc_dual_pip.mp4"), temp, &refs));
+        assert!(!is_orphan(&temp.join("chunks_k").join("part0.mp4"), temp, &refs));
+        assert!(!is_orphan(&temp.join("v1_voiceover.wav"), temp, &refs));
+        assert!(is_orphan(&temp.join("v2_voiceover.wav"), temp, &refs));
+        assert!(is_orphan(&temp.join("abcd.mp4"), temp, &refs));
+        assert!(is_orphan(&temp.join("old_follow_1920x1080.mp4"), temp, &refs));
+        assert!(is_orphan(&temp.join("mezzanine_x.mp4"), temp, &refs));
+    }
+}
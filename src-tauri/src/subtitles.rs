@@ -0,0 +1,382 @@
+use crate::types::{Subtitle, SubtitleIssue, SubtitleIssueKind, SubtitleLanguage, SubtitleReading, SubtitleTrack};
+
+/// Language of subtitles nobody named
+pub const UNDETERMINED: &str = "und";
+
+/// Shortest time a line stays on screen to be read
+const MIN_DURATION_MS: u64 = 800;
+/// Longest duration suggested, however long the text
+const MAX_SUGGESTED_MS: u64 = 7000;
+/// Reading speed most viewers keep up with, spaces and punctuation included
+const CHARS_PER_SECOND: f64 = 15.0;
+
+fn char_count(text: &str) -> usize {
+    text.trim().chars().filter(|c| !c.is_control()).count()
+}
+
+/// Time `text` takes to read, within [`MIN_DURATION_MS`, `MAX_SUGGESTED_MS`]
+pub fn reading_duration_ms(text: &str) -> u64 {
+    let ms = (char_count(text) as f64 / CHARS_PER_SECOND * 1000.0).ceil() as u64;
+    ms.clamp(MIN_DURATION_MS, MAX_SUGGESTED_MS)
+}
+
+/// Suggested duration of each subtitle and whether it shows too briefly
+/// for its text
+pub fn readings(subtitles: &[Subtitle]) -> Vec<SubtitleReading> {
+    subtitles
+        .iter()
+        .map(|sub| {
+            let duration_ms = sub.end_ms.saturating_sub(sub.start_ms);
+            let suggested_ms = reading_duration_ms(&sub.text);
+            SubtitleReading {
+                subtitle_id: sub.id.clone(),
+                suggested_ms,
+                chars_per_second: char_count(&sub.text) as f64 * 1000.0 / duration_ms.max(1) as f64,
+                too_fast: duration_ms < suggested_ms,
+            }
+        })
+        .collect()
+}
+
+/// Index of the first subtitle after `i` shown at the same position, in a
+/// list sorted by start
+fn next_at_same_position(subtitles: &[Subtitle], i: usize) -> Option<usize> {
+    let position = subtitles[i].position;
+    (i + 1..subtitles.len()).find(|&j| subtitles[j].position == position)
+}
+
+/// `ms` as seconds with one decimal, for the messages
+fn seconds(ms: u64) -> String {
+    format!("{:.1}", ms as f64 / 1000.0)
+}
+
+fn sorted(subtitles: &[Subtitle]) -> Vec<Subtitle> {
+    let mut sorted = subtitles.to_vec();
+    sorted.sort_by_key(|s| s.start_ms);
+    sorted
+}
+
+/// Timing problems of `subtitles` on a timeline lasting `timeline_ms`, in
+/// the order they are shown. The end of the timeline isn't checked when it
+/// is 0 (no clips).
+pub fn check(subtitles: &[Subtitle], timeline_ms: u64) -> Vec<SubtitleIssue> {
+    let subtitles = sorted(subtitles);
+    let mut issues = Vec::new();
+    let mut report = |sub: &Subtitle, kind: SubtitleIssueKind, message: String| {
+        issues.push(SubtitleIssue { kind, subtitle_id: sub.id.clone(), message });
+    };
+
+    for (i, sub) in subtitles.iter().enumerate() {
+        let n = i + 1;
+        if timeline_ms > 0 && sub.start_ms >= timeline_ms {
+            report(sub, SubtitleIssueKind::OffTimeline, crate::i18n::tf("subtitle_after_end", &[&n]));
+            continue;
+        }
+        if timeline_ms > 0 && sub.end_ms > timeline_ms {
+            report(sub, SubtitleIssueKind::OffTimeline, crate::i18n::tf("subtitle_cut_by_end", &[&n]));
+        }
+        let duration_ms = sub.end_ms.saturating_sub(sub.start_ms);
+        let reading_ms = reading_duration_ms(&sub.text);
+        if duration_ms < MIN_DURATION_MS {
+            report(
+                sub,
+                SubtitleIssueKind::TooShort,
+                crate::i18n::tf("subtitle_too_short", &[&n, &seconds(MIN_DURATION_MS)]),
+            );
+        } else if duration_ms < reading_ms {
+            report(
+                sub,
+                SubtitleIssueKind::TooFast,
+                crate::i18n::tf("subtitle_too_fast", &[&n, &seconds(reading_ms)]),
+            );
+        }
+        if next_at_same_position(&subtitles, i).is_some_and(|j| subtitles[j].start_ms < sub.end_ms) {
+            report(sub, SubtitleIssueKind::Overlap, crate::i18n::tf("subtitle_overlap", &[&n]));
+        }
+    }
+    issues
+}
+
+/// Fix what `check` finds where there is room: sort by start, end each line
+/// when the next one at its position shows (pushing that one back when they
+/// start together), cut at the end of the timeline and stretch lines too
+/// short to read into the free time after them. Lines starting after the
+/// end are left for `check` to report. Returns how many subtitles were
+/// changed.
+pub fn fit(subtitles: &mut Vec<Subtitle>, timeline_ms: u64) -> usize {
+    let before = subtitles.clone();
+    let end_of_timeline = if timeline_ms > 0 { timeline_ms } else { u64::MAX };
+    subtitles.sort_by_key(|s| s.start_ms);
+
+    for i in 0..subtitles.len() {
+        let start = subtitles[i].start_ms;
+        if start >= end_of_timeline {
+            continue;
+        }
+        let mut limit = end_of_timeline;
+        while let Some(j) = next_at_same_position(subtitles, i) {
+            let next = &mut subtitles[j];
+            // Too close to share the time: the next one waits, if it can
+            // spare it. It may pass others doing so, so the rest is sorted
+            // again and the neighbor looked up anew.
+            if next.start_ms < start + MIN_DURATION_MS && next.end_ms >= start + 2 * MIN_DURATION_MS {
+                next.start_ms = start + MIN_DURATION_MS;
+                subtitles[i + 1..].sort_by_key(|s| s.start_ms);
+                continue;
+            }
+            limit = limit.min(next.start_ms);
+            break;
+        }
+        let sub = &mut subtitles[i];
+        sub.end_ms = sub.end_ms.min(limit).max(start);
+        let reading_ms = reading_duration_ms(&sub.text);
+        if sub.end_ms - start < reading_ms {
+            sub.end_ms = (start + reading_ms).min(limit).max(sub.end_ms);
+        }
+    }
+
+    subtitles
+        .iter()
+        .filter(|s| !before.iter().any(|b| b.id == s.id && b.start_ms == s.start_ms && b.end_ms == s.end_ms))
+        .count()
+}
+
+/// Move every subtitle `delta_ms` later, or earlier when negative, as when a
+/// clip is added or removed at the start of the timeline. Lines moved
+/// before 0 are cut there, dropped when nothing is left.
+pub fn shift(subtitles: &mut Vec<Subtitle>, delta_ms: i64) {
+    let shifted = |ms: u64| ms.saturating_add_signed(delta_ms);
+    subtitles.retain(|s| shifted(s.end_ms) > 0);
+    for sub in subtitles.iter_mut() {
+        sub.start_ms = shifted(sub.start_ms);
+        sub.end_ms = shifted(sub.end_ms);
+    }
+}
+
+/// Lowercased ISO 639 code, with an optional region ("pt-br")
+pub fn normalize_language(code: &str) -> Result<String, String> {
+    let code = code.trim().to_lowercase();
+    let letters = |s: &str, len: std::ops::RangeInclusive<usize>| len.contains(&s.len()) && s.chars().all(|c| c.is_ascii_lowercase());
+    let valid = match code.split_once('-') {
+        Some((language, region)) => letters(language, 2..=3) && letters(region, 2..=4),
+        None => letters(&code, 2..=3),
+    };
+    if valid {
+        Ok(code)
+    } else {
+        Err(crate::i18n::tf("invalid_language_code", &[&code]))
+    }
+}
+
+/// Every language, the active one (`subtitles`) first
+pub fn languages(subtitles: &[Subtitle], active: &str, tracks: &[SubtitleTrack]) -> Vec<SubtitleLanguage> {
+    let active = SubtitleLanguage { language: active.into(), count: subtitles.len(), active: true };
+    std::iter::once(active)
+        .chain(tracks.iter().map(|t| SubtitleLanguage { language: t.language.clone(), count: t.subtitles.len(), active: false }))
+        .collect()
+}
+
+/// Every language as a track, the active one first
+pub fn all_tracks(subtitles: &[Subtitle], active: &str, tracks: &[SubtitleTrack]) -> Vec<SubtitleTrack> {
+    let active = SubtitleTrack { language: active.into(), subtitles: subtitles.to_vec() };
+    std::iter::once(active).chain(tracks.iter().cloned()).collect()
+}
+
+/// Add `language` with a copy of the active lines, their text to translate
+pub fn add_language(subtitles: &[Subtitle], active: &str, tracks: &mut Vec<SubtitleTrack>, language: &str) -> Result<(), String> {
+    let language = normalize_language(language)?;
+    if language == active || tracks.iter().any(|t| t.language == language) {
+        return Err(crate::i18n::tf("subtitles_language_exists", &[&language]));
+    }
+    tracks.push(SubtitleTrack { language, subtitles: subtitles.to_vec() });
+    Ok(())
+}
+
+/// Make `language` the active one: its lines take the place of `subtitles`,
+/// which go back with the other languages
+pub fn activate(subtitles: &mut Vec<Subtitle>, active: &mut String, tracks: &mut [SubtitleTrack], language: &str) -> Result<(), String> {
+    if language == active.as_str() {
+        return Ok(());
+    }
+    let track = tracks
+        .iter_mut()
+        .find(|t| t.language == language)
+        .ok_or_else(|| crate::i18n::tf("subtitles_language_missing", &[&language]))?;
+    std::mem::swap(subtitles, &mut track.subtitles);
+    track.language = std::mem::replace(active, language.into());
+    Ok(())
+}
+
+/// Drop `language`. The active one is replaced by the next language, or
+/// emptied when it was the only one.
+pub fn remove_language(subtitles: &mut Vec<Subtitle>, active: &mut String, tracks: &mut Vec<SubtitleTrack>, language: &str) -> Result<(), String> {
+    if language != active.as_str() {
+        let before = tracks.len();
+        tracks.retain(|t| t.language != language);
+        return if tracks.len() < before { Ok(()) } else { Err(crate::i18n::tf("subtitles_language_missing", &[&language])) };
+    }
+    if tracks.is_empty() {
+        subtitles.clear();
+        *active = UNDETERMINED.into();
+    } else {
+        let next = tracks.remove(0);
+        *subtitles = next.subtitles;
+        *active = next.language;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SubtitlePosition;
+
+    fn sub(id: &str, start_ms: u64, end_ms: u64, position: SubtitlePosition) -> Subtitle {
+        Subtitle {
+            id: id.into(),
+            text: id.into(),
+            start_ms,
+            end_ms,
+            position,
+            font_size: 32,
+            color: "#ffffff".into(),
+            font_file: None,
+        }
+    }
+
+    fn kinds(issues: &[SubtitleIssue], id: &str) -> Vec<SubtitleIssueKind> {
+        issues.iter().filter(|i| i.subtitle_id == id).map(|i| i.kind).collect()
+    }
+
+    #[test]
+    fn test_check_finds_each_problem() {
+        let subs = vec![
+            sub("late", 12_000, 13_000, SubtitlePosition::Bottom),
+            sub("a", 0, 3000, SubtitlePosition::Bottom),
+            sub("b", 2000, 2500, SubtitlePosition::Bottom),
+            // Other position: no overlap
+            sub("top", 1000, 4000, SubtitlePosition::Top),
+            sub("end", 9000, 11_000, SubtitlePosition::Top),
+        ];
+        let issues = check(&subs, 10_000);
+        assert_eq!(kinds(&issues, "a"), [SubtitleIssueKind::Overlap]);
+        assert_eq!(kinds(&issues, "b"), [SubtitleIssueKind::TooShort]);
+        assert!(kinds(&issues, "top").is_empty());
+        assert_eq!(kinds(&issues, "end"), [SubtitleIssueKind::OffTimeline]);
+        assert_eq!(kinds(&issues, "late"), [SubtitleIssueKind::OffTimeline]);
+        // Listed in the order they are shown
+        assert_eq!(issues[0].subtitle_id, "a");
+    }
+
+    #[test]
+    fn test_fit_resolves_timing() {
+        let mut subs = vec![
+            sub("a", 0, 3000, SubtitlePosition::Bottom),
+            sub("b", 2000, 2500, SubtitlePosition::Bottom),
+            sub("c", 5000, 5100, SubtitlePosition::Bottom),
+            sub("end", 9000, 11_000, SubtitlePosition::Top),
+            sub("late", 12_000, 13_000, SubtitlePosition::Bottom),
+        ];
+        let changed = fit(&mut subs, 10_000);
+        let timing = |id: &str| subs.iter().find(|s| s.id == id).map(|s| (s.start_ms, s.end_ms)).unwrap();
+        assert_eq!(timing("a"), (0, 2000));
+        assert_eq!(timing("b"), (2000, 2800));
+        assert_eq!(timing("c"), (5000, 5800));
+        assert_eq!(timing("end"), (9000, 10_000));
+        assert_eq!(changed, 4);
+        // Only the line after the end is left
+        let issues = check(&subs, 10_000);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].subtitle_id, "late");
+    }
+
+    #[test]
+    fn test_fit_pushes_back_lines_starting_together() {
+        let mut subs = vec![
+            sub("a", 1000, 4000, SubtitlePosition::Bottom),
+            sub("b", 1000, 5000, SubtitlePosition::Bottom),
+        ];
+        fit(&mut subs, 0);
+        assert_eq!((subs[0].start_ms, subs[0].end_ms), (1000, 1800));
+        assert_eq!((subs[1].start_ms, subs[1].end_ms), (1800, 5000));
+        assert!(check(&subs, 0).is_empty());
+    }
+
+    #[test]
+    fn test_fit_pushed_lines_keep_their_order() {
+        let mut subs = vec![
+            sub("a", 1000, 4000, SubtitlePosition::Bottom),
+            sub("b", 1000, 5000, SubtitlePosition::Bottom),
+            sub("c", 1500, 6000, SubtitlePosition::Bottom),
+        ];
+        fit(&mut subs, 0);
+        let timing: Vec<_> = subs.iter().map(|s| (s.id.as_str(), s.start_ms, s.end_ms)).collect();
+        assert_eq!(timing, [("a", 1000, 1800), ("c", 1800, 2600), ("b", 2600, 5000)]);
+        assert!(check(&subs, 0).is_empty());
+    }
+
+    #[test]
+    fn test_reading_speed() {
+        assert_eq!(reading_duration_ms("Ok"), MIN_DURATION_MS);
+        // 45 characters at 15 per second
+        let text = "Cliquez sur Exporter pour lancer le rendu ici";
+        assert_eq!(reading_duration_ms(text), 3000);
+        assert_eq!(reading_duration_ms(&"x".repeat(500)), MAX_SUGGESTED_MS);
+
+        let mut fast = sub("fast", 0, 1500, SubtitlePosition::Bottom);
+        fast.text = text.into();
+        let slow = sub("slow", 0, 1500, SubtitlePosition::Top);
+        let reads = readings(&[fast.clone(), slow]);
+        assert!(reads[0].too_fast && (reads[0].chars_per_second - 30.0).abs() < 1e-9);
+        assert!(!reads[1].too_fast);
+        assert_eq!(kinds(&check(&[fast.clone()], 0), "fast"), [SubtitleIssueKind::TooFast]);
+
+        // Stretched to its reading time when there is room
+        let mut subs = vec![fast];
+        fit(&mut subs, 10_000);
+        assert_eq!(subs[0].end_ms, 3000);
+    }
+
+    #[test]
+    fn test_languages() {
+        let mut subs = vec![sub("a", 0, 1000, SubtitlePosition::Bottom)];
+        let mut active = UNDETERMINED.to_string();
+        let mut tracks = Vec::new();
+
+        add_language(&subs, &active, &mut tracks, " EN ").unwrap();
+        assert!(add_language(&subs, &active, &mut tracks, "en").is_err());
+        assert!(add_language(&subs, &active, &mut tracks, "english").is_err());
+        add_language(&subs, &active, &mut tracks, "pt-BR").unwrap();
+
+        // The copy is edited once active, the original kept aside
+        activate(&mut subs, &mut active, &mut tracks, "en").unwrap();
+        subs[0].text = "hello".into();
+        assert_eq!(active, "en");
+        let list = languages(&subs, &active, &tracks);
+        assert_eq!(list.iter().map(|l| l.language.as_str()).collect::<Vec<_>>(), ["en", "und", "pt-br"]);
+        assert!(list[0].active && !list[1].active);
+        assert!(activate(&mut subs, &mut active, &mut tracks, "de").is_err());
+
+        activate(&mut subs, &mut active, &mut tracks, "und").unwrap();
+        assert_eq!(subs[0].text, "a");
+        let all = all_tracks(&subs, &active, &tracks);
+        assert_eq!(all[1].language, "en");
+        assert_eq!(all[1].subtitles[0].text, "hello");
+
+        remove_language(&mut subs, &mut active, &mut tracks, "pt-br").unwrap();
+        remove_language(&mut subs, &mut active, &mut tracks, "und").unwrap();
+        assert_eq!((active.as_str(), subs[0].text.as_str()), ("en", "hello"));
+        assert!(tracks.is_empty());
+    }
+
+    #[test]
+    fn test_shift() {
+        let mut subs = vec![sub("a", 0, 1000, SubtitlePosition::Bottom), sub("b", 2000, 4000, SubtitlePosition::Top)];
+        shift(&mut subs, 5000);
+        assert_eq!((subs[0].start_ms, subs[1].end_ms), (5000, 9000));
+        shift(&mut subs, -7500);
+        // The first one ended before 0, the second is cut there
+        assert_eq!(subs.len(), 1);
+        assert_eq!((subs[0].start_ms, subs[0].end_ms), (0, 1500));
+    }
+}
@@ -0,0 +1,200 @@
+use crate::types::{ExportFormat, TelemetrySettings};
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Oldest events are dropped past this, so a machine that never goes online
+/// doesn't grow the queue forever
+const MAX_QUEUED_EVENTS: usize = 500;
+
+/// Serializes access to the queue file between recorders and the flusher
+static QUEUE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Coarse, anonymous events: no paths, titles or identifiers
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    ExportCompleted {
+        format: ExportFormat,
+        clip_count: usize,
+        duration_bucket: &'static str,
+    },
+    ExportFailed {
+        format: ExportFormat,
+        code: &'static str,
+    },
+}
+
+#[derive(Serialize)]
+struct QueuedEvent {
+    /// Day only, to keep events unlinkable in time
+    date: String,
+    #[serde(flatten)]
+    event: TelemetryEvent,
+}
+
+fn queue_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("ClipFlow")
+        .join("telemetry-queue.jsonl")
+}
+
+/// Queue `event` if the user opted in, then try to send the queue.
+/// Runs in the background; failures leave the queue for the next attempt.
+pub fn record(settings: &TelemetrySettings, event: TelemetryEvent) {
+    if !settings.enabled {
+        return;
+    }
+    let settings = settings.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let line = QueuedEvent {
+            date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+            event,
+        };
+        if let Err(e) = append(&line) {
+            tracing::warn!("Could not queue event: {}", e);
+            return;
+        }
+        if let Err(e) = flush(&settings) {
+            tracing::debug!("Events kept for later: {}", e);
+        }
+    });
+}
+
+/// Send queued events at startup (offline sessions catch up here)
+pub fn flush_in_background(settings: &TelemetrySettings) {
+    if !settings.enabled {
+        return;
+    }
+    let settings = settings.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = flush(&settings) {
+            tracing::debug!("Events kept for later: {}", e);
+        }
+    });
+}
+
+/// Drop everything not yet sent (used when the user opts out)
+pub fn clear_queue() {
+    let _guard = QUEUE_LOCK.lock();
+    let _ = std::fs::remove_file(queue_path());
+}
+
+fn append(event: &QueuedEvent) -> Result<(), String> {
+    let _guard = QUEUE_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = queue_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut lines: Vec<String> = std::fs::read_to_string(&path)
+        .unwrap_or_default()
+        .lines()
+        .map(|l| l.to_string())
+        .collect();
+    lines.push(serde_json::to_string(event).map_err(|e| e.to_string())?);
+    let start = lines.len().saturating_sub(MAX_QUEUED_EVENTS);
+
+    let mut file = std::fs::File::create(&path).map_err(|e| e.to_string())?;
+    for line in &lines[start..] {
+        writeln!(file, "{}", line).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn flush(settings: &TelemetrySettings) -> Result<(), String> {
+    if settings.endpoint.trim().is_empty() {
+        return Err("no endpoint configured".into());
+    }
+    let _guard = QUEUE_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = queue_path();
+    let text = std::fs::read_to_string(&path).unwrap_or_default();
+    let events: Vec<serde_json::Value> = text
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect();
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    let body = serde_json::json!({
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "events": events,
+    });
+    ureq::post(settings.endpoint.trim())
+        .set("Content-Type", "application/json")
+        .timeout(std::time::Duration::from_secs(10))
+        .send_string(&body.to_string())
+        .map_err(|e| e.to_string())?;
+
+    let _ = std::fs::remove_file(&path);
+    tracing::info!("Sent {} usage events", events.len());
+    Ok(())
+}
+
+/// Export length bucket, precise durations are never reported
+pub fn duration_bucket(duration_ms: u64) -> &'static str {
+    match duration_ms / 1000 {
+        0..=29 => "<30s",
+        30..=119 => "30s-2m",
+        120..=599 => "2m-10m",
+        _ => ">10m",
+    }
+}
+
+/// Map an export error to a fixed code so no path or message leaves the machine
+pub fn failure_code(error: &str) -> &'static str {
+    let e = error.to_lowercase();
+    if e.contains("introuvable") || e.contains("not found") || e.contains("no such file") {
+        "file_missing"
+    } else if e.contains("no space") || e.contains("disk space") || e.contains("espace disque") {
+        "disk_full"
+    } else if e.contains("ne prend pas en charge") || e.contains("does not support") || e.contains("unknown encoder") || e.contains("no such filter") {
+        "unsupported"
+    } else if e.contains("ffmpeg") {
+        "ffmpeg"
+    } else {
+        "other"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_bucket() {
+        assert_eq!(duration_bucket(0), "<30s");
+        assert_eq!(duration_bucket(29_999), "<30s");
+        assert_eq!(duration_bucket(30_000), "30s-2m");
+        assert_eq!(duration_bucket(5 * 60_000), "2m-10m");
+        assert_eq!(duration_bucket(3_600_000), ">10m");
+    }
+
+    #[test]
+    fn test_failure_code() {
+        assert_eq!(failure_code("Fichier du clip 1 introuvable : \"C:/x.mp4\""), "file_missing");
+        assert_eq!(failure_code("No space left on device"), "disk_full");
+        assert_eq!(failure_code("Unknown encoder 'libx264'"), "unsupported");
+        assert_eq!(failure_code("FFmpeg exited with code 1"), "ffmpeg");
+        assert_eq!(failure_code("boom"), "other");
+    }
+
+    #[test]
+    fn test_event_serialization_is_coarse() {
+        let line = QueuedEvent {
+            date: "2026-01-01".into(),
+            event: TelemetryEvent::ExportCompleted {
+                format: ExportFormat::Gif,
+                clip_count: 3,
+                duration_bucket: "<30s",
+            },
+        };
+        let json = serde_json::to_value(&line).unwrap();
+        assert_eq!(json["event"], "export_completed");
+        assert_eq!(json["format"], "gif");
+        assert_eq!(json["date"], "2026-01-01");
+    }
+}
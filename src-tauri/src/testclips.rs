@@ -0,0 +1,143 @@
+use crate::types::{Clip, ClipMedia, Region, TestClipSpec};
+use std::path::Path;
+use std::process::Stdio;
+
+/// Shortest clip generated
+const MIN_DURATION_MS: u64 = 100;
+
+/// Generate a clip from FFmpeg's test pattern, with a sine tone as its
+/// system track when `spec` asks for one. The duration and size are exactly
+/// the requested ones and the video is in the timeline format, so exports,
+/// transitions and trims can be exercised without a screen capture.
+pub async fn generate(dir: &Path, spec: &TestClipSpec) -> Result<Clip, String> {
+    let spec = sanitized(spec);
+    let clip_id = uuid::Uuid::new_v4().to_string();
+    let path = dir.join(format!("{}.mp4", clip_id));
+    run_ffmpeg(&video_args(&spec, &path)).await?;
+
+    let mut audio_paths = Vec::new();
+    if let Some(hz) = spec.tone_hz {
+        let wav = dir.join(format!("{}_system.wav", clip_id));
+        if let Err(e) = run_ffmpeg(&audio_args(&spec, hz, &wav)).await {
+            let _ = std::fs::remove_file(&path);
+            return Err(e);
+        }
+        audio_paths.push(wav.to_string_lossy().to_string());
+    }
+
+    let thumbnail_path = path.with_extension("thumb.png");
+    let thumb_ms = crate::capture::screen::default_thumbnail_ms(spec.duration_ms);
+    let thumb = crate::capture::screen::generate_thumbnail(&path, &thumbnail_path, thumb_ms)
+        .is_ok()
+        .then_some(thumbnail_path);
+
+    Ok(Clip {
+        id: clip_id,
+        media: ClipMedia {
+            path,
+            duration_ms: spec.duration_ms,
+            region: Region { x: 0, y: 0, width: spec.width, height: spec.height },
+            has_audio: !audio_paths.is_empty(),
+            thumbnail_path: thumb,
+            trim_start_ms: 0,
+            trim_end_ms: 0,
+            audio_paths,
+            hdr: false,
+            secondary: None,
+            follow_cursor: None,
+            focus_track: Vec::new(),
+            script: None,
+            audio_offset_ms: 0,
+        },
+        zoom_keyframes: Vec::new(),
+        capture_group: None,
+        takes: Vec::new(),
+    })
+}
+
+/// Even size (libx264 yuv420p) of at least 16 pixels, minimum duration
+fn sanitized(spec: &TestClipSpec) -> TestClipSpec {
+    TestClipSpec {
+        duration_ms: spec.duration_ms.max(MIN_DURATION_MS),
+        width: spec.width.clamp(16, 7680) & !1,
+        height: spec.height.clamp(16, 4320) & !1,
+        tone_hz: spec.tone_hz.map(|hz| hz.clamp(20, 20_000)),
+    }
+}
+
+fn seconds(spec: &TestClipSpec) -> String {
+    format!("{:.3}", spec.duration_ms as f64 / 1000.0)
+}
+
+/// Moving test pattern with a frame counter, encoded like a normalized import
+fn video_args(spec: &TestClipSpec, output: &Path) -> Vec<String> {
+    let source = format!(
+        "testsrc2=size={}x{}:rate={}:duration={}",
+        spec.width, spec.height, crate::normalize::FPS, seconds(spec)
+    );
+    [
+        "-f", "lavfi", "-i", source.as_str(),
+        "-c:v", "libx264", "-preset", "veryfast", "-crf", "18", "-pix_fmt", "yuv420p",
+        "-movflags", "+faststart",
+        "-y", &*output.to_string_lossy(),
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+/// Stereo 48 kHz tone, like a recorded system track
+fn audio_args(spec: &TestClipSpec, hz: u32, output: &Path) -> Vec<String> {
+    let source = format!("sine=frequency={}:sample_rate=48000:duration={}", hz, seconds(spec));
+    ["-f", "lavfi", "-i", source.as_str(), "-ac", "2", "-y", &*output.to_string_lossy()]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+async fn run_ffmpeg(args: &[String]) -> Result<(), String> {
+    let result = crate::ffmpeg_command()
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(crate::i18n::tf(
+            "test_clip_failed",
+            &[&crate::export::encoder::translate_ffmpeg_error(&stderr)],
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(duration_ms: u64, width: u32, height: u32, tone_hz: Option<u32>) -> TestClipSpec {
+        TestClipSpec { duration_ms, width, height, tone_hz }
+    }
+
+    #[test]
+    fn test_video_args_use_the_exact_duration_and_size() {
+        let args = video_args(&spec(2500, 640, 360, None), Path::new("t/a.mp4"));
+        assert_eq!(args[3], "testsrc2=size=640x360:rate=30:duration=2.500");
+        assert_eq!(args.last().unwrap(), "t/a.mp4");
+    }
+
+    #[test]
+    fn test_audio_args() {
+        let args = audio_args(&spec(1000, 640, 360, Some(440)), 440, Path::new("t/a_system.wav"));
+        assert_eq!(args[3], "sine=frequency=440:sample_rate=48000:duration=1.000");
+    }
+
+    #[test]
+    fn test_sanitized() {
+        let s = sanitized(&spec(0, 641, 4, Some(5)));
+        assert_eq!((s.duration_ms, s.width, s.height, s.tone_hz), (MIN_DURATION_MS, 640, 16, Some(20)));
+    }
+}
@@ -12,6 +12,25 @@ pub struct Region {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Clip {
     pub id: String,
+    /// The take shown, its fields inline in the JSON
+    #[serde(flatten)]
+    pub media: ClipMedia,
+    /// Manual zoom/pan (zoom_scale, zoom_x, zoom_y), in source time
+    #[serde(default)]
+    pub zoom_keyframes: Vec<Keyframe>,
+    /// Set on clips recorded at the same time on several monitors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_group: Option<CaptureGroup>,
+    /// Every recording of this slot of the timeline once it was retaken,
+    /// the one shown included. Kept until the clip or its project goes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub takes: Vec<Take>,
+}
+
+/// One recording of a clip's slot and how it is cut: what a clip plays and
+/// what each of its takes keeps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipMedia {
     pub path: PathBuf,
     pub duration_ms: u64,
     pub region: Region,
@@ -23,6 +42,124 @@ pub struct Clip {
     pub trim_end_ms: u64,
     #[serde(default)]
     pub audio_paths: Vec<String>,
+    /// Recorded from an HDR display
+    #[serde(default)]
+    pub hdr: bool,
+    /// Second region recorded alongside, composited at export
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secondary: Option<SecondaryVideo>,
+    /// Recorded full screen, exported as this window following the cursor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow_cursor: Option<FollowWindow>,
+    /// Recorded full screen, exported framed on whichever window had focus
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub focus_track: Vec<FocusChange>,
+    /// Script read on the teleprompter while recording
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<ScriptTrack>,
+    /// Shift of the audio tracks against the video, to fix lip-sync or mic
+    /// latency: positive plays the audio later
+    #[serde(default)]
+    pub audio_offset_ms: i64,
+}
+
+/// Clip of `duration_ms` at `<id>.mp4`, 1920×1080 without audio, to build
+/// test timelines from
+#[cfg(test)]
+pub(crate) fn test_clip(id: &str, duration_ms: u64) -> Clip {
+    Clip {
+        id: id.into(),
+        media: ClipMedia {
+            path: PathBuf::from(format!("{id}.mp4")),
+            duration_ms,
+            region: Region { x: 0, y: 0, width: 1920, height: 1080 },
+            has_audio: false,
+            thumbnail_path: None,
+            trim_start_ms: 0,
+            trim_end_ms: 0,
+            audio_paths: Vec::new(),
+            hdr: false,
+            secondary: None,
+            follow_cursor: None,
+            focus_track: Vec::new(),
+            script: None,
+            audio_offset_ms: 0,
+        },
+        zoom_keyframes: Vec::new(),
+        capture_group: None,
+        takes: Vec::new(),
+    }
+}
+
+/// One recording of a clip's slot: what the clip plays when selected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Take {
+    /// Key of its cursor path, keystrokes and markers while not selected
+    pub id: String,
+    #[serde(flatten)]
+    pub media: ClipMedia,
+}
+
+/// Framing of the focused window from `timestamp_ms` (source time) on,
+/// normalized to the capture region
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusChange {
+    pub timestamp_ms: u64,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Size in source pixels of the window that follows the cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FollowWindow {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Window the main capture is bound to, recorded wherever it is dragged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureWindow {
+    /// Its HWND
+    pub hwnd: isize,
+    /// Size of the video, the window's at the start of the recording
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Clips recorded together, one per monitor. Trimming one trims the others
+/// to the same moments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptureGroup {
+    /// Id of the clip that carries the audio and input tracks
+    pub id: String,
+    /// How long after that clip this one started
+    #[serde(default)]
+    pub offset_ms: u64,
+}
+
+/// How a clip and its second region share the frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DualLayout {
+    #[default]
+    SideBySide,
+    Stacked,
+    /// Second region as a small inset over the first
+    Pip,
+}
+
+/// Capture of the second region, linked to the clip it was recorded with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecondaryVideo {
+    pub path: PathBuf,
+    pub region: Region,
+    /// How long after the clip the second capture started
+    #[serde(default)]
+    pub offset_ms: u64,
+    #[serde(default)]
+    pub layout: DualLayout,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -83,6 +220,17 @@ pub enum RecordingState {
 pub enum ExportFormat {
     Mp4,
     Gif,
+    Webm,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Mp4 => "mp4",
+            ExportFormat::Gif => "gif",
+            ExportFormat::Webm => "webm",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -111,6 +259,749 @@ impl ExportQuality {
     }
 }
 
+// How the timeline is fitted into a fixed output size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CanvasFit {
+    /// Scale down and pad with black bars
+    #[default]
+    Letterbox,
+    /// Scale up and cut what overflows
+    Crop,
+}
+
+// Video encoder for exports; hardware encoders also move the output scaling to the GPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportEncoder {
+    #[default]
+    Software,
+    Nvenc,
+    Qsv,
+}
+
+impl ExportEncoder {
+    pub fn encoder_name(&self) -> &'static str {
+        match self {
+            ExportEncoder::Software => "libx264",
+            ExportEncoder::Nvenc => "h264_nvenc",
+            ExportEncoder::Qsv => "h264_qsv",
+        }
+    }
+
+    /// Upload and scale filters of the GPU path
+    pub fn gpu_filters(&self) -> &'static [&'static str] {
+        match self {
+            ExportEncoder::Software => &[],
+            ExportEncoder::Nvenc => &["hwupload_cuda", "scale_cuda"],
+            ExportEncoder::Qsv => &["hwupload", "scale_qsv"],
+        }
+    }
+}
+
+// Export frame rate for outputs that don't set their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFps {
+    /// 30 fps
+    #[default]
+    Default,
+    /// Highest frame rate among the clips
+    Source,
+    Fixed(u32),
+}
+
+// Downscaling of outputs that don't set their own size, when the timeline
+// is larger than the encoder accepts or than wanted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportDownscale {
+    /// Only as much as the encoder needs
+    #[default]
+    Auto,
+    /// Never: exports too large for the encoder are refused
+    Off,
+    /// At most this many lines, aspect ratio kept
+    MaxHeight(u32),
+}
+
+impl ExportFps {
+    pub fn apply_to(&self, output: &mut OutputSettings) {
+        if output.fps.is_some() {
+            return;
+        }
+        match self {
+            ExportFps::Default => {}
+            ExportFps::Source => output.match_source_fps = true,
+            ExportFps::Fixed(fps) => output.fps = Some((*fps).clamp(1, 120)),
+        }
+    }
+}
+
+// GIF color quantization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GifPalette {
+    /// 2 to 256
+    pub max_colors: u32,
+    /// One palette per clip instead of one for the whole GIF, for timelines
+    /// mixing dark and light scenes
+    #[serde(default)]
+    pub per_clip: bool,
+}
+
+impl Default for GifPalette {
+    fn default() -> Self {
+        Self { max_colors: 256, per_clip: false }
+    }
+}
+
+// GIF playback
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GifLoop {
+    /// Play the timeline forward then backward
+    #[serde(default)]
+    pub boomerang: bool,
+    /// Times the GIF plays, 0 = forever
+    #[serde(default)]
+    pub plays: u32,
+}
+
+// Picture of an audio visualizer export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VisualizerStyle {
+    #[default]
+    Waves,
+    Spectrum,
+}
+
+// Watermark color, picked per export to contrast with the corner it covers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatermarkTone {
+    /// White, over dark content
+    #[default]
+    Light,
+    /// Black, over bright content
+    Dark,
+}
+
+// Clip generated from FFmpeg test sources, for QA without a screen capture
+#[cfg(debug_assertions)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestClipSpec {
+    pub duration_ms: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Sine tone recorded as the system track, None for a silent clip
+    #[serde(default)]
+    pub tone_hz: Option<u32>,
+}
+
+// FFmpeg runs of an export, listed instead of run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportPlan {
+    /// FFmpeg binary the export would use
+    pub ffmpeg: String,
+    /// Arguments of each run, in order
+    pub commands: Vec<Vec<String>>,
+    /// Filter graph of the timeline render, the run most failures come from.
+    /// The export passes it through a script file rather than inline.
+    pub filter_graph: Option<String>,
+    /// What the export does that the commands leave out
+    pub notes: Vec<String>,
+}
+
+// Margins of the frame covered by a platform's UI, as fractions of the
+// width (left/right) and height (top/bottom)
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct SafeArea {
+    #[serde(default)]
+    pub top: f64,
+    #[serde(default)]
+    pub right: f64,
+    #[serde(default)]
+    pub bottom: f64,
+    #[serde(default)]
+    pub left: f64,
+}
+
+// Safe area and composition grid of a canvas, shown over the preview
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafeAreaGuide {
+    pub id: String,
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub safe_area: SafeArea,
+    pub grid_columns: u32,
+    pub grid_rows: u32,
+}
+
+// Output overrides applied after composition (None = keep the timeline's own)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputSettings {
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub fps: Option<u32>,
+    #[serde(default)]
+    pub fit: CanvasFit,
+    #[serde(default = "default_true")]
+    pub include_audio: bool,
+    /// Use the clips' own frame rate when `fps` is unset
+    #[serde(default)]
+    pub match_source_fps: bool,
+    /// Lanczos resampling plus sharpening, for upscaled 1440p/4K versions
+    #[serde(default)]
+    pub upscale: bool,
+    /// Keystrokes and subtitles are kept inside it
+    #[serde(default)]
+    pub safe_area: SafeArea,
+    /// Global export setting, filled in when the export starts
+    #[serde(skip)]
+    pub encoder: ExportEncoder,
+    /// Global export setting: tonemap clips recorded from HDR displays
+    #[serde(skip)]
+    pub tonemap_hdr: bool,
+    /// Debug setting: keep the filter graph script in the temp dir, its path logged
+    #[serde(skip)]
+    pub keep_filter_script: bool,
+    /// Global export setting for GIF outputs
+    #[serde(skip)]
+    pub gif_palette: GifPalette,
+    /// Global export setting for GIF outputs
+    #[serde(skip)]
+    pub gif_loop: GifLoop,
+    /// Sampled from the timeline when the export starts
+    #[serde(skip)]
+    pub watermark_tone: WatermarkTone,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for OutputSettings {
+    fn default() -> Self {
+        Self {
+            width: None,
+            height: None,
+            fps: None,
+            fit: CanvasFit::Letterbox,
+            include_audio: true,
+            match_source_fps: false,
+            upscale: false,
+            safe_area: SafeArea::default(),
+            encoder: ExportEncoder::Software,
+            tonemap_hdr: false,
+            keep_filter_script: false,
+            gif_palette: GifPalette::default(),
+            gif_loop: GifLoop::default(),
+            watermark_tone: WatermarkTone::default(),
+        }
+    }
+}
+
+// Named bundle of export settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportPreset {
+    pub id: String,
+    pub name: String,
+    pub format: ExportFormat,
+    pub quality: ExportQuality,
+    #[serde(default)]
+    pub watermark: bool,
+    #[serde(default)]
+    pub target_size_mb: Option<u32>,
+    #[serde(default)]
+    pub output: OutputSettings,
+    /// Shipped with the app, can't be edited or removed
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+// One output of a batch export: explicit settings, or a preset id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportTarget {
+    pub format: ExportFormat,
+    pub quality: ExportQuality,
+    #[serde(default)]
+    pub target_size_mb: Option<u32>,
+    #[serde(default)]
+    pub preset: Option<String>,
+}
+
+// Combined progress of a batch export (render + every transcode)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchExportProgress {
+    /// 0-100 over the whole batch
+    pub percent: u32,
+    /// Output being transcoded, None while the timeline is rendered
+    pub target_index: Option<usize>,
+    pub target_count: usize,
+}
+
+// Progress of a clip being converted to the timeline format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalizeProgress {
+    /// File name of the source
+    pub name: String,
+    pub percent: u32,
+}
+
+// Progress of an MP4 export rendered in chunks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportChunkProgress {
+    /// 0-100 over the whole export
+    pub percent: u32,
+    /// Chunk being rendered, None while the chunks are joined
+    pub chunk_index: Option<usize>,
+    pub chunk_count: usize,
+    /// Chunks kept from an interrupted export of the same timeline
+    pub resumed: usize,
+}
+
+// Language of backend errors and notifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Fr = 0,
+    En = 1,
+}
+
+// What to do once an export has finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostExportAction {
+    OpenFolder,
+    CopyToClipboard,
+    OpenInPlayer,
+    RunCommand,
+    Nothing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostExportSettings {
+    pub action: PostExportAction,
+    /// Command line for `RunCommand`; `{path}` is replaced by the exported file
+    #[serde(default)]
+    pub command: String,
+}
+
+impl Default for PostExportSettings {
+    fn default() -> Self {
+        Self {
+            action: PostExportAction::OpenFolder,
+            command: String::new(),
+        }
+    }
+}
+
+// Folder of another capture tool (OBS, ShareX...) whose new videos become clips
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WatchFolderSettings {
+    pub path: Option<String>,
+    /// Queue new files until the user confirms instead of importing them
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+// Retention of the temp captures, applied at startup
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StoragePolicy {
+    /// Temp files older than this are removed, 0 keeps them forever
+    pub max_age_hours: u32,
+    /// Spare the media of saved projects whatever their age
+    #[serde(default = "default_true")]
+    pub keep_project_media: bool,
+}
+
+impl Default for StoragePolicy {
+    fn default() -> Self {
+        Self {
+            max_age_hours: 24,
+            keep_project_media: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StorageEntry {
+    pub bytes: u64,
+    pub files: u64,
+}
+
+// Disk usage of the temp and cache data. `orphaned` overlaps the other
+// categories: it counts the temp files no clip or saved project uses.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StorageUsage {
+    pub clips: StorageEntry,
+    pub thumbnails: StorageEntry,
+    /// Renders cached for export (follow, focus, dual) and chunked exports
+    pub proxies: StorageEntry,
+    pub previews: StorageEntry,
+    pub orphaned: StorageEntry,
+}
+
+// Upload targets for sharing exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpUploadMethod {
+    #[default]
+    Put,
+    Post,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadHeader {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UploadKind {
+    /// Raw file body sent to `url` with custom (auth) headers
+    Http {
+        url: String,
+        #[serde(default)]
+        method: HttpUploadMethod,
+        #[serde(default)]
+        headers: Vec<UploadHeader>,
+        /// Dotted JSON path of the shareable URL in the response (e.g. `data.link`)
+        #[serde(default)]
+        url_field: Option<String>,
+    },
+    /// S3-compatible bucket (AWS, R2, MinIO, ...), path-style requests
+    S3 {
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key_id: String,
+        secret_access_key: String,
+        #[serde(default)]
+        key_prefix: String,
+        /// Base URL the object is publicly served from, if not the endpoint
+        #[serde(default)]
+        public_url: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadTarget {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: UploadKind,
+}
+
+// Webhooks notified when an export or upload finishes: chat messages, or a
+// JSON description of the file for automation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    Discord,
+    Slack,
+    Generic,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub name: String,
+    pub kind: WebhookKind,
+    pub url: String,
+    pub enabled: bool,
+    pub on_export: bool,
+    pub on_upload: bool,
+    /// Attach the exported file (Discord only) when it fits under `max_file_mb`
+    #[serde(default)]
+    pub attach_file: bool,
+    #[serde(default = "default_webhook_max_file_mb")]
+    pub max_file_mb: u32,
+}
+
+fn default_webhook_max_file_mb() -> u32 {
+    25
+}
+
+// FFmpeg first-run download
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FfmpegDownloadStage {
+    Downloading,
+    Verifying,
+    Extracting,
+    Retrying,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfmpegDownloadProgress {
+    pub stage: FfmpegDownloadStage,
+    pub downloaded_bytes: u64,
+    /// 0 when the server did not send a length
+    pub total_bytes: u64,
+    pub attempt: u32,
+}
+
+// Opt-in anonymous usage metrics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+    /// Collector URL receiving batched JSON events
+    #[serde(default)]
+    pub endpoint: String,
+}
+
+// Capture encoder settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingCodec {
+    /// libx264 yuv420p, CRF-controlled
+    X264,
+    /// libx264rgb at qp 0 — lossless, full RGB
+    X264Rgb,
+    /// Ut Video — lossless intra-only, large files but very light on CPU
+    UtVideo,
+}
+
+// Encoder behind the x264 codec: libx264 or a GPU encoder, much lighter on
+// the CPU while recording games
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingEncoder {
+    /// First GPU encoder that works, libx264 when none does
+    #[default]
+    Auto,
+    Software,
+    H264Nvenc,
+    /// Converted to H.264 in the background once the recording stops
+    HevcNvenc,
+    H264Qsv,
+    H264Amf,
+}
+
+impl RecordingEncoder {
+    pub fn encoder_name(&self) -> &'static str {
+        match self {
+            RecordingEncoder::Auto | RecordingEncoder::Software => "libx264",
+            RecordingEncoder::H264Nvenc => "h264_nvenc",
+            RecordingEncoder::HevcNvenc => "hevc_nvenc",
+            RecordingEncoder::H264Qsv => "h264_qsv",
+            RecordingEncoder::H264Amf => "h264_amf",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingEncoderInfo {
+    pub kind: RecordingEncoder,
+    /// Listed by FFmpeg and able to encode on this machine
+    pub available: bool,
+    /// What Auto records with
+    pub auto: bool,
+}
+
+const X264_PRESETS: [&str; 9] = [
+    "ultrafast", "superfast", "veryfast", "faster", "fast", "medium", "slow", "slower", "veryslow",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingQuality {
+    pub codec: RecordingCodec,
+    pub crf: u32,
+    pub preset: String,
+    /// Encoder of the x264 codec, settled before the capture starts (Auto
+    /// records with libx264)
+    #[serde(default)]
+    pub encoder: RecordingEncoder,
+}
+
+impl Default for RecordingQuality {
+    fn default() -> Self {
+        Self {
+            codec: RecordingCodec::X264,
+            crf: 18,
+            preset: "ultrafast".into(),
+            encoder: RecordingEncoder::Auto,
+        }
+    }
+}
+
+impl RecordingQuality {
+    /// Clamp CRF to the x264 range and fall back to ultrafast for unknown presets.
+    pub fn sanitized(mut self) -> Self {
+        self.crf = self.crf.min(51);
+        if !X264_PRESETS.contains(&self.preset.as_str()) {
+            self.preset = "ultrafast".into();
+        }
+        self
+    }
+
+    /// Name of the FFmpeg encoder behind `codec`.
+    pub fn encoder_name(&self) -> &'static str {
+        match self.codec {
+            RecordingCodec::X264 => self.encoder.encoder_name(),
+            RecordingCodec::X264Rgb => "libx264rgb",
+            RecordingCodec::UtVideo => "utvideo",
+        }
+    }
+
+    /// FFmpeg video encoder arguments for the capture process. The GPU
+    /// encoders take the CRF as their constant quality and use their fastest
+    /// preset.
+    pub fn encoder_args(&self) -> Vec<String> {
+        let crf = self.crf.to_string();
+        let args: Vec<&str> = match (self.codec, self.encoder) {
+            (RecordingCodec::X264, RecordingEncoder::Auto | RecordingEncoder::Software) => {
+                vec!["-c:v", "libx264", "-preset", &self.preset, "-crf", &crf, "-pix_fmt", "yuv420p"]
+            }
+            (RecordingCodec::X264, RecordingEncoder::H264Nvenc | RecordingEncoder::HevcNvenc) => vec![
+                "-c:v", self.encoder.encoder_name(), "-preset", "p1", "-tune", "ll", "-rc", "vbr", "-cq", &crf, "-b:v", "0",
+                "-pix_fmt", "yuv420p",
+            ],
+            (RecordingCodec::X264, RecordingEncoder::H264Qsv) => {
+                vec!["-c:v", "h264_qsv", "-preset", "veryfast", "-global_quality", &crf, "-pix_fmt", "nv12"]
+            }
+            (RecordingCodec::X264, RecordingEncoder::H264Amf) => vec![
+                "-c:v", "h264_amf", "-usage", "lowlatency", "-quality", "speed", "-rc", "cqp", "-qp_i", &crf, "-qp_p", &crf,
+                "-pix_fmt", "yuv420p",
+            ],
+            (RecordingCodec::X264Rgb, _) => vec!["-c:v", "libx264rgb", "-preset", &self.preset, "-qp", "0"],
+            (RecordingCodec::UtVideo, _) => vec!["-c:v", "utvideo"],
+        };
+        args.iter().map(|s| s.to_string()).collect()
+    }
+}
+
+// Capture frame rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureRate {
+    /// 30 fps, the timeline rate
+    #[default]
+    Default,
+    /// The recorded monitor's refresh rate divided by this, 1 for the
+    /// native rate
+    RefreshDivisor(u32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureRateOption {
+    pub divisor: u32,
+    pub fps: u32,
+}
+
+/// Refresh rate of the monitor about to be recorded and the capture rates
+/// dividing it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureTiming {
+    pub refresh_rate: Option<u32>,
+    pub options: Vec<CaptureRateOption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingStats {
+    /// Rate the capture was started at
+    pub target_fps: u32,
+    pub refresh_rate: Option<u32>,
+    /// Rate FFmpeg keeps up with, none while paused or starting
+    pub achieved_fps: Option<f64>,
+}
+
+// Screen grab behind the recordings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureBackendKind {
+    /// GDI, any region of the virtual desktop
+    #[default]
+    Gdigrab,
+    /// Windows Graphics Capture, one monitor at a time, frames piped to
+    /// FFmpeg. Opt-in while it is new.
+    Wgc,
+    /// Desktop Duplication, one monitor at a time, on the GPU
+    Ddagrab,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureBackendInfo {
+    pub kind: CaptureBackendKind,
+    /// The installed FFmpeg has what it needs
+    pub available: bool,
+    pub gpu: bool,
+    /// Records regions across monitor edges and the whole desktop
+    pub spans_monitors: bool,
+}
+
+// How the segments around a pause are joined on stop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PauseTransitionStyle {
+    /// Plain cut, joined without re-encoding
+    #[default]
+    Cut,
+    Crossfade,
+    /// Fade to black and back
+    Dip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PauseTransition {
+    pub style: PauseTransitionStyle,
+    pub duration_ms: u32,
+}
+
+impl Default for PauseTransition {
+    fn default() -> Self {
+        Self {
+            style: PauseTransitionStyle::Cut,
+            duration_ms: 400,
+        }
+    }
+}
+
+// Automatic segment rollover for long recordings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentRollover {
+    pub enabled: bool,
+    /// Roll over after this many minutes (0 = no time limit)
+    pub max_minutes: u32,
+    /// Roll over once the segment file reaches this size (0 = no size limit)
+    pub max_megabytes: u64,
+    /// Keep each segment as a separate clip instead of concatenating on stop
+    pub split_clips: bool,
+}
+
+impl Default for SegmentRollover {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_minutes: 30,
+            max_megabytes: 4096,
+            split_clips: false,
+        }
+    }
+}
+
+// Lighter capture settings while the machine is busy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdaptiveCapture {
+    pub enabled: bool,
+    /// Lighten the capture once system CPU usage stays above this percentage
+    pub cpu_threshold: u32,
+}
+
+impl Default for AdaptiveCapture {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cpu_threshold: 85,
+        }
+    }
+}
+
 // Audio source selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -121,6 +1012,25 @@ pub enum AudioSource {
     Both,
 }
 
+/// Recent levels of an audio track being recorded, oldest first, one per
+/// `audio-waveform` event interval, 0 to 255 for silence to full scale
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaveformTrack {
+    pub source: AudioSource,
+    pub peaks: Vec<u8>,
+}
+
+/// Format of the recorded audio tracks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    /// 48 kHz 16-bit PCM, resampled while recording
+    #[default]
+    Canonical,
+    /// The device's own rate, as 32-bit float
+    Native,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioDevice {
     pub name: String,
@@ -136,6 +1046,25 @@ pub struct WindowInfo {
     pub y: i32,
     pub width: u32,
     pub height: u32,
+    #[serde(default)]
+    pub client_rect: Option<Region>,
+    /// HWND, to bind the capture to the window
+    #[serde(default)]
+    pub hwnd: isize,
+    /// Small PNG preview as a data URL, only filled when requested
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+}
+
+// Monitor bounds and usable work area (excluding taskbar)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorArea {
+    pub bounds: Region,
+    pub work_area: Region,
+    pub is_primary: bool,
+    /// In Hz, 0 when unknown
+    #[serde(default)]
+    pub refresh_rate: u32,
 }
 
 // Annotations
@@ -153,6 +1082,38 @@ pub struct Annotation {
     pub points: Option<Vec<(f64, f64)>>, // for freehand
     pub start_ms: u64,
     pub end_ms: u64,
+    /// Opacity animation, same time base as start_ms
+    #[serde(default)]
+    pub keyframes: Vec<Keyframe>,
+    /// Stacking order, higher is drawn on top, ties in list order
+    #[serde(default)]
+    pub z_index: i32,
+    /// Annotations sharing a group are selected, moved and reordered together
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+/// `kind` annotation of a fifth of the frame near its top left corner,
+/// shown from `start_ms` to `end_ms`, to build test timelines from
+#[cfg(test)]
+pub(crate) fn test_annotation(kind: AnnotationKind, start_ms: u64, end_ms: u64) -> Annotation {
+    Annotation {
+        id: "a".into(),
+        kind,
+        x: 0.1,
+        y: 0.1,
+        width: 0.2,
+        height: 0.2,
+        color: "#fff".into(),
+        stroke_width: 2.0,
+        text: None,
+        points: None,
+        start_ms,
+        end_ms,
+        keyframes: Vec::new(),
+        z_index: 0,
+        group: None,
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -165,6 +1126,52 @@ pub enum AnnotationKind {
     Freehand,
 }
 
+/// Where selected annotations go in the stacking order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZOrder {
+    ToFront,
+    ToBack,
+    /// One step up, past the next annotation above
+    Forward,
+    Backward,
+}
+
+// Keyframe animation shared by zoom/pan and annotations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimatedProperty {
+    /// Zoom factor, 1 = full frame
+    ZoomScale,
+    /// Zoom center, normalized 0-1
+    ZoomX,
+    ZoomY,
+    /// 0 = invisible, 1 = fully visible
+    Opacity,
+}
+
+/// Shape of the segment that starts at a keyframe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// Keep the value until the next keyframe
+    Hold,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyframe {
+    pub time_ms: u64,
+    pub property: AnimatedProperty,
+    pub value: f64,
+    #[serde(default)]
+    pub easing: Easing,
+}
+
 // Subtitles
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Subtitle {
@@ -175,9 +1182,12 @@ pub struct Subtitle {
     pub position: SubtitlePosition,
     pub font_size: u32,
     pub color: String,
+    /// Path to a .ttf/.otf file, the bundled font when unset
+    #[serde(default)]
+    pub font_file: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SubtitlePosition {
     Top,
@@ -185,6 +1195,124 @@ pub enum SubtitlePosition {
     Bottom,
 }
 
+/// Subtitles of a language other than the active one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleTrack {
+    /// ISO 639 code, "und" when not given
+    pub language: String,
+    pub subtitles: Vec<Subtitle>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleLanguage {
+    pub language: String,
+    pub count: usize,
+    /// The one edited and burned into exports
+    pub active: bool,
+}
+
+// How exports carry the subtitles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleExport {
+    /// The active language drawn into the picture
+    #[default]
+    Burned,
+    /// Every language as a track players can switch, burned in GIFs
+    Soft,
+}
+
+pub(crate) fn default_subtitle_language() -> String {
+    crate::subtitles::UNDETERMINED.into()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubtitleIssueKind {
+    /// Still on screen when the next one at the same position shows
+    Overlap,
+    /// Gone before it can be read
+    TooShort,
+    /// Shown for less time than its text takes to read
+    TooFast,
+    /// Cut at the end of the timeline, or starting after it
+    OffTimeline,
+}
+
+/// Timing problem found by `check_subtitles`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleIssue {
+    pub kind: SubtitleIssueKind,
+    pub subtitle_id: String,
+    pub message: String,
+}
+
+/// Subtitles once `fit_subtitles` has fixed their timing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleFit {
+    pub subtitles: Vec<Subtitle>,
+    /// How many had their timing changed
+    pub changed: usize,
+    /// What couldn't be fixed
+    pub issues: Vec<SubtitleIssue>,
+}
+
+/// Time a subtitle's text takes to read, from `suggest_subtitle_durations`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleReading {
+    pub subtitle_id: String,
+    /// Shortest duration to read the text at a comfortable pace
+    pub suggested_ms: u64,
+    /// Reading speed the current timing asks for
+    pub chars_per_second: f64,
+    pub too_fast: bool,
+}
+
+// Narration recorded over the timeline playback, mixed in at export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Voiceover {
+    pub id: String,
+    pub path: PathBuf,
+    /// Position in the timeline where it starts
+    pub start_ms: u64,
+    pub duration_ms: u64,
+}
+
+// Stretch of speech on a clip's voice track, in source time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeechSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+// Loudness and clipping of one audio source of a clip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioAnalysis {
+    pub path: String,
+    /// Integrated loudness (LUFS), None for silence
+    pub integrated_lufs: Option<f64>,
+    /// Loudness range (LU)
+    pub loudness_range_lu: Option<f64>,
+    /// True peak (dBTP), None for silence
+    pub true_peak_dbtp: Option<f64>,
+    /// Samples at full scale, all channels counted
+    pub clipped_samples: u64,
+    pub sample_count: u64,
+    pub advice: AudioAdvice,
+}
+
+/// What a source needs before export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioAdvice {
+    Good,
+    /// Too quiet, too loud or peaking: a gain change fixes it
+    Normalize,
+    /// Clipped while recording, which no gain change undoes
+    Retake,
+    Silent,
+}
+
 // Keystroke events
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeystrokeEvent {
@@ -200,6 +1328,81 @@ pub struct CursorPosition {
     pub y: f64, // relative to region 0-1
 }
 
+/// Controller state, recorded each time it changes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GamepadSample {
+    pub timestamp_ms: u64,
+    pub buttons: u16, // XInput button flags
+    // Sticks in tenths, -10 to 10 (up and right are positive)
+    pub left_x: i8,
+    pub left_y: i8,
+    pub right_x: i8,
+    pub right_y: i8,
+    // Triggers in tenths, 0 to 10
+    pub left_trigger: u8,
+    pub right_trigger: u8,
+}
+
+// Teleprompter shown while recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeleprompterSettings {
+    pub script: String,
+    /// Scrolling speed, in words read per minute
+    pub words_per_minute: u32,
+}
+
+/// Script line that reached the teleprompter's reading line at
+/// `timestamp_ms` (recording time, pauses excluded)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptMark {
+    pub timestamp_ms: u64,
+    pub line: usize,
+}
+
+/// Lines of the script read during a clip, and when each one was reached
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptTrack {
+    pub lines: Vec<String>,
+    pub marks: Vec<ScriptMark>,
+}
+
+// Live drawing during a recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DrawingTool {
+    Pen,
+    Highlighter,
+}
+
+/// Stroke sent by the drawing overlay when the pen is lifted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveStroke {
+    pub points: Vec<(f64, f64)>, // normalized to the recorded area 0-1
+    pub tool: DrawingTool,
+    pub color: String,
+    /// Time the pen was down, the stroke starts that long before it is sent
+    pub duration_ms: u64,
+}
+
+// Timeline markers (gaps, notes) attached to a clip
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkerKind {
+    Privacy,
+    /// Microphone muted from `timestamp_ms` to `end_ms`
+    MicMute,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineMarker {
+    pub timestamp_ms: u64,
+    pub label: String,
+    pub kind: MarkerKind,
+    /// End of a marker covering a span
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_ms: Option<u64>,
+}
+
 // Project types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
@@ -212,6 +1415,20 @@ pub struct Project {
     pub settings: ProjectSettings,
     pub annotations: std::collections::HashMap<String, Vec<Annotation>>,
     pub subtitles: Vec<Subtitle>,
+    /// Language of `subtitles`
+    #[serde(default = "default_subtitle_language")]
+    pub subtitle_language: String,
+    /// The other languages
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtitle_tracks: Vec<SubtitleTrack>,
+    /// Empty when stored in the binary `.cursor` sidecar
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub cursor_positions: std::collections::HashMap<String, Vec<CursorPosition>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub voiceovers: Vec<Voiceover>,
+    /// Over the whole timeline rather than a clip, timed like the subtitles
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub global_annotations: Vec<Annotation>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -232,6 +1449,84 @@ pub struct ProjectSummary {
     pub total_duration_ms: u64,
 }
 
+/// A video found in the export folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedFile {
+    pub path: String,
+    pub name: String,
+    pub size_bytes: u64,
+    /// Last modification, in ms since the Unix epoch
+    pub modified_ms: u64,
+}
+
+// Filters / encoders compiled into the FFmpeg build in use
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FfmpegCapabilities {
+    pub version: Option<String>,
+    pub filters: Vec<String>,
+    pub encoders: Vec<String>,
+}
+
+impl FfmpegCapabilities {
+    pub fn has_filter(&self, name: &str) -> bool {
+        self.filters.iter().any(|f| f == name)
+    }
+
+    pub fn has_encoder(&self, name: &str) -> bool {
+        self.encoders.iter().any(|e| e == name)
+    }
+}
+
+// Installed font offered for subtitles / keystrokes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontFile {
+    pub name: String,
+    pub path: String,
+}
+
+// Result of the GitHub release check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_available: bool,
+    /// Release notes (Markdown)
+    pub changelog: String,
+    /// Installer asset, or the release page when there is none
+    pub download_url: String,
+    pub published_at: Option<String>,
+}
+
+// Support diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncoderAvailability {
+    pub name: String,
+    /// Listed by `ffmpeg -encoders` (the GPU may still be missing at runtime)
+    pub available: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskSpace {
+    pub path: PathBuf,
+    pub free_bytes: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsReport {
+    pub generated_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub ffmpeg_version: Option<String>,
+    pub ffprobe_version: Option<String>,
+    pub gpus: Vec<String>,
+    pub encoders: Vec<EncoderAvailability>,
+    pub audio_devices: Vec<AudioDevice>,
+    pub monitors: Vec<MonitorArea>,
+    pub disks: Vec<DiskSpace>,
+    pub recent_errors: Vec<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -250,6 +1545,39 @@ mod tests {
         assert_eq!(ExportQuality::Low.preset(), "fast");
     }
 
+    #[test]
+    fn test_recording_quality_default_args() {
+        let args = RecordingQuality::default().encoder_args();
+        assert_eq!(args, vec!["-c:v", "libx264", "-preset", "ultrafast", "-crf", "18", "-pix_fmt", "yuv420p"]);
+    }
+
+    #[test]
+    fn test_recording_quality_lossless_rgb() {
+        let q = RecordingQuality { codec: RecordingCodec::X264Rgb, crf: 18, preset: "fast".into(), encoder: RecordingEncoder::H264Nvenc };
+        let args = q.encoder_args();
+        assert!(args.contains(&"libx264rgb".to_string()));
+        assert!(args.contains(&"-qp".to_string()));
+        assert!(!args.contains(&"-pix_fmt".to_string()));
+    }
+
+    #[test]
+    fn test_recording_quality_sanitized() {
+        let q = RecordingQuality { codec: RecordingCodec::X264, crf: 80, preset: "bogus".into(), encoder: RecordingEncoder::Auto }.sanitized();
+        assert_eq!(q.crf, 51);
+        assert_eq!(q.preset, "ultrafast");
+    }
+
+    #[test]
+    fn test_recording_quality_gpu_encoder() {
+        let q = RecordingQuality { encoder: RecordingEncoder::H264Nvenc, crf: 23, ..Default::default() };
+        let args = q.encoder_args();
+        assert_eq!(&args[..2], ["-c:v", "h264_nvenc"]);
+        assert!(args.windows(2).any(|w| w == ["-cq", "23"]));
+        assert_eq!(q.encoder_name(), "h264_nvenc");
+        let qsv = RecordingQuality { encoder: RecordingEncoder::H264Qsv, ..Default::default() };
+        assert!(qsv.encoder_args().windows(2).any(|w| w == ["-pix_fmt", "nv12"]));
+    }
+
     #[test]
     fn test_transition_default() {
         let t = Transition::default();
@@ -275,3 +1603,84 @@ mod tests {
         assert_ne!(AudioSource::System, AudioSource::Microphone);
     }
 }
+
+// Approximate export size shown before exporting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeEstimateMethod {
+    /// Duration × resolution × per-quality bitrate
+    Heuristic,
+    /// Extrapolated from a short sample encode of the first clip
+    Sample,
+}
+
+/// Export size against the encoder limits, before kicking off the export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolutionCheck {
+    /// Timeline or preset size
+    pub width: u32,
+    pub height: u32,
+    /// Size after the downscale setting
+    pub export_width: u32,
+    pub export_height: u32,
+    /// Largest side the encoder accepts
+    pub max_side: u32,
+    /// The timeline size is too large for the encoder
+    pub exceeds_limit: bool,
+    /// The export size is within the encoder limits
+    pub fits: bool,
+    /// Above 4K: slow, heavy export
+    pub slow: bool,
+    /// Downscale settings bringing it down, largest first
+    pub options: Vec<ResolutionOption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolutionOption {
+    pub downscale: ExportDownscale,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    /// The export would fail
+    Error,
+    /// The export works but may not come out as expected
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportIssueKind {
+    NoClips,
+    MissingClip,
+    MissingAudio,
+    UnreadableClip,
+    SizeMismatch,
+    FrameRate,
+    Resolution,
+    DiskSpace,
+    OutputNotWritable,
+}
+
+/// Problem found by `validate_export` before the export starts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportIssue {
+    pub severity: IssueSeverity,
+    pub kind: ExportIssueKind,
+    /// Clip the issue is about, if any
+    pub clip_id: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SizeEstimate {
+    pub bytes: u64,
+    pub duration_ms: u64,
+    /// Output dimensions (GIF is scaled down per quality)
+    pub width: u32,
+    pub height: u32,
+    pub method: SizeEstimateMethod,
+}
@@ -0,0 +1,111 @@
+use crate::types::UpdateInfo;
+use serde::Deserialize;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/thomaslekieffre/ClipFlow/releases/latest";
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Ask GitHub for the latest release and compare it with `current_version`
+pub fn check(current_version: &str) -> Result<UpdateInfo, String> {
+    let body = ureq::get(RELEASES_URL)
+        // GitHub rejects API calls without a user agent
+        .set("User-Agent", &format!("ClipFlow/{}", current_version))
+        .set("Accept", "application/vnd.github+json")
+        .timeout(std::time::Duration::from_secs(10))
+        .call()
+        .map_err(|e| crate::i18n::tf("update_check_failed", &[&e]))?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+    let release: Release = serde_json::from_str(&body).map_err(|e| e.to_string())?;
+
+    let latest = release.tag_name.trim_start_matches('v').to_string();
+    let update_available = is_newer(&latest, current_version);
+    tracing::info!("Latest release {} (current {}, update: {})", latest, current_version, update_available);
+
+    Ok(UpdateInfo {
+        current_version: current_version.to_string(),
+        latest_version: latest,
+        update_available,
+        changelog: release.body.unwrap_or_default(),
+        download_url: pick_installer(&release.assets).unwrap_or(release.html_url),
+        published_at: release.published_at,
+    })
+}
+
+/// Windows installer asset, preferring the NSIS setup over the MSI
+fn pick_installer(assets: &[Asset]) -> Option<String> {
+    let find = |suffix: &str| {
+        assets
+            .iter()
+            .find(|a| a.name.to_lowercase().ends_with(suffix))
+            .map(|a| a.browser_download_url.clone())
+    };
+    find("-setup.exe").or_else(|| find(".exe")).or_else(|| find(".msi"))
+}
+
+/// `1.2.10` > `1.2.9`; pre-release suffixes (`-beta.1`) rank below the release
+fn is_newer(candidate: &str, current: &str) -> bool {
+    parse_version(candidate) > parse_version(current)
+}
+
+fn parse_version(version: &str) -> (Vec<u64>, bool) {
+    let version = version.trim().trim_start_matches('v');
+    let (core, pre) = match version.split_once('-') {
+        Some((core, _)) => (core, true),
+        None => (version, false),
+    };
+    let mut numbers: Vec<u64> = core.split('.').map(|n| n.parse().unwrap_or(0)).collect();
+    while numbers.len() < 3 {
+        numbers.push(0);
+    }
+    // A release sorts after its own pre-releases
+    (numbers, !pre)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> Asset {
+        Asset { name: name.into(), browser_download_url: format!("https://example.com/{}", name) }
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.2.0", "0.1.0"));
+        assert!(is_newer("v1.2.10", "1.2.9"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("0.0.9", "0.1.0"));
+    }
+
+    #[test]
+    fn test_prerelease_ordering() {
+        assert!(is_newer("1.0.0", "1.0.0-beta.2"));
+        assert!(!is_newer("1.0.0-beta.2", "1.0.0"));
+    }
+
+    #[test]
+    fn test_pick_installer_prefers_setup() {
+        let assets = vec![asset("ClipFlow_0.2.0_x64_en-US.msi"), asset("ClipFlow_0.2.0_x64-setup.exe")];
+        assert_eq!(pick_installer(&assets).unwrap(), "https://example.com/ClipFlow_0.2.0_x64-setup.exe");
+        assert_eq!(pick_installer(&[asset("ClipFlow.msi")]).unwrap(), "https://example.com/ClipFlow.msi");
+        assert!(pick_installer(&[asset("latest.json")]).is_none());
+    }
+}
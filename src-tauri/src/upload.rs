@@ -0,0 +1,242 @@
+use crate::types::{HttpUploadMethod, UploadKind, UploadTarget};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+/// Upload `path` to `target` and return a shareable URL.
+/// Blocking: call from `spawn_blocking`. Emits `upload-progress` (0-100).
+pub fn upload_file(app: &AppHandle, path: &Path, target: &UploadTarget) -> Result<String, String> {
+    let file = std::fs::File::open(path).map_err(|e| crate::i18n::tf("file_not_found", &[&e]))?;
+    let total = file.metadata().map_err(|e| e.to_string())?.len();
+    let file_name = path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| crate::i18n::t("file_name_invalid"))?;
+    let reader = ProgressReader::new(file, total, app.clone());
+
+    tracing::info!("{} -> '{}' ({} bytes)", file_name, target.name, total);
+    let url = match target.kind {
+        UploadKind::Http { ref url, method, ref headers, ref url_field } => {
+            let method = match method {
+                HttpUploadMethod::Put => "PUT",
+                HttpUploadMethod::Post => "POST",
+            };
+            let mut request = ureq::request(method, url)
+                .set("Content-Type", content_type(&file_name))
+                .set("Content-Length", &total.to_string());
+            for h in headers {
+                request = request.set(&h.name, &h.value);
+            }
+            let response = request.send(reader).map_err(describe_error)?;
+            let body = response.into_string().unwrap_or_default();
+            resolve_response_url(&body, url_field.as_deref()).unwrap_or_else(|| url.clone())
+        }
+        UploadKind::S3 {
+            ref endpoint, ref bucket, ref region,
+            ref access_key_id, ref secret_access_key,
+            ref key_prefix, ref public_url,
+        } => {
+            let key = format!("{}{}", key_prefix, file_name);
+            let endpoint = endpoint.trim_end_matches('/');
+            let host = endpoint.split("://").nth(1).unwrap_or(endpoint);
+            let canonical_uri = format!("/{}/{}", uri_encode(bucket, true), uri_encode(&key, false));
+            let now = chrono::Utc::now();
+            let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+            let authorization = sigv4_authorization(
+                "PUT", host, &canonical_uri, &amz_date, region, access_key_id, secret_access_key,
+            );
+
+            ureq::put(&format!("{}{}", endpoint, canonical_uri))
+                .set("Authorization", &authorization)
+                .set("x-amz-date", &amz_date)
+                .set("x-amz-content-sha256", UNSIGNED_PAYLOAD)
+                .set("Content-Type", content_type(&file_name))
+                .set("Content-Length", &total.to_string())
+                .send(reader)
+                .map_err(describe_error)?;
+
+            match public_url {
+                Some(base) if !base.is_empty() => {
+                    format!("{}/{}", base.trim_end_matches('/'), uri_encode(&key, false))
+                }
+                _ => format!("{}{}", endpoint, canonical_uri),
+            }
+        }
+    };
+
+    let _ = app.emit("upload-progress", 100u32);
+    tracing::info!("Done: {}", url);
+    Ok(url)
+}
+
+/// Wraps the file being sent and reports progress as it is read
+struct ProgressReader<R> {
+    inner: R,
+    sent: u64,
+    total: u64,
+    last_percent: u32,
+    app: AppHandle,
+}
+
+impl<R: Read> ProgressReader<R> {
+    fn new(inner: R, total: u64, app: AppHandle) -> Self {
+        Self { inner, sent: 0, total, last_percent: 0, app }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.sent += n as u64;
+        if self.total > 0 {
+            // Stay below 100 until the server has answered
+            let percent = ((self.sent * 99) / self.total) as u32;
+            if percent != self.last_percent {
+                self.last_percent = percent;
+                let _ = self.app.emit("upload-progress", percent);
+            }
+        }
+        Ok(n)
+    }
+}
+
+fn describe_error(e: ureq::Error) -> String {
+    match e {
+        ureq::Error::Status(code, response) => {
+            let body = response.into_string().unwrap_or_default();
+            crate::i18n::tf("upload_rejected", &[&code, &body.chars().take(300).collect::<String>()])
+        }
+        ureq::Error::Transport(t) => crate::i18n::tf("upload_failed", &[&t]),
+    }
+}
+
+fn content_type(file_name: &str) -> &'static str {
+    match file_name.rsplit('.').next().map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("mp4") => "video/mp4",
+        Some("gif") => "image/gif",
+        Some("png") => "image/png",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Pick the shareable URL out of a response body.
+/// With `url_field` (dotted path, e.g. `data.link`) the body is read as JSON,
+/// otherwise a body that is itself a URL is used as-is.
+fn resolve_response_url(body: &str, url_field: Option<&str>) -> Option<String> {
+    match url_field.filter(|f| !f.is_empty()) {
+        Some(field) => {
+            let json: serde_json::Value = serde_json::from_str(body).ok()?;
+            let value = field.split('.').try_fold(&json, |v, key| v.get(key))?;
+            value.as_str().map(|s| s.to_string())
+        }
+        None => {
+            let trimmed = body.trim();
+            (trimmed.starts_with("http://") || trimmed.starts_with("https://"))
+                .then(|| trimmed.to_string())
+        }
+    }
+}
+
+// ── AWS Signature V4 (S3-compatible endpoints) ──
+
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+fn sigv4_authorization(
+    method: &str,
+    host: &str,
+    canonical_uri: &str,
+    amz_date: &str,
+    region: &str,
+    access_key_id: &str,
+    secret_access_key: &str,
+) -> String {
+    let date = &amz_date[..8];
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method, canonical_uri, host, UNSIGNED_PAYLOAD, amz_date, signed_headers, UNSIGNED_PAYLOAD,
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date, scope, hex(&Sha256::digest(canonical_request.as_bytes())),
+    );
+    let key = signing_key(secret_access_key, date, region, "s3");
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key_id, scope, signed_headers, signature,
+    )
+}
+
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let k_date = hmac_sha256(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let k_region = hmac_sha256(&k_date, region.as_bytes());
+    let k_service = hmac_sha256(&k_region, service.as_bytes());
+    hmac_sha256(&k_service, b"aws4_request")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// RFC 3986 encoding as required by SigV4; `/` is kept in object keys
+fn uri_encode(s: &str, encode_slash: bool) -> String {
+    let mut out = String::new();
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(b as char),
+            b'/' if !encode_slash => out.push('/'),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_key_aws_example() {
+        // Example from the AWS "deriving the signing key" documentation
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        assert_eq!(hex(&key), "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+    }
+
+    #[test]
+    fn test_uri_encode() {
+        assert_eq!(uri_encode("clips/my video.mp4", false), "clips/my%20video.mp4");
+        assert_eq!(uri_encode("a/b", true), "a%2Fb");
+    }
+
+    #[test]
+    fn test_resolve_url_plain_body() {
+        assert_eq!(
+            resolve_response_url(" https://x.io/abc\n", None),
+            Some("https://x.io/abc".into())
+        );
+        assert_eq!(resolve_response_url("OK", None), None);
+    }
+
+    #[test]
+    fn test_resolve_url_json_field() {
+        let body = r#"{"data":{"link":"https://cdn/x.mp4"}}"#;
+        assert_eq!(resolve_response_url(body, Some("data.link")), Some("https://cdn/x.mp4".into()));
+        assert_eq!(resolve_response_url(body, Some("data.missing")), None);
+    }
+
+    #[test]
+    fn test_content_type() {
+        assert_eq!(content_type("a.MP4"), "video/mp4");
+        assert_eq!(content_type("a.gif"), "image/gif");
+        assert_eq!(content_type("a"), "application/octet-stream");
+    }
+}
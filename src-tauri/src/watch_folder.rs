@@ -0,0 +1,145 @@
+use crate::events::{self, AppEvent};
+use crate::state::AppState;
+use crate::types::RecordingState;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const POLL_INTERVAL_MS: u64 = 2000;
+
+/// What other capture tools write (OBS records to MKV by default)
+const VIDEO_EXTENSIONS: [&str; 4] = ["mp4", "mov", "mkv", "gif"];
+
+/// Files of the watched folder between two scans
+#[derive(Default)]
+struct Tracker {
+    dir: Option<PathBuf>,
+    /// Size of each file not handled yet, on the previous scan
+    sizes: HashMap<PathBuf, u64>,
+    /// Imported, queued, or already there when watching started
+    handled: HashSet<PathBuf>,
+}
+
+impl Tracker {
+    /// Files that appeared since the folder was first scanned and kept the
+    /// same size since the previous scan, i.e. the other tool is done writing
+    fn update(&mut self, dir: &Path, files: Vec<(PathBuf, u64)>) -> Vec<PathBuf> {
+        if self.dir.as_deref() != Some(dir) {
+            *self = Self {
+                dir: Some(dir.to_path_buf()),
+                sizes: HashMap::new(),
+                handled: files.into_iter().map(|(path, _)| path).collect(),
+            };
+            return Vec::new();
+        }
+
+        let mut ready = Vec::new();
+        let mut sizes = HashMap::new();
+        for (path, size) in files {
+            if self.handled.contains(&path) {
+                continue;
+            }
+            if size > 0 && self.sizes.get(&path) == Some(&size) {
+                self.handled.insert(path.clone());
+                ready.push(path);
+            } else {
+                sizes.insert(path, size);
+            }
+        }
+        self.sizes = sizes;
+        ready
+    }
+}
+
+/// Videos directly in `dir`, with their size
+fn scan(dir: &Path) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+            let meta = entry.metadata().ok().filter(|m| m.is_file())?;
+            VIDEO_EXTENSIONS.contains(&ext.as_str()).then_some((path, meta.len()))
+        })
+        .collect()
+}
+
+/// Start a background thread that imports new videos of the watched folder,
+/// or queues them for confirmation. Nothing is imported during a recording.
+pub fn start_watcher(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut tracker = Tracker::default();
+        loop {
+            std::thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+
+            let state = app.state::<Mutex<AppState>>();
+            let (settings, recording_state) = match state.lock() {
+                Ok(s) => (s.watch_folder.clone(), s.recording_state),
+                Err(_) => continue,
+            };
+            let Some(dir) = settings.path.filter(|p| !p.is_empty()).map(PathBuf::from) else {
+                tracker = Tracker::default();
+                continue;
+            };
+            if recording_state != RecordingState::Idle {
+                continue;
+            }
+
+            let ready = tracker.update(&dir, scan(&dir));
+            if ready.is_empty() {
+                continue;
+            }
+            tracing::info!("{} new video(s) in watched folder {:?}", ready.len(), dir);
+            if settings.confirm {
+                if let Ok(mut s) = state.lock() {
+                    s.watch_pending.extend(ready.iter().map(|p| p.to_string_lossy().to_string()));
+                }
+                events::emit(&app, AppEvent::WatchFolderPending { count: ready.len() });
+            } else {
+                crate::import::handle_drop(app.clone(), ready);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(list: &[(&str, u64)]) -> Vec<(PathBuf, u64)> {
+        list.iter().map(|(p, s)| (PathBuf::from(p), *s)).collect()
+    }
+
+    #[test]
+    fn test_existing_files_are_ignored() {
+        let mut tracker = Tracker::default();
+        let dir = Path::new("obs");
+        assert!(tracker.update(dir, files(&[("obs/old.mkv", 10)])).is_empty());
+        assert!(tracker.update(dir, files(&[("obs/old.mkv", 10)])).is_empty());
+    }
+
+    #[test]
+    fn test_new_file_is_ready_once_its_size_settles() {
+        let mut tracker = Tracker::default();
+        let dir = Path::new("obs");
+        tracker.update(dir, Vec::new());
+        assert!(tracker.update(dir, files(&[("obs/a.mkv", 100)])).is_empty());
+        assert!(tracker.update(dir, files(&[("obs/a.mkv", 500)])).is_empty());
+        assert_eq!(tracker.update(dir, files(&[("obs/a.mkv", 500)])), vec![PathBuf::from("obs/a.mkv")]);
+        // Reported only once
+        assert!(tracker.update(dir, files(&[("obs/a.mkv", 500)])).is_empty());
+    }
+
+    #[test]
+    fn test_changing_folder_starts_over() {
+        let mut tracker = Tracker::default();
+        tracker.update(Path::new("obs"), Vec::new());
+        assert!(tracker.update(Path::new("sharex"), files(&[("sharex/b.mp4", 10)])).is_empty());
+        assert!(tracker.update(Path::new("sharex"), files(&[("sharex/b.mp4", 10)])).is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::types::{ExportFormat, Webhook, WebhookKind};
+use std::path::Path;
+
+/// What just finished, for the webhook message
+pub enum WebhookEvent<'a> {
+    Export {
+        path: &'a Path,
+        format: ExportFormat,
+        duration_ms: u64,
+        project_id: Option<&'a str>,
+    },
+    Upload { path: &'a Path, url: &'a str },
+}
+
+/// Post `event` to every enabled webhook that subscribed to it.
+/// Runs in the background; failures are only logged.
+pub fn notify(webhooks: &[Webhook], event: WebhookEvent<'_>) {
+    let targets: Vec<Webhook> = webhooks.iter()
+        .filter(|w| w.enabled && match event {
+            WebhookEvent::Export { .. } => w.on_export,
+            WebhookEvent::Upload { .. } => w.on_upload,
+        })
+        .cloned()
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+
+    let path = match event {
+        WebhookEvent::Export { path, .. } | WebhookEvent::Upload { path, .. } => path.to_path_buf(),
+    };
+    let message = build_message(&event);
+    let details = build_payload(&event).to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        for hook in &targets {
+            if let Err(e) = send(hook, &message, &details, &path) {
+                tracing::warn!("'{}' failed: {}", hook.name, e);
+            }
+        }
+    });
+}
+
+fn send(hook: &Webhook, message: &str, details: &str, path: &Path) -> Result<(), String> {
+    match hook.kind {
+        WebhookKind::Discord => {
+            let payload = serde_json::json!({ "content": message }).to_string();
+            let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(u64::MAX);
+            if hook.attach_file && size <= hook.max_file_mb as u64 * 1024 * 1024 {
+                let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+                let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                let boundary = format!("clipflow-{}", uuid::Uuid::new_v4().simple());
+                let body = multipart_body(&boundary, &payload, &file_name, &bytes);
+                ureq::post(&hook.url)
+                    .set("Content-Type", &format!("multipart/form-data; boundary={}", boundary))
+                    .send_bytes(&body)
+                    .map_err(|e| e.to_string())?;
+            } else {
+                ureq::post(&hook.url)
+                    .set("Content-Type", "application/json")
+                    .send_string(&payload)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        // Incoming webhooks can't carry files: message only
+        WebhookKind::Slack => {
+            let payload = serde_json::json!({ "text": message }).to_string();
+            ureq::post(&hook.url)
+                .set("Content-Type", "application/json")
+                .send_string(&payload)
+                .map_err(|e| e.to_string())?;
+        }
+        WebhookKind::Generic => {
+            ureq::post(&hook.url)
+                .set("Content-Type", "application/json")
+                .send_string(details)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// JSON body of generic webhooks
+fn build_payload(event: &WebhookEvent<'_>) -> serde_json::Value {
+    match event {
+        WebhookEvent::Export { path, format, duration_ms, project_id } => serde_json::json!({
+            "event": "export",
+            "path": path.to_string_lossy(),
+            "file_name": path.file_name().unwrap_or_default().to_string_lossy(),
+            "size_bytes": std::fs::metadata(path).map(|m| m.len()).ok(),
+            "duration_ms": duration_ms,
+            "format": format,
+            "project_id": project_id,
+        }),
+        WebhookEvent::Upload { path, url } => serde_json::json!({
+            "event": "upload",
+            "path": path.to_string_lossy(),
+            "file_name": path.file_name().unwrap_or_default().to_string_lossy(),
+            "url": url,
+        }),
+    }
+}
+
+fn build_message(event: &WebhookEvent<'_>) -> String {
+    match event {
+        WebhookEvent::Export { path, .. } => {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            match std::fs::metadata(path) {
+                Ok(m) => crate::i18n::tf("webhook_export_size", &[&name, &format!("{:.1}", m.len() as f64 / (1024.0 * 1024.0))]),
+                Err(_) => crate::i18n::tf("webhook_export", &[&name]),
+            }
+        }
+        WebhookEvent::Upload { path, url } => {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            crate::i18n::tf("webhook_upload", &[&name, url])
+        }
+    }
+}
+
+/// Discord expects the message as `payload_json` next to `files[0]`
+fn multipart_body(boundary: &str, payload_json: &str, file_name: &str, bytes: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(bytes.len() + 512);
+    body.extend_from_slice(format!(
+        "--{b}\r\nContent-Disposition: form-data; name=\"payload_json\"\r\nContent-Type: application/json\r\n\r\n{p}\r\n",
+        b = boundary, p = payload_json,
+    ).as_bytes());
+    body.extend_from_slice(format!(
+        "--{b}\r\nContent-Disposition: form-data; name=\"files[0]\"; filename=\"{f}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+        b = boundary, f = file_name.replace('"', "_"),
+    ).as_bytes());
+    body.extend_from_slice(bytes);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multipart_body_layout() {
+        let body = multipart_body("XYZ", "{}", "clip.mp4", b"DATA");
+        let text = String::from_utf8(body).unwrap();
+        assert!(text.starts_with("--XYZ\r\n"));
+        assert!(text.contains("name=\"payload_json\""));
+        assert!(text.contains("filename=\"clip.mp4\"\r\nContent-Type: application/octet-stream\r\n\r\nDATA\r\n"));
+        assert!(text.ends_with("\r\n--XYZ--\r\n"));
+    }
+
+    #[test]
+    fn test_upload_message_contains_url() {
+        let msg = build_message(&WebhookEvent::Upload {
+            path: Path::new("recording.mp4"),
+            url: "https://cdn/x.mp4",
+        });
+        assert!(msg.contains("recording.mp4"));
+        assert!(msg.ends_with("https://cdn/x.mp4"));
+    }
+
+    #[test]
+    fn test_export_payload_describes_the_file() {
+        let payload = build_payload(&WebhookEvent::Export {
+            path: Path::new("missing/recording.gif"),
+            format: ExportFormat::Gif,
+            duration_ms: 4200,
+            project_id: Some("p1"),
+        });
+        assert_eq!(payload["event"], "export");
+        assert_eq!(payload["file_name"], "recording.gif");
+        assert_eq!(payload["format"], "gif");
+        assert_eq!(payload["duration_ms"], 4200);
+        assert_eq!(payload["project_id"], "p1");
+        assert!(payload["size_bytes"].is_null());
+    }
+}
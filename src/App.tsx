@@ -5,17 +5,59 @@ import { StatusIndicator } from "./components/controls/StatusIndicator";
 import { RecordButton } from "./components/controls/RecordButton";
 import { ThemeToggle } from "./components/controls/ThemeToggle";
 import { AudioSourceSelector } from "./components/controls/AudioSourceSelector";
+import { AudioWaveform } from "./components/controls/AudioWaveform";
 import { CountdownSelector } from "./components/controls/CountdownSelector";
+import { MonitorSelector } from "./components/controls/MonitorSelector";
 import { ExportButton } from "./components/export/ExportButton";
+import { ExportHistoryModal } from "./components/export/ExportHistoryModal";
+import { StorageModal } from "./components/storage/StorageModal";
 import { Timeline } from "./components/timeline/Timeline";
 import { VideoPreview } from "./components/preview/VideoPreview";
 import { SaveProjectButton } from "./components/project/SaveProjectButton";
 import { ProjectListModal } from "./components/project/ProjectListModal";
+import { ScriptEditor } from "./components/teleprompter/ScriptEditor";
+import { WatchFolderModal } from "./components/import/WatchFolderModal";
 import { SubtitleEditor } from "./components/subtitles/SubtitleEditor";
+import { AnnotationEditor } from "./components/annotations/AnnotationEditor";
 import { OnboardingOverlay } from "./components/onboarding/OnboardingOverlay";
 import { Logo } from "./components/Logo";
-import type { Region } from "./lib/types";
-import * as api from "./lib/tauri";
+import { getCaptureBackends, getCaptureTiming, getRecordingEncoders, getRecordingStats, onAppEvent, openDownloadPage } from "./lib/tauri";
+import type { BatchExportProgress, CaptureBackendInfo, CaptureBackendKind, CaptureTiming, ExportChunkProgress, ExportFormat, FfmpegDownloadProgress, FollowWindow, NormalizeProgress, PauseTransitionStyle, RecordingEncoder, RecordingEncoderInfo, RecordingStats, Region, VisualizerStyle } from "./lib/types";
+
+/** Window sizes offered in follow-cursor mode, in screen pixels */
+const FOLLOW_SIZES: FollowWindow[] = [
+  { width: 1280, height: 720 },
+  { width: 960, height: 540 },
+  { width: 720, height: 720 },
+];
+
+/** Cycled by the pause transition toggle */
+const PAUSE_TRANSITIONS: { style: PauseTransitionStyle; label: string }[] = [
+  { style: "cut", label: "coupe" },
+  { style: "crossfade", label: "fondu" },
+  { style: "dip", label: "noir" },
+];
+
+const CAPTURE_BACKEND_LABELS: Record<CaptureBackendKind, string> = {
+  gdigrab: "GDI",
+  wgc: "WGC",
+  ddagrab: "DXGI",
+};
+
+const CAPTURE_BACKEND_HINTS: Record<CaptureBackendKind, string> = {
+  gdigrab: "Capture GDI : toute zone du bureau",
+  wgc: "Capture Windows Graphics Capture, un écran à la fois, y compris les fenêtres accélérées (GDI pour une zone à cheval sur deux écrans)",
+  ddagrab: "Capture DXGI sur le GPU, un écran à la fois (GDI pour une zone à cheval sur deux écrans)",
+};
+
+const RECORDING_ENCODER_LABELS: Record<RecordingEncoder, string> = {
+  auto: "Auto",
+  software: "x264",
+  h264_nvenc: "NVENC",
+  hevc_nvenc: "NVENC HEVC",
+  h264_qsv: "QSV",
+  h264_amf: "AMF",
+};
 
 function LiveKeystrokeOverlay() {
   const [keys, setKeys] = useState<{ id: number; key: string; time: number }[]>([]);
@@ -60,6 +102,21 @@ function LiveKeystrokeOverlay() {
   );
 }
 
+function ffmpegDownloadLabel(progress: FfmpegDownloadProgress | null): string {
+  if (!progress) return "Téléchargement FFmpeg...";
+  switch (progress.stage) {
+    case "verifying":
+      return "Vérification FFmpeg...";
+    case "extracting":
+      return "Installation FFmpeg...";
+    case "retrying":
+      return `Nouvelle tentative FFmpeg (${progress.attempt})...`;
+    default:
+      if (progress.total_bytes === 0) return "Téléchargement FFmpeg...";
+      return `Téléchargement FFmpeg ${Math.floor((progress.downloaded_bytes / progress.total_bytes) * 100)}%`;
+  }
+}
+
 function App() {
   const {
     theme,
@@ -89,20 +146,66 @@ function App() {
     exportQuality,
     setExportFormat,
     setExportQuality,
+    exportTargetMb,
+    setExportTargetMb,
+    exportPresets,
+    setExportPresets,
+    exportPreset,
+    setExportPreset,
+    exportEncoder,
+    setExportEncoder,
+    exportFps,
+    setExportFps,
+    exportDownscale,
+    setExportDownscale,
+    hdrTonemap,
+    setHdrTonemap,
+    keepFilterScripts,
+    setKeepFilterScripts,
+    gifPalette,
+    setGifPalette,
+    gifLoop,
+    setGifLoop,
+    exportSidecar,
+    setExportSidecar,
+    exportChapters,
+    setExportChapters,
+    subtitleExport,
+    setSubtitleExport,
+    exportChunkMinutes,
+    setExportChunkMinutes,
+    exportChunk,
+    exportRange,
+    setExportRange,
+    postExport,
+    setPostExport,
     refreshState,
     startRecording,
     stopRecording,
     pauseRecording,
     resumeRecording,
     cancelRecording,
+    micMuted,
+    toggleMicMute,
     openRegionSelector,
+    openSecondaryRegionSelector,
+    secondaryRegion,
+    setSecondaryRegion,
+    captureMonitors,
+    setCaptureMonitors,
     setCaptureRegion,
     setAudioSource,
     clearRegion,
     updateDuration,
     exportVideo,
+    exportVideoMulti,
+    exportClipsSeparately,
+    exportAudioVisualizer,
     setExportProgress,
     ensureFfmpeg,
+    ffmpegDownload,
+    updateInfo,
+    checkForUpdates,
     countdownSeconds,
     countdownActive,
     countdownRemaining,
@@ -111,7 +214,42 @@ function App() {
     toggleKeystroke,
     cursorZoomEnabled,
     toggleCursorZoom,
+    gamepadEnabled,
+    toggleGamepad,
+    liveDrawing,
+    setLiveDrawing,
+    keystrokePreview,
+    setKeystrokePreview,
+    followCursor,
+    setFollowCursor,
+    followFocus,
+    setFollowFocus,
+    teleprompter,
+    setTeleprompter,
+    pauseTransition,
+    setPauseTransition,
+    captureBackend,
+    setCaptureBackend,
+    recordingEncoder,
+    setRecordingEncoder,
+    adaptiveCapture,
+    setAdaptiveCapture,
+    captureRate,
+    setCaptureRate,
+    audioFormat,
+    setAudioFormat,
+    watchFolder,
+    setWatchFolder,
+    watchPending,
+    refreshWatchPending,
+    resolveWatchPending,
+    cursorSidecar,
+    setCursorSidecar,
+    telemetry,
+    setTelemetry,
     copyToClipboard,
+    revealFile,
+    openInPlayer,
     saveProject,
     loadProject,
     listProjects,
@@ -124,22 +262,43 @@ function App() {
     showOnboarding,
     selectedMic,
     setSelectedMic,
+    selectedOutput,
+    setSelectedOutput,
     systemVolume,
     micVolume,
     setSystemVolume,
     setMicVolume,
+    autoTrimClips,
   } = useAppStore();
 
   const timerRef = useRef<ReturnType<typeof setInterval> | null>(null);
-  const countdownRef = useRef<ReturnType<typeof setInterval> | null>(null);
   const [showProjectList, setShowProjectList] = useState(false);
+  const [showExportHistory, setShowExportHistory] = useState(false);
   const [showSubtitles, setShowSubtitles] = useState(false);
+  const [showGlobalAnnotations, setShowGlobalAnnotations] = useState(false);
+  const [showScriptEditor, setShowScriptEditor] = useState(false);
+  const [showWatchFolder, setShowWatchFolder] = useState(false);
+  const [showStorage, setShowStorage] = useState(false);
+  const [autoTrimming, setAutoTrimming] = useState(false);
+  const [captureBackends, setCaptureBackends] = useState<CaptureBackendInfo[]>([]);
+  const [recordingEncoders, setRecordingEncoders] = useState<RecordingEncoderInfo[]>([]);
+  // What "auto" records with
+  const autoEncoder = recordingEncoders.find((e) => e.auto)?.kind;
+  const [normalizing, setNormalizing] = useState<NormalizeProgress | null>(null);
+  // Level the adaptive mode lightened the capture to
+  const [adaptedLevel, setAdaptedLevel] = useState<number | null>(null);
+  // Refresh rate of the monitor to record and the capture rates dividing it
+  const [captureTiming, setCaptureTiming] = useState<CaptureTiming | null>(null);
+  const [recordingStats, setRecordingStats] = useState<RecordingStats | null>(null);
 
   // Init
   useEffect(() => {
     ensureFfmpeg();
+    checkForUpdates();
     refreshState().catch(console.error);
     listProjects().catch(console.error);
+    getCaptureBackends().then(setCaptureBackends).catch(console.error);
+    getRecordingEncoders().then(setRecordingEncoders).catch(console.error);
   }, []);
 
   // Listen for region-selected event from overlay window
@@ -152,13 +311,43 @@ function App() {
     };
   }, []);
 
+  // The overlay already handed the second region to the backend
+  useEffect(() => {
+    const unlisten = listen<Region>("secondary-region-selected", (event) => {
+      useAppStore.setState({ secondaryRegion: event.payload });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // FFmpeg first-run download progress
+  useEffect(() => {
+    const unlisten = listen<FfmpegDownloadProgress>("ffmpeg-download-progress", (event) => {
+      useAppStore.setState({ ffmpegDownload: event.payload });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // Listen for export progress events
   useEffect(() => {
     const unlisten = listen<number>("export-progress", (event) => {
-      setExportProgress(event.payload);
+      // Batch exports report their combined progress separately
+      if (!useAppStore.getState().exportBatch) setExportProgress(event.payload);
+    });
+    const unlistenBatch = listen<BatchExportProgress>("batch-export-progress", (event) => {
+      setExportProgress(event.payload.percent);
+    });
+    const unlistenChunked = listen<ExportChunkProgress>("chunked-export-progress", (event) => {
+      setExportProgress(event.payload.percent);
+      useAppStore.setState({ exportChunk: event.payload });
     });
     return () => {
       unlisten.then((fn) => fn());
+      unlistenBatch.then((fn) => fn());
+      unlistenChunked.then((fn) => fn());
     };
   }, []);
 
@@ -172,13 +361,54 @@ function App() {
     };
   }, []);
 
-  // Listen for hotkey-triggered state changes
+  // Recording state changed (hotkeys, watchers, rollover) or a clip was
+  // recorded / imported / converted
   useEffect(() => {
-    const unlisten = listen<string>("recording-state-changed", () => {
+    const refresh = () => refreshState().catch(console.error);
+    const unlistenRecording = (["recording-started", "recording-paused", "recording-resumed", "recording-stopped", "recording-cancelled"] as const)
+      .map((name) => onAppEvent(name, refresh));
+    const unlistenError = onAppEvent("recording-error", (event) => {
+      console.error("Recording failed:", event.message);
+      refresh();
+    });
+    const unlistenClip = (["clip-added", "clip-normalized"] as const).map((name) => onAppEvent(name, refresh));
+    const unlistenAdapted = onAppEvent("capture-adapted", (event) => {
+      setAdaptedLevel(event.level > 0 ? event.level : null);
+    });
+    const unlistenAdaptedReset = (["recording-started", "recording-stopped", "recording-cancelled"] as const)
+      .map((name) => onAppEvent(name, () => setAdaptedLevel(null)));
+    // Imports and captures in another format are converted before joining the timeline
+    const unlistenNormalize = listen<NormalizeProgress>("normalize-progress", (event) => {
+      setNormalizing(event.payload.percent < 100 ? event.payload : null);
+    });
+    // A project was opened
+    const unlistenProject = onAppEvent("project-opened", (event) => {
+      useAppStore.setState({ currentProjectId: event.project_id });
       refreshState().catch(console.error);
+      listProjects().catch(console.error);
+    });
+    const unlistenImportError = onAppEvent("import-error", (event) => {
+      console.error("Import failed:", event.message);
+    });
+    // F6 toggles the mic mute without focusing the window
+    const unlistenMute = onAppEvent("mic-mute-changed", (event) => {
+      useAppStore.setState({ micMuted: event.muted });
+    });
+    // New videos in the watched folder wait for confirmation
+    const unlistenWatch = onAppEvent("watch-folder-pending", () => {
+      refreshWatchPending().catch(console.error);
     });
     return () => {
-      unlisten.then((fn) => fn());
+      unlistenRecording.forEach((u) => u.then((fn) => fn()));
+      unlistenError.then((fn) => fn());
+      unlistenClip.forEach((u) => u.then((fn) => fn()));
+      unlistenAdapted.then((fn) => fn());
+      unlistenAdaptedReset.forEach((u) => u.then((fn) => fn()));
+      unlistenNormalize.then((fn) => fn());
+      unlistenProject.then((fn) => fn());
+      unlistenImportError.then((fn) => fn());
+      unlistenWatch.then((fn) => fn());
+      unlistenMute.then((fn) => fn());
     };
   }, []);
 
@@ -198,28 +428,40 @@ function App() {
     };
   }, [recordingState]);
 
-  // Countdown timer
+  // Frame rate achieved by the capture, next to the one asked
   useEffect(() => {
-    if (countdownActive && countdownRemaining > 0) {
-      countdownRef.current = setInterval(() => {
-        const store = useAppStore.getState();
-        const remaining = store.countdownRemaining - 1;
-        if (remaining <= 0) {
-          useAppStore.setState({ countdownActive: false, countdownRemaining: 0 });
-          if (countdownRef.current) clearInterval(countdownRef.current);
-          // Actually start recording
-          api.startRecording().then(() => {
-            useAppStore.setState({ recordingState: "recording", durationMs: 0 });
-          }).catch(console.error);
-        } else {
-          useAppStore.setState({ countdownRemaining: remaining });
-        }
-      }, 1000);
+    if (recordingState !== "recording") {
+      setRecordingStats(null);
+      return;
     }
+    const timer = setInterval(() => {
+      getRecordingStats().then(setRecordingStats).catch(() => setRecordingStats(null));
+    }, 2000);
+    return () => clearInterval(timer);
+  }, [recordingState]);
+
+  // The monitor to record may have changed with the region
+  useEffect(() => {
+    if (recordingState !== "idle") return;
+    getCaptureTiming().then(setCaptureTiming).catch(() => setCaptureTiming(null));
+  }, [recordingState, currentRegion, captureMonitors]);
+
+  // Countdown ticks from the backend (F9 or record button)
+  useEffect(() => {
+    const unlistenTick = onAppEvent("countdown-tick", (event) => {
+      useAppStore.setState({
+        countdownActive: event.remaining > 0,
+        countdownRemaining: event.remaining,
+      });
+    });
+    const unlistenAbort = onAppEvent("countdown-aborted", () => {
+      useAppStore.setState({ countdownActive: false, countdownRemaining: 0 });
+    });
     return () => {
-      if (countdownRef.current) clearInterval(countdownRef.current);
+      unlistenTick.then((fn) => fn());
+      unlistenAbort.then((fn) => fn());
     };
-  }, [countdownActive]);
+  }, []);
 
   const handleRecord = useCallback(async () => {
     if (recordingState === "idle") {
@@ -251,6 +493,46 @@ function App() {
     }
   };
 
+  const handleExportMulti = async (formats: ExportFormat[]) => {
+    try {
+      await exportVideoMulti(formats.map((format) => ({
+        format,
+        quality: exportQuality,
+        target_size_mb: format === "gif" ? null : exportTargetMb,
+        preset: null,
+      })));
+    } catch (e) {
+      console.error("Batch export failed:", e);
+    }
+  };
+
+  const handleExportEach = async () => {
+    try {
+      await exportClipsSeparately();
+    } catch (e) {
+      console.error("Per-clip export failed:", e);
+    }
+  };
+
+  const handleExportVisualizer = async (style: VisualizerStyle, title: string | null) => {
+    try {
+      await exportAudioVisualizer(style, title);
+    } catch (e) {
+      console.error("Audio visualizer export failed:", e);
+    }
+  };
+
+  const handleAutoTrim = async () => {
+    setAutoTrimming(true);
+    try {
+      await autoTrimClips();
+    } catch (e) {
+      console.error("Auto trim failed:", e);
+    } finally {
+      setAutoTrimming(false);
+    }
+  };
+
   const handlePreview = async () => {
     try {
       await previewVideo();
@@ -303,9 +585,10 @@ function App() {
               {formatDuration(durationMs)}
             </span>
           )}
+          {recordingState !== "idle" && audioSource !== "none" && <AudioWaveform />}
 
           {/* Region indicator */}
-          {currentRegion && recordingState === "idle" && !exporting && (
+          {captureMonitors.length === 0 && currentRegion && recordingState === "idle" && !exporting && (
             <div className="ml-auto flex items-center gap-2 text-xs text-zinc-500 dark:text-zinc-400">
               <span className="font-mono">
                 {currentRegion.width}x{currentRegion.height}
@@ -323,13 +606,56 @@ function App() {
             </div>
           )}
 
-          {!currentRegion && recordingState === "idle" && !exporting && (
+          {captureMonitors.length === 0 && !currentRegion && recordingState === "idle" && !exporting && (
             <span className="ml-auto text-xs text-zinc-400 dark:text-zinc-600">Écran entier</span>
           )}
 
+          {captureMonitors.length > 0 && recordingState === "idle" && !exporting && (
+            <span className="ml-auto text-xs text-zinc-400 dark:text-zinc-600">
+              {captureMonitors.length > 1 ? `${captureMonitors.length} écrans, un clip chacun` : "Écran sélectionné"}
+            </span>
+          )}
+
+          {secondaryRegion && recordingState === "idle" && !exporting && (
+            <div className="flex items-center gap-2 text-xs text-zinc-500 dark:text-zinc-400">
+              <span className="font-mono">+ {secondaryRegion.width}x{secondaryRegion.height}</span>
+              <button
+                onClick={() => setSecondaryRegion(null)}
+                className="text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors"
+                title="Ne plus enregistrer de seconde zone"
+              >
+                ✕
+              </button>
+            </div>
+          )}
+
+          {adaptedLevel !== null && recordingState !== "idle" && (
+            <span
+              className="text-xs text-yellow-600 dark:text-yellow-500"
+              title="Processeur très sollicité : la capture est allégée pour ne pas ralentir les applications"
+            >
+              Capture allégée · niveau {adaptedLevel}
+            </span>
+          )}
+
+          {recordingStats?.achieved_fps != null && (
+            <span
+              className={`text-xs font-mono tabular-nums ${recordingStats.achieved_fps < recordingStats.target_fps - 1 ? "text-yellow-600 dark:text-yellow-500" : "text-zinc-400 dark:text-zinc-600"}`}
+              title={recordingStats.refresh_rate ? `Écran à ${recordingStats.refresh_rate} Hz` : undefined}
+            >
+              {recordingStats.achieved_fps.toFixed(0)} / {recordingStats.target_fps} i/s
+            </span>
+          )}
+
+          {normalizing && (
+            <span className="text-xs text-zinc-500 dark:text-zinc-400 animate-pulse" title={normalizing.name}>
+              Conversion {normalizing.percent}%
+            </span>
+          )}
+
           {!ffmpegReady && !ffmpegError && (
             <span className="text-xs text-yellow-600 dark:text-yellow-500 animate-pulse">
-              Téléchargement FFmpeg...
+              {ffmpegDownloadLabel(ffmpegDownload)}
             </span>
           )}
           {ffmpegError && (
@@ -342,6 +668,16 @@ function App() {
             </button>
           )}
 
+          {updateInfo && (
+            <button
+              className="text-xs text-blue-500 hover:text-blue-400 underline"
+              onClick={() => openDownloadPage(updateInfo.download_url).catch(console.error)}
+              title={updateInfo.changelog}
+            >
+              Mise à jour {updateInfo.latest_version} disponible
+            </button>
+          )}
+
           <ThemeToggle />
         </header>
 
@@ -399,6 +735,29 @@ function App() {
           </div>
         )}
 
+        {/* Videos found in the watched folder */}
+        {watchPending.length > 0 && (
+          <div className="mx-6 mb-2 px-4 py-2.5 bg-blue-50 dark:bg-blue-900/40 border border-blue-200 dark:border-blue-700/50 rounded-lg flex items-center gap-3 animate-fade-in">
+            <span className="text-blue-700 dark:text-blue-300 text-sm flex-1">
+              {watchPending.length > 1
+                ? `${watchPending.length} nouvelles vidéos dans le dossier surveillé`
+                : `Nouvelle vidéo : ${extractFilename(watchPending[0])}`}
+            </span>
+            <button
+              onClick={() => resolveWatchPending(true).catch(console.error)}
+              className="px-2.5 py-1 rounded-md text-xs font-medium bg-blue-600 hover:bg-blue-500 text-white transition-colors"
+            >
+              Importer
+            </button>
+            <button
+              onClick={() => resolveWatchPending(false).catch(console.error)}
+              className="text-xs text-blue-400 dark:text-blue-600 hover:text-blue-600 dark:hover:text-blue-400 transition-colors"
+            >
+              Ignorer
+            </button>
+          </div>
+        )}
+
         {/* Export error */}
         {exportError && (
           <div className="mx-6 mb-2 px-4 py-2.5 bg-red-50 dark:bg-red-900/40 border border-red-200 dark:border-red-700/50 rounded-lg flex items-center gap-3 animate-fade-in">
@@ -407,7 +766,7 @@ function App() {
               <line x1="15" y1="9" x2="9" y2="15" />
               <line x1="9" y1="9" x2="15" y2="15" />
             </svg>
-            <span className="text-red-700 dark:text-red-300 text-sm flex-1">{exportError}</span>
+            <span className="text-red-700 dark:text-red-300 text-sm flex-1 whitespace-pre-line">{exportError}</span>
             <button
               onClick={clearExportError}
               className="text-red-400 dark:text-red-600 hover:text-red-600 dark:hover:text-red-400 transition-colors"
@@ -445,6 +804,14 @@ function App() {
           >
             Nouvelle Zone
           </button>
+          <button
+            onClick={openSecondaryRegionSelector}
+            disabled={recordingState !== "idle" || exporting}
+            className="px-4 py-2.5 bg-zinc-100 dark:bg-zinc-800 hover:bg-zinc-200 dark:hover:bg-zinc-700 disabled:opacity-40 disabled:cursor-not-allowed rounded-lg text-sm font-medium transition-colors"
+            title="Enregistrer une seconde zone en même temps (terminal + navigateur…)"
+          >
+            Seconde Zone
+          </button>
           <div data-onboarding-record>
             <RecordButton
               state={recordingState}
@@ -481,6 +848,8 @@ function App() {
                 onChange={setAudioSource}
                 selectedMic={selectedMic}
                 onMicChange={setSelectedMic}
+                selectedOutput={selectedOutput}
+                onOutputChange={setSelectedOutput}
                 disabled={recordingState !== "idle"}
                 systemVolume={systemVolume}
                 micVolume={micVolume}
@@ -498,6 +867,15 @@ function App() {
               />
             )}
 
+            {/* Monitors recorded at once */}
+            {recordingState === "idle" && !exporting && (
+              <MonitorSelector
+                selected={captureMonitors}
+                onChange={setCaptureMonitors}
+                disabled={recordingState !== "idle"}
+              />
+            )}
+
             {/* Subtitles button */}
             {clips.length > 0 && recordingState === "idle" && !exporting && (
               <button
@@ -514,6 +892,33 @@ function App() {
               </button>
             )}
 
+            {/* Annotations over the whole video: logo, banner */}
+            {clips.length > 0 && recordingState === "idle" && !exporting && (
+              <button
+                onClick={() => setShowGlobalAnnotations(true)}
+                className="px-2.5 py-1.5 bg-zinc-100 dark:bg-zinc-800 hover:bg-zinc-200 dark:hover:bg-zinc-700 rounded-lg text-xs font-medium transition-colors flex items-center gap-1.5"
+                title="Annotations sur toute la vidéo (logo, bandeau)"
+              >
+                <svg className="w-3.5 h-3.5" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+                  <rect x="2" y="4" width="20" height="16" rx="2" />
+                  <rect x="5" y="7" width="6" height="4" />
+                </svg>
+                Calques
+              </button>
+            )}
+
+            {/* Cut the fumbling before and after every take */}
+            {clips.length > 0 && recordingState === "idle" && !exporting && (
+              <button
+                onClick={handleAutoTrim}
+                disabled={autoTrimming}
+                className="px-2.5 py-1.5 bg-zinc-100 dark:bg-zinc-800 hover:bg-zinc-200 dark:hover:bg-zinc-700 disabled:opacity-50 rounded-lg text-xs font-medium transition-colors"
+                title="Couper le silence et l'inactivité au début et à la fin de chaque clip"
+              >
+                {autoTrimming ? "Analyse…" : "Auto-trim"}
+              </button>
+            )}
+
             {/* Preview button */}
             <button
               onClick={handlePreview}
@@ -544,9 +949,50 @@ function App() {
               exportQuality={exportQuality}
               exportSuccess={exportSuccess}
               onExport={handleExport}
+              onExportMulti={handleExportMulti}
+              onExportEach={handleExportEach}
+              onExportVisualizer={handleExportVisualizer}
               onFormatChange={setExportFormat}
               onQualityChange={setExportQuality}
+              exportTargetMb={exportTargetMb}
+              onTargetMbChange={setExportTargetMb}
+              watermark={watermarkEnabled}
+              presets={exportPresets}
+              selectedPreset={exportPreset}
+              onPresetSelect={setExportPreset}
+              onPresetsChange={setExportPresets}
+              exportEncoder={exportEncoder}
+              onEncoderChange={setExportEncoder}
+              exportFps={exportFps}
+              onFpsChange={setExportFps}
+              exportDownscale={exportDownscale}
+              onDownscaleChange={setExportDownscale}
+              hasHdrClips={clips.some((c) => c.hdr)}
+              hdrTonemap={hdrTonemap}
+              onHdrTonemapChange={setHdrTonemap}
+              keepFilterScripts={keepFilterScripts}
+              onKeepFilterScriptsChange={setKeepFilterScripts}
+              gifPalette={gifPalette}
+              onGifPaletteChange={setGifPalette}
+              gifLoop={gifLoop}
+              onGifLoopChange={setGifLoop}
+              exportSidecar={exportSidecar}
+              onExportSidecarChange={setExportSidecar}
+              exportChapters={exportChapters}
+              onExportChaptersChange={setExportChapters}
+              subtitleExport={subtitleExport}
+              onSubtitleExportChange={setSubtitleExport}
+              exportChunkMinutes={exportChunkMinutes}
+              onExportChunkMinutesChange={setExportChunkMinutes}
+              exportChunk={exportChunk}
+              exportRange={exportRange}
+              onRangeChange={setExportRange}
+              timelineDurationMs={totalDurationMs}
+              postExport={postExport}
+              onPostExportChange={setPostExport}
               onCopyToClipboard={copyToClipboard}
+              onRevealFile={revealFile}
+              onOpenInPlayer={openInPlayer}
             />
             {!exporting && recordingState === "idle" && (
               <div className="text-[9px] text-zinc-400 dark:text-zinc-600 flex gap-1.5">
@@ -570,6 +1016,27 @@ function App() {
                   F10
                 </kbd>
                 <span>{recordingState === "paused" ? "Repr." : "Pause"}</span>
+                {(audioSource === "microphone" || audioSource === "both") && (
+                  <>
+                    <kbd className="px-1 py-0.5 bg-zinc-100 dark:bg-zinc-900 border border-zinc-200 dark:border-zinc-800 rounded text-zinc-500 font-mono">
+                      F6
+                    </kbd>
+                    <button
+                      onClick={() => toggleMicMute().catch(console.error)}
+                      className={micMuted ? "text-red-500" : "hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors"}
+                    >
+                      {micMuted ? "Micro coupé" : "Micro"}
+                    </button>
+                  </>
+                )}
+                {liveDrawing && (
+                  <>
+                    <kbd className="px-1 py-0.5 bg-zinc-100 dark:bg-zinc-900 border border-zinc-200 dark:border-zinc-800 rounded text-zinc-500 font-mono">
+                      F8
+                    </kbd>
+                    <span>Stylo</span>
+                  </>
+                )}
                 <kbd className="px-1 py-0.5 bg-zinc-100 dark:bg-zinc-900 border border-zinc-200 dark:border-zinc-800 rounded text-zinc-500 font-mono">
                   ESC
                 </kbd>
@@ -604,6 +1071,19 @@ function App() {
               <span>Touches</span>
             </button>
 
+            {/* Keystroke labels over the recorded area while recording */}
+            {keystrokeEnabled && (
+              <button
+                onClick={() => setKeystrokePreview(!keystrokePreview)}
+                disabled={recordingState !== "idle"}
+                className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors disabled:opacity-50"
+                title="Affiche les touches à l'écran pendant l'enregistrement, là où elles apparaîtront dans la vidéo (invisible dans la capture)"
+              >
+                <div className={`w-1.5 h-1.5 rounded-full ${keystrokePreview ? "bg-blue-500" : "bg-zinc-300 dark:bg-zinc-700"}`} />
+                <span>Aperçu des touches</span>
+              </button>
+            )}
+
             {/* Cursor zoom toggle */}
             <button
               onClick={toggleCursorZoom}
@@ -615,15 +1095,237 @@ function App() {
               <span>Auto-Zoom</span>
             </button>
 
+            {/* Controller overlay toggle */}
+            <button
+              onClick={toggleGamepad}
+              className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors"
+            >
+              <div className={`w-7 h-4 rounded-full transition-colors flex items-center ${gamepadEnabled ? "bg-blue-500 justify-end" : "bg-zinc-300 dark:bg-zinc-700 justify-start"}`}>
+                <div className="w-3 h-3 rounded-full bg-white mx-0.5 shadow-sm" />
+              </div>
+              <span>Manette</span>
+            </button>
+
+            {/* Live drawing overlay toggle */}
+            <button
+              onClick={() => setLiveDrawing(!liveDrawing)}
+              className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors"
+              title="F8 stylo, F7 surligneur pendant l'enregistrement"
+            >
+              <div className={`w-7 h-4 rounded-full transition-colors flex items-center ${liveDrawing ? "bg-blue-500 justify-end" : "bg-zinc-300 dark:bg-zinc-700 justify-start"}`}>
+                <div className="w-3 h-3 rounded-full bg-white mx-0.5 shadow-sm" />
+              </div>
+              <span>Dessin</span>
+            </button>
+
+            {/* Teleprompter script, scrolled during recordings */}
+            <button
+              onClick={() => setShowScriptEditor(true)}
+              disabled={recordingState !== "idle"}
+              className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors disabled:opacity-50"
+              title="Texte qui défile pendant l'enregistrement, invisible dans la vidéo"
+            >
+              <div className={`w-1.5 h-1.5 rounded-full ${teleprompter ? "bg-blue-500" : "bg-zinc-300 dark:bg-zinc-700"}`} />
+              <span>Prompteur</span>
+            </button>
+
+            {/* Transition drawn where the recording was paused */}
+            <button
+              onClick={() => {
+                const i = PAUSE_TRANSITIONS.findIndex((t) => t.style === pauseTransition.style);
+                setPauseTransition({ ...pauseTransition, style: PAUSE_TRANSITIONS[(i + 1) % PAUSE_TRANSITIONS.length].style });
+              }}
+              disabled={recordingState !== "idle"}
+              className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors disabled:opacity-50"
+              title="Raccord entre les segments d'un enregistrement mis en pause"
+            >
+              <div className={`w-1.5 h-1.5 rounded-full ${pauseTransition.style !== "cut" ? "bg-blue-500" : "bg-zinc-300 dark:bg-zinc-700"}`} />
+              <span>Pause : {PAUSE_TRANSITIONS.find((t) => t.style === pauseTransition.style)?.label}</span>
+            </button>
+
+            {/* Screen grab, cycled among those the installed FFmpeg supports */}
+            {captureBackends.filter((b) => b.available).length > 1 && (
+              <button
+                onClick={() => {
+                  const usable = captureBackends.filter((b) => b.available);
+                  const i = usable.findIndex((b) => b.kind === captureBackend);
+                  setCaptureBackend(usable[(i + 1) % usable.length].kind);
+                }}
+                disabled={recordingState !== "idle"}
+                className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors disabled:opacity-50"
+                title={CAPTURE_BACKEND_HINTS[captureBackend]}
+              >
+                <div className={`w-1.5 h-1.5 rounded-full ${captureBackend !== "gdigrab" ? "bg-blue-500" : "bg-zinc-300 dark:bg-zinc-700"}`} />
+                <span>Capture : {CAPTURE_BACKEND_LABELS[captureBackend]}</span>
+              </button>
+            )}
+
+            {/* Capture encoder, cycled among those that work here */}
+            {recordingEncoders.filter((e) => e.available).length > 2 && (
+              <button
+                onClick={() => {
+                  const usable = recordingEncoders.filter((e) => e.available);
+                  const i = usable.findIndex((e) => e.kind === recordingEncoder);
+                  setRecordingEncoder(usable[(i + 1) % usable.length].kind);
+                }}
+                disabled={recordingState !== "idle"}
+                className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors disabled:opacity-50"
+                title="Encodage de la capture : sur la carte graphique, il laisse le processeur au jeu ou à l'application filmée"
+              >
+                <div className={`w-1.5 h-1.5 rounded-full ${recordingEncoder !== "software" ? "bg-blue-500" : "bg-zinc-300 dark:bg-zinc-700"}`} />
+                <span>
+                  Encodeur : {RECORDING_ENCODER_LABELS[recordingEncoder]}
+                  {recordingEncoder === "auto" && autoEncoder && ` (${RECORDING_ENCODER_LABELS[autoEncoder]})`}
+                </span>
+              </button>
+            )}
+
+            {/* Lighter capture settings while the CPU is busy */}
+            <button
+              onClick={() => setAdaptiveCapture({ ...adaptiveCapture, enabled: !adaptiveCapture.enabled })}
+              disabled={recordingState !== "idle"}
+              className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors disabled:opacity-50"
+              title={`Baisse la qualité de la capture quand le processeur dépasse ${adaptiveCapture.cpu_threshold}%`}
+            >
+              <div className={`w-1.5 h-1.5 rounded-full ${adaptiveCapture.enabled ? "bg-blue-500" : "bg-zinc-300 dark:bg-zinc-700"}`} />
+              <span>Capture adaptative</span>
+            </button>
+
+            {/* Capture at a divisor of the monitor refresh rate, no judder */}
+            {captureTiming && captureTiming.options.length > 0 && (
+              <button
+                onClick={() => {
+                  const divisors = captureTiming.options.map((o) => o.divisor);
+                  const current = captureRate === "default" ? -1 : divisors.indexOf(captureRate.refresh_divisor);
+                  const next = current + 1;
+                  setCaptureRate(next < divisors.length ? { refresh_divisor: divisors[next] } : "default");
+                }}
+                disabled={recordingState !== "idle"}
+                className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors disabled:opacity-50"
+                title={`Écran à ${captureTiming.refresh_rate} Hz : une cadence qui le divise évite les saccades`}
+              >
+                <div className={`w-1.5 h-1.5 rounded-full ${captureRate !== "default" ? "bg-blue-500" : "bg-zinc-300 dark:bg-zinc-700"}`} />
+                <span>
+                  Cadence : {captureRate === "default"
+                    ? "30 i/s"
+                    : `${captureTiming.options.find((o) => o.divisor === captureRate.refresh_divisor)?.fps ?? 30} i/s`}
+                </span>
+              </button>
+            )}
+
+            {/* Tracks at the device rate as float, or resampled to 48 kHz 16-bit */}
+            {audioSource !== "none" && (
+              <button
+                onClick={() => setAudioFormat(audioFormat === "canonical" ? "native" : "canonical")}
+                disabled={recordingState !== "idle"}
+                className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors disabled:opacity-50"
+                title={audioFormat === "canonical"
+                  ? "Audio enregistré en 48 kHz 16 bits : fichiers plus légers, export plus fiable"
+                  : "Audio enregistré au format du périphérique (32 bits flottants)"}
+              >
+                <div className={`w-1.5 h-1.5 rounded-full ${audioFormat === "canonical" ? "bg-blue-500" : "bg-zinc-300 dark:bg-zinc-700"}`} />
+                <span>Audio : {audioFormat === "canonical" ? "48 kHz 16 bits" : "natif"}</span>
+              </button>
+            )}
+
+            {/* New videos of another capture tool's folder become clips */}
+            <button
+              onClick={() => setShowWatchFolder(true)}
+              className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors"
+              title={watchFolder.path ?? "Importe les nouvelles vidéos d'un dossier (OBS, ShareX…)"}
+            >
+              <div className={`w-1.5 h-1.5 rounded-full ${watchFolder.path ? "bg-blue-500" : "bg-zinc-300 dark:bg-zinc-700"}`} />
+              <span>Dossier surveillé</span>
+            </button>
+
+            {/* Follow-cursor mode: full screen recorded, window cropped around the cursor */}
+            <div className="flex items-center gap-2">
+              <button
+                onClick={() => setFollowCursor(followCursor ? null : FOLLOW_SIZES[0])}
+                className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors"
+                title="Enregistre l'écran entier et exporte une fenêtre qui suit la souris"
+              >
+                <div className={`w-7 h-4 rounded-full transition-colors flex items-center ${followCursor ? "bg-blue-500 justify-end" : "bg-zinc-300 dark:bg-zinc-700 justify-start"}`}>
+                  <div className="w-3 h-3 rounded-full bg-white mx-0.5 shadow-sm" />
+                </div>
+                <span>Suivre la souris</span>
+              </button>
+              {followCursor && (
+                <select
+                  value={`${followCursor.width}x${followCursor.height}`}
+                  onChange={(e) => {
+                    const [width, height] = e.target.value.split("x").map(Number);
+                    setFollowCursor({ width, height });
+                  }}
+                  disabled={recordingState !== "idle"}
+                  className="px-1.5 py-1 rounded text-[10px] bg-zinc-100 dark:bg-zinc-800 text-zinc-700 dark:text-zinc-300 border border-zinc-300 dark:border-zinc-600"
+                >
+                  {FOLLOW_SIZES.map((s) => (
+                    <option key={`${s.width}x${s.height}`} value={`${s.width}x${s.height}`}>
+                      {s.width}x{s.height}
+                    </option>
+                  ))}
+                </select>
+              )}
+            </div>
+
+            {/* Follow-focus mode: full screen recorded, export framed on the active window */}
+            <button
+              onClick={() => setFollowFocus(!followFocus)}
+              className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors"
+              title="Enregistre l'écran entier et cadre l'export sur la fenêtre active, qui change quand le focus passe à une autre fenêtre"
+            >
+              <div className={`w-7 h-4 rounded-full transition-colors flex items-center ${followFocus ? "bg-blue-500 justify-end" : "bg-zinc-300 dark:bg-zinc-700 justify-start"}`}>
+                <div className="w-3 h-3 rounded-full bg-white mx-0.5 shadow-sm" />
+              </div>
+              <span>Suivre la fenêtre</span>
+            </button>
+
+            {/* Anonymous usage metrics (opt-in) */}
+            <button
+              onClick={() => setTelemetry({ ...telemetry, enabled: !telemetry.enabled })}
+              className="flex items-center gap-2 text-xs text-zinc-400 dark:text-zinc-600 hover:text-zinc-600 dark:hover:text-zinc-400 transition-colors"
+              title="Envoie des statistiques anonymes (nombre d'exports, format, durée approximative)"
+            >
+              <div className={`w-7 h-4 rounded-full transition-colors flex items-center ${telemetry.enabled ? "bg-blue-500 justify-end" : "bg-zinc-300 dark:bg-zinc-700 justify-start"}`}>
+                <div className="w-3 h-3 rounded-full bg-white mx-0.5 shadow-sm" />
+              </div>
+              <span>Statistiques</span>
+            </button>
+
             {/* Project save */}
             {recordingState === "idle" && !exporting && (
               <SaveProjectButton
                 onSave={saveProject}
+                cursorSidecar={cursorSidecar}
+                onCursorSidecarChange={setCursorSidecar}
                 onListOpen={() => { listProjects(); setShowProjectList(true); }}
                 hasClips={clips.length > 0}
                 disabled={recordingState !== "idle"}
               />
             )}
+
+            {/* Earlier exports back on the timeline */}
+            {recordingState === "idle" && !exporting && (
+              <button
+                onClick={() => setShowExportHistory(true)}
+                className="px-2.5 py-1.5 bg-zinc-100 dark:bg-zinc-800 hover:bg-zinc-200 dark:hover:bg-zinc-700 rounded-lg text-xs font-medium transition-colors"
+                title="Ajouter une vidéo déjà exportée à la timeline"
+              >
+                Exports
+              </button>
+            )}
+
+            {/* Disk usage of temp captures and caches */}
+            {recordingState === "idle" && !exporting && (
+              <button
+                onClick={() => setShowStorage(true)}
+                className="px-2.5 py-1.5 bg-zinc-100 dark:bg-zinc-800 hover:bg-zinc-200 dark:hover:bg-zinc-700 rounded-lg text-xs font-medium transition-colors"
+                title="Espace utilisé par les fichiers temporaires"
+              >
+                Stockage
+              </button>
+            )}
           </div>
 
           <div className="flex items-center gap-3">
@@ -669,6 +1371,11 @@ function App() {
         />
       )}
 
+      {/* Export History Modal */}
+      {showExportHistory && (
+        <ExportHistoryModal onClose={() => setShowExportHistory(false)} />
+      )}
+
       {/* Subtitle Editor Modal */}
       {showSubtitles && (
         <SubtitleEditor
@@ -677,6 +1384,38 @@ function App() {
         />
       )}
 
+      {/* Timeline Annotations Modal */}
+      {showGlobalAnnotations && clips.length > 0 && (
+        <AnnotationEditor
+          clip={clips[0]}
+          timelineDurationMs={totalDurationMs}
+          onClose={() => setShowGlobalAnnotations(false)}
+        />
+      )}
+
+      {/* Teleprompter Script Modal */}
+      {showScriptEditor && (
+        <ScriptEditor
+          settings={teleprompter}
+          onSave={setTeleprompter}
+          onClose={() => setShowScriptEditor(false)}
+        />
+      )}
+
+      {/* Storage Modal */}
+      {showStorage && (
+        <StorageModal onClose={() => setShowStorage(false)} />
+      )}
+
+      {/* Watch Folder Modal */}
+      {showWatchFolder && (
+        <WatchFolderModal
+          settings={watchFolder}
+          onSave={setWatchFolder}
+          onClose={() => setShowWatchFolder(false)}
+        />
+      )}
+
       {/* Onboarding Overlay */}
       {onboardingStep !== null && (
         <OnboardingOverlay
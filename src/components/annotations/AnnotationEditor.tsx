@@ -1,14 +1,27 @@
 import { useState, useRef, useCallback, useEffect } from "react";
-import type { Annotation, AnnotationKind, Clip } from "../../lib/types";
+import type { Annotation, AnnotationKind, Clip, Keyframe } from "../../lib/types";
 import { AnnotationToolbar } from "./AnnotationToolbar";
-import { setClipAnnotations, getClipAnnotations } from "../../lib/tauri";
+import { setClipAnnotations, getClipAnnotations, getGlobalAnnotations, setGlobalAnnotations } from "../../lib/tauri";
 
 interface Props {
   clip: Clip;
+  /** Edit the annotations of the whole timeline, `clip` only gives the frame */
+  timelineDurationMs?: number;
   onClose: () => void;
 }
 
-export function AnnotationEditor({ clip, onClose }: Props) {
+const FADE_IN_MS = 500;
+
+function fadeInKeyframes(startMs: number): Keyframe[] {
+  return [
+    { time_ms: startMs, property: "opacity", value: 0, easing: "ease_out" },
+    { time_ms: startMs + FADE_IN_MS, property: "opacity", value: 1, easing: "linear" },
+  ];
+}
+
+export function AnnotationEditor({ clip, timelineDurationMs, onClose }: Props) {
+  const global = timelineDurationMs !== undefined;
+  const durationMs = timelineDurationMs ?? clip.duration_ms;
   const [annotations, setAnnotations] = useState<Annotation[]>([]);
   const [activeTool, setActiveTool] = useState<AnnotationKind | null>(null);
   const [activeColor, setActiveColor] = useState("#ef4444");
@@ -17,13 +30,14 @@ export function AnnotationEditor({ clip, onClose }: Props) {
   const [currentAnnotation, setCurrentAnnotation] = useState<Partial<Annotation> | null>(null);
   const [textInput, setTextInput] = useState<{ x: number; y: number } | null>(null);
   const [textValue, setTextValue] = useState("");
+  const [fadeIn, setFadeIn] = useState(false);
   const svgRef = useRef<SVGSVGElement>(null);
   const containerRef = useRef<HTMLDivElement>(null);
   const textInputRef = useRef<HTMLInputElement>(null);
 
   useEffect(() => {
-    getClipAnnotations(clip.id).then(setAnnotations).catch(console.error);
-  }, [clip.id]);
+    (global ? getGlobalAnnotations() : getClipAnnotations(clip.id)).then(setAnnotations).catch(console.error);
+  }, [clip.id, global]);
 
   useEffect(() => {
     const handler = (e: KeyboardEvent) => {
@@ -68,9 +82,12 @@ export function AnnotationEditor({ clip, onClose }: Props) {
       text: null,
       points: activeTool === "freehand" ? [[pos.x, pos.y]] : null,
       start_ms: 0,
-      end_ms: clip.duration_ms,
+      end_ms: durationMs,
+      keyframes: fadeIn ? fadeInKeyframes(0) : [],
+      z_index: 0,
+      group: null,
     });
-  }, [activeTool, activeColor, clip.duration_ms, getNormalizedPos]);
+  }, [activeTool, activeColor, fadeIn, durationMs, getNormalizedPos]);
 
   const handleMouseMove = useCallback((e: React.MouseEvent) => {
     if (!drawing || !currentAnnotation) return;
@@ -101,7 +118,7 @@ export function AnnotationEditor({ clip, onClose }: Props) {
   }, [drawing, currentAnnotation]);
 
   const handleSave = async () => {
-    await setClipAnnotations(clip.id, annotations);
+    await (global ? setGlobalAnnotations(annotations) : setClipAnnotations(clip.id, annotations));
     onClose();
   };
 
@@ -123,7 +140,10 @@ export function AnnotationEditor({ clip, onClose }: Props) {
         text: textValue.trim(),
         points: null,
         start_ms: 0,
-        end_ms: clip.duration_ms,
+        end_ms: durationMs,
+        keyframes: fadeIn ? fadeInKeyframes(0) : [],
+        z_index: 0,
+        group: null,
       };
       setAnnotations((prev) => [...prev, ann]);
     }
@@ -165,7 +185,7 @@ export function AnnotationEditor({ clip, onClose }: Props) {
       <div className="bg-white dark:bg-zinc-900 rounded-2xl shadow-2xl overflow-hidden max-w-[80vw] max-h-[85vh] flex flex-col">
         <div className="flex items-center justify-between px-4 py-3 border-b border-zinc-200 dark:border-zinc-700">
           <h3 className="text-sm font-semibold text-zinc-800 dark:text-zinc-200">
-            Annotations — Clip {clip.id.slice(0, 8)}
+            {global ? "Annotations — Toute la vidéo" : `Annotations — Clip ${clip.id.slice(0, 8)}`}
           </h3>
           <div className="flex items-center gap-2">
             <button
@@ -212,7 +232,9 @@ export function AnnotationEditor({ clip, onClose }: Props) {
                 <polygon points="0 0, 10 3.5, 0 7" fill="currentColor" />
               </marker>
             </defs>
-            {annotations.map((ann, i) => renderAnnotation(ann, `ann-${i}`))}
+            {[...annotations]
+              .sort((a, b) => a.z_index - b.z_index)
+              .map((ann, i) => renderAnnotation(ann, `ann-${i}`))}
             {currentAnnotation && renderAnnotation(currentAnnotation, "current")}
           </svg>
 
@@ -240,8 +262,17 @@ export function AnnotationEditor({ clip, onClose }: Props) {
           )}
         </div>
 
-        <div className="px-4 py-2 border-t border-zinc-200 dark:border-zinc-700 text-[10px] text-zinc-400">
-          {annotations.length} annotation{annotations.length !== 1 ? "s" : ""}
+        <div className="flex items-center justify-between px-4 py-2 border-t border-zinc-200 dark:border-zinc-700 text-[10px] text-zinc-400">
+          <span>{annotations.length} annotation{annotations.length !== 1 ? "s" : ""}</span>
+          <label className="flex items-center gap-1.5 cursor-pointer">
+            <input
+              type="checkbox"
+              checked={fadeIn}
+              onChange={(e) => setFadeIn(e.target.checked)}
+              className="accent-blue-500"
+            />
+            Apparition en fondu
+          </label>
         </div>
       </div>
     </div>
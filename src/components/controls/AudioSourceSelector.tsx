@@ -7,6 +7,8 @@ interface Props {
   onChange: (source: AudioSource) => void;
   selectedMic: string | null;
   onMicChange: (deviceName: string | null) => void;
+  selectedOutput: string | null;
+  onOutputChange: (deviceName: string | null) => void;
   disabled?: boolean;
   systemVolume: number;
   micVolume: number;
@@ -21,17 +23,20 @@ const sources: { value: AudioSource; label: string; icon: string }[] = [
   { value: "both", label: "Les deux", icon: "+" },
 ];
 
-export function AudioSourceSelector({ audioSource, onChange, selectedMic, onMicChange, disabled, systemVolume, micVolume, onSystemVolumeChange, onMicVolumeChange }: Props) {
+export function AudioSourceSelector({ audioSource, onChange, selectedMic, onMicChange, selectedOutput, onOutputChange, disabled, systemVolume, micVolume, onSystemVolumeChange, onMicVolumeChange }: Props) {
   const [micDevices, setMicDevices] = useState<AudioDevice[]>([]);
+  const [outputDevices, setOutputDevices] = useState<AudioDevice[]>([]);
   const showMicSelect = audioSource === "microphone" || audioSource === "both";
+  const showOutputSelect = audioSource === "system" || audioSource === "both";
 
   useEffect(() => {
-    if (showMicSelect) {
+    if (showMicSelect || showOutputSelect) {
       getAudioDevices().then((devices) => {
         setMicDevices(devices.filter((d) => d.is_input));
+        setOutputDevices(devices.filter((d) => !d.is_input));
       }).catch(() => {});
     }
-  }, [showMicSelect]);
+  }, [showMicSelect, showOutputSelect]);
 
   return (
     <div className="flex items-center gap-1 flex-wrap">
@@ -77,6 +82,22 @@ export function AudioSourceSelector({ audioSource, onChange, selectedMic, onMicC
           )}
         </button>
       ))}
+      {showOutputSelect && outputDevices.length > 1 && (
+        <select
+          value={selectedOutput ?? ""}
+          onChange={(e) => onOutputChange(e.target.value || null)}
+          disabled={disabled}
+          title="Sortie audio enregistrée"
+          className="ml-1 px-1.5 py-1 rounded text-[10px] bg-zinc-100 dark:bg-zinc-800 text-zinc-700 dark:text-zinc-300 border border-zinc-300 dark:border-zinc-600 max-w-[140px] truncate"
+        >
+          <option value="">Sortie par défaut</option>
+          {outputDevices.map((d) => (
+            <option key={d.name} value={d.name}>
+              {d.is_default ? `★ ${d.name}` : d.name}
+            </option>
+          ))}
+        </select>
+      )}
       {showMicSelect && micDevices.length > 0 && (
         <select
           value={selectedMic ?? ""}
@@ -0,0 +1,54 @@
+import { useEffect, useState } from "react";
+import type { WaveformTrack } from "../../lib/types";
+import { onAppEvent } from "../../lib/tauri";
+
+/** Under this peak (about -42 dBFS) a track counts as silent */
+const SILENT_PEAK = 2;
+
+const labels: Record<WaveformTrack["source"], string> = {
+  system: "Sys",
+  microphone: "Mic",
+};
+
+/** Scrolling levels of the audio being recorded, one row per track */
+export function AudioWaveform() {
+  const [tracks, setTracks] = useState<WaveformTrack[]>([]);
+
+  useEffect(() => {
+    const unlisten = onAppEvent("audio-waveform", (event) => setTracks(event.tracks));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  if (tracks.length === 0) return null;
+
+  return (
+    <div className="ml-2 flex flex-col gap-0.5">
+      {tracks.map((track) => {
+        // Two seconds without a sound: likely a muted or wrong device
+        const silent = track.peaks.length >= 20 && track.peaks.slice(-20).every((p) => p < SILENT_PEAK);
+        return (
+          <div
+            key={track.source}
+            className="flex items-center gap-1"
+            title={silent ? "Aucun son capté depuis quelques secondes" : undefined}
+          >
+            <span className={`w-5 text-[9px] ${silent ? "text-yellow-600 dark:text-yellow-500" : "text-zinc-400 dark:text-zinc-600"}`}>
+              {labels[track.source]}
+            </span>
+            <div className="flex items-center h-3 gap-px">
+              {track.peaks.map((peak, i) => (
+                <span
+                  key={i}
+                  className={`w-0.5 rounded-full ${silent ? "bg-yellow-500" : peak >= 250 ? "bg-red-500" : "bg-blue-500"}`}
+                  style={{ height: `${Math.max(8, (peak / 255) * 100)}%` }}
+                />
+              ))}
+            </div>
+          </div>
+        );
+      })}
+    </div>
+  );
+}
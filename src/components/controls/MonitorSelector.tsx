@@ -0,0 +1,57 @@
+import { useEffect, useState } from "react";
+import { getMonitorsInfo } from "../../lib/tauri";
+import type { Region } from "../../lib/types";
+
+interface Props {
+  selected: Region[];
+  onChange: (monitors: Region[]) => void;
+  disabled?: boolean;
+}
+
+const sameMonitor = (a: Region, b: Region) => a.x === b.x && a.y === b.y;
+
+/** Pick the monitors recorded at once, each to its own clip */
+export function MonitorSelector({ selected, onChange, disabled }: Props) {
+  const [monitors, setMonitors] = useState<Region[]>([]);
+
+  useEffect(() => {
+    getMonitorsInfo().then(setMonitors).catch(() => {});
+  }, []);
+
+  // Nothing to pick with a single screen
+  if (monitors.length < 2) return null;
+
+  const toggle = (monitor: Region) => {
+    const isSelected = selected.some((m) => sameMonitor(m, monitor));
+    // Keep the screen order so the first one stays the main capture
+    onChange(monitors.filter((m) => (sameMonitor(m, monitor) ? !isSelected : selected.some((s) => sameMonitor(s, m)))));
+  };
+
+  return (
+    <div className="flex items-center gap-1">
+      <svg className="w-3.5 h-3.5 text-zinc-400 dark:text-zinc-600 mr-0.5" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+        <rect x="2" y="3" width="20" height="14" rx="2" ry="2" />
+        <line x1="8" y1="21" x2="16" y2="21" />
+        <line x1="12" y1="17" x2="12" y2="21" />
+      </svg>
+      {monitors.map((m, i) => {
+        const isSelected = selected.some((s) => sameMonitor(s, m));
+        return (
+          <button
+            key={`${m.x},${m.y}`}
+            onClick={() => toggle(m)}
+            disabled={disabled}
+            className={`w-7 h-6 flex items-center justify-center rounded text-[10px] font-mono font-medium transition-all ${
+              isSelected
+                ? "bg-blue-500 text-white"
+                : "bg-zinc-100 dark:bg-zinc-800 text-zinc-500 dark:text-zinc-400 hover:bg-zinc-200 dark:hover:bg-zinc-700"
+            } ${disabled ? "opacity-40 cursor-not-allowed" : "cursor-pointer"}`}
+            title={`Écran ${i + 1} (${m.width}x${m.height}) — chaque écran sélectionné est enregistré dans son propre clip`}
+          >
+            {i + 1}
+          </button>
+        );
+      })}
+    </div>
+  );
+}
@@ -1,5 +1,21 @@
 import { useState, useEffect } from "react";
-import type { ExportFormat, ExportQuality } from "../../lib/types";
+import { checkExportResolution, debugExportPlan, estimateExportSize, generateChapterText, validateExport } from "../../lib/tauri";
+import type { ExportChunkProgress, ExportDownscale, ExportEncoder, ExportFormat, ExportFps, ExportIssue, ExportPlan, ExportPreset, ExportQuality, ExportRange, GifLoop, GifPalette, PostExportAction, PostExportSettings, ResolutionCheck, SizeEstimate, SubtitleExport, VisualizerStyle } from "../../lib/types";
+
+function formatSize(bytes: number): string {
+  if (bytes >= 1024 * 1024 * 1024) return `${(bytes / (1024 * 1024 * 1024)).toFixed(1)} Go`;
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} Mo`;
+  return `${Math.max(1, Math.round(bytes / 1024))} Ko`;
+}
+
+// Command lines of a dry-run export, to paste in a terminal or a bug report
+function formatPlan(plan: ExportPlan): string {
+  const quote = (arg: string) => (/^[\w.:\/\\=+-]+$/.test(arg) ? arg : `"${arg.replace(/"/g, '\\"')}"`);
+  const lines = plan.commands.map((args) => [plan.ffmpeg, ...args].map(quote).join(" "));
+  if (plan.filter_graph) lines.push("", "Graphe de filtres :", plan.filter_graph);
+  if (plan.notes.length > 0) lines.push("", ...plan.notes.map((note) => `- ${note}`));
+  return lines.join("\n");
+}
 
 interface Props {
   clipCount: number;
@@ -9,9 +25,50 @@ interface Props {
   exportQuality: ExportQuality;
   exportSuccess: string | null;
   onExport: () => void;
+  onExportMulti: (formats: ExportFormat[]) => void;
+  onExportEach: () => void;
+  onExportVisualizer: (style: VisualizerStyle, title: string | null) => void;
   onFormatChange: (format: ExportFormat) => void;
   onQualityChange: (quality: ExportQuality) => void;
+  exportTargetMb: number | null;
+  onTargetMbChange: (mb: number | null) => void;
+  watermark: boolean;
+  presets: ExportPreset[];
+  selectedPreset: string | null;
+  onPresetSelect: (id: string | null) => void;
+  onPresetsChange: (presets: ExportPreset[]) => Promise<void>;
+  exportEncoder: ExportEncoder;
+  onEncoderChange: (encoder: ExportEncoder) => Promise<void>;
+  exportFps: ExportFps;
+  onFpsChange: (fps: ExportFps) => void;
+  exportDownscale: ExportDownscale;
+  onDownscaleChange: (downscale: ExportDownscale) => void;
+  hasHdrClips: boolean;
+  hdrTonemap: boolean;
+  onHdrTonemapChange: (enabled: boolean) => void;
+  keepFilterScripts: boolean;
+  onKeepFilterScriptsChange: (enabled: boolean) => void;
+  gifPalette: GifPalette;
+  onGifPaletteChange: (palette: GifPalette) => void;
+  gifLoop: GifLoop;
+  onGifLoopChange: (gifLoop: GifLoop) => void;
+  exportSidecar: boolean;
+  onExportSidecarChange: (enabled: boolean) => void;
+  exportChapters: boolean;
+  onExportChaptersChange: (enabled: boolean) => void;
+  subtitleExport: SubtitleExport;
+  onSubtitleExportChange: (mode: SubtitleExport) => void;
+  exportChunkMinutes: number;
+  onExportChunkMinutesChange: (minutes: number) => void;
+  exportChunk: ExportChunkProgress | null;
+  exportRange: ExportRange | null;
+  onRangeChange: (range: ExportRange | null) => void;
+  timelineDurationMs: number;
+  postExport: PostExportSettings;
+  onPostExportChange: (settings: PostExportSettings) => void;
   onCopyToClipboard: (path: string) => void;
+  onRevealFile: (path: string) => void;
+  onOpenInPlayer: (path: string) => void;
 }
 
 export function ExportButton({
@@ -22,12 +79,68 @@ export function ExportButton({
   exportQuality,
   exportSuccess,
   onExport,
+  onExportMulti,
+  onExportEach,
+  onExportVisualizer,
   onFormatChange,
   onQualityChange,
+  exportTargetMb,
+  onTargetMbChange,
+  watermark,
+  presets,
+  selectedPreset,
+  onPresetSelect,
+  onPresetsChange,
+  exportEncoder,
+  onEncoderChange,
+  exportFps,
+  onFpsChange,
+  exportDownscale,
+  onDownscaleChange,
+  hasHdrClips,
+  hdrTonemap,
+  onHdrTonemapChange,
+  keepFilterScripts,
+  onKeepFilterScriptsChange,
+  gifPalette,
+  onGifPaletteChange,
+  gifLoop,
+  onGifLoopChange,
+  exportSidecar,
+  onExportSidecarChange,
+  exportChapters,
+  onExportChaptersChange,
+  subtitleExport,
+  onSubtitleExportChange,
+  exportChunkMinutes,
+  onExportChunkMinutesChange,
+  exportChunk,
+  exportRange,
+  onRangeChange,
+  timelineDurationMs,
+  postExport,
+  onPostExportChange,
   onCopyToClipboard,
+  onRevealFile,
+  onOpenInPlayer,
 }: Props) {
   const [showSettings, setShowSettings] = useState(false);
   const [copied, setCopied] = useState(false);
+  const [estimate, setEstimate] = useState<SizeEstimate | null>(null);
+  const [resolution, setResolution] = useState<ResolutionCheck | null>(null);
+  const [issues, setIssues] = useState<ExportIssue[]>([]);
+  const [presetName, setPresetName] = useState("");
+  const [batchFormats, setBatchFormats] = useState<ExportFormat[]>([]);
+  const [visualizerStyle, setVisualizerStyle] = useState<VisualizerStyle>("waves");
+  const [visualizerTitle, setVisualizerTitle] = useState("");
+  const [encoderError, setEncoderError] = useState<string | null>(null);
+  const [planCopied, setPlanCopied] = useState(false);
+  // Copied, or too few chapters for YouTube
+  const [chaptersStatus, setChaptersStatus] = useState<"copied" | "too_few" | null>(null);
+
+  const preset = presets.find((p) => p.id === selectedPreset) ?? null;
+  const effectiveFormat = preset?.format ?? exportFormat;
+  const effectiveTargetMb = preset ? preset.target_size_mb : exportFormat !== "gif" ? exportTargetMb : null;
 
   useEffect(() => {
     if (!showSettings) return;
@@ -38,6 +151,59 @@ export function ExportButton({
     return () => document.removeEventListener("keydown", handler);
   }, [showSettings]);
 
+  useEffect(() => {
+    if (!showSettings || clipCount === 0) return;
+    let cancelled = false;
+    estimateExportSize(exportFormat, exportQuality, exportFormat !== "gif" ? exportTargetMb : null, selectedPreset)
+      .then((e) => { if (!cancelled) setEstimate(e); })
+      .catch(() => { if (!cancelled) setEstimate(null); });
+    checkExportResolution(selectedPreset)
+      .then((r) => { if (!cancelled) setResolution(r); })
+      .catch(() => { if (!cancelled) setResolution(null); });
+    validateExport(exportFormat, exportQuality, exportFormat !== "gif" ? exportTargetMb : null, selectedPreset, exportRange)
+      .then((found) => { if (!cancelled) setIssues(found); })
+      .catch(() => { if (!cancelled) setIssues([]); });
+    return () => { cancelled = true; };
+  }, [showSettings, clipCount, exportFormat, exportQuality, exportTargetMb, selectedPreset, exportFps, exportDownscale, exportEncoder, exportRange]);
+
+  const refineEstimate = () => {
+    estimateExportSize(exportFormat, exportQuality, exportFormat !== "gif" ? exportTargetMb : null, selectedPreset, true)
+      .then(setEstimate)
+      .catch(console.error);
+  };
+
+  const saveCurrentAsPreset = async () => {
+    const name = presetName.trim();
+    if (!name) return;
+    const id = `user-${Date.now()}`;
+    const created: ExportPreset = {
+      id,
+      name,
+      format: exportFormat,
+      quality: exportQuality,
+      watermark,
+      target_size_mb: exportFormat !== "gif" ? exportTargetMb : null,
+      output: {
+        width: null,
+        height: null,
+        fps: exportFormat !== "gif" && typeof exportFps === "object" ? exportFps.fixed : null,
+        fit: "letterbox",
+        include_audio: exportFormat !== "gif",
+        match_source_fps: exportFormat !== "gif" && exportFps === "source",
+        upscale: false,
+        safe_area: { top: 0, right: 0, bottom: 0, left: 0 },
+      },
+      builtin: false,
+    };
+    await onPresetsChange([...presets, created]);
+    onPresetSelect(id);
+    setPresetName("");
+  };
+
+  const deletePreset = async (id: string) => {
+    await onPresetsChange(presets.filter((p) => p.id !== id));
+  };
+
   useEffect(() => {
     if (copied) {
       const t = setTimeout(() => setCopied(false), 2000);
@@ -58,6 +224,14 @@ export function ExportButton({
           />
         </div>
         <span className="text-sm text-zinc-600 dark:text-zinc-300 font-mono tabular-nums">{exportProgress}%</span>
+        {exportChunk && exportChunk.chunk_count > 1 && (
+          <span className="text-xs text-zinc-400 dark:text-zinc-500">
+            {exportChunk.chunk_index !== null
+              ? `Partie ${exportChunk.chunk_index + 1}/${exportChunk.chunk_count}`
+              : "Assemblage..."}
+            {exportChunk.resumed > 0 && ` · ${exportChunk.resumed} reprise${exportChunk.resumed > 1 ? "s" : ""}`}
+          </span>
+        )}
       </div>
     );
   }
@@ -68,7 +242,7 @@ export function ExportButton({
         onClick={onExport}
         className="px-4 py-2.5 bg-blue-600 hover:bg-blue-500 rounded-l-lg text-sm font-semibold text-white transition-colors cursor-pointer"
       >
-        Export {exportFormat.toUpperCase()}
+        Export {preset ? preset.name : effectiveFormat.toUpperCase()}
       </button>
       <button
         onClick={() => setShowSettings(!showSettings)}
@@ -88,7 +262,7 @@ export function ExportButton({
             setCopied(true);
           }}
           className="px-3 py-2.5 bg-zinc-100 dark:bg-zinc-800 hover:bg-zinc-200 dark:hover:bg-zinc-700 rounded-lg text-xs font-medium transition-colors flex items-center gap-1.5"
-          title="Copier le chemin dans le presse-papiers"
+          title="Copier le fichier dans le presse-papiers"
         >
           {copied ? (
             <svg className="w-3.5 h-3.5 text-green-500" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
@@ -104,6 +278,30 @@ export function ExportButton({
         </button>
       )}
 
+      {/* Jump to the exported file */}
+      {exportSuccess && (
+        <>
+          <button
+            onClick={() => onOpenInPlayer(exportSuccess)}
+            className="px-2.5 py-2.5 bg-zinc-100 dark:bg-zinc-800 hover:bg-zinc-200 dark:hover:bg-zinc-700 rounded-lg transition-colors"
+            title="Ouvrir dans le lecteur"
+          >
+            <svg className="w-3.5 h-3.5" viewBox="0 0 24 24" fill="currentColor">
+              <polygon points="5 3 19 12 5 21 5 3" />
+            </svg>
+          </button>
+          <button
+            onClick={() => onRevealFile(exportSuccess)}
+            className="px-2.5 py-2.5 bg-zinc-100 dark:bg-zinc-800 hover:bg-zinc-200 dark:hover:bg-zinc-700 rounded-lg transition-colors"
+            title="Afficher dans l'explorateur"
+          >
+            <svg className="w-3.5 h-3.5" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+              <path d="M22 19a2 2 0 0 1-2 2H4a2 2 0 0 1-2-2V5a2 2 0 0 1 2-2h5l2 3h9a2 2 0 0 1 2 2z" />
+            </svg>
+          </button>
+        </>
+      )}
+
       {showSettings && (
         <div
           className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm animate-fade-in"
@@ -127,59 +325,613 @@ export function ExportButton({
               </button>
             </div>
 
-            {/* Format */}
+            {/* Presets */}
             <div className="mb-4">
               <div className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold mb-2">
-                Format
+                Préréglage
               </div>
-              <div className="grid grid-cols-2 gap-2">
-                {(["mp4", "gif"] as ExportFormat[]).map((f) => (
-                  <button
-                    key={f}
-                    onClick={() => onFormatChange(f)}
-                    className={`px-3 py-2 rounded-xl text-sm font-medium transition-all ${
-                      exportFormat === f
-                        ? "bg-blue-100 dark:bg-blue-900/50 text-blue-600 dark:text-blue-400 ring-2 ring-blue-400 dark:ring-blue-600"
-                        : "bg-zinc-50 dark:bg-zinc-700/50 hover:bg-zinc-100 dark:hover:bg-zinc-700 text-zinc-600 dark:text-zinc-300"
-                    }`}
-                  >
-                    {f.toUpperCase()}
-                  </button>
+              <div className="flex flex-wrap gap-1.5">
+                {[null, ...presets].map((p) => (
+                  <span key={p?.id ?? "custom"} className="relative group">
+                    <button
+                      onClick={() => onPresetSelect(p?.id ?? null)}
+                      className={`px-2.5 py-1.5 rounded-lg text-xs font-medium transition-all ${
+                        selectedPreset === (p?.id ?? null)
+                          ? "bg-blue-100 dark:bg-blue-900/50 text-blue-600 dark:text-blue-400 ring-2 ring-blue-400 dark:ring-blue-600"
+                          : "bg-zinc-50 dark:bg-zinc-700/50 hover:bg-zinc-100 dark:hover:bg-zinc-700 text-zinc-600 dark:text-zinc-300"
+                      }`}
+                    >
+                      {p ? p.name : "Personnalisé"}
+                    </button>
+                    {p && !p.builtin && (
+                      <button
+                        onClick={() => deletePreset(p.id)}
+                        className="absolute -top-1.5 -right-1.5 w-4 h-4 hidden group-hover:flex items-center justify-center rounded-full bg-zinc-500 text-white text-[10px] leading-none"
+                        title="Supprimer le préréglage"
+                      >
+                        ×
+                      </button>
+                    )}
+                  </span>
                 ))}
               </div>
-              {exportFormat === "gif" && (
+              {preset && (
                 <p className="text-[10px] text-zinc-400 dark:text-zinc-500 mt-1.5">
-                  Résolution réduite, pas de son
+                  {preset.format.toUpperCase()}
+                  {preset.output.width && preset.output.height
+                    ? ` · ${preset.output.width}×${preset.output.height}`
+                    : preset.output.width ? ` · ${preset.output.width} px de large` : ""}
+                  {preset.output.fps ? ` · ${preset.output.fps} i/s` : ""}
+                  {preset.target_size_mb ? ` · ${preset.target_size_mb} Mo max` : ""}
+                  {preset.format !== "gif" && !preset.output.include_audio ? " · sans son" : ""}
                 </p>
               )}
             </div>
 
-            {/* Quality */}
-            <div>
-              <div className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold mb-2">
-                Qualité
+            {!preset && (
+              <>
+                {/* Format */}
+                <div className="mb-4">
+                  <div className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold mb-2">
+                    Format
+                  </div>
+                  <div className="grid grid-cols-3 gap-2">
+                    {(["mp4", "gif", "webm"] as ExportFormat[]).map((f) => (
+                      <button
+                        key={f}
+                        onClick={() => onFormatChange(f)}
+                        className={`px-3 py-2 rounded-xl text-sm font-medium transition-all ${
+                          exportFormat === f
+                            ? "bg-blue-100 dark:bg-blue-900/50 text-blue-600 dark:text-blue-400 ring-2 ring-blue-400 dark:ring-blue-600"
+                            : "bg-zinc-50 dark:bg-zinc-700/50 hover:bg-zinc-100 dark:hover:bg-zinc-700 text-zinc-600 dark:text-zinc-300"
+                        }`}
+                      >
+                        {f.toUpperCase()}
+                      </button>
+                    ))}
+                  </div>
+                  {exportFormat === "gif" && (
+                    <>
+                      <p className="text-[10px] text-zinc-400 dark:text-zinc-500 mt-1.5">
+                        Résolution réduite, pas de son
+                      </p>
+                      <div className="mt-2 flex items-center gap-2 text-[10px] text-zinc-400 dark:text-zinc-500">
+                        <span>Couleurs</span>
+                        <select
+                          value={gifPalette.max_colors}
+                          onChange={(e) => onGifPaletteChange({ ...gifPalette, max_colors: Number(e.target.value) })}
+                          className="px-2 py-1 rounded-lg text-[10px] bg-zinc-50 dark:bg-zinc-700/50 text-zinc-600 dark:text-zinc-300 border border-zinc-200 dark:border-zinc-700"
+                        >
+                          {[256, 128, 64, 32, 16].map((n) => (
+                            <option key={n} value={n}>{n}</option>
+                          ))}
+                        </select>
+                      </div>
+                      {/* Dark and light scenes each get their own colors */}
+                      {clipCount > 1 && (
+                        <label className="mt-2 flex items-center gap-2 text-[10px] text-zinc-400 dark:text-zinc-500 cursor-pointer">
+                          <input
+                            type="checkbox"
+                            checked={gifPalette.per_clip}
+                            onChange={(e) => onGifPaletteChange({ ...gifPalette, per_clip: e.target.checked })}
+                            className="rounded"
+                          />
+                          Une palette par clip
+                        </label>
+                      )}
+                      <div className="mt-2 flex items-center gap-2 text-[10px] text-zinc-400 dark:text-zinc-500">
+                        <label className="flex items-center gap-2 cursor-pointer" title="Rejouer la séquence à l'envers à la fin">
+                          <input
+                            type="checkbox"
+                            checked={gifLoop.boomerang}
+                            onChange={(e) => onGifLoopChange({ ...gifLoop, boomerang: e.target.checked })}
+                            className="rounded"
+                          />
+                          Aller-retour
+                        </label>
+                        <span className="ml-auto">Lecture</span>
+                        <select
+                          value={gifLoop.plays}
+                          onChange={(e) => onGifLoopChange({ ...gifLoop, plays: Number(e.target.value) })}
+                          className="px-2 py-1 rounded-lg text-[10px] bg-zinc-50 dark:bg-zinc-700/50 text-zinc-600 dark:text-zinc-300 border border-zinc-200 dark:border-zinc-700"
+                        >
+                          <option value={0}>En boucle</option>
+                          {[1, 2, 3, 5].map((n) => (
+                            <option key={n} value={n}>{n} fois</option>
+                          ))}
+                        </select>
+                      </div>
+                    </>
+                  )}
+                </div>
+
+                {/* Quality */}
+                <div>
+                  <div className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold mb-2">
+                    Qualité
+                  </div>
+                  <div className="grid grid-cols-3 gap-2">
+                    {([
+                      { value: "high" as ExportQuality, label: "Haute", desc: "Lent" },
+                      { value: "medium" as ExportQuality, label: "Moyenne", desc: "Équilibré" },
+                      { value: "low" as ExportQuality, label: "Basse", desc: "Rapide" },
+                    ]).map((q) => (
+                      <button
+                        key={q.value}
+                        onClick={() => onQualityChange(q.value)}
+                        className={`flex flex-col items-center gap-0.5 px-2 py-2 rounded-xl text-xs transition-all ${
+                          exportQuality === q.value
+                            ? "bg-blue-100 dark:bg-blue-900/50 text-blue-600 dark:text-blue-400 ring-2 ring-blue-400 dark:ring-blue-600"
+                            : "bg-zinc-50 dark:bg-zinc-700/50 hover:bg-zinc-100 dark:hover:bg-zinc-700 text-zinc-600 dark:text-zinc-300"
+                        }`}
+                      >
+                        <span className="font-medium">{q.label}</span>
+                        <span className="text-[10px] opacity-60">{q.desc}</span>
+                      </button>
+                    ))}
+                  </div>
+                </div>
+
+                {/* Target size (video formats only) */}
+                {exportFormat !== "gif" && (
+                  <div className="mt-4">
+                    <div className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold mb-2">
+                      Taille maximale
+                    </div>
+                    <div className="grid grid-cols-4 gap-2">
+                      {[null, 10, 25, 50].map((mb) => (
+                        <button
+                          key={mb ?? "off"}
+                          onClick={() => onTargetMbChange(mb)}
+                          className={`px-2 py-2 rounded-xl text-xs font-medium transition-all ${
+                            exportTargetMb === mb
+                              ? "bg-blue-100 dark:bg-blue-900/50 text-blue-600 dark:text-blue-400 ring-2 ring-blue-400 dark:ring-blue-600"
+                              : "bg-zinc-50 dark:bg-zinc-700/50 hover:bg-zinc-100 dark:hover:bg-zinc-700 text-zinc-600 dark:text-zinc-300"
+                          }`}
+                        >
+                          {mb === null ? "Libre" : `${mb} Mo`}
+                        </button>
+                      ))}
+                    </div>
+                    {exportTargetMb !== null && (
+                      <p className="text-[10px] text-zinc-400 dark:text-zinc-500 mt-1.5">
+                        Débit calculé pour tenir sous {exportTargetMb} Mo
+                      </p>
+                    )}
+                  </div>
+                )}
+
+                {/* Frame rate (video formats only) */}
+                {exportFormat !== "gif" && (
+                  <div className="mt-4">
+                    <div className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold mb-2">
+                      Images/s
+                    </div>
+                    <div className="grid grid-cols-4 gap-1.5">
+                      {([
+                        ["default", "30"],
+                        ["source", "Source"],
+                        [{ fixed: 24 }, "24"],
+                        [{ fixed: 60 }, "60"],
+                      ] as [ExportFps, string][]).map(([fps, label]) => (
+                        <button
+                          key={label}
+                          onClick={() => onFpsChange(fps)}
+                          className={`px-2 py-2 rounded-xl text-xs font-medium transition-all ${
+                            JSON.stringify(exportFps) === JSON.stringify(fps)
+                              ? "bg-blue-100 dark:bg-blue-900/50 text-blue-600 dark:text-blue-400 ring-2 ring-blue-400 dark:ring-blue-600"
+                              : "bg-zinc-50 dark:bg-zinc-700/50 hover:bg-zinc-100 dark:hover:bg-zinc-700 text-zinc-600 dark:text-zinc-300"
+                          }`}
+                        >
+                          {label}
+                        </button>
+                      ))}
+                    </div>
+                    {exportFps === "source" && (
+                      <p className="text-[10px] text-zinc-400 dark:text-zinc-500 mt-1.5">
+                        Fréquence la plus élevée parmi les clips
+                      </p>
+                    )}
+                  </div>
+                )}
+
+                {/* Save the current settings as a preset */}
+                <div className="mt-4 flex gap-1.5">
+                  <input
+                    type="text"
+                    value={presetName}
+                    onChange={(e) => setPresetName(e.target.value)}
+                    onKeyDown={(e) => { if (e.key === "Enter") saveCurrentAsPreset(); }}
+                    placeholder="Nom du préréglage"
+                    className="flex-1 min-w-0 px-2 py-1.5 rounded-lg text-xs bg-zinc-50 dark:bg-zinc-700/50 border border-zinc-200 dark:border-zinc-700 focus:outline-none focus:ring-2 focus:ring-blue-400"
+                  />
+                  <button
+                    onClick={saveCurrentAsPreset}
+                    disabled={!presetName.trim()}
+                    className="px-2.5 py-1.5 rounded-lg text-xs font-medium bg-zinc-100 dark:bg-zinc-700 hover:bg-zinc-200 dark:hover:bg-zinc-600 disabled:opacity-40 transition-colors"
+                  >
+                    Enregistrer
+                  </button>
+                </div>
+
+                {/* Several formats from a single render */}
+                <div className="mt-4">
+                  <div className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold mb-2">
+                    Multi-format
+                  </div>
+                  <div className="flex gap-1.5">
+                    {(["mp4", "gif", "webm"] as ExportFormat[]).map((f) => (
+                      <button
+                        key={f}
+                        onClick={() => setBatchFormats((prev) => prev.includes(f) ? prev.filter((x) => x !== f) : [...prev, f])}
+                        className={`px-2.5 py-1.5 rounded-lg text-xs font-medium transition-all ${
+                          batchFormats.includes(f)
+                            ? "bg-blue-100 dark:bg-blue-900/50 text-blue-600 dark:text-blue-400 ring-2 ring-blue-400 dark:ring-blue-600"
+                            : "bg-zinc-50 dark:bg-zinc-700/50 hover:bg-zinc-100 dark:hover:bg-zinc-700 text-zinc-600 dark:text-zinc-300"
+                        }`}
+                      >
+                        {f.toUpperCase()}
+                      </button>
+                    ))}
+                    <button
+                      onClick={() => {
+                        setShowSettings(false);
+                        onExportMulti(batchFormats);
+                      }}
+                      disabled={batchFormats.length < 2}
+                      className="ml-auto px-2.5 py-1.5 rounded-lg text-xs font-medium bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-40 transition-colors"
+                    >
+                      Exporter la sélection
+                    </button>
+                  </div>
+                </div>
+
+                {/* Every clip of the strip to its own file */}
+                <div className="mt-4 flex items-center justify-between">
+                  <span className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold">
+                    Un fichier par clip
+                  </span>
+                  <button
+                    onClick={() => {
+                      setShowSettings(false);
+                      onExportEach();
+                    }}
+                    disabled={clipCount < 2 || exportFormat === "webm"}
+                    title={exportFormat === "webm" ? "MP4 ou GIF uniquement" : undefined}
+                    className="px-2.5 py-1.5 rounded-lg text-xs font-medium bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-40 transition-colors"
+                  >
+                    Exporter {clipCount} fichiers
+                  </button>
+                </div>
+
+                {/* The sound alone, drawn as a waveform or spectrum */}
+                <div className="mt-4">
+                  <div className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold mb-2">
+                    Vidéo audio
+                  </div>
+                  <div className="flex gap-1.5">
+                    <select
+                      value={visualizerStyle}
+                      onChange={(e) => setVisualizerStyle(e.target.value as VisualizerStyle)}
+                      className="px-2 py-1.5 rounded-lg text-xs bg-zinc-50 dark:bg-zinc-700/50 text-zinc-600 dark:text-zinc-300 border border-zinc-200 dark:border-zinc-700"
+                    >
+                      <option value="waves">Onde</option>
+                      <option value="spectrum">Spectre</option>
+                    </select>
+                    <input
+                      type="text"
+                      value={visualizerTitle}
+                      onChange={(e) => setVisualizerTitle(e.target.value)}
+                      placeholder="Titre (facultatif)"
+                      className="flex-1 min-w-0 px-2 py-1.5 rounded-lg text-xs bg-zinc-50 dark:bg-zinc-700/50 border border-zinc-200 dark:border-zinc-700"
+                    />
+                    <button
+                      onClick={() => {
+                        setShowSettings(false);
+                        onExportVisualizer(visualizerStyle, visualizerTitle.trim() || null);
+                      }}
+                      className="px-2.5 py-1.5 rounded-lg text-xs font-medium bg-blue-600 hover:bg-blue-500 text-white transition-colors"
+                    >
+                      Exporter
+                    </button>
+                  </div>
+                </div>
+              </>
+            )}
+
+            {/* Part of the timeline only, in seconds */}
+            <div className="mt-4">
+              <div className="flex items-center justify-between mb-2">
+                <span className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold">
+                  Plage
+                </span>
+                {exportRange && (
+                  <button
+                    onClick={() => onRangeChange(null)}
+                    className="text-[10px] text-zinc-400 hover:text-zinc-600 dark:hover:text-zinc-300 transition-colors"
+                  >
+                    Tout exporter
+                  </button>
+                )}
+              </div>
+              <div className="flex items-center gap-1.5 text-xs text-zinc-500 dark:text-zinc-400">
+                <input
+                  type="number"
+                  min={0}
+                  step={0.5}
+                  value={exportRange ? exportRange.start_ms / 1000 : 0}
+                  onChange={(e) => {
+                    const start_ms = Math.max(0, Math.round(Number(e.target.value) * 1000));
+                    onRangeChange({ start_ms, end_ms: exportRange?.end_ms ?? null });
+                  }}
+                  className="w-16 px-2 py-1.5 rounded-lg text-xs bg-zinc-50 dark:bg-zinc-700/50 border border-zinc-200 dark:border-zinc-700"
+                />
+                <span>à</span>
+                <input
+                  type="number"
+                  min={0}
+                  step={0.5}
+                  value={exportRange?.end_ms != null ? exportRange.end_ms / 1000 : ""}
+                  placeholder={(timelineDurationMs / 1000).toFixed(1)}
+                  onChange={(e) => {
+                    const end_ms = e.target.value === "" ? null : Math.round(Number(e.target.value) * 1000);
+                    onRangeChange({ start_ms: exportRange?.start_ms ?? 0, end_ms });
+                  }}
+                  className="w-16 px-2 py-1.5 rounded-lg text-xs bg-zinc-50 dark:bg-zinc-700/50 border border-zinc-200 dark:border-zinc-700"
+                />
+                <span>s</span>
+              </div>
+            </div>
+
+            {/* Only shown once a clip was recorded on an HDR display */}
+            {hasHdrClips && (
+              <label className="mt-4 flex items-center gap-2 text-xs text-zinc-600 dark:text-zinc-300 cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={hdrTonemap}
+                  onChange={(e) => onHdrTonemapChange(e.target.checked)}
+                  className="rounded"
+                />
+                Corriger les couleurs des clips HDR
+              </label>
+            )}
+
+            {/* Video encoder, hardware ones also scale on the GPU */}
+            {effectiveFormat !== "gif" && (
+              <div className="mt-4">
+                <div className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold mb-2">
+                  Encodeur
+                </div>
+                <div className="grid grid-cols-3 gap-1.5">
+                  {([
+                    ["software", "CPU"],
+                    ["nvenc", "NVIDIA"],
+                    ["qsv", "Intel QSV"],
+                  ] as [ExportEncoder, string][]).map(([enc, label]) => (
+                    <button
+                      key={enc}
+                      onClick={() => {
+                        setEncoderError(null);
+                        onEncoderChange(enc).catch((e) => setEncoderError(String(e)));
+                      }}
+                      className={`px-2 py-2 rounded-xl text-xs font-medium transition-all ${
+                        exportEncoder === enc
+                          ? "bg-blue-100 dark:bg-blue-900/50 text-blue-600 dark:text-blue-400 ring-2 ring-blue-400 dark:ring-blue-600"
+                          : "bg-zinc-50 dark:bg-zinc-700/50 hover:bg-zinc-100 dark:hover:bg-zinc-700 text-zinc-600 dark:text-zinc-300"
+                      }`}
+                    >
+                      {label}
+                    </button>
+                  ))}
+                </div>
+                {encoderError && (
+                  <p className="text-[10px] text-red-500 mt-1.5">{encoderError}</p>
+                )}
+              </div>
+            )}
+
+            {/* Long MP4 exports: one encode per chunk, an interrupted export resumes */}
+            {effectiveFormat === "mp4" && effectiveTargetMb === null && (
+              <div className="mt-4">
+                <div className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold mb-2">
+                  Export par parties
+                </div>
+                <div className="grid grid-cols-3 gap-1.5">
+                  {([
+                    [0, "Désactivé"],
+                    [10, "10 min"],
+                    [20, "20 min"],
+                  ] as [number, string][]).map(([minutes, label]) => (
+                    <button
+                      key={minutes}
+                      onClick={() => onExportChunkMinutesChange(minutes)}
+                      className={`px-2 py-2 rounded-xl text-xs font-medium transition-all ${
+                        exportChunkMinutes === minutes
+                          ? "bg-blue-100 dark:bg-blue-900/50 text-blue-600 dark:text-blue-400 ring-2 ring-blue-400 dark:ring-blue-600"
+                          : "bg-zinc-50 dark:bg-zinc-700/50 hover:bg-zinc-100 dark:hover:bg-zinc-700 text-zinc-600 dark:text-zinc-300"
+                      }`}
+                    >
+                      {label}
+                    </button>
+                  ))}
+                </div>
               </div>
-              <div className="grid grid-cols-3 gap-2">
-                {([
-                  { value: "high" as ExportQuality, label: "Haute", desc: "Lent" },
-                  { value: "medium" as ExportQuality, label: "Moyenne", desc: "Équilibré" },
-                  { value: "low" as ExportQuality, label: "Basse", desc: "Rapide" },
-                ]).map((q) => (
+            )}
+
+            {/* Estimated size */}
+            {estimate && (
+              <div className="mt-4 flex items-center justify-between text-xs text-zinc-500 dark:text-zinc-400">
+                <span>
+                  Taille estimée : <span className="font-medium text-zinc-700 dark:text-zinc-200">~{formatSize(estimate.bytes)}</span>
+                  <span className="opacity-60"> ({estimate.width}×{estimate.height})</span>
+                </span>
+                {effectiveFormat === "mp4" && effectiveTargetMb === null && estimate.method === "heuristic" && (
                   <button
-                    key={q.value}
-                    onClick={() => onQualityChange(q.value)}
-                    className={`flex flex-col items-center gap-0.5 px-2 py-2 rounded-xl text-xs transition-all ${
-                      exportQuality === q.value
-                        ? "bg-blue-100 dark:bg-blue-900/50 text-blue-600 dark:text-blue-400 ring-2 ring-blue-400 dark:ring-blue-600"
-                        : "bg-zinc-50 dark:bg-zinc-700/50 hover:bg-zinc-100 dark:hover:bg-zinc-700 text-zinc-600 dark:text-zinc-300"
-                    }`}
+                    onClick={refineEstimate}
+                    className="text-[10px] text-blue-500 hover:text-blue-400 transition-colors"
+                    title="Encode 5 secondes pour affiner l'estimation"
                   >
-                    <span className="font-medium">{q.label}</span>
-                    <span className="text-[10px] opacity-60">{q.desc}</span>
+                    Affiner
                   </button>
+                )}
+              </div>
+            )}
+
+            {/* Resolution against the encoder limits */}
+            {resolution && (!resolution.fits || resolution.slow || resolution.export_width < resolution.width) && (
+              <div className="mt-3 text-xs">
+                <p className={resolution.fits ? "text-amber-600 dark:text-amber-400" : "text-red-500"}>
+                  {!resolution.fits
+                    ? `Trop grand pour l'encodeur : ${resolution.width}×${resolution.height}, ${resolution.max_side} px max par côté`
+                    : resolution.export_width < resolution.width
+                      ? `Réduit à ${resolution.export_width}×${resolution.export_height} (timeline en ${resolution.width}×${resolution.height})`
+                      : `Export en ${resolution.width}×${resolution.height} : lent et volumineux`}
+                </p>
+                {resolution.options.length > 0 && (
+                  <div className="mt-1.5 flex flex-wrap gap-1.5">
+                    {resolution.options.map((o) => (
+                      <button
+                        key={JSON.stringify(o.downscale)}
+                        onClick={() => onDownscaleChange(o.downscale)}
+                        className={`px-2 py-1 rounded-lg text-[11px] font-medium transition-all ${
+                          JSON.stringify(exportDownscale) === JSON.stringify(o.downscale)
+                            ? "bg-blue-100 dark:bg-blue-900/50 text-blue-600 dark:text-blue-400 ring-2 ring-blue-400 dark:ring-blue-600"
+                            : "bg-zinc-50 dark:bg-zinc-700/50 hover:bg-zinc-100 dark:hover:bg-zinc-700 text-zinc-600 dark:text-zinc-300"
+                        }`}
+                      >
+                        {o.downscale === "auto" ? "Max. encodeur" : `${o.height}p`} · {o.width}×{o.height}
+                      </button>
+                    ))}
+                    {exportDownscale !== "auto" && (
+                      <button
+                        onClick={() => onDownscaleChange("auto")}
+                        className="px-2 py-1 rounded-lg text-[11px] text-zinc-500 hover:text-zinc-700 dark:hover:text-zinc-300 transition-colors"
+                      >
+                        Taille maximale
+                      </button>
+                    )}
+                  </div>
+                )}
+              </div>
+            )}
+
+            {/* Problems found before exporting */}
+            {issues.filter((i) => i.kind !== "resolution").length > 0 && (
+              <ul className="mt-3 space-y-1 text-xs">
+                {issues.filter((i) => i.kind !== "resolution").map((issue, i) => (
+                  <li key={i} className={issue.severity === "error" ? "text-red-500" : "text-amber-600 dark:text-amber-400"}>
+                    {issue.message}
+                  </li>
                 ))}
+              </ul>
+            )}
+
+            {/* After export */}
+            <div className="mt-4">
+              <div className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold mb-2">
+                Après l'export
               </div>
+              <select
+                value={postExport.action}
+                onChange={(e) => onPostExportChange({ ...postExport, action: e.target.value as PostExportAction })}
+                className="w-full px-3 py-2 rounded-xl text-xs bg-zinc-50 dark:bg-zinc-700/50 text-zinc-600 dark:text-zinc-300 border border-zinc-200 dark:border-zinc-700"
+              >
+                <option value="open_folder">Ouvrir le dossier</option>
+                <option value="copy_to_clipboard">Copier dans le presse-papiers</option>
+                <option value="open_in_player">Ouvrir dans le lecteur</option>
+                <option value="run_command">Lancer une commande</option>
+                <option value="nothing">Ne rien faire</option>
+              </select>
+              {postExport.action === "run_command" && (
+                <input
+                  type="text"
+                  value={postExport.command}
+                  onChange={(e) => onPostExportChange({ ...postExport, command: e.target.value })}
+                  placeholder='upload.exe {path}'
+                  className="w-full mt-2 px-3 py-2 rounded-xl text-xs font-mono bg-zinc-50 dark:bg-zinc-700/50 text-zinc-600 dark:text-zinc-300 border border-zinc-200 dark:border-zinc-700"
+                />
+              )}
             </div>
+
+            {/* Every subtitle language as a track players can switch (GIFs burn the active one) */}
+            <label className="mt-4 flex items-center gap-2 text-[10px] text-zinc-400 dark:text-zinc-500 cursor-pointer">
+              <input
+                type="checkbox"
+                checked={subtitleExport === "soft"}
+                onChange={(e) => onSubtitleExportChange(e.target.checked ? "soft" : "burned")}
+                className="rounded"
+              />
+              Sous-titres en pistes séparées, une par langue, plutôt qu'incrustés
+            </label>
+
+            {/* Clips, trims, keystrokes, markers and subtitles in output time, as <export>.json */}
+            <label className="mt-4 flex items-center gap-2 text-[10px] text-zinc-400 dark:text-zinc-500 cursor-pointer">
+              <input
+                type="checkbox"
+                checked={exportSidecar}
+                onChange={(e) => onExportSidecarChange(e.target.checked)}
+                className="rounded"
+              />
+              Écrire un fichier JSON décrivant la timeline
+            </label>
+
+            {/* One "00:00 Title" line per clip, as <export>.chapters.txt */}
+            <label className="mt-4 flex items-center gap-2 text-[10px] text-zinc-400 dark:text-zinc-500 cursor-pointer">
+              <input
+                type="checkbox"
+                checked={exportChapters}
+                onChange={(e) => onExportChaptersChange(e.target.checked)}
+                className="rounded"
+              />
+              Écrire les chapitres YouTube à côté de l'export
+            </label>
+            <button
+              onClick={() => {
+                generateChapterText()
+                  .then(async (text) => {
+                    if (!text) {
+                      setChaptersStatus("too_few");
+                      return;
+                    }
+                    await navigator.clipboard.writeText(text);
+                    setChaptersStatus("copied");
+                  })
+                  .catch(console.error);
+              }}
+              disabled={clipCount === 0}
+              className="mt-1 text-[10px] text-zinc-400 dark:text-zinc-500 hover:text-zinc-600 dark:hover:text-zinc-300 underline disabled:opacity-50"
+              title="Une ligne « 00:00 Titre » par clip, à coller dans la description. YouTube demande au moins 3 chapitres."
+            >
+              {chaptersStatus === "copied"
+                ? "Chapitres copiés"
+                : chaptersStatus === "too_few"
+                  ? "Moins de 3 chapitres, rien à copier"
+                  : "Copier les chapitres YouTube"}
+            </button>
+
+            {/* Debugging aid: the .filtergraph.txt file stays in the temp dir, its path in the log */}
+            <label className="mt-4 flex items-center gap-2 text-[10px] text-zinc-400 dark:text-zinc-500 cursor-pointer">
+              <input
+                type="checkbox"
+                checked={keepFilterScripts}
+                onChange={(e) => onKeepFilterScriptsChange(e.target.checked)}
+                className="rounded"
+              />
+              Conserver le graphe de filtres FFmpeg (débogage)
+            </label>
+            <button
+              onClick={() => {
+                debugExportPlan(exportFormat, exportQuality, watermark, exportFormat !== "gif" ? exportTargetMb : null, selectedPreset, exportRange)
+                  .then((plan) => navigator.clipboard.writeText(formatPlan(plan)))
+                  .then(() => setPlanCopied(true))
+                  .catch(console.error);
+              }}
+              disabled={clipCount === 0}
+              className="mt-1 text-[10px] text-zinc-400 dark:text-zinc-500 hover:text-zinc-600 dark:hover:text-zinc-300 underline disabled:opacity-50"
+              title="Commandes FFmpeg et graphe de filtres de cet export, sans le lancer"
+            >
+              {planCopied ? "Commandes copiées" : "Copier les commandes FFmpeg"}
+            </button>
           </div>
         </div>
       )}
@@ -0,0 +1,118 @@
+import { useEffect, useState } from "react";
+import { importExportedVideos, listExportedVideos } from "../../lib/tauri";
+import type { ExportedFile } from "../../lib/types";
+
+interface Props {
+  onClose: () => void;
+}
+
+function formatSize(bytes: number): string {
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} Mo`;
+  return `${Math.max(1, Math.round(bytes / 1024))} Ko`;
+}
+
+export function ExportHistoryModal({ onClose }: Props) {
+  const [files, setFiles] = useState<ExportedFile[] | null>(null);
+  const [selected, setSelected] = useState<string[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    listExportedVideos().then(setFiles).catch((e) => setError(String(e)));
+  }, []);
+
+  useEffect(() => {
+    const handler = (e: KeyboardEvent) => {
+      if (e.key === "Escape") onClose();
+    };
+    document.addEventListener("keydown", handler);
+    return () => document.removeEventListener("keydown", handler);
+  }, [onClose]);
+
+  const toggle = (path: string) => {
+    setSelected((prev) => prev.includes(path) ? prev.filter((p) => p !== path) : [...prev, path]);
+  };
+
+  // Clips are appended in the order the files were picked
+  const handleImport = async () => {
+    try {
+      await importExportedVideos(selected);
+      onClose();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const formatDate = (ms: number) =>
+    new Date(ms).toLocaleDateString("fr-FR", { day: "2-digit", month: "short", year: "numeric", hour: "2-digit", minute: "2-digit" });
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm animate-fade-in"
+      onClick={(e) => { if (e.target === e.currentTarget) onClose(); }}
+    >
+      <div className="bg-white dark:bg-zinc-800 border border-zinc-200 dark:border-zinc-700 rounded-2xl shadow-2xl p-5 w-full max-w-md mx-4 max-h-[70vh] flex flex-col animate-fade-in">
+        <div className="flex items-center justify-between mb-4">
+          <h3 className="text-sm font-semibold text-zinc-800 dark:text-zinc-200">
+            Ajouter un export à la timeline
+          </h3>
+          <button
+            onClick={onClose}
+            className="w-6 h-6 flex items-center justify-center rounded-md hover:bg-zinc-100 dark:hover:bg-zinc-700 text-zinc-400 hover:text-zinc-600 dark:hover:text-zinc-300 transition-colors"
+          >
+            <svg width="14" height="14" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+              <line x1="18" y1="6" x2="6" y2="18" />
+              <line x1="6" y1="6" x2="18" y2="18" />
+            </svg>
+          </button>
+        </div>
+
+        {files === null ? (
+          <p className="text-sm text-zinc-400 dark:text-zinc-500 text-center py-8">Chargement…</p>
+        ) : files.length === 0 ? (
+          <p className="text-sm text-zinc-400 dark:text-zinc-500 text-center py-8">
+            Aucun export dans le dossier ClipFlow
+          </p>
+        ) : (
+          <div className="space-y-2 overflow-y-auto flex-1">
+            {files.map((file) => {
+              const order = selected.indexOf(file.path);
+              return (
+                <button
+                  key={file.path}
+                  onClick={() => toggle(file.path)}
+                  className={`w-full flex items-center gap-3 px-3 py-2.5 rounded-xl text-left transition-colors ${
+                    order >= 0
+                      ? "bg-blue-50 dark:bg-blue-900/30 border border-blue-200 dark:border-blue-800"
+                      : "bg-zinc-50 dark:bg-zinc-700/50 hover:bg-zinc-100 dark:hover:bg-zinc-700 border border-transparent"
+                  }`}
+                >
+                  <span className={`w-5 h-5 shrink-0 rounded-full flex items-center justify-center text-[10px] font-semibold ${
+                    order >= 0 ? "bg-blue-500 text-white" : "bg-zinc-200 dark:bg-zinc-600 text-transparent"
+                  }`}>
+                    {order >= 0 ? order + 1 : ""}
+                  </span>
+                  <span className="flex-1 min-w-0">
+                    <span className="block text-sm font-medium text-zinc-800 dark:text-zinc-200 truncate">{file.name}</span>
+                    <span className="block text-[10px] text-zinc-400 dark:text-zinc-500 mt-0.5">
+                      {formatSize(file.size_bytes)} · {formatDate(file.modified_ms)}
+                    </span>
+                  </span>
+                </button>
+              );
+            })}
+          </div>
+        )}
+
+        {error && <p className="mt-3 text-xs text-red-500">{error}</p>}
+
+        <button
+          onClick={handleImport}
+          disabled={selected.length === 0}
+          className="mt-4 px-3 py-2 rounded-lg text-xs font-medium bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-40 transition-colors"
+        >
+          {selected.length > 1 ? `Ajouter ${selected.length} vidéos` : "Ajouter à la timeline"}
+        </button>
+      </div>
+    </div>
+  );
+}
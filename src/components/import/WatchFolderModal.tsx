@@ -0,0 +1,79 @@
+import { useEffect, useState } from "react";
+import type { WatchFolderSettings } from "../../lib/types";
+
+interface Props {
+  settings: WatchFolderSettings;
+  onSave: (settings: WatchFolderSettings) => Promise<void>;
+  onClose: () => void;
+}
+
+export function WatchFolderModal({ settings, onSave, onClose }: Props) {
+  const [path, setPath] = useState(settings.path ?? "");
+  const [confirm, setConfirm] = useState(settings.confirm);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const handler = (e: KeyboardEvent) => {
+      if (e.key === "Escape") onClose();
+    };
+    document.addEventListener("keydown", handler);
+    return () => document.removeEventListener("keydown", handler);
+  }, [onClose]);
+
+  const save = async () => {
+    try {
+      await onSave({ path: path.trim() || null, confirm });
+      onClose();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm animate-fade-in"
+      onClick={(e) => { if (e.target === e.currentTarget) onClose(); }}
+    >
+      <div className="bg-white dark:bg-zinc-800 border border-zinc-200 dark:border-zinc-700 rounded-2xl shadow-2xl p-5 w-full max-w-lg mx-4 flex flex-col gap-3 animate-fade-in">
+        <h3 className="text-sm font-semibold text-zinc-800 dark:text-zinc-200">
+          Dossier surveillé
+        </h3>
+        <p className="text-xs text-zinc-400 dark:text-zinc-500">
+          Les nouvelles vidéos de ce dossier (OBS, ShareX…) sont ajoutées à la timeline dès qu'elles sont terminées. Laisser vide pour désactiver.
+        </p>
+        <input
+          type="text"
+          value={path}
+          onChange={(e) => setPath(e.target.value)}
+          onKeyDown={(e) => { if (e.key === "Enter") save(); }}
+          placeholder="C:\Users\…\Videos\OBS"
+          className="w-full px-3 py-2 rounded-lg text-xs font-mono bg-zinc-50 dark:bg-zinc-900 text-zinc-800 dark:text-zinc-200 border border-zinc-200 dark:border-zinc-700"
+        />
+        <label className="flex items-center gap-2 text-xs text-zinc-500 dark:text-zinc-400 cursor-pointer">
+          <input
+            type="checkbox"
+            checked={confirm}
+            onChange={(e) => setConfirm(e.target.checked)}
+            className="rounded"
+          />
+          Demander avant d'importer
+        </label>
+        {error && <p className="text-xs text-red-500">{error}</p>}
+        <div className="flex justify-end gap-2">
+          <button
+            onClick={onClose}
+            className="px-3 py-1.5 rounded-lg text-xs text-zinc-500 hover:bg-zinc-100 dark:hover:bg-zinc-700 transition-colors"
+          >
+            Annuler
+          </button>
+          <button
+            onClick={save}
+            className="px-3 py-1.5 rounded-lg text-xs bg-blue-500 hover:bg-blue-600 text-white transition-colors"
+          >
+            Enregistrer
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
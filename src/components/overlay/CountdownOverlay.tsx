@@ -0,0 +1,28 @@
+import { useEffect, useState } from "react";
+import { onAppEvent } from "../../lib/tauri";
+
+export function CountdownOverlay() {
+  const [remaining, setRemaining] = useState<number | null>(null);
+
+  useEffect(() => {
+    const unlisten = onAppEvent("countdown-tick", (event) => {
+      setRemaining(event.remaining);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  if (!remaining) return null;
+
+  return (
+    <div className="fixed inset-0 flex items-center justify-center bg-black/40 pointer-events-none">
+      <div className="text-center">
+        <div className="text-[12rem] leading-none font-bold text-white animate-pulse tabular-nums drop-shadow-lg">
+          {remaining}
+        </div>
+        <p className="text-zinc-200 text-sm mt-4">Échap pour annuler</p>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,112 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+import { addLiveStroke, clearLiveStrokes, onAppEvent } from "../../lib/tauri";
+import type { DrawingTool } from "../../lib/types";
+
+interface Stroke {
+  points: [number, number][];
+  tool: DrawingTool;
+  color: string;
+}
+
+const TOOL_COLORS: Record<DrawingTool, string> = {
+  pen: "#ef4444",
+  highlighter: "#facc15",
+};
+
+export function DrawingOverlay() {
+  const [tool, setTool] = useState<DrawingTool | null>(null);
+  const [strokes, setStrokes] = useState<Stroke[]>([]);
+  const [current, setCurrent] = useState<Stroke | null>(null);
+  const startedAt = useRef(0);
+
+  useEffect(() => {
+    const unlisten = onAppEvent("drawing-tool", (event) => {
+      setTool(event.tool);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const clear = useCallback(() => {
+    setStrokes([]);
+    clearLiveStrokes().catch(() => {});
+  }, []);
+
+  // Only reachable while a tool is active: the window is click-through otherwise
+  useEffect(() => {
+    const onKey = (e: KeyboardEvent) => {
+      if (e.key === "Delete" || e.key === "Backspace") clear();
+    };
+    window.addEventListener("keydown", onKey);
+    return () => window.removeEventListener("keydown", onKey);
+  }, [clear]);
+
+  const normalized = (e: React.PointerEvent): [number, number] => [
+    e.clientX / window.innerWidth,
+    e.clientY / window.innerHeight,
+  ];
+
+  const handlePointerDown = (e: React.PointerEvent) => {
+    if (!tool) return;
+    e.currentTarget.setPointerCapture(e.pointerId);
+    startedAt.current = performance.now();
+    setCurrent({ points: [normalized(e)], tool, color: TOOL_COLORS[tool] });
+  };
+
+  const handlePointerMove = (e: React.PointerEvent) => {
+    if (!current) return;
+    const point = normalized(e);
+    setCurrent((prev) => (prev ? { ...prev, points: [...prev.points, point] } : prev));
+  };
+
+  const handlePointerUp = () => {
+    if (!current) return;
+    if (current.points.length > 1) {
+      setStrokes((prev) => [...prev, current]);
+      addLiveStroke({
+        ...current,
+        duration_ms: Math.round(performance.now() - startedAt.current),
+      }).catch(() => {});
+    }
+    setCurrent(null);
+  };
+
+  const renderStroke = (stroke: Stroke, key: string | number) => {
+    const d = stroke.points.map((p, i) => `${i === 0 ? "M" : "L"}${p[0] * 100} ${p[1] * 100}`).join(" ");
+    const highlighter = stroke.tool === "highlighter";
+    return (
+      <path
+        key={key}
+        d={d}
+        fill="none"
+        stroke={stroke.color}
+        strokeOpacity={highlighter ? 0.45 : 1}
+        strokeWidth={highlighter ? 18 : 4}
+        strokeLinecap="round"
+        strokeLinejoin="round"
+        vectorEffect="non-scaling-stroke"
+      />
+    );
+  };
+
+  return (
+    <div
+      className={`fixed inset-0 ${tool ? "cursor-crosshair" : "pointer-events-none"}`}
+      onPointerDown={handlePointerDown}
+      onPointerMove={handlePointerMove}
+      onPointerUp={handlePointerUp}
+    >
+      <svg className="w-full h-full" viewBox="0 0 100 100" preserveAspectRatio="none">
+        {strokes.map((s, i) => renderStroke(s, i))}
+        {current && renderStroke(current, "current")}
+      </svg>
+
+      {tool && (
+        <div className="absolute top-3 left-1/2 -translate-x-1/2 px-3 py-1.5 rounded-full bg-black/60 text-white text-xs select-none">
+          {tool === "pen" ? "Stylo" : "Surligneur"} · {tool === "pen" ? "F8" : "F7"} pour relâcher · Suppr pour effacer
+        </div>
+      )}
+    </div>
+  );
+}
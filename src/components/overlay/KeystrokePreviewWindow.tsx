@@ -0,0 +1,53 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+
+// Same as the export, in physical pixels of the recorded area
+const DISPLAY_MS = 1800;
+const FONT_SIZE = 36;
+const PADDING = 16;
+const MARGIN_X = 30;
+// Top of the text, up from the bottom of the recorded area
+const TEXT_BOTTOM = 80;
+
+/** Strip over the bottom of the recorded area showing each keystroke as
+ * the export will burn it in. Click-through and left out of the capture. */
+export function KeystrokePreviewWindow() {
+  const [label, setLabel] = useState<{ text: string; time: number } | null>(null);
+  const dpr = window.devicePixelRatio || 1;
+
+  useEffect(() => {
+    const unlisten = listen<string>("keystroke-live", (event) => {
+      setLabel({ text: event.payload, time: Date.now() });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    if (!label) return;
+    const timer = setTimeout(() => setLabel(null), Math.max(0, label.time + DISPLAY_MS - Date.now()));
+    return () => clearTimeout(timer);
+  }, [label]);
+
+  if (!label) return null;
+
+  return (
+    <div className="fixed inset-0 pointer-events-none select-none">
+      <div
+        className="absolute rounded-xl text-white font-semibold whitespace-nowrap"
+        style={{
+          left: (MARGIN_X - PADDING) / dpr,
+          top: `calc(100% - ${(TEXT_BOTTOM + PADDING) / dpr}px)`,
+          fontSize: FONT_SIZE / dpr,
+          lineHeight: 1.2,
+          padding: PADDING / dpr,
+          background: "rgba(0, 0, 0, 0.55)",
+          textShadow: `${2 / dpr}px ${2 / dpr}px 0 rgba(0, 0, 0, 0.4)`,
+        }}
+      >
+        {label.text}
+      </div>
+    </div>
+  );
+}
@@ -16,6 +16,7 @@ interface WindowInfo {
   y: number;
   width: number;
   height: number;
+  hwnd: number;
 }
 
 interface MonitorInfo {
@@ -25,6 +26,9 @@ interface MonitorInfo {
   height: number;
 }
 
+// Opened with ?target=secondary to pick the second region of a dual capture
+const secondary = new URLSearchParams(window.location.search).get("target") === "secondary";
+
 export function RegionOverlay() {
   const [drawing, setDrawing] = useState(false);
   const [startPos, setStartPos] = useState({ x: 0, y: 0 });
@@ -33,6 +37,9 @@ export function RegionOverlay() {
   const [windows, setWindows] = useState<WindowInfo[]>([]);
   const [monitors, setMonitors] = useState<MonitorInfo[]>([]);
   const [hoveredWindow, setHoveredWindow] = useState<number | null>(null);
+  // HWND of the window the selection was snapped to, followed while recording
+  const [pickedWindow, setPickedWindow] = useState<number | null>(null);
+  const [followWindow, setFollowWindow] = useState(true);
   const [overlayOffset, setOverlayOffset] = useState({ x: 0, y: 0 });
   const [scaleFactor, setScaleFactor] = useState(1);
   const overlayRef = useRef<HTMLDivElement>(null);
@@ -65,6 +72,7 @@ export function RegionOverlay() {
   const handleWindowSnap = useCallback((win: WindowInfo) => {
     const local = toLocal(win.x, win.y, win.width, win.height);
     setRect(local);
+    setPickedWindow(win.hwnd);
     setConfirmed(true);
   }, [toLocal]);
 
@@ -75,6 +83,7 @@ export function RegionOverlay() {
       if ((e.target as HTMLElement).closest("button")) return;
       if ((e.target as HTMLElement).closest("[data-window-snap]")) return;
       setDrawing(true);
+      setPickedWindow(null);
       setStartPos({ x: e.clientX, y: e.clientY });
       setRect({ x: e.clientX, y: e.clientY, width: 0, height: 0 });
     },
@@ -133,19 +142,29 @@ export function RegionOverlay() {
     // clientX/clientY are in CSS (logical) pixels
     // outerPosition and gdigrab use physical pixels
     // Convert logical → physical by multiplying by scale factor
-    const region = {
+    const drawn = {
       x: Math.round(rect.x * scale + offsetX),
       y: Math.round(rect.y * scale + offsetY),
       width: Math.round((rect.width * scale) / 2) * 2,
       height: Math.round((rect.height * scale) / 2) * 2,
     };
-    await invoke("set_capture_region", { region });
-    await emit("region-selected", region);
+    // Snap to nearby window edges / monitor bounds so the selection is pixel-perfect
+    const region = await invoke<Rect>("snap_region", { region: drawn }).catch(() => drawn);
+    if (secondary) {
+      await invoke("set_secondary_region", { region });
+      await emit("secondary-region-selected", region);
+    } else {
+      await invoke("set_capture_region", { region });
+      const hwnd = followWindow && pickedWindow !== null ? pickedWindow : null;
+      await invoke("set_capture_window", { hwnd }).catch(() => invoke("set_capture_window", { hwnd: null }));
+      await emit("region-selected", region);
+    }
     await closeOverlay();
-  }, [rect, closeOverlay]);
+  }, [rect, closeOverlay, followWindow, pickedWindow]);
 
   const handleReset = useCallback(() => {
     setRect(null);
+    setPickedWindow(null);
     setConfirmed(false);
   }, []);
 
@@ -229,7 +248,7 @@ export function RegionOverlay() {
       {/* Instructions */}
       {!rect && (
         <div className="absolute top-8 left-1/2 -translate-x-1/2 bg-black/80 text-white px-5 py-2.5 rounded-lg text-sm pointer-events-none z-50">
-          Clique sur une fenêtre ou dessine un rectangle · ESC pour annuler
+          {secondary && "Seconde zone · "}Clique sur une fenêtre ou dessine un rectangle · ESC pour annuler
         </div>
       )}
 
@@ -292,6 +311,19 @@ export function RegionOverlay() {
           >
             Annuler
           </button>
+          {!secondary && pickedWindow !== null && (
+            <label
+              className="flex items-center gap-1.5 px-3 py-1.5 bg-zinc-800 text-zinc-300 text-sm rounded-lg cursor-pointer"
+              title="La capture suit la fenêtre si elle est déplacée ou redimensionnée"
+            >
+              <input
+                type="checkbox"
+                checked={followWindow}
+                onChange={(e) => setFollowWindow(e.target.checked)}
+              />
+              Suivre la fenêtre
+            </label>
+          )}
         </div>
       )}
     </div>
@@ -0,0 +1,95 @@
+import { useEffect, useMemo, useRef, useState } from "react";
+import { getRecordingDurationMs, getTeleprompter, reportScriptLine } from "../../lib/tauri";
+import type { TeleprompterSettings } from "../../lib/types";
+
+const TICK_MS = 100;
+// Reading line, from the top of the window
+const READING_LINE = 0.35;
+// Arrow keys move the script by this much
+const NUDGE_PX = 40;
+
+export function TeleprompterWindow() {
+  const [settings, setSettings] = useState<TeleprompterSettings | null>(null);
+  const [offset, setOffset] = useState(0);
+  const [nudge, setNudge] = useState(0);
+  const contentRef = useRef<HTMLDivElement>(null);
+  const lineRefs = useRef<(HTMLParagraphElement | null)[]>([]);
+  const lastLine = useRef(-1);
+
+  useEffect(() => {
+    getTeleprompter().then(setSettings).catch(() => {});
+  }, []);
+
+  // Same split as the backend, mark line numbers index into it
+  const lines = useMemo(
+    () => (settings?.script ?? "").split("\n").map((l) => l.trim()).filter(Boolean),
+    [settings],
+  );
+  const totalWords = useMemo(() => lines.join(" ").split(/\s+/).filter(Boolean).length, [lines]);
+
+  // Scroll with the recording time: pauses hold the script in place
+  useEffect(() => {
+    if (!settings || totalWords === 0) return;
+    const id = setInterval(() => {
+      getRecordingDurationMs()
+        .then((elapsed) => {
+          const height = contentRef.current?.scrollHeight ?? 0;
+          const readingMs = (totalWords / Math.max(settings.words_per_minute, 1)) * 60_000;
+          setOffset(Math.min(elapsed / readingMs, 1) * height);
+        })
+        .catch(() => {});
+    }, TICK_MS);
+    return () => clearInterval(id);
+  }, [settings, totalWords]);
+
+  const position = Math.max(offset + nudge, 0);
+
+  useEffect(() => {
+    let line = 0;
+    lineRefs.current.forEach((el, i) => {
+      if (el && el.offsetTop <= position) line = i;
+    });
+    if (line !== lastLine.current) {
+      lastLine.current = line;
+      reportScriptLine(line).catch(() => {});
+    }
+  }, [position]);
+
+  useEffect(() => {
+    const onKey = (e: KeyboardEvent) => {
+      if (e.key === "ArrowDown") setNudge((n) => n + NUDGE_PX);
+      if (e.key === "ArrowUp") setNudge((n) => n - NUDGE_PX);
+    };
+    window.addEventListener("keydown", onKey);
+    return () => window.removeEventListener("keydown", onKey);
+  }, []);
+
+  return (
+    <div data-tauri-drag-region className="fixed inset-0 overflow-hidden bg-black/90 text-white select-none cursor-move">
+      <div
+        className="absolute left-0 right-0 h-px bg-red-500/70 pointer-events-none"
+        style={{ top: `${READING_LINE * 100}%` }}
+      />
+      <div
+        ref={contentRef}
+        className="absolute left-0 right-0 px-8 pointer-events-none"
+        style={{ top: `${READING_LINE * 100}%`, transform: `translateY(${-position}px)` }}
+      >
+        {lines.map((line, i) => (
+          <p
+            key={i}
+            ref={(el) => {
+              lineRefs.current[i] = el;
+            }}
+            className="text-2xl leading-snug font-medium mb-4"
+          >
+            {line}
+          </p>
+        ))}
+      </div>
+      <div className="absolute bottom-1.5 right-3 text-[10px] text-white/40 pointer-events-none">
+        ↑ ↓ pour ajuster
+      </div>
+    </div>
+  );
+}
@@ -1,5 +1,11 @@
 import { useState, useRef, useEffect } from "react";
 import { convertFileSrc } from "@tauri-apps/api/core";
+import { useAppStore } from "../../stores/appStore";
+import { getSafeAreaGuides } from "../../lib/tauri";
+import type { SafeAreaGuide } from "../../lib/types";
+
+/** Guide id standing for the selected export preset's own safe area */
+const EXPORT_GUIDE = "export";
 
 interface Props {
   filePath: string;
@@ -9,9 +15,91 @@ interface Props {
 export function VideoPreview({ filePath, onClose }: Props) {
   const videoRef = useRef<HTMLVideoElement>(null);
   const [error, setError] = useState(false);
+  const [voiceoverError, setVoiceoverError] = useState<string | null>(null);
+  const voiceovers = useAppStore((s) => s.voiceovers);
+  const voiceoverRecording = useAppStore((s) => s.voiceoverRecording);
+  const startVoiceover = useAppStore((s) => s.startVoiceover);
+  const stopVoiceover = useAppStore((s) => s.stopVoiceover);
+  const deleteVoiceover = useAppStore((s) => s.deleteVoiceover);
+  const [guides, setGuides] = useState<SafeAreaGuide[]>([]);
+  // The safe area the selected export preset keeps keystrokes and subtitles in
+  const exportGuide = useAppStore((s) => {
+    const preset = s.exportPresets.find((p) => p.id === s.exportPreset);
+    const area = preset?.output.safe_area;
+    if (!preset || !area || ![area.top, area.right, area.bottom, area.left].some((m) => m > 0)) return null;
+    return preset;
+  });
+  const [guideId, setGuideId] = useState(exportGuide ? EXPORT_GUIDE : "");
+  const guide =
+    guideId === EXPORT_GUIDE && exportGuide
+      ? {
+          id: EXPORT_GUIDE,
+          name: exportGuide.name,
+          width: exportGuide.output.width ?? 0,
+          height: exportGuide.output.height ?? 0,
+          safe_area: exportGuide.output.safe_area,
+          grid_columns: 3,
+          grid_rows: 3,
+        }
+      : guides.find((g) => g.id === guideId) ?? null;
+
+  useEffect(() => {
+    getSafeAreaGuides().then(setGuides).catch(() => {});
+  }, []);
 
   const videoSrc = convertFileSrc(filePath);
 
+  // Pausing the video stops the narration, and stopping it pauses the video
+  const recordingRef = useRef(voiceoverRecording);
+  recordingRef.current = voiceoverRecording;
+  const stoppingRef = useRef(false);
+
+  const endVoiceover = async () => {
+    if (!recordingRef.current || stoppingRef.current) return;
+    stoppingRef.current = true;
+    const video = videoRef.current;
+    if (video) {
+      video.pause();
+      video.muted = false;
+    }
+    try {
+      await stopVoiceover();
+    } catch (e) {
+      setVoiceoverError(String(e));
+    } finally {
+      stoppingRef.current = false;
+    }
+  };
+
+  // Narrate from the current position: the mic records while the preview
+  // plays muted, so the playback isn't picked up again
+  const beginVoiceover = async () => {
+    const video = videoRef.current;
+    if (!video) return;
+    setVoiceoverError(null);
+    video.pause();
+    try {
+      await startVoiceover(Math.round(video.currentTime * 1000));
+    } catch (e) {
+      setVoiceoverError(String(e));
+      return;
+    }
+    video.muted = true;
+    await video.play();
+  };
+
+  // Closing the preview ends the narration
+  useEffect(() => {
+    return () => {
+      if (recordingRef.current) stopVoiceover().catch(() => {});
+    };
+  }, [stopVoiceover]);
+
+  const formatTime = (ms: number) => {
+    const s = Math.floor(ms / 1000);
+    return `${Math.floor(s / 60)}:${(s % 60).toString().padStart(2, "0")}`;
+  };
+
   // Close on ESC
   useEffect(() => {
     const handler = (e: KeyboardEvent) => {
@@ -34,6 +122,32 @@ export function VideoPreview({ filePath, onClose }: Props) {
           <span className="text-sm text-zinc-300 font-medium truncate max-w-[400px]">
             {extractFilename(filePath)}
           </span>
+          <select
+            value={guideId}
+            onChange={(e) => setGuideId(e.target.value)}
+            className="ml-auto mr-2 px-2 h-8 rounded-lg text-xs bg-zinc-800 text-zinc-300 border border-zinc-700"
+            title="Zone laissée libre par l'interface de la plateforme, et grille des tiers"
+          >
+            <option value="">Sans repères</option>
+            {exportGuide && <option value={EXPORT_GUIDE}>Export : {exportGuide.name}</option>}
+            {guides.map((g) => (
+              <option key={g.id} value={g.id}>
+                {g.name}
+              </option>
+            ))}
+          </select>
+          <button
+            onClick={voiceoverRecording ? endVoiceover : beginVoiceover}
+            disabled={error}
+            className={`mr-2 px-3 h-8 rounded-lg text-xs font-medium transition-colors disabled:opacity-40 ${
+              voiceoverRecording
+                ? "bg-red-500/20 text-red-400 hover:bg-red-500/30"
+                : "text-zinc-400 hover:bg-zinc-800 hover:text-white"
+            }`}
+            title="Enregistrer le micro pendant la lecture, mixé à l'export"
+          >
+            {voiceoverRecording ? "Arrêter la voix off" : "Voix off"}
+          </button>
           <button
             onClick={onClose}
             className="w-8 h-8 flex items-center justify-center rounded-lg hover:bg-zinc-800 text-zinc-400 hover:text-white transition-colors"
@@ -57,17 +171,61 @@ export function VideoPreview({ filePath, onClose }: Props) {
               Impossible de lire la vidéo
             </div>
           ) : (
-            <video
-              ref={videoRef}
-              src={videoSrc}
-              controls
-              autoPlay
-              className="max-w-full max-h-[70vh] outline-none"
-              onError={() => setError(true)}
-            />
+            <div className="relative">
+              <video
+                ref={videoRef}
+                src={videoSrc}
+                controls
+                autoPlay
+                className="block max-w-full max-h-[70vh] outline-none"
+                onError={() => setError(true)}
+                onPause={endVoiceover}
+              />
+              {guide && <GuideOverlay guide={guide} />}
+            </div>
           )}
         </div>
+
+        {/* Voiceovers */}
+        {(voiceovers.length > 0 || voiceoverError) && (
+          <div className="px-4 py-2 border-t border-zinc-800 flex flex-wrap items-center gap-2 text-xs">
+            {voiceoverError && <span className="text-red-400">{voiceoverError}</span>}
+            {voiceovers.map((v) => (
+              <span key={v.id} className="flex items-center gap-1 px-2 py-1 rounded-md bg-zinc-800 text-zinc-300">
+                Voix off {formatTime(v.start_ms)}-{formatTime(v.start_ms + v.duration_ms)}
+                <button
+                  onClick={() => deleteVoiceover(v.id).catch((e) => setVoiceoverError(String(e)))}
+                  className="text-zinc-500 hover:text-red-400"
+                  title="Supprimer"
+                >
+                  ×
+                </button>
+              </span>
+            ))}
+          </div>
+        )}
       </div>
     </div>
   );
 }
+
+/** Grid over the whole frame and the platform's safe area, drawn over the video */
+function GuideOverlay({ guide }: { guide: SafeAreaGuide }) {
+  const { top, right, bottom, left } = guide.safe_area;
+  const lines = (count: number) => Array.from({ length: Math.max(0, count - 1) }, (_, i) => ((i + 1) * 100) / count);
+
+  return (
+    <div className="absolute inset-0 pointer-events-none">
+      {lines(guide.grid_columns).map((x) => (
+        <div key={`c${x}`} className="absolute top-0 bottom-0 border-l border-white/25" style={{ left: `${x}%` }} />
+      ))}
+      {lines(guide.grid_rows).map((y) => (
+        <div key={`r${y}`} className="absolute left-0 right-0 border-t border-white/25" style={{ top: `${y}%` }} />
+      ))}
+      <div
+        className="absolute border-2 border-dashed border-amber-400/80"
+        style={{ top: `${top * 100}%`, right: `${right * 100}%`, bottom: `${bottom * 100}%`, left: `${left * 100}%` }}
+      />
+    </div>
+  );
+}
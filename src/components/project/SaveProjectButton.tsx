@@ -2,12 +2,14 @@ import { useState } from "react";
 
 interface Props {
   onSave: (name: string) => void;
+  cursorSidecar: boolean;
+  onCursorSidecarChange: (enabled: boolean) => void;
   onListOpen: () => void;
   hasClips: boolean;
   disabled?: boolean;
 }
 
-export function SaveProjectButton({ onSave, onListOpen, hasClips, disabled }: Props) {
+export function SaveProjectButton({ onSave, cursorSidecar, onCursorSidecarChange, onListOpen, hasClips, disabled }: Props) {
   const [showInput, setShowInput] = useState(false);
   const [name, setName] = useState("");
 
@@ -56,6 +58,18 @@ export function SaveProjectButton({ onSave, onListOpen, hasClips, disabled }: Pr
             placeholder="Nom du projet"
             autoFocus
           />
+          <label
+            className="flex items-center gap-1 text-[10px] text-zinc-400 dark:text-zinc-500 cursor-pointer"
+            title="Enregistre le trajet du curseur dans un fichier binaire .cursor, plus compact que le JSON du projet"
+          >
+            <input
+              type="checkbox"
+              checked={cursorSidecar}
+              onChange={(e) => onCursorSidecarChange(e.target.checked)}
+              className="rounded"
+            />
+            Curseur à part
+          </label>
           <button
             onClick={() => setShowInput(false)}
             className="text-zinc-400 hover:text-zinc-600 text-xs"
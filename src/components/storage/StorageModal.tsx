@@ -0,0 +1,158 @@
+import { useCallback, useEffect, useState } from "react";
+import {
+  clearOrphanedTemp,
+  clearPreviews,
+  getStoragePolicy,
+  getStorageUsage,
+  setStoragePolicy,
+} from "../../lib/tauri";
+import type { StorageEntry, StoragePolicy, StorageUsage } from "../../lib/types";
+
+interface Props {
+  onClose: () => void;
+}
+
+function formatSize(bytes: number): string {
+  if (bytes >= 1024 * 1024 * 1024) return `${(bytes / (1024 * 1024 * 1024)).toFixed(2)} Go`;
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} Mo`;
+  return `${Math.round(bytes / 1024)} Ko`;
+}
+
+const CATEGORIES: { key: keyof StorageUsage; label: string }[] = [
+  { key: "clips", label: "Clips temporaires" },
+  { key: "thumbnails", label: "Miniatures" },
+  { key: "proxies", label: "Rendus en cache" },
+  { key: "previews", label: "Aperçus" },
+];
+
+export function StorageModal({ onClose }: Props) {
+  const [usage, setUsage] = useState<StorageUsage | null>(null);
+  const [policy, setPolicy] = useState<StoragePolicy | null>(null);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const refresh = useCallback(() => {
+    getStorageUsage().then(setUsage).catch((e) => setError(String(e)));
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    getStoragePolicy().then(setPolicy).catch((e) => setError(String(e)));
+  }, [refresh]);
+
+  useEffect(() => {
+    const handler = (e: KeyboardEvent) => {
+      if (e.key === "Escape") onClose();
+    };
+    document.addEventListener("keydown", handler);
+    return () => document.removeEventListener("keydown", handler);
+  }, [onClose]);
+
+  const clear = async (action: () => Promise<StorageEntry>) => {
+    try {
+      setError(null);
+      const freed = await action();
+      setMessage(`${freed.files} fichier(s) supprimé(s), ${formatSize(freed.bytes)} libérés`);
+      refresh();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const updatePolicy = (next: StoragePolicy) => {
+    setPolicy(next);
+    setStoragePolicy(next).catch((e) => setError(String(e)));
+  };
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm animate-fade-in"
+      onClick={(e) => { if (e.target === e.currentTarget) onClose(); }}
+    >
+      <div className="bg-white dark:bg-zinc-800 border border-zinc-200 dark:border-zinc-700 rounded-2xl shadow-2xl p-5 w-full max-w-md mx-4 flex flex-col gap-3 animate-fade-in">
+        <h3 className="text-sm font-semibold text-zinc-800 dark:text-zinc-200">
+          Stockage
+        </h3>
+
+        {usage === null ? (
+          <p className="text-sm text-zinc-400 dark:text-zinc-500 text-center py-4">Chargement…</p>
+        ) : (
+          <div className="space-y-1.5">
+            {CATEGORIES.map(({ key, label }) => (
+              <div key={key} className="flex items-center justify-between text-xs">
+                <span className="text-zinc-500 dark:text-zinc-400">{label}</span>
+                <span className="font-mono text-zinc-700 dark:text-zinc-300">
+                  {formatSize(usage[key].bytes)}
+                  <span className="text-zinc-400 dark:text-zinc-500"> · {usage[key].files}</span>
+                </span>
+              </div>
+            ))}
+            <div className="flex items-center justify-between text-xs pt-1.5 border-t border-zinc-100 dark:border-zinc-700">
+              <span className="text-zinc-500 dark:text-zinc-400" title="Fichiers qu'aucun clip ni projet n'utilise">
+                Dont orphelins
+              </span>
+              <span className="font-mono text-zinc-700 dark:text-zinc-300">{formatSize(usage.orphaned.bytes)}</span>
+            </div>
+          </div>
+        )}
+
+        <div className="flex gap-2">
+          <button
+            onClick={() => clear(clearPreviews)}
+            disabled={!usage || usage.previews.files === 0}
+            className="flex-1 px-3 py-1.5 rounded-lg text-xs bg-zinc-100 dark:bg-zinc-700 hover:bg-zinc-200 dark:hover:bg-zinc-600 text-zinc-700 dark:text-zinc-200 disabled:opacity-40 transition-colors"
+          >
+            Vider les aperçus
+          </button>
+          <button
+            onClick={() => clear(clearOrphanedTemp)}
+            disabled={!usage || usage.orphaned.files === 0}
+            className="flex-1 px-3 py-1.5 rounded-lg text-xs bg-zinc-100 dark:bg-zinc-700 hover:bg-zinc-200 dark:hover:bg-zinc-600 text-zinc-700 dark:text-zinc-200 disabled:opacity-40 transition-colors"
+          >
+            Supprimer les orphelins
+          </button>
+        </div>
+
+        {policy && (
+          <div className="flex flex-col gap-2 pt-2 border-t border-zinc-100 dark:border-zinc-700">
+            <label className="flex items-center justify-between gap-2 text-xs text-zinc-500 dark:text-zinc-400">
+              Supprimer au démarrage les fichiers de plus de
+              <span className="flex items-center gap-1">
+                <input
+                  type="number"
+                  min={0}
+                  value={policy.max_age_hours}
+                  onChange={(e) => updatePolicy({ ...policy, max_age_hours: Math.max(0, Math.round(Number(e.target.value) || 0)) })}
+                  className="w-16 px-2 py-1 rounded-md text-xs font-mono bg-zinc-50 dark:bg-zinc-900 text-zinc-800 dark:text-zinc-200 border border-zinc-200 dark:border-zinc-700"
+                />
+                h
+              </span>
+            </label>
+            <p className="text-[10px] text-zinc-400 dark:text-zinc-500">0 pour tout garder</p>
+            <label className="flex items-center gap-2 text-xs text-zinc-500 dark:text-zinc-400 cursor-pointer">
+              <input
+                type="checkbox"
+                checked={policy.keep_project_media}
+                onChange={(e) => updatePolicy({ ...policy, keep_project_media: e.target.checked })}
+                className="rounded"
+              />
+              Garder les vidéos des projets enregistrés
+            </label>
+          </div>
+        )}
+
+        {message && <p className="text-xs text-green-600 dark:text-green-400">{message}</p>}
+        {error && <p className="text-xs text-red-500">{error}</p>}
+
+        <div className="flex justify-end">
+          <button
+            onClick={onClose}
+            className="px-3 py-1.5 rounded-lg text-xs text-zinc-500 hover:bg-zinc-100 dark:hover:bg-zinc-700 transition-colors"
+          >
+            Fermer
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,6 +1,19 @@
 import { useState, useEffect } from "react";
-import type { Subtitle, SubtitlePosition } from "../../lib/types";
-import { getSubtitles, setSubtitles } from "../../lib/tauri";
+import type { FontFile, Subtitle, SubtitleIssue, SubtitleLanguage, SubtitlePosition, SubtitleReading } from "../../lib/types";
+import {
+  addSubtitleLanguage,
+  fitSubtitles,
+  getSubtitleLanguages,
+  getSubtitles,
+  listFonts,
+  removeSubtitleLanguage,
+  setActiveSubtitleLanguage,
+  setSubtitles,
+  shiftSubtitles,
+  suggestSubtitleDurations,
+} from "../../lib/tauri";
+
+const languageLabel = (code: string) => (code === "und" ? "Langue ?" : code.toUpperCase());
 
 interface Props {
   totalDurationMs: number;
@@ -10,11 +23,26 @@ interface Props {
 export function SubtitleEditor({ totalDurationMs, onClose }: Props) {
   const [subs, setSubs] = useState<Subtitle[]>([]);
   const [editingId, setEditingId] = useState<string | null>(null);
+  const [fonts, setFonts] = useState<FontFile[]>([]);
+  const [issues, setIssues] = useState<SubtitleIssue[] | null>(null);
+  const [shiftSeconds, setShiftSeconds] = useState("0");
+  const [readings, setReadings] = useState<Record<string, SubtitleReading>>({});
+  const [languages, setLanguages] = useState<SubtitleLanguage[]>([]);
+  const [newLanguage, setNewLanguage] = useState("");
 
   useEffect(() => {
     getSubtitles().then(setSubs).catch(console.error);
+    getSubtitleLanguages().then(setLanguages).catch(console.error);
+    listFonts().then(setFonts).catch(console.error);
   }, []);
 
+  // Reading time of the lines as they are typed
+  useEffect(() => {
+    suggestSubtitleDurations(subs)
+      .then((list) => setReadings(Object.fromEntries(list.map((r) => [r.subtitle_id, r]))))
+      .catch(console.error);
+  }, [subs]);
+
   useEffect(() => {
     const handler = (e: KeyboardEvent) => {
       if (e.key === "Escape") onClose();
@@ -51,6 +79,67 @@ export function SubtitleEditor({ totalDurationMs, onClose }: Props) {
     onClose();
   };
 
+  // Both work on the saved list, so the edits in progress go first
+  const handleFit = async () => {
+    try {
+      await setSubtitles(subs);
+      const result = await fitSubtitles();
+      setSubs(result.subtitles);
+      setIssues(result.issues);
+    } catch (e) {
+      console.error(e);
+    }
+  };
+
+  // The lines being edited are saved to their language before switching
+  const switchLanguage = async (language: string) => {
+    try {
+      await setSubtitles(subs);
+      setSubs(await setActiveSubtitleLanguage(language));
+      setLanguages(await getSubtitleLanguages());
+      setEditingId(null);
+      setIssues(null);
+    } catch (e) {
+      console.error(e);
+    }
+  };
+
+  const handleAddLanguage = async () => {
+    const code = newLanguage.trim();
+    if (!code) return;
+    try {
+      await setSubtitles(subs);
+      await addSubtitleLanguage(code);
+      setNewLanguage("");
+      // Translated from a copy of the active lines
+      await switchLanguage(code.toLowerCase());
+    } catch (e) {
+      console.error(e);
+    }
+  };
+
+  const handleRemoveLanguage = async (language: SubtitleLanguage) => {
+    try {
+      await setSubtitles(subs);
+      setLanguages(await removeSubtitleLanguage(language.language));
+      if (language.active) setSubs(await getSubtitles());
+    } catch (e) {
+      console.error(e);
+    }
+  };
+
+  const handleShift = async () => {
+    const seconds = parseFloat(shiftSeconds.replace(",", "."));
+    if (isNaN(seconds) || seconds === 0) return;
+    try {
+      await setSubtitles(subs);
+      setSubs(await shiftSubtitles(Math.round(seconds * 1000)));
+      setIssues(null);
+    } catch (e) {
+      console.error(e);
+    }
+  };
+
   const formatTime = (ms: number) => {
     const s = Math.floor(ms / 1000);
     const m = Math.floor(s / 60);
@@ -95,6 +184,52 @@ export function SubtitleEditor({ totalDurationMs, onClose }: Props) {
           </div>
         </div>
 
+        {/* Languages, the active one edited and burned in */}
+        <div className="flex items-center gap-1.5 mb-3 flex-wrap">
+          {languages.map((lang) => (
+            <div
+              key={lang.language}
+              className={`flex items-center rounded-lg text-[11px] font-medium ${
+                lang.active
+                  ? "bg-blue-500 text-white"
+                  : "bg-zinc-100 dark:bg-zinc-700 text-zinc-600 dark:text-zinc-300"
+              }`}
+            >
+              <button
+                onClick={() => switchLanguage(lang.language)}
+                className="pl-2 pr-1 py-1"
+                title={`${lang.count} sous-titre(s)`}
+              >
+                {languageLabel(lang.language)}
+              </button>
+              {languages.length > 1 && (
+                <button
+                  onClick={() => handleRemoveLanguage(lang)}
+                  className="pr-2 pl-0.5 py-1 opacity-60 hover:opacity-100"
+                  title="Supprimer cette langue"
+                >
+                  ×
+                </button>
+              )}
+            </div>
+          ))}
+          <input
+            type="text"
+            value={newLanguage}
+            onChange={(e) => setNewLanguage(e.target.value)}
+            onKeyDown={(e) => { if (e.key === "Enter") handleAddLanguage(); }}
+            placeholder="en, es…"
+            className="w-16 text-xs text-center bg-white dark:bg-zinc-800 border border-zinc-300 dark:border-zinc-600 rounded px-1 py-0.5 focus:outline-none focus:ring-1 focus:ring-blue-500"
+            title="Ajouter une langue, à partir d'une copie des sous-titres affichés"
+          />
+          <button
+            onClick={handleAddLanguage}
+            className="px-2 py-1 rounded-lg text-[11px] font-medium bg-zinc-100 dark:bg-zinc-700 text-zinc-600 dark:text-zinc-300 hover:bg-zinc-200 dark:hover:bg-zinc-600 transition-colors"
+          >
+            + Langue
+          </button>
+        </div>
+
         {/* Mini timeline */}
         {subs.length > 0 && totalDurationMs > 0 && (
           <div className="h-6 bg-zinc-100 dark:bg-zinc-700 rounded-lg mb-3 relative overflow-hidden cursor-pointer">
@@ -118,6 +253,54 @@ export function SubtitleEditor({ totalDurationMs, onClose }: Props) {
           </div>
         )}
 
+        {subs.length > 0 && (
+          <div className="flex items-center gap-2 mb-3">
+            <button
+              onClick={handleFit}
+              className="px-2 py-1 rounded-lg text-[11px] font-medium bg-zinc-100 dark:bg-zinc-700 text-zinc-600 dark:text-zinc-300 hover:bg-zinc-200 dark:hover:bg-zinc-600 transition-colors"
+              title="Corriger les chevauchements, les sous-titres trop courts et ceux qui dépassent la fin"
+            >
+              Ajuster le timing
+            </button>
+            <div className="flex items-center gap-1 ml-auto">
+              <input
+                type="text"
+                value={shiftSeconds}
+                onChange={(e) => setShiftSeconds(e.target.value)}
+                className="w-12 text-xs font-mono text-center bg-white dark:bg-zinc-800 border border-zinc-300 dark:border-zinc-600 rounded px-1 py-0.5 focus:outline-none focus:ring-1 focus:ring-blue-500"
+                title="Secondes, négatif pour avancer"
+              />
+              <span className="text-[10px] text-zinc-400">s</span>
+              <button
+                onClick={handleShift}
+                className="px-2 py-1 rounded-lg text-[11px] font-medium bg-zinc-100 dark:bg-zinc-700 text-zinc-600 dark:text-zinc-300 hover:bg-zinc-200 dark:hover:bg-zinc-600 transition-colors"
+              >
+                Décaler tout
+              </button>
+            </div>
+          </div>
+        )}
+
+        {issues && (
+          <div className="mb-3 px-3 py-2 rounded-xl text-[11px] bg-zinc-50 dark:bg-zinc-700/50 text-zinc-500 dark:text-zinc-400">
+            {issues.length === 0 ? (
+              <p>Timing correct</p>
+            ) : (
+              <ul className="space-y-0.5">
+                {issues.map((issue, i) => (
+                  <li
+                    key={i}
+                    onClick={() => setEditingId(issue.subtitle_id)}
+                    className="text-amber-600 dark:text-amber-400 cursor-pointer hover:underline"
+                  >
+                    {issue.message}
+                  </li>
+                ))}
+              </ul>
+            )}
+          </div>
+        )}
+
         <div className="space-y-2 overflow-y-auto flex-1 mb-3">
           {subs.map((sub) => (
             <div
@@ -194,6 +377,27 @@ export function SubtitleEditor({ totalDurationMs, onClose }: Props) {
                     />
                   </div>
 
+                  {/* Reading speed */}
+                  {readings[sub.id] && (
+                    <div className="flex items-center justify-between text-[10px]">
+                      <span className={readings[sub.id].too_fast ? "text-amber-600 dark:text-amber-400" : "text-zinc-400"}>
+                        {readings[sub.id].chars_per_second.toFixed(0)} car./s
+                        {readings[sub.id].too_fast && ` : trop rapide, ${(readings[sub.id].suggested_ms / 1000).toFixed(1)} s conseillées`}
+                      </span>
+                      {readings[sub.id].too_fast && (
+                        <button
+                          onClick={(e) => {
+                            e.stopPropagation();
+                            updateSubtitle(sub.id, { end_ms: Math.min(sub.start_ms + readings[sub.id].suggested_ms, totalDurationMs || Infinity) });
+                          }}
+                          className="px-1.5 py-0.5 rounded bg-zinc-200 dark:bg-zinc-600 text-zinc-600 dark:text-zinc-300 font-medium"
+                        >
+                          Allonger
+                        </button>
+                      )}
+                    </div>
+                  )}
+
                   {/* Position buttons */}
                   <div className="flex items-center gap-2">
                     <span className="text-[10px] text-zinc-400">Position :</span>
@@ -240,6 +444,18 @@ export function SubtitleEditor({ totalDurationMs, onClose }: Props) {
                     </div>
                   </div>
 
+                  {/* Font */}
+                  <select
+                    value={sub.font_file ?? ""}
+                    onChange={(e) => updateSubtitle(sub.id, { font_file: e.target.value || null })}
+                    className="w-full px-2 py-1.5 rounded-lg text-xs bg-white dark:bg-zinc-800 text-zinc-600 dark:text-zinc-300 border border-zinc-200 dark:border-zinc-700"
+                  >
+                    <option value="">Police par défaut</option>
+                    {fonts.map((f) => (
+                      <option key={f.path} value={f.path}>{f.name}</option>
+                    ))}
+                  </select>
+
                   {/* Delete button */}
                   <div className="flex justify-end">
                     <button
@@ -262,6 +478,9 @@ export function SubtitleEditor({ totalDurationMs, onClose }: Props) {
                       style={{ backgroundColor: sub.color }}
                     />
                     <span className="text-sm text-zinc-700 dark:text-zinc-300 truncate">{sub.text}</span>
+                    {readings[sub.id]?.too_fast && (
+                      <span className="w-1.5 h-1.5 rounded-full bg-amber-500 shrink-0" title="Trop rapide à lire" />
+                    )}
                   </div>
                   <div className="flex items-center gap-2 ml-2 shrink-0">
                     <span className="text-[9px] text-zinc-400 font-mono">{sub.font_size}px</span>
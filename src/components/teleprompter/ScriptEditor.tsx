@@ -0,0 +1,84 @@
+import { useEffect, useState } from "react";
+import type { TeleprompterSettings } from "../../lib/types";
+
+const DEFAULT_WPM = 140;
+
+interface Props {
+  settings: TeleprompterSettings | null;
+  onSave: (settings: TeleprompterSettings | null) => void;
+  onClose: () => void;
+}
+
+export function ScriptEditor({ settings, onSave, onClose }: Props) {
+  const [script, setScript] = useState(settings?.script ?? "");
+  const [wpm, setWpm] = useState(settings?.words_per_minute ?? DEFAULT_WPM);
+
+  useEffect(() => {
+    const handler = (e: KeyboardEvent) => {
+      if (e.key === "Escape") onClose();
+    };
+    document.addEventListener("keydown", handler);
+    return () => document.removeEventListener("keydown", handler);
+  }, [onClose]);
+
+  const words = script.split(/\s+/).filter(Boolean).length;
+  const minutes = words / Math.max(wpm, 1);
+
+  const save = () => {
+    onSave(script.trim() ? { script, words_per_minute: wpm } : null);
+    onClose();
+  };
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm animate-fade-in"
+      onClick={(e) => { if (e.target === e.currentTarget) onClose(); }}
+    >
+      <div className="bg-white dark:bg-zinc-800 border border-zinc-200 dark:border-zinc-700 rounded-2xl shadow-2xl p-5 w-full max-w-lg mx-4 flex flex-col gap-3 animate-fade-in">
+        <h3 className="text-sm font-semibold text-zinc-800 dark:text-zinc-200">
+          Prompteur
+        </h3>
+        <p className="text-xs text-zinc-400 dark:text-zinc-500">
+          Le texte défile au-dessus de l'écran pendant l'enregistrement, sans apparaître dans la vidéo. Une ligne par phrase.
+        </p>
+        <textarea
+          value={script}
+          onChange={(e) => setScript(e.target.value)}
+          rows={10}
+          placeholder="Bonjour à tous, aujourd'hui…"
+          className="w-full px-3 py-2 rounded-lg text-sm bg-zinc-50 dark:bg-zinc-900 text-zinc-800 dark:text-zinc-200 border border-zinc-200 dark:border-zinc-700 resize-none"
+        />
+        <div className="flex items-center gap-2 text-xs text-zinc-500 dark:text-zinc-400">
+          <span>Vitesse</span>
+          <input
+            type="number"
+            min={60}
+            max={300}
+            step={10}
+            value={wpm}
+            onChange={(e) => setWpm(Number(e.target.value) || DEFAULT_WPM)}
+            className="w-16 px-1.5 py-1 rounded bg-zinc-100 dark:bg-zinc-700 text-zinc-700 dark:text-zinc-200 border border-zinc-300 dark:border-zinc-600"
+          />
+          <span>mots/min</span>
+          <span className="ml-auto">
+            {words} mots · ~{minutes < 1 ? `${Math.round(minutes * 60)} s` : `${minutes.toFixed(1)} min`}
+          </span>
+        </div>
+        <div className="flex justify-end gap-2">
+          <button
+            onClick={onClose}
+            className="px-3 py-1.5 rounded-lg text-xs text-zinc-500 hover:bg-zinc-100 dark:hover:bg-zinc-700 transition-colors"
+          >
+            Annuler
+          </button>
+          <button
+            onClick={save}
+            className="px-3 py-1.5 rounded-lg text-xs bg-blue-500 hover:bg-blue-600 text-white transition-colors"
+          >
+            Enregistrer
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,11 +1,18 @@
 import { useEffect, useRef, useState } from "react";
 import { useSortable } from "@dnd-kit/sortable";
 import { CSS } from "@dnd-kit/utilities";
-import type { Clip } from "../../lib/types";
-import { getThumbnailBase64 } from "../../lib/tauri";
+import type { Clip, DualLayout } from "../../lib/types";
+import { exportCursorHeatmap, extractFrame, getThumbnailBase64, renderWaveformImage, revealInExplorer } from "../../lib/tauri";
+import { useAppStore } from "../../stores/appStore";
 import { TrimModal } from "./TrimModal";
 import { AnnotationEditor } from "../annotations/AnnotationEditor";
 
+const DUAL_LAYOUTS: { value: DualLayout; label: string }[] = [
+  { value: "side_by_side", label: "Côte à côte" },
+  { value: "stacked", label: "Empilé" },
+  { value: "pip", label: "Incrusté" },
+];
+
 interface Props {
   clip: Clip;
   index: number;
@@ -16,10 +23,19 @@ interface Props {
 export function SortableClipCard({ clip, index, onDelete, onTrim }: Props) {
   const [thumbSrc, setThumbSrc] = useState<string | null>(null);
   const [thumbFailed, setThumbFailed] = useState(false);
+  const [waveSrc, setWaveSrc] = useState<string | null>(null);
   const [confirmDelete, setConfirmDelete] = useState(false);
   const [showTrim, setShowTrim] = useState(false);
   const [showAnnotations, setShowAnnotations] = useState(false);
+  const [heatmapError, setHeatmapError] = useState<string | null>(null);
   const confirmTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
+  const setClipDualLayout = useAppStore((s) => s.setClipDualLayout);
+  const regenerateThumbnail = useAppStore((s) => s.regenerateThumbnail);
+  const insertFreeze = useAppStore((s) => s.insertFreeze);
+  const recordRetake = useAppStore((s) => s.recordRetake);
+  const selectTake = useAppStore((s) => s.selectTake);
+  const setAudioOffset = useAppStore((s) => s.setAudioOffset);
+  const recordingState = useAppStore((s) => s.recordingState);
 
   const {
     attributes,
@@ -47,7 +63,17 @@ export function SortableClipCard({ clip, index, onDelete, onTrim }: Props) {
       if (mounted) setThumbFailed(true);
     });
     return () => { mounted = false; };
-  }, [clip.id]);
+  }, [clip.id, clip.thumbnail_path]);
+
+  const hasSound = clip.has_audio || clip.audio_paths.length > 0;
+  useEffect(() => {
+    if (!hasSound) return;
+    let mounted = true;
+    renderWaveformImage(clip.id, 352, 32)
+      .then((src) => { if (mounted) setWaveSrc(src); })
+      .catch(() => {});
+    return () => { mounted = false; };
+  }, [clip.id, hasSound]);
 
   useEffect(() => {
     return () => {
@@ -76,6 +102,34 @@ export function SortableClipCard({ clip, index, onDelete, onTrim }: Props) {
     setShowAnnotations(true);
   };
 
+  const handleHeatmapClick = (e: React.MouseEvent) => {
+    e.stopPropagation();
+    setHeatmapError(null);
+    exportCursorHeatmap(clip.id, true)
+      .then((paths) => revealInExplorer(paths[paths.length - 1]))
+      .catch((err) => setHeatmapError(String(err)));
+  };
+
+  const handleRetakeClick = (e: React.MouseEvent) => {
+    e.stopPropagation();
+    recordRetake(clip.id).catch(console.error);
+  };
+
+  // Takes in recording order, the played one found by its file
+  const takes = clip.takes ?? [];
+  const takeIndex = takes.findIndex((t) => t.path === clip.path);
+  const handleTakeClick = (e: React.MouseEvent) => {
+    e.stopPropagation();
+    selectTake(clip.id, (takeIndex + 1) % takes.length).catch(console.error);
+  };
+
+  const handleLayoutClick = (e: React.MouseEvent) => {
+    e.stopPropagation();
+    if (!clip.secondary) return;
+    const i = DUAL_LAYOUTS.findIndex((l) => l.value === clip.secondary?.layout);
+    setClipDualLayout(clip.id, DUAL_LAYOUTS[(i + 1) % DUAL_LAYOUTS.length].value).catch(console.error);
+  };
+
   const isTrimmed = clip.trim_start_ms > 0 || clip.trim_end_ms > 0;
   const effectiveDuration = isTrimmed
     ? ((clip.trim_end_ms || clip.duration_ms) - clip.trim_start_ms) / 1000
@@ -118,6 +172,28 @@ export function SortableClipCard({ clip, index, onDelete, onTrim }: Props) {
             </div>
           )}
 
+          {/* Second region: click to cycle the export layout */}
+          {clip.secondary && (
+            <button
+              onClick={handleLayoutClick}
+              className="absolute top-1 right-1 px-1.5 py-0.5 bg-violet-500/80 hover:bg-violet-500 rounded text-[9px] text-white font-medium transition-colors"
+              title="Disposition des deux zones à l'export"
+            >
+              2 zones · {DUAL_LAYOUTS.find((l) => l.value === clip.secondary?.layout)?.label}
+            </button>
+          )}
+
+          {/* Takes of this slot: click to play the next one */}
+          {takes.length > 1 && (
+            <button
+              onClick={handleTakeClick}
+              className="absolute bottom-1 left-1 px-1.5 py-0.5 bg-amber-500/80 hover:bg-amber-500 rounded text-[9px] text-white font-medium transition-colors"
+              title="Changer de prise"
+            >
+              Prise {takeIndex + 1}/{takes.length}
+            </button>
+          )}
+
           {/* Action buttons overlay */}
           <div className="absolute bottom-1 right-1 flex gap-1 opacity-0 group-hover:opacity-100 transition-all">
             <button
@@ -130,6 +206,28 @@ export function SortableClipCard({ clip, index, onDelete, onTrim }: Props) {
                 <path d="M18 13l-1.5-7.5L2 2l3.5 14.5L13 18l5-5z" />
               </svg>
             </button>
+            <button
+              onClick={handleHeatmapClick}
+              className={`w-6 h-6 flex items-center justify-center rounded text-white transition-all ${heatmapError ? "bg-red-500/80 hover:bg-red-500" : "bg-black/50 hover:bg-black/70"}`}
+              title={heatmapError ?? "Carte de chaleur du curseur"}
+            >
+              <svg width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+                <circle cx="12" cy="12" r="9" />
+                <circle cx="12" cy="12" r="5" />
+                <circle cx="12" cy="12" r="1" />
+              </svg>
+            </button>
+            <button
+              onClick={handleRetakeClick}
+              disabled={recordingState !== "idle"}
+              className="w-6 h-6 flex items-center justify-center rounded bg-black/50 hover:bg-black/70 text-white transition-all disabled:opacity-50"
+              title="Refaire une prise"
+            >
+              <svg width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+                <path d="M3 12a9 9 0 1 0 3-6.7" />
+                <polyline points="3 3 3 9 9 9" />
+              </svg>
+            </button>
             <button
               onClick={handleTrimClick}
               className="w-6 h-6 flex items-center justify-center rounded bg-black/50 hover:bg-black/70 text-white transition-all"
@@ -146,6 +244,22 @@ export function SortableClipCard({ clip, index, onDelete, onTrim }: Props) {
           </div>
         </div>
 
+        {/* Waveform, cropped to the kept part */}
+        {waveSrc && (
+          <div className="h-4 bg-zinc-50 dark:bg-zinc-950 overflow-hidden relative">
+            <img
+              src={waveSrc}
+              alt=""
+              className="absolute top-0 h-full max-w-none"
+              style={{
+                width: `${(100 * clip.duration_ms) / Math.max(1, effectiveDuration * 1000)}%`,
+                left: `${(-100 * clip.trim_start_ms) / Math.max(1, effectiveDuration * 1000)}%`,
+              }}
+              draggable={false}
+            />
+          </div>
+        )}
+
         {/* Info bar */}
         <div className="px-3 py-2 flex items-center justify-between">
           <div className="flex items-center gap-2">
@@ -174,6 +288,10 @@ export function SortableClipCard({ clip, index, onDelete, onTrim }: Props) {
         <TrimModal
           clip={clip}
           onSave={onTrim}
+          onAudioOffset={(ms) => setAudioOffset(clip.id, ms).catch(console.error)}
+          onThumbnail={(atMs) => regenerateThumbnail(clip.id, atMs).catch(console.error)}
+          onSaveFrame={(atMs) => extractFrame(clip.id, atMs, null, true).then(revealInExplorer).catch(console.error)}
+          onFreeze={(atMs, durationMs) => insertFreeze(clip.id, atMs, durationMs)}
           onClose={() => setShowTrim(false)}
         />
       )}
@@ -1,16 +1,66 @@
 import { useState, useEffect } from "react";
-import type { Clip } from "../../lib/types";
+import { analyzeAudio, detectAutoTrim, getFramesAt, getSpeechSegments } from "../../lib/tauri";
+import type { AudioAdvice, AudioAnalysis, Clip, SpeechSegment } from "../../lib/types";
+
+const FREEZE_DURATIONS = [1000, 2000, 3000, 5000, 10000];
+
+const ADVICE: Record<AudioAdvice, { label: string; className: string }> = {
+  good: { label: "Niveau correct", className: "text-emerald-500" },
+  normalize: { label: "À normaliser", className: "text-amber-500" },
+  retake: { label: "Saturé, à refaire", className: "text-red-500" },
+  silent: { label: "Silencieux", className: "text-zinc-400" },
+};
 
 interface Props {
   clip: Clip;
   onSave: (trimStartMs: number, trimEndMs: number) => void;
+  onAudioOffset: (ms: number) => void;
+  onThumbnail: (atMs: number) => void;
+  onSaveFrame: (atMs: number) => void;
+  onFreeze: (atMs: number, durationMs: number) => Promise<void>;
   onClose: () => void;
 }
 
-export function TrimModal({ clip, onSave, onClose }: Props) {
+export function TrimModal({ clip, onSave, onAudioOffset, onThumbnail, onSaveFrame, onFreeze, onClose }: Props) {
   const maxMs = clip.duration_ms;
   const [trimStart, setTrimStart] = useState(clip.trim_start_ms);
   const [trimEnd, setTrimEnd] = useState(clip.trim_end_ms || maxMs);
+  const [frameAt, setFrameAt] = useState(Math.round(maxMs / 10));
+  const [detecting, setDetecting] = useState(false);
+  const [autoError, setAutoError] = useState<string | null>(null);
+  const [speech, setSpeech] = useState<SpeechSegment[]>([]);
+  const [freezeMs, setFreezeMs] = useState(2000);
+  const [freezing, setFreezing] = useState(false);
+  const [edgeFrames, setEdgeFrames] = useState<string[]>([]);
+  const [audioOffset, setAudioOffset] = useState(clip.audio_offset_ms ?? 0);
+  const [analysis, setAnalysis] = useState<AudioAnalysis[] | null>(null);
+  const [analyzing, setAnalyzing] = useState(false);
+
+  useEffect(() => {
+    getSpeechSegments(clip.id).then(setSpeech).catch(() => {});
+  }, [clip.id]);
+
+  // Nearest sentence boundary before / after `fromMs`
+  const jump = (boundaries: number[], fromMs: number, forward: boolean) =>
+    forward
+      ? boundaries.find((b) => b > fromMs)
+      : [...boundaries].reverse().find((b) => b < fromMs);
+  const starts = speech.map((s) => s.start_ms);
+  const ends = speech.map((s) => s.end_ms);
+
+  // First and last frames the trim keeps, once the sliders settle
+  useEffect(() => {
+    let cancelled = false;
+    const timer = setTimeout(() => {
+      getFramesAt(clip.id, [trimStart, Math.max(trimStart, trimEnd - 1)], 160)
+        .then((frames) => { if (!cancelled) setEdgeFrames(frames); })
+        .catch(() => {});
+    }, 300);
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [clip.id, trimStart, trimEnd]);
 
   useEffect(() => {
     const handler = (e: KeyboardEvent) => {
@@ -32,9 +82,56 @@ export function TrimModal({ clip, onSave, onClose }: Props) {
     const startMs = Math.max(0, trimStart);
     const endMs = trimEnd >= maxMs ? 0 : trimEnd; // 0 means no trim end
     onSave(startMs, endMs);
+    if (audioOffset !== (clip.audio_offset_ms ?? 0)) onAudioOffset(audioOffset);
     onClose();
   };
 
+  // Fills the sliders only, the user still applies
+  const handleAuto = async () => {
+    setDetecting(true);
+    setAutoError(null);
+    try {
+      const [startMs, endMs] = await detectAutoTrim(clip.id);
+      setTrimStart(startMs);
+      setTrimEnd(endMs || maxMs);
+    } catch (e) {
+      setAutoError(String(e));
+    } finally {
+      setDetecting(false);
+    }
+  };
+
+  const handleAnalyze = async () => {
+    setAnalyzing(true);
+    setAutoError(null);
+    try {
+      setAnalysis(await analyzeAudio(clip.id));
+    } catch (e) {
+      setAutoError(String(e));
+    } finally {
+      setAnalyzing(false);
+    }
+  };
+
+  const trackName = (path: string) => {
+    if (path.endsWith("_mic.wav")) return "Micro";
+    if (path.endsWith("_system.wav")) return "Système";
+    return "Audio";
+  };
+
+  // The clip is split around the hold, so the modal has nothing left to edit
+  const handleFreeze = async () => {
+    setFreezing(true);
+    setAutoError(null);
+    try {
+      await onFreeze(frameAt, freezeMs);
+      onClose();
+    } catch (e) {
+      setAutoError(String(e));
+      setFreezing(false);
+    }
+  };
+
   const handleReset = () => {
     onSave(0, 0);
     onClose();
@@ -81,6 +178,17 @@ export function TrimModal({ clip, onSave, onClose }: Props) {
               width: `${((trimEnd - trimStart) / maxMs) * 100}%`,
             }}
           />
+          {/* Where talking happens */}
+          {speech.map((s) => (
+            <div
+              key={s.start_ms}
+              className="absolute bottom-0 h-1.5 bg-emerald-500/70 rounded-sm"
+              style={{
+                left: `${(s.start_ms / maxMs) * 100}%`,
+                width: `${((s.end_ms - s.start_ms) / maxMs) * 100}%`,
+              }}
+            />
+          ))}
         </div>
 
         {/* Start slider */}
@@ -89,7 +197,17 @@ export function TrimModal({ clip, onSave, onClose }: Props) {
             <label className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold">
               Début
             </label>
-            <span className="text-xs font-mono text-zinc-500 dark:text-zinc-400">{formatMs(trimStart)}</span>
+            <span className="flex items-center gap-1">
+              {speech.length > 0 && (
+                <SentenceJump
+                  onJump={(forward) => {
+                    const to = jump(starts, trimStart, forward);
+                    if (to !== undefined && to < trimEnd - 100) setTrimStart(to);
+                  }}
+                />
+              )}
+              <span className="text-xs font-mono text-zinc-500 dark:text-zinc-400">{formatMs(trimStart)}</span>
+            </span>
           </div>
           <input
             type="range"
@@ -111,7 +229,17 @@ export function TrimModal({ clip, onSave, onClose }: Props) {
             <label className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold">
               Fin
             </label>
-            <span className="text-xs font-mono text-zinc-500 dark:text-zinc-400">{formatMs(trimEnd)}</span>
+            <span className="flex items-center gap-1">
+              {speech.length > 0 && (
+                <SentenceJump
+                  onJump={(forward) => {
+                    const to = jump(ends, trimEnd, forward);
+                    if (to !== undefined && to > trimStart + 100) setTrimEnd(to);
+                  }}
+                />
+              )}
+              <span className="text-xs font-mono text-zinc-500 dark:text-zinc-400">{formatMs(trimEnd)}</span>
+            </span>
           </div>
           <input
             type="range"
@@ -127,6 +255,128 @@ export function TrimModal({ clip, onSave, onClose }: Props) {
           />
         </div>
 
+        {edgeFrames.length === 2 && (
+          <div className="grid grid-cols-2 gap-2 mb-4">
+            {edgeFrames.map((src, i) => (
+              <figure key={i}>
+                <img src={src} alt="" className="w-full rounded-md border border-zinc-200 dark:border-zinc-700" />
+                <figcaption className="mt-0.5 text-[10px] text-zinc-400 dark:text-zinc-500 text-center">
+                  {i === 0 ? "Première image" : "Dernière image"}
+                </figcaption>
+              </figure>
+            ))}
+          </div>
+        )}
+
+        {/* Lip-sync or mic latency fix */}
+        {clip.audio_paths.length > 0 && (
+          <div className="mb-4 flex items-center justify-between">
+            <label
+              className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold"
+              title="Positif : le son est joué plus tard que l'image"
+            >
+              Décalage audio
+            </label>
+            <span className="flex items-center gap-1">
+              <input
+                type="number"
+                min={-5000}
+                max={5000}
+                step={10}
+                value={audioOffset}
+                onChange={(e) => setAudioOffset(Math.max(-5000, Math.min(5000, Math.round(Number(e.target.value) || 0))))}
+                className="w-20 px-1.5 py-0.5 bg-zinc-100 dark:bg-zinc-700 rounded text-xs font-mono text-right text-zinc-600 dark:text-zinc-300"
+              />
+              <span className="text-xs text-zinc-400">ms</span>
+            </span>
+          </div>
+        )}
+
+        {/* Loudness report */}
+        {(clip.audio_paths.length > 0 || clip.has_audio) && (
+          <div className="mb-4">
+            <div className="flex items-center justify-between mb-1">
+              <label className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold">
+                Niveau sonore
+              </label>
+              <button
+                onClick={handleAnalyze}
+                disabled={analyzing}
+                className="px-2 py-1 bg-zinc-100 dark:bg-zinc-700 hover:bg-zinc-200 dark:hover:bg-zinc-600 rounded text-[10px] font-medium disabled:opacity-50 transition-colors"
+                title="Mesurer le volume perçu, les crêtes et la saturation de chaque piste"
+              >
+                {analyzing ? "Analyse…" : "Analyser"}
+              </button>
+            </div>
+            {analysis?.map((a) => (
+              <div key={a.path} className="flex items-center justify-between text-[11px] font-mono text-zinc-500 dark:text-zinc-400">
+                <span>
+                  {trackName(a.path)} : {a.integrated_lufs !== null ? `${a.integrated_lufs.toFixed(1)} LUFS` : "—"}
+                  {a.true_peak_dbtp !== null && `, crête ${a.true_peak_dbtp.toFixed(1)} dBTP`}
+                  {a.clipped_samples > 0 && `, ${a.clipped_samples} échantillons saturés`}
+                </span>
+                <span className={`font-sans ${ADVICE[a.advice].className}`}>{ADVICE[a.advice].label}</span>
+              </div>
+            ))}
+          </div>
+        )}
+
+        {/* Single frame: clip thumbnail or PNG still */}
+        <div className="mb-4">
+          <div className="flex items-center justify-between mb-1">
+            <label className="text-[10px] uppercase tracking-wider text-zinc-400 dark:text-zinc-500 font-semibold">
+              Image
+            </label>
+            <span className="text-xs font-mono text-zinc-500 dark:text-zinc-400">{formatMs(frameAt)}</span>
+          </div>
+          <div className="flex items-center gap-2">
+            <input
+              type="range"
+              min={0}
+              max={maxMs}
+              step={100}
+              value={frameAt}
+              onChange={(e) => setFrameAt(Number(e.target.value))}
+              className="flex-1 accent-blue-500"
+            />
+            <button
+              onClick={() => onThumbnail(frameAt)}
+              className="px-2 py-1 bg-zinc-100 dark:bg-zinc-700 hover:bg-zinc-200 dark:hover:bg-zinc-600 rounded text-[10px] font-medium transition-colors"
+              title="Utiliser cette image comme miniature du clip"
+            >
+              Miniature
+            </button>
+            <button
+              onClick={() => onSaveFrame(frameAt)}
+              className="px-2 py-1 bg-zinc-100 dark:bg-zinc-700 hover:bg-zinc-200 dark:hover:bg-zinc-600 rounded text-[10px] font-medium transition-colors"
+              title="Enregistrer cette image en PNG, annotations comprises"
+            >
+              PNG
+            </button>
+          </div>
+          <div className="flex items-center justify-end gap-2 mt-2">
+            <select
+              value={freezeMs}
+              onChange={(e) => setFreezeMs(Number(e.target.value))}
+              className="px-1.5 py-1 bg-zinc-100 dark:bg-zinc-700 rounded text-[10px] text-zinc-600 dark:text-zinc-300"
+            >
+              {FREEZE_DURATIONS.map((ms) => (
+                <option key={ms} value={ms}>{ms / 1000} s</option>
+              ))}
+            </select>
+            <button
+              onClick={handleFreeze}
+              disabled={freezing}
+              className="px-2 py-1 bg-zinc-100 dark:bg-zinc-700 hover:bg-zinc-200 dark:hover:bg-zinc-600 rounded text-[10px] font-medium disabled:opacity-50 transition-colors"
+              title="Figer cette image dans la timeline, le clip reprend ensuite"
+            >
+              {freezing ? "Création…" : "Figer"}
+            </button>
+          </div>
+        </div>
+
+        {autoError && <p className="mb-3 text-xs text-red-500">{autoError}</p>}
+
         {/* Actions */}
         <div className="flex items-center gap-2">
           <button
@@ -135,6 +385,14 @@ export function TrimModal({ clip, onSave, onClose }: Props) {
           >
             Reset
           </button>
+          <button
+            onClick={handleAuto}
+            disabled={detecting}
+            className="px-3 py-2 text-xs text-zinc-500 dark:text-zinc-400 hover:text-zinc-700 dark:hover:text-zinc-200 disabled:opacity-50 transition-colors"
+            title="Couper le silence et l'inactivité au début et à la fin"
+          >
+            {detecting ? "Analyse…" : "Auto"}
+          </button>
           <div className="flex-1" />
           <button
             onClick={onClose}
@@ -153,3 +411,14 @@ export function TrimModal({ clip, onSave, onClose }: Props) {
     </div>
   );
 }
+
+/** Previous / next sentence buttons */
+function SentenceJump({ onJump }: { onJump: (forward: boolean) => void }) {
+  const className = "px-1 rounded text-[10px] text-zinc-400 hover:text-zinc-700 dark:hover:text-zinc-200 hover:bg-zinc-100 dark:hover:bg-zinc-700 transition-colors";
+  return (
+    <>
+      <button onClick={() => onJump(false)} className={className} title="Phrase précédente">‹</button>
+      <button onClick={() => onJump(true)} className={className} title="Phrase suivante">›</button>
+    </>
+  );
+}
@@ -1,20 +1,87 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import type {
+  AdaptiveCapture,
   Annotation,
+  AppEventMap,
+  AudioAnalysis,
   AudioDevice,
+  AudioFormat,
   AudioSource,
+  CaptureBackendInfo,
+  CaptureBackendKind,
+  CaptureRate,
+  CaptureTiming,
   Clip,
+  DiagnosticsResult,
+  DualLayout,
+  ExportDownscale,
+  ExportEncoder,
+  ExportedFile,
   ExportFormat,
+  ExportFps,
+  ExportIssue,
+  ExportPlan,
+  ExportPreset,
+  ExportTarget,
   ExportQuality,
+  ExportRange,
+  FfmpegCapabilities,
+  FollowWindow,
+  FontFile,
+  GifLoop,
+  GifPalette,
+  Keyframe,
+  LiveStroke,
+  Locale,
+  MonitorArea,
+  PauseTransition,
+  PostExportSettings,
   ProjectSummary,
+  RecordingEncoder,
+  RecordingEncoderInfo,
   RecordingState,
+  RecordingStats,
+  RecordingQuality,
   Region,
+  ResolutionCheck,
+  SafeAreaGuide,
+  SegmentRollover,
+  SizeEstimate,
+  SpeechSegment,
+  StorageEntry,
+  StoragePolicy,
+  StorageUsage,
   Subtitle,
+  SubtitleExport,
+  SubtitleFit,
+  SubtitleIssue,
+  SubtitleLanguage,
+  SubtitleReading,
+  TelemetrySettings,
+  TeleprompterSettings,
+  TestClipSpec,
+  TimelineMarker,
   Transition,
   TransitionType,
+  UpdateInfo,
+  UploadTarget,
+  VisualizerStyle,
+  Voiceover,
+  WatchFolderSettings,
+  Webhook,
   WindowInfo,
+  ZOrder,
 } from "./types";
 
+/** Listen to one of the typed backend events, see `AppEvent` */
+export function onAppEvent<K extends keyof AppEventMap>(
+  name: K,
+  handler: (event: AppEventMap[K]) => void,
+): Promise<UnlistenFn> {
+  return listen<AppEventMap[K]>(name, (event) => handler(event.payload));
+}
+
 export async function getRecordingState(): Promise<RecordingState> {
   return invoke("get_recording_state");
 }
@@ -23,6 +90,26 @@ export async function setCaptureRegion(region: Region): Promise<void> {
   return invoke("set_capture_region", { region });
 }
 
+export async function setSecondaryRegion(region: Region | null): Promise<void> {
+  return invoke("set_secondary_region", { region });
+}
+
+export async function setCaptureMonitors(monitors: Region[]): Promise<void> {
+  return invoke("set_capture_monitors", { monitors });
+}
+
+export async function setFollowCursor(window: FollowWindow | null): Promise<void> {
+  return invoke("set_follow_cursor", { window });
+}
+
+export async function setFollowFocus(enabled: boolean): Promise<void> {
+  return invoke("set_follow_focus", { enabled });
+}
+
+export async function setClipDualLayout(clipId: string, layout: DualLayout): Promise<void> {
+  return invoke("set_clip_dual_layout", { clipId, layout });
+}
+
 export async function toggleAudio(): Promise<boolean> {
   return invoke("toggle_audio");
 }
@@ -63,10 +150,19 @@ export async function setAllTransitions(transitionType: TransitionType): Promise
   return invoke("set_all_transitions", { transitionType });
 }
 
-export async function startRecording(): Promise<void> {
+export async function startRecording(): Promise<boolean> {
   return invoke("start_recording");
 }
 
+/** Record another take of a clip, played in its place once stopped */
+export async function recordRetake(clipId: string): Promise<boolean> {
+  return invoke("record_retake", { clipId });
+}
+
+export async function selectTake(clipId: string, take: number): Promise<Clip> {
+  return invoke("select_take", { clipId, take });
+}
+
 export async function stopRecording(): Promise<Clip> {
   return invoke("stop_recording");
 }
@@ -83,12 +179,20 @@ export async function cancelRecording(): Promise<void> {
   return invoke("cancel_recording");
 }
 
+export async function toggleMicMute(): Promise<boolean> {
+  return invoke("toggle_mic_mute");
+}
+
 export async function getRecordingDurationMs(): Promise<number> {
   return invoke("get_recording_duration_ms");
 }
 
-export async function openRegionSelector(): Promise<void> {
-  return invoke("open_region_selector");
+export async function getRecordingStats(): Promise<RecordingStats> {
+  return invoke("get_recording_stats");
+}
+
+export async function openRegionSelector(secondary = false): Promise<void> {
+  return invoke("open_region_selector", { secondary });
 }
 
 export async function closeRegionSelector(): Promise<void> {
@@ -99,18 +203,243 @@ export async function getThumbnailBase64(clipId: string): Promise<string | null>
   return invoke("get_thumbnail_base64", { clipId });
 }
 
+export async function regenerateThumbnail(clipId: string, atMs: number | null): Promise<void> {
+  return invoke("regenerate_thumbnail", { clipId, atMs });
+}
+
+export async function insertFreeze(clipId: string, atMs: number, durationMs: number): Promise<Clip[]> {
+  return invoke("insert_freeze", { clipId, atMs, durationMs });
+}
+
+export async function getFramesAt(clipId: string, timestamps: number[], width?: number): Promise<string[]> {
+  return invoke("get_frames_at", { clipId, timestamps, width });
+}
+
+export async function renderWaveformImage(clipId: string, width: number, height: number): Promise<string | null> {
+  return invoke("render_waveform_image", { clipId, width, height });
+}
+
+export async function getLiveSnapshot(maxWidth?: number): Promise<string | null> {
+  return invoke("get_live_snapshot", { maxWidth });
+}
+
 export async function getTransitions(): Promise<Transition[]> {
   return invoke("get_transitions");
 }
 
-export async function exportVideo(watermark: boolean, format: ExportFormat, quality: ExportQuality): Promise<string> {
-  return invoke("export_video", { watermark, format, quality });
+export async function exportVideo(
+  watermark: boolean,
+  format: ExportFormat,
+  quality: ExportQuality,
+  targetSizeMb: number | null = null,
+  preset: string | null = null,
+  range: ExportRange | null = null,
+): Promise<string> {
+  return invoke("export_video", {
+    watermark,
+    format,
+    quality,
+    targetSizeMb,
+    preset,
+    startMs: range?.start_ms ?? null,
+    endMs: range?.end_ms ?? null,
+  });
+}
+
+export async function debugExportPlan(
+  format: ExportFormat,
+  quality: ExportQuality,
+  watermark: boolean,
+  targetSizeMb: number | null = null,
+  preset: string | null = null,
+  range: ExportRange | null = null,
+): Promise<ExportPlan> {
+  return invoke("debug_export_plan", {
+    format,
+    quality,
+    watermark,
+    targetSizeMb,
+    preset,
+    startMs: range?.start_ms ?? null,
+    endMs: range?.end_ms ?? null,
+  });
+}
+
+export async function exportVideoMulti(watermark: boolean, targets: ExportTarget[]): Promise<string[]> {
+  return invoke("export_video_multi", { watermark, targets });
+}
+
+export async function exportClipsSeparately(
+  watermark: boolean,
+  format: ExportFormat,
+  quality: ExportQuality,
+  preset: string | null = null,
+): Promise<string[]> {
+  return invoke("export_clips_separately", { watermark, format, quality, preset });
+}
+
+export async function exportAudioVisualizer(
+  style: VisualizerStyle,
+  title: string | null,
+  quality: ExportQuality,
+): Promise<string> {
+  return invoke("export_audio_visualizer", { style, title, quality });
+}
+
+export async function setWatchFolder(settings: WatchFolderSettings): Promise<void> {
+  return invoke("set_watch_folder", { settings });
+}
+
+export async function getWatchFolder(): Promise<WatchFolderSettings> {
+  return invoke("get_watch_folder");
+}
+
+export async function getWatchPending(): Promise<string[]> {
+  return invoke("get_watch_pending");
+}
+
+export async function resolveWatchPending(paths: string[], importFiles: boolean): Promise<void> {
+  return invoke("resolve_watch_pending", { paths, import: importFiles });
+}
+
+export async function listExportedVideos(): Promise<ExportedFile[]> {
+  return invoke("list_exported_videos");
+}
+
+export async function importExportedVideos(paths: string[]): Promise<void> {
+  return invoke("import_exported_videos", { paths });
+}
+
+export async function estimateExportSize(
+  format: ExportFormat,
+  quality: ExportQuality,
+  targetSizeMb: number | null = null,
+  preset: string | null = null,
+  sample = false,
+): Promise<SizeEstimate> {
+  return invoke("estimate_export_size", { format, quality, targetSizeMb, preset, sample });
+}
+
+export async function validateExport(
+  format: ExportFormat,
+  quality: ExportQuality,
+  targetSizeMb: number | null = null,
+  preset: string | null = null,
+  range: ExportRange | null = null,
+): Promise<ExportIssue[]> {
+  return invoke("validate_export", {
+    format,
+    quality,
+    targetSizeMb,
+    preset,
+    startMs: range?.start_ms ?? null,
+    endMs: range?.end_ms ?? null,
+  });
+}
+
+export async function checkExportResolution(preset: string | null = null): Promise<ResolutionCheck> {
+  return invoke("check_export_resolution", { preset });
+}
+
+export async function getExportPresets(): Promise<ExportPreset[]> {
+  return invoke("get_export_presets");
+}
+
+export async function getSafeAreaGuides(): Promise<SafeAreaGuide[]> {
+  return invoke("get_safe_area_guides");
+}
+
+export async function setExportPresets(presets: ExportPreset[]): Promise<ExportPreset[]> {
+  return invoke("set_export_presets", { presets });
+}
+
+export async function setExportEncoder(encoder: ExportEncoder): Promise<void> {
+  return invoke("set_export_encoder", { encoder });
+}
+
+export async function getExportEncoder(): Promise<ExportEncoder> {
+  return invoke("get_export_encoder");
+}
+
+export async function setExportFps(fps: ExportFps): Promise<void> {
+  return invoke("set_export_fps", { fps });
+}
+
+export async function getExportFps(): Promise<ExportFps> {
+  return invoke("get_export_fps");
+}
+
+export async function setExportDownscale(downscale: ExportDownscale): Promise<void> {
+  return invoke("set_export_downscale", { downscale });
+}
+
+export async function getExportDownscale(): Promise<ExportDownscale> {
+  return invoke("get_export_downscale");
+}
+
+export async function setHdrTonemap(enabled: boolean): Promise<void> {
+  return invoke("set_hdr_tonemap", { enabled });
+}
+
+export async function setKeepFilterScripts(enabled: boolean): Promise<void> {
+  return invoke("set_keep_filter_scripts", { enabled });
+}
+
+export async function setGifPalette(palette: GifPalette): Promise<void> {
+  return invoke("set_gif_palette", { palette });
+}
+
+export async function setGifLoop(gifLoop: GifLoop): Promise<void> {
+  return invoke("set_gif_loop", { gifLoop });
+}
+
+export async function setExportSidecar(enabled: boolean): Promise<void> {
+  return invoke("set_export_sidecar", { enabled });
+}
+
+export async function setExportChapters(enabled: boolean): Promise<void> {
+  return invoke("set_export_chapters", { enabled });
+}
+
+export async function generateChapterText(): Promise<string> {
+  return invoke("generate_chapter_text");
+}
+
+export async function setExportChunkMinutes(minutes: number): Promise<void> {
+  return invoke("set_export_chunk_minutes", { minutes });
 }
 
 export async function setClipTrim(clipId: string, trimStartMs: number, trimEndMs: number): Promise<void> {
   return invoke("set_clip_trim", { clipId, trimStartMs, trimEndMs });
 }
 
+/** Shift a clip's audio by `ms` at export, later when positive */
+export async function setAudioOffset(clipId: string, ms: number): Promise<void> {
+  return invoke("set_audio_offset", { clipId, ms });
+}
+
+/** Suggested [trimStartMs, trimEndMs], end 0 when nothing to cut at the end */
+export async function detectAutoTrim(clipId: string): Promise<[number, number]> {
+  return invoke("detect_auto_trim", { clipId });
+}
+
+export async function getSpeechSegments(clipId: string): Promise<SpeechSegment[]> {
+  return invoke("get_speech_segments", { clipId });
+}
+
+/** Loudness, true peak and clipping of each audio track of the clip */
+export async function analyzeAudio(clipId: string): Promise<AudioAnalysis[]> {
+  return invoke("analyze_audio", { clipId });
+}
+
+export async function autoTrimClips(): Promise<Clip[]> {
+  return invoke("auto_trim_clips");
+}
+
+export async function setClipZoomKeyframes(clipId: string, keyframes: Keyframe[]): Promise<void> {
+  return invoke("set_clip_zoom_keyframes", { clipId, keyframes });
+}
+
 export async function previewVideo(): Promise<string> {
   return invoke("preview_video");
 }
@@ -119,14 +448,32 @@ export async function ensureFfmpeg(): Promise<string> {
   return invoke("ensure_ffmpeg");
 }
 
-export async function getVisibleWindows(): Promise<WindowInfo[]> {
-  return invoke("get_visible_windows");
+export async function getFfmpegCapabilities(): Promise<FfmpegCapabilities> {
+  return invoke("get_ffmpeg_capabilities");
+}
+
+// QA: appends test pattern clips of known duration and size to the timeline.
+// Only registered in debug builds.
+export async function generateTestClips(specs: TestClipSpec[]): Promise<Clip[]> {
+  return invoke("generate_test_clips", { specs });
+}
+
+export async function getVisibleWindows(includeThumbnails = false): Promise<WindowInfo[]> {
+  return invoke("get_visible_windows", { includeThumbnails });
 }
 
 export async function getMonitorsInfo(): Promise<Region[]> {
   return invoke("get_monitors_info");
 }
 
+export async function getMonitorWorkAreas(): Promise<MonitorArea[]> {
+  return invoke("get_monitor_work_areas");
+}
+
+export async function snapRegion(region: Region): Promise<Region> {
+  return invoke("snap_region", { region });
+}
+
 export async function setCountdown(seconds: number): Promise<void> {
   return invoke("set_countdown", { seconds });
 }
@@ -135,6 +482,118 @@ export async function getCountdown(): Promise<number> {
   return invoke("get_countdown");
 }
 
+export async function setLockPauseSettings(autoPause: boolean, autoResume: boolean): Promise<void> {
+  return invoke("set_lock_pause_settings", { autoPause, autoResume });
+}
+
+export async function getLockPauseSettings(): Promise<[boolean, boolean]> {
+  return invoke("get_lock_pause_settings");
+}
+
+export async function setRecordingQuality(quality: RecordingQuality): Promise<void> {
+  return invoke("set_recording_quality", { quality });
+}
+
+export async function getRecordingQuality(): Promise<RecordingQuality> {
+  return invoke("get_recording_quality");
+}
+
+export async function setRecordingEncoder(encoder: RecordingEncoder): Promise<void> {
+  return invoke("set_recording_encoder", { encoder });
+}
+
+export async function getRecordingEncoders(): Promise<RecordingEncoderInfo[]> {
+  return invoke("get_recording_encoders");
+}
+
+export async function setCaptureBackend(backend: CaptureBackendKind): Promise<void> {
+  return invoke("set_capture_backend", { backend });
+}
+
+export async function getCaptureBackends(): Promise<CaptureBackendInfo[]> {
+  return invoke("get_capture_backends");
+}
+
+export async function setSegmentRollover(settings: SegmentRollover): Promise<void> {
+  return invoke("set_segment_rollover", { settings });
+}
+
+export async function getSegmentRollover(): Promise<SegmentRollover> {
+  return invoke("get_segment_rollover");
+}
+
+export async function setAdaptiveCapture(settings: AdaptiveCapture): Promise<void> {
+  return invoke("set_adaptive_capture", { settings });
+}
+
+export async function getAdaptiveCapture(): Promise<AdaptiveCapture> {
+  return invoke("get_adaptive_capture");
+}
+
+export async function setCaptureRate(rate: CaptureRate): Promise<void> {
+  return invoke("set_capture_rate", { rate });
+}
+
+export async function getCaptureRate(): Promise<CaptureRate> {
+  return invoke("get_capture_rate");
+}
+
+export async function getCaptureTiming(): Promise<CaptureTiming> {
+  return invoke("get_capture_timing");
+}
+
+export async function setPauseTransition(transition: PauseTransition): Promise<void> {
+  return invoke("set_pause_transition", { transition });
+}
+
+export async function getPauseTransition(): Promise<PauseTransition> {
+  return invoke("get_pause_transition");
+}
+
+export async function setPrivacyBlacklist(patterns: string[]): Promise<void> {
+  return invoke("set_privacy_blacklist", { patterns });
+}
+
+export async function getPrivacyBlacklist(): Promise<string[]> {
+  return invoke("get_privacy_blacklist");
+}
+
+export async function getClipMarkers(clipId: string): Promise<TimelineMarker[]> {
+  return invoke("get_clip_markers", { clipId });
+}
+
+export async function listRecoveredRecordings(): Promise<Clip[]> {
+  return invoke("list_recovered_recordings");
+}
+
+export async function restoreRecoveredRecording(clipId: string): Promise<Clip> {
+  return invoke("restore_recovered_recording", { clipId });
+}
+
+export async function discardRecoveredRecording(clipId: string): Promise<void> {
+  return invoke("discard_recovered_recording", { clipId });
+}
+
+export async function getStorageUsage(): Promise<StorageUsage> {
+  return invoke("get_storage_usage");
+}
+
+export async function clearPreviews(): Promise<StorageEntry> {
+  return invoke("clear_previews");
+}
+
+export async function clearOrphanedTemp(): Promise<StorageEntry> {
+  return invoke("clear_orphaned_temp");
+}
+
+export async function getStoragePolicy(): Promise<StoragePolicy> {
+  return invoke("get_storage_policy");
+}
+
+export async function setStoragePolicy(policy: StoragePolicy): Promise<void> {
+  return invoke("set_storage_policy", { policy });
+}
+
 export async function setClipAnnotations(clipId: string, annotations: Annotation[]): Promise<void> {
   return invoke("set_clip_annotations", { clipId, annotations });
 }
@@ -143,6 +602,40 @@ export async function getClipAnnotations(clipId: string): Promise<Annotation[]>
   return invoke("get_clip_annotations", { clipId });
 }
 
+export async function hitTestAnnotation(clipId: string, x: number, y: number, atMs: number): Promise<string | null> {
+  return invoke("hit_test_annotation", { clipId, x, y, atMs });
+}
+
+export async function reorderAnnotations(clipId: string, annotationIds: string[], change: ZOrder): Promise<Annotation[]> {
+  return invoke("reorder_annotations", { clipId, annotationIds, change });
+}
+
+export async function groupAnnotations(clipId: string, annotationIds: string[]): Promise<Annotation[]> {
+  return invoke("group_annotations", { clipId, annotationIds });
+}
+
+export async function ungroupAnnotations(clipId: string, group: string): Promise<Annotation[]> {
+  return invoke("ungroup_annotations", { clipId, group });
+}
+
+export async function transformAnnotations(
+  clipId: string,
+  annotationIds: string[],
+  dx: number,
+  dy: number,
+  scale: number,
+): Promise<Annotation[]> {
+  return invoke("transform_annotations", { clipId, annotationIds, dx, dy, scale });
+}
+
+export async function setGlobalAnnotations(annotations: Annotation[]): Promise<void> {
+  return invoke("set_global_annotations", { annotations });
+}
+
+export async function getGlobalAnnotations(): Promise<Annotation[]> {
+  return invoke("get_global_annotations");
+}
+
 export async function setSubtitles(subtitles: Subtitle[]): Promise<void> {
   return invoke("set_subtitles", { subtitles });
 }
@@ -151,6 +644,70 @@ export async function getSubtitles(): Promise<Subtitle[]> {
   return invoke("get_subtitles");
 }
 
+export async function checkSubtitles(): Promise<SubtitleIssue[]> {
+  return invoke("check_subtitles");
+}
+
+export async function fitSubtitles(): Promise<SubtitleFit> {
+  return invoke("fit_subtitles");
+}
+
+export async function getSubtitleLanguages(): Promise<SubtitleLanguage[]> {
+  return invoke("get_subtitle_languages");
+}
+
+export async function addSubtitleLanguage(language: string): Promise<SubtitleLanguage[]> {
+  return invoke("add_subtitle_language", { language });
+}
+
+export async function setActiveSubtitleLanguage(language: string): Promise<Subtitle[]> {
+  return invoke("set_active_subtitle_language", { language });
+}
+
+export async function removeSubtitleLanguage(language: string): Promise<SubtitleLanguage[]> {
+  return invoke("remove_subtitle_language", { language });
+}
+
+export async function setSubtitleExport(mode: SubtitleExport): Promise<void> {
+  return invoke("set_subtitle_export", { mode });
+}
+
+export async function suggestSubtitleDurations(subtitles: Subtitle[]): Promise<SubtitleReading[]> {
+  return invoke("suggest_subtitle_durations", { subtitles });
+}
+
+export async function shiftSubtitles(deltaMs: number): Promise<Subtitle[]> {
+  return invoke("shift_subtitles", { deltaMs });
+}
+
+export async function startVoiceover(startMs: number): Promise<void> {
+  return invoke("start_voiceover", { startMs });
+}
+
+export async function stopVoiceover(): Promise<Voiceover> {
+  return invoke("stop_voiceover");
+}
+
+export async function getVoiceovers(): Promise<Voiceover[]> {
+  return invoke("get_voiceovers");
+}
+
+export async function deleteVoiceover(voiceoverId: string): Promise<void> {
+  return invoke("delete_voiceover", { voiceoverId });
+}
+
+export async function listFonts(): Promise<FontFile[]> {
+  return invoke("list_fonts");
+}
+
+export async function setKeystrokeFont(fontFile: string | null): Promise<void> {
+  return invoke("set_keystroke_font", { fontFile });
+}
+
+export async function getKeystrokeFont(): Promise<string | null> {
+  return invoke("get_keystroke_font");
+}
+
 export async function toggleKeystrokeDisplay(): Promise<boolean> {
   return invoke("toggle_keystroke_display");
 }
@@ -167,6 +724,131 @@ export async function getCursorZoomEnabled(): Promise<boolean> {
   return invoke("get_cursor_zoom_enabled");
 }
 
+export async function setTeleprompter(settings: TeleprompterSettings | null): Promise<void> {
+  return invoke("set_teleprompter", { settings });
+}
+
+export async function getTeleprompter(): Promise<TeleprompterSettings | null> {
+  return invoke("get_teleprompter");
+}
+
+export async function reportScriptLine(line: number): Promise<void> {
+  return invoke("report_script_line", { line });
+}
+
+export async function setLiveDrawing(enabled: boolean): Promise<void> {
+  return invoke("set_live_drawing", { enabled });
+}
+
+export async function setKeystrokePreview(enabled: boolean): Promise<void> {
+  return invoke("set_keystroke_preview", { enabled });
+}
+
+export async function addLiveStroke(stroke: LiveStroke): Promise<void> {
+  return invoke("add_live_stroke", { stroke });
+}
+
+export async function clearLiveStrokes(): Promise<void> {
+  return invoke("clear_live_strokes");
+}
+
+export async function toggleGamepad(): Promise<boolean> {
+  return invoke("toggle_gamepad");
+}
+
+export async function getGamepadEnabled(): Promise<boolean> {
+  return invoke("get_gamepad_enabled");
+}
+
+export async function setCursorSidecar(enabled: boolean): Promise<void> {
+  return invoke("set_cursor_sidecar", { enabled });
+}
+
+export async function setPostExportSettings(settings: PostExportSettings): Promise<void> {
+  return invoke("set_post_export_settings", { settings });
+}
+
+export async function getPostExportSettings(): Promise<PostExportSettings> {
+  return invoke("get_post_export_settings");
+}
+
+export async function setUploadTargets(targets: UploadTarget[]): Promise<void> {
+  return invoke("set_upload_targets", { targets });
+}
+
+export async function getUploadTargets(): Promise<UploadTarget[]> {
+  return invoke("get_upload_targets");
+}
+
+export async function uploadExport(path: string, target: string): Promise<string> {
+  return invoke("upload_export", { path, target });
+}
+
+export async function setWebhooks(webhooks: Webhook[]): Promise<void> {
+  return invoke("set_webhooks", { webhooks });
+}
+
+export async function getWebhooks(): Promise<Webhook[]> {
+  return invoke("get_webhooks");
+}
+
+export async function checkForUpdates(): Promise<UpdateInfo> {
+  return invoke("check_for_updates");
+}
+
+export async function openDownloadPage(url: string): Promise<void> {
+  return invoke("open_download_page", { url });
+}
+
+export async function setTelemetrySettings(settings: TelemetrySettings): Promise<void> {
+  return invoke("set_telemetry_settings", { settings });
+}
+
+export async function getTelemetrySettings(): Promise<TelemetrySettings> {
+  return invoke("get_telemetry_settings");
+}
+
+export async function getRecentLogs(lines?: number): Promise<string[]> {
+  return invoke("get_recent_logs", { lines });
+}
+
+export async function openLogFolder(): Promise<void> {
+  return invoke("open_log_folder");
+}
+
+export async function setLocale(lang: string): Promise<Locale> {
+  return invoke("set_locale", { lang });
+}
+
+export async function getLocale(): Promise<Locale> {
+  return invoke("get_locale");
+}
+
+export async function generateDiagnostics(zip = false): Promise<DiagnosticsResult> {
+  return invoke("generate_diagnostics", { zip });
+}
+
+export async function exportCursorHeatmap(clipId: string, withFrame?: boolean): Promise<string[]> {
+  return invoke("export_cursor_heatmap", { clipId, withFrame });
+}
+
+export async function extractFrame(
+  clipId: string,
+  atMs: number,
+  path: string | null,
+  withAnnotations: boolean,
+): Promise<string> {
+  return invoke("extract_frame", { clipId, atMs, path, withAnnotations });
+}
+
+export async function revealInExplorer(path: string): Promise<void> {
+  return invoke("reveal_in_explorer", { path });
+}
+
+export async function openWithDefaultPlayer(path: string): Promise<void> {
+  return invoke("open_with_default_player", { path });
+}
+
 export async function copyFileToClipboard(path: string): Promise<void> {
   return invoke("copy_file_to_clipboard", { path });
 }
@@ -195,6 +877,26 @@ export async function getSelectedMic(): Promise<string | null> {
   return invoke("get_selected_mic");
 }
 
+export async function setSelectedOutput(deviceName: string | null): Promise<void> {
+  return invoke("set_selected_output", { deviceName });
+}
+
+export async function getSelectedOutput(): Promise<string | null> {
+  return invoke("get_selected_output");
+}
+
+export async function toggleMicMonitoring(): Promise<boolean> {
+  return invoke("toggle_mic_monitoring");
+}
+
+export async function getMicMonitoring(): Promise<boolean> {
+  return invoke("get_mic_monitoring");
+}
+
+export async function setAudioFormat(format: AudioFormat): Promise<void> {
+  return invoke("set_audio_format", { format });
+}
+
 export async function setAudioVolumes(systemVolume: number, micVolume: number): Promise<void> {
   return invoke("set_audio_volumes", { systemVolume, micVolume });
 }
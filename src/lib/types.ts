@@ -15,6 +15,64 @@ export interface Clip {
   trim_start_ms: number;
   trim_end_ms: number;
   audio_paths: string[];
+  hdr: boolean;
+  zoom_keyframes: Keyframe[];
+  secondary?: SecondaryVideo;
+  capture_group?: CaptureGroup;
+  follow_cursor?: FollowWindow;
+  focus_track?: FocusChange[];
+  script?: ScriptTrack;
+  /** Every recording of the slot once retaken, the one played included */
+  takes?: Take[];
+  /** Shift of the audio against the video, positive = later */
+  audio_offset_ms?: number;
+}
+
+/** One recording of a clip's slot */
+export interface Take {
+  id: string;
+  path: string;
+  duration_ms: number;
+  region: Region;
+  has_audio: boolean;
+  thumbnail_path: string | null;
+  trim_start_ms: number;
+  trim_end_ms: number;
+  audio_paths: string[];
+  hdr: boolean;
+  secondary?: SecondaryVideo;
+  follow_cursor?: FollowWindow;
+  focus_track?: FocusChange[];
+  script?: ScriptTrack;
+  audio_offset_ms?: number;
+}
+
+export interface FollowWindow {
+  width: number;
+  height: number;
+}
+
+/** Foreground window framed from `timestamp_ms` on, normalized to the capture */
+export interface FocusChange {
+  timestamp_ms: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export interface CaptureGroup {
+  id: string;
+  offset_ms: number;
+}
+
+export type DualLayout = "side_by_side" | "stacked" | "pip";
+
+export interface SecondaryVideo {
+  path: string;
+  region: Region;
+  offset_ms: number;
+  layout: DualLayout;
 }
 
 export type TransitionType =
@@ -46,11 +104,275 @@ export interface Transition {
 
 export type RecordingState = "idle" | "recording" | "paused";
 
-export type ExportFormat = "mp4" | "gif";
+export type ExportFormat = "mp4" | "gif" | "webm";
+
+// Picture of an audio visualizer export
+export type VisualizerStyle = "waves" | "spectrum";
 export type ExportQuality = "high" | "medium" | "low";
 
+export type CanvasFit = "letterbox" | "crop";
+
+export type ExportEncoder = "software" | "nvenc" | "qsv";
+
+export interface OutputSettings {
+  width: number | null;
+  height: number | null;
+  fps: number | null;
+  fit: CanvasFit;
+  include_audio: boolean;
+  match_source_fps: boolean;
+  upscale: boolean;
+  safe_area: SafeArea;
+}
+
+/** Margins covered by a platform's UI, as fractions of the frame */
+export interface SafeArea {
+  top: number;
+  right: number;
+  bottom: number;
+  left: number;
+}
+
+export interface SafeAreaGuide {
+  id: string;
+  name: string;
+  width: number;
+  height: number;
+  safe_area: SafeArea;
+  grid_columns: number;
+  grid_rows: number;
+}
+
+export type ExportFps = "default" | "source" | { fixed: number };
+
+export type ExportDownscale = "auto" | "off" | { max_height: number };
+
+export interface GifPalette {
+  /** 2 to 256 */
+  max_colors: number;
+  /** One palette per clip instead of one for the whole GIF */
+  per_clip: boolean;
+}
+
+export interface GifLoop {
+  /** Play the timeline forward then backward */
+  boomerang: boolean;
+  /** Times the GIF plays, 0 = forever */
+  plays: number;
+}
+
+// Part of the assembled timeline to export, end null = until the end
+export interface ExportRange {
+  start_ms: number;
+  end_ms: number | null;
+}
+
+export interface ExportTarget {
+  format: ExportFormat;
+  quality: ExportQuality;
+  target_size_mb: number | null;
+  preset: string | null;
+}
+
+export interface BatchExportProgress {
+  percent: number;
+  target_index: number | null;
+  target_count: number;
+}
+
+export interface ExportChunkProgress {
+  percent: number;
+  chunk_index: number | null;
+  chunk_count: number;
+  resumed: number;
+}
+
+export interface ExportPreset {
+  id: string;
+  name: string;
+  format: ExportFormat;
+  quality: ExportQuality;
+  watermark: boolean;
+  target_size_mb: number | null;
+  output: OutputSettings;
+  builtin: boolean;
+}
+
+export type PostExportAction =
+  | "open_folder"
+  | "copy_to_clipboard"
+  | "open_in_player"
+  | "run_command"
+  | "nothing";
+
+export interface PostExportSettings {
+  action: PostExportAction;
+  command: string;
+}
+
+export type HttpUploadMethod = "put" | "post";
+
+export interface UploadHeader {
+  name: string;
+  value: string;
+}
+
+export type UploadTarget = { name: string } & (
+  | {
+      kind: "http";
+      url: string;
+      method: HttpUploadMethod;
+      headers: UploadHeader[];
+      url_field: string | null;
+    }
+  | {
+      kind: "s3";
+      endpoint: string;
+      bucket: string;
+      region: string;
+      access_key_id: string;
+      secret_access_key: string;
+      key_prefix: string;
+      public_url: string | null;
+    }
+);
+
+export type WebhookKind = "discord" | "slack" | "generic";
+
+export interface Webhook {
+  name: string;
+  kind: WebhookKind;
+  url: string;
+  enabled: boolean;
+  on_export: boolean;
+  on_upload: boolean;
+  attach_file: boolean;
+  max_file_mb: number;
+}
+
+export type FfmpegDownloadStage = "downloading" | "verifying" | "extracting" | "retrying";
+
+export interface FfmpegDownloadProgress {
+  stage: FfmpegDownloadStage;
+  downloaded_bytes: number;
+  total_bytes: number;
+  attempt: number;
+}
+
+export interface FfmpegCapabilities {
+  version: string | null;
+  filters: string[];
+  encoders: string[];
+}
+
+export interface TelemetrySettings {
+  enabled: boolean;
+  endpoint: string;
+}
+
+export interface UpdateInfo {
+  current_version: string;
+  latest_version: string;
+  update_available: boolean;
+  changelog: string;
+  download_url: string;
+  published_at: string | null;
+}
+
+export type Locale = "fr" | "en";
+
+export type RecordingCodec = "x264" | "x264rgb" | "utvideo";
+
+export type RecordingEncoder = "auto" | "software" | "h264_nvenc" | "hevc_nvenc" | "h264_qsv" | "h264_amf";
+
+export interface RecordingQuality {
+  codec: RecordingCodec;
+  crf: number;
+  preset: string;
+  /** Encoder of the x264 codec */
+  encoder?: RecordingEncoder;
+}
+
+export interface RecordingEncoderInfo {
+  kind: RecordingEncoder;
+  /** Listed by FFmpeg and able to encode on this machine */
+  available: boolean;
+  /** What "auto" records with */
+  auto: boolean;
+}
+
+export type CaptureBackendKind = "gdigrab" | "wgc" | "ddagrab";
+
+export interface CaptureBackendInfo {
+  kind: CaptureBackendKind;
+  available: boolean;
+  gpu: boolean;
+  spans_monitors: boolean;
+}
+
+export type PauseTransitionStyle = "cut" | "crossfade" | "dip";
+
+export interface PauseTransition {
+  style: PauseTransitionStyle;
+  duration_ms: number;
+}
+
+export interface TestClipSpec {
+  duration_ms: number;
+  width: number;
+  height: number;
+  tone_hz?: number | null;
+}
+
+export interface ExportPlan {
+  ffmpeg: string;
+  commands: string[][];
+  filter_graph: string | null;
+  notes: string[];
+}
+
+export interface SegmentRollover {
+  enabled: boolean;
+  max_minutes: number;
+  max_megabytes: number;
+  split_clips: boolean;
+}
+
+export interface AdaptiveCapture {
+  enabled: boolean;
+  cpu_threshold: number;
+}
+
+/** Capture frame rate: the timeline's, or the monitor refresh rate divided */
+export type CaptureRate = "default" | { refresh_divisor: number };
+
+export interface CaptureRateOption {
+  divisor: number;
+  fps: number;
+}
+
+export interface CaptureTiming {
+  refresh_rate: number | null;
+  options: CaptureRateOption[];
+}
+
+export interface RecordingStats {
+  target_fps: number;
+  refresh_rate: number | null;
+  achieved_fps: number | null;
+}
+
 export type AudioSource = "none" | "system" | "microphone" | "both";
 
+/** Latest levels of a track being recorded, oldest first, 0 to 255 */
+export interface WaveformTrack {
+  source: "system" | "microphone";
+  peaks: number[];
+}
+
+/** "canonical": 48 kHz 16-bit PCM, "native": the device rate as 32-bit float */
+export type AudioFormat = "canonical" | "native";
+
 export interface AudioDevice {
   name: string;
   is_input: boolean;
@@ -63,6 +385,17 @@ export interface WindowInfo {
   y: number;
   width: number;
   height: number;
+  client_rect: Region | null;
+  hwnd: number;
+  thumbnail: string | null;
+}
+
+export interface MonitorArea {
+  bounds: Region;
+  work_area: Region;
+  is_primary: boolean;
+  /** Hz, 0 when unknown */
+  refresh_rate: number;
 }
 
 export interface Annotation {
@@ -78,10 +411,28 @@ export interface Annotation {
   points: [number, number][] | null;
   start_ms: number;
   end_ms: number;
+  keyframes: Keyframe[];
+  /** Stacking order, higher is drawn on top, ties in list order */
+  z_index: number;
+  /** Annotations sharing a group are selected, moved and reordered together */
+  group: string | null;
 }
 
 export type AnnotationKind = "arrow" | "rectangle" | "circle" | "text" | "freehand";
 
+export type ZOrder = "to_front" | "to_back" | "forward" | "backward";
+
+export type AnimatedProperty = "zoom_scale" | "zoom_x" | "zoom_y" | "opacity";
+
+export type Easing = "linear" | "ease_in" | "ease_out" | "ease_in_out" | "hold";
+
+export interface Keyframe {
+  time_ms: number;
+  property: AnimatedProperty;
+  value: number;
+  easing: Easing;
+}
+
 export interface Subtitle {
   id: string;
   text: string;
@@ -90,10 +441,154 @@ export interface Subtitle {
   position: SubtitlePosition;
   font_size: number;
   color: string;
+  font_file?: string | null;
+}
+
+/** Narration recorded over the timeline playback, mixed in at export */
+export interface Voiceover {
+  id: string;
+  path: string;
+  /** Position in the timeline where it starts */
+  start_ms: number;
+  duration_ms: number;
+}
+
+export interface FontFile {
+  name: string;
+  path: string;
 }
 
 export type SubtitlePosition = "top" | "center" | "bottom";
 
+/** Subtitles of a language other than the active one */
+export interface SubtitleTrack {
+  /** ISO 639 code, "und" when not given */
+  language: string;
+  subtitles: Subtitle[];
+}
+
+export interface SubtitleLanguage {
+  language: string;
+  count: number;
+  /** The one edited and burned into exports */
+  active: boolean;
+}
+
+/** Active language drawn into the picture, or every language as a track */
+export type SubtitleExport = "burned" | "soft";
+
+export type SubtitleIssueKind = "overlap" | "too_short" | "too_fast" | "off_timeline";
+
+export interface SubtitleIssue {
+  kind: SubtitleIssueKind;
+  subtitle_id: string;
+  message: string;
+}
+
+/** Time a subtitle's text takes to read */
+export interface SubtitleReading {
+  subtitle_id: string;
+  suggested_ms: number;
+  chars_per_second: number;
+  too_fast: boolean;
+}
+
+/** Subtitles after the timing fixes, with what couldn't be fixed */
+export interface SubtitleFit {
+  subtitles: Subtitle[];
+  changed: number;
+  issues: SubtitleIssue[];
+}
+
+// Teleprompter shown while recording
+export interface WatchFolderSettings {
+  path: string | null;
+  confirm: boolean;
+}
+
+export interface TeleprompterSettings {
+  script: string;
+  words_per_minute: number;
+}
+
+// Script line that reached the reading line, in recording time
+export interface ScriptMark {
+  timestamp_ms: number;
+  line: number;
+}
+
+export interface ScriptTrack {
+  lines: string[];
+  marks: ScriptMark[];
+}
+
+export type DrawingTool = "pen" | "highlighter";
+
+// Stroke drawn on the live drawing overlay, sent when the pen is lifted
+export interface LiveStroke {
+  points: [number, number][]; // normalized 0-1
+  tool: DrawingTool;
+  color: string;
+  duration_ms: number;
+}
+
+export type MarkerKind = "privacy" | "micmute";
+
+export interface TimelineMarker {
+  timestamp_ms: number;
+  label: string;
+  kind: MarkerKind;
+  // End of a marker covering a span (mic muted)
+  end_ms?: number;
+}
+
+export interface ExportedFile {
+  path: string;
+  name: string;
+  size_bytes: number;
+  modified_ms: number;
+}
+
+/** Source time of the clip */
+export interface SpeechSegment {
+  start_ms: number;
+  end_ms: number;
+}
+
+/** What an audio track needs before export */
+export type AudioAdvice = "good" | "normalize" | "retake" | "silent";
+
+/** Loudness and clipping of one audio track of a clip */
+export interface AudioAnalysis {
+  path: string;
+  integrated_lufs: number | null;
+  loudness_range_lu: number | null;
+  true_peak_dbtp: number | null;
+  clipped_samples: number;
+  sample_count: number;
+  advice: AudioAdvice;
+}
+
+export interface StoragePolicy {
+  /** 0 keeps temp files forever */
+  max_age_hours: number;
+  keep_project_media: boolean;
+}
+
+export interface StorageEntry {
+  bytes: number;
+  files: number;
+}
+
+export interface StorageUsage {
+  clips: StorageEntry;
+  thumbnails: StorageEntry;
+  proxies: StorageEntry;
+  previews: StorageEntry;
+  /** Overlaps the other categories */
+  orphaned: StorageEntry;
+}
+
 export interface ProjectSummary {
   id: string;
   name: string;
@@ -102,3 +597,109 @@ export interface ProjectSummary {
   clip_count: number;
   total_duration_ms: number;
 }
+
+export interface EncoderAvailability {
+  name: string;
+  available: boolean;
+}
+
+export interface DiskSpace {
+  path: string;
+  free_bytes: number;
+  total_bytes: number;
+}
+
+export interface DiagnosticsReport {
+  generated_at: string;
+  app_version: string;
+  os: string;
+  ffmpeg_version: string | null;
+  ffprobe_version: string | null;
+  gpus: string[];
+  encoders: EncoderAvailability[];
+  audio_devices: AudioDevice[];
+  monitors: MonitorArea[];
+  disks: DiskSpace[];
+  recent_errors: string[];
+}
+
+export interface DiagnosticsResult {
+  report: DiagnosticsReport;
+  archive_path: string | null;
+}
+
+export interface NormalizeProgress {
+  name: string;
+  percent: number;
+}
+
+export interface ResolutionOption {
+  downscale: ExportDownscale;
+  width: number;
+  height: number;
+}
+
+export interface ResolutionCheck {
+  width: number;
+  height: number;
+  export_width: number;
+  export_height: number;
+  max_side: number;
+  exceeds_limit: boolean;
+  fits: boolean;
+  slow: boolean;
+  options: ResolutionOption[];
+}
+
+export type ExportIssueKind =
+  | "no_clips"
+  | "missing_clip"
+  | "missing_audio"
+  | "unreadable_clip"
+  | "size_mismatch"
+  | "frame_rate"
+  | "resolution"
+  | "disk_space"
+  | "output_not_writable";
+
+export interface ExportIssue {
+  severity: "error" | "warning";
+  kind: ExportIssueKind;
+  clip_id: string | null;
+  message: string;
+}
+
+export interface SizeEstimate {
+  bytes: number;
+  duration_ms: number;
+  width: number;
+  height: number;
+  method: "heuristic" | "sample";
+}
+
+// Typed backend events: each is emitted under its `type`, with the whole
+// object as payload (src-tauri/src/events.rs)
+export type AppEvent =
+  | { type: "recording-started" }
+  | { type: "recording-paused"; reason: "session" | "privacy" | null }
+  | { type: "recording-resumed" }
+  | { type: "recording-stopped"; clip_id: string }
+  | { type: "recording-cancelled" }
+  | { type: "recording-error"; message: string }
+  | { type: "capture-adapted"; level: number; cpu_percent: number }
+  | { type: "audio-waveform"; tracks: WaveformTrack[] }
+  | { type: "countdown-tick"; remaining: number }
+  | { type: "countdown-aborted" }
+  | { type: "mic-mute-changed"; muted: boolean }
+  | { type: "drawing-tool"; tool: DrawingTool | null }
+  | { type: "recordings-recovered"; count: number }
+  | { type: "clip-added"; clip: Clip }
+  | { type: "clip-normalized"; clip_id: string }
+  | { type: "import-error"; message: string }
+  | { type: "project-opened"; project_id: string }
+  | { type: "watch-folder-pending"; count: number }
+  | { type: "export-started"; formats: ExportFormat[]; clip_count: number }
+  | { type: "export-finished"; paths: string[] }
+  | { type: "export-failed"; message: string };
+
+export type AppEventMap = { [E in AppEvent as E["type"]]: E };
@@ -2,6 +2,10 @@ import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
 import { RegionOverlay } from "./components/overlay/RegionOverlay";
+import { CountdownOverlay } from "./components/overlay/CountdownOverlay";
+import { DrawingOverlay } from "./components/overlay/DrawingOverlay";
+import { TeleprompterWindow } from "./components/overlay/TeleprompterWindow";
+import { KeystrokePreviewWindow } from "./components/overlay/KeystrokePreviewWindow";
 import "./styles.css";
 
 function Root() {
@@ -11,6 +15,22 @@ function Root() {
     return <RegionOverlay />;
   }
 
+  if (path === "/countdown") {
+    return <CountdownOverlay />;
+  }
+
+  if (path === "/drawing") {
+    return <DrawingOverlay />;
+  }
+
+  if (path === "/teleprompter") {
+    return <TeleprompterWindow />;
+  }
+
+  if (path === "/keystrokes") {
+    return <KeystrokePreviewWindow />;
+  }
+
   return <App />;
 }
 
@@ -1,14 +1,44 @@
 import { create } from "zustand";
 import type {
+  AdaptiveCapture,
+  AudioFormat,
   AudioSource,
+  CaptureBackendKind,
+  CaptureRate,
   Clip,
+  DualLayout,
+  ExportChunkProgress,
+  ExportDownscale,
   ExportFormat,
+  ExportEncoder,
+  ExportFps,
+  ExportPreset,
+  ExportTarget,
   ExportQuality,
+  ExportRange,
+  FfmpegDownloadProgress,
+  FollowWindow,
+  GifLoop,
+  GifPalette,
+  Keyframe,
+  Locale,
+  PauseTransition,
+  PostExportSettings,
   ProjectSummary,
+  RecordingEncoder,
   RecordingState,
   Region,
+  SubtitleExport,
+  TelemetrySettings,
+  TeleprompterSettings,
+  WatchFolderSettings,
   Transition,
   TransitionType,
+  UpdateInfo,
+  UploadTarget,
+  VisualizerStyle,
+  Voiceover,
+  Webhook,
 } from "../lib/types";
 import * as api from "../lib/tauri";
 
@@ -17,15 +47,28 @@ type Theme = "light" | "dark";
 interface AppStore {
   // State
   theme: Theme;
+  // Language of backend errors and notifications
+  locale: Locale;
   recordingState: RecordingState;
   clips: Clip[];
   transitions: Transition[];
   currentRegion: Region | null;
+  secondaryRegion: Region | null;
+  captureMonitors: Region[];
   audioSource: AudioSource;
+  // Mic silenced mid-recording (F6)
+  micMuted: boolean;
   durationMs: number;
   ffmpegReady: boolean;
   ffmpegError: string | null;
+  ffmpegDownload: FfmpegDownloadProgress | null;
+  // Set when a newer release exists on GitHub
+  updateInfo: UpdateInfo | null;
   exporting: boolean;
+  // Render + transcode and chunked exports report their own combined progress
+  exportBatch: boolean;
+  // Chunk being rendered by a chunked MP4 export
+  exportChunk: ExportChunkProgress | null;
   exportProgress: number;
   exportError: string | null;
   exportSuccess: string | null;
@@ -33,22 +76,85 @@ interface AppStore {
   previewProgress: number;
   previewPath: string | null;
   previewError: string | null;
+  // Narrations over the timeline, mixed in at export
+  voiceovers: Voiceover[];
+  voiceoverRecording: boolean;
   watermarkEnabled: boolean;
   exportFormat: ExportFormat;
   exportQuality: ExportQuality;
+  // "Fit under N MB" for MP4 exports, null = quality-based
+  exportTargetMb: number | null;
+  // Built-in and user presets; the selected one overrides the settings above
+  exportPresets: ExportPreset[];
+  exportPreset: string | null;
+  // Only this part of the timeline, for the session
+  exportRange: ExportRange | null;
+  // x264 or a hardware encoder (NVENC / QSV) for MP4 exports
+  exportEncoder: ExportEncoder;
+  // Frame rate of MP4/WebM exports whose preset doesn't set one
+  exportFps: ExportFps;
+  // Downscaling of timelines too large for the encoder, or than wanted
+  exportDownscale: ExportDownscale;
+  // Correct the colors of clips recorded on HDR displays
+  hdrTonemap: boolean;
+  // Debug: keep FFmpeg filter graph scripts in the temp dir, their path logged
+  keepFilterScripts: boolean;
+  gifPalette: GifPalette;
+  gifLoop: GifLoop;
+  exportSidecar: boolean;
+  // YouTube chapters written as <export>.chapters.txt
+  exportChapters: boolean;
+  // Active subtitle language burned in, or every language as a track
+  subtitleExport: SubtitleExport;
+  // Render long MP4 exports in chunks of N minutes, resumable (0 = off)
+  exportChunkMinutes: number;
+  postExport: PostExportSettings;
+  uploadTargets: UploadTarget[];
+  webhooks: Webhook[];
+  // Opt-in anonymous usage metrics
+  telemetry: TelemetrySettings;
   // Countdown
   countdownSeconds: number;
   countdownActive: boolean;
   countdownRemaining: number;
   // Keystroke display
   keystrokeEnabled: boolean;
+  // Font file for the keystroke overlay, null = bundled font
+  keystrokeFont: string | null;
   // Cursor zoom
   cursorZoomEnabled: boolean;
+  // Controller overlay
+  gamepadEnabled: boolean;
+  // Drawing overlay opened with each recording (F8 pen, F7 highlighter)
+  liveDrawing: boolean;
+  // Keystroke labels shown over the recorded area while recording
+  keystrokePreview: boolean;
+  followCursor: FollowWindow | null;
+  followFocus: boolean;
+  // Script scrolled on the teleprompter during recordings (null = no teleprompter)
+  teleprompter: TeleprompterSettings | null;
+  // Crossfade or dip to black where a recording was paused
+  pauseTransition: PauseTransition;
+  // Screen grab of the recordings, gdigrab where it can't record the region
+  captureBackend: CaptureBackendKind;
+  // Encoder of the capture, the first GPU encoder that works on "auto"
+  recordingEncoder: RecordingEncoder;
+  // Lighter capture settings while the CPU is busy
+  adaptiveCapture: AdaptiveCapture;
+  // Capture frame rate, from the monitor refresh rate when set
+  captureRate: CaptureRate;
+  audioFormat: AudioFormat;
+  // Folder of another capture tool whose new videos are imported
+  watchFolder: WatchFolderSettings;
+  watchPending: string[];
   // Projects
+  // Save cursor paths in a binary .cursor file next to the project JSON
+  cursorSidecar: boolean;
   projects: ProjectSummary[];
   currentProjectId: string | null;
   // Mic selection
   selectedMic: string | null;
+  selectedOutput: string | null;
   // Audio volumes
   systemVolume: number;
   micVolume: number;
@@ -62,6 +168,7 @@ interface AppStore {
   pauseRecording: () => Promise<void>;
   resumeRecording: () => Promise<void>;
   cancelRecording: () => Promise<void>;
+  toggleMicMute: () => Promise<void>;
   setAudioSource: (source: AudioSource) => Promise<void>;
   deleteClip: (clipId: string) => Promise<void>;
   setTransition: (index: number, type_: TransitionType, durationS?: number) => Promise<void>;
@@ -70,29 +177,87 @@ interface AppStore {
   setCaptureRegion: (region: Region) => Promise<void>;
   openRegionSelector: () => Promise<void>;
   clearRegion: () => void;
+  openSecondaryRegionSelector: () => Promise<void>;
+  setSecondaryRegion: (region: Region | null) => Promise<void>;
+  setCaptureMonitors: (monitors: Region[]) => Promise<void>;
   updateDuration: () => Promise<void>;
   exportVideo: () => Promise<string>;
+  exportVideoMulti: (targets: ExportTarget[]) => Promise<string[]>;
+  exportClipsSeparately: () => Promise<string[]>;
+  exportAudioVisualizer: (style: VisualizerStyle, title: string | null) => Promise<string>;
   setExportProgress: (progress: number) => void;
   clearExportError: () => void;
   clearExportSuccess: () => void;
   previewVideo: () => Promise<void>;
   setPreviewProgress: (progress: number) => void;
   closePreview: () => void;
+  startVoiceover: (startMs: number) => Promise<void>;
+  stopVoiceover: () => Promise<void>;
+  deleteVoiceover: (voiceoverId: string) => Promise<void>;
   toggleWatermark: () => void;
   setExportFormat: (format: ExportFormat) => void;
   setExportQuality: (quality: ExportQuality) => void;
+  setExportTargetMb: (mb: number | null) => void;
+  setExportPresets: (presets: ExportPreset[]) => Promise<void>;
+  setExportPreset: (id: string | null) => void;
+  setExportRange: (range: ExportRange | null) => void;
+  setExportEncoder: (encoder: ExportEncoder) => Promise<void>;
+  setExportFps: (fps: ExportFps) => void;
+  setExportDownscale: (downscale: ExportDownscale) => void;
+  setHdrTonemap: (enabled: boolean) => void;
+  setKeepFilterScripts: (enabled: boolean) => void;
+  setGifPalette: (palette: GifPalette) => void;
+  setGifLoop: (gifLoop: GifLoop) => void;
+  setExportSidecar: (enabled: boolean) => void;
+  setExportChapters: (enabled: boolean) => void;
+  setSubtitleExport: (mode: SubtitleExport) => void;
+  setExportChunkMinutes: (minutes: number) => void;
+  setPostExport: (settings: PostExportSettings) => Promise<void>;
+  setUploadTargets: (targets: UploadTarget[]) => Promise<void>;
+  setWebhooks: (webhooks: Webhook[]) => Promise<void>;
+  setTelemetry: (settings: TelemetrySettings) => Promise<void>;
   setClipTrim: (clipId: string, trimStartMs: number, trimEndMs: number) => Promise<void>;
+  setAudioOffset: (clipId: string, ms: number) => Promise<void>;
+  autoTrimClips: () => Promise<void>;
+  setClipZoomKeyframes: (clipId: string, keyframes: Keyframe[]) => Promise<void>;
+  setClipDualLayout: (clipId: string, layout: DualLayout) => Promise<void>;
+  regenerateThumbnail: (clipId: string, atMs: number | null) => Promise<void>;
+  insertFreeze: (clipId: string, atMs: number, durationMs: number) => Promise<void>;
+  recordRetake: (clipId: string) => Promise<void>;
+  selectTake: (clipId: string, take: number) => Promise<void>;
   toggleTheme: () => void;
+  setLocale: (locale: Locale) => Promise<void>;
   ensureFfmpeg: () => Promise<void>;
+  checkForUpdates: () => Promise<void>;
   // Countdown
   setCountdownSeconds: (seconds: number) => void;
   startCountdown: () => void;
   // Keystroke
   toggleKeystroke: () => Promise<void>;
+  setKeystrokeFont: (fontFile: string | null) => Promise<void>;
   // Cursor zoom
   toggleCursorZoom: () => Promise<void>;
+  // Controller overlay
+  toggleGamepad: () => Promise<void>;
+  setLiveDrawing: (enabled: boolean) => void;
+  setKeystrokePreview: (enabled: boolean) => void;
+  setFollowCursor: (window: FollowWindow | null) => void;
+  setFollowFocus: (enabled: boolean) => void;
+  setTeleprompter: (settings: TeleprompterSettings | null) => void;
+  setPauseTransition: (transition: PauseTransition) => void;
+  setCaptureBackend: (backend: CaptureBackendKind) => void;
+  setRecordingEncoder: (encoder: RecordingEncoder) => void;
+  setAdaptiveCapture: (settings: AdaptiveCapture) => void;
+  setCaptureRate: (rate: CaptureRate) => void;
+  setAudioFormat: (format: AudioFormat) => void;
+  setWatchFolder: (settings: WatchFolderSettings) => Promise<void>;
+  refreshWatchPending: () => Promise<void>;
+  resolveWatchPending: (importFiles: boolean) => Promise<void>;
+  setCursorSidecar: (enabled: boolean) => void;
   // Clipboard export
   copyToClipboard: (path: string) => Promise<void>;
+  revealFile: (path: string) => Promise<void>;
+  openInPlayer: (path: string) => Promise<void>;
   // Projects
   saveProject: (name: string) => Promise<void>;
   loadProject: (projectId: string) => Promise<void>;
@@ -100,6 +265,7 @@ interface AppStore {
   deleteProject: (projectId: string) => Promise<void>;
   // Mic selection
   setSelectedMic: (deviceName: string | null) => Promise<void>;
+  setSelectedOutput: (deviceName: string | null) => Promise<void>;
   // Audio volumes
   setSystemVolume: (volume: number) => Promise<void>;
   setMicVolume: (volume: number) => Promise<void>;
@@ -121,15 +287,23 @@ const getInitialTheme = (): Theme => {
 
 export const useAppStore = create<AppStore>((set, get) => ({
   theme: getInitialTheme(),
+  locale: localStorage.getItem("clipflow-locale") === "en" ? "en" : "fr",
   recordingState: "idle",
   clips: [],
   transitions: [],
   currentRegion: null,
+  secondaryRegion: null,
+  captureMonitors: [],
   audioSource: "none",
+  micMuted: false,
   durationMs: 0,
   ffmpegReady: false,
   ffmpegError: null,
+  ffmpegDownload: null,
+  updateInfo: null,
   exporting: false,
+  exportBatch: false,
+  exportChunk: null,
   exportProgress: 0,
   exportError: null,
   exportSuccess: null,
@@ -137,10 +311,12 @@ export const useAppStore = create<AppStore>((set, get) => ({
   previewProgress: 0,
   previewPath: null,
   previewError: null,
+  voiceovers: [],
+  voiceoverRecording: false,
   exportFormat: ((): ExportFormat => {
     try {
       const saved = localStorage.getItem("clipflow-format");
-      if (saved === "mp4" || saved === "gif") return saved;
+      if (saved === "mp4" || saved === "gif" || saved === "webm") return saved;
     } catch {}
     return "mp4";
   })(),
@@ -151,6 +327,128 @@ export const useAppStore = create<AppStore>((set, get) => ({
     } catch {}
     return "medium";
   })(),
+  exportTargetMb: (() => {
+    try {
+      const saved = localStorage.getItem("clipflow-target-mb");
+      if (saved) { const n = parseInt(saved, 10); if (n > 0) return n; }
+    } catch {}
+    return null;
+  })(),
+  exportRange: null,
+  exportPresets: ((): ExportPreset[] => {
+    try {
+      const saved = localStorage.getItem("clipflow-export-presets");
+      if (saved) return JSON.parse(saved);
+    } catch {}
+    return [];
+  })(),
+  exportPreset: (() => {
+    try {
+      return localStorage.getItem("clipflow-export-preset");
+    } catch {}
+    return null;
+  })(),
+  exportEncoder: ((): ExportEncoder => {
+    try {
+      const saved = localStorage.getItem("clipflow-export-encoder");
+      if (saved === "nvenc" || saved === "qsv") return saved;
+    } catch {}
+    return "software";
+  })(),
+  exportFps: ((): ExportFps => {
+    try {
+      const saved = localStorage.getItem("clipflow-export-fps");
+      if (saved) return JSON.parse(saved);
+    } catch {}
+    return "default";
+  })(),
+  exportDownscale: ((): ExportDownscale => {
+    try {
+      const saved = localStorage.getItem("clipflow-export-downscale");
+      if (saved) return JSON.parse(saved);
+    } catch {}
+    return "auto";
+  })(),
+  hdrTonemap: (() => {
+    try {
+      return localStorage.getItem("clipflow-hdr-tonemap") !== "false";
+    } catch {}
+    return true;
+  })(),
+  keepFilterScripts: (() => {
+    try {
+      return localStorage.getItem("clipflow-keep-filter-scripts") === "true";
+    } catch {}
+    return false;
+  })(),
+  gifPalette: ((): GifPalette => {
+    try {
+      const saved = localStorage.getItem("clipflow-gif-palette");
+      if (saved) return { max_colors: 256, per_clip: false, ...JSON.parse(saved) };
+    } catch {}
+    return { max_colors: 256, per_clip: false };
+  })(),
+  gifLoop: ((): GifLoop => {
+    try {
+      const saved = localStorage.getItem("clipflow-gif-loop");
+      if (saved) return { boomerang: false, plays: 0, ...JSON.parse(saved) };
+    } catch {}
+    return { boomerang: false, plays: 0 };
+  })(),
+  exportSidecar: (() => {
+    try {
+      return localStorage.getItem("clipflow-export-sidecar") === "true";
+    } catch {}
+    return false;
+  })(),
+  exportChapters: (() => {
+    try {
+      return localStorage.getItem("clipflow-export-chapters") === "true";
+    } catch {}
+    return false;
+  })(),
+  subtitleExport: localStorage.getItem("clipflow-subtitle-export") === "soft" ? "soft" : "burned",
+  exportChunkMinutes: (() => {
+    try {
+      const n = parseInt(localStorage.getItem("clipflow-export-chunk-minutes") ?? "", 10);
+      if (n >= 0) return n;
+    } catch {}
+    return 0;
+  })(),
+  keystrokeFont: (() => {
+    try {
+      return localStorage.getItem("clipflow-keystroke-font");
+    } catch {}
+    return null;
+  })(),
+  postExport: ((): PostExportSettings => {
+    try {
+      const saved = localStorage.getItem("clipflow-post-export");
+      if (saved) return { action: "open_folder", command: "", ...JSON.parse(saved) };
+    } catch {}
+    return { action: "open_folder", command: "" };
+  })(),
+  uploadTargets: ((): UploadTarget[] => {
+    try {
+      const saved = localStorage.getItem("clipflow-upload-targets");
+      if (saved) return JSON.parse(saved);
+    } catch {}
+    return [];
+  })(),
+  webhooks: ((): Webhook[] => {
+    try {
+      const saved = localStorage.getItem("clipflow-webhooks");
+      if (saved) return JSON.parse(saved);
+    } catch {}
+    return [];
+  })(),
+  telemetry: ((): TelemetrySettings => {
+    try {
+      const saved = localStorage.getItem("clipflow-telemetry");
+      if (saved) return { enabled: false, endpoint: "", ...JSON.parse(saved) };
+    } catch {}
+    return { enabled: false, endpoint: "" };
+  })(),
   watermarkEnabled: (() => {
     try {
       const saved = localStorage.getItem("clipflow-watermark");
@@ -171,7 +469,87 @@ export const useAppStore = create<AppStore>((set, get) => ({
   countdownRemaining: 0,
   keystrokeEnabled: false,
   cursorZoomEnabled: false,
+  gamepadEnabled: false,
+  liveDrawing: (() => {
+    try {
+      return localStorage.getItem("clipflow-live-drawing") === "true";
+    } catch {}
+    return false;
+  })(),
+  keystrokePreview: (() => {
+    try {
+      return localStorage.getItem("clipflow-keystroke-preview") === "true";
+    } catch {}
+    return false;
+  })(),
+  followCursor: (() => {
+    try {
+      const saved = localStorage.getItem("clipflow-follow-cursor");
+      if (saved) return JSON.parse(saved);
+    } catch {}
+    return null;
+  })(),
+  followFocus: (() => {
+    try {
+      return localStorage.getItem("clipflow-follow-focus") === "true";
+    } catch {}
+    return false;
+  })(),
+  teleprompter: (() => {
+    try {
+      const saved = localStorage.getItem("clipflow-teleprompter");
+      if (saved) return JSON.parse(saved);
+    } catch {}
+    return null;
+  })(),
+  pauseTransition: ((): PauseTransition => {
+    try {
+      const saved = localStorage.getItem("clipflow-pause-transition");
+      if (saved) return { style: "cut", duration_ms: 400, ...JSON.parse(saved) };
+    } catch {}
+    return { style: "cut", duration_ms: 400 };
+  })(),
+  captureBackend: ((): CaptureBackendKind => {
+    const saved = localStorage.getItem("clipflow-capture-backend");
+    return saved === "wgc" || saved === "ddagrab" ? saved : "gdigrab";
+  })(),
+  recordingEncoder: (localStorage.getItem("clipflow-recording-encoder") as RecordingEncoder | null) ?? "auto",
+  adaptiveCapture: ((): AdaptiveCapture => {
+    try {
+      const saved = localStorage.getItem("clipflow-adaptive-capture");
+      if (saved) return { enabled: false, cpu_threshold: 85, ...JSON.parse(saved) };
+    } catch {}
+    return { enabled: false, cpu_threshold: 85 };
+  })(),
+  captureRate: ((): CaptureRate => {
+    try {
+      const saved = localStorage.getItem("clipflow-capture-rate");
+      if (saved) return JSON.parse(saved);
+    } catch {}
+    return "default";
+  })(),
+  audioFormat: ((): AudioFormat => {
+    try {
+      return localStorage.getItem("clipflow-audio-format") === "native" ? "native" : "canonical";
+    } catch {}
+    return "canonical";
+  })(),
+  watchFolder: (() => {
+    try {
+      const saved = localStorage.getItem("clipflow-watch-folder");
+      if (saved) return JSON.parse(saved);
+    } catch {}
+    return { path: null, confirm: false };
+  })(),
+  watchPending: [],
+  cursorSidecar: (() => {
+    try {
+      return localStorage.getItem("clipflow-cursor-sidecar") !== "false";
+    } catch {}
+    return true;
+  })(),
   selectedMic: null,
+  selectedOutput: null,
   systemVolume: (() => {
     try {
       const saved = localStorage.getItem("clipflow-system-volume");
@@ -196,26 +574,61 @@ export const useAppStore = create<AppStore>((set, get) => ({
   })(),
 
   refreshState: async () => {
-    const [recordingState, clips, transitions] = await Promise.all([
+    const [recordingState, clips, transitions, voiceovers] = await Promise.all([
       api.getRecordingState(),
       api.getClips(),
       api.getTransitions(),
+      api.getVoiceovers(),
     ]);
-    set({ recordingState, clips, transitions });
+    set({ recordingState, clips, transitions, voiceovers });
+    // Every recording starts unmuted
+    if (recordingState === "idle") set({ micMuted: false });
     // Sync audio volumes from local storage to backend
     const { systemVolume, micVolume } = get();
     api.setAudioVolumes(systemVolume, micVolume).catch(() => {});
+    api.setPostExportSettings(get().postExport).catch(() => {});
+    api.setUploadTargets(get().uploadTargets).catch(() => {});
+    api.setWebhooks(get().webhooks).catch(() => {});
+    api.setExportPresets(get().exportPresets.filter((p) => !p.builtin))
+      .then((exportPresets) => set({ exportPresets }))
+      .catch(() => {});
+    // Falls back to x264 when this FFmpeg build lacks the saved encoder
+    api.setExportEncoder(get().exportEncoder).catch(() => set({ exportEncoder: "software" }));
+    api.setExportFps(get().exportFps).catch(() => {});
+    api.setExportDownscale(get().exportDownscale).catch(() => {});
+    api.setHdrTonemap(get().hdrTonemap).catch(() => {});
+    api.setKeepFilterScripts(get().keepFilterScripts).catch(() => {});
+    api.setGifPalette(get().gifPalette).catch(() => {});
+    api.setGifLoop(get().gifLoop).catch(() => {});
+    api.setExportSidecar(get().exportSidecar).catch(() => {});
+    api.setExportChapters(get().exportChapters).catch(() => {});
+    api.setSubtitleExport(get().subtitleExport).catch(() => {});
+    api.setExportChunkMinutes(get().exportChunkMinutes).catch(() => {});
+    api.setCursorSidecar(get().cursorSidecar).catch(() => {});
+    api.setLiveDrawing(get().liveDrawing).catch(() => {});
+    api.setKeystrokePreview(get().keystrokePreview).catch(() => {});
+    api.setFollowCursor(get().followCursor).catch(() => {});
+    api.setFollowFocus(get().followFocus).catch(() => {});
+    api.setTeleprompter(get().teleprompter).catch(() => {});
+    api.setPauseTransition(get().pauseTransition).catch(() => {});
+    api.setCaptureBackend(get().captureBackend).catch(() => {});
+    api.setRecordingEncoder(get().recordingEncoder).catch(() => {});
+    api.setAdaptiveCapture(get().adaptiveCapture).catch(() => {});
+    api.setCaptureRate(get().captureRate).catch(() => {});
+    api.setAudioFormat(get().audioFormat).catch(() => {});
+    api.setWatchFolder(get().watchFolder).catch(() => {});
+    api.setTelemetrySettings(get().telemetry).catch(() => {});
+    api.setKeystrokeFont(get().keystrokeFont).catch(() => {});
+    api.setLocale(get().locale).catch(() => {});
   },
 
   startRecording: async () => {
-    const { countdownSeconds } = get();
-    if (countdownSeconds > 0) {
-      set({ countdownActive: true, countdownRemaining: countdownSeconds });
-      // Countdown is handled in App.tsx via interval
-      return;
+    // Countdown runs in the backend and is reported via countdown-tick events
+    await api.setCountdown(get().countdownSeconds);
+    const started = await api.startRecording();
+    if (started) {
+      set({ recordingState: "recording", durationMs: 0 });
     }
-    await api.startRecording();
-    set({ recordingState: "recording", durationMs: 0 });
   },
 
   stopRecording: async () => {
@@ -240,7 +653,12 @@ export const useAppStore = create<AppStore>((set, get) => ({
 
   cancelRecording: async () => {
     await api.cancelRecording();
-    set({ recordingState: "idle", durationMs: 0 });
+    set({ recordingState: "idle", durationMs: 0, micMuted: false });
+  },
+
+  toggleMicMute: async () => {
+    const micMuted = await api.toggleMicMute();
+    set({ micMuted });
   },
 
   setAudioSource: async (source: AudioSource) => {
@@ -292,6 +710,20 @@ export const useAppStore = create<AppStore>((set, get) => ({
     set({ currentRegion: null });
   },
 
+  openSecondaryRegionSelector: async () => {
+    await api.openRegionSelector(true);
+  },
+
+  setSecondaryRegion: async (region: Region | null) => {
+    await api.setSecondaryRegion(region);
+    set({ secondaryRegion: region });
+  },
+
+  setCaptureMonitors: async (monitors: Region[]) => {
+    await api.setCaptureMonitors(monitors);
+    set({ captureMonitors: monitors });
+  },
+
   updateDuration: async () => {
     const state = get().recordingState;
     if (state === "recording" || state === "paused") {
@@ -301,9 +733,56 @@ export const useAppStore = create<AppStore>((set, get) => ({
   },
 
   exportVideo: async () => {
+    const preset = get().exportPresets.find((p) => p.id === get().exportPreset);
+    const format = preset?.format ?? get().exportFormat;
+    const targetMb = preset ? preset.target_size_mb : get().exportTargetMb;
+    const chunked = format === "mp4" && get().exportChunkMinutes > 0 && !targetMb;
+    set({ exporting: true, exportBatch: format === "webm" || chunked, exportChunk: null, exportProgress: 0, exportError: null, exportSuccess: null });
+    try {
+      // Refuse in seconds what would fail minutes into the encode
+      const issues = await api.validateExport(get().exportFormat, get().exportQuality, get().exportTargetMb, get().exportPreset, get().exportRange);
+      const blocking = issues.filter((i) => i.severity === "error");
+      if (blocking.length > 0) throw new Error(blocking.map((i) => i.message).join("\n"));
+      const path = await api.exportVideo(get().watermarkEnabled, get().exportFormat, get().exportQuality, get().exportTargetMb, get().exportPreset, get().exportRange);
+      set({ exporting: false, exportBatch: false, exportChunk: null, exportProgress: 100, exportSuccess: path });
+      return path;
+    } catch (e) {
+      const msg = e instanceof Error ? e.message : String(e);
+      set({ exporting: false, exportBatch: false, exportChunk: null, exportProgress: 0, exportError: msg });
+      throw e;
+    }
+  },
+
+  exportVideoMulti: async (targets: ExportTarget[]) => {
+    set({ exporting: true, exportBatch: true, exportProgress: 0, exportError: null, exportSuccess: null });
+    try {
+      const paths = await api.exportVideoMulti(get().watermarkEnabled, targets);
+      set({ exporting: false, exportBatch: false, exportProgress: 100, exportSuccess: paths[0] ?? null });
+      return paths;
+    } catch (e) {
+      const msg = e instanceof Error ? e.message : String(e);
+      set({ exporting: false, exportBatch: false, exportProgress: 0, exportError: msg });
+      throw e;
+    }
+  },
+
+  exportClipsSeparately: async () => {
+    set({ exporting: true, exportBatch: true, exportProgress: 0, exportError: null, exportSuccess: null });
+    try {
+      const paths = await api.exportClipsSeparately(get().watermarkEnabled, get().exportFormat, get().exportQuality, get().exportPreset);
+      set({ exporting: false, exportBatch: false, exportProgress: 100, exportSuccess: paths[0] ?? null });
+      return paths;
+    } catch (e) {
+      const msg = e instanceof Error ? e.message : String(e);
+      set({ exporting: false, exportBatch: false, exportProgress: 0, exportError: msg });
+      throw e;
+    }
+  },
+
+  exportAudioVisualizer: async (style: VisualizerStyle, title: string | null) => {
     set({ exporting: true, exportProgress: 0, exportError: null, exportSuccess: null });
     try {
-      const path = await api.exportVideo(get().watermarkEnabled, get().exportFormat, get().exportQuality);
+      const path = await api.exportAudioVisualizer(style, title, get().exportQuality);
       set({ exporting: false, exportProgress: 100, exportSuccess: path });
       return path;
     } catch (e) {
@@ -344,6 +823,25 @@ export const useAppStore = create<AppStore>((set, get) => ({
     set({ previewPath: null, previewError: null });
   },
 
+  startVoiceover: async (startMs: number) => {
+    await api.startVoiceover(startMs);
+    set({ voiceoverRecording: true });
+  },
+
+  stopVoiceover: async () => {
+    try {
+      const voiceover = await api.stopVoiceover();
+      set({ voiceovers: [...get().voiceovers, voiceover] });
+    } finally {
+      set({ voiceoverRecording: false });
+    }
+  },
+
+  deleteVoiceover: async (voiceoverId: string) => {
+    await api.deleteVoiceover(voiceoverId);
+    set({ voiceovers: get().voiceovers.filter((v) => v.id !== voiceoverId) });
+  },
+
   toggleWatermark: () => {
     const next = !get().watermarkEnabled;
     localStorage.setItem("clipflow-watermark", String(next));
@@ -360,33 +858,222 @@ export const useAppStore = create<AppStore>((set, get) => ({
     set({ exportQuality: quality });
   },
 
+  setExportRange: (range: ExportRange | null) => {
+    set({ exportRange: range });
+  },
+
+  setExportTargetMb: (mb: number | null) => {
+    if (mb && mb > 0) {
+      localStorage.setItem("clipflow-target-mb", String(mb));
+    } else {
+      localStorage.removeItem("clipflow-target-mb");
+    }
+    set({ exportTargetMb: mb && mb > 0 ? mb : null });
+  },
+
+  setExportPresets: async (presets: ExportPreset[]) => {
+    const all = await api.setExportPresets(presets.filter((p) => !p.builtin));
+    localStorage.setItem("clipflow-export-presets", JSON.stringify(all.filter((p) => !p.builtin)));
+    const selected = get().exportPreset;
+    if (selected && !all.some((p) => p.id === selected)) {
+      localStorage.removeItem("clipflow-export-preset");
+      set({ exportPresets: all, exportPreset: null });
+    } else {
+      set({ exportPresets: all });
+    }
+  },
+
+  setExportPreset: (id: string | null) => {
+    if (id) {
+      localStorage.setItem("clipflow-export-preset", id);
+    } else {
+      localStorage.removeItem("clipflow-export-preset");
+    }
+    set({ exportPreset: id });
+  },
+
+  setExportEncoder: async (encoder: ExportEncoder) => {
+    await api.setExportEncoder(encoder);
+    localStorage.setItem("clipflow-export-encoder", encoder);
+    set({ exportEncoder: encoder });
+  },
+
+  setExportFps: (fps: ExportFps) => {
+    localStorage.setItem("clipflow-export-fps", JSON.stringify(fps));
+    set({ exportFps: fps });
+    api.setExportFps(fps).catch(() => {});
+  },
+
+  setExportDownscale: (downscale: ExportDownscale) => {
+    localStorage.setItem("clipflow-export-downscale", JSON.stringify(downscale));
+    set({ exportDownscale: downscale });
+    api.setExportDownscale(downscale).catch(() => {});
+  },
+
+  setHdrTonemap: (enabled: boolean) => {
+    localStorage.setItem("clipflow-hdr-tonemap", String(enabled));
+    set({ hdrTonemap: enabled });
+    api.setHdrTonemap(enabled).catch(() => {});
+  },
+
+  setKeepFilterScripts: (enabled: boolean) => {
+    localStorage.setItem("clipflow-keep-filter-scripts", String(enabled));
+    set({ keepFilterScripts: enabled });
+    api.setKeepFilterScripts(enabled).catch(() => {});
+  },
+
+  setGifPalette: (palette: GifPalette) => {
+    localStorage.setItem("clipflow-gif-palette", JSON.stringify(palette));
+    set({ gifPalette: palette });
+    api.setGifPalette(palette).catch(() => {});
+  },
+
+  setGifLoop: (gifLoop: GifLoop) => {
+    localStorage.setItem("clipflow-gif-loop", JSON.stringify(gifLoop));
+    set({ gifLoop });
+    api.setGifLoop(gifLoop).catch(() => {});
+  },
+
+  setExportSidecar: (enabled: boolean) => {
+    localStorage.setItem("clipflow-export-sidecar", String(enabled));
+    set({ exportSidecar: enabled });
+    api.setExportSidecar(enabled).catch(() => {});
+  },
+
+  setExportChapters: (enabled: boolean) => {
+    localStorage.setItem("clipflow-export-chapters", String(enabled));
+    set({ exportChapters: enabled });
+    api.setExportChapters(enabled).catch(() => {});
+  },
+
+  setSubtitleExport: (mode: SubtitleExport) => {
+    localStorage.setItem("clipflow-subtitle-export", mode);
+    set({ subtitleExport: mode });
+    api.setSubtitleExport(mode).catch(() => {});
+  },
+
+  setExportChunkMinutes: (minutes: number) => {
+    localStorage.setItem("clipflow-export-chunk-minutes", String(minutes));
+    set({ exportChunkMinutes: minutes });
+    api.setExportChunkMinutes(minutes).catch(() => {});
+  },
+
+  setPostExport: async (settings: PostExportSettings) => {
+    await api.setPostExportSettings(settings);
+    localStorage.setItem("clipflow-post-export", JSON.stringify(settings));
+    set({ postExport: settings });
+  },
+
+  setUploadTargets: async (targets: UploadTarget[]) => {
+    await api.setUploadTargets(targets);
+    localStorage.setItem("clipflow-upload-targets", JSON.stringify(targets));
+    set({ uploadTargets: targets });
+  },
+
+  setWebhooks: async (webhooks: Webhook[]) => {
+    await api.setWebhooks(webhooks);
+    localStorage.setItem("clipflow-webhooks", JSON.stringify(webhooks));
+    set({ webhooks });
+  },
+
+  setTelemetry: async (settings: TelemetrySettings) => {
+    await api.setTelemetrySettings(settings);
+    localStorage.setItem("clipflow-telemetry", JSON.stringify(settings));
+    set({ telemetry: settings });
+  },
+
   setClipTrim: async (clipId: string, trimStartMs: number, trimEndMs: number) => {
     await api.setClipTrim(clipId, trimStartMs, trimEndMs);
     const clips = await api.getClips();
     set({ clips });
   },
 
+  setAudioOffset: async (clipId: string, ms: number) => {
+    await api.setAudioOffset(clipId, ms);
+    set({ clips: get().clips.map((c) => (c.id === clipId ? { ...c, audio_offset_ms: ms } : c)) });
+  },
+
+  autoTrimClips: async () => {
+    const clips = await api.autoTrimClips();
+    set({ clips });
+  },
+
+  setClipZoomKeyframes: async (clipId: string, keyframes: Keyframe[]) => {
+    await api.setClipZoomKeyframes(clipId, keyframes);
+    const clips = await api.getClips();
+    set({ clips });
+  },
+
+  setClipDualLayout: async (clipId: string, layout: DualLayout) => {
+    await api.setClipDualLayout(clipId, layout);
+    const clips = await api.getClips();
+    set({ clips });
+  },
+
+  regenerateThumbnail: async (clipId: string, atMs: number | null) => {
+    await api.regenerateThumbnail(clipId, atMs);
+    const clips = await api.getClips();
+    set({ clips });
+  },
+
+  insertFreeze: async (clipId: string, atMs: number, durationMs: number) => {
+    const clips = await api.insertFreeze(clipId, atMs, durationMs);
+    const transitions = await api.getTransitions();
+    set({ clips, transitions });
+  },
+
+  recordRetake: async (clipId: string) => {
+    await api.setCountdown(get().countdownSeconds);
+    const started = await api.recordRetake(clipId);
+    if (started) {
+      set({ recordingState: "recording", durationMs: 0 });
+    }
+  },
+
+  selectTake: async (clipId: string, take: number) => {
+    const clip = await api.selectTake(clipId, take);
+    set({ clips: get().clips.map((c) => (c.id === clip.id ? clip : c)) });
+  },
+
   toggleTheme: () => {
     const next = get().theme === "dark" ? "light" : "dark";
     localStorage.setItem("clipflow-theme", next);
     set({ theme: next });
   },
 
+  setLocale: async (locale: Locale) => {
+    const applied = await api.setLocale(locale);
+    localStorage.setItem("clipflow-locale", applied);
+    set({ locale: applied });
+  },
+
+
   ensureFfmpeg: async () => {
     try {
       set({ ffmpegError: null });
       await api.ensureFfmpeg();
-      set({ ffmpegReady: true });
+      set({ ffmpegReady: true, ffmpegDownload: null });
     } catch (e) {
       const msg = e instanceof Error ? e.message : String(e);
       console.error("FFmpeg init failed:", msg);
-      set({ ffmpegError: msg });
+      set({ ffmpegError: msg, ffmpegDownload: null });
+    }
+  },
+  checkForUpdates: async () => {
+    try {
+      const info = await api.checkForUpdates();
+      set({ updateInfo: info.update_available ? info : null });
+    } catch (e) {
+      // Offline or rate-limited: not worth bothering the user
+      console.warn("Update check failed:", e);
     }
   },
 
+
   setCountdownSeconds: (seconds: number) => {
     localStorage.setItem("clipflow-countdown", String(seconds));
     set({ countdownSeconds: seconds });
+    api.setCountdown(seconds).catch(() => {});
   },
 
   startCountdown: () => {
@@ -398,15 +1085,131 @@ export const useAppStore = create<AppStore>((set, get) => ({
     set({ keystrokeEnabled: enabled });
   },
 
+  setKeystrokeFont: async (fontFile: string | null) => {
+    await api.setKeystrokeFont(fontFile);
+    if (fontFile) {
+      localStorage.setItem("clipflow-keystroke-font", fontFile);
+    } else {
+      localStorage.removeItem("clipflow-keystroke-font");
+    }
+    set({ keystrokeFont: fontFile });
+  },
+
   toggleCursorZoom: async () => {
     const enabled = await api.toggleCursorZoom();
     set({ cursorZoomEnabled: enabled });
   },
 
+  toggleGamepad: async () => {
+    const enabled = await api.toggleGamepad();
+    set({ gamepadEnabled: enabled });
+  },
+
+  setLiveDrawing: (enabled: boolean) => {
+    localStorage.setItem("clipflow-live-drawing", String(enabled));
+    set({ liveDrawing: enabled });
+    api.setLiveDrawing(enabled).catch(() => {});
+  },
+
+  setKeystrokePreview: (enabled: boolean) => {
+    localStorage.setItem("clipflow-keystroke-preview", String(enabled));
+    set({ keystrokePreview: enabled });
+    api.setKeystrokePreview(enabled).catch(() => {});
+  },
+
+  setFollowCursor: (window: FollowWindow | null) => {
+    if (window) localStorage.setItem("clipflow-follow-cursor", JSON.stringify(window));
+    else localStorage.removeItem("clipflow-follow-cursor");
+    set({ followCursor: window });
+    api.setFollowCursor(window).catch(() => {});
+    // Both modes reframe the full screen, only one at a time
+    if (window && get().followFocus) get().setFollowFocus(false);
+  },
+
+  setFollowFocus: (enabled: boolean) => {
+    localStorage.setItem("clipflow-follow-focus", String(enabled));
+    set({ followFocus: enabled });
+    api.setFollowFocus(enabled).catch(() => {});
+    if (enabled && get().followCursor) get().setFollowCursor(null);
+  },
+
+  setTeleprompter: (settings: TeleprompterSettings | null) => {
+    if (settings) localStorage.setItem("clipflow-teleprompter", JSON.stringify(settings));
+    else localStorage.removeItem("clipflow-teleprompter");
+    set({ teleprompter: settings });
+    api.setTeleprompter(settings).catch(() => {});
+  },
+
+  setPauseTransition: (transition: PauseTransition) => {
+    localStorage.setItem("clipflow-pause-transition", JSON.stringify(transition));
+    set({ pauseTransition: transition });
+    api.setPauseTransition(transition).catch(() => {});
+  },
+
+  setCaptureBackend: (backend: CaptureBackendKind) => {
+    localStorage.setItem("clipflow-capture-backend", backend);
+    set({ captureBackend: backend });
+    api.setCaptureBackend(backend).catch(() => {});
+  },
+
+  setRecordingEncoder: (encoder: RecordingEncoder) => {
+    localStorage.setItem("clipflow-recording-encoder", encoder);
+    set({ recordingEncoder: encoder });
+    api.setRecordingEncoder(encoder).catch(() => {});
+  },
+
+  setAdaptiveCapture: (settings: AdaptiveCapture) => {
+    localStorage.setItem("clipflow-adaptive-capture", JSON.stringify(settings));
+    set({ adaptiveCapture: settings });
+    api.setAdaptiveCapture(settings).catch(() => {});
+  },
+
+  setCaptureRate: (rate: CaptureRate) => {
+    localStorage.setItem("clipflow-capture-rate", JSON.stringify(rate));
+    set({ captureRate: rate });
+    api.setCaptureRate(rate).catch(() => {});
+  },
+
+  setAudioFormat: (format: AudioFormat) => {
+    localStorage.setItem("clipflow-audio-format", format);
+    set({ audioFormat: format });
+    api.setAudioFormat(format).catch(() => {});
+  },
+
+  setWatchFolder: async (settings: WatchFolderSettings) => {
+    await api.setWatchFolder(settings);
+    localStorage.setItem("clipflow-watch-folder", JSON.stringify(settings));
+    set({ watchFolder: settings });
+  },
+
+  refreshWatchPending: async () => {
+    const watchPending = await api.getWatchPending();
+    set({ watchPending });
+  },
+
+  resolveWatchPending: async (importFiles: boolean) => {
+    await api.resolveWatchPending(get().watchPending, importFiles);
+    set({ watchPending: [] });
+  },
+
+  setCursorSidecar: (enabled: boolean) => {
+    localStorage.setItem("clipflow-cursor-sidecar", String(enabled));
+    set({ cursorSidecar: enabled });
+    api.setCursorSidecar(enabled).catch(() => {});
+  },
+
   copyToClipboard: async (path: string) => {
     await api.copyFileToClipboard(path);
   },
 
+  revealFile: async (path: string) => {
+    await api.revealInExplorer(path);
+  },
+
+  openInPlayer: async (path: string) => {
+    await api.openWithDefaultPlayer(path);
+  },
+
   saveProject: async (name: string) => {
     const id = await api.saveProject(name);
     set({ currentProjectId: id });
@@ -437,6 +1240,11 @@ export const useAppStore = create<AppStore>((set, get) => ({
     set({ selectedMic: deviceName });
   },
 
+  setSelectedOutput: async (deviceName: string | null) => {
+    await api.setSelectedOutput(deviceName);
+    set({ selectedOutput: deviceName });
+  },
+
   setSystemVolume: async (volume: number) => {
     const clamped = Math.max(0, Math.min(2, volume));
     localStorage.setItem("clipflow-system-volume", String(clamped));